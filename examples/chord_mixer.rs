@@ -43,8 +43,14 @@ impl ChordType {
     }
 }
 
+/// Commands sent from the key handler to the audio thread.
+enum Command {
+    /// Fade in a chord built on the UI thread
+    Play(Box<dyn Signal + Send>),
+    Stop,
+}
+
 struct AudioState {
-    chord_type: Option<ChordType>,
     signal: Box<dyn Signal + Send>,
     fade_samples: usize,
 }
@@ -52,7 +58,6 @@ struct AudioState {
 impl AudioState {
     fn new() -> Self {
         Self {
-            chord_type: None,
             signal: Box::new(SineOscillator::<SAMPLE_RATE>::new(0.0).gain(0.0)),
             fade_samples: 0,
         }
@@ -120,20 +125,11 @@ impl AudioState {
             )),
         }
     }
-
-    fn play_chord(&mut self, chord_type: ChordType) {
-        self.chord_type = Some(chord_type);
-        self.signal = Self::create_signal(chord_type);
-        self.fade_samples = (SAMPLE_RATE as f64 * 0.005) as usize;
-    }
-
-    fn stop(&mut self) {
-        self.chord_type = None;
-        self.signal = Box::new(SineOscillator::<SAMPLE_RATE>::new(0.0).gain(0.0));
-    }
 }
 
 impl ExampleAudioState for AudioState {
+    type Command = Command;
+
     fn next_sample(&mut self) -> f64 {
         let sample = self.signal.next_sample();
 
@@ -146,6 +142,18 @@ impl ExampleAudioState for AudioState {
             sample
         }
     }
+
+    fn apply(&mut self, command: Command) {
+        match command {
+            Command::Play(signal) => {
+                self.signal = signal;
+                self.fade_samples = (SAMPLE_RATE as f64 * 0.005) as usize;
+            }
+            Command::Stop => {
+                self.signal = Box::new(SineOscillator::<SAMPLE_RATE>::new(0.0).gain(0.0));
+            }
+        }
+    }
 }
 
 fn draw_ui(chord_type: Option<ChordType>) -> Result<()> {
@@ -175,53 +183,30 @@ fn draw_ui(chord_type: Option<ChordType>) -> Result<()> {
 fn main() -> Result<()> {
     run_interactive_example(
         AudioState::new(),
+        (),
         KeyboardConfig::default(),
-        |state| draw_ui(state.lock().unwrap().chord_type),
-        |state, key_event: &KeyEvent| {
-            match key_event.code {
-                KeyCode::Char('1') => {
-                    let mut s = state.lock().unwrap();
-                    s.play_chord(ChordType::Major);
-                    let chord_type = s.chord_type;
-                    drop(s);
-                    draw_ui(chord_type)?;
-                }
-                KeyCode::Char('2') => {
-                    let mut s = state.lock().unwrap();
-                    s.play_chord(ChordType::Minor);
-                    let chord_type = s.chord_type;
-                    drop(s);
-                    draw_ui(chord_type)?;
-                }
-                KeyCode::Char('3') => {
-                    let mut s = state.lock().unwrap();
-                    s.play_chord(ChordType::Dominant7);
-                    let chord_type = s.chord_type;
-                    drop(s);
-                    draw_ui(chord_type)?;
-                }
-                KeyCode::Char('4') => {
-                    let mut s = state.lock().unwrap();
-                    s.play_chord(ChordType::Complex);
-                    let chord_type = s.chord_type;
-                    drop(s);
-                    draw_ui(chord_type)?;
-                }
-                KeyCode::Char('5') => {
-                    let mut s = state.lock().unwrap();
-                    s.play_chord(ChordType::Octaves);
-                    let chord_type = s.chord_type;
-                    drop(s);
-                    draw_ui(chord_type)?;
-                }
+        |_ui| draw_ui(None),
+        |_ui, commands, key_event: &KeyEvent| {
+            let chord_type = match key_event.code {
+                KeyCode::Char('1') => ChordType::Major,
+                KeyCode::Char('2') => ChordType::Minor,
+                KeyCode::Char('3') => ChordType::Dominant7,
+                KeyCode::Char('4') => ChordType::Complex,
+                KeyCode::Char('5') => ChordType::Octaves,
                 KeyCode::Char('s') | KeyCode::Char('S') => {
-                    let mut s = state.lock().unwrap();
-                    s.stop();
-                    drop(s);
-                    draw_ui(None)?;
+                    if commands.send(Command::Stop).is_ok() {
+                        draw_ui(None)?;
+                    }
+                    return Ok(KeyAction::Continue);
                 }
                 code if is_quit_key(code) => return Ok(KeyAction::Exit),
-                _ => {}
+                _ => return Ok(KeyAction::Continue),
+            };
+
+            // Build the chord here so the audio thread only swaps it in
+            let signal = AudioState::create_signal(chord_type);
+            if commands.send(Command::Play(signal)).is_ok() {
+                draw_ui(Some(chord_type))?;
             }
             Ok(KeyAction::Continue)
         },
@@ -0,0 +1,45 @@
+//! Real-time safe control through a command queue.
+//!
+//! The voice allocator is moved to the audio thread; the main thread plays an
+//! arpeggio by sending note commands. The audio callback never locks.
+
+use anyhow::Result;
+use earworm::core::command_queue;
+use earworm::music::{NoteCommand, VoiceAllocator};
+use earworm::playback::{AudioOutput, OutputOptions};
+use earworm::{ADSR, SawtoothOscillator, SignalExt};
+use std::time::Duration;
+
+const SAMPLE_RATE: u32 = 48000;
+
+fn main() -> Result<()> {
+    let allocator = VoiceAllocator::<SAMPLE_RATE, 4, _, _>::new(|| {
+        let osc = SawtoothOscillator::<SAMPLE_RATE>::new(440.0);
+        let env = ADSR::new(0.01, 0.1, 0.6, 0.3, SAMPLE_RATE as f64);
+        (osc, env)
+    });
+
+    let (sender, receiver) = command_queue(64);
+    let synth = receiver.control(allocator).gain(0.5);
+    let _output = AudioOutput::start(synth, &OutputOptions::default())?;
+
+    println!("Playing an arpeggio...");
+    for _ in 0..4 {
+        for note in [60, 64, 67, 72] {
+            if sender
+                .send(NoteCommand::NoteOn {
+                    note,
+                    velocity: 0.8,
+                })
+                .is_err()
+            {
+                eprintln!("command queue full, note dropped");
+            }
+            std::thread::sleep(Duration::from_millis(200));
+            sender.send(NoteCommand::NoteOff { note }).ok();
+        }
+    }
+
+    std::thread::sleep(Duration::from_millis(500));
+    Ok(())
+}
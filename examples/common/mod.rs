@@ -6,27 +6,357 @@ use cpal::{FromSample, Sample, SampleFormat, StreamConfig};
 use crossterm::{
     ExecutableCommand,
     event::{
-        self, Event, KeyCode, KeyEvent, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
-        PushKeyboardEnhancementFlags,
+        self, Event, KeyCode, KeyEvent, KeyEventKind, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
     },
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
+use earworm::Signal;
+use earworm::core::{
+    CommandSender, CommandTarget, Controlled, LogDrain, LogLevel, RtLogger, command_queue, rt_log,
+};
 use std::io::{Write, stdout};
 use std::panic;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::time::Duration;
 
-/// Trait for audio state that can generate samples.
-/// Types implementing this trait can be used as audio sources in interactive examples.
+/// Audio-thread half of an interactive example.
+///
+/// The state is moved into the audio callback, which owns it outright, so the
+/// UI thread never locks it. Key handlers change it by sending
+/// [`Command`](Self::Command)s, applied between samples, or by setting
+/// [`ParamHandle`](earworm::core::ParamHandle)s wired into the patch.
 pub trait ExampleAudioState: Send + 'static {
+    /// Commands the key handler can send (use `()` if there are none).
+    type Command: Send + 'static;
+
     fn next_sample(&mut self) -> f64;
 
+    /// Applies a command sent with [`Commands::send`].
+    /// This is called on the audio thread, so keep it cheap.
+    fn apply(&mut self, _command: Self::Command) {}
+
+    /// Called when a note key is pressed, if musical keys are enabled.
+    ///
+    /// `note` already includes the current octave shift.
+    fn note_on(&mut self, _note: u8, _velocity: f64) {}
+
+    /// Called when a note key is released, if musical keys are enabled.
+    ///
+    /// `note` is the note started by the matching press, even if the octave
+    /// was shifted while the key was held.
+    fn note_off(&mut self, _note: u8) {}
+
+    /// Called once at startup with a logger whose messages the framework
+    /// shows on screen. Keep it (or pass it to `DebugGuard::with_logger`) to
+    /// report problems from `next_sample`.
+    fn set_logger(&mut self, _logger: RtLogger) {}
+}
+
+/// UI-thread half of an interactive example.
+///
+/// Values that change on the audio thread (levels, voice counts) should be
+/// read from [`ParamHandle`](earworm::core::ParamHandle)s or a [`Scope`] the
+/// audio state publishes to.
+pub trait ExampleUi {
     /// Optional output/metrics information to display in the UI.
     /// Return None to hide the output line, or Some(String) to show it.
-    /// This is called periodically from the UI thread, not the audio thread.
     fn output_info(&self) -> Option<String> {
         None
     }
+
+    /// Optional recent output to draw as a waveform and spectrum at the
+    /// bottom of the screen.
+    fn scope(&self) -> Option<&Scope> {
+        None
+    }
+
+    /// Optional labelled level (0.0-1.0) to draw as a meter, e.g. an
+    /// envelope.
+    fn meter(&self) -> Option<(&'static str, f64)> {
+        None
+    }
+
+    /// Called when a note key is pressed, if musical keys are enabled,
+    /// after the note has been sent to the audio thread.
+    fn note_on(&mut self, _note: u8, _velocity: f64) {}
+
+    /// Called when a note key is released, if musical keys are enabled.
+    fn note_off(&mut self, _note: u8) {}
+
+    /// Called when the octave shift or velocity changes, and once at startup,
+    /// if musical keys are enabled.
+    fn keyboard_changed(&mut self, _keyboard: &KeyboardState) {}
+}
+
+/// For examples whose key handler draws everything they show.
+impl ExampleUi for () {}
+
+/// What the UI thread sends to the audio thread: the example's own commands
+/// plus the notes played on the musical keys.
+enum FrameworkCommand<C> {
+    NoteOn { note: u8, velocity: f64 },
+    NoteOff { note: u8 },
+    Example(C),
+}
+
+/// Lets the library's command queue drive an [`ExampleAudioState`].
+struct AudioSide<S>(S);
+
+impl<S: ExampleAudioState> CommandTarget<FrameworkCommand<S::Command>> for AudioSide<S> {
+    fn apply(&mut self, command: FrameworkCommand<S::Command>) {
+        match command {
+            FrameworkCommand::NoteOn { note, velocity } => self.0.note_on(note, velocity),
+            FrameworkCommand::NoteOff { note } => self.0.note_off(note),
+            FrameworkCommand::Example(command) => self.0.apply(command),
+        }
+    }
+}
+
+impl<S: ExampleAudioState> Signal for AudioSide<S> {
+    fn next_sample(&mut self) -> f64 {
+        self.0.next_sample()
+    }
+}
+
+/// Commands the audio thread can have pending before new ones are dropped.
+const COMMAND_CAPACITY: usize = 256;
+
+/// Sends commands from the key handler to the audio thread without blocking.
+pub struct Commands<C> {
+    sender: CommandSender<FrameworkCommand<C>>,
+}
+
+#[allow(dead_code)]
+impl<C> Commands<C> {
+    /// Queues `command` for the audio thread.
+    ///
+    /// # Errors
+    ///
+    /// Returns the command if the queue is full.
+    pub fn send(&self, command: C) -> Result<(), C> {
+        self.sender
+            .send(FrameworkCommand::Example(command))
+            .map_err(|rejected| match rejected {
+                FrameworkCommand::Example(command) => command,
+                _ => unreachable!("only example commands are sent here"),
+            })
+    }
+}
+
+/// Lock-free ring buffer of the most recent output samples, for the visualizers.
+///
+/// Clones share the same buffer: push every sample from the audio state's
+/// `next_sample()` and return another clone from [`ExampleUi::scope`].
+#[derive(Clone)]
+pub struct Scope {
+    samples: Arc<[AtomicU64]>,
+    write_pos: Arc<AtomicUsize>,
+    sample_rate: u32,
+}
+
+#[allow(dead_code)]
+impl Scope {
+    /// Creates a scope holding the last `len` samples.
+    pub fn new(len: usize, sample_rate: u32) -> Self {
+        Self {
+            samples: (0..len.max(1)).map(|_| AtomicU64::new(0)).collect(),
+            write_pos: Arc::new(AtomicUsize::new(0)),
+            sample_rate,
+        }
+    }
+
+    /// Records a sample, overwriting the oldest one.
+    ///
+    /// Only the audio thread's clone should push.
+    pub fn push(&self, sample: f64) {
+        let pos = self.write_pos.load(Ordering::Relaxed);
+        self.samples[pos].store(sample.to_bits(), Ordering::Relaxed);
+        self.write_pos
+            .store((pos + 1) % self.samples.len(), Ordering::Relaxed);
+    }
+
+    /// Returns the recorded samples, oldest first.
+    ///
+    /// Samples pushed while copying may tear the trace slightly, which is
+    /// invisible in a display refreshed ten times a second.
+    pub fn samples(&self) -> Vec<f64> {
+        let (newest, oldest) = self
+            .samples
+            .split_at(self.write_pos.load(Ordering::Relaxed));
+        oldest
+            .iter()
+            .chain(newest)
+            .map(|bits| f64::from_bits(bits.load(Ordering::Relaxed)))
+            .collect()
+    }
+}
+
+/// Renders `samples` as an oscilloscope trace `width` columns by `height` rows.
+///
+/// Each column covers a slice of the samples and fills the rows between its
+/// minimum and maximum, so dense audio still reads as a solid shape.
+#[allow(dead_code)]
+pub fn waveform_lines(samples: &[f64], width: usize, height: usize) -> Vec<String> {
+    let mut grid = vec![vec![' '; width]; height];
+    if samples.is_empty() || width == 0 || height == 0 {
+        return grid.into_iter().map(String::from_iter).collect();
+    }
+
+    let row_of = |value: f64| {
+        let normalized = (1.0 - value.clamp(-1.0, 1.0)) / 2.0;
+        ((normalized * (height - 1) as f64).round() as usize).min(height - 1)
+    };
+    for (column, chunk) in samples
+        .chunks(samples.len().div_ceil(width))
+        .enumerate()
+        .take(width)
+    {
+        let min = chunk.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = chunk.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        for row in grid.iter_mut().take(row_of(min) + 1).skip(row_of(max)) {
+            row[column] = '#';
+        }
+    }
+    grid.into_iter().map(String::from_iter).collect()
+}
+
+/// Renders a bar spectrum of `samples`, `width` bars by `height` rows.
+///
+/// Bars are spaced logarithmically from 40Hz to 16kHz (or Nyquist) and cover
+/// a 60dB range, with full scale at the top.
+#[allow(dead_code)]
+pub fn spectrum_lines(
+    samples: &[f64],
+    sample_rate: u32,
+    width: usize,
+    height: usize,
+) -> Vec<String> {
+    const MIN_FREQ: f64 = 40.0;
+    const RANGE_DB: f64 = 60.0;
+
+    let n = samples.len();
+    let max_freq = (sample_rate as f64 / 2.0).min(16000.0);
+    let levels: Vec<f64> = (0..width)
+        .map(|bar| {
+            if n == 0 {
+                return 0.0;
+            }
+            let position = bar as f64 / (width.max(2) - 1) as f64;
+            let freq = MIN_FREQ * (max_freq / MIN_FREQ).powf(position);
+
+            // Goertzel filter over a Hann-windowed block
+            let coeff = 2.0 * (std::f64::consts::TAU * freq / sample_rate as f64).cos();
+            let (mut s1, mut s2) = (0.0, 0.0);
+            for (i, sample) in samples.iter().enumerate() {
+                let window = 0.5 - 0.5 * (std::f64::consts::TAU * i as f64 / n as f64).cos();
+                let s0 = sample * window + coeff * s1 - s2;
+                s2 = s1;
+                s1 = s0;
+            }
+            let power = (s1 * s1 + s2 * s2 - coeff * s1 * s2).max(0.0);
+            // A full-scale sine gives a magnitude of n/4 through the window
+            let magnitude = power.sqrt() / (n as f64 / 4.0);
+            let db = 20.0 * magnitude.max(1e-12).log10();
+            ((db + RANGE_DB) / RANGE_DB).clamp(0.0, 1.0)
+        })
+        .collect();
+
+    (0..height)
+        .map(|row| {
+            let threshold = (height - row) as f64 / height as f64;
+            levels
+                .iter()
+                .map(|&level| if level >= threshold { '#' } else { ' ' })
+                .collect()
+        })
+        .collect()
+}
+
+/// Renders a horizontal meter, e.g. `Envelope [########------------] 0.40`.
+#[allow(dead_code)]
+pub fn meter_line(label: &str, level: f64, width: usize) -> String {
+    let level = level.clamp(0.0, 1.0);
+    let filled = (level * width as f64).round() as usize;
+    format!(
+        "{} [{}{}] {:.2}",
+        label,
+        "#".repeat(filled),
+        "-".repeat(width - filled),
+        level
+    )
+}
+
+/// Draws the UI's visualizers (if any) at the bottom of the terminal,
+/// returning how many rows they use.
+fn draw_visualizers<U: ExampleUi>(ui: &U) -> Result<usize> {
+    const SECTION_HEIGHT: usize = 8;
+
+    let mut lines = Vec::new();
+    let (columns, rows) = crossterm::terminal::size()?;
+    let width = (columns as usize).saturating_sub(2).min(100);
+
+    if let Some(scope) = ui.scope() {
+        let samples = scope.samples();
+        lines.push("Waveform:".to_string());
+        lines.extend(waveform_lines(&samples, width, SECTION_HEIGHT));
+        lines.push("Spectrum (40Hz - 16kHz):".to_string());
+        lines.extend(spectrum_lines(
+            &samples,
+            scope.sample_rate,
+            width,
+            SECTION_HEIGHT,
+        ));
+    }
+    if let Some((label, level)) = ui.meter() {
+        lines.push(meter_line(
+            label,
+            level,
+            width.saturating_sub(label.len() + 8),
+        ));
+    }
+    if lines.is_empty() {
+        return Ok(0);
+    }
+
+    let mut stdout = stdout();
+    let top = (rows as usize).saturating_sub(lines.len());
+    for (offset, line) in lines.iter().enumerate() {
+        stdout.execute(crossterm::cursor::MoveTo(0, (top + offset) as u16))?;
+        stdout.execute(crossterm::terminal::Clear(
+            crossterm::terminal::ClearType::CurrentLine,
+        ))?;
+        write!(stdout, "{}", line)?;
+    }
+    stdout.flush()?;
+    Ok(lines.len())
+}
+
+/// Draws the most recent audio-thread log message just above the visualizers.
+///
+/// Printing from the audio callback would corrupt the raw-mode display (and is
+/// not real-time safe), so the callback records into an [`RtLogger`] and the
+/// UI loop drains it here.
+fn draw_log_line(drain: &LogDrain, last: &mut Option<String>, bottom_rows: usize) -> Result<()> {
+    drain.drain(|record| *last = Some(record.to_string()));
+    let Some(message) = last else {
+        return Ok(());
+    };
+
+    let (_, rows) = crossterm::terminal::size()?;
+    let row = (rows as usize).saturating_sub(bottom_rows + 1);
+    let mut stdout = stdout();
+    stdout.execute(crossterm::cursor::MoveTo(0, row as u16))?;
+    stdout.execute(crossterm::terminal::Clear(
+        crossterm::terminal::ClearType::CurrentLine,
+    ))?;
+    match drain.dropped() {
+        0 => write!(stdout, "{}", message)?,
+        dropped => write!(stdout, "{} ({} more dropped)", message, dropped)?,
+    }
+    stdout.flush()?;
+    Ok(())
 }
 
 /// Configuration for keyboard enhancements (needed for detecting key press/release).
@@ -34,6 +364,9 @@ pub trait ExampleAudioState: Send + 'static {
 pub struct KeyboardConfig {
     /// Enable keyboard enhancements (for press/release detection)
     pub enable_enhancements: bool,
+    /// Let the framework handle note keys, octave shifting and velocity
+    /// (see [`KeyboardState`])
+    pub musical_keys: bool,
 }
 
 impl KeyboardConfig {
@@ -42,10 +375,140 @@ impl KeyboardConfig {
     pub fn with_enhancements() -> Self {
         Self {
             enable_enhancements: true,
+            musical_keys: false,
+        }
+    }
+
+    /// Create config for playing notes: press/release detection plus
+    /// framework-handled note keys, octave shifting and velocity
+    #[allow(dead_code)]
+    pub fn with_musical_keys() -> Self {
+        Self {
+            enable_enhancements: true,
+            musical_keys: true,
         }
     }
 }
 
+/// Help line describing the keys handled by [`KeyboardState`].
+#[allow(dead_code)]
+pub const MUSICAL_KEYS_HELP: &str = "Z/X = Octave down/up | 1-9 = Velocity";
+
+/// Lowest and highest octave shift reachable with Z/X.
+const OCTAVE_SHIFT_RANGE: std::ops::RangeInclusive<i8> = -3..=3;
+
+/// Computer-keyboard performance state shared by musical examples.
+///
+/// When [`KeyboardConfig::musical_keys`] is set, the framework handles these
+/// keys itself instead of passing them to the example's key handler:
+/// - Note keys (see [`key_to_midi_note`]): call [`ExampleAudioState::note_on`]
+///   and [`ExampleAudioState::note_off`]
+/// - Z/X: shift the keyboard down/up an octave
+/// - 1-9: set the velocity of new notes (1 = softest, 9 = full)
+#[derive(Debug, Clone)]
+pub struct KeyboardState {
+    octave_shift: i8,
+    velocity: f64,
+    held: Vec<(KeyCode, u8)>, // note started by each held key
+}
+
+impl Default for KeyboardState {
+    fn default() -> Self {
+        Self {
+            octave_shift: 0,
+            velocity: 0.8,
+            held: Vec::new(),
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl KeyboardState {
+    /// Octaves added to [`key_to_midi_note`]'s mapping.
+    pub fn octave_shift(&self) -> i8 {
+        self.octave_shift
+    }
+
+    /// Velocity used for new notes (0.0-1.0).
+    pub fn velocity(&self) -> f64 {
+        self.velocity
+    }
+
+    /// Short status text, e.g. "Octave +1 | Velocity 0.80".
+    pub fn status(&self) -> String {
+        format!(
+            "Octave {:+} | Velocity {:.2}",
+            self.octave_shift, self.velocity
+        )
+    }
+
+    /// Note for `code` at the current octave shift.
+    pub fn note_for_key(&self, code: KeyCode) -> Option<u8> {
+        let note = key_to_midi_note(code)? as i16 + self.octave_shift as i16 * 12;
+        u8::try_from(note).ok().filter(|&note| note <= 127)
+    }
+
+    /// Handles a musical key, returning `false` if `event` isn't one.
+    fn handle<C, U: ExampleUi>(
+        &mut self,
+        event: &KeyEvent,
+        commands: &Commands<C>,
+        ui: &mut U,
+    ) -> bool {
+        let code = match event.code {
+            KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
+            code => code,
+        };
+
+        if let Some(note) = self.note_for_key(code) {
+            match event.kind {
+                KeyEventKind::Press if !self.held.iter().any(|&(key, _)| key == code) => {
+                    let velocity = self.velocity;
+                    self.held.push((code, note));
+                    commands
+                        .sender
+                        .send(FrameworkCommand::NoteOn { note, velocity })
+                        .ok();
+                    ui.note_on(note, velocity);
+                }
+                KeyEventKind::Release => {
+                    if let Some(index) = self.held.iter().position(|&(key, _)| key == code) {
+                        let (_, note) = self.held.swap_remove(index);
+                        commands
+                            .sender
+                            .send(FrameworkCommand::NoteOff { note })
+                            .ok();
+                        ui.note_off(note);
+                    }
+                }
+                // Key repeat, or a press we've already seen
+                _ => {}
+            }
+            return true;
+        }
+
+        let changed = match code {
+            KeyCode::Char('z') => {
+                self.octave_shift = (self.octave_shift - 1).max(*OCTAVE_SHIFT_RANGE.start());
+                true
+            }
+            KeyCode::Char('x') => {
+                self.octave_shift = (self.octave_shift + 1).min(*OCTAVE_SHIFT_RANGE.end());
+                true
+            }
+            KeyCode::Char(c @ '1'..='9') => {
+                self.velocity = c.to_digit(10).unwrap() as f64 / 9.0;
+                true
+            }
+            _ => false,
+        };
+        if changed && event.kind == KeyEventKind::Press {
+            ui.keyboard_changed(self);
+        }
+        changed
+    }
+}
+
 /// Key handling result that controls the event loop
 pub enum KeyAction {
     /// Continue the event loop
@@ -61,47 +524,67 @@ pub enum KeyAction {
 /// - Terminal raw mode and alternate screen
 /// - Panic hook for terminal cleanup
 /// - Event loop with key polling
+/// - A command queue from the key handler to the audio thread
+///
+/// The audio state is moved into the audio callback and never shared with
+/// the UI thread, so the callback never waits on a lock. The key handler
+/// keeps its own copy of whatever it displays in `ui`.
 ///
 /// # Arguments
 ///
-/// * `state` - The audio state (must implement AudioState trait)
+/// * `state` - The audio-thread state
+/// * `ui` - The UI-thread state, passed to both closures
 /// * `keyboard_config` - Configuration for keyboard handling
 /// * `initial_ui` - Closure to draw the initial UI
-/// * `key_handler` - Closure that handles key events and returns whether to continue or exit
+/// * `key_handler` - Closure that handles key events, sending any changes to
+///   the audio thread, and returns whether to continue or exit
 ///
 /// # Example
 ///
 /// ```no_run
-/// use earworm::Signal;
-///
 /// struct MyAudioState { /* ... */ }
 ///
-/// impl AudioState for MyAudioState {
+/// enum Command {
+///     Louder,
+/// }
+///
+/// impl ExampleAudioState for MyAudioState {
+///     type Command = Command;
+///
 ///     fn next_sample(&mut self) -> f64 { /* ... */ }
+///
+///     fn apply(&mut self, command: Command) { /* ... */ }
 /// }
 ///
 /// run_interactive_example(
 ///     MyAudioState::new(),
+///     (),
 ///     KeyboardConfig::default(),
-///     |state| { /* draw initial UI */ Ok(()) },
-///     |state, key_event| {
+///     |_ui| { /* draw initial UI */ Ok(()) },
+///     |_ui, commands, key_event| {
 ///         match key_event.code {
-///             KeyCode::Char('q') => KeyAction::Exit,
-///             _ => KeyAction::Continue,
+///             KeyCode::Char(' ') => {
+///                 commands.send(Command::Louder).ok();
+///                 Ok(KeyAction::Continue)
+///             }
+///             KeyCode::Char('q') => Ok(KeyAction::Exit),
+///             _ => Ok(KeyAction::Continue),
 ///         }
 ///     }
 /// )
 /// ```
-pub fn run_interactive_example<S, F, K>(
-    state: S,
+pub fn run_interactive_example<S, U, F, K>(
+    mut state: S,
+    mut ui: U,
     keyboard_config: KeyboardConfig,
     initial_ui: F,
-    key_handler: K,
+    mut key_handler: K,
 ) -> Result<()>
 where
     S: ExampleAudioState,
-    F: FnOnce(&Arc<Mutex<S>>) -> Result<()>,
-    K: Fn(&Arc<Mutex<S>>, &KeyEvent) -> Result<KeyAction>,
+    U: ExampleUi,
+    F: FnOnce(&U) -> Result<()>,
+    K: FnMut(&mut U, &Commands<S::Command>, &KeyEvent) -> Result<KeyAction>,
 {
     // Setup audio
     let host = cpal::default_host();
@@ -110,13 +593,19 @@ where
         .ok_or_else(|| anyhow::anyhow!("No output device available"))?;
 
     let config = device.default_output_config()?;
-    let state = Arc::new(Mutex::new(state));
+    let (logger, log_drain) = rt_log(64);
+    state.set_logger(logger.clone());
+    let logger = logger.with_source("audio stream");
+
+    let (sender, receiver) = command_queue(COMMAND_CAPACITY);
+    let commands = Commands { sender };
+    let audio = receiver.control(AudioSide(state));
 
     // Start audio stream
     let _stream = match config.sample_format() {
-        SampleFormat::F32 => create_audio_stream::<f32, S>(&device, &config.into(), state.clone())?,
-        SampleFormat::I16 => create_audio_stream::<i16, S>(&device, &config.into(), state.clone())?,
-        SampleFormat::U16 => create_audio_stream::<u16, S>(&device, &config.into(), state.clone())?,
+        SampleFormat::F32 => create_audio_stream::<f32, S>(&device, &config.into(), audio, logger)?,
+        SampleFormat::I16 => create_audio_stream::<i16, S>(&device, &config.into(), audio, logger)?,
+        SampleFormat::U16 => create_audio_stream::<u16, S>(&device, &config.into(), audio, logger)?,
         sample_format => {
             return Err(anyhow::anyhow!(
                 "Unsupported sample format: {}",
@@ -145,16 +634,23 @@ where
     }));
 
     // Draw initial UI
-    initial_ui(&state)?;
+    initial_ui(&ui)?;
+
+    let mut keyboard = KeyboardState::default();
+    if keyboard_config.musical_keys {
+        ui.keyboard_changed(&keyboard);
+    }
 
     // Event loop with periodic output info updates
     let mut last_output_update = std::time::Instant::now();
+    let mut last_log = None;
     loop {
         // Poll for keyboard events
         if event::poll(Duration::from_millis(50))?
             && let Event::Key(key_event) = event::read()?
+            && !(keyboard_config.musical_keys && keyboard.handle(&key_event, &commands, &mut ui))
         {
-            match key_handler(&state, &key_event)? {
+            match key_handler(&mut ui, &commands, &key_event)? {
                 KeyAction::Continue => {}
                 KeyAction::Exit => break,
             }
@@ -162,8 +658,7 @@ where
 
         // Periodically update output info display (if provided)
         if last_output_update.elapsed() >= Duration::from_millis(100) {
-            let state_guard = state.lock().unwrap();
-            if let Some(info) = state_guard.output_info() {
+            if let Some(info) = ui.output_info() {
                 // Move to second line and display output info
                 let mut stdout = stdout();
                 stdout.execute(crossterm::cursor::MoveTo(0, 1))?;
@@ -173,7 +668,8 @@ where
                 write!(stdout, "{}", info)?;
                 stdout.flush()?;
             }
-            drop(state_guard);
+            let visualizer_rows = draw_visualizers(&ui)?;
+            draw_log_line(&log_drain, &mut last_log, visualizer_rows)?;
             last_output_update = std::time::Instant::now();
         }
     }
@@ -184,11 +680,13 @@ where
     Ok(())
 }
 
-/// Creates an audio stream that pulls samples from the audio state.
+/// Creates an audio stream that owns the audio state, applying queued
+/// commands between samples.
 fn create_audio_stream<T, S>(
     device: &cpal::Device,
     config: &StreamConfig,
-    state: Arc<Mutex<S>>,
+    mut audio: Controlled<AudioSide<S>, FrameworkCommand<S::Command>>,
+    logger: RtLogger,
 ) -> Result<cpal::Stream>
 where
     T: Sample + FromSample<f64> + cpal::SizedSample,
@@ -199,16 +697,21 @@ where
     let stream = device.build_output_stream(
         config,
         move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
-            let mut state = state.lock().unwrap();
             for frame in data.chunks_mut(channels) {
-                let sample = state.next_sample();
+                let sample = audio.next_sample();
                 let value: T = T::from_sample(sample);
                 for s in frame.iter_mut() {
                     *s = value;
                 }
             }
         },
-        |err| eprintln!("Audio stream error: {}", err),
+        move |err| {
+            let message = match err {
+                cpal::StreamError::DeviceNotAvailable => "output device no longer available",
+                cpal::StreamError::BackendSpecific { .. } => "backend stream error",
+            };
+            logger.log(LogLevel::Error, message);
+        },
         None,
     )?;
 
@@ -4,15 +4,22 @@
 //! Press SPACE to cycle through compressor presets.
 //! Press UP/DOWN to adjust threshold, LEFT/RIGHT to adjust ratio.
 //! Press Q or ESC to quit.
+//!
+//! The custom preset's threshold and ratio are `ParamHandle`s shared with the
+//! compressor, so the arrow keys change them without rebuilding anything.
+//! Level meters travel the other way through handles the audio thread sets.
 
 mod common;
 
 use anyhow::Result;
-use common::{ExampleAudioState, KeyAction, KeyboardConfig, is_quit_key, run_interactive_example};
+use common::{
+    ExampleAudioState, ExampleUi, KeyAction, KeyboardConfig, is_quit_key, run_interactive_example,
+};
 use crossterm::{
     ExecutableCommand,
     event::{KeyCode, KeyEvent, KeyEventKind},
 };
+use earworm::core::ParamHandle;
 use earworm::{
     Compressor, Gain, Mix3, Multiply, Offset, Signal, SignalExt, SineOscillator, TriangleOscillator,
 };
@@ -93,30 +100,18 @@ impl CompressorWrapper {
     }
 }
 
-struct AudioState {
+/// A compressor preset with a matching uncompressed copy of its input, built
+/// on the UI thread and swapped in by the audio thread.
+struct Patch {
     signal: CompressorWrapper,
     reference_signal: DynamicSignal, // Parallel signal to track input level
-    preset: CompressorPreset,
-    threshold: f64,
-    ratio: f64,
-    // Metrics for display
-    peak_input: f64,
-    peak_output: f64,
-    peak_decay: f64,
 }
 
-impl AudioState {
-    fn new() -> Self {
-        let preset = CompressorPreset::Off;
+impl Patch {
+    fn new(preset: CompressorPreset, threshold: &ParamHandle, ratio: &ParamHandle) -> Self {
         Self {
-            signal: Self::create_signal(preset, 0.5, 4.0),
+            signal: Self::create_signal(preset, threshold, ratio),
             reference_signal: Self::create_dynamic_source(),
-            preset,
-            threshold: 0.5,
-            ratio: 4.0,
-            peak_input: 0.0,
-            peak_output: 0.0,
-            peak_decay: 0.995, // Decay coefficient for peak meter
         }
     }
 
@@ -147,7 +142,11 @@ impl AudioState {
         mixed.multiply(lfo)
     }
 
-    fn create_signal(preset: CompressorPreset, threshold: f64, ratio: f64) -> CompressorWrapper {
+    fn create_signal(
+        preset: CompressorPreset,
+        threshold: &ParamHandle,
+        ratio: &ParamHandle,
+    ) -> CompressorWrapper {
         let source = Self::create_dynamic_source();
         match preset {
             CompressorPreset::Off => CompressorWrapper::Off(source),
@@ -159,37 +158,56 @@ impl AudioState {
             }
         }
     }
+}
 
-    fn switch_preset(&mut self) {
-        self.preset = self.preset.next();
-        self.signal = Self::create_signal(self.preset, self.threshold, self.ratio);
-        self.reference_signal = Self::create_dynamic_source();
-    }
+/// Levels published by the audio thread for the UI's meters.
+#[derive(Clone)]
+struct Meters {
+    input: ParamHandle,
+    output: ParamHandle,
+    gain: ParamHandle,
+}
 
-    fn adjust_threshold(&mut self, delta: f64) {
-        self.threshold = (self.threshold + delta).clamp(0.1, 0.9);
-        if self.preset == CompressorPreset::Custom {
-            self.signal = Self::create_signal(self.preset, self.threshold, self.ratio);
-            self.reference_signal = Self::create_dynamic_source();
+impl Meters {
+    fn new() -> Self {
+        Self {
+            input: ParamHandle::new(0.0),
+            output: ParamHandle::new(0.0),
+            gain: ParamHandle::new(1.0),
         }
     }
+}
+
+struct AudioState {
+    patch: Patch,
+    // Metrics for display
+    peak_input: f64,
+    peak_output: f64,
+    peak_decay: f64,
+    meters: Meters,
+}
 
-    fn adjust_ratio(&mut self, delta: f64) {
-        self.ratio = (self.ratio + delta).clamp(1.0, 20.0);
-        if self.preset == CompressorPreset::Custom {
-            self.signal = Self::create_signal(self.preset, self.threshold, self.ratio);
-            self.reference_signal = Self::create_dynamic_source();
+impl AudioState {
+    fn new(patch: Patch, meters: Meters) -> Self {
+        Self {
+            patch,
+            peak_input: 0.0,
+            peak_output: 0.0,
+            peak_decay: 0.995, // Decay coefficient for peak meter
+            meters,
         }
     }
 }
 
 impl ExampleAudioState for AudioState {
+    type Command = Patch;
+
     fn next_sample(&mut self) -> f64 {
         // Get output from the (possibly compressed) signal
-        let output = self.signal.next_sample();
+        let output = self.patch.signal.next_sample();
 
         // Get reference input level from parallel uncompressed signal
-        let reference_input = self.reference_signal.next_sample() * 0.6;
+        let reference_input = self.patch.reference_signal.next_sample() * 0.6;
 
         let input_level = reference_input.abs();
         let output_level = output.abs();
@@ -206,21 +224,50 @@ impl ExampleAudioState for AudioState {
             self.peak_output = output_level;
         }
 
+        self.meters.input.set(self.peak_input);
+        self.meters.output.set(self.peak_output);
+        self.meters.gain.set(self.patch.signal.current_gain());
+
         output
     }
 
+    fn apply(&mut self, patch: Patch) {
+        self.patch = patch;
+    }
+}
+
+struct Ui {
+    preset: CompressorPreset,
+    threshold: ParamHandle,
+    ratio: ParamHandle,
+    meters: Meters,
+}
+
+impl Ui {
+    fn adjust_threshold(&self, delta: f64) {
+        self.threshold
+            .set((self.threshold.get() + delta).clamp(0.1, 0.9));
+    }
+
+    fn adjust_ratio(&self, delta: f64) {
+        self.ratio.set((self.ratio.get() + delta).clamp(1.0, 20.0));
+    }
+}
+
+impl ExampleUi for Ui {
     fn output_info(&self) -> Option<String> {
         // Get actual gain reduction from compressor
-        let current_gain = self.signal.current_gain();
-        let gain_reduction_db = 20.0 * current_gain.log10();
+        let gain_reduction_db = 20.0 * self.meters.gain.get().log10();
 
         // Create a simple text meter
-        let input_meter = create_meter(self.peak_input, 20);
-        let output_meter = create_meter(self.peak_output, 20);
+        let peak_input = self.meters.input.get();
+        let peak_output = self.meters.output.get();
+        let input_meter = create_meter(peak_input, 20);
+        let output_meter = create_meter(peak_output, 20);
 
         Some(format!(
             "Input: [{}] {:.2}  Output: [{}] {:.2}  GR: {:.1} dB",
-            input_meter, self.peak_input, output_meter, self.peak_output, gain_reduction_db
+            input_meter, peak_input, output_meter, peak_output, gain_reduction_db
         ))
     }
 }
@@ -232,18 +279,19 @@ fn create_meter(level: f64, width: usize) -> String {
     format!("{}{}", "█".repeat(filled), "░".repeat(empty))
 }
 
-fn draw_ui(state: &AudioState) -> Result<()> {
+fn draw_ui(ui: &Ui) -> Result<()> {
     let mut stdout = stdout();
     stdout.execute(crossterm::terminal::Clear(
         crossterm::terminal::ClearType::All,
     ))?;
     stdout.execute(crossterm::cursor::MoveTo(0, 0))?;
 
-    let preset_str = state.preset.name();
-    let params = if state.preset == CompressorPreset::Custom {
+    let preset_str = ui.preset.name();
+    let params = if ui.preset == CompressorPreset::Custom {
         format!(
             " | Threshold: {:.2} | Ratio: {:.1}:1",
-            state.threshold, state.ratio
+            ui.threshold.get(),
+            ui.ratio.get()
         )
     } else {
         String::new()
@@ -260,40 +308,43 @@ fn draw_ui(state: &AudioState) -> Result<()> {
 }
 
 fn main() -> Result<()> {
+    let ui = Ui {
+        preset: CompressorPreset::Off,
+        threshold: ParamHandle::new(0.5),
+        ratio: ParamHandle::new(4.0),
+        meters: Meters::new(),
+    };
+    let patch = Patch::new(ui.preset, &ui.threshold, &ui.ratio);
+
     run_interactive_example(
-        AudioState::new(),
+        AudioState::new(patch, ui.meters.clone()),
+        ui,
         KeyboardConfig::default(),
-        |state| {
-            let state = state.lock().unwrap();
-            draw_ui(&state)
-        },
-        |state, key_event: &KeyEvent| {
+        draw_ui,
+        |ui, commands, key_event: &KeyEvent| {
             if !matches!(key_event.kind, KeyEventKind::Press) {
                 return Ok(KeyAction::Continue);
             }
 
             match key_event.code {
                 KeyCode::Char(' ') => {
-                    state.lock().unwrap().switch_preset();
-                }
-                KeyCode::Up => {
-                    state.lock().unwrap().adjust_threshold(0.05);
-                }
-                KeyCode::Down => {
-                    state.lock().unwrap().adjust_threshold(-0.05);
-                }
-                KeyCode::Right => {
-                    state.lock().unwrap().adjust_ratio(0.5);
-                }
-                KeyCode::Left => {
-                    state.lock().unwrap().adjust_ratio(-0.5);
+                    let preset = ui.preset.next();
+                    if commands
+                        .send(Patch::new(preset, &ui.threshold, &ui.ratio))
+                        .is_ok()
+                    {
+                        ui.preset = preset;
+                    }
                 }
+                KeyCode::Up => ui.adjust_threshold(0.05),
+                KeyCode::Down => ui.adjust_threshold(-0.05),
+                KeyCode::Right => ui.adjust_ratio(0.5),
+                KeyCode::Left => ui.adjust_ratio(-0.5),
                 code if is_quit_key(code) => return Ok(KeyAction::Exit),
                 _ => return Ok(KeyAction::Continue),
             }
 
-            let state_guard = state.lock().unwrap();
-            draw_ui(&state_guard)?;
+            draw_ui(ui)?;
             Ok(KeyAction::Continue)
         },
     )?;
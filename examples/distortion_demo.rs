@@ -3,15 +3,21 @@
 //! Press SPACE to cycle through distortion types.
 //! Press UP/DOWN to adjust drive, LEFT/RIGHT to adjust mix.
 //! Press Q or ESC to quit.
+//!
+//! The custom type's drive and mix are `ParamHandle`s shared with the
+//! distortion, so adjusting them never touches the audio thread's state.
 
 mod common;
 
 use anyhow::Result;
-use common::{ExampleAudioState, KeyAction, KeyboardConfig, is_quit_key, run_interactive_example};
+use common::{
+    ExampleAudioState, ExampleUi, KeyAction, KeyboardConfig, is_quit_key, run_interactive_example,
+};
 use crossterm::{
     ExecutableCommand,
     event::{KeyCode, KeyEvent},
 };
+use earworm::core::ParamHandle;
 use earworm::{Distortion, Signal, TriangleOscillator};
 use std::io::{Write, stdout};
 
@@ -68,78 +74,81 @@ impl Signal for DistortionWrapper {
     }
 }
 
+fn create_signal(
+    dist_type: DistortionType,
+    frequency: f64,
+    drive: &ParamHandle,
+    mix: &ParamHandle,
+) -> DistortionWrapper {
+    let osc = TriangleOscillator::new(frequency);
+    match dist_type {
+        DistortionType::Clean => DistortionWrapper::Clean(osc),
+        DistortionType::Overdrive => DistortionWrapper::Overdrive(Distortion::overdrive(osc)),
+        DistortionType::Classic => DistortionWrapper::Classic(Distortion::classic(osc)),
+        DistortionType::Fuzz => DistortionWrapper::Fuzz(Distortion::fuzz(osc)),
+        DistortionType::Custom => DistortionWrapper::Custom(Distortion::new(osc, drive, mix)),
+    }
+}
+
 struct AudioState {
     signal: DistortionWrapper,
+}
+
+impl ExampleAudioState for AudioState {
+    /// A distortion of another type, built on the UI thread
+    type Command = DistortionWrapper;
+
+    fn next_sample(&mut self) -> f64 {
+        self.signal.next_sample()
+    }
+
+    fn apply(&mut self, signal: DistortionWrapper) {
+        self.signal = signal;
+    }
+}
+
+struct Ui {
     dist_type: DistortionType,
     frequency: f64,
-    drive: f64,
-    mix: f64,
+    drive: ParamHandle,
+    mix: ParamHandle,
 }
 
-impl AudioState {
+impl Ui {
     fn new(frequency: f64) -> Self {
-        let dist_type = DistortionType::Clean;
         Self {
-            signal: Self::create_signal(dist_type, frequency, 5.0, 0.7),
-            dist_type,
+            dist_type: DistortionType::Clean,
             frequency,
-            drive: 5.0,
-            mix: 0.7,
+            drive: ParamHandle::new(5.0),
+            mix: ParamHandle::new(0.7),
         }
     }
 
-    fn create_signal(
-        dist_type: DistortionType,
-        frequency: f64,
-        drive: f64,
-        mix: f64,
-    ) -> DistortionWrapper {
-        let osc = TriangleOscillator::new(frequency);
-        match dist_type {
-            DistortionType::Clean => DistortionWrapper::Clean(osc),
-            DistortionType::Overdrive => DistortionWrapper::Overdrive(Distortion::overdrive(osc)),
-            DistortionType::Classic => DistortionWrapper::Classic(Distortion::classic(osc)),
-            DistortionType::Fuzz => DistortionWrapper::Fuzz(Distortion::fuzz(osc)),
-            DistortionType::Custom => DistortionWrapper::Custom(Distortion::new(osc, drive, mix)),
-        }
+    fn create_signal(&self, dist_type: DistortionType) -> DistortionWrapper {
+        create_signal(dist_type, self.frequency, &self.drive, &self.mix)
     }
 
-    fn switch_type(&mut self) {
-        self.dist_type = self.dist_type.next();
-        self.signal = Self::create_signal(self.dist_type, self.frequency, self.drive, self.mix);
+    fn adjust_drive(&self, delta: f64) {
+        self.drive.set((self.drive.get() + delta).clamp(1.0, 50.0));
     }
 
-    fn adjust_drive(&mut self, delta: f64) {
-        self.drive = (self.drive + delta).clamp(1.0, 50.0);
-        if self.dist_type == DistortionType::Custom {
-            self.signal = Self::create_signal(self.dist_type, self.frequency, self.drive, self.mix);
-        }
-    }
-
-    fn adjust_mix(&mut self, delta: f64) {
-        self.mix = (self.mix + delta).clamp(0.0, 1.0);
-        if self.dist_type == DistortionType::Custom {
-            self.signal = Self::create_signal(self.dist_type, self.frequency, self.drive, self.mix);
-        }
+    fn adjust_mix(&self, delta: f64) {
+        self.mix.set((self.mix.get() + delta).clamp(0.0, 1.0));
     }
 }
 
-impl ExampleAudioState for AudioState {
-    fn next_sample(&mut self) -> f64 {
-        self.signal.next_sample()
-    }
-}
+impl ExampleUi for Ui {}
 
-fn draw_ui(state: &AudioState) -> Result<()> {
+fn draw_ui(ui: &Ui) -> Result<()> {
     let mut stdout = stdout();
     stdout.execute(crossterm::terminal::Clear(
         crossterm::terminal::ClearType::All,
     ))?;
     stdout.execute(crossterm::cursor::MoveTo(0, 0))?;
 
-    let type_str = state.dist_type.name();
-    let params = if state.dist_type == DistortionType::Custom {
-        format!(" | Drive: {:.1} | Mix: {:.2}", state.drive, state.mix)
+    let type_str = ui.dist_type.name();
+    let params = if ui.dist_type == DistortionType::Custom {
+        format!(" | Drive: {:.1} | Mix: {:.2}", ui.drive.get(), ui.mix.get())
     } else {
         String::new()
     };
@@ -155,36 +164,33 @@ fn draw_ui(state: &AudioState) -> Result<()> {
 }
 
 fn main() -> Result<()> {
+    let ui = Ui::new(220.0); // A3 - lower frequency shows distortion better
+    let state = AudioState {
+        signal: ui.create_signal(ui.dist_type),
+    };
+
     run_interactive_example(
-        AudioState::new(220.0), // A3 - lower frequency shows distortion better
+        state,
+        ui,
         KeyboardConfig::default(),
-        |state| {
-            let state = state.lock().unwrap();
-            draw_ui(&state)
-        },
-        |state, key_event: &KeyEvent| {
+        draw_ui,
+        |ui, commands, key_event: &KeyEvent| {
             match key_event.code {
                 KeyCode::Char(' ') => {
-                    state.lock().unwrap().switch_type();
-                }
-                KeyCode::Up => {
-                    state.lock().unwrap().adjust_drive(1.0);
-                }
-                KeyCode::Down => {
-                    state.lock().unwrap().adjust_drive(-1.0);
-                }
-                KeyCode::Right => {
-                    state.lock().unwrap().adjust_mix(0.05);
-                }
-                KeyCode::Left => {
-                    state.lock().unwrap().adjust_mix(-0.05);
+                    let dist_type = ui.dist_type.next();
+                    if commands.send(ui.create_signal(dist_type)).is_ok() {
+                        ui.dist_type = dist_type;
+                    }
                 }
+                KeyCode::Up => ui.adjust_drive(1.0),
+                KeyCode::Down => ui.adjust_drive(-1.0),
+                KeyCode::Right => ui.adjust_mix(0.05),
+                KeyCode::Left => ui.adjust_mix(-0.05),
                 code if is_quit_key(code) => return Ok(KeyAction::Exit),
                 _ => return Ok(KeyAction::Continue),
             }
 
-            let state_guard = state.lock().unwrap();
-            draw_ui(&state_guard)?;
+            draw_ui(ui)?;
             Ok(KeyAction::Continue)
         },
     )
@@ -14,14 +14,17 @@
 mod common;
 
 use anyhow::Result;
-use common::{ExampleAudioState, KeyAction, KeyboardConfig, is_quit_key, run_interactive_example};
+use common::{
+    Commands, ExampleAudioState, ExampleUi, KeyAction, KeyboardConfig, is_quit_key,
+    run_interactive_example,
+};
 use crossterm::{
     ExecutableCommand,
     event::{KeyCode, KeyEvent, KeyEventKind},
 };
+use earworm::core::ParamHandle;
 use earworm::{Signal, SineOscillator};
 use std::io::{Write, stdout};
-use std::sync::{Arc, Mutex};
 
 const SAMPLE_RATE: u32 = 44100;
 const NOTE_FREQ: f64 = 220.0; // A3
@@ -115,68 +118,83 @@ impl EnvelopeWrapper for earworm::AHD {
     }
 }
 
+fn create_envelope(env_type: EnvelopeType) -> Box<dyn EnvelopeWrapper> {
+    match env_type {
+        EnvelopeType::ADSR => Box::new(earworm::ADSR::new(0.01, 0.1, 0.7, 0.2, SAMPLE_RATE as f64)),
+        EnvelopeType::AR => Box::new(earworm::AR::new(0.01, 0.3, SAMPLE_RATE as f64)),
+        EnvelopeType::AHD => Box::new(earworm::AHD::new(0.01, 0.1, 0.4, SAMPLE_RATE as f64)),
+    }
+}
+
+/// Commands sent from the key handler to the audio thread.
+enum Command {
+    Trigger,
+    Release,
+    /// Use an envelope built on the UI thread
+    SetEnvelope(Box<dyn EnvelopeWrapper>),
+}
+
 struct EnvelopeState {
     oscillator: SineOscillator<SAMPLE_RATE>,
     envelope: Box<dyn EnvelopeWrapper>,
-    current_envelope_type: EnvelopeType,
-    note_is_held: bool,
+    active: ParamHandle, // 1.0 while the envelope is running, for the UI
 }
 
-impl EnvelopeState {
-    fn new() -> Self {
-        let oscillator = SineOscillator::new(NOTE_FREQ);
-        let envelope = Self::create_envelope(EnvelopeType::ADSR);
+impl ExampleAudioState for EnvelopeState {
+    type Command = Command;
 
-        Self {
-            oscillator,
-            envelope,
-            current_envelope_type: EnvelopeType::ADSR,
-            note_is_held: false,
-        }
+    fn next_sample(&mut self) -> f64 {
+        let osc_sample = self.oscillator.next_sample();
+        let env_sample = self.envelope.next_sample();
+        self.active
+            .set(if self.envelope.is_active() { 1.0 } else { 0.0 });
+        osc_sample * env_sample * 0.3 // Reduce volume
     }
 
-    fn create_envelope(env_type: EnvelopeType) -> Box<dyn EnvelopeWrapper> {
-        match env_type {
-            EnvelopeType::ADSR => {
-                Box::new(earworm::ADSR::new(0.01, 0.1, 0.7, 0.2, SAMPLE_RATE as f64))
-            }
-            EnvelopeType::AR => Box::new(earworm::AR::new(0.01, 0.3, SAMPLE_RATE as f64)),
-            EnvelopeType::AHD => Box::new(earworm::AHD::new(0.01, 0.1, 0.4, SAMPLE_RATE as f64)),
+    fn apply(&mut self, command: Command) {
+        match command {
+            Command::Trigger => self.envelope.trigger(0.8),
+            Command::Release => self.envelope.release(),
+            Command::SetEnvelope(envelope) => self.envelope = envelope,
         }
     }
+}
 
-    fn switch_envelope(&mut self, env_type: EnvelopeType) {
-        if env_type != self.current_envelope_type {
+struct Ui {
+    current_envelope_type: EnvelopeType,
+    note_is_held: bool,
+    active: ParamHandle,
+}
+
+impl Ui {
+    fn switch_envelope(&mut self, commands: &Commands<Command>, env_type: EnvelopeType) {
+        if env_type != self.current_envelope_type
+            && commands
+                .send(Command::SetEnvelope(create_envelope(env_type)))
+                .is_ok()
+        {
             self.current_envelope_type = env_type;
-            self.envelope = Self::create_envelope(env_type);
+            self.note_is_held = false;
         }
     }
 
-    fn trigger_note(&mut self) {
+    fn trigger_note(&mut self, commands: &Commands<Command>) {
         // Only trigger if note isn't already held
-        if !self.note_is_held {
-            self.envelope.trigger(0.8);
+        if !self.note_is_held && commands.send(Command::Trigger).is_ok() {
             self.note_is_held = true;
         }
     }
 
-    fn release_note(&mut self) {
-        if self.note_is_held {
-            self.envelope.release();
+    fn release_note(&mut self, commands: &Commands<Command>) {
+        if self.note_is_held && commands.send(Command::Release).is_ok() {
             self.note_is_held = false;
         }
     }
 }
 
-impl ExampleAudioState for EnvelopeState {
-    fn next_sample(&mut self) -> f64 {
-        let osc_sample = self.oscillator.next_sample();
-        let env_sample = self.envelope.next_sample();
-        osc_sample * env_sample * 0.3 // Reduce volume
-    }
-
+impl ExampleUi for Ui {
     fn output_info(&self) -> Option<String> {
-        let status = if self.envelope.is_active() {
+        let status = if self.active.get() > 0.0 {
             "PLAYING"
         } else {
             "IDLE"
@@ -192,51 +210,46 @@ impl ExampleAudioState for EnvelopeState {
     }
 }
 
-fn draw_ui(state: &Arc<Mutex<EnvelopeState>>) -> Result<()> {
+fn draw_ui(ui: &Ui) -> Result<()> {
     let mut stdout = stdout();
     stdout.execute(crossterm::terminal::Clear(
         crossterm::terminal::ClearType::All,
     ))?;
     stdout.execute(crossterm::cursor::MoveTo(0, 0))?;
 
-    let mode = state.lock().unwrap().current_envelope_type;
     write!(
         stdout,
         "Envelope Demo | {} | SPACE=play 1/2/3=switch Q=quit",
-        mode.name()
+        ui.current_envelope_type.name()
     )?;
     stdout.flush()?;
     Ok(())
 }
 
-fn handle_key(state: &Arc<Mutex<EnvelopeState>>, key: &KeyEvent) -> Result<KeyAction> {
+fn handle_key(ui: &mut Ui, commands: &Commands<Command>, key: &KeyEvent) -> Result<KeyAction> {
     match key.code {
         KeyCode::Char(' ') => {
             // Only trigger on key press, release on key release
             match key.kind {
-                KeyEventKind::Press => {
-                    state.lock().unwrap().trigger_note();
-                }
-                KeyEventKind::Release => {
-                    state.lock().unwrap().release_note();
-                }
+                KeyEventKind::Press => ui.trigger_note(commands),
+                KeyEventKind::Release => ui.release_note(commands),
                 _ => {}
             }
             Ok(KeyAction::Continue)
         }
         KeyCode::Char('1') => {
-            state.lock().unwrap().switch_envelope(EnvelopeType::ADSR);
-            draw_ui(state)?;
+            ui.switch_envelope(commands, EnvelopeType::ADSR);
+            draw_ui(ui)?;
             Ok(KeyAction::Continue)
         }
         KeyCode::Char('2') => {
-            state.lock().unwrap().switch_envelope(EnvelopeType::AR);
-            draw_ui(state)?;
+            ui.switch_envelope(commands, EnvelopeType::AR);
+            draw_ui(ui)?;
             Ok(KeyAction::Continue)
         }
         KeyCode::Char('3') => {
-            state.lock().unwrap().switch_envelope(EnvelopeType::AHD);
-            draw_ui(state)?;
+            ui.switch_envelope(commands, EnvelopeType::AHD);
+            draw_ui(ui)?;
             Ok(KeyAction::Continue)
         }
         code if is_quit_key(code) => Ok(KeyAction::Exit),
@@ -245,10 +258,21 @@ fn handle_key(state: &Arc<Mutex<EnvelopeState>>, key: &KeyEvent) -> Result<KeyAc
 }
 
 fn main() -> Result<()> {
-    let state = EnvelopeState::new();
+    let active = ParamHandle::new(0.0);
+    let state = EnvelopeState {
+        oscillator: SineOscillator::new(NOTE_FREQ),
+        envelope: create_envelope(EnvelopeType::ADSR),
+        active: active.clone(),
+    };
+    let ui = Ui {
+        current_envelope_type: EnvelopeType::ADSR,
+        note_is_held: false,
+        active,
+    };
 
     run_interactive_example(
         state,
+        ui,
         KeyboardConfig::with_enhancements(), // Enable key press/release detection
         draw_ui,
         handle_key,
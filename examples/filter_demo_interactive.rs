@@ -1,12 +1,19 @@
 //! Interactive filter demonstration using BiquadFilter.
 //!
-//! Press SPACE to cycle through different filter configurations.
+//! Press SPACE to cycle through different filter configurations. The waveform
+//! and spectrum at the bottom of the screen show what each filter does.
 //! Press Q or ESC to quit.
+//!
+//! Each filter chain is built on the UI thread and sent to the audio thread
+//! as a command, so switching never allocates or locks in the audio callback.
 
 mod common;
 
 use anyhow::Result;
-use common::{ExampleAudioState, KeyAction, KeyboardConfig, is_quit_key, run_interactive_example};
+use common::{
+    ExampleAudioState, ExampleUi, KeyAction, KeyboardConfig, Scope, is_quit_key,
+    run_interactive_example,
+};
 use crossterm::{
     ExecutableCommand,
     event::{KeyCode, KeyEvent},
@@ -71,6 +78,36 @@ enum FilteredSignal {
     NotchFilter(BiquadFilter<SAMPLE_RATE, TriangleOscillator<SAMPLE_RATE>>),
 }
 
+impl FilteredSignal {
+    fn new(mode: FilterMode, base_frequency: f64) -> Self {
+        let osc = TriangleOscillator::new(base_frequency);
+        match mode {
+            FilterMode::Raw => FilteredSignal::Raw(osc),
+            FilterMode::LowPass => FilteredSignal::LowPass(osc.lowpass_filter(800.0, 0.707)),
+            FilterMode::HighPass => FilteredSignal::HighPass(osc.highpass_filter(600.0, 0.707)),
+            FilterMode::BandPass => {
+                FilteredSignal::BandPass(osc.bandpass_filter(base_frequency, 5.0))
+            }
+            FilterMode::ResonantLowPass => {
+                FilteredSignal::ResonantLowPass(osc.lowpass_filter(500.0, 10.0))
+            }
+            FilterMode::SweptLowPass => {
+                let lfo = SineOscillator::<SAMPLE_RATE>::new(0.5)
+                    .gain(600.0)
+                    .offset(900.0);
+                FilteredSignal::SweptLowPass(osc.lowpass_filter(lfo, 2.0))
+            }
+            FilterMode::ChainedFilters => FilteredSignal::ChainedFilters(
+                osc.highpass_filter(100.0, 0.707)
+                    .lowpass_filter(1000.0, 0.707),
+            ),
+            FilterMode::NotchFilter => {
+                FilteredSignal::NotchFilter(osc.notch_filter(base_frequency, 8.0))
+            }
+        }
+    }
+}
+
 impl Signal for FilteredSignal {
     fn next_sample(&mut self) -> f64 {
         let sample = match self {
@@ -89,60 +126,33 @@ impl Signal for FilteredSignal {
 
 struct AudioState {
     signal: FilteredSignal,
-    mode: FilterMode,
-    base_frequency: f64,
+    scope: Scope,
 }
 
-impl AudioState {
-    fn new(base_frequency: f64) -> Self {
-        let osc = TriangleOscillator::new(base_frequency);
-        Self {
-            signal: FilteredSignal::Raw(osc),
-            mode: FilterMode::Raw,
-            base_frequency,
-        }
+impl ExampleAudioState for AudioState {
+    /// A freshly built filter chain to play instead of the current one
+    type Command = FilteredSignal;
+
+    fn next_sample(&mut self) -> f64 {
+        let sample = self.signal.next_sample();
+        self.scope.push(sample);
+        sample
     }
 
-    fn set_mode(&mut self, mode: FilterMode) {
-        self.mode = mode;
-        self.signal = match mode {
-            FilterMode::Raw => FilteredSignal::Raw(TriangleOscillator::new(self.base_frequency)),
-            FilterMode::LowPass => FilteredSignal::LowPass(
-                TriangleOscillator::new(self.base_frequency).lowpass_filter(800.0, 0.707),
-            ),
-            FilterMode::HighPass => FilteredSignal::HighPass(
-                TriangleOscillator::new(self.base_frequency).highpass_filter(600.0, 0.707),
-            ),
-            FilterMode::BandPass => FilteredSignal::BandPass(
-                TriangleOscillator::new(self.base_frequency)
-                    .bandpass_filter(self.base_frequency, 5.0),
-            ),
-            FilterMode::ResonantLowPass => FilteredSignal::ResonantLowPass(
-                TriangleOscillator::new(self.base_frequency).lowpass_filter(500.0, 10.0),
-            ),
-            FilterMode::SweptLowPass => {
-                let lfo = SineOscillator::<SAMPLE_RATE>::new(0.5)
-                    .gain(600.0)
-                    .offset(900.0);
-                FilteredSignal::SweptLowPass(
-                    TriangleOscillator::new(self.base_frequency).lowpass_filter(lfo, 2.0),
-                )
-            }
-            FilterMode::ChainedFilters => FilteredSignal::ChainedFilters(
-                TriangleOscillator::new(self.base_frequency)
-                    .highpass_filter(100.0, 0.707)
-                    .lowpass_filter(1000.0, 0.707),
-            ),
-            FilterMode::NotchFilter => FilteredSignal::NotchFilter(
-                TriangleOscillator::new(self.base_frequency).notch_filter(self.base_frequency, 8.0),
-            ),
-        };
+    fn apply(&mut self, signal: FilteredSignal) {
+        self.signal = signal;
     }
 }
 
-impl ExampleAudioState for AudioState {
-    fn next_sample(&mut self) -> f64 {
-        self.signal.next_sample()
+struct Ui {
+    mode: FilterMode,
+    base_frequency: f64,
+    scope: Scope,
+}
+
+impl ExampleUi for Ui {
+    fn scope(&self) -> Option<&Scope> {
+        Some(&self.scope)
     }
 }
 
@@ -158,17 +168,32 @@ fn draw_ui(mode: FilterMode) -> Result<()> {
 }
 
 fn main() -> Result<()> {
+    let base_frequency = 220.0;
+    let mode = FilterMode::Raw;
+    let scope = Scope::new(2048, SAMPLE_RATE);
+
     run_interactive_example(
-        AudioState::new(220.0),
+        AudioState {
+            signal: FilteredSignal::new(mode, base_frequency),
+            scope: scope.clone(),
+        },
+        Ui {
+            mode,
+            base_frequency,
+            scope,
+        },
         KeyboardConfig::default(),
-        |state| draw_ui(state.lock().unwrap().mode),
-        |state, key_event: &KeyEvent| match key_event.code {
+        |ui| draw_ui(ui.mode),
+        |ui, commands, key_event: &KeyEvent| match key_event.code {
             KeyCode::Char(' ') => {
-                let mut s = state.lock().unwrap();
-                let next_mode = s.mode.next();
-                s.set_mode(next_mode);
-                drop(s);
-                draw_ui(next_mode)?;
+                let next_mode = ui.mode.next();
+                if commands
+                    .send(FilteredSignal::new(next_mode, ui.base_frequency))
+                    .is_ok()
+                {
+                    ui.mode = next_mode;
+                }
+                draw_ui(ui.mode)?;
                 Ok(KeyAction::Continue)
             }
             code if is_quit_key(code) => Ok(KeyAction::Exit),
@@ -0,0 +1,43 @@
+//! Plays a stereo pair of tones as a named JACK client.
+//!
+//! Usage: `cargo run --example jack_output --features jack -- [CLIENT] [PORT...]`
+//!
+//! The client defaults to `earworm`, so its ports show up as
+//! `earworm_out:out_0` and `earworm_out:out_1`. With no ports named they are
+//! connected to the system playback ports; otherwise they are connected to
+//! the given ports in order, e.g. `recorder:in_1 recorder:in_2`. Under
+//! PipeWire, run it through `pw-jack`.
+
+use anyhow::Result;
+use earworm::SineOscillator;
+use earworm::playback::{AudioOutput, Backend, OutputOptions};
+use std::time::Duration;
+
+const SAMPLE_RATE: u32 = 48000;
+
+fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let mut options = OutputOptions::default().with_backend(Backend::Jack);
+    if let Some(client) = args.next() {
+        options = options.with_client_name(client);
+    }
+    options = options.with_ports(args);
+
+    // A fifth apart, so each side is easy to pick out when patching
+    let tones = [220.0, 330.0].map(SineOscillator::<SAMPLE_RATE>::new);
+    let output = AudioOutput::start_frames(tones, &options)?;
+    println!(
+        "JACK client '{}' playing on {} ports at {} Hz",
+        output.device_name(),
+        output.channels(),
+        output.stream_info().sample_rate
+    );
+    if options.ports.is_empty() {
+        println!("Connected to the system playback ports");
+    } else {
+        println!("Connected to {}", options.ports.join(", "));
+    }
+
+    std::thread::sleep(Duration::from_secs(5));
+    Ok(())
+}
@@ -7,11 +7,14 @@
 mod common;
 
 use anyhow::Result;
-use common::{ExampleAudioState, KeyAction, KeyboardConfig, is_quit_key, run_interactive_example};
+use common::{
+    ExampleAudioState, ExampleUi, KeyAction, KeyboardConfig, is_quit_key, run_interactive_example,
+};
 use crossterm::{
     ExecutableCommand,
     event::{KeyCode, KeyEvent, KeyEventKind},
 };
+use earworm::core::ParamHandle;
 use earworm::{Gain, Limiter, Mix3, Signal, SignalExt, SineOscillator};
 use std::io::{Write, stdout};
 
@@ -77,10 +80,19 @@ struct AudioState {
     peak_input: f64,
     peak_output: f64,
     peak_decay: f64,
+    meters: Meters,
+}
+
+/// Levels published by the audio thread for the UI's meters.
+#[derive(Clone)]
+struct Meters {
+    input: ParamHandle,
+    output: ParamHandle,
+    gain: ParamHandle,
 }
 
 impl AudioState {
-    fn new() -> Self {
+    fn new(meters: Meters) -> Self {
         Self {
             signal: LimitedSignal::Enabled(Limiter::new(Self::create_loud_signal(), 0.9, 0.05)),
             reference_signal: Self::create_loud_signal(),
@@ -88,6 +100,7 @@ impl AudioState {
             peak_input: 0.0,
             peak_output: 0.0,
             peak_decay: 0.995,
+            meters,
         }
     }
 
@@ -103,8 +116,10 @@ impl AudioState {
         Mix3::new(loud_osc, 1.0, harmonic1, 1.0, harmonic2, 1.0)
     }
 
-    fn toggle_limiter(&mut self) {
-        self.mode = self.mode.toggle();
+    /// Rebuilds the chain for `mode`. Everything here lives inline, so this
+    /// is safe to run on the audio thread.
+    fn set_mode(&mut self, mode: LimiterMode) {
+        self.mode = mode;
         self.signal = match self.mode {
             LimiterMode::Bypassed => LimitedSignal::Bypassed(Self::create_loud_signal()),
             LimiterMode::Enabled => {
@@ -116,6 +131,8 @@ impl AudioState {
 }
 
 impl ExampleAudioState for AudioState {
+    type Command = LimiterMode;
+
     fn next_sample(&mut self) -> f64 {
         // Get output from the (possibly limited) signal
         let output = self.signal.next_sample();
@@ -138,6 +155,10 @@ impl ExampleAudioState for AudioState {
             self.peak_output = output_level;
         }
 
+        self.meters.input.set(self.peak_input);
+        self.meters.output.set(self.peak_output);
+        self.meters.gain.set(self.signal.current_gain());
+
         // When bypassed, clamp to prevent actual clipping (just to be safe for speakers)
         // but the distortion will still be audible
         match self.mode {
@@ -146,18 +167,30 @@ impl ExampleAudioState for AudioState {
         }
     }
 
+    fn apply(&mut self, mode: LimiterMode) {
+        self.set_mode(mode);
+    }
+}
+
+struct Ui {
+    mode: LimiterMode,
+    meters: Meters,
+}
+
+impl ExampleUi for Ui {
     fn output_info(&self) -> Option<String> {
         // Get actual gain reduction from limiter
-        let current_gain = self.signal.current_gain();
-        let gain_reduction_db = 20.0 * current_gain.log10();
+        let gain_reduction_db = 20.0 * self.meters.gain.get().log10();
 
         // Create a simple text meter
-        let input_meter = create_meter(self.peak_input, 20);
-        let output_meter = create_meter(self.peak_output, 20);
+        let peak_input = self.meters.input.get();
+        let peak_output = self.meters.output.get();
+        let input_meter = create_meter(peak_input, 20);
+        let output_meter = create_meter(peak_output, 20);
 
         Some(format!(
             "Input: [{}] {:.2}  Output: [{}] {:.2}  GR: {:.1} dB",
-            input_meter, self.peak_input, output_meter, self.peak_output, gain_reduction_db
+            input_meter, peak_input, output_meter, peak_output, gain_reduction_db
         ))
     }
 }
@@ -181,22 +214,34 @@ fn draw_ui(mode: LimiterMode) -> Result<()> {
 }
 
 fn main() -> Result<()> {
+    let meters = Meters {
+        input: ParamHandle::new(0.0),
+        output: ParamHandle::new(0.0),
+        gain: ParamHandle::new(1.0),
+    };
+    let state = AudioState::new(meters.clone());
+    let ui = Ui {
+        mode: state.mode,
+        meters,
+    };
+
     run_interactive_example(
-        AudioState::new(),
+        state,
+        ui,
         KeyboardConfig::default(),
-        |state| draw_ui(state.lock().unwrap().mode),
-        |state, key_event: &KeyEvent| {
+        |ui| draw_ui(ui.mode),
+        |ui, commands, key_event: &KeyEvent| {
             if !matches!(key_event.kind, KeyEventKind::Press) {
                 return Ok(KeyAction::Continue);
             }
 
             match key_event.code {
                 KeyCode::Char(' ') => {
-                    let mut s = state.lock().unwrap();
-                    s.toggle_limiter();
-                    let mode = s.mode;
-                    drop(s);
-                    draw_ui(mode)?;
+                    let mode = ui.mode.toggle();
+                    if commands.send(mode).is_ok() {
+                        ui.mode = mode;
+                    }
+                    draw_ui(ui.mode)?;
                     Ok(KeyAction::Continue)
                 }
                 code if is_quit_key(code) => Ok(KeyAction::Exit),
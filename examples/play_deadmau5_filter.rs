@@ -61,6 +61,8 @@ impl Signal for DeadmauFilter {
 }
 
 impl ExampleAudioState for DeadmauFilter {
+    type Command = ();
+
     fn next_sample(&mut self) -> f64 {
         Signal::next_sample(self)
     }
@@ -83,9 +85,10 @@ fn draw_ui() -> Result<()> {
 fn main() -> Result<()> {
     run_interactive_example(
         DeadmauFilter::new(),
+        (),
         KeyboardConfig::default(),
-        |_state| draw_ui(),
-        |_state, key_event: &KeyEvent| {
+        |_ui| draw_ui(),
+        |_ui, _commands, key_event: &KeyEvent| {
             if is_quit_key(key_event.code) {
                 Ok(KeyAction::Exit)
             } else {
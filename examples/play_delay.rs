@@ -6,7 +6,9 @@
 mod common;
 
 use anyhow::Result;
-use common::{ExampleAudioState, KeyAction, KeyboardConfig, is_quit_key, run_interactive_example};
+use common::{
+    ExampleAudioState, ExampleUi, KeyAction, KeyboardConfig, is_quit_key, run_interactive_example,
+};
 use crossterm::{
     ExecutableCommand,
     event::{KeyCode, KeyEvent},
@@ -50,6 +52,8 @@ impl DelayType {
     }
 }
 
+impl ExampleUi for DelayType {}
+
 enum DelayWrapper {
     Slapback(Delay<SAMPLE_RATE, Gate<Gain<SineOscillator<SAMPLE_RATE>>>>),
     ShortEcho(Delay<SAMPLE_RATE, Gate<Gain<SineOscillator<SAMPLE_RATE>>>>),
@@ -95,30 +99,13 @@ impl Signal for DelayWrapper {
 
 struct AudioState {
     delay: DelayWrapper,
-    delay_type: DelayType,
-    frequency: f64,
     fade_samples: usize,
 }
 
-impl AudioState {
-    fn new(frequency: f64) -> Self {
-        let delay_type = DelayType::Slapback;
-        Self {
-            delay: DelayWrapper::new(delay_type, frequency),
-            delay_type,
-            frequency,
-            fade_samples: 0,
-        }
-    }
-
-    fn switch_delay(&mut self) {
-        self.delay_type = self.delay_type.next();
-        self.delay = DelayWrapper::new(self.delay_type, self.frequency);
-        self.fade_samples = (SAMPLE_RATE as f64 * 0.01) as usize;
-    }
-}
-
 impl ExampleAudioState for AudioState {
+    /// A delay built on the UI thread, since creating one allocates its buffer
+    type Command = DelayWrapper;
+
     fn next_sample(&mut self) -> f64 {
         let sample = self.delay.next_sample();
 
@@ -133,6 +120,11 @@ impl ExampleAudioState for AudioState {
 
         sample * 0.5
     }
+
+    fn apply(&mut self, delay: DelayWrapper) {
+        self.delay = delay;
+        self.fade_samples = (SAMPLE_RATE as f64 * 0.01) as usize;
+    }
 }
 
 fn draw_ui(delay_type: DelayType, frequency: f64) -> Result<()> {
@@ -153,21 +145,24 @@ fn draw_ui(delay_type: DelayType, frequency: f64) -> Result<()> {
 
 fn main() -> Result<()> {
     let frequency = 440.0;
+    let delay_type = DelayType::Slapback;
+    let state = AudioState {
+        delay: DelayWrapper::new(delay_type, frequency),
+        fade_samples: 0,
+    };
 
     run_interactive_example(
-        AudioState::new(frequency),
+        state,
+        delay_type,
         KeyboardConfig::default(),
-        |state| {
-            let state = state.lock().unwrap();
-            draw_ui(state.delay_type, state.frequency)
-        },
-        |state, key_event: &KeyEvent| match key_event.code {
+        |&delay_type| draw_ui(delay_type, frequency),
+        |delay_type, commands, key_event: &KeyEvent| match key_event.code {
             KeyCode::Char(' ') => {
-                let mut state = state.lock().unwrap();
-                state.switch_delay();
-                let delay_type = state.delay_type;
-                drop(state);
-                draw_ui(delay_type, frequency)?;
+                let next = delay_type.next();
+                if commands.send(DelayWrapper::new(next, frequency)).is_ok() {
+                    *delay_type = next;
+                }
+                draw_ui(*delay_type, frequency)?;
                 Ok(KeyAction::Continue)
             }
             code if is_quit_key(code) => Ok(KeyAction::Exit),
@@ -6,7 +6,9 @@
 mod common;
 
 use anyhow::Result;
-use common::{ExampleAudioState, KeyAction, KeyboardConfig, is_quit_key, run_interactive_example};
+use common::{
+    ExampleAudioState, ExampleUi, KeyAction, KeyboardConfig, is_quit_key, run_interactive_example,
+};
 use crossterm::{
     ExecutableCommand,
     event::{KeyCode, KeyEvent},
@@ -68,30 +70,23 @@ impl Signal for NoiseGenerator {
 
 struct AudioState {
     generator: NoiseGenerator,
-    noise_type: NoiseType,
-}
-
-impl AudioState {
-    fn new() -> Self {
-        let noise_type = NoiseType::White;
-        Self {
-            generator: NoiseGenerator::new(noise_type),
-            noise_type,
-        }
-    }
-
-    fn switch_noise_type(&mut self) {
-        self.noise_type = self.noise_type.next();
-        self.generator = NoiseGenerator::new(self.noise_type);
-    }
 }
 
 impl ExampleAudioState for AudioState {
+    /// A generator built on the UI thread, where seeding its RNG may block
+    type Command = NoiseGenerator;
+
     fn next_sample(&mut self) -> f64 {
         self.generator.next_sample()
     }
+
+    fn apply(&mut self, generator: NoiseGenerator) {
+        self.generator = generator;
+    }
 }
 
+impl ExampleUi for NoiseType {}
+
 fn draw_ui(noise_type: NoiseType) -> Result<()> {
     let mut stdout = stdout();
     stdout.execute(crossterm::terminal::Clear(
@@ -108,20 +103,23 @@ fn draw_ui(noise_type: NoiseType) -> Result<()> {
 }
 
 fn main() -> Result<()> {
+    let noise_type = NoiseType::White;
+    let state = AudioState {
+        generator: NoiseGenerator::new(noise_type),
+    };
+
     run_interactive_example(
-        AudioState::new(),
+        state,
+        noise_type,
         KeyboardConfig::default(),
-        |state| {
-            let noise_type = state.lock().unwrap().noise_type;
-            draw_ui(noise_type)
-        },
-        |state, key_event: &KeyEvent| match key_event.code {
+        |&noise_type| draw_ui(noise_type),
+        |noise_type, commands, key_event: &KeyEvent| match key_event.code {
             KeyCode::Char(' ') => {
-                let mut state = state.lock().unwrap();
-                state.switch_noise_type();
-                let noise_type = state.noise_type;
-                drop(state);
-                draw_ui(noise_type)?;
+                let next = noise_type.next();
+                if commands.send(NoiseGenerator::new(next)).is_ok() {
+                    *noise_type = next;
+                }
+                draw_ui(*noise_type)?;
                 Ok(KeyAction::Continue)
             }
             code if is_quit_key(code) => Ok(KeyAction::Exit),
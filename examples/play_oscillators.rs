@@ -6,7 +6,9 @@
 mod common;
 
 use anyhow::Result;
-use common::{ExampleAudioState, KeyAction, KeyboardConfig, is_quit_key, run_interactive_example};
+use common::{
+    ExampleAudioState, ExampleUi, KeyAction, KeyboardConfig, is_quit_key, run_interactive_example,
+};
 use crossterm::{
     ExecutableCommand,
     event::{KeyCode, KeyEvent},
@@ -183,30 +185,13 @@ impl Signal for OscillatorWrapper {
 
 struct AudioState {
     oscillator: OscillatorWrapper,
-    osc_type: OscillatorType,
-    frequency: f64,
     fade_samples: usize,
 }
 
-impl AudioState {
-    fn new(frequency: f64) -> Self {
-        let osc_type = OscillatorType::Sine;
-        Self {
-            oscillator: OscillatorWrapper::new(osc_type, frequency),
-            osc_type,
-            frequency,
-            fade_samples: 0,
-        }
-    }
-
-    fn switch_oscillator(&mut self) {
-        self.osc_type = self.osc_type.next();
-        self.oscillator = OscillatorWrapper::new(self.osc_type, self.frequency);
-        self.fade_samples = (SAMPLE_RATE as f64 * 0.002) as usize;
-    }
-}
-
 impl ExampleAudioState for AudioState {
+    /// An oscillator built on the UI thread, since wavetables allocate
+    type Command = OscillatorWrapper;
+
     fn next_sample(&mut self) -> f64 {
         let sample = self.oscillator.next_sample();
 
@@ -219,8 +204,15 @@ impl ExampleAudioState for AudioState {
             sample
         }
     }
+
+    fn apply(&mut self, oscillator: OscillatorWrapper) {
+        self.oscillator = oscillator;
+        self.fade_samples = (SAMPLE_RATE as f64 * 0.002) as usize;
+    }
 }
 
+impl ExampleUi for OscillatorType {}
+
 fn draw_ui(osc_type: OscillatorType, frequency: f64) -> Result<()> {
     let mut stdout = stdout();
     stdout.execute(crossterm::terminal::Clear(
@@ -239,21 +231,27 @@ fn draw_ui(osc_type: OscillatorType, frequency: f64) -> Result<()> {
 
 fn main() -> Result<()> {
     let frequency = 440.0;
+    let osc_type = OscillatorType::Sine;
+    let state = AudioState {
+        oscillator: OscillatorWrapper::new(osc_type, frequency),
+        fade_samples: 0,
+    };
 
     run_interactive_example(
-        AudioState::new(frequency),
+        state,
+        osc_type,
         KeyboardConfig::default(),
-        |state| {
-            let state = state.lock().unwrap();
-            draw_ui(state.osc_type, state.frequency)
-        },
-        |state, key_event: &KeyEvent| match key_event.code {
+        |&osc_type| draw_ui(osc_type, frequency),
+        |osc_type, commands, key_event: &KeyEvent| match key_event.code {
             KeyCode::Char(' ') => {
-                let mut state = state.lock().unwrap();
-                state.switch_oscillator();
-                let osc_type = state.osc_type;
-                drop(state);
-                draw_ui(osc_type, frequency)?;
+                let next = osc_type.next();
+                if commands
+                    .send(OscillatorWrapper::new(next, frequency))
+                    .is_ok()
+                {
+                    *osc_type = next;
+                }
+                draw_ui(*osc_type, frequency)?;
                 Ok(KeyAction::Continue)
             }
             code if is_quit_key(code) => Ok(KeyAction::Exit),
@@ -0,0 +1,43 @@
+//! Plays a tone through a selectable audio backend.
+//!
+//! Usage: `cargo run --example playback_backend --features playback -- [BACKEND] [CHANNELS]`
+//!
+//! With no arguments the platform default host is used. Pass `JACK` to play
+//! into a JACK (or PipeWire via `pw-jack`) graph, where the output ports can
+//! be connected to other software.
+
+use anyhow::Result;
+use earworm::SineOscillator;
+use earworm::playback::{AudioOutput, Backend, OutputOptions, available_backends, output_devices};
+use std::time::Duration;
+
+const SAMPLE_RATE: u32 = 48000;
+
+fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let backend = match args.next() {
+        None => Backend::Default,
+        Some(name) if name.eq_ignore_ascii_case("jack") => Backend::Jack,
+        Some(name) => Backend::Named(name),
+    };
+
+    println!("Available backends: {:?}", available_backends());
+    println!("Devices on {:?}: {:?}", backend, output_devices(&backend)?);
+
+    let mut options = OutputOptions::default().with_backend(backend);
+    if let Some(channels) = args.next() {
+        options = options.with_channels(channels.parse()?);
+    }
+
+    let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+    let output = AudioOutput::start(osc, &options)?;
+    println!(
+        "Playing 440 Hz on '{}' ({} channels, {} Hz)",
+        output.device_name(),
+        output.channels(),
+        output.sample_rate()
+    );
+
+    std::thread::sleep(Duration::from_secs(2));
+    Ok(())
+}
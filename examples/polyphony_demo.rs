@@ -28,12 +28,12 @@ mod common;
 
 use anyhow::Result;
 use common::{
-    ExampleAudioState, KeyAction, KeyboardConfig, draw_keyboard_ui, is_quit_key, key_to_midi_note,
-    midi_note_to_name, run_interactive_example,
+    Commands, ExampleAudioState, ExampleUi, KeyAction, KeyboardConfig, draw_keyboard_ui,
+    is_quit_key, key_to_midi_note, midi_note_to_name, run_interactive_example,
 };
 use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use earworm::core::ParamHandle;
 use earworm::{ADSR, Signal, SineOscillator, music::VoiceAllocator};
-use std::sync::{Arc, Mutex};
 
 const SAMPLE_RATE: u32 = 44100;
 
@@ -123,34 +123,46 @@ impl PolyAllocator {
     }
 }
 
+/// Commands sent from the key handler to the audio thread.
+enum Command {
+    NoteOn(u8),
+    NoteOff(u8),
+    /// Replace the allocator (built on the UI thread, since it allocates)
+    SetAllocator(PolyAllocator),
+}
+
 struct PolyphonyDemoState {
     allocator: PolyAllocator,
-    active_notes: Vec<u8>, // Track which notes are currently pressed
+    active_voices: ParamHandle,
 }
 
-impl PolyphonyDemoState {
-    fn new(voice_count: usize) -> Self {
-        Self {
-            allocator: PolyAllocator::new(voice_count),
-            active_notes: Vec::new(),
-        }
+impl ExampleAudioState for PolyphonyDemoState {
+    type Command = Command;
+
+    fn next_sample(&mut self) -> f64 {
+        let sample = self.allocator.next_sample() * 0.3; // Reduce volume
+        self.active_voices
+            .set(self.allocator.active_voice_count() as f64);
+        sample
     }
 
-    fn set_voice_count(&mut self, count: usize) {
-        self.allocator = PolyAllocator::new(count);
-        self.active_notes.clear();
+    fn apply(&mut self, command: Command) {
+        match command {
+            Command::NoteOn(note) => self.allocator.note_on(note, 0.8),
+            Command::NoteOff(note) => self.allocator.note_off(note),
+            Command::SetAllocator(allocator) => self.allocator = allocator,
+        }
     }
 }
 
-impl ExampleAudioState for PolyphonyDemoState {
-    fn next_sample(&mut self) -> f64 {
-        self.allocator.next_sample() * 0.3 // Reduce volume
-    }
+struct Ui {
+    max_voices: usize,
+    active_voices: ParamHandle,
+    active_notes: Vec<u8>, // Track which notes are currently pressed
+}
 
+impl ExampleUi for Ui {
     fn output_info(&self) -> Option<String> {
-        let max_voices = self.allocator.max_voices();
-        let active_voices = self.allocator.active_voice_count();
-
         let notes_str = if self.active_notes.is_empty() {
             "No notes playing".to_string()
         } else {
@@ -163,7 +175,9 @@ impl ExampleAudioState for PolyphonyDemoState {
 
         Some(format!(
             "Voices: {}/{} active | Notes: {} | Press 1-9 to change voice count",
-            active_voices, max_voices, notes_str
+            self.active_voices.get() as usize,
+            self.max_voices,
+            notes_str
         ))
     }
 }
@@ -175,13 +189,20 @@ fn draw_ui() -> Result<()> {
     )
 }
 
-fn handle_key(state: &Arc<Mutex<PolyphonyDemoState>>, key_event: &KeyEvent) -> Result<KeyAction> {
+fn handle_key(
+    ui: &mut Ui,
+    commands: &Commands<Command>,
+    key_event: &KeyEvent,
+) -> Result<KeyAction> {
     match key_event.code {
         code if is_quit_key(code) => return Ok(KeyAction::Exit),
         KeyCode::Char(c @ '1'..='9') if key_event.kind == KeyEventKind::Press => {
-            let count = c.to_digit(10).unwrap() as usize;
-            let mut s = state.lock().unwrap();
-            s.set_voice_count(count);
+            let allocator = PolyAllocator::new(c.to_digit(10).unwrap() as usize);
+            let max_voices = allocator.max_voices();
+            if commands.send(Command::SetAllocator(allocator)).is_ok() {
+                ui.max_voices = max_voices;
+                ui.active_notes.clear();
+            }
             return Ok(KeyAction::Continue);
         }
         _ => {}
@@ -191,21 +212,21 @@ fn handle_key(state: &Arc<Mutex<PolyphonyDemoState>>, key_event: &KeyEvent) -> R
     match key_event.kind {
         KeyEventKind::Press => {
             if let Some(midi_note) = key_to_midi_note(key_event.code) {
-                let mut s = state.lock().unwrap();
                 // Only trigger note_on if this note isn't already active
-                if !s.active_notes.contains(&midi_note) {
-                    s.active_notes.push(midi_note);
-                    s.allocator.note_on(midi_note, 0.8);
+                if !ui.active_notes.contains(&midi_note)
+                    && commands.send(Command::NoteOn(midi_note)).is_ok()
+                {
+                    ui.active_notes.push(midi_note);
                 }
             }
         }
         KeyEventKind::Release => {
             if let Some(released_note) = key_to_midi_note(key_event.code) {
-                let mut s = state.lock().unwrap();
                 // Remove from active notes and trigger note_off
-                if let Some(pos) = s.active_notes.iter().position(|&n| n == released_note) {
-                    s.active_notes.remove(pos);
-                    s.allocator.note_off(released_note);
+                if let Some(pos) = ui.active_notes.iter().position(|&n| n == released_note)
+                    && commands.send(Command::NoteOff(released_note)).is_ok()
+                {
+                    ui.active_notes.remove(pos);
                 }
             }
         }
@@ -216,10 +237,22 @@ fn handle_key(state: &Arc<Mutex<PolyphonyDemoState>>, key_event: &KeyEvent) -> R
 }
 
 fn main() -> Result<()> {
+    let allocator = PolyAllocator::new(4); // Start with 4 voices
+    let active_voices = ParamHandle::new(0.0);
+    let ui = Ui {
+        max_voices: allocator.max_voices(),
+        active_voices: active_voices.clone(),
+        active_notes: Vec::new(),
+    };
+
     run_interactive_example(
-        PolyphonyDemoState::new(4), // Start with 4 voices
+        PolyphonyDemoState {
+            allocator,
+            active_voices,
+        },
+        ui,
         KeyboardConfig::with_enhancements(),
-        |_state| draw_ui(),
+        |_ui| draw_ui(),
         handle_key,
     )
 }
@@ -10,7 +10,9 @@
 mod common;
 
 use anyhow::Result;
-use common::{ExampleAudioState, KeyAction, KeyboardConfig, is_quit_key, run_interactive_example};
+use common::{
+    ExampleAudioState, ExampleUi, KeyAction, KeyboardConfig, is_quit_key, run_interactive_example,
+};
 use crossterm::{
     ExecutableCommand,
     event::{KeyCode, KeyEvent},
@@ -52,56 +54,38 @@ impl ModulationType {
 }
 
 struct AudioState {
-    carrier_freq: f64,
-    mod_type: ModulationType,
     signal: Box<dyn Signal + Send>,
     fade_samples: usize,
 }
 
-impl AudioState {
-    fn new(carrier_freq: f64) -> Self {
-        let mod_type = ModulationType::None;
-        let signal = Self::create_signal(mod_type, carrier_freq);
-        Self {
-            carrier_freq,
-            mod_type,
-            signal,
-            fade_samples: 0,
-        }
-    }
+fn create_signal(mod_type: ModulationType, carrier_freq: f64) -> Box<dyn Signal + Send> {
+    let carrier = SineOscillator::<SAMPLE_RATE>::new(carrier_freq);
 
-    fn create_signal(mod_type: ModulationType, carrier_freq: f64) -> Box<dyn Signal + Send> {
-        let carrier = SineOscillator::<SAMPLE_RATE>::new(carrier_freq);
-
-        match mod_type {
-            ModulationType::None => Box::new(carrier.gain(0.3)),
-            ModulationType::Tremolo => {
-                let lfo = SineOscillator::<SAMPLE_RATE>::new(6.0);
-                Box::new(carrier.multiply(lfo.offset(1.0).gain(0.5)).gain(0.3))
-            }
-            ModulationType::RingLow => {
-                let modulator = SineOscillator::<SAMPLE_RATE>::new(30.0);
-                Box::new(carrier.multiply(modulator).gain(0.3))
-            }
-            ModulationType::RingHarmonic => {
-                let modulator = SineOscillator::<SAMPLE_RATE>::new(carrier_freq * 1.5);
-                Box::new(carrier.multiply(modulator).gain(0.3))
-            }
-            ModulationType::RingInharmonic => {
-                let modulator = SineOscillator::<SAMPLE_RATE>::new(573.0);
-                Box::new(carrier.multiply(modulator).gain(0.3))
-            }
+    match mod_type {
+        ModulationType::None => Box::new(carrier.gain(0.3)),
+        ModulationType::Tremolo => {
+            let lfo = SineOscillator::<SAMPLE_RATE>::new(6.0);
+            Box::new(carrier.multiply(lfo.offset(1.0).gain(0.5)).gain(0.3))
+        }
+        ModulationType::RingLow => {
+            let modulator = SineOscillator::<SAMPLE_RATE>::new(30.0);
+            Box::new(carrier.multiply(modulator).gain(0.3))
+        }
+        ModulationType::RingHarmonic => {
+            let modulator = SineOscillator::<SAMPLE_RATE>::new(carrier_freq * 1.5);
+            Box::new(carrier.multiply(modulator).gain(0.3))
+        }
+        ModulationType::RingInharmonic => {
+            let modulator = SineOscillator::<SAMPLE_RATE>::new(573.0);
+            Box::new(carrier.multiply(modulator).gain(0.3))
         }
-    }
-
-    fn switch_modulation(&mut self) {
-        self.mod_type = self.mod_type.next();
-        self.signal = Self::create_signal(self.mod_type, self.carrier_freq);
-        self.fade_samples = (SAMPLE_RATE as f64 * 0.002) as usize;
     }
 }
 
 impl ExampleAudioState for AudioState {
+    /// A patch built on the UI thread, faded in on arrival
+    type Command = Box<dyn Signal + Send>;
+
     fn next_sample(&mut self) -> f64 {
         let sample = self.signal.next_sample();
 
@@ -114,8 +98,15 @@ impl ExampleAudioState for AudioState {
             sample
         }
     }
+
+    fn apply(&mut self, signal: Box<dyn Signal + Send>) {
+        self.signal = signal;
+        self.fade_samples = (SAMPLE_RATE as f64 * 0.002) as usize;
+    }
 }
 
+impl ExampleUi for ModulationType {}
+
 fn draw_ui(mod_type: ModulationType) -> Result<()> {
     let mut stdout = stdout();
     stdout.execute(crossterm::terminal::Clear(
@@ -128,17 +119,25 @@ fn draw_ui(mod_type: ModulationType) -> Result<()> {
 }
 
 fn main() -> Result<()> {
+    let carrier_freq = 440.0;
+    let mod_type = ModulationType::None;
+    let state = AudioState {
+        signal: create_signal(mod_type, carrier_freq),
+        fade_samples: 0,
+    };
+
     run_interactive_example(
-        AudioState::new(440.0),
+        state,
+        mod_type,
         KeyboardConfig::default(),
-        |state| draw_ui(state.lock().unwrap().mod_type),
-        |state, key_event: &KeyEvent| match key_event.code {
+        |&mod_type| draw_ui(mod_type),
+        |mod_type, commands, key_event: &KeyEvent| match key_event.code {
             KeyCode::Char(' ') => {
-                let mut state = state.lock().unwrap();
-                state.switch_modulation();
-                let mod_type = state.mod_type;
-                drop(state);
-                draw_ui(mod_type)?;
+                let next = mod_type.next();
+                if commands.send(create_signal(next, carrier_freq)).is_ok() {
+                    *mod_type = next;
+                }
+                draw_ui(*mod_type)?;
                 Ok(KeyAction::Continue)
             }
             code if is_quit_key(code) => Ok(KeyAction::Exit),
@@ -6,7 +6,6 @@
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use earworm::music::{ADSR, Pattern, Sequencer, StealingStrategy, VoiceAllocator};
 use earworm::{NoteEvent, Pitch, SawtoothOscillator, Signal};
-use std::sync::{Arc, Mutex};
 
 const SAMPLE_RATE: u32 = 44100;
 const VOICES: usize = 4;
@@ -36,7 +35,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     // Create synth with punchy envelope
-    let synth = VoiceAllocator::<SAMPLE_RATE, VOICES, _, _>::new(|| {
+    let mut synth = VoiceAllocator::<SAMPLE_RATE, VOICES, _, _>::new(|| {
         let osc = SawtoothOscillator::new(440.0);
         let env = ADSR::new(0.005, 0.1, 0.3, 0.2, SAMPLE_RATE as f64);
         (osc, env)
@@ -62,23 +61,16 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let config = device.default_output_config()?;
 
-    // Separate locks to avoid borrow conflicts
-    let sequencer = Arc::new(Mutex::new(sequencer));
-    let synth = Arc::new(Mutex::new(synth));
-
-    let sequencer_clone = Arc::clone(&sequencer);
-    let synth_clone = Arc::clone(&synth);
-
+    // Only the audio callback touches the sequencer and synth, so it owns them
     let stream = device.build_output_stream(
         &config.into(),
         move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
             for frame in data.chunks_mut(2) {
                 // Tick the sequencer - now returns events directly!
-                let events_opt = sequencer_clone.lock().unwrap().tick();
+                let events_opt = sequencer.tick();
 
                 if let Some(events) = events_opt {
                     // Trigger notes (no more copying needed at this level!)
-                    let mut synth = synth_clone.lock().unwrap();
                     for event in events {
                         println!(
                             "  → Note: {:?} at velocity {:.2}",
@@ -92,7 +84,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
 
                 // Generate audio sample
-                let sample = synth_clone.lock().unwrap().next_sample() * 0.3;
+                let sample = synth.next_sample() * 0.3;
 
                 // Write to both channels
                 frame[0] = sample as f32;
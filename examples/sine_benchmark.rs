@@ -0,0 +1,64 @@
+//! Benchmark for the fast sine approximation.
+//!
+//! Renders a sine oscillator and a sine LFO with `SinePrecision::Exact` and
+//! `SinePrecision::Fast`, prints the time per second of audio, and measures
+//! the largest difference between the two:
+//!
+//! ```bash
+//! cargo run --release --example sine_benchmark
+//! ```
+
+use earworm::{Lfo, LfoWaveform, Signal, SineOscillator, SinePrecision};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const SAMPLE_RATE: u32 = 44100;
+const SECONDS: usize = 20;
+
+/// Renders `SECONDS` of audio one sample at a time.
+fn render(signal: &mut impl Signal) -> Duration {
+    let start = Instant::now();
+    for _ in 0..SECONDS * SAMPLE_RATE as usize {
+        black_box(signal.next_sample());
+    }
+    start.elapsed()
+}
+
+/// Largest absolute difference between two signals over one second.
+fn max_error(mut a: impl Signal, mut b: impl Signal) -> f64 {
+    (0..SAMPLE_RATE)
+        .map(|_| (a.next_sample() - b.next_sample()).abs())
+        .fold(0.0, f64::max)
+}
+
+fn report(name: &str, exact: Duration, fast: Duration, error: f64) {
+    let per_second = |d: Duration| d.as_secs_f64() * 1000.0 / SECONDS as f64;
+    println!(
+        "{:<12} exact {:>7.3} ms/s   fast {:>7.3} ms/s   speedup {:>5.2}x   max error {:.1e}",
+        name,
+        per_second(exact),
+        per_second(fast),
+        exact.as_secs_f64() / fast.as_secs_f64(),
+        error
+    );
+}
+
+fn main() {
+    println!("Rendering {} s per test\n", SECONDS);
+
+    let osc = |precision| SineOscillator::<SAMPLE_RATE>::new(441.3).with_precision(precision);
+    report(
+        "oscillator",
+        render(&mut osc(SinePrecision::Exact)),
+        render(&mut osc(SinePrecision::Fast)),
+        max_error(osc(SinePrecision::Exact), osc(SinePrecision::Fast)),
+    );
+
+    let lfo = |precision| Lfo::<SAMPLE_RATE>::new(LfoWaveform::Sine, 5.3).with_precision(precision);
+    report(
+        "lfo",
+        render(&mut lfo(SinePrecision::Exact)),
+        render(&mut lfo(SinePrecision::Fast)),
+        max_error(lfo(SinePrecision::Exact), lfo(SinePrecision::Fast)),
+    );
+}
@@ -7,113 +7,132 @@
 mod common;
 
 use anyhow::Result;
-use common::{ExampleAudioState, KeyAction, KeyboardConfig, is_quit_key, run_interactive_example};
+use common::{
+    ExampleAudioState, ExampleUi, KeyAction, KeyboardConfig, is_quit_key, run_interactive_example,
+};
 use crossterm::{
     ExecutableCommand,
     event::{KeyCode, KeyEvent},
 };
-use earworm::{Signal, SineOscillator};
+use earworm::core::ParamHandle;
+use earworm::{Pitched, Signal, SineOscillator};
 use std::io::{Write, stdout};
 
 const SAMPLE_RATE: u32 = 44100;
 
-struct AudioState {
-    oscillator: SineOscillator<SAMPLE_RATE>,
-    lfo: SineOscillator<SAMPLE_RATE>,
-    rate: f64,
-    depth: f64,
-    tremolo_enabled: bool,
+/// Parameters shared between the UI and audio threads.
+#[derive(Clone)]
+struct Controls {
+    rate: ParamHandle,
+    depth: ParamHandle,
+    enabled: ParamHandle,
 }
 
-impl AudioState {
-    fn new(frequency: f64) -> Self {
-        let rate = 5.0;
+impl Controls {
+    fn new() -> Self {
         Self {
-            oscillator: SineOscillator::new(frequency),
-            lfo: SineOscillator::new(rate),
-            rate,
-            depth: 0.5,
-            tremolo_enabled: false,
+            rate: ParamHandle::new(5.0),
+            depth: ParamHandle::new(0.5),
+            enabled: ParamHandle::new(0.0),
         }
     }
 
-    fn toggle_tremolo(&mut self) {
-        self.tremolo_enabled = !self.tremolo_enabled;
+    fn toggle_tremolo(&self) {
+        self.enabled.set(if self.is_enabled() { 0.0 } else { 1.0 });
     }
 
-    fn adjust_rate(&mut self, delta: f64) {
-        self.rate = (self.rate + delta).clamp(0.5, 20.0);
-        self.lfo = SineOscillator::new(self.rate);
+    fn is_enabled(&self) -> bool {
+        self.enabled.get() > 0.5
     }
 
-    fn adjust_depth(&mut self, delta: f64) {
-        self.depth = (self.depth + delta).clamp(0.0, 1.0);
+    fn adjust_rate(&self, delta: f64) {
+        self.rate.set((self.rate.get() + delta).clamp(0.5, 20.0));
+    }
+
+    fn adjust_depth(&self, delta: f64) {
+        self.depth.set((self.depth.get() + delta).clamp(0.0, 1.0));
+    }
+}
+
+struct AudioState {
+    oscillator: SineOscillator<SAMPLE_RATE>,
+    lfo: SineOscillator<SAMPLE_RATE>,
+    controls: Controls,
+}
+
+impl AudioState {
+    fn new(frequency: f64, controls: Controls) -> Self {
+        Self {
+            oscillator: SineOscillator::new(frequency),
+            lfo: SineOscillator::new(controls.rate.get()),
+            controls,
+        }
     }
 }
 
 impl ExampleAudioState for AudioState {
+    type Command = ();
+
     fn next_sample(&mut self) -> f64 {
         let audio = self.oscillator.next_sample();
 
-        if self.tremolo_enabled {
-            let mod_value = self.lfo.next_sample();
-            let gain = 1.0 + self.depth / 2.0 * (mod_value - 1.0);
+        // Retune rather than rebuild the LFO so its phase stays continuous
+        let rate = self.controls.rate.get();
+        if self.lfo.frequency() != rate {
+            self.lfo.set_frequency(rate);
+        }
+        let mod_value = self.lfo.next_sample();
+
+        if self.controls.is_enabled() {
+            let gain = 1.0 + self.controls.depth.get() / 2.0 * (mod_value - 1.0);
             audio * gain * 0.3
         } else {
-            self.lfo.next_sample();
             audio * 0.3
         }
     }
 }
 
-fn draw_ui(state: &AudioState) -> Result<()> {
+impl ExampleUi for Controls {}
+
+fn draw_ui(controls: &Controls) -> Result<()> {
     let mut stdout = stdout();
     stdout.execute(crossterm::terminal::Clear(
         crossterm::terminal::ClearType::All,
     ))?;
     stdout.execute(crossterm::cursor::MoveTo(0, 0))?;
 
-    let status = if state.tremolo_enabled { "ON" } else { "OFF" };
+    let status = if controls.is_enabled() { "ON" } else { "OFF" };
     write!(
         stdout,
         "Tremolo: {} | Rate: {:.1}Hz | Depth: {:.2} | SPACE=toggle ↑↓=rate ←→=depth Q=quit",
-        status, state.rate, state.depth
+        status,
+        controls.rate.get(),
+        controls.depth.get()
     )?;
     stdout.flush()?;
     Ok(())
 }
 
 fn main() -> Result<()> {
+    let controls = Controls::new();
+
     run_interactive_example(
-        AudioState::new(440.0),
+        AudioState::new(440.0, controls.clone()),
+        controls,
         KeyboardConfig::default(),
-        |state| {
-            let state = state.lock().unwrap();
-            draw_ui(&state)
-        },
-        |state, key_event: &KeyEvent| {
+        draw_ui,
+        |controls, _commands, key_event: &KeyEvent| {
             match key_event.code {
-                KeyCode::Char(' ') => {
-                    state.lock().unwrap().toggle_tremolo();
-                }
-                KeyCode::Up => {
-                    state.lock().unwrap().adjust_rate(0.5);
-                }
-                KeyCode::Down => {
-                    state.lock().unwrap().adjust_rate(-0.5);
-                }
-                KeyCode::Right => {
-                    state.lock().unwrap().adjust_depth(0.05);
-                }
-                KeyCode::Left => {
-                    state.lock().unwrap().adjust_depth(-0.05);
-                }
+                KeyCode::Char(' ') => controls.toggle_tremolo(),
+                KeyCode::Up => controls.adjust_rate(0.5),
+                KeyCode::Down => controls.adjust_rate(-0.5),
+                KeyCode::Right => controls.adjust_depth(0.05),
+                KeyCode::Left => controls.adjust_depth(-0.05),
                 code if is_quit_key(code) => return Ok(KeyAction::Exit),
                 _ => return Ok(KeyAction::Continue),
             }
 
-            let state_guard = state.lock().unwrap();
-            draw_ui(&state_guard)?;
+            draw_ui(controls)?;
             Ok(KeyAction::Continue)
         },
     )
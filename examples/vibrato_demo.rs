@@ -8,11 +8,14 @@
 mod common;
 
 use anyhow::Result;
-use common::{ExampleAudioState, KeyAction, KeyboardConfig, is_quit_key, run_interactive_example};
+use common::{
+    ExampleAudioState, ExampleUi, KeyAction, KeyboardConfig, is_quit_key, run_interactive_example,
+};
 use crossterm::{
     ExecutableCommand,
     event::{KeyCode, KeyEvent, KeyEventKind},
 };
+use earworm::core::ParamHandle;
 use earworm::{Signal, SineOscillator, Vibrato};
 use std::io::{Write, stdout};
 
@@ -69,84 +72,63 @@ impl Signal for VibratoWrapper {
     }
 }
 
-struct AudioState {
-    signal: VibratoWrapper,
+/// Builds the chain for `preset`. The custom preset reads rate and depth
+/// from the handles, so adjusting them never rebuilds the signal.
+fn create_signal(
     preset: VibratoPreset,
     frequency: f64,
-    rate: f64,
-    depth: f64,
-}
-
-impl AudioState {
-    fn new(frequency: f64) -> Self {
-        let preset = VibratoPreset::Off;
-        Self {
-            signal: Self::create_signal(preset, frequency, 5.0, 20.0),
-            preset,
-            frequency,
-            rate: 5.0,
-            depth: 20.0,
-        }
-    }
-
-    fn create_oscillator(frequency: f64) -> SineOscillator<SAMPLE_RATE> {
-        SineOscillator::<SAMPLE_RATE>::new(frequency)
+    rate: &ParamHandle,
+    depth: &ParamHandle,
+) -> VibratoWrapper {
+    let osc = SineOscillator::<SAMPLE_RATE>::new(frequency);
+    match preset {
+        VibratoPreset::Off => VibratoWrapper::Off(osc),
+        VibratoPreset::Subtle => VibratoWrapper::Subtle(Vibrato::subtle(osc)),
+        VibratoPreset::Guitar => VibratoWrapper::Guitar(Vibrato::guitar(osc)),
+        VibratoPreset::Wide => VibratoWrapper::Wide(Vibrato::wide(osc)),
+        VibratoPreset::Custom => VibratoWrapper::Custom(Vibrato::new(osc, rate, depth)),
     }
+}
 
-    fn create_signal(
-        preset: VibratoPreset,
-        frequency: f64,
-        rate: f64,
-        depth: f64,
-    ) -> VibratoWrapper {
-        let osc = Self::create_oscillator(frequency);
-        match preset {
-            VibratoPreset::Off => VibratoWrapper::Off(osc),
-            VibratoPreset::Subtle => VibratoWrapper::Subtle(Vibrato::subtle(osc)),
-            VibratoPreset::Guitar => VibratoWrapper::Guitar(Vibrato::guitar(osc)),
-            VibratoPreset::Wide => VibratoWrapper::Wide(Vibrato::wide(osc)),
-            VibratoPreset::Custom => VibratoWrapper::Custom(Vibrato::new(osc, rate, depth)),
-        }
-    }
+struct AudioState {
+    signal: VibratoWrapper,
+}
 
-    fn switch_preset(&mut self) {
-        self.preset = self.preset.next();
-        self.signal = Self::create_signal(self.preset, self.frequency, self.rate, self.depth);
-    }
+impl ExampleAudioState for AudioState {
+    /// A chain built on the UI thread, since the vibrato delay line allocates
+    type Command = VibratoWrapper;
 
-    fn adjust_rate(&mut self, delta: f64) {
-        self.rate = (self.rate + delta).clamp(0.5, 20.0);
-        if self.preset == VibratoPreset::Custom {
-            self.signal = Self::create_signal(self.preset, self.frequency, self.rate, self.depth);
-        }
+    fn next_sample(&mut self) -> f64 {
+        self.signal.next_sample()
     }
 
-    fn adjust_depth(&mut self, delta: f64) {
-        self.depth = (self.depth + delta).clamp(0.0, 100.0);
-        if self.preset == VibratoPreset::Custom {
-            self.signal = Self::create_signal(self.preset, self.frequency, self.rate, self.depth);
-        }
+    fn apply(&mut self, signal: VibratoWrapper) {
+        self.signal = signal;
     }
 }
 
-impl ExampleAudioState for AudioState {
-    fn next_sample(&mut self) -> f64 {
-        self.signal.next_sample()
-    }
+struct Ui {
+    preset: VibratoPreset,
+    frequency: f64,
+    rate: ParamHandle,
+    depth: ParamHandle,
 }
 
-fn draw_ui(state: &AudioState) -> Result<()> {
+impl ExampleUi for Ui {}
+
+fn draw_ui(ui: &Ui) -> Result<()> {
     let mut stdout = stdout();
     stdout.execute(crossterm::terminal::Clear(
         crossterm::terminal::ClearType::All,
     ))?;
     stdout.execute(crossterm::cursor::MoveTo(0, 0))?;
 
-    let preset_str = state.preset.name();
-    let params = if state.preset == VibratoPreset::Custom {
+    let preset_str = ui.preset.name();
+    let params = if ui.preset == VibratoPreset::Custom {
         format!(
             " | Rate: {:.1} Hz | Depth: {:.0} cents",
-            state.rate, state.depth
+            ui.rate.get(),
+            ui.depth.get()
         )
     } else {
         String::new()
@@ -163,40 +145,43 @@ fn draw_ui(state: &AudioState) -> Result<()> {
 }
 
 fn main() -> Result<()> {
+    let ui = Ui {
+        preset: VibratoPreset::Off,
+        frequency: 440.0, // A4
+        rate: ParamHandle::new(5.0),
+        depth: ParamHandle::new(20.0),
+    };
+    let state = AudioState {
+        signal: create_signal(ui.preset, ui.frequency, &ui.rate, &ui.depth),
+    };
+
     run_interactive_example(
-        AudioState::new(440.0), // A4
+        state,
+        ui,
         KeyboardConfig::default(),
-        |state| {
-            let state = state.lock().unwrap();
-            draw_ui(&state)
-        },
-        |state, key_event: &KeyEvent| {
+        draw_ui,
+        |ui, commands, key_event: &KeyEvent| {
             if !matches!(key_event.kind, KeyEventKind::Press) {
                 return Ok(KeyAction::Continue);
             }
 
             match key_event.code {
                 KeyCode::Char(' ') => {
-                    state.lock().unwrap().switch_preset();
-                }
-                KeyCode::Up => {
-                    state.lock().unwrap().adjust_rate(0.5);
-                }
-                KeyCode::Down => {
-                    state.lock().unwrap().adjust_rate(-0.5);
-                }
-                KeyCode::Right => {
-                    state.lock().unwrap().adjust_depth(5.0);
-                }
-                KeyCode::Left => {
-                    state.lock().unwrap().adjust_depth(-5.0);
+                    let preset = ui.preset.next();
+                    let signal = create_signal(preset, ui.frequency, &ui.rate, &ui.depth);
+                    if commands.send(signal).is_ok() {
+                        ui.preset = preset;
+                    }
                 }
+                KeyCode::Up => ui.rate.set((ui.rate.get() + 0.5).clamp(0.5, 20.0)),
+                KeyCode::Down => ui.rate.set((ui.rate.get() - 0.5).clamp(0.5, 20.0)),
+                KeyCode::Right => ui.depth.set((ui.depth.get() + 5.0).clamp(0.0, 100.0)),
+                KeyCode::Left => ui.depth.set((ui.depth.get() - 5.0).clamp(0.0, 100.0)),
                 code if is_quit_key(code) => return Ok(KeyAction::Exit),
                 _ => return Ok(KeyAction::Continue),
             }
 
-            let state_guard = state.lock().unwrap();
-            draw_ui(&state_guard)?;
+            draw_ui(ui)?;
             Ok(KeyAction::Continue)
         },
     )?;
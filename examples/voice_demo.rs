@@ -3,8 +3,8 @@
 //! This example demonstrates:
 //! - Voice with ADSR envelope control
 //! - Note on/off behavior
-//! - Keyboard-to-MIDI mapping
-//! - Real-time envelope state visualization
+//! - Keyboard-to-MIDI mapping with octave shifting and velocity
+//! - Real-time envelope state visualization with a level meter
 //!
 //! ## Controls
 //!
@@ -12,6 +12,10 @@
 //! - Bottom row (A-L): White keys (C4-D5)
 //! - Top row (W-O, T-Y-U, P): Black keys (sharps)
 //!
+//! **Performance:**
+//! - Z/X: Octave down/up
+//! - 1-9: Velocity (1 = softest, 9 = full)
+//!
 //! **Other:**
 //! - Q or ESC: Quit
 //!
@@ -27,21 +31,30 @@ mod common;
 
 use anyhow::Result;
 use common::{
-    ExampleAudioState, KeyAction, KeyboardConfig, draw_keyboard_ui, is_quit_key, key_to_midi_note,
-    midi_note_to_name, run_interactive_example,
+    ExampleAudioState, ExampleUi, KeyAction, KeyboardConfig, KeyboardState, MUSICAL_KEYS_HELP,
+    draw_keyboard_ui, is_quit_key, midi_note_to_name, run_interactive_example,
 };
-use crossterm::event::{KeyEvent, KeyEventKind};
+use crossterm::event::KeyEvent;
+use earworm::core::ParamHandle;
 use earworm::{ADSR, Signal, SineOscillator, music::Voice};
 
 const SAMPLE_RATE: u32 = 44100;
 
+/// Voice state published by the audio thread for display.
+#[derive(Clone)]
+struct VoiceLevels {
+    envelope: ParamHandle,
+    active: ParamHandle,
+}
+
 struct VoiceDemoState {
     voice: Voice<SAMPLE_RATE, SineOscillator<SAMPLE_RATE>, ADSR>,
     current_note: Option<u8>,
+    levels: VoiceLevels,
 }
 
 impl VoiceDemoState {
-    fn new() -> Self {
+    fn new(levels: VoiceLevels) -> Self {
         let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
         let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
         let voice = Voice::new(osc, env);
@@ -49,30 +62,49 @@ impl VoiceDemoState {
         Self {
             voice,
             current_note: None,
+            levels,
         }
     }
+}
 
-    fn note_on(&mut self, midi_note: u8) {
-        self.current_note = Some(midi_note);
-        self.voice.note_on(midi_note, 0.8);
+impl ExampleAudioState for VoiceDemoState {
+    type Command = ();
+
+    fn next_sample(&mut self) -> f64 {
+        let sample = self.voice.next_sample() * 0.3; // Reduce volume
+        self.levels.envelope.set(self.voice.envelope_level());
+        self.levels
+            .active
+            .set(if self.voice.is_active() { 1.0 } else { 0.0 });
+        sample
     }
 
-    fn note_off(&mut self) {
-        self.voice.note_off();
-        self.current_note = None;
+    fn note_on(&mut self, note: u8, velocity: f64) {
+        self.current_note = Some(note);
+        self.voice.note_on(note, velocity);
     }
 
-    fn is_active(&self) -> bool {
-        self.voice.is_active()
+    fn note_off(&mut self, note: u8) {
+        // Only release if the released key matches the currently playing note
+        if self.current_note == Some(note) {
+            self.voice.note_off();
+            self.current_note = None;
+        }
     }
 }
 
-impl ExampleAudioState for VoiceDemoState {
-    fn next_sample(&mut self) -> f64 {
-        self.voice.next_sample() * 0.3 // Reduce volume
+struct Ui {
+    levels: VoiceLevels,
+    current_note: Option<u8>,
+    keyboard_status: String,
+}
+
+impl Ui {
+    fn is_active(&self) -> bool {
+        self.levels.active.get() > 0.5
     }
 
-    fn output_info(&self) -> Option<String> {
+    fn playing_status(&self) -> String {
         if let Some(note) = self.current_note {
             let note_name = midi_note_to_name(note);
             let freq = 440.0 * 2.0_f64.powf((note as f64 - 69.0) / 12.0);
@@ -81,56 +113,68 @@ impl ExampleAudioState for VoiceDemoState {
             } else {
                 "RELEASED"
             };
-            Some(format!(
-                "Note: {} ({:.1} Hz) | Status: {}",
-                note_name, freq, status
-            ))
+            format!("Note: {} ({:.1} Hz) | Status: {}", note_name, freq, status)
         } else if self.is_active() {
-            Some("Status: RELEASING...".to_string())
+            "Status: RELEASING...".to_string()
         } else {
-            Some("Status: IDLE | Press keys to play".to_string())
+            "Status: IDLE | Press keys to play".to_string()
+        }
+    }
+}
+
+impl ExampleUi for Ui {
+    fn note_on(&mut self, note: u8, _velocity: f64) {
+        self.current_note = Some(note);
+    }
+
+    fn note_off(&mut self, note: u8) {
+        if self.current_note == Some(note) {
+            self.current_note = None;
         }
     }
+
+    fn keyboard_changed(&mut self, keyboard: &KeyboardState) {
+        self.keyboard_status = keyboard.status();
+    }
+
+    fn meter(&self) -> Option<(&'static str, f64)> {
+        Some(("Envelope", self.levels.envelope.get()))
+    }
+
+    fn output_info(&self) -> Option<String> {
+        let status = self.playing_status();
+        Some(format!("{} | {}", self.keyboard_status, status))
+    }
 }
 
 fn draw_ui() -> Result<()> {
-    draw_keyboard_ui("Voice Demo - Monophonic Synthesizer", None)
+    draw_keyboard_ui(
+        "Voice Demo - Monophonic Synthesizer",
+        Some(MUSICAL_KEYS_HELP),
+    )
 }
 
 fn main() -> Result<()> {
-    run_interactive_example(
-        VoiceDemoState::new(),
-        KeyboardConfig::with_enhancements(),
-        |_state| draw_ui(),
-        |state, key_event: &KeyEvent| {
-            match key_event.code {
-                code if is_quit_key(code) => return Ok(KeyAction::Exit),
-                _ => {}
-            }
+    let levels = VoiceLevels {
+        envelope: ParamHandle::new(0.0),
+        active: ParamHandle::new(0.0),
+    };
+    let ui = Ui {
+        levels: levels.clone(),
+        current_note: None,
+        keyboard_status: String::new(),
+    };
 
-            // Handle note on/off based on key press/release
-            match key_event.kind {
-                KeyEventKind::Press => {
-                    if let Some(midi_note) = key_to_midi_note(key_event.code) {
-                        let mut s = state.lock().unwrap();
-                        // Only trigger note_on if this is a new note (prevent key repeat retriggering)
-                        if s.current_note != Some(midi_note) {
-                            s.note_on(midi_note);
-                        }
-                    }
-                }
-                KeyEventKind::Release => {
-                    if let Some(released_note) = key_to_midi_note(key_event.code) {
-                        let mut s = state.lock().unwrap();
-                        // Only trigger note_off if the released key matches the currently playing note
-                        if s.current_note == Some(released_note) {
-                            s.note_off();
-                        }
-                    }
-                }
-                _ => {}
+    run_interactive_example(
+        VoiceDemoState::new(levels),
+        ui,
+        KeyboardConfig::with_musical_keys(),
+        |_ui| draw_ui(),
+        |_ui, _commands, key_event: &KeyEvent| {
+            // Note, octave and velocity keys are handled by the framework
+            if is_quit_key(key_event.code) {
+                return Ok(KeyAction::Exit);
             }
-
             Ok(KeyAction::Continue)
         },
     )
@@ -14,11 +14,14 @@
 mod common;
 
 use anyhow::Result;
-use common::{ExampleAudioState, KeyAction, KeyboardConfig, is_quit_key, run_interactive_example};
+use common::{
+    ExampleAudioState, ExampleUi, KeyAction, KeyboardConfig, is_quit_key, run_interactive_example,
+};
 use crossterm::{
     ExecutableCommand,
     event::{KeyCode, KeyEvent},
 };
+use earworm::core::ParamHandle;
 use earworm::{Gain, InterpolationMode, Pitched, Signal, WavetableOscillator};
 use std::io::{Write, stdout};
 
@@ -26,46 +29,35 @@ const SAMPLE_RATE: u32 = 44100;
 
 struct AudioState {
     oscillator: Gain<WavetableOscillator<SAMPLE_RATE>>,
+    frequency: ParamHandle,
+}
+
+impl ExampleAudioState for AudioState {
+    type Command = ();
+
+    fn next_sample(&mut self) -> f64 {
+        let frequency = self.frequency.get();
+        if self.oscillator.source.frequency() != frequency {
+            self.oscillator.source.set_frequency(frequency);
+        }
+        self.oscillator.next_sample()
+    }
+}
+
+struct Ui {
     playing: bool,
     pitch_offset_cents: i32, // Pitch offset in cents (100 cents = 1 semitone)
     base_frequency: f64,     // Frequency for normal playback (no pitch shift)
     table_size: usize,
+    interpolation: InterpolationMode,
+    gain: ParamHandle,
+    frequency: ParamHandle,
 }
 
-impl AudioState {
-    fn new() -> Result<Self> {
-        // Load the vocal sample
-        let mut osc = WavetableOscillator::<SAMPLE_RATE>::from_wav_file(
-            SAMPLE_RATE as f64,
-            "resources/short-male-vox-hey.wav",
-        )
-        .map_err(|e| anyhow::anyhow!("Failed to load WAV file: {}", e))?
-        .with_interpolation(InterpolationMode::Cubic);
-
-        let table_size = osc.table_size();
-
-        // Calculate base frequency for normal playback (1 loop per second would be SAMPLE_RATE / table_size Hz)
-        // For normal playback speed, we want the wavetable to play at its original rate
-        let base_frequency = SAMPLE_RATE as f64 / table_size as f64;
-
-        // Set initial frequency
-        osc.set_frequency(base_frequency);
-
-        Ok(Self {
-            oscillator: Gain {
-                source: osc,
-                gain: 0.0.into(), // Start muted
-            },
-            playing: false,
-            pitch_offset_cents: 0,
-            base_frequency,
-            table_size,
-        })
-    }
-
+impl Ui {
     fn toggle_playback(&mut self) {
         self.playing = !self.playing;
-        self.oscillator.gain = if self.playing { 0.5.into() } else { 0.0.into() };
+        self.gain.set(if self.playing { 0.5 } else { 0.0 });
     }
 
     fn adjust_pitch(&mut self, cents: i32) {
@@ -80,22 +72,53 @@ impl AudioState {
         self.update_frequency();
     }
 
-    fn update_frequency(&mut self) {
+    fn update_frequency(&self) {
         // Convert cents to frequency multiplier
         // freq = base_freq * 2^(cents/1200)
         let multiplier = 2.0_f64.powf(self.pitch_offset_cents as f64 / 1200.0);
-        let frequency = self.base_frequency * multiplier;
-        self.oscillator.source.set_frequency(frequency);
+        self.frequency.set(self.base_frequency * multiplier);
     }
 }
 
-impl ExampleAudioState for AudioState {
-    fn next_sample(&mut self) -> f64 {
-        self.oscillator.next_sample()
-    }
+impl ExampleUi for Ui {}
+
+/// Loads the vocal sample and splits it into the audio and UI halves.
+fn load() -> Result<(AudioState, Ui)> {
+    let osc = WavetableOscillator::<SAMPLE_RATE>::from_wav_file(
+        SAMPLE_RATE as f64,
+        "resources/short-male-vox-hey.wav",
+    )
+    .map_err(|e| anyhow::anyhow!("Failed to load WAV file: {}", e))?
+    .with_interpolation(InterpolationMode::Cubic);
+
+    let table_size = osc.table_size();
+
+    // Calculate base frequency for normal playback (1 loop per second would be SAMPLE_RATE / table_size Hz)
+    // For normal playback speed, we want the wavetable to play at its original rate
+    let base_frequency = SAMPLE_RATE as f64 / table_size as f64;
+
+    let gain = ParamHandle::new(0.0); // Start muted
+    let frequency = ParamHandle::new(base_frequency);
+    let ui = Ui {
+        playing: false,
+        pitch_offset_cents: 0,
+        base_frequency,
+        table_size,
+        interpolation: osc.interpolation(),
+        gain: gain.clone(),
+        frequency: frequency.clone(),
+    };
+    let state = AudioState {
+        oscillator: Gain {
+            source: osc,
+            gain: gain.into(),
+        },
+        frequency,
+    };
+    Ok((state, ui))
 }
 
-fn draw_ui(state: &AudioState) -> Result<()> {
+fn draw_ui(ui: &Ui) -> Result<()> {
     let mut stdout = stdout();
     stdout.execute(crossterm::terminal::Clear(
         crossterm::terminal::ClearType::All,
@@ -107,23 +130,19 @@ fn draw_ui(state: &AudioState) -> Result<()> {
     write!(
         stdout,
         "File: resources/short-male-vox-hey.wav ({} samples)\r\n",
-        state.table_size
+        ui.table_size
     )?;
     write!(
         stdout,
         "Duration: {:.2} seconds\r\n",
-        state.table_size as f64 / SAMPLE_RATE as f64
-    )?;
-    write!(
-        stdout,
-        "Interpolation: {:?}\r\n",
-        state.oscillator.source.interpolation()
+        ui.table_size as f64 / SAMPLE_RATE as f64
     )?;
+    write!(stdout, "Interpolation: {:?}\r\n", ui.interpolation)?;
     write!(stdout, "\r\n")?;
     write!(
         stdout,
         "Status: {}\r\n",
-        if state.playing {
+        if ui.playing {
             "▶ PLAYING"
         } else {
             "⏸ PAUSED"
@@ -132,10 +151,10 @@ fn draw_ui(state: &AudioState) -> Result<()> {
     write!(
         stdout,
         "Pitch: {:+} cents ({:+.1} semitones)\r\n",
-        state.pitch_offset_cents,
-        state.pitch_offset_cents as f64 / 100.0
+        ui.pitch_offset_cents,
+        ui.pitch_offset_cents as f64 / 100.0
     )?;
-    let multiplier = 2.0_f64.powf(state.pitch_offset_cents as f64 / 1200.0);
+    let multiplier = 2.0_f64.powf(ui.pitch_offset_cents as f64 / 1200.0);
     write!(stdout, "Playback speed: {:.2}x\r\n", multiplier)?;
     write!(stdout, "\r\n")?;
     write!(stdout, "Controls:\r\n")?;
@@ -150,64 +169,28 @@ fn draw_ui(state: &AudioState) -> Result<()> {
 }
 
 fn main() -> Result<()> {
-    let audio_state = AudioState::new()?;
+    let (audio_state, ui) = load()?;
 
     run_interactive_example(
         audio_state,
+        ui,
         KeyboardConfig::default(),
-        |state| {
-            let state = state.lock().unwrap();
-            draw_ui(&state)
-        },
-        |state, key_event: &KeyEvent| {
-            let result = match key_event.code {
-                KeyCode::Char(' ') => {
-                    let mut s = state.lock().unwrap();
-                    s.toggle_playback();
-                    drop(s);
-                    Ok(KeyAction::Continue)
-                }
-                KeyCode::Up => {
-                    let mut s = state.lock().unwrap();
-                    s.adjust_pitch(100);
-                    drop(s);
-                    Ok(KeyAction::Continue)
-                }
-                KeyCode::Down => {
-                    let mut s = state.lock().unwrap();
-                    s.adjust_pitch(-100);
-                    drop(s);
-                    Ok(KeyAction::Continue)
-                }
-                KeyCode::Right => {
-                    let mut s = state.lock().unwrap();
-                    s.adjust_pitch(10);
-                    drop(s);
-                    Ok(KeyAction::Continue)
-                }
-                KeyCode::Left => {
-                    let mut s = state.lock().unwrap();
-                    s.adjust_pitch(-10);
-                    drop(s);
-                    Ok(KeyAction::Continue)
-                }
-                KeyCode::Char('r') | KeyCode::Char('R') => {
-                    let mut s = state.lock().unwrap();
-                    s.reset_pitch();
-                    drop(s);
-                    Ok(KeyAction::Continue)
-                }
-                code if is_quit_key(code) => Ok(KeyAction::Exit),
-                _ => Ok(KeyAction::Continue),
-            };
-
-            // Redraw UI after any key press
-            if result.is_ok() {
-                let s = state.lock().unwrap();
-                let _ = draw_ui(&s);
+        draw_ui,
+        |ui, _commands, key_event: &KeyEvent| {
+            match key_event.code {
+                KeyCode::Char(' ') => ui.toggle_playback(),
+                KeyCode::Up => ui.adjust_pitch(100),
+                KeyCode::Down => ui.adjust_pitch(-100),
+                KeyCode::Right => ui.adjust_pitch(10),
+                KeyCode::Left => ui.adjust_pitch(-10),
+                KeyCode::Char('r') | KeyCode::Char('R') => ui.reset_pitch(),
+                code if is_quit_key(code) => return Ok(KeyAction::Exit),
+                _ => return Ok(KeyAction::Continue),
             }
 
-            result
+            // Redraw UI after any key press
+            let _ = draw_ui(ui);
+            Ok(KeyAction::Continue)
         },
     )
 }
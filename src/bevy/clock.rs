@@ -0,0 +1,116 @@
+//! A musical clock that runs on game time.
+
+use crate::music::Metronome;
+use bevy::ecs::message::{Message, MessageWriter};
+use bevy::ecs::resource::Resource;
+use bevy::ecs::system::{Res, ResMut};
+use bevy::time::Time;
+
+/// A step of the [`MusicClock`], written as a message in `PreUpdate` so
+/// gameplay systems in `Update` can react on the same frame.
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MusicStep {
+    /// Step number since the clock started
+    pub step: u64,
+    /// Beat the step falls in
+    pub beat: u64,
+    /// Whether the step is the first of its beat
+    pub on_beat: bool,
+}
+
+/// A [`Metronome`] driven by Bevy's game time.
+///
+/// The clock advances by the frame's [`Time`] delta, so it pauses, slows and
+/// speeds up with the game's virtual time. Steps are counted at audio sample
+/// resolution and carried over between frames, so a beat lands in the frame
+/// it falls in regardless of frame rate. Change the tempo (or start a ramp)
+/// through [`metronome_mut`](Self::metronome_mut).
+#[derive(Resource, Debug, Clone)]
+pub struct MusicClock {
+    metronome: Metronome,
+    sample_rate: u32,
+    // Fraction of a sample left over from the last frame
+    pending: f64,
+}
+
+impl MusicClock {
+    /// Creates a clock at `bpm` with `steps_per_beat` steps.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bpm` or `steps_per_beat` is <= 0.
+    pub fn new(bpm: f64, steps_per_beat: u32, sample_rate: u32) -> Self {
+        Self {
+            metronome: Metronome::new(bpm, steps_per_beat, sample_rate),
+            sample_rate,
+            pending: 0.0,
+        }
+    }
+
+    /// Returns the underlying metronome.
+    pub fn metronome(&self) -> &Metronome {
+        &self.metronome
+    }
+
+    /// Returns the underlying metronome for changing tempo or position.
+    pub fn metronome_mut(&mut self) -> &mut Metronome {
+        &mut self.metronome
+    }
+
+    /// Returns the position in beats since the clock started.
+    pub fn beat_position(&self) -> f64 {
+        self.metronome.beat_position()
+    }
+
+    /// Advances the clock by `seconds`, calling `on_step` for every step
+    /// crossed.
+    pub fn advance(&mut self, seconds: f64, mut on_step: impl FnMut(MusicStep)) {
+        self.pending += seconds.max(0.0) * self.sample_rate as f64;
+        let samples = self.pending.floor();
+        self.pending -= samples;
+
+        let steps_per_beat = self.metronome.steps_per_beat() as u64;
+        for _ in 0..samples as u64 {
+            if self.metronome.tick() {
+                let step = self.metronome.current_step();
+                on_step(MusicStep {
+                    step,
+                    beat: step / steps_per_beat,
+                    on_beat: step.is_multiple_of(steps_per_beat),
+                });
+            }
+        }
+    }
+}
+
+/// Advances the [`MusicClock`] by the frame's time.
+pub(super) fn advance_clock(
+    time: Res<Time>,
+    mut clock: ResMut<MusicClock>,
+    mut steps: MessageWriter<MusicStep>,
+) {
+    clock.advance(time.delta_secs_f64(), |step| {
+        steps.write(step);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps_carry_across_frames() {
+        // 120 BPM, 4 steps per beat: a step every 1/8 second
+        let mut clock = MusicClock::new(120.0, 4, 48000);
+        let mut steps = Vec::new();
+        for _ in 0..60 {
+            clock.advance(1.0 / 60.0, |step| steps.push(step));
+        }
+        assert_eq!(steps.len(), 8);
+        assert_eq!(steps[3].step, 4);
+        assert_eq!(steps[3].beat, 1);
+        assert!(steps[3].on_beat);
+        assert!(!steps[4].on_beat);
+        assert!((clock.beat_position() - 2.0).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,192 @@
+//! Components for entities that make sound and the one that hears it.
+
+use crate::core::Signal;
+use bevy::ecs::component::Component;
+use bevy::math::Vec3;
+use bevy::platform::cell::SyncCell;
+use bevy::transform::components::GlobalTransform;
+
+/// How an emitter's loudness falls off with distance from the listener.
+///
+/// Uses the inverse-distance model: full volume up to `reference_distance`,
+/// then `reference_distance / (reference_distance + rolloff * (d - reference_distance))`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Attenuation {
+    /// Distance at and inside which the emitter plays at full volume
+    pub reference_distance: f64,
+    /// How quickly the volume falls beyond the reference distance (0.0 never fades)
+    pub rolloff: f64,
+}
+
+impl Default for Attenuation {
+    fn default() -> Self {
+        Self {
+            reference_distance: 1.0,
+            rolloff: 1.0,
+        }
+    }
+}
+
+impl Attenuation {
+    /// Returns the gain at `distance` from the listener.
+    pub fn gain(&self, distance: f64) -> f64 {
+        let reference = self.reference_distance.max(f64::EPSILON);
+        let beyond = (distance - reference).max(0.0);
+        reference / (reference + self.rolloff.max(0.0) * beyond)
+    }
+}
+
+/// Marks the entity whose position and orientation sounds are heard from,
+/// usually the camera or player.
+///
+/// With no listener, or for emitters without a transform, sounds play
+/// centered at their own volume.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct SoundListener;
+
+/// A sound played from an entity's position.
+///
+/// When the component is added, the plugin gives the sound a slot in the
+/// [`EmitterMixer`](super::EmitterMixer) and from then on pans and
+/// attenuates it to follow the entity relative to the [`SoundListener`].
+/// Removing the component (or despawning the entity) stops the sound.
+///
+/// A looping emitter holds its slot until removed. A one-shot emitter plays
+/// for a fixed time and frees its slot afterwards, and may be stolen by a
+/// newer one-shot when every slot is busy. If no slot can be had, the sound
+/// waits until one frees up.
+#[derive(Component)]
+pub struct SoundEmitter {
+    sound: Option<SyncCell<Box<dyn Signal + Send>>>,
+    /// Volume before distance attenuation
+    pub volume: f64,
+    /// Distance falloff
+    pub attenuation: Attenuation,
+    duration: Option<f64>,
+    despawn: bool,
+    pub(super) last: Option<(f64, f64)>,
+}
+
+impl SoundEmitter {
+    /// Creates an emitter that plays `sound` until removed.
+    pub fn looping(sound: impl Signal + Send + 'static) -> Self {
+        Self {
+            sound: Some(SyncCell::new(Box::new(sound))),
+            volume: 1.0,
+            attenuation: Attenuation::default(),
+            duration: None,
+            despawn: false,
+            last: None,
+        }
+    }
+
+    /// Creates an emitter that plays `sound` for `seconds`.
+    ///
+    /// Once the time is up the component is removed from the entity (see
+    /// [`despawn_when_done`](Self::despawn_when_done)).
+    pub fn one_shot(sound: impl Signal + Send + 'static, seconds: f64) -> Self {
+        Self {
+            duration: Some(seconds.max(0.0)),
+            ..Self::looping(sound)
+        }
+    }
+
+    /// Sets the volume before attenuation (builder style).
+    pub fn with_volume(mut self, volume: f64) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    /// Sets the distance falloff (builder style).
+    pub fn with_attenuation(mut self, attenuation: Attenuation) -> Self {
+        self.attenuation = attenuation;
+        self
+    }
+
+    /// Despawns the whole entity when a one-shot finishes, for entities that
+    /// exist only to make a sound.
+    pub fn despawn_when_done(mut self) -> Self {
+        self.despawn = true;
+        self
+    }
+
+    /// Returns `true` once the sound has been handed to the mixer.
+    pub fn is_started(&self) -> bool {
+        self.sound.is_none()
+    }
+
+    /// Returns the one-shot length in seconds, or `None` for a looping emitter.
+    pub fn duration(&self) -> Option<f64> {
+        self.duration
+    }
+
+    pub(super) fn despawns(&self) -> bool {
+        self.despawn
+    }
+
+    pub(super) fn take_sound(&mut self) -> Option<Box<dyn Signal + Send>> {
+        self.sound.take().map(SyncCell::to_inner)
+    }
+
+    /// Gain and pan for this emitter heard from `listener`.
+    pub(super) fn spatialize(
+        &self,
+        listener: Option<&GlobalTransform>,
+        position: Option<Vec3>,
+    ) -> (f64, f64) {
+        let (Some(listener), Some(position)) = (listener, position) else {
+            return (self.volume, 0.0);
+        };
+        let offset = position - listener.translation();
+        let distance = offset.length() as f64;
+        let pan = if distance > 1e-6 {
+            offset.normalize().dot(*listener.right()) as f64
+        } else {
+            0.0
+        };
+        (self.volume * self.attenuation.gain(distance), pan)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ConstantSignal;
+    use bevy::transform::components::Transform;
+
+    #[test]
+    fn test_inverse_distance_attenuation() {
+        let attenuation = Attenuation {
+            reference_distance: 2.0,
+            rolloff: 1.0,
+        };
+        assert_eq!(attenuation.gain(0.5), 1.0);
+        assert_eq!(attenuation.gain(2.0), 1.0);
+        assert_eq!(attenuation.gain(4.0), 0.5);
+        let flat = Attenuation {
+            rolloff: 0.0,
+            ..attenuation
+        };
+        assert_eq!(flat.gain(100.0), 1.0);
+    }
+
+    #[test]
+    fn test_spatialize_pans_relative_to_listener() {
+        let emitter = SoundEmitter::looping(ConstantSignal::<44100>(0.0)).with_volume(0.5);
+        let listener = GlobalTransform::from(Transform::from_xyz(0.0, 0.0, 0.0));
+
+        // Default orientation: right is +X
+        let (gain, pan) = emitter.spatialize(Some(&listener), Some(Vec3::new(3.0, 0.0, 0.0)));
+        assert!((gain - 0.5 / 3.0).abs() < 1e-6);
+        assert!((pan - 1.0).abs() < 1e-6);
+        let (_, pan) = emitter.spatialize(Some(&listener), Some(Vec3::new(0.0, 0.0, -1.0)));
+        assert!(pan.abs() < 1e-6);
+
+        // Turning the listener around swaps the sides
+        let turned = GlobalTransform::from(Transform::default().looking_to(Vec3::Z, Vec3::Y));
+        let (_, pan) = emitter.spatialize(Some(&turned), Some(Vec3::new(3.0, 0.0, 0.0)));
+        assert!((pan + 1.0).abs() < 1e-6);
+
+        assert_eq!(emitter.spatialize(None, Some(Vec3::X)), (0.5, 0.0));
+    }
+}
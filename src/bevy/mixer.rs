@@ -0,0 +1,236 @@
+//! The audio-thread side of the plugin: a fixed set of emitter slots mixed
+//! to stereo.
+
+use crate::core::{AudioFrameSignal, CommandReceiver, CommandTarget, FrameSignal, Signal};
+
+/// Smoothing time for gain and pan changes, so emitters moving at frame rate
+/// don't produce zipper noise.
+const SMOOTHING_MS: f64 = 20.0;
+
+/// A change to one slot of an [`EmitterMixer`].
+///
+/// The plugin sends these from its systems; they are only public so a mixer
+/// can be driven by hand outside of Bevy.
+pub enum EmitterCommand {
+    /// Starts a sound in a slot, replacing whatever was playing there.
+    ///
+    /// The replaced sound is dropped on the audio thread.
+    Play {
+        slot: usize,
+        sound: Box<dyn Signal + Send>,
+        gain: f64,
+        pan: f64,
+        /// Stop after this many frames; `None` plays until stopped
+        frames: Option<u64>,
+    },
+    /// Moves a slot to a new gain and pan (-1.0 left to 1.0 right).
+    Spatialize { slot: usize, gain: f64, pan: f64 },
+    /// Silences a slot.
+    Stop(usize),
+}
+
+struct Slot {
+    sound: Option<Box<dyn Signal + Send>>,
+    remaining: Option<u64>,
+    // Channel gains being approached and their smoothed values
+    target: [f64; 2],
+    current: [f64; 2],
+}
+
+impl Slot {
+    fn empty() -> Self {
+        Self {
+            sound: None,
+            remaining: None,
+            target: [0.0; 2],
+            current: [0.0; 2],
+        }
+    }
+}
+
+/// Mixes the sounds of every emitter slot into a stereo frame.
+///
+/// This is the graph [`EarwormPlugin`](super::EarwormPlugin) manages: take it
+/// from [`EarwormAudio::take_mixer`](super::EarwormAudio::take_mixer) and hand
+/// it to whatever plays audio, such as `AudioOutput::start_frames` (with the
+/// `playback` feature) or a [`FrameProvider`](crate::core::FrameProvider).
+/// Pending commands are applied before every frame.
+pub struct EmitterMixer<const SAMPLE_RATE: u32> {
+    slots: Vec<Slot>,
+    commands: CommandReceiver<EmitterCommand>,
+    coeff: f64,
+}
+
+impl<const SAMPLE_RATE: u32> EmitterMixer<SAMPLE_RATE> {
+    /// Creates a mixer with `slots` silent slots, driven by `commands`.
+    pub fn new(slots: usize, commands: CommandReceiver<EmitterCommand>) -> Self {
+        let samples = SMOOTHING_MS * 0.001 * SAMPLE_RATE as f64;
+        Self {
+            slots: (0..slots).map(|_| Slot::empty()).collect(),
+            commands,
+            coeff: 1.0 - (-1.0 / samples).exp(),
+        }
+    }
+
+    /// Returns the number of slots.
+    pub fn slots(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns the number of slots currently playing a sound.
+    pub fn active(&self) -> usize {
+        self.slots
+            .iter()
+            .filter(|slot| slot.sound.is_some())
+            .count()
+    }
+}
+
+/// Equal-power channel gains for `gain` at `pan` (-1.0 left to 1.0 right).
+fn pan_gains(gain: f64, pan: f64) -> [f64; 2] {
+    let angle = (pan.clamp(-1.0, 1.0) + 1.0) * std::f64::consts::FRAC_PI_4;
+    [gain * angle.cos(), gain * angle.sin()]
+}
+
+impl<const SAMPLE_RATE: u32> CommandTarget<EmitterCommand> for EmitterMixer<SAMPLE_RATE> {
+    fn apply(&mut self, command: EmitterCommand) {
+        match command {
+            EmitterCommand::Play {
+                slot,
+                sound,
+                gain,
+                pan,
+                frames,
+            } => {
+                if let Some(slot) = self.slots.get_mut(slot) {
+                    let gains = pan_gains(gain, pan);
+                    slot.sound = Some(sound);
+                    slot.remaining = frames;
+                    slot.target = gains;
+                    slot.current = gains;
+                }
+            }
+            EmitterCommand::Spatialize { slot, gain, pan } => {
+                if let Some(slot) = self.slots.get_mut(slot) {
+                    slot.target = pan_gains(gain, pan);
+                }
+            }
+            EmitterCommand::Stop(slot) => {
+                if let Some(slot) = self.slots.get_mut(slot) {
+                    slot.sound = None;
+                }
+            }
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32> FrameSignal<2> for EmitterMixer<SAMPLE_RATE> {
+    fn next_frame(&mut self) -> [f64; 2] {
+        while let Some(command) = self.commands.try_recv() {
+            self.apply(command);
+        }
+
+        let mut frame = [0.0; 2];
+        for slot in self.slots.iter_mut() {
+            let Some(sound) = slot.sound.as_mut() else {
+                continue;
+            };
+            if slot.remaining == Some(0) {
+                slot.sound = None;
+                continue;
+            }
+            if let Some(remaining) = slot.remaining.as_mut() {
+                *remaining -= 1;
+            }
+
+            let sample = sound.next_sample();
+            for ((out, current), target) in frame
+                .iter_mut()
+                .zip(slot.current.iter_mut())
+                .zip(slot.target)
+            {
+                *current += (target - *current) * self.coeff;
+                *out += sample * *current;
+            }
+        }
+        frame
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        for sound in self.slots.iter_mut().filter_map(|slot| slot.sound.as_mut()) {
+            sound.prepare(max_block_size, sample_rate);
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32> AudioFrameSignal<SAMPLE_RATE, 2> for EmitterMixer<SAMPLE_RATE> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{ConstantSignal, command_queue};
+
+    fn play(slot: usize, value: f64, pan: f64, frames: Option<u64>) -> EmitterCommand {
+        EmitterCommand::Play {
+            slot,
+            sound: Box::new(ConstantSignal::<44100>(value)),
+            gain: 1.0,
+            pan,
+            frames,
+        }
+    }
+
+    #[test]
+    fn test_slots_mix_with_equal_power_pan() {
+        let (sender, receiver) = command_queue(8);
+        let mut mixer = EmitterMixer::<44100>::new(4, receiver);
+        assert_eq!(mixer.next_frame(), [0.0, 0.0]);
+
+        sender.send(play(0, 1.0, -1.0, None)).ok();
+        sender.send(play(3, 1.0, 0.0, None)).ok();
+        let [left, right] = mixer.next_frame();
+        let center = std::f64::consts::FRAC_1_SQRT_2;
+        assert!((left - (1.0 + center)).abs() < 1e-12);
+        assert!((right - center).abs() < 1e-12);
+        assert_eq!(mixer.active(), 2);
+
+        sender.send(EmitterCommand::Stop(0)).ok();
+        sender.send(EmitterCommand::Stop(9)).ok(); // out of range is ignored
+        mixer.next_frame();
+        assert_eq!(mixer.active(), 1);
+    }
+
+    #[test]
+    fn test_frame_limit_ends_sound() {
+        let (sender, receiver) = command_queue(8);
+        let mut mixer = EmitterMixer::<44100>::new(1, receiver);
+        sender.send(play(0, 1.0, 0.0, Some(2))).ok();
+        assert!(mixer.next_frame()[0] > 0.0);
+        assert!(mixer.next_frame()[0] > 0.0);
+        assert_eq!(mixer.next_frame(), [0.0, 0.0]);
+        assert_eq!(mixer.active(), 0);
+    }
+
+    #[test]
+    fn test_spatialize_glides() {
+        let (sender, receiver) = command_queue(8);
+        let mut mixer = EmitterMixer::<44100>::new(1, receiver);
+        sender.send(play(0, 1.0, -1.0, None)).ok();
+        mixer.next_frame();
+
+        sender
+            .send(EmitterCommand::Spatialize {
+                slot: 0,
+                gain: 1.0,
+                pan: 1.0,
+            })
+            .ok();
+        let [left, right] = mixer.next_frame();
+        assert!(left > 0.9 && right < 0.1);
+        for _ in 0..44100 {
+            mixer.next_frame();
+        }
+        let [left, right] = mixer.next_frame();
+        assert!(left < 1e-6 && (right - 1.0).abs() < 1e-6);
+    }
+}
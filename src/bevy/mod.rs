@@ -0,0 +1,64 @@
+//! Bevy integration.
+//!
+//! [`EarwormPlugin`] makes earworm a procedural-music and sound-effects engine
+//! for [Bevy](https://bevyengine.org) games. It manages an [`EmitterMixer`]
+//! graph as the [`EarwormAudio`] resource: entities with a [`SoundEmitter`]
+//! play their sound from a pool of mixer slots, panned and attenuated
+//! relative to the [`SoundListener`], and one-shots can be fired from any
+//! system. A [`MusicClock`] resource runs a [`Metronome`](crate::music::Metronome)
+//! on game time and writes a [`MusicStep`] message on every step, so gameplay
+//! can land on the beat.
+//!
+//! The mixer is an ordinary stereo [`AudioFrameSignal`](crate::core::AudioFrameSignal):
+//! take it once at startup and play it with `AudioOutput::start_frames`
+//! (`playback` feature), or feed another engine through a
+//! [`FrameProvider`](crate::core::FrameProvider).
+//!
+//! Requires the `bevy` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use bevy::prelude::*;
+//! use earworm::bevy::{EarwormAudio, EarwormPlugin, MusicStep, SoundEmitter, SoundListener};
+//! use earworm::core::FrameProvider;
+//! use earworm::{SawtoothOscillator, SineOscillator};
+//!
+//! const SAMPLE_RATE: u32 = 48000;
+//!
+//! fn setup(mut commands: Commands, mut audio: ResMut<EarwormAudio<SAMPLE_RATE>>) {
+//!     // Hand the graph to the audio backend, here a frame provider
+//!     let mixer = audio.take_mixer().unwrap();
+//!     let _provider = FrameProvider::from_frames(mixer);
+//!
+//!     commands.spawn((SoundListener, Transform::default()));
+//!     // A humming machine off to the left
+//!     commands.spawn((
+//!         SoundEmitter::looping(SawtoothOscillator::<SAMPLE_RATE>::new(55.0)).with_volume(0.3),
+//!         Transform::from_xyz(-4.0, 0.0, 0.0),
+//!     ));
+//! }
+//!
+//! fn on_beat(mut steps: MessageReader<MusicStep>, mut audio: ResMut<EarwormAudio<SAMPLE_RATE>>) {
+//!     for step in steps.read().filter(|step| step.on_beat) {
+//!         audio.play_one_shot(SineOscillator::<SAMPLE_RATE>::new(880.0), 0.05, 0.2, 0.0);
+//!     }
+//! }
+//!
+//! let mut app = App::new();
+//! app.add_plugins((MinimalPlugins, TransformPlugin))
+//!     .add_plugins(EarwormPlugin::<SAMPLE_RATE>::new().with_tempo(96.0, 4))
+//!     .add_systems(Startup, setup)
+//!     .add_systems(Update, on_beat);
+//! app.update();
+//! ```
+
+mod clock;
+mod emitter;
+mod mixer;
+mod plugin;
+
+pub use clock::{MusicClock, MusicStep};
+pub use emitter::{Attenuation, SoundEmitter, SoundListener};
+pub use mixer::{EmitterCommand, EmitterMixer};
+pub use plugin::{EarwormAudio, EarwormPlugin};
@@ -0,0 +1,465 @@
+//! The plugin, its audio resource and the systems that keep the mixer in
+//! step with the world.
+
+use super::clock::{MusicClock, MusicStep, advance_clock};
+use super::emitter::{SoundEmitter, SoundListener};
+use super::mixer::{EmitterCommand, EmitterMixer};
+use crate::core::{CommandSender, Signal, command_queue};
+use bevy::app::{App, Plugin, PostUpdate, PreUpdate};
+use bevy::ecs::entity::Entity;
+use bevy::ecs::lifecycle::RemovedComponents;
+use bevy::ecs::query::With;
+use bevy::ecs::resource::Resource;
+use bevy::ecs::schedule::IntoScheduleConfigs;
+use bevy::ecs::system::{Commands, Query, Res, ResMut};
+use bevy::platform::cell::SyncCell;
+use bevy::time::Time;
+use bevy::transform::TransformSystems;
+use bevy::transform::components::GlobalTransform;
+
+/// Adds earworm to a Bevy app.
+///
+/// The plugin inserts an [`EarwormAudio`] resource holding the emitter graph
+/// and a [`MusicClock`] resource that follows game time, and registers the
+/// [`MusicStep`] message. Its systems start, move and stop the sounds of
+/// [`SoundEmitter`] entities after transforms have been propagated each
+/// frame.
+///
+/// The plugin does not open an audio device itself: take the mixer from
+/// [`EarwormAudio::take_mixer`] in a startup system and play it however the
+/// game outputs audio.
+#[derive(Debug, Clone)]
+pub struct EarwormPlugin<const SAMPLE_RATE: u32> {
+    slots: usize,
+    bpm: f64,
+    steps_per_beat: u32,
+    command_capacity: usize,
+}
+
+impl<const SAMPLE_RATE: u32> Default for EarwormPlugin<SAMPLE_RATE> {
+    fn default() -> Self {
+        Self {
+            slots: 16,
+            bpm: 120.0,
+            steps_per_beat: 4,
+            command_capacity: 1024,
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32> EarwormPlugin<SAMPLE_RATE> {
+    /// Creates a plugin with 16 emitter slots and a 120 BPM clock in 16th
+    /// note steps.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how many sounds can play at once (builder style).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slots` is 0.
+    pub fn with_slots(mut self, slots: usize) -> Self {
+        assert!(slots > 0, "slot count must be greater than 0");
+        self.slots = slots;
+        self
+    }
+
+    /// Sets the music clock's starting tempo and resolution (builder style).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bpm` or `steps_per_beat` is <= 0.
+    pub fn with_tempo(mut self, bpm: f64, steps_per_beat: u32) -> Self {
+        assert!(bpm > 0.0, "BPM must be greater than 0");
+        assert!(steps_per_beat > 0, "steps_per_beat must be greater than 0");
+        self.bpm = bpm;
+        self.steps_per_beat = steps_per_beat;
+        self
+    }
+
+    /// Sets how many commands can wait for the audio thread at once
+    /// (builder style).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    pub fn with_command_capacity(mut self, capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "command queue capacity must be greater than 0"
+        );
+        self.command_capacity = capacity;
+        self
+    }
+}
+
+impl<const SAMPLE_RATE: u32> Plugin for EarwormPlugin<SAMPLE_RATE> {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = command_queue(self.command_capacity);
+        let mixer = EmitterMixer::<SAMPLE_RATE>::new(self.slots, receiver);
+
+        app.insert_resource(EarwormAudio {
+            sender,
+            mixer: Some(SyncCell::new(mixer)),
+            slots: vec![SlotUse::Free; self.slots],
+            stolen: Vec::new(),
+            now: 0.0,
+        })
+        .insert_resource(MusicClock::new(self.bpm, self.steps_per_beat, SAMPLE_RATE))
+        .add_message::<MusicStep>()
+        .add_systems(PreUpdate, advance_clock)
+        .add_systems(
+            PostUpdate,
+            (
+                release_slots::<SAMPLE_RATE>,
+                start_emitters::<SAMPLE_RATE>,
+                spatialize_emitters::<SAMPLE_RATE>,
+            )
+                .chain()
+                .after(TransformSystems::Propagate),
+        );
+    }
+}
+
+/// What an emitter slot is being used for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SlotUse {
+    Free,
+    /// Held by a looping emitter until it is removed
+    Looping(Entity),
+    /// Playing a one-shot, from an emitter entity or played directly
+    OneShot {
+        entity: Option<Entity>,
+        started: f64,
+        ends: f64,
+    },
+}
+
+/// The earworm graph of an app running [`EarwormPlugin`].
+///
+/// Holds the [`EmitterMixer`] until it is taken for playback, and the control
+/// end of its command queue afterwards.
+#[derive(Resource)]
+pub struct EarwormAudio<const SAMPLE_RATE: u32> {
+    sender: CommandSender<EmitterCommand>,
+    mixer: Option<SyncCell<EmitterMixer<SAMPLE_RATE>>>,
+    slots: Vec<SlotUse>,
+    // One-shot emitters whose slots were taken by newer sounds
+    stolen: Vec<Entity>,
+    // Game time in seconds as of the last update
+    now: f64,
+}
+
+impl<const SAMPLE_RATE: u32> EarwormAudio<SAMPLE_RATE> {
+    /// Takes the mixer to play it; returns `None` if it was already taken.
+    pub fn take_mixer(&mut self) -> Option<EmitterMixer<SAMPLE_RATE>> {
+        self.mixer.take().map(SyncCell::to_inner)
+    }
+
+    /// Returns the number of emitter slots.
+    pub fn slots(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns the number of slots not playing anything.
+    pub fn free_slots(&self) -> usize {
+        self.slots
+            .iter()
+            .filter(|slot| **slot == SlotUse::Free)
+            .count()
+    }
+
+    /// Plays a sound that isn't attached to any entity, such as a UI click.
+    ///
+    /// The sound plays for `seconds` at `gain` and `pan` (-1.0 left to 1.0
+    /// right), in a free slot or the slot of the oldest one-shot.
+    /// Returns `false` if every slot is held by a looping emitter or the
+    /// command queue is full.
+    pub fn play_one_shot(
+        &mut self,
+        sound: impl Signal + Send + 'static,
+        seconds: f64,
+        gain: f64,
+        pan: f64,
+    ) -> bool {
+        self.start(None, Box::new(sound), Some(seconds.max(0.0)), gain, pan)
+    }
+
+    /// Finds a free slot, or the slot of the oldest one-shot.
+    fn claim(&self) -> Option<usize> {
+        if let Some(free) = self.slots.iter().position(|slot| *slot == SlotUse::Free) {
+            return Some(free);
+        }
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| match slot {
+                SlotUse::OneShot { started, .. } => Some((index, *started)),
+                _ => None,
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(index, _)| index)
+    }
+
+    /// Starts `sound` in a slot, returning `false` if there is none.
+    fn start(
+        &mut self,
+        entity: Option<Entity>,
+        sound: Box<dyn Signal + Send>,
+        duration: Option<f64>,
+        gain: f64,
+        pan: f64,
+    ) -> bool {
+        let Some(slot) = self.claim() else {
+            return false;
+        };
+        let command = EmitterCommand::Play {
+            slot,
+            sound,
+            gain,
+            pan,
+            frames: duration.map(|seconds| (seconds * SAMPLE_RATE as f64).round() as u64),
+        };
+        if self.sender.send(command).is_err() {
+            return false;
+        }
+
+        if let SlotUse::OneShot {
+            entity: Some(stolen),
+            ..
+        } = self.slots[slot]
+        {
+            self.stolen.push(stolen);
+        }
+        self.slots[slot] = match (duration, entity) {
+            (None, Some(entity)) => SlotUse::Looping(entity),
+            (duration, entity) => SlotUse::OneShot {
+                entity,
+                started: self.now,
+                ends: self.now + duration.unwrap_or(0.0),
+            },
+        };
+        true
+    }
+
+    /// Returns the slot an emitter entity is playing in.
+    fn slot_of(&self, entity: Entity) -> Option<usize> {
+        self.slots.iter().position(|slot| match slot {
+            SlotUse::Looping(holder) => *holder == entity,
+            SlotUse::OneShot { entity: holder, .. } => *holder == Some(entity),
+            SlotUse::Free => false,
+        })
+    }
+}
+
+/// Finishes one-shot emitters whose time is up, removing or despawning them.
+fn finish_emitter(commands: &mut Commands, entity: Entity, despawn: bool) {
+    if let Ok(mut entity) = commands.get_entity(entity) {
+        if despawn {
+            entity.despawn();
+        } else {
+            entity.remove::<SoundEmitter>();
+        }
+    }
+}
+
+/// Frees the slots of removed emitters and finished one-shots, and finishes
+/// one-shot emitters whose slots were stolen.
+fn release_slots<const SAMPLE_RATE: u32>(
+    time: Res<Time>,
+    mut audio: ResMut<EarwormAudio<SAMPLE_RATE>>,
+    mut removed: RemovedComponents<SoundEmitter>,
+    emitters: Query<&SoundEmitter>,
+    mut commands: Commands,
+) {
+    audio.now = time.elapsed_secs_f64();
+
+    for entity in removed.read() {
+        if let Some(slot) = audio.slot_of(entity) {
+            audio.sender.send(EmitterCommand::Stop(slot)).ok();
+            audio.slots[slot] = SlotUse::Free;
+        }
+    }
+
+    let now = audio.now;
+    let mut finished = std::mem::take(&mut audio.stolen);
+    for slot in audio.slots.iter_mut() {
+        if let SlotUse::OneShot { entity, ends, .. } = *slot
+            && ends <= now
+        {
+            *slot = SlotUse::Free;
+            finished.extend(entity);
+        }
+    }
+    for entity in finished.drain(..) {
+        let despawn = emitters.get(entity).is_ok_and(SoundEmitter::despawns);
+        finish_emitter(&mut commands, entity, despawn);
+    }
+    audio.stolen = finished;
+}
+
+/// Gives newly added emitters a slot and starts their sounds.
+fn start_emitters<const SAMPLE_RATE: u32>(
+    mut audio: ResMut<EarwormAudio<SAMPLE_RATE>>,
+    mut emitters: Query<(Entity, &mut SoundEmitter, Option<&GlobalTransform>)>,
+    listener: Query<&GlobalTransform, With<SoundListener>>,
+    mut commands: Commands,
+) {
+    let listener = listener.iter().next();
+    for (entity, mut emitter, transform) in emitters.iter_mut() {
+        let Some(sound) = emitter.take_sound() else {
+            continue;
+        };
+        let (gain, pan) = emitter.spatialize(listener, transform.map(|t| t.translation()));
+        if audio.start(Some(entity), sound, emitter.duration(), gain, pan) {
+            emitter.last = Some((gain, pan));
+        } else if emitter.duration().is_some() {
+            // No slot: a one-shot is dropped, a looping emitter waits
+            finish_emitter(&mut commands, entity, emitter.despawns());
+        }
+    }
+}
+
+/// Sends the gain and pan of every moving emitter to the mixer.
+fn spatialize_emitters<const SAMPLE_RATE: u32>(
+    audio: Res<EarwormAudio<SAMPLE_RATE>>,
+    mut emitters: Query<(Entity, &mut SoundEmitter, Option<&GlobalTransform>)>,
+    listener: Query<&GlobalTransform, With<SoundListener>>,
+) {
+    let listener = listener.iter().next();
+    for (entity, mut emitter, transform) in emitters.iter_mut() {
+        let Some(slot) = audio.slot_of(entity) else {
+            continue;
+        };
+        let (gain, pan) = emitter.spatialize(listener, transform.map(|t| t.translation()));
+        let moved = emitter
+            .last
+            .is_none_or(|(g, p)| (g - gain).abs() > 1e-4 || (p - pan).abs() > 1e-4);
+        if moved
+            && audio
+                .sender
+                .send(EmitterCommand::Spatialize { slot, gain, pan })
+                .is_ok()
+        {
+            emitter.last = Some((gain, pan));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{ConstantSignal, FrameSignal};
+    use bevy::ecs::message::Messages;
+    use bevy::transform::components::Transform;
+    use std::time::Duration;
+
+    const SAMPLE_RATE: u32 = 1000;
+
+    fn app(slots: usize) -> (App, EmitterMixer<SAMPLE_RATE>) {
+        let mut app = App::new();
+        app.insert_resource(Time::<()>::default())
+            .add_plugins(EarwormPlugin::<SAMPLE_RATE>::new().with_slots(slots));
+        let mixer = app
+            .world_mut()
+            .resource_mut::<EarwormAudio<SAMPLE_RATE>>()
+            .take_mixer()
+            .unwrap();
+        (app, mixer)
+    }
+
+    fn step(app: &mut App, seconds: f64) {
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs_f64(seconds));
+        app.update();
+    }
+
+    fn audio(app: &App) -> &EarwormAudio<SAMPLE_RATE> {
+        app.world().resource::<EarwormAudio<SAMPLE_RATE>>()
+    }
+
+    #[test]
+    fn test_emitters_follow_the_listener() {
+        let (mut app, mut mixer) = app(4);
+        app.world_mut()
+            .spawn((SoundListener, GlobalTransform::default()));
+        let emitter = app
+            .world_mut()
+            .spawn((
+                SoundEmitter::looping(ConstantSignal::<SAMPLE_RATE>(1.0)),
+                GlobalTransform::from(Transform::from_xyz(-1.0, 0.0, 0.0)),
+            ))
+            .id();
+        step(&mut app, 0.01);
+        assert_eq!(audio(&app).free_slots(), 3);
+
+        let [left, right] = mixer.next_frame();
+        assert!((left - 1.0).abs() < 1e-9 && right.abs() < 1e-9);
+
+        // Moving to the right glides the sound across
+        app.world_mut()
+            .entity_mut(emitter)
+            .insert(GlobalTransform::from(Transform::from_xyz(1.0, 0.0, 0.0)));
+        step(&mut app, 0.01);
+        for _ in 0..SAMPLE_RATE {
+            mixer.next_frame();
+        }
+        let [left, right] = mixer.next_frame();
+        assert!(left.abs() < 1e-6 && (right - 1.0).abs() < 1e-6);
+
+        app.world_mut().entity_mut(emitter).remove::<SoundEmitter>();
+        step(&mut app, 0.01);
+        assert_eq!(audio(&app).free_slots(), 4);
+        assert_eq!(mixer.next_frame(), [0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_one_shots_finish_and_steal() {
+        let (mut app, mut mixer) = app(2);
+        let first = app
+            .world_mut()
+            .spawn(
+                SoundEmitter::one_shot(ConstantSignal::<SAMPLE_RATE>(1.0), 1.0).despawn_when_done(),
+            )
+            .id();
+        step(&mut app, 0.1);
+        let second = app
+            .world_mut()
+            .spawn(SoundEmitter::one_shot(
+                ConstantSignal::<SAMPLE_RATE>(1.0),
+                1.0,
+            ))
+            .id();
+        step(&mut app, 0.1);
+        assert_eq!(audio(&app).free_slots(), 0);
+
+        // A third steals the oldest, despawning its entity
+        let played = app
+            .world_mut()
+            .resource_mut::<EarwormAudio<SAMPLE_RATE>>()
+            .play_one_shot(ConstantSignal::<SAMPLE_RATE>(1.0), 0.5, 1.0, 0.0);
+        assert!(played);
+        step(&mut app, 0.1);
+        assert!(app.world().get_entity(first).is_err());
+
+        // The second finishes and loses its component
+        step(&mut app, 1.0);
+        assert!(app.world().get::<SoundEmitter>(second).is_none());
+        assert_eq!(audio(&app).free_slots(), 2);
+
+        mixer.next_frame();
+        assert_eq!(mixer.active(), 2);
+    }
+
+    #[test]
+    fn test_clock_writes_steps() {
+        let (mut app, _mixer) = app(1);
+        // 120 BPM in 16ths: 8 steps a second
+        step(&mut app, 0.5);
+        let steps = app.world().resource::<Messages<MusicStep>>();
+        assert_eq!(steps.len(), 4);
+        assert!((app.world().resource::<MusicClock>().beat_position() - 1.0).abs() < 1e-2);
+    }
+}
@@ -31,3 +31,10 @@ pub trait AudioSignal<const SAMPLE_RATE: u32>: Signal {
         SAMPLE_RATE as f64
     }
 }
+
+/// Boxed audio signals keep their sample rate, so graphs of different types
+/// can be stored as `Box<dyn AudioSignal<SAMPLE_RATE> + Send>`.
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE> + ?Sized> AudioSignal<SAMPLE_RATE>
+    for Box<S>
+{
+}
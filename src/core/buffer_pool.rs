@@ -0,0 +1,193 @@
+//! Reusable scratch buffers for block processing.
+//!
+//! A `process()` implementation that needs a temporary buffer on every call
+//! (e.g. to render each voice before mixing) would otherwise put the allocator
+//! on the audio thread. A [`BufferPool`] keeps released buffers around so that,
+//! once warmed up or preallocated, acquiring a buffer no longer allocates.
+//!
+//! Within the crate only `VoiceAllocator::process` uses a pool. The effects
+//! work one sample at a time and own their delay lines outright, and the file
+//! renderers allocate their blocks once when a render starts, so neither has
+//! per-call scratch to pool.
+
+/// Default block size used to size pooled buffers.
+pub const DEFAULT_BLOCK_SIZE: usize = 512;
+
+/// A pool of reusable `f64` buffers.
+///
+/// Buffers are handed out zero-filled with the requested length and should be
+/// given back with [`release`](BufferPool::release) (or borrowed through
+/// [`with_buffer`](BufferPool::with_buffer), which does both). A buffer only
+/// allocates when the pool is empty or the request exceeds its capacity.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::core::BufferPool;
+///
+/// let mut pool = BufferPool::with_buffers(2, 256);
+///
+/// let buffer = pool.acquire(128);
+/// assert_eq!(buffer.len(), 128);
+/// pool.release(buffer);
+///
+/// let peak = pool.with_buffer(64, |buf| {
+///     buf[0] = 0.5;
+///     buf.iter().fold(0.0_f64, |max, s| max.max(s.abs()))
+/// });
+/// assert_eq!(peak, 0.5);
+/// assert_eq!(pool.allocations(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BufferPool {
+    free: Vec<Vec<f64>>,
+    block_size: usize,
+    allocations: usize,
+}
+
+impl BufferPool {
+    /// Creates an empty pool whose buffers are allocated with `block_size` capacity.
+    ///
+    /// Buffers are allocated lazily on first use; use
+    /// [`with_buffers`](BufferPool::with_buffers) to allocate them up front.
+    pub fn new(block_size: usize) -> Self {
+        Self {
+            free: Vec::new(),
+            block_size: block_size.max(1),
+            allocations: 0,
+        }
+    }
+
+    /// Creates a pool with `count` buffers of `block_size` capacity preallocated.
+    pub fn with_buffers(count: usize, block_size: usize) -> Self {
+        let mut pool = Self::new(block_size);
+        pool.reserve(count, block_size);
+        pool
+    }
+
+    /// Makes sure at least `count` free buffers with `block_size` capacity exist.
+    ///
+    /// Raises the pool's block size if `block_size` is larger. Call this outside
+    /// the audio thread, e.g. when the maximum block size is known.
+    pub fn reserve(&mut self, count: usize, block_size: usize) {
+        self.block_size = self.block_size.max(block_size);
+        for buffer in self.free.iter_mut() {
+            if buffer.capacity() < self.block_size {
+                buffer.reserve_exact(self.block_size - buffer.len());
+                self.allocations += 1;
+            }
+        }
+        while self.free.len() < count {
+            self.free.push(Vec::with_capacity(self.block_size));
+            self.allocations += 1;
+        }
+    }
+
+    /// Takes a zero-filled buffer of length `len` from the pool.
+    ///
+    /// Allocates only if no free buffer is available or the request is larger
+    /// than the free buffer's capacity.
+    pub fn acquire(&mut self, len: usize) -> Vec<f64> {
+        let mut buffer = match self.free.pop() {
+            Some(buffer) => buffer,
+            None => {
+                self.allocations += 1;
+                Vec::with_capacity(self.block_size.max(len))
+            }
+        };
+        if buffer.capacity() < len {
+            self.allocations += 1;
+        }
+        buffer.clear();
+        buffer.resize(len, 0.0);
+        buffer
+    }
+
+    /// Returns a buffer to the pool for reuse.
+    pub fn release(&mut self, buffer: Vec<f64>) {
+        self.free.push(buffer);
+    }
+
+    /// Runs `f` with a zero-filled pooled buffer of length `len`, then returns it.
+    pub fn with_buffer<R>(&mut self, len: usize, f: impl FnOnce(&mut [f64]) -> R) -> R {
+        let mut buffer = self.acquire(len);
+        let result = f(&mut buffer);
+        self.release(buffer);
+        result
+    }
+
+    /// Returns the capacity new buffers are allocated with.
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Returns the number of free buffers in the pool.
+    pub fn available(&self) -> usize {
+        self.free.len()
+    }
+
+    /// Returns how many times the pool has allocated or grown a buffer.
+    ///
+    /// Useful for checking that a processing path stops allocating after warm-up.
+    pub fn allocations(&self) -> usize {
+        self.allocations
+    }
+}
+
+impl Default for BufferPool {
+    fn default() -> Self {
+        Self::new(DEFAULT_BLOCK_SIZE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reuses_released_buffers() {
+        let mut pool = BufferPool::new(64);
+        let buffer = pool.acquire(32);
+        assert_eq!(pool.allocations(), 1);
+        pool.release(buffer);
+
+        for _ in 0..10 {
+            let buffer = pool.acquire(64);
+            pool.release(buffer);
+        }
+        assert_eq!(pool.allocations(), 1);
+        assert_eq!(pool.available(), 1);
+    }
+
+    #[test]
+    fn test_acquired_buffers_are_zeroed() {
+        let mut pool = BufferPool::with_buffers(1, 8);
+        pool.with_buffer(8, |buf| buf.fill(1.0));
+        let buffer = pool.acquire(8);
+        assert!(buffer.iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn test_oversized_request_counts_allocation() {
+        let mut pool = BufferPool::with_buffers(1, 16);
+        assert_eq!(pool.allocations(), 1);
+        let buffer = pool.acquire(32);
+        assert_eq!(buffer.len(), 32);
+        assert_eq!(pool.allocations(), 2);
+    }
+
+    #[test]
+    fn test_reserve_grows_pool() {
+        let mut pool = BufferPool::with_buffers(1, 16);
+        pool.reserve(3, 128);
+        assert_eq!(pool.available(), 3);
+        assert_eq!(pool.block_size(), 128);
+
+        let before = pool.allocations();
+        let buffers: Vec<_> = (0..3).map(|_| pool.acquire(128)).collect();
+        assert_eq!(pool.allocations(), before);
+        for buffer in buffers {
+            pool.release(buffer);
+        }
+    }
+}
@@ -4,6 +4,9 @@
 //! including mathematical operations (addition, multiplication), gain control,
 //! offsetting, and mixing multiple signals together.
 
+use crate::core::control_rate::{ControlRate, control_interval};
+use crate::core::guard::DebugGuard;
+use crate::core::registry::{Named, ParamRegistry};
 use crate::{AudioSignal, Param, Signal};
 
 /// Multiplies two signals together (amplitude modulation / ring modulation).
@@ -38,6 +41,11 @@ impl<A: Signal, B: Signal> Signal for Multiply<A, B> {
     fn next_sample(&mut self) -> f64 {
         self.a.next_sample() * self.b.next_sample()
     }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.a.prepare(max_block_size, sample_rate);
+        self.b.prepare(max_block_size, sample_rate);
+    }
 }
 
 impl<const SAMPLE_RATE: u32, A: AudioSignal<SAMPLE_RATE>, B: AudioSignal<SAMPLE_RATE>>
@@ -76,6 +84,11 @@ impl<A: Signal, B: Signal> Signal for Add<A, B> {
     fn next_sample(&mut self) -> f64 {
         self.a.next_sample() + self.b.next_sample()
     }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.a.prepare(max_block_size, sample_rate);
+        self.b.prepare(max_block_size, sample_rate);
+    }
 }
 
 impl<const SAMPLE_RATE: u32, A: AudioSignal<SAMPLE_RATE>, B: AudioSignal<SAMPLE_RATE>>
@@ -106,6 +119,11 @@ impl<S: Signal> Signal for Gain<S> {
     fn next_sample(&mut self) -> f64 {
         self.source.next_sample() * self.gain.value()
     }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.gain.prepare(max_block_size, sample_rate);
+    }
 }
 
 impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE> for Gain<S> {}
@@ -134,10 +152,76 @@ impl<S: Signal> Signal for Offset<S> {
     fn next_sample(&mut self) -> f64 {
         self.source.next_sample() + self.offset.value()
     }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.offset.prepare(max_block_size, sample_rate);
+    }
 }
 
 impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE> for Offset<S> {}
 
+/// How a mix combinator keeps its summed output within range.
+///
+/// Summing several full-scale signals easily exceeds [-1.0, 1.0]. The default
+/// [`MixMode::Sum`] leaves the sum untouched; the other modes trade a little
+/// accuracy for headroom.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{ConstantSignal, Mix2, MixMode, Signal};
+///
+/// let a = ConstantSignal::<44100>(1.0);
+/// let b = ConstantSignal::<44100>(1.0);
+/// let mut mixer = Mix2::new(a, 1.0, b, 1.0).with_mode(MixMode::SoftClip { ceiling: 1.0 });
+/// assert!(mixer.next_sample() < 1.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum MixMode {
+    /// Plain weighted sum (may exceed [-1.0, 1.0]).
+    #[default]
+    Sum,
+    /// Smoothly saturates the sum with `ceiling * tanh(x / ceiling)`.
+    ///
+    /// Quiet material passes nearly unchanged; loud peaks approach but never
+    /// exceed `ceiling`.
+    SoftClip {
+        /// Maximum output magnitude (must be positive).
+        ceiling: f64,
+    },
+    /// Divides the sum by the total absolute weight whenever that total exceeds 1.0.
+    ///
+    /// Inputs within [-1.0, 1.0] are then guaranteed to produce output within
+    /// [-1.0, 1.0], at the cost of a quieter mix.
+    Normalize,
+}
+
+impl MixMode {
+    /// Applies the mode to a weighted sum.
+    ///
+    /// # Arguments
+    ///
+    /// * `sum` - The weighted sum of all inputs
+    /// * `total_weight` - Sum of the absolute weights that produced `sum`
+    pub fn apply(self, sum: f64, total_weight: f64) -> f64 {
+        match self {
+            MixMode::Sum => sum,
+            MixMode::SoftClip { ceiling } => {
+                let ceiling = ceiling.max(f64::EPSILON);
+                ceiling * (sum / ceiling).tanh()
+            }
+            MixMode::Normalize => {
+                if total_weight > 1.0 {
+                    sum / total_weight
+                } else {
+                    sum
+                }
+            }
+        }
+    }
+}
+
 /// Mixes two signals together with individual weights.
 ///
 /// This combinator combines two signals with independent gain factors.
@@ -157,6 +241,7 @@ pub struct Mix2<A: Signal, B: Signal> {
     weight_a: Param,
     b: B,
     weight_b: Param,
+    mode: MixMode,
 }
 
 impl<A: Signal, B: Signal> Mix2<A, B> {
@@ -167,13 +252,34 @@ impl<A: Signal, B: Signal> Mix2<A, B> {
             weight_a: weight_a.into(),
             b,
             weight_b: weight_b.into(),
+            mode: MixMode::Sum,
         }
     }
+
+    /// Sets how the summed output is kept in range (see [`MixMode`]).
+    pub fn with_mode(mut self, mode: MixMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Returns the current mix mode.
+    pub fn mode(&self) -> MixMode {
+        self.mode
+    }
 }
 
 impl<A: Signal, B: Signal> Signal for Mix2<A, B> {
     fn next_sample(&mut self) -> f64 {
-        self.a.next_sample() * self.weight_a.value() + self.b.next_sample() * self.weight_b.value()
+        let (wa, wb) = (self.weight_a.value(), self.weight_b.value());
+        let sum = self.a.next_sample() * wa + self.b.next_sample() * wb;
+        self.mode.apply(sum, wa.abs() + wb.abs())
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.a.prepare(max_block_size, sample_rate);
+        self.weight_a.prepare(max_block_size, sample_rate);
+        self.b.prepare(max_block_size, sample_rate);
+        self.weight_b.prepare(max_block_size, sample_rate);
     }
 }
 
@@ -201,6 +307,7 @@ pub struct Mix3<A: Signal, B: Signal, C: Signal> {
     weight_b: Param,
     c: C,
     weight_c: Param,
+    mode: MixMode,
 }
 
 impl<A: Signal, B: Signal, C: Signal> Mix3<A, B, C> {
@@ -220,15 +327,40 @@ impl<A: Signal, B: Signal, C: Signal> Mix3<A, B, C> {
             weight_b: weight_b.into(),
             c,
             weight_c: weight_c.into(),
+            mode: MixMode::Sum,
         }
     }
+
+    /// Sets how the summed output is kept in range (see [`MixMode`]).
+    pub fn with_mode(mut self, mode: MixMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Returns the current mix mode.
+    pub fn mode(&self) -> MixMode {
+        self.mode
+    }
 }
 
 impl<A: Signal, B: Signal, C: Signal> Signal for Mix3<A, B, C> {
     fn next_sample(&mut self) -> f64 {
-        self.a.next_sample() * self.weight_a.value()
-            + self.b.next_sample() * self.weight_b.value()
-            + self.c.next_sample() * self.weight_c.value()
+        let (wa, wb, wc) = (
+            self.weight_a.value(),
+            self.weight_b.value(),
+            self.weight_c.value(),
+        );
+        let sum = self.a.next_sample() * wa + self.b.next_sample() * wb + self.c.next_sample() * wc;
+        self.mode.apply(sum, wa.abs() + wb.abs() + wc.abs())
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.a.prepare(max_block_size, sample_rate);
+        self.weight_a.prepare(max_block_size, sample_rate);
+        self.b.prepare(max_block_size, sample_rate);
+        self.weight_b.prepare(max_block_size, sample_rate);
+        self.c.prepare(max_block_size, sample_rate);
+        self.weight_c.prepare(max_block_size, sample_rate);
     }
 }
 
@@ -263,6 +395,7 @@ pub struct Mix4<A: Signal, B: Signal, C: Signal, D: Signal> {
     weight_c: Param,
     d: D,
     weight_d: Param,
+    mode: MixMode,
 }
 
 impl<A: Signal, B: Signal, C: Signal, D: Signal> Mix4<A, B, C, D> {
@@ -287,16 +420,47 @@ impl<A: Signal, B: Signal, C: Signal, D: Signal> Mix4<A, B, C, D> {
             weight_c: weight_c.into(),
             d,
             weight_d: weight_d.into(),
+            mode: MixMode::Sum,
         }
     }
+
+    /// Sets how the summed output is kept in range (see [`MixMode`]).
+    pub fn with_mode(mut self, mode: MixMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Returns the current mix mode.
+    pub fn mode(&self) -> MixMode {
+        self.mode
+    }
 }
 
 impl<A: Signal, B: Signal, C: Signal, D: Signal> Signal for Mix4<A, B, C, D> {
     fn next_sample(&mut self) -> f64 {
-        self.a.next_sample() * self.weight_a.value()
-            + self.b.next_sample() * self.weight_b.value()
-            + self.c.next_sample() * self.weight_c.value()
-            + self.d.next_sample() * self.weight_d.value()
+        let (wa, wb, wc, wd) = (
+            self.weight_a.value(),
+            self.weight_b.value(),
+            self.weight_c.value(),
+            self.weight_d.value(),
+        );
+        let sum = self.a.next_sample() * wa
+            + self.b.next_sample() * wb
+            + self.c.next_sample() * wc
+            + self.d.next_sample() * wd;
+        self.mode
+            .apply(sum, wa.abs() + wb.abs() + wc.abs() + wd.abs())
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.a.prepare(max_block_size, sample_rate);
+        self.weight_a.prepare(max_block_size, sample_rate);
+        self.b.prepare(max_block_size, sample_rate);
+        self.weight_b.prepare(max_block_size, sample_rate);
+        self.c.prepare(max_block_size, sample_rate);
+        self.weight_c.prepare(max_block_size, sample_rate);
+        self.d.prepare(max_block_size, sample_rate);
+        self.weight_d.prepare(max_block_size, sample_rate);
     }
 }
 
@@ -334,6 +498,10 @@ impl<S: Signal> Signal for Clamp<S> {
     fn next_sample(&mut self) -> f64 {
         self.source.next_sample().clamp(self.min, self.max)
     }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+    }
 }
 
 impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE> for Clamp<S> {}
@@ -369,6 +537,10 @@ where
         let sample = self.source.next_sample();
         (self.func)(sample)
     }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+    }
 }
 
 impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>, F> AudioSignal<SAMPLE_RATE> for Map<S, F> where
@@ -397,6 +569,10 @@ impl<S: Signal> Signal for Invert<S> {
     fn next_sample(&mut self) -> f64 {
         -self.source.next_sample()
     }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+    }
 }
 
 impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE> for Invert<S> {}
@@ -440,6 +616,12 @@ impl<A: Signal, B: Signal> Signal for Crossfade<A, B> {
         let sample_b = self.b.next_sample();
         sample_a * (1.0 - mix) + sample_b * mix
     }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.a.prepare(max_block_size, sample_rate);
+        self.b.prepare(max_block_size, sample_rate);
+        self.mix.prepare(max_block_size, sample_rate);
+    }
 }
 
 impl<const SAMPLE_RATE: u32, A: AudioSignal<SAMPLE_RATE>, B: AudioSignal<SAMPLE_RATE>>
@@ -478,6 +660,11 @@ impl<A: Signal, B: Signal> Signal for Min<A, B> {
     fn next_sample(&mut self) -> f64 {
         self.a.next_sample().min(self.b.next_sample())
     }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.a.prepare(max_block_size, sample_rate);
+        self.b.prepare(max_block_size, sample_rate);
+    }
 }
 
 impl<const SAMPLE_RATE: u32, A: AudioSignal<SAMPLE_RATE>, B: AudioSignal<SAMPLE_RATE>>
@@ -516,6 +703,11 @@ impl<A: Signal, B: Signal> Signal for Max<A, B> {
     fn next_sample(&mut self) -> f64 {
         self.a.next_sample().max(self.b.next_sample())
     }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.a.prepare(max_block_size, sample_rate);
+        self.b.prepare(max_block_size, sample_rate);
+    }
 }
 
 impl<const SAMPLE_RATE: u32, A: AudioSignal<SAMPLE_RATE>, B: AudioSignal<SAMPLE_RATE>>
@@ -545,6 +737,10 @@ impl<S: Signal> Signal for Abs<S> {
     fn next_sample(&mut self) -> f64 {
         self.source.next_sample().abs()
     }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+    }
 }
 
 impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE> for Abs<S> {}
@@ -577,6 +773,11 @@ impl<S: Signal> Signal for Gate<S> {
             0.0
         }
     }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.threshold.prepare(max_block_size, sample_rate);
+    }
 }
 
 impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE> for Gate<S> {}
@@ -681,6 +882,26 @@ pub trait SignalExt: Signal + Sized {
             threshold: threshold.into(),
         }
     }
+
+    /// Runs this signal at control rate, using the global control interval.
+    fn control_rate(self) -> ControlRate<Self> {
+        ControlRate::new(self, control_interval())
+    }
+
+    /// Runs this signal at control rate, updating every `interval` samples.
+    fn control_rate_every(self, interval: usize) -> ControlRate<Self> {
+        ControlRate::new(self, interval)
+    }
+
+    /// Panics with `name` if this signal produces a NaN or infinite sample.
+    fn debug_guard(self, name: impl Into<String>) -> DebugGuard<Self> {
+        DebugGuard::new(self, name)
+    }
+
+    /// Registers this signal as the node `name` in `registry`.
+    fn named(self, registry: &ParamRegistry, name: &str) -> Named<Self> {
+        Named::new(self, registry, name)
+    }
 }
 
 // Blanket implementation for all Signal types
@@ -767,6 +988,70 @@ mod tests {
         assert!(sample.abs() <= 1.0);
     }
 
+    #[test]
+    fn test_mix_mode_sum_is_default() {
+        let mut mixer = Mix2::new(
+            ConstantSignal::<44100>(1.0),
+            1.0,
+            ConstantSignal::<44100>(1.0),
+            1.0,
+        );
+        assert_eq!(mixer.mode(), MixMode::Sum);
+        assert_eq!(mixer.next_sample(), 2.0);
+    }
+
+    #[test]
+    fn test_mix_soft_clip() {
+        let mut mixer = Mix4::new(
+            ConstantSignal::<44100>(1.0),
+            1.0,
+            ConstantSignal::<44100>(1.0),
+            1.0,
+            ConstantSignal::<44100>(1.0),
+            1.0,
+            ConstantSignal::<44100>(1.0),
+            1.0,
+        )
+        .with_mode(MixMode::SoftClip { ceiling: 0.8 });
+
+        let sample = mixer.next_sample();
+        assert!(sample < 0.8 && sample > 0.79, "Sample: {}", sample);
+
+        // Quiet material is nearly untouched
+        let mut quiet = Mix2::new(
+            ConstantSignal::<44100>(0.01),
+            1.0,
+            ConstantSignal::<44100>(0.01),
+            1.0,
+        )
+        .with_mode(MixMode::SoftClip { ceiling: 1.0 });
+        assert!((quiet.next_sample() - 0.02).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_mix_normalize() {
+        let mut mixer = Mix3::new(
+            ConstantSignal::<44100>(1.0),
+            1.0,
+            ConstantSignal::<44100>(-1.0),
+            -1.0,
+            ConstantSignal::<44100>(1.0),
+            1.0,
+        )
+        .with_mode(MixMode::Normalize);
+        assert!((mixer.next_sample() - 1.0).abs() < 1e-12);
+
+        // Weights already summing to at most 1.0 are left alone
+        let mut mixer = Mix2::new(
+            ConstantSignal::<44100>(1.0),
+            0.25,
+            ConstantSignal::<44100>(1.0),
+            0.25,
+        )
+        .with_mode(MixMode::Normalize);
+        assert_eq!(mixer.next_sample(), 0.5);
+    }
+
     // NOTE: Sample rate mismatch test removed - const generics now enforce
     // sample rate matching at compile time, making runtime panics impossible!
 
@@ -0,0 +1,257 @@
+//! Real-time safe command queue.
+//!
+//! The audio callback must never block, so sharing state with a control thread
+//! (UI, MIDI, network) through a `Mutex` risks priority inversion and dropouts.
+//! Instead, the control thread sends commands through a bounded
+//! single-producer/single-consumer queue and the audio thread applies them
+//! between samples.
+//!
+//! Sending and receiving never block and never allocate: the queue's storage is
+//! allocated once by [`command_queue`]. When the queue is full, [`CommandSender::send`]
+//! hands the command back so the caller can decide whether to retry or drop it.
+//!
+//! # Examples
+//!
+//! ```
+//! use earworm::core::{CommandTarget, command_queue};
+//! use earworm::Signal;
+//!
+//! // The commands understood by our patch
+//! enum Command {
+//!     SetVolume(f64),
+//! }
+//!
+//! struct Patch {
+//!     volume: f64,
+//! }
+//!
+//! impl CommandTarget<Command> for Patch {
+//!     fn apply(&mut self, command: Command) {
+//!         match command {
+//!             Command::SetVolume(v) => self.volume = v,
+//!         }
+//!     }
+//! }
+//!
+//! impl Signal for Patch {
+//!     fn next_sample(&mut self) -> f64 {
+//!         self.volume
+//!     }
+//! }
+//!
+//! let (sender, receiver) = command_queue(64);
+//!
+//! // Move the controlled patch to the audio thread...
+//! let mut audio = receiver.control(Patch { volume: 0.0 });
+//!
+//! // ...and send commands from anywhere else
+//! sender.send(Command::SetVolume(0.5)).ok();
+//! assert_eq!(audio.next_sample(), 0.5);
+//! ```
+
+use crate::{AudioSignal, Signal};
+use std::sync::mpsc::{Receiver, SyncSender, TrySendError, sync_channel};
+
+/// Something that can be changed by commands of type `C`.
+///
+/// Implement this on the state that lives on the audio thread. `apply` is
+/// called on the audio thread, so it should be cheap and must not block.
+pub trait CommandTarget<C> {
+    /// Applies a single command.
+    fn apply(&mut self, command: C);
+}
+
+/// Creates a bounded command queue holding at most `capacity` pending commands.
+///
+/// # Panics
+///
+/// Panics if `capacity` is 0.
+pub fn command_queue<C: Send>(capacity: usize) -> (CommandSender<C>, CommandReceiver<C>) {
+    assert!(
+        capacity > 0,
+        "command queue capacity must be greater than 0"
+    );
+    let (sender, receiver) = sync_channel(capacity);
+    (CommandSender { sender }, CommandReceiver { receiver })
+}
+
+/// Control-thread end of a command queue.
+///
+/// Senders can be cloned, but the queue is designed for one producer: with
+/// several, commands from different senders may interleave in any order.
+pub struct CommandSender<C> {
+    sender: SyncSender<C>,
+}
+
+impl<C> Clone for CommandSender<C> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<C> CommandSender<C> {
+    /// Queues a command without blocking.
+    ///
+    /// # Errors
+    ///
+    /// Returns the command if the queue is full or the receiver has been dropped.
+    pub fn send(&self, command: C) -> Result<(), C> {
+        self.sender.try_send(command).map_err(|err| match err {
+            TrySendError::Full(command) | TrySendError::Disconnected(command) => command,
+        })
+    }
+}
+
+/// Audio-thread end of a command queue.
+pub struct CommandReceiver<C> {
+    receiver: Receiver<C>,
+}
+
+impl<C> CommandReceiver<C> {
+    /// Applies every pending command to `target`, returning how many were applied.
+    ///
+    /// Never blocks; returns 0 immediately if nothing is pending.
+    pub fn apply_pending<T: CommandTarget<C> + ?Sized>(&self, target: &mut T) -> usize {
+        let mut applied = 0;
+        while let Ok(command) = self.receiver.try_recv() {
+            target.apply(command);
+            applied += 1;
+        }
+        applied
+    }
+
+    /// Returns the next pending command, if any, without blocking.
+    pub fn try_recv(&self) -> Option<C> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Wraps `target` so pending commands are applied before every sample.
+    pub fn control<T: CommandTarget<C>>(self, target: T) -> Controlled<T, C> {
+        Controlled {
+            target,
+            commands: self,
+        }
+    }
+}
+
+/// A signal whose state is updated from a command queue.
+///
+/// Pending commands are applied before each sample, so a change takes effect
+/// on the next sample after it is sent. Created by [`CommandReceiver::control`].
+pub struct Controlled<T, C> {
+    target: T,
+    commands: CommandReceiver<C>,
+}
+
+impl<T, C> Controlled<T, C> {
+    /// Returns a reference to the controlled state.
+    pub fn inner(&self) -> &T {
+        &self.target
+    }
+
+    /// Returns a mutable reference to the controlled state.
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.target
+    }
+}
+
+impl<T: CommandTarget<C> + Signal, C> Signal for Controlled<T, C> {
+    fn next_sample(&mut self) -> f64 {
+        self.commands.apply_pending(&mut self.target);
+        self.target.next_sample()
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.target.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, T, C> AudioSignal<SAMPLE_RATE> for Controlled<T, C> where
+    T: CommandTarget<C> + AudioSignal<SAMPLE_RATE>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Recorder {
+        values: Vec<i32>,
+    }
+
+    impl CommandTarget<i32> for Recorder {
+        fn apply(&mut self, command: i32) {
+            self.values.push(command);
+        }
+    }
+
+    impl Signal for Recorder {
+        fn next_sample(&mut self) -> f64 {
+            self.values.len() as f64
+        }
+    }
+
+    #[test]
+    fn test_commands_applied_in_order() {
+        let (sender, receiver) = command_queue(8);
+        sender.send(1).unwrap();
+        sender.send(2).unwrap();
+        sender.send(3).unwrap();
+
+        let mut recorder = Recorder::default();
+        assert_eq!(receiver.apply_pending(&mut recorder), 3);
+        assert_eq!(recorder.values, vec![1, 2, 3]);
+        assert_eq!(receiver.apply_pending(&mut recorder), 0);
+    }
+
+    #[test]
+    fn test_full_queue_returns_command() {
+        let (sender, _receiver) = command_queue(2);
+        assert_eq!(sender.send(1), Ok(()));
+        assert_eq!(sender.send(2), Ok(()));
+        assert_eq!(sender.send(3), Err(3));
+    }
+
+    #[test]
+    fn test_disconnected_returns_command() {
+        let (sender, receiver) = command_queue::<i32>(2);
+        drop(receiver);
+        assert_eq!(sender.send(7), Err(7));
+    }
+
+    #[test]
+    fn test_controlled_signal_applies_before_sample() {
+        let (sender, receiver) = command_queue(8);
+        let mut controlled = receiver.control(Recorder::default());
+
+        assert_eq!(controlled.next_sample(), 0.0);
+        sender.send(10).unwrap();
+        assert_eq!(controlled.next_sample(), 1.0);
+        assert_eq!(controlled.inner().values, vec![10]);
+    }
+
+    #[test]
+    fn test_send_from_other_thread() {
+        let (sender, receiver) = command_queue(16);
+        std::thread::spawn(move || {
+            for i in 0..10 {
+                sender.send(i).unwrap();
+            }
+        })
+        .join()
+        .unwrap();
+
+        let mut recorder = Recorder::default();
+        receiver.apply_pending(&mut recorder);
+        assert_eq!(recorder.values, (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[should_panic(expected = "command queue capacity must be greater than 0")]
+    fn test_zero_capacity() {
+        command_queue::<i32>(0);
+    }
+}
@@ -0,0 +1,214 @@
+//! Control-rate evaluation of modulation signals.
+//!
+//! LFOs, envelopes, and other modulation sources rarely need to be computed
+//! at audio rate. [`ControlRate`] reads its source once every N samples and
+//! linearly interpolates between those values, which keeps modulation smooth.
+//! The source still runs at the full sample rate, so its frequencies and
+//! stage timings are unchanged: between reads it is advanced with
+//! [`Signal::skip`], which the basic oscillators implement as a phase jump,
+//! dividing their cost by N.
+//!
+//! The interval can be set per node with
+//! [`SignalExt::control_rate_every`](crate::SignalExt::control_rate_every), or
+//! globally for all nodes created with
+//! [`SignalExt::control_rate`](crate::SignalExt::control_rate) via
+//! [`set_control_interval`].
+
+use crate::{AudioSignal, Signal};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Default number of samples between control-rate updates.
+pub const DEFAULT_CONTROL_INTERVAL: usize = 32;
+
+static CONTROL_INTERVAL: AtomicUsize = AtomicUsize::new(DEFAULT_CONTROL_INTERVAL);
+
+/// Returns the global control-rate interval in samples.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::core::control_interval;
+///
+/// assert!(control_interval() >= 1);
+/// ```
+pub fn control_interval() -> usize {
+    CONTROL_INTERVAL.load(Ordering::Relaxed)
+}
+
+/// Sets the global control-rate interval used by [`SignalExt::control_rate`](crate::SignalExt::control_rate).
+///
+/// Only affects nodes created after the call. Values below 1 are clamped to 1
+/// (audio rate).
+///
+/// # Examples
+///
+/// ```
+/// use earworm::core::{control_interval, set_control_interval};
+///
+/// set_control_interval(64);
+/// assert_eq!(control_interval(), 64);
+/// # set_control_interval(earworm::core::DEFAULT_CONTROL_INTERVAL);
+/// ```
+pub fn set_control_interval(interval: usize) {
+    CONTROL_INTERVAL.store(interval.max(1), Ordering::Relaxed);
+}
+
+/// Evaluates a signal at control rate with linear interpolation.
+///
+/// The source is read once every `interval` samples and skipped over for the
+/// rest, so it keeps running at the full sample rate. Between updates the
+/// output ramps linearly from the previous control value to the newest one,
+/// so the output trails the source by one control period.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{Signal, SignalExt, SineOscillator};
+///
+/// // A slow LFO only needs updating every 64 samples
+/// let mut lfo = SineOscillator::<44100>::new(0.5).control_rate_every(64);
+/// let value = lfo.next_sample();
+/// assert!(value.abs() <= 1.0);
+/// ```
+pub struct ControlRate<S: Signal> {
+    source: S,
+    interval: usize,
+    remaining: usize,
+    current: f64,
+    step: f64,
+    started: bool,
+}
+
+impl<S: Signal> ControlRate<S> {
+    /// Creates a control-rate wrapper updating every `interval` samples.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is 0.
+    pub fn new(source: S, interval: usize) -> Self {
+        assert!(interval > 0, "control interval must be greater than 0");
+        Self {
+            source,
+            interval,
+            remaining: 0,
+            current: 0.0,
+            step: 0.0,
+            started: false,
+        }
+    }
+
+    /// Returns the number of samples between source updates.
+    pub fn interval(&self) -> usize {
+        self.interval
+    }
+}
+
+impl<S: Signal> Signal for ControlRate<S> {
+    fn next_sample(&mut self) -> f64 {
+        if self.remaining == 0 {
+            let target = self.source.next_sample();
+            self.source.skip(self.interval - 1);
+            if !self.started {
+                self.current = target;
+                self.started = true;
+            }
+            self.step = (target - self.current) / self.interval as f64;
+            self.remaining = self.interval;
+        }
+
+        let value = self.current;
+        self.current += self.step;
+        self.remaining -= 1;
+        value
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE>
+    for ControlRate<S>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SignalExt;
+
+    /// Counts how many times it is pulled and outputs that count.
+    struct Counter(f64);
+
+    impl Signal for Counter {
+        fn next_sample(&mut self) -> f64 {
+            self.0 += 1.0;
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_follows_source_one_period_late() {
+        let mut cr = ControlRate::new(Counter(0.0), 4);
+        let samples: Vec<f64> = cr.iter().take(12).collect();
+
+        // First block holds the first value, then ramps towards each new one
+        assert_eq!(
+            samples,
+            vec![1.0, 1.0, 1.0, 1.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]
+        );
+    }
+
+    #[cfg(feature = "synth")]
+    #[test]
+    fn test_keeps_oscillator_frequency() {
+        let osc = crate::SineOscillator::<44100>::new(100.0);
+        let mut cr = osc.control_rate_every(32);
+        let samples: Vec<f64> = cr.iter().take(44100).collect();
+        let crossings = samples
+            .windows(2)
+            .filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0))
+            .count();
+        assert!((199..=201).contains(&crossings), "{} crossings", crossings);
+    }
+
+    #[cfg(feature = "music")]
+    #[test]
+    fn test_keeps_envelope_timing() {
+        // 0.1 s attack to 1.0, 0.2 s decay to 0.5 at 1 kHz
+        let env = crate::music::ModEnvelope::new(crate::ADSR::new(0.1, 0.2, 0.5, 0.3, 1000.0));
+        env.trigger().open();
+        let mut cr = env.control_rate_every(10);
+        let samples: Vec<f64> = cr.iter().take(1000).collect();
+
+        // One control period late
+        let peak = samples
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap()
+            .0;
+        assert!((105..=115).contains(&peak), "peak at {}", peak);
+        assert!((samples[320] - 0.5).abs() < 1e-9);
+        assert!(samples[290] > 0.5);
+    }
+
+    #[test]
+    fn test_interval_one_is_audio_rate() {
+        let mut cr = ControlRate::new(Counter(0.0), 1);
+        let samples: Vec<f64> = cr.iter().take(3).collect();
+        assert_eq!(samples, vec![1.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "control interval must be greater than 0")]
+    fn test_zero_interval() {
+        ControlRate::new(Counter(0.0), 0);
+    }
+
+    #[test]
+    fn test_ext_uses_interval() {
+        assert_eq!(Counter(0.0).control_rate_every(16).interval(), 16);
+        assert!(Counter(0.0).control_rate().interval() >= 1);
+    }
+}
@@ -0,0 +1,358 @@
+//! Multi-channel signals.
+//!
+//! A [`FrameSignal`] produces one frame of `CHANNELS` samples at a time, so the
+//! same graph-building style used for mono signals extends to stereo, quad,
+//! 5.1, or any other channel count. Channel counts are const generics, which
+//! means a stereo signal cannot be accidentally routed to a quad output.
+//!
+//! Frames carry raw `f64` values with no DC filtering, so a channel can just as
+//! well carry control voltages for a DC-coupled (Eurorack-style) interface as audio.
+
+use crate::core::{AudioSignal, CommandTarget, Signal};
+
+/// Common interface for multi-channel signal sources and processors.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{FrameSignal, SineOscillator};
+///
+/// // An array of mono signals is a multi-channel signal
+/// let mut stereo = [
+///     SineOscillator::<44100>::new(440.0),
+///     SineOscillator::<44100>::new(660.0),
+/// ];
+/// let frame = stereo.next_frame();
+/// assert_eq!(frame.len(), 2);
+/// ```
+pub trait FrameSignal<const CHANNELS: usize> {
+    /// Generates the next frame, one sample per channel.
+    fn next_frame(&mut self) -> [f64; CHANNELS];
+
+    /// Generates multiple frames into a buffer.
+    ///
+    /// Default implementation calls `next_frame()` for each element.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - Mutable slice of frames to fill
+    fn process_frames(&mut self, buffer: &mut [[f64; CHANNELS]]) {
+        for frame in buffer.iter_mut() {
+            *frame = self.next_frame();
+        }
+    }
+
+    /// Fills an interleaved buffer (`[ch0, ch1, ..., ch0, ch1, ...]`).
+    ///
+    /// Trailing samples that do not make up a whole frame are left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{ConstantSignal, FrameSignal};
+    ///
+    /// let mut stereo = [ConstantSignal::<44100>(0.25), ConstantSignal::<44100>(-0.25)];
+    /// let mut interleaved = [0.0; 4];
+    /// stereo.process_interleaved(&mut interleaved);
+    /// assert_eq!(interleaved, [0.25, -0.25, 0.25, -0.25]);
+    /// ```
+    fn process_interleaved(&mut self, buffer: &mut [f64]) {
+        for chunk in buffer.chunks_exact_mut(CHANNELS) {
+            chunk.copy_from_slice(&self.next_frame());
+        }
+    }
+
+    /// Prepares the signal for blocks of up to `max_block_size` frames.
+    ///
+    /// The multi-channel counterpart of [`Signal::prepare`]; the default
+    /// implementation does nothing.
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        let _ = (max_block_size, sample_rate);
+    }
+}
+
+/// Marker trait for multi-channel signals with a known sample rate.
+///
+/// This is the multi-channel counterpart of [`AudioSignal`].
+pub trait AudioFrameSignal<const SAMPLE_RATE: u32, const CHANNELS: usize>:
+    FrameSignal<CHANNELS>
+{
+    /// Gets the sample rate at which this audio is being generated.
+    fn sample_rate(&self) -> f64 {
+        SAMPLE_RATE as f64
+    }
+}
+
+impl<S: Signal, const CHANNELS: usize> FrameSignal<CHANNELS> for [S; CHANNELS] {
+    fn next_frame(&mut self) -> [f64; CHANNELS] {
+        std::array::from_fn(|channel| self[channel].next_sample())
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        for signal in self.iter_mut() {
+            signal.prepare(max_block_size, sample_rate);
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>, const CHANNELS: usize>
+    AudioFrameSignal<SAMPLE_RATE, CHANNELS> for [S; CHANNELS]
+{
+}
+
+/// Plays a mono signal on every channel of a frame.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{Broadcast, ConstantSignal, FrameSignal};
+///
+/// let mut quad = Broadcast::<_, 4>::new(ConstantSignal::<44100>(0.5));
+/// assert_eq!(quad.next_frame(), [0.5; 4]);
+/// ```
+pub struct Broadcast<S, const CHANNELS: usize> {
+    source: S,
+}
+
+impl<S: Signal, const CHANNELS: usize> Broadcast<S, CHANNELS> {
+    /// Creates a new broadcast adapter.
+    pub fn new(source: S) -> Self {
+        Self { source }
+    }
+}
+
+impl<S: Signal, const CHANNELS: usize> FrameSignal<CHANNELS> for Broadcast<S, CHANNELS> {
+    fn next_frame(&mut self) -> [f64; CHANNELS] {
+        [self.source.next_sample(); CHANNELS]
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>, const CHANNELS: usize>
+    AudioFrameSignal<SAMPLE_RATE, CHANNELS> for Broadcast<S, CHANNELS>
+{
+}
+
+impl<C, S: CommandTarget<C>, const CHANNELS: usize> CommandTarget<C> for Broadcast<S, CHANNELS> {
+    fn apply(&mut self, command: C) {
+        self.source.apply(command);
+    }
+}
+
+/// Describes which input channel feeds each output channel.
+///
+/// Each output channel either copies one input channel or is silent. A map
+/// can reorder channels (e.g. to match a surround layout), duplicate them, or
+/// address a subset of outputs on a larger interface.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::ChannelMap;
+///
+/// // Stereo into the rear pair of a quad rig, fronts silent
+/// let map = ChannelMap::<2, 4>::new([None, None, Some(0), Some(1)]);
+/// assert_eq!(map.apply(&[0.1, 0.2]), [0.0, 0.0, 0.1, 0.2]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelMap<const IN: usize, const OUT: usize> {
+    routes: [Option<usize>; OUT],
+}
+
+impl<const IN: usize, const OUT: usize> ChannelMap<IN, OUT> {
+    /// Creates a channel map from one route per output channel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any route refers to an input channel `>= IN`.
+    pub fn new(routes: [Option<usize>; OUT]) -> Self {
+        for route in routes.iter().flatten() {
+            assert!(*route < IN, "input channel {} out of range", route);
+        }
+        Self { routes }
+    }
+
+    /// Creates a map that passes channel `n` to output `n`, silencing any
+    /// outputs beyond the input count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::ChannelMap;
+    ///
+    /// let map = ChannelMap::<2, 3>::identity();
+    /// assert_eq!(map.apply(&[1.0, 2.0]), [1.0, 2.0, 0.0]);
+    /// ```
+    pub fn identity() -> Self {
+        Self {
+            routes: std::array::from_fn(|channel| (channel < IN).then_some(channel)),
+        }
+    }
+
+    /// Returns the input channel routed to `output`, if any.
+    pub fn route(&self, output: usize) -> Option<usize> {
+        self.routes.get(output).copied().flatten()
+    }
+
+    /// Applies the map to a single frame.
+    pub fn apply(&self, frame: &[f64; IN]) -> [f64; OUT] {
+        std::array::from_fn(|channel| self.routes[channel].map_or(0.0, |input| frame[input]))
+    }
+}
+
+impl<const IN: usize, const OUT: usize> Default for ChannelMap<IN, OUT> {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+/// Routes a multi-channel signal through a [`ChannelMap`].
+///
+/// Created by [`FrameSignalExt::remap`].
+pub struct Remap<F, const IN: usize, const OUT: usize> {
+    source: F,
+    map: ChannelMap<IN, OUT>,
+}
+
+impl<F: FrameSignal<IN>, const IN: usize, const OUT: usize> FrameSignal<OUT> for Remap<F, IN, OUT> {
+    fn next_frame(&mut self) -> [f64; OUT] {
+        self.map.apply(&self.source.next_frame())
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, F, const IN: usize, const OUT: usize>
+    AudioFrameSignal<SAMPLE_RATE, OUT> for Remap<F, IN, OUT>
+where
+    F: AudioFrameSignal<SAMPLE_RATE, IN>,
+{
+}
+
+/// Averages all channels of a multi-channel signal into a mono signal.
+///
+/// Created by [`FrameSignalExt::downmix`].
+pub struct Downmix<F, const CHANNELS: usize> {
+    source: F,
+}
+
+impl<F: FrameSignal<CHANNELS>, const CHANNELS: usize> Signal for Downmix<F, CHANNELS> {
+    fn next_sample(&mut self) -> f64 {
+        let frame = self.source.next_frame();
+        frame.iter().sum::<f64>() / CHANNELS.max(1) as f64
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, F, const CHANNELS: usize> AudioSignal<SAMPLE_RATE>
+    for Downmix<F, CHANNELS>
+where
+    F: AudioFrameSignal<SAMPLE_RATE, CHANNELS>,
+{
+}
+
+/// Extension trait providing channel routing combinators for multi-channel signals.
+pub trait FrameSignalExt<const CHANNELS: usize>: FrameSignal<CHANNELS> + Sized {
+    /// Routes channels through a [`ChannelMap`], changing the channel count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{ChannelMap, ConstantSignal, FrameSignal, FrameSignalExt};
+    ///
+    /// let stereo = [ConstantSignal::<44100>(1.0), ConstantSignal::<44100>(2.0)];
+    /// // Swap left and right
+    /// let mut swapped = stereo.remap(ChannelMap::new([Some(1), Some(0)]));
+    /// assert_eq!(swapped.next_frame(), [2.0, 1.0]);
+    /// ```
+    fn remap<const OUT: usize>(self, map: ChannelMap<CHANNELS, OUT>) -> Remap<Self, CHANNELS, OUT> {
+        Remap { source: self, map }
+    }
+
+    /// Averages all channels into a mono signal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{ConstantSignal, FrameSignalExt, Signal};
+    ///
+    /// let stereo = [ConstantSignal::<44100>(1.0), ConstantSignal::<44100>(0.0)];
+    /// let mut mono = stereo.downmix();
+    /// assert_eq!(mono.next_sample(), 0.5);
+    /// ```
+    fn downmix(self) -> Downmix<Self, CHANNELS> {
+        Downmix { source: self }
+    }
+}
+
+impl<F: FrameSignal<CHANNELS>, const CHANNELS: usize> FrameSignalExt<CHANNELS> for F {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConstantSignal;
+
+    #[test]
+    fn test_array_frame() {
+        let mut signals = [
+            ConstantSignal::<44100>(0.1),
+            ConstantSignal::<44100>(0.2),
+            ConstantSignal::<44100>(0.3),
+        ];
+        assert_eq!(signals.next_frame(), [0.1, 0.2, 0.3]);
+    }
+
+    #[test]
+    fn test_process_frames() {
+        let mut broadcast = Broadcast::<_, 2>::new(ConstantSignal::<44100>(0.7));
+        let mut buffer = [[0.0; 2]; 8];
+        broadcast.process_frames(&mut buffer);
+        assert!(buffer.iter().all(|frame| *frame == [0.7, 0.7]));
+    }
+
+    #[test]
+    fn test_process_interleaved_partial_frame() {
+        let mut stereo = [ConstantSignal::<44100>(1.0), ConstantSignal::<44100>(2.0)];
+        let mut buffer = [9.0; 5];
+        stereo.process_interleaved(&mut buffer);
+        assert_eq!(buffer, [1.0, 2.0, 1.0, 2.0, 9.0]);
+    }
+
+    #[test]
+    fn test_channel_map_duplicate_and_silence() {
+        let map = ChannelMap::<2, 6>::new([Some(0), Some(1), Some(0), None, Some(1), Some(1)]);
+        assert_eq!(map.apply(&[1.0, -1.0]), [1.0, -1.0, 1.0, 0.0, -1.0, -1.0]);
+        assert_eq!(map.route(3), None);
+        assert_eq!(map.route(4), Some(1));
+        assert_eq!(map.route(10), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "input channel 2 out of range")]
+    fn test_channel_map_out_of_range() {
+        ChannelMap::<2, 2>::new([Some(0), Some(2)]);
+    }
+
+    #[test]
+    fn test_identity_downmix() {
+        let map = ChannelMap::<4, 2>::identity();
+        assert_eq!(map.apply(&[1.0, 2.0, 3.0, 4.0]), [1.0, 2.0]);
+
+        let quad = [
+            ConstantSignal::<44100>(1.0),
+            ConstantSignal::<44100>(2.0),
+            ConstantSignal::<44100>(3.0),
+            ConstantSignal::<44100>(4.0),
+        ];
+        let mut mono = quad.downmix();
+        assert_eq!(mono.next_sample(), 2.5);
+    }
+}
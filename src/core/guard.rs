@@ -0,0 +1,202 @@
+//! NaN/Inf detection for tracking down corrupted signal state.
+//!
+//! An unstable filter or runaway feedback loop usually shows up far downstream
+//! as silence or garbage audio. [`DebugGuard`] checks every sample passing
+//! through it and panics with the node's name as soon as a non-finite value
+//! appears, so the problem is reported where it starts.
+//!
+//! On the audio thread a panic takes the whole stream down, so a guard can
+//! instead report through an [`RtLogger`] and mute the bad samples.
+//!
+//! Built-in filters and effects additionally check their own output with
+//! `debug_assert!`, so debug builds catch corrupted state without any guards.
+
+use super::rt_log::{LogLevel, RtLogger};
+use crate::{AudioSignal, Signal};
+
+/// Panics if a signal produces a NaN or infinite sample.
+///
+/// Unlike the built-in debug assertions, the guard is active in release builds
+/// too; remove it once the problem is found.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{Signal, SignalExt, SineOscillator};
+///
+/// let mut osc = SineOscillator::<44100>::new(440.0).debug_guard("lead osc");
+/// osc.next_sample();
+/// ```
+///
+/// ```should_panic
+/// use earworm::{ConstantSignal, Signal, SignalExt};
+///
+/// let mut broken = ConstantSignal::<44100>(1.0)
+///     .map(|x| x / 0.0)
+///     .debug_guard("divider");
+/// broken.next_sample(); // panics: "divider produced non-finite sample inf at sample 0"
+/// ```
+///
+/// With a logger attached, the guard outputs silence instead of panicking:
+///
+/// ```
+/// use earworm::core::rt_log;
+/// use earworm::{ConstantSignal, Signal, SignalExt};
+///
+/// let (logger, drain) = rt_log(16);
+/// let mut broken = ConstantSignal::<44100>(1.0)
+///     .map(|x| x / 0.0)
+///     .debug_guard("divider")
+///     .with_logger(&logger);
+/// assert_eq!(broken.next_sample(), 0.0);
+/// drain.drain(|record| println!("{}", record)); // "[ERROR] divider: produced non-finite sample (inf)"
+/// ```
+pub struct DebugGuard<S: Signal> {
+    source: S,
+    name: String,
+    position: u64,
+    logger: Option<RtLogger>,
+    faulted: bool,
+}
+
+impl<S: Signal> DebugGuard<S> {
+    /// Creates a guard around `source`, reporting failures as `name`.
+    pub fn new(source: S, name: impl Into<String>) -> Self {
+        Self {
+            source,
+            name: name.into(),
+            position: 0,
+            logger: None,
+            faulted: false,
+        }
+    }
+
+    /// Reports non-finite samples to `logger` instead of panicking.
+    ///
+    /// Non-finite samples are replaced with 0.0. One record is logged when
+    /// the source goes bad, and another is not logged until it has produced
+    /// a finite sample again, so a stuck node does not flood the queue.
+    pub fn with_logger(mut self, logger: &RtLogger) -> Self {
+        self.logger = Some(logger.with_source(self.name.as_str()));
+        self
+    }
+
+    /// Returns the name used in panic messages.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<S: Signal> Signal for DebugGuard<S> {
+    fn next_sample(&mut self) -> f64 {
+        let sample = self.source.next_sample();
+        if let Some(logger) = &self.logger {
+            self.position += 1;
+            if sample.is_finite() {
+                self.faulted = false;
+                return sample;
+            }
+            if !self.faulted {
+                logger.log_value(LogLevel::Error, "produced non-finite sample", sample);
+                self.faulted = true;
+            }
+            return 0.0;
+        }
+        if !sample.is_finite() {
+            panic!(
+                "{} produced non-finite sample {} at sample {}",
+                self.name, sample, self.position
+            );
+        }
+        self.position += 1;
+        sample
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE>
+    for DebugGuard<S>
+{
+}
+
+/// Debug-build check that a node's output is finite.
+///
+/// Used by built-in filters and effects; compiles to nothing in release builds.
+#[cfg(feature = "synth")]
+#[inline]
+pub(crate) fn debug_assert_finite(node: &str, input: f64, output: f64) {
+    debug_assert!(
+        output.is_finite(),
+        "{} produced non-finite output {} (input {})",
+        node,
+        output,
+        input
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstantSignal, SignalExt};
+
+    #[test]
+    fn test_passes_finite_samples() {
+        let mut guard = ConstantSignal::<44100>(0.5).debug_guard("constant");
+        assert_eq!(guard.name(), "constant");
+        for _ in 0..100 {
+            assert_eq!(guard.next_sample(), 0.5);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "broken produced non-finite sample NaN at sample 3")]
+    fn test_panics_with_name_and_position() {
+        let mut count = 0;
+        let mut guard = ConstantSignal::<44100>(0.0)
+            .map(move |_| {
+                count += 1;
+                if count > 3 { f64::NAN } else { 0.0 }
+            })
+            .debug_guard("broken");
+        for _ in 0..4 {
+            guard.next_sample();
+        }
+    }
+
+    #[test]
+    fn test_logger_mutes_and_reports_once_per_fault() {
+        let (logger, drain) = crate::core::rt_log(8);
+        let mut count = 0;
+        let mut guard = ConstantSignal::<44100>(0.5)
+            .map(move |x| {
+                count += 1;
+                if (3..6).contains(&count) { f64::NAN } else { x }
+            })
+            .debug_guard("flaky")
+            .with_logger(&logger);
+
+        let samples: Vec<f64> = (0..8).map(|_| guard.next_sample()).collect();
+        assert_eq!(samples, vec![0.5, 0.5, 0.0, 0.0, 0.0, 0.5, 0.5, 0.5]);
+
+        let mut records = Vec::new();
+        drain.drain(|r| records.push(r.to_string()));
+        assert_eq!(
+            records,
+            vec!["[ERROR] flaky: produced non-finite sample (NaN)"]
+        );
+    }
+
+    #[test]
+    #[cfg(all(debug_assertions, feature = "synth"))]
+    #[should_panic(expected = "BiquadFilter produced non-finite output")]
+    fn test_builtin_nodes_assert_in_debug() {
+        use crate::BiquadFilter;
+
+        let source = ConstantSignal::<44100>(f64::INFINITY);
+        let mut filter = BiquadFilter::lowpass(source, 1000.0, 0.707);
+        filter.next_sample();
+    }
+}
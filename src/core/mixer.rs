@@ -0,0 +1,825 @@
+//! A mixing desk for any number of signals.
+//!
+//! [`Mix2`](crate::Mix2), [`Mix3`](crate::Mix3) and [`Mix4`](crate::Mix4)
+//! suit a fixed handful of sources. A [`Mixer`] holds as many
+//! [`ChannelStrip`]s as needed, each with its own gain, pan, mute, solo and
+//! optional insert effect, and sums them through a master gain and an
+//! optional soft clip or normalization stage. Channels can
+//! also be routed to separate output buses, such as a pair of individual
+//! outs on an audio interface; [`Mixer::outputs`] lays the main mix and the
+//! buses out side by side as one multi-channel signal. One bus can serve as
+//! a cue bus, for auditioning channels on headphones while the main mix
+//! plays on.
+
+use crate::core::{AudioFrameSignal, AudioSignal, FrameSignal, MixMode, Param, Signal};
+use std::f64::consts::FRAC_PI_4;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The dry signal of a [`ChannelStrip`], handed to its insert effect.
+///
+/// Plays whatever the channel's source produced for the current sample.
+pub struct InsertInput<const SAMPLE_RATE: u32> {
+    sample: Arc<AtomicU64>,
+}
+
+impl<const SAMPLE_RATE: u32> Signal for InsertInput<SAMPLE_RATE> {
+    fn next_sample(&mut self) -> f64 {
+        f64::from_bits(self.sample.load(Ordering::Relaxed))
+    }
+}
+
+impl<const SAMPLE_RATE: u32> AudioSignal<SAMPLE_RATE> for InsertInput<SAMPLE_RATE> {}
+
+/// An effect in a channel's insert slot, and where it reads its input.
+struct Insert {
+    input: Arc<AtomicU64>,
+    effect: Box<dyn Signal + Send>,
+}
+
+/// Where a [`ChannelStrip`] sends its output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Route {
+    /// The main mix, through the master gain
+    #[default]
+    Main,
+    /// An output bus, by index; silent if the mixer has no such bus
+    Bus(usize),
+}
+
+/// One channel of a [`Mixer`]: a source with gain, pan, mute and solo.
+///
+/// Muted and unsoloed channels keep running silently, so they stay in time
+/// and come back exactly where they would have been.
+pub struct ChannelStrip<const SAMPLE_RATE: u32> {
+    source: Box<dyn Signal + Send>,
+    insert: Option<Insert>,
+    gain: Param,
+    pan: f64,
+    mute: bool,
+    solo: bool,
+    route: Route,
+    cue: bool,
+    pre_fader: f64, // latest output ahead of the gain, for the cue bus
+    fader: f64,     // latest gain, for normalizing the master sum
+}
+
+impl<const SAMPLE_RATE: u32> ChannelStrip<SAMPLE_RATE> {
+    /// Creates a centered channel at unity gain.
+    pub fn new(source: impl AudioSignal<SAMPLE_RATE> + Send + 'static) -> Self {
+        Self {
+            source: Box::new(source),
+            insert: None,
+            gain: Param::fixed(1.0),
+            pan: 0.0,
+            mute: false,
+            solo: false,
+            route: Route::Main,
+            cue: false,
+            pre_fader: 0.0,
+            fader: 1.0,
+        }
+    }
+
+    /// Sets the channel gain, fixed or modulated (builder style).
+    pub fn with_gain(mut self, gain: impl Into<Param>) -> Self {
+        self.gain = gain.into();
+        self
+    }
+
+    /// Sets the pan from -1.0 (left) to 1.0 (right) (builder style).
+    pub fn with_pan(mut self, pan: f64) -> Self {
+        self.set_pan(pan);
+        self
+    }
+
+    /// Sends the channel to the main mix or an output bus (builder style).
+    pub fn with_route(mut self, route: Route) -> Self {
+        self.route = route;
+        self
+    }
+
+    /// Puts an effect in the insert slot (builder style).
+    ///
+    /// `effect` is given the channel's dry signal and returns the chain that
+    /// processes it, which replaces the source ahead of gain and pan.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{AudioSignalExt, ChannelStrip, SawtoothOscillator};
+    ///
+    /// let strip = ChannelStrip::new(SawtoothOscillator::<44100>::new(110.0))
+    ///     .with_insert(|dry| dry.lowpass_filter(800.0, 0.7));
+    /// assert!(strip.has_insert());
+    /// ```
+    pub fn with_insert<E, F>(mut self, effect: F) -> Self
+    where
+        E: AudioSignal<SAMPLE_RATE> + Send + 'static,
+        F: FnOnce(InsertInput<SAMPLE_RATE>) -> E,
+    {
+        self.set_insert(effect);
+        self
+    }
+
+    /// Puts an effect in the insert slot, replacing any already there.
+    pub fn set_insert<E, F>(&mut self, effect: F)
+    where
+        E: AudioSignal<SAMPLE_RATE> + Send + 'static,
+        F: FnOnce(InsertInput<SAMPLE_RATE>) -> E,
+    {
+        let input = Arc::new(AtomicU64::new(0.0f64.to_bits()));
+        let effect = effect(InsertInput {
+            sample: Arc::clone(&input),
+        });
+        self.insert = Some(Insert {
+            input,
+            effect: Box::new(effect),
+        });
+    }
+
+    /// Empties the insert slot, so the source plays dry.
+    pub fn clear_insert(&mut self) {
+        self.insert = None;
+    }
+
+    /// Returns `true` if an effect is in the insert slot.
+    pub fn has_insert(&self) -> bool {
+        self.insert.is_some()
+    }
+
+    /// Sets the channel gain, fixed or modulated.
+    pub fn set_gain(&mut self, gain: impl Into<Param>) {
+        self.gain = gain.into();
+    }
+
+    /// Sets the pan, clamped to -1.0 (left) to 1.0 (right).
+    pub fn set_pan(&mut self, pan: f64) {
+        self.pan = pan.clamp(-1.0, 1.0);
+    }
+
+    /// Returns the pan.
+    pub fn pan(&self) -> f64 {
+        self.pan
+    }
+
+    /// Mutes or unmutes the channel.
+    pub fn set_mute(&mut self, mute: bool) {
+        self.mute = mute;
+    }
+
+    /// Returns `true` if the channel is muted.
+    pub fn is_muted(&self) -> bool {
+        self.mute
+    }
+
+    /// Solos or unsolos the channel.
+    pub fn set_solo(&mut self, solo: bool) {
+        self.solo = solo;
+    }
+
+    /// Returns `true` if the channel is soloed.
+    pub fn is_soloed(&self) -> bool {
+        self.solo
+    }
+
+    /// Sends the channel to the main mix or an output bus.
+    pub fn set_route(&mut self, route: Route) {
+        self.route = route;
+    }
+
+    /// Returns where the channel is sent.
+    pub fn route(&self) -> Route {
+        self.route
+    }
+
+    /// Sends or stops sending the channel to the mixer's cue bus.
+    ///
+    /// The cue is taken before the channel's gain, mute and solo, so a
+    /// channel can be auditioned while it is muted or faded out of the main
+    /// mix.
+    pub fn set_cue(&mut self, cue: bool) {
+        self.cue = cue;
+    }
+
+    /// Returns `true` if the channel is sent to the cue bus.
+    pub fn is_cued(&self) -> bool {
+        self.cue
+    }
+
+    /// Advances the channel, returning its output after insert and gain.
+    fn next_sample(&mut self) -> f64 {
+        let dry = self.source.next_sample();
+        let gain = self.gain.value();
+        let wet = match &mut self.insert {
+            Some(insert) => {
+                insert.input.store(dry.to_bits(), Ordering::Relaxed);
+                insert.effect.next_sample()
+            }
+            None => dry,
+        };
+        self.pre_fader = wet;
+        self.fader = gain;
+        wet * gain
+    }
+
+    /// Equal-power left and right gains for the pan.
+    fn pan_gains(&self) -> (f64, f64) {
+        let angle = (self.pan + 1.0) * FRAC_PI_4;
+        (angle.cos(), angle.sin())
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.gain.prepare(max_block_size, sample_rate);
+        if let Some(insert) = &mut self.insert {
+            insert.effect.prepare(max_block_size, sample_rate);
+        }
+    }
+}
+
+/// Sums any number of [`ChannelStrip`]s through a master gain.
+///
+/// As a [`Signal`] the mixer outputs the mono sum and pan has no effect; as
+/// a stereo [`FrameSignal`] each channel is placed with an equal-power pan
+/// law (-3dB per side at center). Pull one or the other, not both, since
+/// each advances every channel. When any channel is soloed only soloed
+/// channels are heard, and mute wins over solo.
+///
+/// Both carry the main mix only. Channels routed to an output bus (see
+/// [`ChannelStrip::with_route`]) are left out of it and mixed onto their
+/// bus, a stereo pair with its own gain, which
+/// [`next_outputs`](Self::next_outputs) and [`outputs`](Self::outputs)
+/// render alongside the main mix.
+///
+/// The main mix is a plain sum by default, so many loud channels can push it
+/// far past [-1.0, 1.0]. [`with_mode`](Self::with_mode) adds a stage after
+/// the master gain that keeps it in range: [`MixMode::SoftClip`] saturates
+/// the peaks, and [`MixMode::Normalize`] scales the sum down by the total
+/// channel gain. Output buses are not affected.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{ChannelStrip, FrameSignal, Mixer, SineOscillator, SquareOscillator};
+///
+/// const SAMPLE_RATE: u32 = 44100;
+///
+/// let mut mixer = Mixer::<SAMPLE_RATE>::new()
+///     .with_channel(ChannelStrip::new(SineOscillator::new(110.0)).with_gain(0.8))
+///     .with_channel(ChannelStrip::new(SquareOscillator::new(440.0)).with_pan(-0.5))
+///     .with_master_gain(0.5);
+///
+/// mixer.channel_mut(1).unwrap().set_solo(true);
+/// let [left, right] = mixer.next_frame();
+/// assert!(left.abs() >= right.abs()); // only the lead, panned left
+/// ```
+pub struct Mixer<const SAMPLE_RATE: u32> {
+    channels: Vec<ChannelStrip<SAMPLE_RATE>>,
+    master_gain: Param,
+    buses: Vec<Param>, // gain of each output bus
+    cue_bus: Option<usize>,
+    mode: MixMode,
+}
+
+impl<const SAMPLE_RATE: u32> Mixer<SAMPLE_RATE> {
+    /// Creates a mixer with no channels at unity master gain.
+    pub fn new() -> Self {
+        Self {
+            channels: Vec::new(),
+            master_gain: Param::fixed(1.0),
+            buses: Vec::new(),
+            cue_bus: None,
+            mode: MixMode::Sum,
+        }
+    }
+
+    /// Adds a channel (builder style).
+    pub fn with_channel(mut self, channel: ChannelStrip<SAMPLE_RATE>) -> Self {
+        self.channels.push(channel);
+        self
+    }
+
+    /// Sets the master gain, fixed or modulated (builder style).
+    pub fn with_master_gain(mut self, gain: impl Into<Param>) -> Self {
+        self.master_gain = gain.into();
+        self
+    }
+
+    /// Sets how the main mix is kept in range (builder style).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{ChannelStrip, ConstantSignal, Mixer, MixMode, Signal};
+    ///
+    /// // Sixteen full-scale channels would sum to 16.0
+    /// let mut mixer = Mixer::<44100>::new().with_mode(MixMode::SoftClip { ceiling: 1.0 });
+    /// for _ in 0..16 {
+    ///     mixer.add_channel(ChannelStrip::new(ConstantSignal(1.0)));
+    /// }
+    /// assert!(mixer.next_sample() <= 1.0);
+    /// ```
+    pub fn with_mode(mut self, mode: MixMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Adds an output bus with its gain (builder style).
+    pub fn with_bus(mut self, gain: impl Into<Param>) -> Self {
+        self.buses.push(gain.into());
+        self
+    }
+
+    /// Adds a cue bus with its gain (builder style); see
+    /// [`add_cue_bus`](Self::add_cue_bus).
+    pub fn with_cue_bus(mut self, gain: impl Into<Param>) -> Self {
+        self.add_cue_bus(gain);
+        self
+    }
+
+    /// Adds a channel, returning its index.
+    pub fn add_channel(&mut self, channel: ChannelStrip<SAMPLE_RATE>) -> usize {
+        self.channels.push(channel);
+        self.channels.len() - 1
+    }
+
+    /// Removes and returns the channel at `index`, shifting later channels
+    /// down.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of range.
+    pub fn remove_channel(&mut self, index: usize) -> ChannelStrip<SAMPLE_RATE> {
+        self.channels.remove(index)
+    }
+
+    /// Returns the channel at `index`.
+    pub fn channel(&self, index: usize) -> Option<&ChannelStrip<SAMPLE_RATE>> {
+        self.channels.get(index)
+    }
+
+    /// Returns the channel at `index` for changing its settings.
+    pub fn channel_mut(&mut self, index: usize) -> Option<&mut ChannelStrip<SAMPLE_RATE>> {
+        self.channels.get_mut(index)
+    }
+
+    /// Returns the number of channels.
+    pub fn len(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Returns `true` if the mixer has no channels.
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty()
+    }
+
+    /// Sets the master gain, fixed or modulated.
+    pub fn set_master_gain(&mut self, gain: impl Into<Param>) {
+        self.master_gain = gain.into();
+    }
+
+    /// Sets how the main mix is kept in range.
+    pub fn set_mode(&mut self, mode: MixMode) {
+        self.mode = mode;
+    }
+
+    /// Returns how the main mix is kept in range.
+    pub fn mode(&self) -> MixMode {
+        self.mode
+    }
+
+    /// Adds an output bus with its gain, returning its index for
+    /// [`Route::Bus`].
+    pub fn add_bus(&mut self, gain: impl Into<Param>) -> usize {
+        self.buses.push(gain.into());
+        self.buses.len() - 1
+    }
+
+    /// Adds an output bus and makes it the cue bus, returning its index.
+    ///
+    /// Cued channels (see [`ChannelStrip::set_cue`]) are copied onto the cue
+    /// bus ahead of their gain, mute and solo, on top of whatever is routed
+    /// to it. Play the bus on a headphone output to pre-listen to a pattern
+    /// or instrument before bringing it into the main mix; the bus gain is
+    /// the cue volume, independent of the master gain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{ChannelStrip, ConstantSignal, Mixer};
+    ///
+    /// let mut mixer = Mixer::<44100>::new()
+    ///     .with_channel(ChannelStrip::new(ConstantSignal(0.5)))
+    ///     .with_channel(ChannelStrip::new(ConstantSignal(0.3)));
+    /// let cue = mixer.add_cue_bus(0.8);
+    ///
+    /// // Audition the second channel before unmuting it
+    /// let next = mixer.channel_mut(1).unwrap();
+    /// next.set_mute(true);
+    /// next.set_cue(true);
+    ///
+    /// let mut outputs = [[0.0; 2]; 2];
+    /// mixer.next_outputs(&mut outputs);
+    /// assert!(outputs[cue + 1][0] > 0.0);
+    /// ```
+    pub fn add_cue_bus(&mut self, gain: impl Into<Param>) -> usize {
+        let bus = self.add_bus(gain);
+        self.cue_bus = Some(bus);
+        bus
+    }
+
+    /// Makes an existing bus the cue bus, or with `None` stops cueing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bus` is out of range.
+    pub fn set_cue_bus(&mut self, bus: Option<usize>) {
+        if let Some(bus) = bus {
+            assert!(bus < self.buses.len(), "no bus {}", bus);
+        }
+        self.cue_bus = bus;
+    }
+
+    /// Returns the index of the cue bus, if there is one.
+    pub fn cue_bus(&self) -> Option<usize> {
+        self.cue_bus
+    }
+
+    /// Sets the cue volume, fixed or modulated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mixer has no cue bus.
+    pub fn set_cue_gain(&mut self, gain: impl Into<Param>) {
+        let bus = self.cue_bus.expect("mixer has no cue bus");
+        self.set_bus_gain(bus, gain);
+    }
+
+    /// Returns the number of output buses.
+    pub fn bus_count(&self) -> usize {
+        self.buses.len()
+    }
+
+    /// Sets an output bus's gain, fixed or modulated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bus` is out of range.
+    pub fn set_bus_gain(&mut self, bus: usize, gain: impl Into<Param>) {
+        self.buses[bus] = gain.into();
+    }
+
+    /// Renders one frame of every output as stereo pairs: the main mix in
+    /// `outputs[0]`, then bus 0, bus 1 and so on.
+    ///
+    /// Outputs beyond the buses are silent, and buses beyond `outputs` are
+    /// dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{ChannelStrip, ConstantSignal, Mixer, Route};
+    ///
+    /// // The click goes to its own bus for the drummer's headphones
+    /// let mut mixer = Mixer::<44100>::new()
+    ///     .with_bus(1.0)
+    ///     .with_channel(ChannelStrip::new(ConstantSignal(0.5)))
+    ///     .with_channel(ChannelStrip::new(ConstantSignal(0.2)).with_route(Route::Bus(0)));
+    ///
+    /// let mut outputs = [[0.0; 2]; 2];
+    /// mixer.next_outputs(&mut outputs);
+    /// assert!(outputs[0][0] > 0.3 && outputs[1][0] < 0.2);
+    /// ```
+    pub fn next_outputs(&mut self, outputs: &mut [[f64; 2]]) {
+        outputs.fill([0.0; 2]);
+        let master = self.mix(|channel, sample| {
+            let output = match channel.route {
+                Route::Main => 0,
+                Route::Bus(bus) => bus + 1,
+            };
+            if let Some([left, right]) = outputs.get_mut(output) {
+                let (l, r) = channel.pan_gains();
+                *left += sample * l;
+                *right += sample * r;
+            }
+        });
+        if let Some([left, right]) = self.cue_bus.and_then(|bus| outputs.get_mut(bus + 1)) {
+            for channel in self.channels.iter().filter(|channel| channel.cue) {
+                let (l, r) = channel.pan_gains();
+                *left += channel.pre_fader * l;
+                *right += channel.pre_fader * r;
+            }
+        }
+        if let Some(main) = outputs.first_mut() {
+            main.iter_mut()
+                .for_each(|sample| *sample = self.mode.apply(*sample * master.gain, master.weight));
+        }
+        for (bus, output) in self.buses.iter_mut().zip(outputs.iter_mut().skip(1)) {
+            let gain = bus.value();
+            output.iter_mut().for_each(|sample| *sample *= gain);
+        }
+    }
+
+    /// Turns the mixer into a `CHANNELS`-channel signal carrying the main
+    /// mix on channels 0 and 1, bus 0 on channels 2 and 3, and so on.
+    ///
+    /// Play it on a multi-output interface to send each bus to its own pair
+    /// of hardware outputs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{ChannelStrip, ConstantSignal, FrameSignal, Mixer, Route};
+    ///
+    /// let mut outputs = Mixer::<44100>::new()
+    ///     .with_bus(1.0)
+    ///     .with_channel(ChannelStrip::new(ConstantSignal(1.0)).with_route(Route::Bus(0)))
+    ///     .outputs::<4>();
+    /// let frame = outputs.next_frame();
+    /// assert_eq!(&frame[..2], &[0.0, 0.0]);
+    /// assert!(frame[2] > 0.0 && frame[3] > 0.0);
+    /// ```
+    pub fn outputs<const CHANNELS: usize>(self) -> MixerOutputs<SAMPLE_RATE, CHANNELS> {
+        MixerOutputs {
+            pairs: vec![[0.0; 2]; CHANNELS.div_ceil(2)],
+            mixer: self,
+        }
+    }
+
+    /// Advances every channel, passing each audible one's output to `mix`.
+    fn mix(&mut self, mut mix: impl FnMut(&ChannelStrip<SAMPLE_RATE>, f64)) -> Master {
+        let soloing = self.channels.iter().any(|channel| channel.solo);
+        let mut weight = 0.0;
+        for channel in &mut self.channels {
+            let sample = channel.next_sample();
+            if !channel.mute && (channel.solo || !soloing) {
+                if channel.route == Route::Main {
+                    weight += channel.fader.abs();
+                }
+                mix(channel, sample);
+            }
+        }
+        let gain = self.master_gain.value();
+        Master {
+            gain,
+            weight: weight * gain.abs(),
+        }
+    }
+}
+
+/// The master stage for one sample of the main mix.
+struct Master {
+    gain: f64,
+    /// Total absolute gain from the channels to the output, for
+    /// [`MixMode::Normalize`]
+    weight: f64,
+}
+
+impl<const SAMPLE_RATE: u32> Default for Mixer<SAMPLE_RATE> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const SAMPLE_RATE: u32> Signal for Mixer<SAMPLE_RATE> {
+    fn next_sample(&mut self) -> f64 {
+        let mut sum = 0.0;
+        let master = self.mix(|channel, sample| {
+            if channel.route == Route::Main {
+                sum += sample;
+            }
+        });
+        self.mode.apply(sum * master.gain, master.weight)
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        for channel in &mut self.channels {
+            channel.prepare(max_block_size, sample_rate);
+        }
+        self.master_gain.prepare(max_block_size, sample_rate);
+        for bus in &mut self.buses {
+            bus.prepare(max_block_size, sample_rate);
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32> AudioSignal<SAMPLE_RATE> for Mixer<SAMPLE_RATE> {}
+
+impl<const SAMPLE_RATE: u32> FrameSignal<2> for Mixer<SAMPLE_RATE> {
+    fn next_frame(&mut self) -> [f64; 2] {
+        let (mut left, mut right) = (0.0, 0.0);
+        let master = self.mix(|channel, sample| {
+            if channel.route == Route::Main {
+                let (l, r) = channel.pan_gains();
+                left += sample * l;
+                right += sample * r;
+            }
+        });
+        [left, right].map(|side| self.mode.apply(side * master.gain, master.weight))
+    }
+}
+
+impl<const SAMPLE_RATE: u32> AudioFrameSignal<SAMPLE_RATE, 2> for Mixer<SAMPLE_RATE> {}
+
+/// A [`Mixer`]'s main mix and output buses as one multi-channel signal.
+///
+/// Created by [`Mixer::outputs`].
+pub struct MixerOutputs<const SAMPLE_RATE: u32, const CHANNELS: usize> {
+    mixer: Mixer<SAMPLE_RATE>,
+    pairs: Vec<[f64; 2]>,
+}
+
+impl<const SAMPLE_RATE: u32, const CHANNELS: usize> MixerOutputs<SAMPLE_RATE, CHANNELS> {
+    /// Returns the mixer.
+    pub fn mixer(&self) -> &Mixer<SAMPLE_RATE> {
+        &self.mixer
+    }
+
+    /// Returns the mixer for changing its settings.
+    pub fn mixer_mut(&mut self) -> &mut Mixer<SAMPLE_RATE> {
+        &mut self.mixer
+    }
+}
+
+impl<const SAMPLE_RATE: u32, const CHANNELS: usize> FrameSignal<CHANNELS>
+    for MixerOutputs<SAMPLE_RATE, CHANNELS>
+{
+    fn next_frame(&mut self) -> [f64; CHANNELS] {
+        self.mixer.next_outputs(&mut self.pairs);
+        std::array::from_fn(|channel| self.pairs[channel / 2][channel % 2])
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        Signal::prepare(&mut self.mixer, max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, const CHANNELS: usize> AudioFrameSignal<SAMPLE_RATE, CHANNELS>
+    for MixerOutputs<SAMPLE_RATE, CHANNELS>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstantSignal, SignalExt};
+
+    fn constant(value: f64) -> ChannelStrip<1000> {
+        ChannelStrip::new(ConstantSignal::<1000>(value))
+    }
+
+    #[test]
+    fn test_gain_mute_and_solo() {
+        let mut mixer = Mixer::<1000>::new()
+            .with_channel(constant(1.0).with_gain(0.5))
+            .with_channel(constant(0.25))
+            .with_master_gain(2.0);
+        assert_eq!(mixer.next_sample(), 1.5);
+
+        mixer.channel_mut(0).unwrap().set_mute(true);
+        assert_eq!(mixer.next_sample(), 0.5);
+
+        // Solo silences the rest; mute beats solo
+        mixer.channel_mut(0).unwrap().set_solo(true);
+        assert_eq!(mixer.next_sample(), 0.0);
+        mixer.channel_mut(0).unwrap().set_mute(false);
+        assert_eq!(mixer.next_sample(), 1.0);
+        mixer.channel_mut(1).unwrap().set_solo(true);
+        assert_eq!(mixer.next_sample(), 1.5);
+
+        assert!(Mixer::<1000>::new().is_empty());
+        assert_eq!(Mixer::<1000>::new().next_sample(), 0.0);
+    }
+
+    #[test]
+    fn test_equal_power_pan() {
+        let mut mixer = Mixer::<1000>::new()
+            .with_channel(constant(1.0).with_pan(-1.0))
+            .with_channel(constant(1.0));
+        let [left, right] = mixer.next_frame();
+        let center = std::f64::consts::FRAC_1_SQRT_2;
+        assert!((left - (1.0 + center)).abs() < 1e-12);
+        assert!((right - center).abs() < 1e-12);
+        assert_eq!(constant(1.0).with_pan(3.0).pan(), 1.0);
+    }
+
+    #[test]
+    fn test_routes_channels_to_buses() {
+        let mut mixer = Mixer::<1000>::new()
+            .with_channel(constant(1.0))
+            .with_channel(constant(0.5).with_route(Route::Bus(1)))
+            .with_channel(constant(0.25).with_route(Route::Bus(7)))
+            .with_bus(1.0)
+            .with_bus(2.0)
+            .with_master_gain(0.5);
+        assert_eq!(mixer.bus_count(), 2);
+        assert_eq!(mixer.channel(1).unwrap().route(), Route::Bus(1));
+
+        // Bused channels stay out of the main mix
+        assert_eq!(mixer.next_sample(), 0.5);
+
+        let center = std::f64::consts::FRAC_1_SQRT_2;
+        let mut outputs = [[9.0; 2]; 4];
+        mixer.next_outputs(&mut outputs);
+        assert!((outputs[0][0] - 0.5 * center).abs() < 1e-12);
+        assert_eq!(outputs[1], [0.0; 2]);
+        assert!((outputs[2][1] - center).abs() < 1e-12);
+        // No bus 7: silent
+        assert_eq!(outputs[3], [0.0; 2]);
+
+        mixer.set_bus_gain(1, 0.0);
+        mixer.channel_mut(0).unwrap().set_route(Route::Bus(0));
+        let mut frames = mixer.outputs::<6>();
+        let frame = frames.next_frame();
+        assert!((frame[2] - center).abs() < 1e-12);
+        assert_eq!([frame[0], frame[4], frame[5]], [0.0; 3]);
+        assert_eq!(frames.mixer().len(), 3);
+    }
+
+    #[test]
+    fn test_cue_bus_hears_channels_pre_fader() {
+        let mut mixer = Mixer::<1000>::new()
+            .with_bus(1.0)
+            .with_cue_bus(0.5)
+            .with_channel(constant(1.0))
+            .with_channel(constant(0.5).with_gain(0.0));
+        assert_eq!(mixer.cue_bus(), Some(1));
+
+        let cued = mixer.channel_mut(1).unwrap();
+        cued.set_mute(true);
+        cued.set_cue(true);
+        assert!(cued.is_cued());
+
+        let center = std::f64::consts::FRAC_1_SQRT_2;
+        let mut outputs = [[0.0; 2]; 3];
+        mixer.next_outputs(&mut outputs);
+        // Main carries only the first channel; the cue hears the muted,
+        // faded-out channel at the cue volume
+        assert!((outputs[0][0] - center).abs() < 1e-12);
+        assert_eq!(outputs[1], [0.0; 2]);
+        assert!((outputs[2][0] - 0.25 * center).abs() < 1e-12);
+
+        mixer.set_cue_gain(1.0);
+        mixer.set_cue_bus(Some(0));
+        mixer.next_outputs(&mut outputs);
+        assert!((outputs[1][0] - 0.5 * center).abs() < 1e-12);
+        assert_eq!(outputs[2], [0.0; 2]);
+
+        mixer.set_cue_bus(None);
+        mixer.next_outputs(&mut outputs);
+        assert_eq!(outputs[1], [0.0; 2]);
+    }
+
+    #[test]
+    fn test_mix_modes_bound_many_full_scale_channels() {
+        let loud = |mode| {
+            let mut mixer = Mixer::<1000>::new().with_mode(mode);
+            for _ in 0..64 {
+                mixer.add_channel(constant(1.0));
+                mixer.add_channel(constant(-1.0).with_gain(-1.0).with_pan(0.5));
+            }
+            mixer
+        };
+
+        // A plain sum runs far out of range
+        let mut mixer = loud(MixMode::Sum);
+        assert_eq!(mixer.mode(), MixMode::Sum);
+        assert_eq!(mixer.next_sample(), 128.0);
+
+        let soft_clip = MixMode::SoftClip { ceiling: 1.0 };
+        for mode in [soft_clip, MixMode::Normalize] {
+            let mut mixer = loud(mode);
+            let sample = mixer.next_sample();
+            assert!(sample > 0.9 && sample <= 1.0, "{:?}: {}", mode, sample);
+            let [left, right] = mixer.next_frame();
+            assert!(left.abs() <= 1.0 && right.abs() <= 1.0);
+            let mut outputs = [[0.0; 2]; 1];
+            mixer.next_outputs(&mut outputs);
+            assert!(outputs[0].iter().all(|sample| sample.abs() <= 1.0));
+        }
+
+        // Normalizing accounts for the master gain too
+        let mut mixer = loud(MixMode::Normalize).with_master_gain(4.0);
+        assert_eq!(mixer.next_sample(), 1.0);
+
+        // Quiet mixes pass through normalization untouched
+        let mut mixer = Mixer::<1000>::new()
+            .with_channel(constant(0.5).with_gain(0.5))
+            .with_channel(constant(0.5).with_gain(0.25));
+        mixer.set_mode(MixMode::Normalize);
+        assert_eq!(mixer.next_sample(), 0.375);
+    }
+
+    #[test]
+    fn test_insert_processes_dry_signal() {
+        let mut strip = constant(0.5).with_insert(|dry| dry.gain(3.0));
+        let mut mixer = Mixer::<1000>::new().with_channel(constant(0.0));
+        assert_eq!(strip.next_sample(), 1.5);
+        strip.clear_insert();
+        assert_eq!(strip.next_sample(), 0.5);
+        mixer.add_channel(strip);
+        assert_eq!(mixer.len(), 2);
+        assert_eq!(mixer.next_sample(), 0.5);
+    }
+}
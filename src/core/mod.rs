@@ -6,16 +6,86 @@
 //! - `AudioSignal` trait for sample-rate-aware signals
 //! - `AudioSignalExt` and `SignalExt` traits for convenient combinators
 //! - `Param` type for fixed or modulated parameters
+//! - `ParamHandle` for changing parameters from another thread, and snapshots
+//!   for capturing and morphing between sets of them
+//! - `ParamRegistry` for addressing parameters and nodes by name
+//! - `RngContext` for reproducing generative pieces from a single seed
+//! - `SmoothedParam` for click-free parameter changes
 //! - `ConstantSignal` for fixed values
+//! - `BufferPool` for reusing per-call scratch buffers, as `VoiceAllocator::process` does
+//! - A real-time safe command queue for control-thread to audio-thread changes
+//! - `ControlRate` for evaluating modulation sources at a reduced rate
+//! - `ModulationMonitor` for reporting parameter and modulation values to a UI
+//! - `FrameSignal` and channel routing for multi-channel signals
+//! - `Mixer` for summing any number of channels with gain, pan, mute and solo
+//! - `FrameProvider` for filling another audio engine's buffers
+//! - `Resample` for converting signals between sample rates
+//! - Double-buffered graph swapping for glitch-free patch changes
+//! - Graph validation for catching NaNs, clipping, DC offset, silence and
+//!   effects that are not unity gain
+//! - `DebugGuard` for catching NaN/Inf at the node that produced it
+//! - A real-time safe logging queue for reporting from the audio thread
+//! - Multi-threaded offline rendering (`parallel` feature)
 //! - Signal combinators for composing signals
 
 mod audio;
+mod buffer_pool;
 pub mod combinators;
+mod command;
+mod control_rate;
+mod frame;
+mod guard;
+mod mixer;
+mod monitor;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod param_handle;
+mod provider;
+mod registry;
+mod resample;
+mod rng;
+mod rt_log;
 mod signal;
+mod smoothed;
+mod snapshot;
+mod swap;
+mod validate;
 
 pub use audio::AudioSignal;
+pub use buffer_pool::{BufferPool, DEFAULT_BLOCK_SIZE};
 pub use combinators::{
-    Abs, Add, Clamp, Crossfade, Gain, Gate, Invert, Map, Max, Min, Mix2, Mix3, Mix4, Multiply,
-    Offset, SignalExt,
+    Abs, Add, Clamp, Crossfade, Gain, Gate, Invert, Map, Max, Min, Mix2, Mix3, Mix4, MixMode,
+    Multiply, Offset, SignalExt,
 };
+pub use command::{CommandReceiver, CommandSender, CommandTarget, Controlled, command_queue};
+pub use control_rate::{
+    ControlRate, DEFAULT_CONTROL_INTERVAL, control_interval, set_control_interval,
+};
+pub use frame::{
+    AudioFrameSignal, Broadcast, ChannelMap, Downmix, FrameSignal, FrameSignalExt, Remap,
+};
+pub use guard::DebugGuard;
+#[cfg(feature = "synth")]
+pub(crate) use guard::debug_assert_finite;
+pub use mixer::{ChannelStrip, InsertInput, Mixer, MixerOutputs, Route};
+pub use monitor::{ModulationMonitor, ModulationSnapshot, NodeReport, ParamReport, Watched};
+#[cfg(feature = "parallel")]
+pub use parallel::render_parallel;
+#[cfg(all(feature = "parallel", feature = "music"))]
+pub(crate) use parallel::render_parallel_with;
+pub use param_handle::ParamHandle;
+pub use provider::FrameProvider;
+pub use registry::{Named, ParamRegistry};
+pub use resample::{Resample, ResampleExt, ResampleMode};
+pub use rng::{RngContext, SeededRng};
+#[cfg(feature = "music")]
+pub(crate) use rng::{splitmix64, splitmix64_mix};
+pub use rt_log::{LogDrain, LogLevel, LogRecord, RtLogger, rt_log};
 pub use signal::{ConstantSignal, Param, Pitched, Signal, SignalIterator};
+pub use smoothed::{Slew, SmoothedParam};
+pub use snapshot::{Snapshot, SnapshotMorph};
+pub use swap::{GraphSwapper, SwappableGraph, graph_swap};
+pub use validate::{
+    TestInput, TestSignal, ValidationIssue, ValidationReport, ValidationThresholds, validate,
+    validate_effect, validate_effect_with, validate_with,
+};
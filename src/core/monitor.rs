@@ -0,0 +1,341 @@
+//! Parameter and modulation readouts for user interfaces.
+//!
+//! Meters, knob rings and modulation indicators need to know what a patch's
+//! parameters are doing right now, but the values live deep inside a signal
+//! graph on the audio thread. A [`ModulationMonitor`] hands out [`Watched`]
+//! wrappers that pass a parameter signal through unchanged while publishing
+//! its value to a shared atomic slot at control rate. The UI thread calls
+//! [`ModulationMonitor::snapshot`] to read every watched parameter, grouped
+//! by the node it belongs to.
+//!
+//! Parameters built as a knob plus modulation (see
+//! [`watch_modulated`](ModulationMonitor::watch_modulated)) also report the
+//! knob's position and how far the modulation moves the value from it, which
+//! is what a modulation ring displays.
+//!
+//! # Examples
+//!
+//! ```
+//! use earworm::core::{ModulationMonitor, ParamHandle};
+//! use earworm::{BiquadFilter, SawtoothOscillator, Signal, SignalExt, SineOscillator};
+//!
+//! const SAMPLE_RATE: u32 = 44100;
+//!
+//! let monitor = ModulationMonitor::new();
+//! let cutoff_knob = ParamHandle::new(1200.0);
+//! let lfo = SineOscillator::<SAMPLE_RATE>::new(0.5).gain(400.0);
+//! let cutoff = monitor.watch_modulated("filter", "cutoff", cutoff_knob.clone(), lfo);
+//!
+//! let osc = SawtoothOscillator::<SAMPLE_RATE>::new(110.0);
+//! let mut filter = BiquadFilter::lowpass(osc, cutoff, 0.707);
+//! for _ in 0..1000 {
+//!     filter.next_sample();
+//! }
+//!
+//! // On the UI thread
+//! let snapshot = monitor.snapshot();
+//! let cutoff = snapshot.param("filter", "cutoff").unwrap();
+//! assert_eq!(cutoff.base, Some(1200.0));
+//! assert!(cutoff.modulation.unwrap().abs() <= 400.0);
+//! ```
+
+use super::combinators::{Add, SignalExt};
+use super::control_rate::control_interval;
+use super::param_handle::ParamHandle;
+use super::signal::Signal;
+use crate::AudioSignal;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+struct Entry {
+    node: String,
+    param: String,
+    value: Arc<AtomicU64>,
+    base: Option<ParamHandle>,
+}
+
+/// A registry of watched parameters.
+///
+/// Clones share the same registry. Register parameters while building the
+/// graph (registration allocates and takes a lock), then take snapshots from
+/// any non-real-time thread.
+#[derive(Clone, Default)]
+pub struct ModulationMonitor {
+    entries: Arc<Mutex<Vec<Entry>>>,
+}
+
+impl ModulationMonitor {
+    /// Creates an empty monitor.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wraps a parameter signal so its value is reported in snapshots.
+    ///
+    /// The value is published every [`control_interval`] samples (as set
+    /// when this is called).
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - Name of the node the parameter belongs to
+    /// * `param` - Name of the parameter within the node
+    /// * `signal` - The parameter's signal; its output passes through unchanged
+    pub fn watch<S: Signal>(&self, node: &str, param: &str, signal: S) -> Watched<S> {
+        self.register(node, param, signal, None)
+    }
+
+    /// Builds a parameter as a knob plus modulation and reports both.
+    ///
+    /// The returned signal outputs `base + modulation`. Snapshots include the
+    /// knob's value as [`ParamReport::base`] and the difference between the
+    /// output and the knob as [`ParamReport::modulation`].
+    pub fn watch_modulated<S: Signal>(
+        &self,
+        node: &str,
+        param: &str,
+        base: ParamHandle,
+        modulation: S,
+    ) -> Watched<Add<ParamHandle, S>> {
+        let signal = base.clone().add(modulation);
+        self.register(node, param, signal, Some(base))
+    }
+
+    fn register<S: Signal>(
+        &self,
+        node: &str,
+        param: &str,
+        signal: S,
+        base: Option<ParamHandle>,
+    ) -> Watched<S> {
+        let initial = base.as_ref().map_or(0.0, ParamHandle::get);
+        let value = Arc::new(AtomicU64::new(initial.to_bits()));
+        self.entries.lock().unwrap().push(Entry {
+            node: node.to_string(),
+            param: param.to_string(),
+            value: value.clone(),
+            base,
+        });
+        let interval = control_interval();
+        Watched {
+            signal,
+            value,
+            interval,
+            remaining: 0,
+        }
+    }
+
+    /// Returns the latest reported values, grouped by node in the order the
+    /// nodes were first registered.
+    pub fn snapshot(&self) -> ModulationSnapshot {
+        let entries = self.entries.lock().unwrap();
+        let mut nodes: Vec<NodeReport> = Vec::new();
+        for entry in entries.iter() {
+            let value = f64::from_bits(entry.value.load(Ordering::Relaxed));
+            let base = entry.base.as_ref().map(ParamHandle::get);
+            let report = ParamReport {
+                name: entry.param.clone(),
+                value,
+                base,
+                modulation: base.map(|base| value - base),
+            };
+            match nodes.iter_mut().find(|node| node.name == entry.node) {
+                Some(node) => node.params.push(report),
+                None => nodes.push(NodeReport {
+                    name: entry.node.clone(),
+                    params: vec![report],
+                }),
+            }
+        }
+        ModulationSnapshot { nodes }
+    }
+}
+
+/// A parameter signal whose value is published to a [`ModulationMonitor`].
+///
+/// Created by [`ModulationMonitor::watch`] and
+/// [`ModulationMonitor::watch_modulated`]. Publishing is a single relaxed
+/// atomic store every control interval, so it is safe on the audio thread.
+pub struct Watched<S> {
+    signal: S,
+    value: Arc<AtomicU64>,
+    interval: usize,
+    remaining: usize,
+}
+
+impl<S> Watched<S> {
+    fn publish(&self, value: f64) {
+        self.value.store(value.to_bits(), Ordering::Relaxed);
+    }
+}
+
+impl<S: Signal> Signal for Watched<S> {
+    fn next_sample(&mut self) -> f64 {
+        let value = self.signal.next_sample();
+        if self.remaining == 0 {
+            self.publish(value);
+            self.remaining = self.interval;
+        }
+        self.remaining -= 1;
+        value
+    }
+
+    fn process(&mut self, buffer: &mut [f64]) {
+        self.signal.process(buffer);
+        if buffer.len() > self.remaining {
+            // Publish the most recent control point within the block
+            let steps = (buffer.len() - 1 - self.remaining) / self.interval;
+            self.publish(buffer[self.remaining + steps * self.interval]);
+            self.remaining += (steps + 1) * self.interval;
+        }
+        self.remaining -= buffer.len();
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.signal.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE> for Watched<S> {}
+
+/// The reported state of every watched parameter, from
+/// [`ModulationMonitor::snapshot`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModulationSnapshot {
+    /// Reports for each node, in registration order
+    pub nodes: Vec<NodeReport>,
+}
+
+impl ModulationSnapshot {
+    /// Returns the report for the node named `node`.
+    pub fn node(&self, node: &str) -> Option<&NodeReport> {
+        self.nodes.iter().find(|report| report.name == node)
+    }
+
+    /// Returns the report for parameter `param` of node `node`.
+    pub fn param(&self, node: &str, param: &str) -> Option<&ParamReport> {
+        self.node(node)?.param(param)
+    }
+}
+
+/// The watched parameters of one node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeReport {
+    /// Node name
+    pub name: String,
+    /// Reports for each watched parameter, in registration order
+    pub params: Vec<ParamReport>,
+}
+
+impl NodeReport {
+    /// Returns the report for the parameter named `param`.
+    pub fn param(&self, param: &str) -> Option<&ParamReport> {
+        self.params.iter().find(|report| report.name == param)
+    }
+}
+
+/// The latest reported state of one parameter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamReport {
+    /// Parameter name
+    pub name: String,
+    /// Current value, including modulation
+    pub value: f64,
+    /// Knob position, for parameters registered with
+    /// [`watch_modulated`](ModulationMonitor::watch_modulated)
+    pub base: Option<f64>,
+    /// `value - base`, for parameters with a base
+    pub modulation: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ConstantSignal;
+
+    /// Outputs 0, 1, 2, ...
+    struct Counter(f64);
+
+    impl Signal for Counter {
+        fn next_sample(&mut self) -> f64 {
+            let value = self.0;
+            self.0 += 1.0;
+            value
+        }
+    }
+
+    #[test]
+    fn test_publishes_at_control_rate() {
+        let monitor = ModulationMonitor::new();
+        let mut watched = monitor.watch("osc", "pitch", Counter(0.0));
+        let interval = watched.interval;
+
+        watched.next_sample();
+        assert_eq!(monitor.snapshot().param("osc", "pitch").unwrap().value, 0.0);
+        for _ in 1..interval {
+            watched.next_sample();
+        }
+        assert_eq!(monitor.snapshot().param("osc", "pitch").unwrap().value, 0.0);
+        watched.next_sample();
+        let report = monitor.snapshot();
+        let pitch = report.param("osc", "pitch").unwrap();
+        assert_eq!(pitch.value, interval as f64);
+        assert_eq!(pitch.base, None);
+        assert_eq!(pitch.modulation, None);
+    }
+
+    #[test]
+    fn test_block_processing_matches_per_sample() {
+        let monitor = ModulationMonitor::new();
+        let mut per_sample = monitor.watch("a", "x", Counter(0.0));
+        let mut blocks = monitor.watch("b", "x", Counter(0.0));
+        let interval = per_sample.interval;
+
+        let mut buffer = vec![0.0; 50];
+        for len in [1, 7, 50, 13, 32, 3] {
+            for _ in 0..len {
+                per_sample.next_sample();
+            }
+            blocks.process(&mut buffer[..len]);
+            let snapshot = monitor.snapshot();
+            assert_eq!(
+                snapshot.param("a", "x").unwrap().value,
+                snapshot.param("b", "x").unwrap().value,
+                "interval {}",
+                interval
+            );
+        }
+    }
+
+    #[test]
+    fn test_modulated_reports_base_and_amount() {
+        let monitor = ModulationMonitor::new();
+        let knob = ParamHandle::new(1000.0);
+        let mut cutoff = monitor.watch_modulated(
+            "filter",
+            "cutoff",
+            knob.clone(),
+            ConstantSignal::<44100>(-250.0),
+        );
+        let mut resonance = monitor.watch("filter", "resonance", ConstantSignal::<44100>(0.7));
+        let mut level = monitor.watch("amp", "level", ConstantSignal::<44100>(0.5));
+
+        // Before the first sample the value is the knob position
+        let report = monitor.snapshot();
+        assert_eq!(report.param("filter", "cutoff").unwrap().value, 1000.0);
+
+        assert_eq!(cutoff.next_sample(), 750.0);
+        resonance.next_sample();
+        level.next_sample();
+
+        let report = monitor.snapshot();
+        assert_eq!(report.nodes.len(), 2);
+        assert_eq!(report.nodes[0].name, "filter");
+        assert_eq!(report.nodes[0].params.len(), 2);
+        let cutoff = report.param("filter", "cutoff").unwrap();
+        assert_eq!(cutoff.value, 750.0);
+        assert_eq!(cutoff.base, Some(1000.0));
+        assert_eq!(cutoff.modulation, Some(-250.0));
+        assert_eq!(report.param("amp", "level").unwrap().value, 0.5);
+        assert!(report.param("amp", "cutoff").is_none());
+    }
+}
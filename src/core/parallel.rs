@@ -0,0 +1,137 @@
+//! Multi-threaded offline rendering.
+//!
+//! For non-realtime work such as bouncing an arrangement to disk, independent
+//! signals (voices, tracks) can be rendered on separate threads and summed
+//! afterwards. [`render_parallel`] splits the signals across rayon's thread
+//! pool, renders each group into its own buffer, and mixes the results.
+//!
+//! Handing work to the pool costs far more than rendering a small audio
+//! block, so these functions are meant for long buffers (seconds of audio),
+//! not for the realtime audio callback.
+
+use rayon::prelude::*;
+
+use crate::Signal;
+
+/// Renders several signals in parallel and sums them into `buffer`.
+///
+/// `buffer` is overwritten with the sum of `buffer.len()` samples from every
+/// signal. Each signal advances exactly as if it had been processed on the
+/// calling thread, so the result matches a sequential render.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::core::render_parallel;
+/// use earworm::SineOscillator;
+///
+/// let mut oscillators: Vec<_> = [220.0, 277.18, 329.63]
+///     .iter()
+///     .map(|&freq| SineOscillator::<44100>::new(freq))
+///     .collect();
+///
+/// let mut bounce = vec![0.0; 44100];
+/// render_parallel(&mut oscillators, &mut bounce);
+/// ```
+pub fn render_parallel<S: Signal + Send>(signals: &mut [S], buffer: &mut [f64]) {
+    render_parallel_with(signals, buffer, |signal, block| signal.process(block));
+}
+
+/// Renders `items` in parallel with `render` and sums the results into `buffer`.
+///
+/// `render` must overwrite the block it is given. Items are split into one
+/// contiguous group per worker thread.
+pub(crate) fn render_parallel_with<T, F>(items: &mut [T], buffer: &mut [f64], render: F)
+where
+    T: Send,
+    F: Fn(&mut T, &mut [f64]) + Sync,
+{
+    buffer.fill(0.0);
+    if items.is_empty() || buffer.is_empty() {
+        return;
+    }
+
+    // One group per pool thread, mixed in order, so the result does not
+    // depend on how the pool schedules the work
+    let threads = rayon::current_num_threads().min(items.len());
+    let group_size = items.len().div_ceil(threads);
+    let len = buffer.len();
+
+    let mixes: Vec<Vec<f64>> = items
+        .par_chunks_mut(group_size)
+        .map(|group| {
+            let mut mix = vec![0.0; len];
+            let mut block = vec![0.0; len];
+            for item in group {
+                render(item, &mut block);
+                for (out, &sample) in mix.iter_mut().zip(block.iter()) {
+                    *out += sample;
+                }
+            }
+            mix
+        })
+        .collect();
+
+    for mix in mixes {
+        for (out, sample) in buffer.iter_mut().zip(mix) {
+            *out += sample;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConstantSignal;
+
+    #[test]
+    fn test_sums_all_signals() {
+        let mut signals: Vec<_> = (1..=10)
+            .map(|i| ConstantSignal::<44100>(i as f64))
+            .collect();
+        let mut buffer = vec![1.0; 64];
+        render_parallel(&mut signals, &mut buffer);
+        assert!(buffer.iter().all(|&s| s == 55.0));
+    }
+
+    #[test]
+    fn test_empty_inputs() {
+        let mut signals: Vec<ConstantSignal<44100>> = Vec::new();
+        let mut buffer = vec![1.0; 8];
+        render_parallel(&mut signals, &mut buffer);
+        assert!(buffer.iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn test_matches_sequential_render() {
+        use crate::SignalExt;
+
+        let make = || -> Vec<_> {
+            (0..7)
+                .map(|i| {
+                    let mut n = 0.0;
+                    ConstantSignal::<44100>(0.0).map(move |_| {
+                        n += 1.0;
+                        (n * (i + 1) as f64 * 0.01).sin()
+                    })
+                })
+                .collect()
+        };
+
+        let mut sequential = make();
+        let mut expected = vec![0.0; 1000];
+        for signal in sequential.iter_mut() {
+            for (out, sample) in expected.iter_mut().zip(signal.iter()) {
+                *out += sample;
+            }
+        }
+
+        let mut parallel = make();
+        let mut buffer = vec![0.0; 1000];
+        render_parallel(&mut parallel, &mut buffer);
+
+        for (a, b) in buffer.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Shared, lock-free parameter values.
+
+use super::registry::ParamRegistry;
+use super::signal::Signal;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A parameter value that can be changed from another thread.
+///
+/// Clones share the same value: keep one clone on the control thread (UI,
+/// MIDI) and pass another into a signal graph as a [`Param`](crate::Param).
+/// Reads and writes are single atomic operations, so neither side ever blocks.
+///
+/// Changes are not smoothed; a jump in value takes effect on the next sample.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::core::ParamHandle;
+/// use earworm::{Param, SineOscillator, Tremolo};
+///
+/// let depth = ParamHandle::new(0.2);
+/// let audio = SineOscillator::<44100>::new(440.0);
+/// let mut tremolo = Tremolo::with_rate(audio, 5.0, depth.clone());
+///
+/// // Later, from the control thread
+/// depth.set(0.8);
+/// assert_eq!(depth.get(), 0.8);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParamHandle {
+    bits: Arc<AtomicU64>,
+}
+
+impl ParamHandle {
+    /// Creates a handle holding `value`.
+    pub fn new(value: f64) -> Self {
+        Self {
+            bits: Arc::new(AtomicU64::new(value.to_bits())),
+        }
+    }
+
+    /// Returns the current value.
+    pub fn get(&self) -> f64 {
+        f64::from_bits(self.bits.load(Ordering::Relaxed))
+    }
+
+    /// Sets the value seen by every clone of this handle.
+    pub fn set(&self, value: f64) {
+        self.bits.store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Returns `true` if both handles share the same value.
+    pub fn same_as(&self, other: &ParamHandle) -> bool {
+        Arc::ptr_eq(&self.bits, &other.bits)
+    }
+
+    /// Registers this handle in `registry` under `id` (builder style).
+    ///
+    /// See [`ParamRegistry`] for the ID format.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not a valid path or is already registered.
+    pub fn named(self, registry: &ParamRegistry, id: &str) -> Self {
+        registry.register(id, &self);
+        self
+    }
+}
+
+impl Signal for ParamHandle {
+    fn next_sample(&mut self) -> f64 {
+        self.get()
+    }
+
+    fn process(&mut self, buffer: &mut [f64]) {
+        buffer.fill(self.get());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Param;
+
+    #[test]
+    fn test_clones_share_value() {
+        let handle = ParamHandle::new(1.0);
+        let mut param: Param = handle.clone().into();
+        assert_eq!(param.value(), 1.0);
+
+        std::thread::spawn(move || handle.set(-3.5)).join().unwrap();
+        assert_eq!(param.value(), -3.5);
+    }
+
+    #[test]
+    fn test_param_from_reference() {
+        let handle = ParamHandle::new(0.25);
+        let mut param = Param::from(&handle);
+        handle.set(0.75);
+        assert_eq!(param.value(), 0.75);
+    }
+}
@@ -0,0 +1,261 @@
+//! Pulling audio from earworm into another engine's callback.
+//!
+//! Game engines and audio libraries (bevy_audio, kira, SDL, or a hand-written
+//! cpal callback) each have their own audio thread and ask their sources to
+//! fill an interleaved `f32` buffer. A [`FrameProvider`] answers that request
+//! for any earworm signal: it pulls frames, spreads or maps them onto the
+//! engine's channel count, and applies queued commands before each buffer.
+//!
+//! # Examples
+//!
+//! ```
+//! use earworm::{FrameProvider, SineOscillator};
+//!
+//! let mut provider = FrameProvider::new(SineOscillator::<48000>::new(440.0));
+//!
+//! // Inside the engine's audio callback, with whatever channel count it uses
+//! let mut buffer = [0.0f32; 512];
+//! provider.fill(&mut buffer, 2);
+//! assert_eq!(buffer[2], buffer[3]); // mono is played on both channels
+//! ```
+
+use crate::core::{Broadcast, CommandReceiver, CommandTarget, FrameSignal, Signal};
+
+/// Fills interleaved `f32` buffers from a signal.
+///
+/// A mono signal ([`new`](Self::new)) is written to every channel. A
+/// multi-channel signal ([`from_frames`](Self::from_frames)) is written one
+/// frame channel per output channel: if the output has more channels the
+/// extra ones are silent, and if it has fewer the extra frame channels are
+/// dropped.
+///
+/// The provider does no resampling: run it at the engine's sample rate.
+pub struct FrameProvider<F, const CHANNELS: usize, C = ()> {
+    frames: F,
+    commands: Option<PendingCommands<F, C>>,
+}
+
+/// A command queue and the function that applies it to the provider's signal.
+struct PendingCommands<F, C> {
+    receiver: CommandReceiver<C>,
+    apply: fn(&CommandReceiver<C>, &mut F) -> usize,
+}
+
+impl<S: Signal> FrameProvider<Broadcast<S, 1>, 1> {
+    /// Creates a provider that plays a mono signal on every channel.
+    ///
+    /// Commands sent to the provider reach the signal itself.
+    pub fn new(signal: S) -> Self {
+        Self::from_frames(Broadcast::new(signal))
+    }
+}
+
+impl<F: FrameSignal<CHANNELS>, const CHANNELS: usize> FrameProvider<F, CHANNELS> {
+    /// Creates a provider for a multi-channel signal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{ConstantSignal, FrameProvider};
+    ///
+    /// let stereo = [ConstantSignal::<44100>(0.5), ConstantSignal::<44100>(-0.5)];
+    /// let mut provider = FrameProvider::from_frames(stereo);
+    ///
+    /// // A quad engine gets silence on its rear channels
+    /// let mut buffer = [1.0f32; 4];
+    /// provider.fill(&mut buffer, 4);
+    /// assert_eq!(buffer, [0.5, -0.5, 0.0, 0.0]);
+    /// ```
+    pub fn from_frames(frames: F) -> Self {
+        Self {
+            frames,
+            commands: None,
+        }
+    }
+
+    /// Applies commands from `receiver` to the signal before every buffer.
+    ///
+    /// Commands are applied at the start of each [`fill`](FrameProvider::fill)
+    /// call, so a change lands at the next buffer boundary. For changes that
+    /// must land on an exact sample, control the signal itself with
+    /// [`CommandReceiver::control`] instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::core::{CommandTarget, command_queue};
+    /// use earworm::{FrameProvider, Signal};
+    ///
+    /// struct Level(f64);
+    ///
+    /// impl CommandTarget<f64> for Level {
+    ///     fn apply(&mut self, level: f64) {
+    ///         self.0 = level;
+    ///     }
+    /// }
+    ///
+    /// impl Signal for Level {
+    ///     fn next_sample(&mut self) -> f64 {
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// let (sender, receiver) = command_queue(16);
+    /// let mut provider = FrameProvider::new(Level(0.0)).with_commands(receiver);
+    ///
+    /// sender.send(0.25).ok();
+    /// let mut buffer = [0.0f32; 2];
+    /// provider.fill(&mut buffer, 1);
+    /// assert_eq!(buffer, [0.25, 0.25]);
+    /// ```
+    pub fn with_commands<C>(self, receiver: CommandReceiver<C>) -> FrameProvider<F, CHANNELS, C>
+    where
+        F: CommandTarget<C>,
+    {
+        FrameProvider {
+            frames: self.frames,
+            commands: Some(PendingCommands {
+                receiver,
+                apply: CommandReceiver::apply_pending::<F>,
+            }),
+        }
+    }
+}
+
+impl<F: FrameSignal<CHANNELS>, const CHANNELS: usize, C> FrameProvider<F, CHANNELS, C> {
+    /// Fills an interleaved buffer with `channels` samples per frame.
+    ///
+    /// Pending commands are applied first. Trailing samples that do not make
+    /// up a whole frame are set to silence.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `channels` is 0.
+    pub fn fill(&mut self, buffer: &mut [f32], channels: usize) {
+        assert!(channels > 0, "channel count must be greater than 0");
+        self.apply_pending();
+
+        let mut chunks = buffer.chunks_exact_mut(channels);
+        for out in &mut chunks {
+            let frame = self.frames.next_frame();
+            if CHANNELS == 1 {
+                out.fill(frame[0] as f32);
+            } else {
+                for (sample, value) in out
+                    .iter_mut()
+                    .zip(frame.iter().chain(std::iter::repeat(&0.0)))
+                {
+                    *sample = *value as f32;
+                }
+            }
+        }
+        chunks.into_remainder().fill(0.0);
+    }
+
+    /// Applies every pending command to the signal, returning how many were
+    /// applied.
+    ///
+    /// [`fill`](Self::fill) does this itself; call it directly to apply
+    /// commands while the engine is not pulling audio.
+    pub fn apply_pending(&mut self) -> usize {
+        match &self.commands {
+            Some(commands) => (commands.apply)(&commands.receiver, &mut self.frames),
+            None => 0,
+        }
+    }
+
+    /// Prepares the signal for buffers of up to `max_frames` frames.
+    pub fn prepare(&mut self, max_frames: usize, sample_rate: u32) {
+        self.frames.prepare(max_frames, sample_rate);
+    }
+
+    /// Returns a reference to the signal.
+    pub fn inner(&self) -> &F {
+        &self.frames
+    }
+
+    /// Returns a mutable reference to the signal.
+    pub fn inner_mut(&mut self) -> &mut F {
+        &mut self.frames
+    }
+
+    /// Consumes the provider, returning the signal.
+    pub fn into_inner(self) -> F {
+        self.frames
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{ConstantSignal, command_queue};
+
+    struct Counter(f64);
+
+    impl Signal for Counter {
+        fn next_sample(&mut self) -> f64 {
+            self.0 += 1.0;
+            self.0
+        }
+    }
+
+    impl CommandTarget<f64> for Counter {
+        fn apply(&mut self, value: f64) {
+            self.0 = value;
+        }
+    }
+
+    #[test]
+    fn test_mono_fills_every_channel() {
+        let mut provider = FrameProvider::new(Counter(0.0));
+        let mut buffer = [0.0f32; 6];
+        provider.fill(&mut buffer, 3);
+        assert_eq!(buffer, [1.0, 1.0, 1.0, 2.0, 2.0, 2.0]);
+    }
+
+    #[test]
+    fn test_frames_map_to_channels() {
+        let quad = [0.1, 0.2, 0.3, 0.4].map(ConstantSignal::<44100>);
+        let mut provider = FrameProvider::from_frames(quad);
+
+        let mut stereo = [0.0f32; 4];
+        provider.fill(&mut stereo, 2);
+        assert_eq!(stereo, [0.1, 0.2, 0.1, 0.2]);
+
+        let mut six = [1.0f32; 6];
+        provider.fill(&mut six, 6);
+        assert_eq!(six, [0.1, 0.2, 0.3, 0.4, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_partial_frame_is_silenced() {
+        let mut provider = FrameProvider::new(ConstantSignal::<44100>(0.5));
+        let mut buffer = [1.0f32; 5];
+        provider.fill(&mut buffer, 2);
+        assert_eq!(buffer, [0.5, 0.5, 0.5, 0.5, 0.0]);
+    }
+
+    #[test]
+    fn test_commands_applied_per_buffer() {
+        let (sender, receiver) = command_queue(4);
+        let mut provider = FrameProvider::new(Counter(0.0)).with_commands(receiver);
+
+        sender.send(10.0).unwrap();
+        let mut buffer = [0.0f32; 2];
+        provider.fill(&mut buffer, 1);
+        assert_eq!(buffer, [11.0, 12.0]);
+
+        sender.send(0.0).unwrap();
+        sender.send(5.0).unwrap();
+        assert_eq!(provider.apply_pending(), 2);
+        assert_eq!(provider.apply_pending(), 0);
+        provider.fill(&mut buffer, 2);
+        assert_eq!(buffer, [6.0, 6.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "channel count must be greater than 0")]
+    fn test_zero_channels() {
+        FrameProvider::new(ConstantSignal::<44100>(0.0)).fill(&mut [0.0; 4], 0);
+    }
+}
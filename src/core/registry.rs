@@ -0,0 +1,306 @@
+//! Named nodes and addressable parameters.
+//!
+//! Automation lanes, OSC and MIDI mappings and parameter locks all need to
+//! find a parameter by name rather than by holding a handle. A
+//! [`ParamRegistry`] maps string IDs to [`ParamHandle`]s, filled in while
+//! the graph is built:
+//!
+//! - [`ParamHandle::named`] registers a parameter under an ID such as
+//!   `"filter.cutoff"`. Everything before the last `.` is the node the
+//!   parameter belongs to, so IDs can nest (`"voice1.filter.cutoff"`).
+//! - [`SignalExt::named`](crate::SignalExt::named) registers a node, wrapping
+//!   it in a [`Named`] signal whose latest output can be read back with
+//!   [`ParamRegistry::output`].
+//!
+//! Registration takes a lock and allocates, so do it before playback starts.
+//! Looking up a handle also locks, so mapping code should resolve IDs once
+//! and keep the handles; reading and writing a resolved handle is lock-free.
+//!
+//! # Examples
+//!
+//! ```
+//! use earworm::core::{ParamHandle, ParamRegistry};
+//! use earworm::{BiquadFilter, SawtoothOscillator, Signal, SignalExt};
+//!
+//! const SAMPLE_RATE: u32 = 44100;
+//!
+//! let registry = ParamRegistry::new();
+//! let cutoff = ParamHandle::new(800.0).named(&registry, "filter.cutoff");
+//! let q = ParamHandle::new(0.707).named(&registry, "filter.q");
+//!
+//! let osc = SawtoothOscillator::<SAMPLE_RATE>::new(110.0);
+//! let mut filter = BiquadFilter::lowpass(osc, cutoff, q).named(&registry, "filter");
+//!
+//! // A controller mapping only knows the address
+//! assert!(registry.set("filter.cutoff", 2000.0));
+//! assert_eq!(registry.params_of("filter").len(), 2);
+//!
+//! let sample = filter.next_sample();
+//! assert_eq!(registry.output("filter"), Some(sample));
+//! ```
+
+use super::param_handle::ParamHandle;
+use super::signal::Signal;
+use crate::AudioSignal;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+struct Entries {
+    params: BTreeMap<String, ParamHandle>,
+    // Latest output of each named node
+    nodes: BTreeMap<String, ParamHandle>,
+}
+
+/// A shared table of named parameters and nodes.
+///
+/// Clones share the same table. IDs are `.`-separated paths with no empty
+/// segments; lookups return results sorted by ID.
+#[derive(Clone, Default)]
+pub struct ParamRegistry {
+    entries: Arc<Mutex<Entries>>,
+}
+
+impl ParamRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `handle` under `id`.
+    ///
+    /// Usually called through [`ParamHandle::named`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not a valid path or is already registered.
+    pub fn register(&self, id: &str, handle: &ParamHandle) {
+        assert!(valid_id(id), "invalid parameter ID {:?}", id);
+        let mut entries = self.entries.lock().unwrap();
+        assert!(
+            !entries.params.contains_key(id),
+            "parameter {:?} is already registered",
+            id
+        );
+        entries.params.insert(id.to_string(), handle.clone());
+    }
+
+    /// Returns the handle registered under `id`.
+    pub fn param(&self, id: &str) -> Option<ParamHandle> {
+        self.entries.lock().unwrap().params.get(id).cloned()
+    }
+
+    /// Returns `true` if a parameter is registered under `id`.
+    pub fn contains(&self, id: &str) -> bool {
+        self.entries.lock().unwrap().params.contains_key(id)
+    }
+
+    /// Returns the current value of the parameter `id`.
+    pub fn get(&self, id: &str) -> Option<f64> {
+        self.param(id).map(|handle| handle.get())
+    }
+
+    /// Sets the parameter `id`, returning `false` if no such parameter exists.
+    pub fn set(&self, id: &str, value: f64) -> bool {
+        match self.entries.lock().unwrap().params.get(id) {
+            Some(handle) => {
+                handle.set(value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns every registered parameter ID.
+    pub fn ids(&self) -> Vec<String> {
+        self.entries
+            .lock()
+            .unwrap()
+            .params
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the parameters directly under `node`, keyed by the rest of
+    /// their ID (`"cutoff"` for `"filter.cutoff"`).
+    pub fn params_of(&self, node: &str) -> Vec<(String, ParamHandle)> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .params
+            .iter()
+            .filter_map(|(id, handle)| match id.rsplit_once('.') {
+                Some((parent, name)) if parent == node => Some((name.to_string(), handle.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns every node name: the nodes registered with
+    /// [`SignalExt::named`](crate::SignalExt::named) and the node part of
+    /// every parameter ID.
+    pub fn nodes(&self) -> Vec<String> {
+        let entries = self.entries.lock().unwrap();
+        let mut nodes: Vec<String> = entries.nodes.keys().cloned().collect();
+        for id in entries.params.keys() {
+            if let Some((node, _)) = id.rsplit_once('.') {
+                nodes.push(node.to_string());
+            }
+        }
+        nodes.sort();
+        nodes.dedup();
+        nodes
+    }
+
+    /// Returns the latest output of the named node `node`.
+    ///
+    /// Returns `None` for nodes that only appear as a parameter prefix.
+    pub fn output(&self, node: &str) -> Option<f64> {
+        self.entries
+            .lock()
+            .unwrap()
+            .nodes
+            .get(node)
+            .map(ParamHandle::get)
+    }
+
+    fn register_node(&self, name: &str) -> ParamHandle {
+        assert!(valid_id(name), "invalid node name {:?}", name);
+        let mut entries = self.entries.lock().unwrap();
+        assert!(
+            !entries.nodes.contains_key(name),
+            "node {:?} is already registered",
+            name
+        );
+        let output = ParamHandle::new(0.0);
+        entries.nodes.insert(name.to_string(), output.clone());
+        output
+    }
+}
+
+fn valid_id(id: &str) -> bool {
+    id.split('.').all(|segment| !segment.is_empty())
+}
+
+/// A node registered in a [`ParamRegistry`].
+///
+/// Created by [`SignalExt::named`](crate::SignalExt::named). Passes the wrapped
+/// signal through unchanged and records each output sample (a single relaxed
+/// atomic store) so it can be read with [`ParamRegistry::output`].
+pub struct Named<S: Signal> {
+    source: S,
+    output: ParamHandle,
+}
+
+impl<S: Signal> Named<S> {
+    /// Registers `source` in `registry` as `name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a valid path or is already a registered node.
+    pub fn new(source: S, registry: &ParamRegistry, name: &str) -> Self {
+        Self {
+            source,
+            output: registry.register_node(name),
+        }
+    }
+
+    /// Returns the wrapped signal.
+    pub fn inner(&self) -> &S {
+        &self.source
+    }
+
+    /// Returns the wrapped signal mutably.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+}
+
+impl<S: Signal> Signal for Named<S> {
+    fn next_sample(&mut self) -> f64 {
+        let sample = self.source.next_sample();
+        self.output.set(sample);
+        sample
+    }
+
+    fn process(&mut self, buffer: &mut [f64]) {
+        self.source.process(buffer);
+        if let Some(&last) = buffer.last() {
+            self.output.set(last);
+        }
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE> for Named<S> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstantSignal, SignalExt};
+
+    #[test]
+    fn test_lookup_and_set() {
+        let registry = ParamRegistry::new();
+        let cutoff = ParamHandle::new(500.0).named(&registry, "filter.cutoff");
+        ParamHandle::new(1.0).named(&registry, "filter.q");
+        ParamHandle::new(0.3).named(&registry, "voice1.env.attack");
+        ParamHandle::new(120.0).named(&registry, "tempo");
+
+        assert!(registry.param("filter.cutoff").unwrap().same_as(&cutoff));
+        assert!(registry.set("filter.cutoff", 900.0));
+        assert_eq!(cutoff.get(), 900.0);
+        assert_eq!(registry.get("tempo"), Some(120.0));
+        assert!(!registry.set("filter.drive", 1.0));
+        assert_eq!(registry.get("filter"), None);
+
+        let names: Vec<String> = registry
+            .params_of("filter")
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, ["cutoff", "q"]);
+        assert_eq!(registry.params_of("voice1").len(), 0);
+        assert_eq!(registry.params_of("voice1.env").len(), 1);
+        assert_eq!(registry.nodes(), ["filter", "voice1.env"]);
+        assert_eq!(registry.ids().len(), 4);
+    }
+
+    #[test]
+    fn test_named_node_reports_output() {
+        let registry = ParamRegistry::new();
+        let mut node = ConstantSignal::<44100>(0.25)
+            .gain(2.0)
+            .named(&registry, "amp");
+        assert_eq!(registry.output("amp"), Some(0.0));
+        node.next_sample();
+        assert_eq!(registry.output("amp"), Some(0.5));
+        assert_eq!(registry.nodes(), ["amp"]);
+        assert_eq!(registry.output("filter"), None);
+
+        let mut buffer = [0.0; 8];
+        node.process(&mut buffer);
+        assert_eq!(registry.output("amp"), Some(0.5));
+    }
+
+    #[test]
+    #[should_panic(expected = "already registered")]
+    fn test_duplicate_id_panics() {
+        let registry = ParamRegistry::new();
+        ParamHandle::new(0.0).named(&registry, "lfo.rate");
+        ParamHandle::new(0.0).named(&registry, "lfo.rate");
+    }
+
+    #[test]
+    fn test_invalid_ids_rejected() {
+        for id in ["", ".cutoff", "filter.", "filter..cutoff"] {
+            let result = std::panic::catch_unwind(|| {
+                ParamHandle::new(0.0).named(&ParamRegistry::new(), id);
+            });
+            assert!(result.is_err(), "{:?} accepted", id);
+        }
+    }
+}
@@ -0,0 +1,279 @@
+//! Sample-rate conversion between audio signals.
+//!
+//! Sample rates are part of the type of an [`AudioSignal`], so a 44.1 kHz
+//! signal cannot be mixed into a 48 kHz graph by accident. When it is
+//! intentional, [`Resample`] adapts a signal from one rate to another, either
+//! cheaply by holding each input sample or with linear interpolation. When
+//! downsampling, the source is low-pass filtered first so content above the
+//! new Nyquist frequency is attenuated rather than folded back as aliases.
+
+use crate::{AudioSignal, ConstantSignal, Signal};
+
+/// How [`Resample`] computes output samples between input samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResampleMode {
+    /// Hold the most recent input sample (a zero-order hold). Cheapest, but
+    /// images when upsampling.
+    Hold,
+    /// Linearly interpolate between neighbouring input samples.
+    #[default]
+    Linear,
+}
+
+/// Adapts an `AudioSignal<FROM>` for use in a graph running at `TO` Hz.
+///
+/// Each output sample advances the source by `FROM / TO` input samples. In
+/// [`ResampleMode::Linear`] the output lags the source by one input sample.
+///
+/// When `FROM > TO`, the source first passes through an 8th-order Butterworth
+/// low-pass at 45% of `TO`, which adds a few input samples of delay. Content
+/// just above the new Nyquist frequency is only partly attenuated, so a tone
+/// there still leaks through at a reduced level.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::core::{ResampleExt, ResampleMode};
+/// use earworm::{AudioSignal, Signal, SineOscillator};
+///
+/// let osc = SineOscillator::<44100>::new(440.0);
+/// let mut at_48k = osc.resample::<48000>(ResampleMode::Linear);
+/// assert_eq!(at_48k.sample_rate(), 48000.0);
+/// let sample = at_48k.next_sample();
+/// ```
+pub struct Resample<const FROM: u32, const TO: u32, S> {
+    source: S,
+    mode: ResampleMode,
+    position: f64,
+    previous: f64,
+    next: f64,
+    started: bool,
+    anti_alias: Option<[Lowpass; 4]>,
+}
+
+impl<const FROM: u32, const TO: u32, S: AudioSignal<FROM>> Resample<FROM, TO, S> {
+    /// Creates a resampler from `FROM` Hz to `TO` Hz.
+    pub fn new(source: S, mode: ResampleMode) -> Self {
+        Self {
+            source,
+            mode,
+            position: 0.0,
+            previous: 0.0,
+            next: 0.0,
+            started: false,
+            anti_alias: (FROM > TO).then(|| {
+                let cutoff = 0.45 * TO as f64;
+                BUTTERWORTH_Q.map(|q| Lowpass::new(cutoff, q, FROM))
+            }),
+        }
+    }
+
+    /// Returns the conversion mode.
+    pub fn mode(&self) -> ResampleMode {
+        self.mode
+    }
+
+    fn next_input(&mut self) -> f64 {
+        let sample = self.source.next_sample();
+        match &mut self.anti_alias {
+            Some(stages) => stages
+                .iter_mut()
+                .fold(sample, |sample, stage| stage.process(sample)),
+            None => sample,
+        }
+    }
+}
+
+impl<const FROM: u32, const TO: u32, S: AudioSignal<FROM>> Signal for Resample<FROM, TO, S> {
+    fn next_sample(&mut self) -> f64 {
+        if !self.started {
+            self.previous = self.next_input();
+            self.next = self.next_input();
+            self.started = true;
+        }
+
+        while self.position >= 1.0 {
+            self.previous = self.next;
+            self.next = self.next_input();
+            self.position -= 1.0;
+        }
+
+        let sample = match self.mode {
+            ResampleMode::Hold => self.previous,
+            ResampleMode::Linear => self.previous + self.position * (self.next - self.previous),
+        };
+        self.position += FROM as f64 / TO as f64;
+        sample
+    }
+
+    fn prepare(&mut self, max_block_size: usize, _sample_rate: u32) {
+        // The source runs at FROM and may need a few more samples per block
+        let source_block = (max_block_size as f64 * FROM as f64 / TO as f64).ceil() as usize + 2;
+        self.source.prepare(source_block, FROM);
+    }
+}
+
+impl<const FROM: u32, const TO: u32, S: AudioSignal<FROM>> AudioSignal<TO>
+    for Resample<FROM, TO, S>
+{
+}
+
+/// Stage Qs of an 8th-order Butterworth response.
+const BUTTERWORTH_Q: [f64; 4] = [0.5098, 0.6013, 0.9000, 2.5629];
+
+/// One low-pass biquad stage of the anti-aliasing filter.
+struct Lowpass {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+    z1: f64,
+    z2: f64,
+}
+
+impl Lowpass {
+    fn new(cutoff: f64, q: f64, sample_rate: u32) -> Self {
+        let omega = std::f64::consts::TAU * cutoff / sample_rate as f64;
+        let alpha = omega.sin() / (2.0 * q);
+        let cos = omega.cos();
+        let a0 = 1.0 + alpha;
+        Self {
+            b0: (1.0 - cos) / 2.0 / a0,
+            b1: (1.0 - cos) / a0,
+            b2: (1.0 - cos) / 2.0 / a0,
+            a1: -2.0 * cos / a0,
+            a2: (1.0 - alpha) / a0,
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    fn process(&mut self, input: f64) -> f64 {
+        // Transposed direct form II
+        let output = self.b0 * input + self.z1;
+        self.z1 = self.b1 * input - self.a1 * output + self.z2;
+        self.z2 = self.b2 * input - self.a2 * output;
+        output
+    }
+}
+
+/// Extension trait adding [`resample`](ResampleExt::resample) to every audio signal.
+pub trait ResampleExt<const FROM: u32>: AudioSignal<FROM> + Sized {
+    /// Converts this signal to run at `TO` Hz.
+    fn resample<const TO: u32>(self, mode: ResampleMode) -> Resample<FROM, TO, Self> {
+        Resample::new(self, mode)
+    }
+}
+
+impl<const FROM: u32, S: AudioSignal<FROM>> ResampleExt<FROM> for S {}
+
+impl<const SAMPLE_RATE: u32> ConstantSignal<SAMPLE_RATE> {
+    /// Re-tags a constant at another sample rate.
+    ///
+    /// A constant is the same at every rate, so this conversion is exact and
+    /// free. Handy in tests and when a fixed value feeds a graph at another rate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::ConstantSignal;
+    ///
+    /// let at_48k: ConstantSignal<48000> = ConstantSignal::<44100>(0.5).with_sample_rate();
+    /// assert_eq!(at_48k.0, 0.5);
+    /// ```
+    pub fn with_sample_rate<const TO: u32>(self) -> ConstantSignal<TO> {
+        ConstantSignal(self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A ramp counting input samples at a given rate.
+    struct Ramp<const SAMPLE_RATE: u32>(f64);
+
+    impl<const SAMPLE_RATE: u32> Signal for Ramp<SAMPLE_RATE> {
+        fn next_sample(&mut self) -> f64 {
+            let value = self.0;
+            self.0 += 1.0;
+            value
+        }
+    }
+
+    impl<const SAMPLE_RATE: u32> AudioSignal<SAMPLE_RATE> for Ramp<SAMPLE_RATE> {}
+
+    #[test]
+    fn test_upsample_hold_repeats() {
+        let mut up = Ramp::<100>(0.0).resample::<200>(ResampleMode::Hold);
+        let samples: Vec<f64> = up.iter().take(6).collect();
+        assert_eq!(samples, vec![0.0, 0.0, 1.0, 1.0, 2.0, 2.0]);
+    }
+
+    #[test]
+    fn test_upsample_linear_interpolates() {
+        let mut up = Ramp::<100>(0.0).resample::<200>(ResampleMode::Linear);
+        let samples: Vec<f64> = up.iter().take(5).collect();
+        assert_eq!(samples, vec![0.0, 0.5, 1.0, 1.5, 2.0]);
+    }
+
+    /// A sine tone at a given rate.
+    struct Tone<const SAMPLE_RATE: u32> {
+        frequency: f64,
+        index: u64,
+    }
+
+    impl<const SAMPLE_RATE: u32> Signal for Tone<SAMPLE_RATE> {
+        fn next_sample(&mut self) -> f64 {
+            let t = self.index as f64 / SAMPLE_RATE as f64;
+            self.index += 1;
+            (std::f64::consts::TAU * self.frequency * t).sin()
+        }
+    }
+
+    impl<const SAMPLE_RATE: u32> AudioSignal<SAMPLE_RATE> for Tone<SAMPLE_RATE> {}
+
+    /// Peak level of a tone resampled from 48 kHz to 8 kHz, after the filter
+    /// has settled.
+    fn downsampled_peak(frequency: f64, mode: ResampleMode) -> f64 {
+        let tone = Tone::<48000> {
+            frequency,
+            index: 0,
+        };
+        let mut down = tone.resample::<8000>(mode);
+        down.iter()
+            .skip(800)
+            .take(800)
+            .fold(0.0, |peak, s: f64| peak.max(s.abs()))
+    }
+
+    #[test]
+    fn test_downsample_attenuates_tones_above_nyquist() {
+        for mode in [ResampleMode::Hold, ResampleMode::Linear] {
+            // 7 kHz would alias to 1 kHz at 8 kHz
+            assert!(downsampled_peak(7000.0, mode) < 0.01);
+            assert!(downsampled_peak(1000.0, mode) > 0.9);
+        }
+    }
+
+    #[test]
+    fn test_downsample_keeps_dc() {
+        let mut down = ConstantSignal::<300>(0.5).resample::<100>(ResampleMode::Hold);
+        let settled: Vec<f64> = down.iter().skip(50).take(10).collect();
+        assert!(settled.iter().all(|s| (s - 0.5).abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_same_rate_is_identity() {
+        let mut same = Ramp::<100>(0.0).resample::<100>(ResampleMode::Linear);
+        let samples: Vec<f64> = same.iter().take(3).collect();
+        assert_eq!(samples, vec![0.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_constant_signal_rate_change() {
+        let mut constant = ConstantSignal::<44100>(0.25).resample::<96000>(ResampleMode::Linear);
+        assert!(constant.iter().take(10).all(|s| s == 0.25));
+    }
+}
@@ -0,0 +1,245 @@
+//! Real-time safe logging.
+//!
+//! Printing or calling into a logging framework from the audio callback can
+//! lock, allocate or block on I/O, any of which causes dropouts. Instead, the
+//! audio thread records fixed-size [`LogRecord`]s into a bounded queue through
+//! an [`RtLogger`], and another thread drains and formats them with a
+//! [`LogDrain`].
+//!
+//! Recording never blocks and never allocates: messages are `&'static str`
+//! with an optional numeric value, and the queue's storage is allocated once
+//! by [`rt_log`]. When the queue is full the record is dropped and counted
+//! rather than waiting for the reader.
+//!
+//! # Examples
+//!
+//! ```
+//! use earworm::core::{LogLevel, rt_log};
+//!
+//! let (logger, drain) = rt_log(64);
+//! let filter_log = logger.with_source("lowpass");
+//!
+//! // On the audio thread
+//! filter_log.log_value(LogLevel::Warn, "cutoff above Nyquist", 30000.0);
+//!
+//! // On the UI thread
+//! drain.drain(|record| println!("{}", record));
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{Receiver, SyncSender, sync_channel};
+
+/// Severity of a log record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    /// Detailed diagnostics
+    Debug,
+    /// Normal operational messages
+    Info,
+    /// Something unexpected that the signal recovered from
+    Warn,
+    /// Something that broke the output
+    Error,
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warn => "WARN",
+            LogLevel::Error => "ERROR",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A single message recorded by an [`RtLogger`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord {
+    /// Severity of the message
+    pub level: LogLevel,
+    /// Name of the logger that recorded it
+    pub source: Arc<str>,
+    /// The message text
+    pub message: &'static str,
+    /// Optional value attached to the message
+    pub value: Option<f64>,
+}
+
+impl fmt::Display for LogRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}: {}", self.level, self.source, self.message)?;
+        if let Some(value) = self.value {
+            write!(f, " ({})", value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Creates a logging queue holding at most `capacity` undrained records.
+///
+/// The returned logger's source name is `"audio"`; use
+/// [`RtLogger::with_source`] to create loggers for individual nodes.
+///
+/// # Panics
+///
+/// Panics if `capacity` is 0.
+pub fn rt_log(capacity: usize) -> (RtLogger, LogDrain) {
+    assert!(capacity > 0, "log capacity must be greater than 0");
+    let (sender, receiver) = sync_channel(capacity);
+    let dropped = Arc::new(AtomicU64::new(0));
+    (
+        RtLogger {
+            sender,
+            source: Arc::from("audio"),
+            dropped: dropped.clone(),
+        },
+        LogDrain { receiver, dropped },
+    )
+}
+
+/// Audio-thread end of a logging queue.
+///
+/// Loggers can be cloned freely and moved to any thread; all clones feed the
+/// same [`LogDrain`].
+#[derive(Debug, Clone)]
+pub struct RtLogger {
+    sender: SyncSender<LogRecord>,
+    source: Arc<str>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl RtLogger {
+    /// Returns a logger for the same queue that tags records with `source`.
+    ///
+    /// This allocates the name, so call it while building the graph rather
+    /// than on the audio thread.
+    pub fn with_source(&self, source: impl Into<Arc<str>>) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            source: source.into(),
+            dropped: self.dropped.clone(),
+        }
+    }
+
+    /// Returns the source name attached to records from this logger.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Records a message without blocking.
+    ///
+    /// Returns `false` if the queue was full or the drain has been dropped,
+    /// in which case the record is counted in [`LogDrain::dropped`].
+    pub fn log(&self, level: LogLevel, message: &'static str) -> bool {
+        self.record(level, message, None)
+    }
+
+    /// Records a message with an attached value without blocking.
+    ///
+    /// Returns `false` if the record was dropped, as for [`log`](Self::log).
+    pub fn log_value(&self, level: LogLevel, message: &'static str, value: f64) -> bool {
+        self.record(level, message, Some(value))
+    }
+
+    fn record(&self, level: LogLevel, message: &'static str, value: Option<f64>) -> bool {
+        let record = LogRecord {
+            level,
+            source: self.source.clone(),
+            message,
+            value,
+        };
+        if self.sender.try_send(record).is_ok() {
+            true
+        } else {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            false
+        }
+    }
+}
+
+/// Reading end of a logging queue.
+///
+/// Drain it periodically from a non-real-time thread, where formatting and
+/// printing are safe.
+pub struct LogDrain {
+    receiver: Receiver<LogRecord>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl LogDrain {
+    /// Returns the next pending record, if any, without blocking.
+    pub fn try_recv(&self) -> Option<LogRecord> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Passes every pending record to `handler`, returning how many were handled.
+    ///
+    /// Never blocks; returns 0 immediately if nothing is pending.
+    pub fn drain(&self, mut handler: impl FnMut(&LogRecord)) -> usize {
+        let mut count = 0;
+        while let Ok(record) = self.receiver.try_recv() {
+            handler(&record);
+            count += 1;
+        }
+        count
+    }
+
+    /// Returns how many records have been dropped because the queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_drained_in_order() {
+        let (logger, drain) = rt_log(8);
+        let osc = logger.with_source("osc");
+        assert!(logger.log(LogLevel::Info, "started"));
+        assert!(osc.log_value(LogLevel::Warn, "clipped", 1.5));
+
+        let mut records = Vec::new();
+        assert_eq!(drain.drain(|r| records.push(r.clone())), 2);
+        assert_eq!(records[0].to_string(), "[INFO] audio: started");
+        assert_eq!(records[1].to_string(), "[WARN] osc: clipped (1.5)");
+        assert!(drain.try_recv().is_none());
+    }
+
+    #[test]
+    fn test_full_queue_drops_and_counts() {
+        let (logger, drain) = rt_log(2);
+        assert!(logger.log(LogLevel::Debug, "a"));
+        assert!(logger.log(LogLevel::Debug, "b"));
+        assert!(!logger.log(LogLevel::Debug, "c"));
+        assert!(!logger.with_source("other").log(LogLevel::Debug, "d"));
+        assert_eq!(drain.dropped(), 2);
+        assert_eq!(drain.drain(|_| {}), 2);
+        assert!(logger.log(LogLevel::Debug, "e"));
+    }
+
+    #[test]
+    fn test_logging_from_another_thread() {
+        let (logger, drain) = rt_log(16);
+        std::thread::spawn(move || {
+            for _ in 0..10 {
+                logger.log(LogLevel::Info, "tick");
+            }
+        })
+        .join()
+        .unwrap();
+        assert_eq!(drain.drain(|r| assert_eq!(r.message, "tick")), 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "log capacity must be greater than 0")]
+    fn test_zero_capacity_panics() {
+        let _ = rt_log(0);
+    }
+}
@@ -13,6 +13,9 @@
 /// - Single sample generation via `next_sample()`
 /// - Batch processing via `process()`
 /// - Iterator adapter via `iter()`
+///
+/// Before processing starts, `prepare()` gives nodes a chance to allocate
+/// everything they will need up front.
 pub trait Signal {
     /// Generates the next sample from the signal.
     ///
@@ -35,6 +38,54 @@ pub trait Signal {
         }
     }
 
+    /// Prepares the signal for processing blocks of up to `max_block_size` samples.
+    ///
+    /// Call this once before processing starts (and again whenever the block
+    /// size changes), outside the audio thread. Nodes use it to preallocate
+    /// scratch buffers and delay lines so that processing itself does not
+    /// allocate. Nodes that wrap other signals forward the call to them,
+    /// including modulation sources inside [`Param`]s, so preparing the output
+    /// node prepares the whole graph.
+    ///
+    /// `sample_rate` is the rate the node is run at; for [`AudioSignal`](crate::AudioSignal)
+    /// nodes it equals their `SAMPLE_RATE`.
+    ///
+    /// The default implementation does nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_block_size` - Largest buffer that will be passed to `process()`
+    /// * `sample_rate` - Sample rate in Hz
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{Signal, SignalExt, SineOscillator};
+    ///
+    /// let lfo = SineOscillator::<44100>::new(2.0);
+    /// let mut graph = SineOscillator::<44100>::new(440.0).gain(lfo);
+    ///
+    /// graph.prepare(512, 44100);
+    /// let mut block = [0.0; 512];
+    /// graph.process(&mut block);
+    /// ```
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        let _ = (max_block_size, sample_rate);
+    }
+
+    /// Advances the signal by `samples` samples without producing output.
+    ///
+    /// [`ControlRate`](crate::core::ControlRate) uses this to keep its source
+    /// running in time while reading it only once per control period.
+    ///
+    /// The default implementation pulls and discards the samples. Oscillators
+    /// whose only state is their phase override it to jump there directly.
+    fn skip(&mut self, samples: usize) {
+        for _ in 0..samples {
+            self.next_sample();
+        }
+    }
+
     /// Returns an iterator adapter over this signal.
     ///
     /// This allows using iterator methods like `.take()`, `.map()`, `.collect()`, etc.
@@ -62,6 +113,26 @@ pub trait Signal {
     }
 }
 
+/// Boxed signals are signals, so differently-typed sources can share a
+/// collection (e.g. one per output channel) or be swapped at runtime.
+impl<S: Signal + ?Sized> Signal for Box<S> {
+    fn next_sample(&mut self) -> f64 {
+        (**self).next_sample()
+    }
+
+    fn process(&mut self, buffer: &mut [f64]) {
+        (**self).process(buffer)
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        (**self).prepare(max_block_size, sample_rate)
+    }
+
+    fn skip(&mut self, samples: usize) {
+        (**self).skip(samples)
+    }
+}
+
 /// Iterator adapter for `Signal` types.
 ///
 /// This type is returned by `Signal::iter()` and implements `Iterator`,
@@ -118,6 +189,78 @@ pub trait Pitched {
     ///
     /// Current frequency in Hz
     fn frequency(&self) -> f64;
+
+    /// Detunes the signal by a number of cents (hundredths of a semitone).
+    ///
+    /// The change is relative to the current frequency; positive values raise
+    /// the pitch and negative values lower it.
+    ///
+    /// # Arguments
+    ///
+    /// * `cents` - Amount to detune by (100 cents = 1 semitone)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{Pitched, SineOscillator};
+    ///
+    /// let mut osc = SineOscillator::<44100>::new(440.0);
+    /// osc.detune_cents(1200.0); // One octave up
+    /// assert!((osc.frequency() - 880.0).abs() < 1e-9);
+    /// ```
+    fn detune_cents(&mut self, cents: f64) {
+        let freq = self.frequency() * 2.0_f64.powf(cents / 1200.0);
+        self.set_frequency(freq);
+    }
+
+    /// Transposes the signal by a number of semitones.
+    ///
+    /// The change is relative to the current frequency. Fractional values are
+    /// allowed.
+    ///
+    /// # Arguments
+    ///
+    /// * `semitones` - Amount to transpose by (12 = one octave)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{Pitched, SineOscillator};
+    ///
+    /// let mut osc = SineOscillator::<44100>::new(440.0);
+    /// osc.transpose_semitones(-12.0); // One octave down
+    /// assert!((osc.frequency() - 220.0).abs() < 1e-9);
+    /// ```
+    fn transpose_semitones(&mut self, semitones: f64) {
+        self.detune_cents(semitones * 100.0);
+    }
+
+    /// Moves to a frequency immediately, skipping any portamento.
+    ///
+    /// For most signals this is the same as [`set_frequency`](Self::set_frequency);
+    /// signals that slew between frequencies (such as `Glide`) override it to
+    /// jump instead. Voices use it for notes that shouldn't glide.
+    ///
+    /// # Arguments
+    ///
+    /// * `freq` - New frequency in Hz
+    fn jump_to_frequency(&mut self, freq: f64) {
+        self.set_frequency(freq);
+    }
+
+    /// Restarts the signal from its beginning for a new note.
+    ///
+    /// Oscillators run continuously and ignore this (the default). Signals
+    /// with a start and an end, such as a `Sampler`, override it to play from
+    /// the top. Voices call it whenever a note retriggers the envelope.
+    fn restart(&mut self) {}
+
+    /// Tells the signal that its note has been released.
+    ///
+    /// Most signals ignore this (the default). Signals with envelopes of
+    /// their own, such as an `FmVoice`, override it to release them. Voices
+    /// call it on every note-off.
+    fn release(&mut self) {}
 }
 
 /// A constant signal that always returns the same value.
@@ -164,6 +307,11 @@ impl<const SAMPLE_RATE: u32> crate::AudioSignal<SAMPLE_RATE> for ConstantSignal<
 /// for heterogeneous collections of modulatable parameters, at the cost of
 /// a small performance overhead from dynamic dispatch.
 ///
+/// To change a parameter from another thread after the graph is built, pass
+/// a [`ParamHandle`](crate::core::ParamHandle) (or a reference to one) and
+/// keep a clone on the control thread; wrap it in a
+/// [`SmoothedParam`](crate::core::SmoothedParam) to avoid zipper noise.
+///
 /// # Examples
 ///
 /// ```
@@ -182,6 +330,21 @@ impl<const SAMPLE_RATE: u32> crate::AudioSignal<SAMPLE_RATE> for ConstantSignal<
 /// let lfo2 = SineOscillator::<44100>::new(2.0);
 /// let mut modulated_param2 = Param::modulated(lfo2);
 /// ```
+///
+/// Live control from a UI or MIDI thread:
+///
+/// ```
+/// use earworm::core::ParamHandle;
+/// use earworm::{BiquadFilter, SawtoothOscillator, Signal};
+///
+/// let cutoff = ParamHandle::new(500.0);
+/// let osc = SawtoothOscillator::<44100>::new(110.0);
+/// let mut filter = BiquadFilter::lowpass(osc, &cutoff, 0.707);
+///
+/// let control = cutoff.clone();
+/// std::thread::spawn(move || control.set(2000.0)).join().unwrap();
+/// filter.next_sample(); // Filters at 2000 Hz
+/// ```
 pub enum Param {
     /// A fixed, constant value
     Fixed(f64),
@@ -240,6 +403,15 @@ impl Param {
         Param::Signal(Box::new(signal))
     }
 
+    /// Prepares a modulation source for processing (see [`Signal::prepare`]).
+    ///
+    /// Does nothing for fixed parameters.
+    pub fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        if let Param::Signal(s) = self {
+            s.prepare(max_block_size, sample_rate);
+        }
+    }
+
     /// Returns true if this parameter is fixed (non-modulated).
     pub fn is_fixed(&self) -> bool {
         matches!(self, Param::Fixed(_))
@@ -257,10 +429,82 @@ impl<S: Signal + Send + 'static> From<S> for Param {
         Param::Signal(Box::new(signal))
     }
 }
+
+impl From<&super::param_handle::ParamHandle> for Param {
+    /// Shares the handle's value with the parameter.
+    fn from(handle: &super::param_handle::ParamHandle) -> Self {
+        Param::Signal(Box::new(handle.clone()))
+    }
+}
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    struct Tone(f64);
+
+    /// Records the block size it was prepared with.
+    struct Probe(std::sync::Arc<std::sync::atomic::AtomicUsize>);
+
+    impl Signal for Probe {
+        fn next_sample(&mut self) -> f64 {
+            0.0
+        }
+
+        fn prepare(&mut self, max_block_size: usize, _sample_rate: u32) {
+            self.0
+                .store(max_block_size, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+
+    impl crate::AudioSignal<44100> for Probe {}
+
+    #[test]
+    fn test_prepare_propagates_through_graph() {
+        use crate::SignalExt;
+        use std::sync::{Arc, atomic::AtomicUsize, atomic::Ordering};
+
+        let source = Arc::new(AtomicUsize::new(0));
+        let modulator = Arc::new(AtomicUsize::new(0));
+        let mut graph: Box<dyn Signal> = Box::new(
+            Probe(source.clone())
+                .gain(Probe(modulator.clone()))
+                .offset(0.5)
+                .debug_guard("graph"),
+        );
+
+        graph.prepare(256, 44100);
+        assert_eq!(source.load(Ordering::Relaxed), 256);
+        assert_eq!(modulator.load(Ordering::Relaxed), 256);
+    }
+
+    impl Pitched for Tone {
+        fn set_frequency(&mut self, freq: f64) {
+            self.0 = freq;
+        }
+
+        fn frequency(&self) -> f64 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_detune_cents() {
+        let mut tone = Tone(440.0);
+        tone.detune_cents(100.0);
+        assert!((tone.frequency() - 466.1638).abs() < 1e-3);
+        tone.detune_cents(-100.0);
+        assert!((tone.frequency() - 440.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_transpose_semitones() {
+        let mut tone = Tone(261.6256);
+        tone.transpose_semitones(7.0);
+        assert!((tone.frequency() - 391.9954).abs() < 1e-3);
+        tone.transpose_semitones(-19.0);
+        assert!((tone.frequency() - 130.8128).abs() < 1e-3);
+    }
+
     #[test]
     fn test_f64_to_constant_signal() {
         let constant: ConstantSignal<44100> = 0.5.into();
@@ -0,0 +1,255 @@
+//! Click-free parameter changes.
+//!
+//! A [`ParamHandle`] changes instantly, so moving a gain or cutoff from a UI
+//! produces audible steps ("zipper noise"). [`SmoothedParam`] follows a
+//! handle but slews toward each new value over a short time, and converts
+//! into a [`Param`] like any other signal.
+
+use super::param_handle::ParamHandle;
+use super::signal::{Param, Signal};
+use crate::AudioSignal;
+
+/// Differences smaller than this are treated as having reached the target.
+const SETTLE_THRESHOLD: f64 = 1e-9;
+
+/// How a [`SmoothedParam`] moves toward its target.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Slew {
+    /// Constant rate: reaches the target in exactly the smoothing time,
+    /// then stops.
+    Linear,
+    /// One-pole approach: fast at first, then easing in. The smoothing time
+    /// is the time constant (63% of the way there).
+    #[default]
+    Exponential,
+}
+
+/// A parameter that glides toward the value of a [`ParamHandle`].
+///
+/// Set the handle from the control thread as usual; the audio side sees the
+/// value move smoothly instead of jumping. Pass it anywhere a [`Param`] is
+/// accepted, or use [`Param::smoothed`].
+///
+/// # Examples
+///
+/// ```
+/// use earworm::core::{ParamHandle, SmoothedParam};
+/// use earworm::{SignalExt, SineOscillator};
+///
+/// const SAMPLE_RATE: u32 = 44100;
+///
+/// let volume = ParamHandle::new(0.5);
+/// let level = SmoothedParam::<SAMPLE_RATE>::new(volume.clone(), 20.0);
+/// let mut voice = SineOscillator::<SAMPLE_RATE>::new(440.0).gain(level);
+///
+/// // A UI fader move ramps over about 20 ms instead of clicking
+/// volume.set(0.1);
+/// ```
+pub struct SmoothedParam<const SAMPLE_RATE: u32> {
+    target: ParamHandle,
+    slew: Slew,
+    time_ms: f64,
+    current: f64,
+    // Linear slew: the target being approached, the per-sample step and
+    // samples left
+    heading: f64,
+    step: f64,
+    remaining: usize,
+    // Exponential slew: fraction of the remaining distance covered per sample
+    coeff: f64,
+}
+
+impl<const SAMPLE_RATE: u32> SmoothedParam<SAMPLE_RATE> {
+    /// Creates an exponentially smoothed parameter starting at the handle's
+    /// current value.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - Handle whose value is followed
+    /// * `time_ms` - Smoothing time in milliseconds (0.0 follows instantly)
+    pub fn new(target: ParamHandle, time_ms: f64) -> Self {
+        let current = target.get();
+        let mut param = Self {
+            target,
+            slew: Slew::default(),
+            time_ms: 0.0,
+            current,
+            heading: current,
+            step: 0.0,
+            remaining: 0,
+            coeff: 1.0,
+        };
+        param.set_time(time_ms);
+        param
+    }
+
+    /// Sets how the value moves toward the target (builder style).
+    pub fn with_slew(mut self, slew: Slew) -> Self {
+        self.slew = slew;
+        self.heading = self.current;
+        self.remaining = 0;
+        self
+    }
+
+    /// Changes the smoothing time in milliseconds.
+    ///
+    /// A linear slew already in progress keeps its original rate.
+    pub fn set_time(&mut self, time_ms: f64) {
+        self.time_ms = time_ms.max(0.0);
+        let samples = self.time_ms * 0.001 * SAMPLE_RATE as f64;
+        self.coeff = if samples > 0.0 {
+            1.0 - (-1.0 / samples).exp()
+        } else {
+            1.0
+        };
+    }
+
+    /// Returns the smoothing time in milliseconds.
+    pub fn time_ms(&self) -> f64 {
+        self.time_ms
+    }
+
+    /// Returns the handle being followed.
+    pub fn handle(&self) -> &ParamHandle {
+        &self.target
+    }
+
+    /// Returns the most recent output value.
+    pub fn current(&self) -> f64 {
+        self.current
+    }
+
+    /// Returns `true` while the value is still moving toward the target.
+    pub fn is_smoothing(&self) -> bool {
+        self.current != self.target.get()
+    }
+
+    /// Jumps straight to the target value.
+    pub fn snap(&mut self) {
+        self.current = self.target.get();
+        self.heading = self.current;
+        self.remaining = 0;
+    }
+}
+
+impl<const SAMPLE_RATE: u32> Signal for SmoothedParam<SAMPLE_RATE> {
+    fn next_sample(&mut self) -> f64 {
+        let target = self.target.get();
+        match self.slew {
+            Slew::Linear => {
+                if target != self.heading {
+                    // Start a new ramp from wherever we are now
+                    self.heading = target;
+                    let samples = (self.time_ms * 0.001 * SAMPLE_RATE as f64).round();
+                    self.remaining = samples as usize;
+                    self.step = (target - self.current) / samples.max(1.0);
+                }
+                if self.remaining > 1 {
+                    self.remaining -= 1;
+                    self.current += self.step;
+                } else {
+                    self.remaining = 0;
+                    self.current = target;
+                }
+            }
+            Slew::Exponential => {
+                self.current += (target - self.current) * self.coeff;
+                if (target - self.current).abs() <= SETTLE_THRESHOLD * target.abs().max(1.0) {
+                    self.current = target;
+                }
+            }
+        }
+        self.current
+    }
+}
+
+impl<const SAMPLE_RATE: u32> AudioSignal<SAMPLE_RATE> for SmoothedParam<SAMPLE_RATE> {}
+
+impl Param {
+    /// Creates a parameter that follows `target` with exponential smoothing.
+    ///
+    /// Shorthand for [`SmoothedParam::new`] converted into a `Param`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::core::ParamHandle;
+    /// use earworm::{BiquadFilter, Param, SawtoothOscillator};
+    ///
+    /// const SAMPLE_RATE: u32 = 44100;
+    ///
+    /// let cutoff = ParamHandle::new(800.0);
+    /// let osc = SawtoothOscillator::<SAMPLE_RATE>::new(110.0);
+    /// let filter = BiquadFilter::lowpass(
+    ///     osc,
+    ///     Param::smoothed::<SAMPLE_RATE>(cutoff.clone(), 30.0),
+    ///     0.707,
+    /// );
+    /// ```
+    pub fn smoothed<const SAMPLE_RATE: u32>(target: ParamHandle, time_ms: f64) -> Self {
+        SmoothedParam::<SAMPLE_RATE>::new(target, time_ms).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SR: u32 = 1000;
+
+    #[test]
+    fn test_linear_reaches_target_in_time() {
+        let handle = ParamHandle::new(0.0);
+        let mut param = SmoothedParam::<SR>::new(handle.clone(), 10.0).with_slew(Slew::Linear);
+        assert_eq!(param.next_sample(), 0.0);
+
+        handle.set(1.0);
+        let ramp: Vec<f64> = (0..10).map(|_| param.next_sample()).collect();
+        assert!((ramp[0] - 0.1).abs() < 1e-12);
+        assert!((ramp[4] - 0.5).abs() < 1e-12);
+        assert_eq!(ramp[9], 1.0);
+        assert!(!param.is_smoothing());
+        assert_eq!(param.next_sample(), 1.0);
+
+        // Retargeting mid-ramp starts a new full-length ramp from there
+        handle.set(0.0);
+        for _ in 0..5 {
+            param.next_sample();
+        }
+        handle.set(1.0);
+        assert!((param.next_sample() - 0.55).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_exponential_time_constant() {
+        let handle = ParamHandle::new(0.0);
+        let mut param = SmoothedParam::<SR>::new(handle.clone(), 10.0);
+        handle.set(1.0);
+        let mut value = 0.0;
+        for _ in 0..10 {
+            value = param.next_sample();
+        }
+        assert!((value - (1.0 - (-1.0f64).exp())).abs() < 1e-9);
+        assert!(param.is_smoothing());
+
+        // Settles exactly on the target eventually
+        for _ in 0..1000 {
+            param.next_sample();
+        }
+        assert_eq!(param.current(), 1.0);
+        assert!(!param.is_smoothing());
+    }
+
+    #[test]
+    fn test_zero_time_and_snap() {
+        let handle = ParamHandle::new(2.0);
+        let mut param: Param = Param::smoothed::<SR>(handle.clone(), 0.0);
+        handle.set(5.0);
+        assert_eq!(param.value(), 5.0);
+
+        let mut slow = SmoothedParam::<SR>::new(handle.clone(), 1000.0);
+        handle.set(-3.0);
+        slow.snap();
+        assert_eq!(slow.next_sample(), -3.0);
+    }
+}
@@ -0,0 +1,220 @@
+//! Parameter snapshots and morphing between them.
+//!
+//! A [`Snapshot`] records the values of a set of [`ParamHandle`]s, like a
+//! scene on a hardware synth. A [`SnapshotMorph`] interpolates the handles
+//! between two snapshots, either directly from a macro knob or automatically
+//! over a span of time.
+//!
+//! # Examples
+//!
+//! ```
+//! use earworm::core::{ParamHandle, Snapshot, SnapshotMorph};
+//!
+//! let cutoff = ParamHandle::new(400.0);
+//! let resonance = ParamHandle::new(0.7);
+//! let handles = vec![cutoff.clone(), resonance.clone()];
+//!
+//! // Capture the verse sound, tweak, capture the chorus sound
+//! let verse = Snapshot::capture(&handles);
+//! cutoff.set(4000.0);
+//! resonance.set(3.0);
+//! let chorus = Snapshot::capture(&handles);
+//!
+//! // Halfway between the two scenes
+//! let mut morph = SnapshotMorph::new(handles, verse, chorus);
+//! morph.set_position(0.5);
+//! assert_eq!(cutoff.get(), 2200.0);
+//! ```
+
+use super::param_handle::ParamHandle;
+
+/// Captured values of a set of parameters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    values: Vec<f64>,
+}
+
+impl Snapshot {
+    /// Records the current value of each handle, in order.
+    pub fn capture(handles: &[ParamHandle]) -> Self {
+        Self {
+            values: handles.iter().map(ParamHandle::get).collect(),
+        }
+    }
+
+    /// Creates a snapshot from explicit values.
+    pub fn from_values(values: impl Into<Vec<f64>>) -> Self {
+        Self {
+            values: values.into(),
+        }
+    }
+
+    /// Returns the captured values.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Sets each handle back to its captured value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `handles` doesn't match the number of captured values.
+    pub fn recall(&self, handles: &[ParamHandle]) {
+        assert_eq!(
+            handles.len(),
+            self.values.len(),
+            "snapshot has {} values but {} handles were given",
+            self.values.len(),
+            handles.len()
+        );
+        for (handle, &value) in handles.iter().zip(&self.values) {
+            handle.set(value);
+        }
+    }
+}
+
+/// Crossfades a set of parameters between two snapshots.
+///
+/// The position runs from 0.0 (`from`) to 1.0 (`to`) and each handle is set to
+/// the linear interpolation of its two captured values. Move the position
+/// directly with [`set_position`](Self::set_position) (for a macro knob), or
+/// start a timed morph with [`morph_to`](Self::morph_to) and call
+/// [`advance`](Self::advance) once per audio block.
+pub struct SnapshotMorph {
+    handles: Vec<ParamHandle>,
+    from: Snapshot,
+    to: Snapshot,
+    position: f64,
+    target: f64,
+    step: f64, // position change per sample while morphing
+}
+
+impl SnapshotMorph {
+    /// Creates a morph between `from` and `to`, starting at `from`.
+    ///
+    /// The handles are not touched until the position is first moved.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either snapshot doesn't have one value per handle.
+    pub fn new(handles: Vec<ParamHandle>, from: Snapshot, to: Snapshot) -> Self {
+        assert!(
+            from.values.len() == handles.len() && to.values.len() == handles.len(),
+            "snapshots must have one value per handle"
+        );
+        Self {
+            handles,
+            from,
+            to,
+            position: 0.0,
+            target: 0.0,
+            step: 0.0,
+        }
+    }
+
+    /// Returns the current morph position (0.0 = `from`, 1.0 = `to`).
+    pub fn position(&self) -> f64 {
+        self.position
+    }
+
+    /// Returns `true` while a timed morph is in progress.
+    pub fn is_morphing(&self) -> bool {
+        self.position != self.target
+    }
+
+    /// Jumps to `position` and updates every handle, cancelling any timed morph.
+    pub fn set_position(&mut self, position: f64) {
+        self.position = position.clamp(0.0, 1.0);
+        self.target = self.position;
+        self.apply();
+    }
+
+    /// Starts moving towards `target` so that the full 0-to-1 sweep takes
+    /// `duration` seconds.
+    ///
+    /// A zero duration jumps straight to the target.
+    pub fn morph_to(&mut self, target: f64, duration: f64, sample_rate: u32) {
+        let target = target.clamp(0.0, 1.0);
+        if duration <= 0.0 {
+            self.set_position(target);
+            return;
+        }
+        self.target = target;
+        self.step = 1.0 / (duration * sample_rate as f64);
+    }
+
+    /// Advances a timed morph by `samples` and updates every handle.
+    ///
+    /// Returns `true` while the morph is still in progress.
+    pub fn advance(&mut self, samples: usize) -> bool {
+        if !self.is_morphing() {
+            return false;
+        }
+        let distance = self.target - self.position;
+        let travel = self.step * samples as f64;
+        self.position = if distance.abs() <= travel {
+            self.target
+        } else {
+            self.position + travel.copysign(distance)
+        };
+        self.apply();
+        self.is_morphing()
+    }
+
+    fn apply(&self) {
+        for ((handle, from), to) in self
+            .handles
+            .iter()
+            .zip(&self.from.values)
+            .zip(&self.to.values)
+        {
+            handle.set(from + (to - from) * self.position);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_and_recall() {
+        let handles = vec![ParamHandle::new(1.0), ParamHandle::new(2.0)];
+        let snapshot = Snapshot::capture(&handles);
+        handles[0].set(5.0);
+        snapshot.recall(&handles);
+        assert_eq!(handles[0].get(), 1.0);
+        assert_eq!(snapshot.values(), &[1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_timed_morph() {
+        let handle = ParamHandle::new(0.0);
+        let mut morph = SnapshotMorph::new(
+            vec![handle.clone()],
+            Snapshot::from_values([0.0]),
+            Snapshot::from_values([10.0]),
+        );
+        morph.morph_to(1.0, 1.0, 1000);
+        assert!(morph.advance(250));
+        assert!((handle.get() - 2.5).abs() < 1e-9);
+        assert!(!morph.advance(1000));
+        assert_eq!(handle.get(), 10.0);
+
+        // And back halfway
+        morph.morph_to(0.5, 1.0, 1000);
+        while morph.advance(64) {}
+        assert_eq!(morph.position(), 0.5);
+        assert_eq!(handle.get(), 5.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "one value per handle")]
+    fn test_mismatched_snapshot() {
+        SnapshotMorph::new(
+            vec![ParamHandle::new(0.0)],
+            Snapshot::from_values([0.0, 1.0]),
+            Snapshot::from_values([0.0]),
+        );
+    }
+}
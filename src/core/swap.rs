@@ -0,0 +1,243 @@
+//! Glitch-free replacement of a running signal graph.
+//!
+//! Building a new patch can allocate and take time, which must not happen on
+//! the audio thread. With [`graph_swap`], the new graph is built on any other
+//! thread and handed over through a non-blocking queue. The audio thread
+//! crossfades from the old graph to the new one, then sends the old graph back
+//! so it is dropped (and its memory freed) off the audio thread.
+//!
+//! # Examples
+//!
+//! ```
+//! use earworm::core::graph_swap;
+//! use earworm::{AudioSignal, Signal, SineOscillator, SawtoothOscillator};
+//!
+//! type Graph = Box<dyn AudioSignal<44100> + Send>;
+//!
+//! let initial: Graph = Box::new(SineOscillator::<44100>::new(220.0));
+//! let (swapper, mut audio) = graph_swap(initial, 256);
+//!
+//! // On a worker thread: build the new patch and hand it over
+//! let replacement: Graph = Box::new(SawtoothOscillator::<44100>::new(220.0));
+//! swapper.swap(replacement).ok();
+//!
+//! // On the audio thread the graphs crossfade over 256 samples
+//! for _ in 0..512 {
+//!     audio.next_sample();
+//! }
+//!
+//! // Back on the control thread: free the old graph
+//! assert_eq!(swapper.collect(), 1);
+//! ```
+
+use crate::{AudioSignal, Signal};
+use std::f64::consts::FRAC_PI_2;
+use std::sync::mpsc::{Receiver, SyncSender, TrySendError, sync_channel};
+
+/// Number of graphs that can be in flight in each direction.
+const QUEUE_CAPACITY: usize = 4;
+
+/// Creates a swappable graph and the handle used to replace it.
+///
+/// # Arguments
+///
+/// * `initial` - The graph to play until the first swap
+/// * `crossfade_samples` - Length of the crossfade between graphs (0 switches instantly)
+pub fn graph_swap<S: Signal + Send>(
+    initial: S,
+    crossfade_samples: usize,
+) -> (GraphSwapper<S>, SwappableGraph<S>) {
+    let (incoming_tx, incoming_rx) = sync_channel(QUEUE_CAPACITY);
+    let (retired_tx, retired_rx) = sync_channel(QUEUE_CAPACITY);
+
+    let swapper = GraphSwapper {
+        incoming: incoming_tx,
+        retired: retired_rx,
+    };
+    let graph = SwappableGraph {
+        current: initial,
+        next: None,
+        pending_retire: None,
+        fade_position: 0,
+        crossfade_samples,
+        incoming: incoming_rx,
+        retired: retired_tx,
+    };
+    (swapper, graph)
+}
+
+/// Control-side handle for replacing a [`SwappableGraph`].
+pub struct GraphSwapper<S> {
+    incoming: SyncSender<S>,
+    retired: Receiver<S>,
+}
+
+impl<S> GraphSwapper<S> {
+    /// Sends a new graph to replace the playing one.
+    ///
+    /// Never blocks. Any graphs already retired by the audio thread are
+    /// collected first. Call [`Signal::prepare`](crate::Signal::prepare) on the
+    /// new graph before sending it; the audio side does not prepare it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the graph if too many swaps are already pending or the audio
+    /// side has been dropped.
+    pub fn swap(&self, graph: S) -> Result<(), S> {
+        self.collect();
+        self.incoming.try_send(graph).map_err(|err| match err {
+            TrySendError::Full(graph) | TrySendError::Disconnected(graph) => graph,
+        })
+    }
+
+    /// Drops graphs the audio thread has finished with, returning how many.
+    ///
+    /// Call this periodically from the control thread (it is also called by
+    /// [`swap`](Self::swap)).
+    pub fn collect(&self) -> usize {
+        self.retired.try_iter().count()
+    }
+}
+
+/// Audio-side signal that plays the current graph and crossfades to new ones.
+///
+/// Swaps requested while a crossfade is running wait until it completes.
+pub struct SwappableGraph<S> {
+    current: S,
+    next: Option<S>,
+    pending_retire: Option<S>,
+    fade_position: usize,
+    crossfade_samples: usize,
+    incoming: Receiver<S>,
+    retired: SyncSender<S>,
+}
+
+impl<S> SwappableGraph<S> {
+    /// Returns true while crossfading to a new graph.
+    pub fn is_crossfading(&self) -> bool {
+        self.next.is_some()
+    }
+
+    /// Hands a retired graph back to the control thread.
+    ///
+    /// If the return queue is full the graph is held until the next sample;
+    /// if the control side is gone there is nobody to free it, so it is dropped here.
+    fn retire(&mut self) {
+        if let Some(graph) = self.pending_retire.take() {
+            match self.retired.try_send(graph) {
+                Ok(()) | Err(TrySendError::Disconnected(_)) => {}
+                Err(TrySendError::Full(graph)) => self.pending_retire = Some(graph),
+            }
+        }
+    }
+}
+
+impl<S: Signal> Signal for SwappableGraph<S> {
+    fn next_sample(&mut self) -> f64 {
+        self.retire();
+
+        if self.next.is_none()
+            && self.pending_retire.is_none()
+            && let Ok(graph) = self.incoming.try_recv()
+        {
+            self.next = Some(graph);
+            self.fade_position = 0;
+        }
+
+        let Some(next) = self.next.as_mut() else {
+            return self.current.next_sample();
+        };
+
+        if self.fade_position >= self.crossfade_samples {
+            // Crossfade complete: promote the new graph
+            let old = std::mem::replace(&mut self.current, self.next.take().unwrap());
+            self.pending_retire = Some(old);
+            self.retire();
+            return self.current.next_sample();
+        }
+
+        // Equal-power crossfade between uncorrelated graphs
+        let t = self.fade_position as f64 / self.crossfade_samples as f64;
+        let angle = t * FRAC_PI_2;
+        self.fade_position += 1;
+        self.current.next_sample() * angle.cos() + next.next_sample() * angle.sin()
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.current.prepare(max_block_size, sample_rate);
+        if let Some(next) = self.next.as_mut() {
+            next.prepare(max_block_size, sample_rate);
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE>
+    for SwappableGraph<S>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConstantSignal;
+
+    #[test]
+    fn test_plays_initial_graph() {
+        let (_swapper, mut graph) = graph_swap(ConstantSignal::<44100>(0.5), 4);
+        assert_eq!(graph.next_sample(), 0.5);
+        assert!(!graph.is_crossfading());
+    }
+
+    #[test]
+    fn test_crossfade_to_new_graph() {
+        let (swapper, mut graph) = graph_swap(ConstantSignal::<44100>(1.0), 4);
+        swapper.swap(ConstantSignal(0.0)).unwrap();
+
+        let samples: Vec<f64> = graph.iter().take(6).collect();
+        assert_eq!(samples[0], 1.0);
+        // Monotonically fades out towards the new (silent) graph
+        for pair in samples[..5].windows(2) {
+            assert!(pair[1] < pair[0]);
+        }
+        assert_eq!(samples[4], 0.0);
+        assert_eq!(samples[5], 0.0);
+        assert!(!graph.is_crossfading());
+    }
+
+    #[test]
+    fn test_instant_swap() {
+        let (swapper, mut graph) = graph_swap(ConstantSignal::<44100>(1.0), 0);
+        swapper.swap(ConstantSignal(0.25)).unwrap();
+        assert_eq!(graph.next_sample(), 0.25);
+    }
+
+    #[test]
+    fn test_old_graph_returned_for_disposal() {
+        let (swapper, mut graph) = graph_swap(ConstantSignal::<44100>(1.0), 2);
+        assert_eq!(swapper.collect(), 0);
+
+        swapper.swap(ConstantSignal(0.0)).unwrap();
+        for _ in 0..4 {
+            graph.next_sample();
+        }
+        assert_eq!(swapper.collect(), 1);
+    }
+
+    #[test]
+    fn test_swaps_queue_behind_crossfade() {
+        let (swapper, mut graph) = graph_swap(ConstantSignal::<44100>(1.0), 2);
+        swapper.swap(ConstantSignal(2.0)).unwrap();
+        swapper.swap(ConstantSignal(3.0)).unwrap();
+
+        let samples: Vec<f64> = graph.iter().take(8).collect();
+        assert_eq!(*samples.last().unwrap(), 3.0);
+        assert_eq!(swapper.collect(), 2);
+    }
+
+    #[test]
+    fn test_swap_fails_when_audio_side_dropped() {
+        let (swapper, graph) = graph_swap(ConstantSignal::<44100>(1.0), 2);
+        drop(graph);
+        assert!(swapper.swap(ConstantSignal(0.0)).is_err());
+    }
+}
@@ -0,0 +1,519 @@
+//! Graph validation for catching common patch problems before playback.
+//!
+//! [`validate`] renders a signal graph for a short time and reports problems
+//! that are easy to miss by ear until they reach the audio device: NaN/inf
+//! production, clipping, DC offset and silent output. Drive the graph with a
+//! representative test source (e.g. a sine or noise) when building it.
+//!
+//! [`validate_effect`] checks an effect or processing chain instead: it feeds
+//! the chain a known [`TestSignal`] and also reports the chain's gain, flagging
+//! chains that should pass audio at unity gain but don't.
+
+use std::fmt;
+
+use crate::core::rng::splitmix64;
+use crate::core::{AudioSignal, Signal};
+
+/// Thresholds used when deciding whether a rendered signal has problems.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValidationThresholds {
+    /// Absolute sample value above which a sample counts as clipped.
+    pub clip_level: f64,
+    /// Absolute mean value above which the output is flagged for DC offset.
+    pub dc_offset: f64,
+    /// Peak level below which the output is considered silent.
+    pub silence: f64,
+    /// Largest gain away from unity, in dB, that [`validate_effect`] accepts.
+    pub gain_tolerance_db: f64,
+}
+
+impl Default for ValidationThresholds {
+    fn default() -> Self {
+        Self {
+            clip_level: 1.0,
+            dc_offset: 0.01,
+            silence: 1e-6,
+            gain_tolerance_db: 0.5,
+        }
+    }
+}
+
+/// A single problem found while validating a signal.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationIssue {
+    /// The signal produced NaN or infinite samples.
+    NonFinite {
+        /// Number of non-finite samples.
+        count: usize,
+        /// Index of the first non-finite sample.
+        first_index: usize,
+    },
+    /// Samples exceeded the clip level.
+    Clipping {
+        /// Number of clipped samples.
+        count: usize,
+        /// Highest absolute sample value seen.
+        peak: f64,
+    },
+    /// The signal has a significant DC offset (its mean value).
+    DcOffset(f64),
+    /// The signal never rose above the silence threshold.
+    Silent,
+    /// An effect's output level differs from its input level by more than
+    /// the tolerance.
+    NotUnityGain {
+        /// Output-to-input gain in dB.
+        gain_db: f64,
+    },
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationIssue::NonFinite { count, first_index } => write!(
+                f,
+                "{} non-finite samples (first at sample {})",
+                count, first_index
+            ),
+            ValidationIssue::Clipping { count, peak } => {
+                write!(f, "{} clipped samples (peak {:.3})", count, peak)
+            }
+            ValidationIssue::DcOffset(mean) => write!(f, "DC offset of {:.4}", mean),
+            ValidationIssue::Silent => write!(f, "output is silent"),
+            ValidationIssue::NotUnityGain { gain_db } => {
+                write!(f, "gain of {:+.2} dB instead of unity", gain_db)
+            }
+        }
+    }
+}
+
+/// Statistics and issues collected by [`validate`] and [`validate_effect`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationReport {
+    /// Number of samples rendered.
+    pub samples: usize,
+    /// Highest absolute finite sample value.
+    pub peak: f64,
+    /// RMS level of the finite samples.
+    pub rms: f64,
+    /// Mean of the finite samples (DC offset).
+    pub dc_offset: f64,
+    /// Output-to-input RMS gain of an effect, as a linear factor. Only set by
+    /// [`validate_effect`].
+    pub gain: Option<f64>,
+    /// Problems found, in order of severity.
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Returns true if no issues were found.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} samples, peak {:.3}, rms {:.3}, dc {:.4}",
+            self.samples, self.peak, self.rms, self.dc_offset
+        )?;
+        if let Some(gain) = self.gain {
+            write!(f, ", gain {:+.2} dB", 20.0 * gain.log10())?;
+        }
+        if self.issues.is_empty() {
+            write!(f, ": ok")
+        } else {
+            for issue in &self.issues {
+                write!(f, "\n  - {}", issue)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Renders `duration_secs` of a signal and reports potential problems using the
+/// default [`ValidationThresholds`].
+///
+/// The signal is advanced by the rendered duration, so validate a graph before
+/// handing it to playback (or validate a separately constructed copy).
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{SineOscillator, SignalExt};
+/// use earworm::core::validate;
+///
+/// let mut patch = SineOscillator::<44100>::new(440.0).gain(2.0).offset(0.5);
+/// let report = validate(&mut patch, 0.1);
+///
+/// assert!(!report.is_ok());
+/// println!("{}", report);
+/// ```
+pub fn validate<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>>(
+    signal: &mut S,
+    duration_secs: f64,
+) -> ValidationReport {
+    validate_with(signal, duration_secs, &ValidationThresholds::default())
+}
+
+/// Renders `duration_secs` of a signal and reports potential problems using
+/// custom thresholds.
+///
+/// # Arguments
+///
+/// * `signal` - The graph to validate
+/// * `duration_secs` - How long to render, in seconds
+/// * `thresholds` - Levels used to flag clipping, DC offset and silence
+pub fn validate_with<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>>(
+    signal: &mut S,
+    duration_secs: f64,
+    thresholds: &ValidationThresholds,
+) -> ValidationReport {
+    let samples = duration_to_samples(duration_secs, SAMPLE_RATE);
+    measure(samples, || signal.next_sample(), thresholds)
+}
+
+/// A known signal for driving an effect in [`validate_effect`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TestSignal {
+    /// A single sample of 1.0 followed by silence. The gain is then the
+    /// level of the effect's whole impulse response.
+    Impulse,
+    /// A sine at half full scale.
+    Sine {
+        /// Frequency in Hz.
+        frequency: f64,
+    },
+    /// Uniform white noise at half full scale, the same on every run.
+    Noise,
+}
+
+/// The test signal an effect under test is built around.
+///
+/// [`validate_effect`] creates one and hands it to the closure that builds
+/// the effect chain.
+#[derive(Debug, Clone)]
+pub struct TestInput<const SAMPLE_RATE: u32> {
+    signal: TestSignal,
+    position: usize,
+    state: u64,
+}
+
+impl<const SAMPLE_RATE: u32> TestInput<SAMPLE_RATE> {
+    fn new(signal: TestSignal) -> Self {
+        Self {
+            signal,
+            position: 0,
+            state: 0x5EED,
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32> Signal for TestInput<SAMPLE_RATE> {
+    fn next_sample(&mut self) -> f64 {
+        let position = self.position;
+        self.position += 1;
+        match self.signal {
+            TestSignal::Impulse => {
+                if position == 0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            TestSignal::Sine { frequency } => {
+                let phase = position as f64 * frequency / SAMPLE_RATE as f64;
+                0.5 * (std::f64::consts::TAU * phase).sin()
+            }
+            TestSignal::Noise => {
+                let unit = (splitmix64(&mut self.state) >> 11) as f64 / (1u64 << 53) as f64;
+                unit - 0.5
+            }
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32> AudioSignal<SAMPLE_RATE> for TestInput<SAMPLE_RATE> {}
+
+/// Drives an effect with a test signal and reports problems with its output,
+/// including a gain away from unity, using the default
+/// [`ValidationThresholds`].
+///
+/// `effect` builds the chain under test around the [`TestInput`] it is
+/// given. The gain is the RMS level of `duration_secs` of output over that
+/// of the same length of input, so effects that add delay or a tail should be
+/// run long enough to capture it.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::SignalExt;
+/// use earworm::core::{TestSignal, ValidationIssue, validate_effect};
+///
+/// // A stage that should be unity gain but drops 6 dB
+/// let report = validate_effect::<44100, _, _>(
+///     |input| input.gain(0.5),
+///     TestSignal::Sine { frequency: 441.0 },
+///     0.1,
+/// );
+///
+/// assert!(matches!(report.issues[0], ValidationIssue::NotUnityGain { .. }));
+/// println!("{}", report);
+/// ```
+pub fn validate_effect<const SAMPLE_RATE: u32, E, F>(
+    effect: F,
+    test: TestSignal,
+    duration_secs: f64,
+) -> ValidationReport
+where
+    E: AudioSignal<SAMPLE_RATE>,
+    F: FnOnce(TestInput<SAMPLE_RATE>) -> E,
+{
+    validate_effect_with(
+        effect,
+        test,
+        duration_secs,
+        &ValidationThresholds::default(),
+    )
+}
+
+/// Drives an effect with a test signal and reports problems with its output
+/// using custom thresholds.
+///
+/// # Arguments
+///
+/// * `effect` - Builds the chain under test around the test input
+/// * `test` - Test signal to feed the chain
+/// * `duration_secs` - How long to render, in seconds
+/// * `thresholds` - Levels used to flag clipping, DC offset, silence and gain
+pub fn validate_effect_with<const SAMPLE_RATE: u32, E, F>(
+    effect: F,
+    test: TestSignal,
+    duration_secs: f64,
+    thresholds: &ValidationThresholds,
+) -> ValidationReport
+where
+    E: AudioSignal<SAMPLE_RATE>,
+    F: FnOnce(TestInput<SAMPLE_RATE>) -> E,
+{
+    let samples = duration_to_samples(duration_secs, SAMPLE_RATE);
+    let mut chain = effect(TestInput::new(test));
+    let mut report = measure(samples, || chain.next_sample(), thresholds);
+
+    let mut input = TestInput::<SAMPLE_RATE>::new(test);
+    let input_energy: f64 = (0..samples).map(|_| input.next_sample().powi(2)).sum();
+    if input_energy > 0.0 {
+        let input_rms = (input_energy / samples as f64).sqrt();
+        let gain = report.rms / input_rms;
+        let gain_db = 20.0 * gain.log10();
+        if gain_db.abs() > thresholds.gain_tolerance_db {
+            report
+                .issues
+                .push(ValidationIssue::NotUnityGain { gain_db });
+        }
+        report.gain = Some(gain);
+    }
+    report
+}
+
+fn duration_to_samples(duration_secs: f64, sample_rate: u32) -> usize {
+    (duration_secs.max(0.0) * sample_rate as f64).round() as usize
+}
+
+/// Renders `samples` samples from `next` and collects the statistics.
+fn measure(
+    samples: usize,
+    mut next: impl FnMut() -> f64,
+    thresholds: &ValidationThresholds,
+) -> ValidationReport {
+    let mut non_finite = 0;
+    let mut first_non_finite = None;
+    let mut clipped = 0;
+    let mut peak: f64 = 0.0;
+    let mut sum = 0.0;
+    let mut sum_squares = 0.0;
+
+    for i in 0..samples {
+        let sample = next();
+        if !sample.is_finite() {
+            non_finite += 1;
+            first_non_finite.get_or_insert(i);
+            continue;
+        }
+        let magnitude = sample.abs();
+        if magnitude > thresholds.clip_level {
+            clipped += 1;
+        }
+        peak = peak.max(magnitude);
+        sum += sample;
+        sum_squares += sample * sample;
+    }
+
+    let finite = samples - non_finite;
+    let (dc_offset, rms) = if finite > 0 {
+        (sum / finite as f64, (sum_squares / finite as f64).sqrt())
+    } else {
+        (0.0, 0.0)
+    };
+
+    let mut issues = Vec::new();
+    if let Some(first_index) = first_non_finite {
+        issues.push(ValidationIssue::NonFinite {
+            count: non_finite,
+            first_index,
+        });
+    }
+    if clipped > 0 {
+        issues.push(ValidationIssue::Clipping {
+            count: clipped,
+            peak,
+        });
+    }
+    if dc_offset.abs() > thresholds.dc_offset {
+        issues.push(ValidationIssue::DcOffset(dc_offset));
+    }
+    if samples > 0 && peak < thresholds.silence {
+        issues.push(ValidationIssue::Silent);
+    }
+
+    ValidationReport {
+        samples,
+        peak,
+        rms,
+        dc_offset,
+        gain: None,
+        issues,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstantSignal, SignalExt, SineOscillator};
+
+    #[test]
+    fn test_clean_signal_is_ok() {
+        let mut osc = SineOscillator::<44100>::new(441.0).gain(0.5);
+        let report = validate(&mut osc, 0.1);
+
+        assert!(report.is_ok(), "{}", report);
+        assert_eq!(report.samples, 4410);
+        assert!((report.peak - 0.5).abs() < 1e-3);
+        assert!(report.dc_offset.abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_detects_clipping_and_dc() {
+        let mut patch = SineOscillator::<44100>::new(441.0).gain(1.5).offset(0.2);
+        let report = validate(&mut patch, 0.1);
+
+        assert!(matches!(report.issues[0], ValidationIssue::Clipping { .. }));
+        assert!(
+            matches!(report.issues[1], ValidationIssue::DcOffset(dc) if (dc - 0.2).abs() < 1e-3)
+        );
+    }
+
+    #[test]
+    fn test_detects_non_finite() {
+        let mut patch = ConstantSignal::<44100>(0.5).map(|x| x / 0.0);
+        let report = validate(&mut patch, 0.01);
+
+        assert_eq!(
+            report.issues,
+            vec![
+                ValidationIssue::NonFinite {
+                    count: 441,
+                    first_index: 0
+                },
+                ValidationIssue::Silent
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detects_silence() {
+        let mut patch = ConstantSignal::<44100>(0.0);
+        let report = validate(&mut patch, 0.01);
+        assert_eq!(report.issues, vec![ValidationIssue::Silent]);
+    }
+
+    #[test]
+    fn test_unity_effect_passes_every_test_signal() {
+        for test in [
+            TestSignal::Impulse,
+            TestSignal::Sine { frequency: 441.0 },
+            TestSignal::Noise,
+        ] {
+            let report = validate_effect::<44100, _, _>(|input| input, test, 0.1);
+            assert!(report.is_ok(), "{:?}: {}", test, report);
+            assert!((report.gain.unwrap() - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_reports_gain_away_from_unity() {
+        let report =
+            validate_effect::<44100, _, _>(|input| input.gain(0.5), TestSignal::Noise, 0.1);
+        assert!((report.gain.unwrap() - 0.5).abs() < 1e-12);
+        assert!(matches!(
+            report.issues[..],
+            [ValidationIssue::NotUnityGain { gain_db }] if (gain_db + 6.02).abs() < 0.01
+        ));
+        assert!(report.to_string().contains("-6.02 dB"));
+    }
+
+    #[test]
+    fn test_filter_gain_depends_on_test_signal() {
+        use crate::BiquadFilter;
+
+        let lowpass = |input: TestInput<44100>| BiquadFilter::lowpass(input, 1000.0, 0.707);
+        let passband =
+            validate_effect::<44100, _, _>(lowpass, TestSignal::Sine { frequency: 100.0 }, 0.2);
+        assert!(passband.is_ok(), "{}", passband);
+
+        let stopband =
+            validate_effect::<44100, _, _>(lowpass, TestSignal::Sine { frequency: 8000.0 }, 0.2);
+        assert!(stopband.gain.unwrap() < 0.05);
+        assert!(matches!(
+            stopband.issues[..],
+            [ValidationIssue::NotUnityGain { .. }]
+        ));
+    }
+
+    #[test]
+    fn test_impulse_gain_measures_the_whole_response() {
+        // Two equal taps: the impulse response carries twice the energy
+        let report = validate_effect::<44100, _, _>(
+            |input| {
+                let mut previous = 0.0;
+                input.map(move |x| {
+                    let y = x + previous;
+                    previous = x;
+                    y
+                })
+            },
+            TestSignal::Impulse,
+            0.01,
+        );
+        assert!((report.gain.unwrap() - 2f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_plain_validation_has_no_gain() {
+        let mut osc = SineOscillator::<44100>::new(441.0).gain(0.5);
+        assert_eq!(validate(&mut osc, 0.01).gain, None);
+    }
+
+    #[test]
+    fn test_custom_thresholds() {
+        let thresholds = ValidationThresholds {
+            clip_level: 0.25,
+            ..Default::default()
+        };
+        let mut osc = SineOscillator::<44100>::new(441.0).gain(0.5);
+        let report = validate_with(&mut osc, 0.1, &thresholds);
+        assert!(matches!(report.issues[0], ValidationIssue::Clipping { .. }));
+    }
+}
@@ -0,0 +1,202 @@
+//! Integer-only ADSR envelope.
+
+use super::{Q15, Q31};
+use crate::Signal;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    Idle,
+    Attack,
+    Decay,
+    Sustain,
+    Release,
+}
+
+/// A linear ADSR envelope that runs entirely in integer arithmetic.
+///
+/// The level is a Q31 value from 0 to [`Q31::MAX`]. Each stage moves it by a
+/// fixed integer step per sample, computed when the envelope is created (and,
+/// for the release, with one integer division at note-off), so generating
+/// samples needs no floating point. Segments are linear; use the
+/// floating-point [`ADSR`](crate::music::ADSR) for curved segments.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::fixed::{FixedAdsr, FixedOscillator, FixedWaveform};
+///
+/// let mut osc = FixedOscillator::<48000>::new(FixedWaveform::Square, 220.0);
+/// let mut env = FixedAdsr::new(0.01, 0.1, 0.6, 0.2, 48000);
+///
+/// env.note_on();
+/// let sample = osc.next_q15() * env.next_q15();
+/// env.note_off();
+/// while env.is_active() {
+///     let sample = osc.next_q15() * env.next_q15();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FixedAdsr {
+    stage: Stage,
+    level: i32,
+    attack_step: i32,
+    decay_step: i32,
+    sustain: i32,
+    release_samples: i32,
+    release_step: i32,
+}
+
+impl FixedAdsr {
+    /// Creates an idle envelope.
+    ///
+    /// # Arguments
+    ///
+    /// * `attack_time` - Attack time in seconds (0 or positive)
+    /// * `decay_time` - Decay time in seconds (0 or positive)
+    /// * `sustain_level` - Sustain level (0.0 to 1.0, will be clamped)
+    /// * `release_time` - Release time in seconds (0 or positive)
+    /// * `sample_rate` - Sample rate in Hz
+    pub fn new(
+        attack_time: f64,
+        decay_time: f64,
+        sustain_level: f64,
+        release_time: f64,
+        sample_rate: u32,
+    ) -> Self {
+        let samples = |seconds: f64| (seconds.max(0.0) * sample_rate as f64).round().max(1.0);
+        let sustain = Q31::from_f64(sustain_level.clamp(0.0, 1.0)).0;
+        Self {
+            stage: Stage::Idle,
+            level: 0,
+            attack_step: (i32::MAX as f64 / samples(attack_time)).ceil() as i32,
+            decay_step: ((i32::MAX - sustain) as f64 / samples(decay_time)).ceil() as i32,
+            sustain,
+            release_samples: samples(release_time).min(i32::MAX as f64) as i32,
+            release_step: 0,
+        }
+    }
+
+    /// Starts the attack from the current level.
+    pub fn note_on(&mut self) {
+        self.stage = Stage::Attack;
+    }
+
+    /// Starts the release from the current level.
+    pub fn note_off(&mut self) {
+        if self.stage == Stage::Idle {
+            return;
+        }
+        self.stage = Stage::Release;
+        // Round up so the release always reaches zero in time
+        self.release_step = (self.level + self.release_samples - 1) / self.release_samples;
+        self.release_step = self.release_step.max(1);
+    }
+
+    /// Returns `true` until the release has finished.
+    pub fn is_active(&self) -> bool {
+        self.stage != Stage::Idle
+    }
+
+    /// Silences the envelope immediately.
+    pub fn reset(&mut self) {
+        self.stage = Stage::Idle;
+        self.level = 0;
+    }
+
+    /// Generates the next level as Q31.
+    pub fn next_q31(&mut self) -> Q31 {
+        let output = self.level;
+        match self.stage {
+            Stage::Idle | Stage::Sustain => {}
+            Stage::Attack => {
+                self.level = self.level.saturating_add(self.attack_step);
+                if self.level == i32::MAX {
+                    self.stage = Stage::Decay;
+                }
+            }
+            Stage::Decay => {
+                self.level = (self.level - self.decay_step).max(self.sustain);
+                if self.level == self.sustain {
+                    self.stage = Stage::Sustain;
+                }
+            }
+            Stage::Release => {
+                self.level = (self.level - self.release_step).max(0);
+                if self.level == 0 {
+                    self.stage = Stage::Idle;
+                }
+            }
+        }
+        Q31(output)
+    }
+
+    /// Generates the next level as Q15.
+    pub fn next_q15(&mut self) -> Q15 {
+        self.next_q31().to_q15()
+    }
+}
+
+impl Signal for FixedAdsr {
+    fn next_sample(&mut self) -> f64 {
+        self.next_q31().to_f64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_lengths() {
+        // 10-sample attack, 10-sample decay to 0.5, 20-sample release
+        let mut env = FixedAdsr::new(0.01, 0.01, 0.5, 0.02, 1000);
+        env.note_on();
+        let attack: Vec<f64> = (0..11).map(|_| env.next_sample()).collect();
+        assert_eq!(attack[0], 0.0);
+        assert!((attack[5] - 0.5).abs() < 1e-6);
+        assert!((attack[10] - 1.0).abs() < 1e-6);
+
+        for _ in 0..10 {
+            env.next_sample();
+        }
+        assert!((env.next_sample() - 0.5).abs() < 1e-6);
+        assert!((env.next_sample() - 0.5).abs() < 1e-6);
+
+        env.note_off();
+        let release: Vec<f64> = (0..21).map(|_| env.next_sample()).collect();
+        assert!(release.windows(2).all(|w| w[1] < w[0]));
+        assert_eq!(release[20], 0.0);
+        assert!(!env.is_active());
+    }
+
+    #[test]
+    fn test_release_during_attack_and_retrigger() {
+        let mut env = FixedAdsr::new(0.1, 0.1, 0.8, 0.005, 1000);
+        env.note_on();
+        for _ in 0..50 {
+            env.next_q31();
+        }
+        env.note_off();
+        for _ in 0..6 {
+            env.next_q31();
+        }
+        assert!(!env.is_active());
+        assert_eq!(env.next_q31(), Q31::ZERO);
+
+        env.note_on();
+        env.next_q31();
+        assert!(env.next_q31() > Q31::ZERO);
+    }
+
+    #[test]
+    fn test_zero_times_jump() {
+        let mut env = FixedAdsr::new(0.0, 0.0, 0.25, 0.0, 1000);
+        env.note_on();
+        env.next_q31();
+        assert_eq!(env.next_q31(), Q31::MAX);
+        assert_eq!(env.next_q15(), Q15::from_f64(0.25));
+        env.note_off();
+        env.next_q31();
+        assert_eq!(env.next_q31(), Q31::ZERO);
+    }
+}
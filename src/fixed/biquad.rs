@@ -0,0 +1,168 @@
+//! Integer-only biquad filter.
+
+use super::{Q15, Q31};
+use crate::synthesis::filters::{FilterType, biquad_coefficients};
+
+/// Fractional bits of the coefficients (Q2.30, covering [-2.0, 2.0)).
+const COEFF_BITS: u32 = 30;
+
+/// A biquad filter that processes Q15 or Q31 samples in integer arithmetic.
+///
+/// Coefficients come from the same cookbook formulas as
+/// [`BiquadFilter`](crate::BiquadFilter) and are stored in Q2.30, so
+/// computing them needs floating point once, in [`new`](Self::new) or
+/// [`set_parameters`](Self::set_parameters); filtering itself is integer
+/// multiply-accumulate only. Feedback coefficients must stay below 2.0 in
+/// magnitude, which holds for every stable biquad.
+///
+/// Unlike [`BiquadFilter`](crate::BiquadFilter), this filter does not wrap a
+/// source: pass each sample to [`process_q15`](Self::process_q15) or
+/// [`process_q31`](Self::process_q31).
+///
+/// # Examples
+///
+/// ```
+/// use earworm::FilterType;
+/// use earworm::fixed::{FixedBiquad, FixedOscillator, FixedWaveform};
+///
+/// let mut osc = FixedOscillator::<48000>::new(FixedWaveform::Saw, 110.0);
+/// let mut filter = FixedBiquad::new(FilterType::LowPass, 800.0, 0.707, 48000);
+/// let sample = filter.process_q15(osc.next_q15());
+/// ```
+#[derive(Debug, Clone)]
+pub struct FixedBiquad {
+    filter_type: FilterType,
+    // b0, b1, b2, a1, a2 in Q2.30
+    coefficients: [i64; 5],
+    x1: i64,
+    x2: i64,
+    y1: i64,
+    y2: i64,
+}
+
+impl FixedBiquad {
+    /// Creates a filter with cleared state.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter_type` - Filter response
+    /// * `cutoff` - Cutoff or center frequency in Hz
+    /// * `q` - Q factor (resonance)
+    /// * `sample_rate` - Sample rate in Hz
+    pub fn new(filter_type: FilterType, cutoff: f64, q: f64, sample_rate: u32) -> Self {
+        let mut filter = Self {
+            filter_type,
+            coefficients: [0; 5],
+            x1: 0,
+            x2: 0,
+            y1: 0,
+            y2: 0,
+        };
+        filter.set_parameters(cutoff, q, sample_rate);
+        filter
+    }
+
+    /// Recomputes the coefficients for a new cutoff and Q, keeping the state.
+    pub fn set_parameters(&mut self, cutoff: f64, q: f64, sample_rate: u32) {
+        let scale = (1u64 << COEFF_BITS) as f64;
+        let coefficients = biquad_coefficients(self.filter_type, cutoff, q, sample_rate);
+        for (fixed, value) in self.coefficients.iter_mut().zip(coefficients) {
+            *fixed = (value * scale).round() as i64;
+        }
+    }
+
+    /// Returns the filter response.
+    pub fn filter_type(&self) -> FilterType {
+        self.filter_type
+    }
+
+    /// Clears the filter state.
+    pub fn reset(&mut self) {
+        self.x1 = 0;
+        self.x2 = 0;
+        self.y1 = 0;
+        self.y2 = 0;
+    }
+
+    /// Filters one Q31 sample, saturating the output.
+    pub fn process_q31(&mut self, input: Q31) -> Q31 {
+        let [b0, b1, b2, a1, a2] = self.coefficients;
+        let x = input.0 as i64;
+        // Each product is shifted back to Q31 before summing so the
+        // accumulator cannot overflow 64 bits
+        let acc = ((b0 * x) >> COEFF_BITS)
+            + ((b1 * self.x1) >> COEFF_BITS)
+            + ((b2 * self.x2) >> COEFF_BITS)
+            - ((a1 * self.y1) >> COEFF_BITS)
+            - ((a2 * self.y2) >> COEFF_BITS);
+        let y = acc.clamp(i32::MIN as i64, i32::MAX as i64);
+
+        self.x2 = self.x1;
+        self.x1 = x;
+        self.y2 = self.y1;
+        self.y1 = y;
+        Q31(y as i32)
+    }
+
+    /// Filters one Q15 sample.
+    ///
+    /// The filter runs at Q31 internally, so its state keeps the extra
+    /// precision between samples.
+    pub fn process_q15(&mut self, input: Q15) -> Q15 {
+        self.process_q31(input.to_q31()).to_q15()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BiquadFilter, SawtoothOscillator, Signal, SignalExt};
+
+    #[test]
+    fn test_matches_float_biquad() {
+        for filter_type in [
+            FilterType::LowPass,
+            FilterType::HighPass,
+            FilterType::BandPass,
+        ] {
+            let mut fixed = FixedBiquad::new(filter_type, 1200.0, 2.0, 48000);
+            let mut float = BiquadFilter::new(
+                SawtoothOscillator::<48000>::new(220.0).gain(0.5),
+                1200.0,
+                2.0,
+                filter_type,
+            );
+            let mut input = SawtoothOscillator::<48000>::new(220.0);
+            for i in 0..4800 {
+                let x = Q31::from_f64(input.next_sample() * 0.5);
+                let y = fixed.process_q31(x).to_f64();
+                let expected = float.next_sample();
+                assert!(
+                    (y - expected).abs() < 1e-6,
+                    "{:?} sample {}",
+                    filter_type,
+                    i
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_q15_lowpass_blocks_nyquist() {
+        let mut filter = FixedBiquad::new(FilterType::LowPass, 1000.0, 0.707, 48000);
+        let mut last = Q15::ZERO;
+        for i in 0..2000 {
+            let input = if i % 2 == 0 { Q15::MAX } else { Q15::MIN };
+            last = filter.process_q15(input);
+        }
+        assert!(last.to_f64().abs() < 1e-3);
+
+        // DC passes at unity gain
+        filter.reset();
+        let half = Q15::from_f64(0.5);
+        for _ in 0..2000 {
+            last = filter.process_q15(half);
+        }
+        assert!((last.to_f64() - 0.5).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,46 @@
+//! Integer fixed-point DSP for targets without a fast FPU.
+//!
+//! Many microcontrollers have no double-precision FPU, or none at all, so the
+//! crate's `f64` signal graph runs in slow software emulation there. This
+//! module provides integer-only versions of the core building blocks, working
+//! on [`Q15`] (16-bit) and [`Q31`] (32-bit) samples:
+//!
+//! - [`FixedOscillator`]: sine, saw, square and triangle from a 32-bit phase
+//! - [`FixedBiquad`]: the cookbook biquad filters with Q2.30 coefficients
+//! - [`FixedAdsr`]: a linear ADSR envelope
+//!
+//! Floating point appears only when setting things up (converting Hz, seconds
+//! and Q into integer steps and coefficients), never per sample. Each type
+//! also implements [`Signal`](crate::Signal) or offers conversions, so
+//! fixed-point output can be compared against the floating-point originals.
+//!
+//! Requires the `fixed-point` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use earworm::FilterType;
+//! use earworm::fixed::{FixedAdsr, FixedBiquad, FixedOscillator, FixedWaveform, Q15};
+//!
+//! const SAMPLE_RATE: u32 = 32000;
+//!
+//! let mut osc = FixedOscillator::<SAMPLE_RATE>::new(FixedWaveform::Saw, 110.0);
+//! let mut filter = FixedBiquad::new(FilterType::LowPass, 1500.0, 2.0, SAMPLE_RATE);
+//! let mut env = FixedAdsr::new(0.005, 0.2, 0.5, 0.3, SAMPLE_RATE);
+//!
+//! env.note_on();
+//! let mut block = [Q15::ZERO; 32];
+//! for sample in block.iter_mut() {
+//!     *sample = filter.process_q15(osc.next_q15()) * env.next_q15();
+//! }
+//! ```
+
+mod adsr;
+mod biquad;
+mod oscillator;
+mod q;
+
+pub use adsr::FixedAdsr;
+pub use biquad::FixedBiquad;
+pub use oscillator::{FixedOscillator, FixedWaveform};
+pub use q::{Q15, Q31};
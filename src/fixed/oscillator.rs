@@ -0,0 +1,233 @@
+//! Integer-only oscillator.
+
+use super::{Q15, Q31};
+use crate::core::Pitched;
+use crate::{AudioSignal, Signal};
+
+/// sin(π/2 · x) polynomial coefficients in Q2.30, from the same minimax fit
+/// as the floating-point fast sine (maximum error about 6e-7).
+const SINE_K1: i64 = 1_686_624_005;
+const SINE_K3: i64 = -693_522_166;
+const SINE_K5: i64 = 85_291_978;
+const SINE_K7: i64 = -4_652_626;
+
+/// Shape of a [`FixedOscillator`]'s output.
+///
+/// Each waveform starts at the same point in its cycle as the matching
+/// floating-point oscillator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixedWaveform {
+    /// Sine wave, starting at 0 and rising
+    Sine,
+    /// Rising sawtooth, starting at -1
+    Saw,
+    /// Square wave, +1 for the first half of the cycle
+    Square,
+    /// Triangle wave, starting at -1
+    Triangle,
+}
+
+/// An oscillator that runs entirely in integer arithmetic.
+///
+/// The phase is a 32-bit fraction of a cycle that wraps on overflow, and
+/// every waveform, including the sine, is computed with integer operations
+/// only. Floating point is used only to convert a frequency in Hz into a
+/// phase increment, in [`new`](Self::new) and
+/// [`set_frequency`](Pitched::set_frequency); use
+/// [`set_increment`](Self::set_increment) to retune without it.
+///
+/// Read samples with [`next_q15`](Self::next_q15) or
+/// [`next_q31`](Self::next_q31). The [`Signal`] implementation converts to
+/// `f64`, so the oscillator can also be auditioned in a normal graph.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::fixed::{FixedOscillator, FixedWaveform, Q15};
+///
+/// let mut osc = FixedOscillator::<48000>::new(FixedWaveform::Saw, 440.0);
+/// let mut buffer = [Q15::ZERO; 64];
+/// for sample in buffer.iter_mut() {
+///     *sample = osc.next_q15();
+/// }
+/// assert_eq!(buffer[0], Q15::MIN);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FixedOscillator<const SAMPLE_RATE: u32> {
+    waveform: FixedWaveform,
+    phase: u32,
+    increment: u32,
+}
+
+impl<const SAMPLE_RATE: u32> FixedOscillator<SAMPLE_RATE> {
+    /// Creates an oscillator at phase 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `waveform` - Output shape
+    /// * `frequency` - Frequency in Hz; negative frequencies run backwards
+    pub fn new(waveform: FixedWaveform, frequency: f64) -> Self {
+        Self {
+            waveform,
+            phase: 0,
+            increment: frequency_to_increment(frequency, SAMPLE_RATE),
+        }
+    }
+
+    /// Returns the waveform.
+    pub fn waveform(&self) -> FixedWaveform {
+        self.waveform
+    }
+
+    /// Returns the phase increment, in 2^-32 cycles per sample.
+    pub fn increment(&self) -> u32 {
+        self.increment
+    }
+
+    /// Sets the phase increment directly, in 2^-32 cycles per sample.
+    ///
+    /// The frequency in Hz is `increment * SAMPLE_RATE / 2^32`.
+    pub fn set_increment(&mut self, increment: u32) {
+        self.increment = increment;
+    }
+
+    /// Returns the current phase, in 2^-32 cycles.
+    pub fn phase(&self) -> u32 {
+        self.phase
+    }
+
+    /// Restarts the cycle from phase 0.
+    pub fn reset(&mut self) {
+        self.phase = 0;
+    }
+
+    /// Generates the next sample as Q31.
+    pub fn next_q31(&mut self) -> Q31 {
+        let phase = self.phase;
+        self.phase = self.phase.wrapping_add(self.increment);
+        Q31(match self.waveform {
+            FixedWaveform::Sine => sine(phase),
+            FixedWaveform::Saw => phase.wrapping_sub(0x8000_0000) as i32,
+            FixedWaveform::Square => {
+                if phase < 0x8000_0000 {
+                    i32::MAX
+                } else {
+                    i32::MIN
+                }
+            }
+            FixedWaveform::Triangle => {
+                let ramp = if phase < 0x8000_0000 { phase } else { !phase };
+                (ramp << 1).wrapping_sub(0x8000_0000) as i32
+            }
+        })
+    }
+
+    /// Generates the next sample as Q15.
+    pub fn next_q15(&mut self) -> Q15 {
+        self.next_q31().to_q15()
+    }
+}
+
+impl<const SAMPLE_RATE: u32> Signal for FixedOscillator<SAMPLE_RATE> {
+    fn next_sample(&mut self) -> f64 {
+        self.next_q31().to_f64()
+    }
+}
+
+impl<const SAMPLE_RATE: u32> AudioSignal<SAMPLE_RATE> for FixedOscillator<SAMPLE_RATE> {}
+
+impl<const SAMPLE_RATE: u32> Pitched for FixedOscillator<SAMPLE_RATE> {
+    fn set_frequency(&mut self, frequency: f64) {
+        self.increment = frequency_to_increment(frequency, SAMPLE_RATE);
+    }
+
+    fn frequency(&self) -> f64 {
+        self.increment as i32 as f64 * SAMPLE_RATE as f64 / 4_294_967_296.0
+    }
+}
+
+/// Converts Hz to a 32-bit phase increment; negative frequencies wrap to
+/// increments that run the phase backwards.
+fn frequency_to_increment(frequency: f64, sample_rate: u32) -> u32 {
+    let cycles = (frequency / sample_rate as f64).rem_euclid(1.0);
+    (cycles * 4_294_967_296.0).round() as u64 as u32
+}
+
+/// Computes sin(2π · phase) in Q31 from a 32-bit phase.
+fn sine(phase: u32) -> i32 {
+    // Signed phase in [-0.5, 0.5) cycles, folded into [-0.25, 0.25]
+    let signed = phase as i32 as i64;
+    let quarter = if signed > 1 << 30 {
+        (1 << 31) - signed
+    } else if signed < -(1 << 30) {
+        -(1 << 31) - signed
+    } else {
+        signed
+    };
+    // x = 4 · quarter in Q31 covers [-1.0, 1.0]
+    let x = quarter << 1;
+    let x2 = (x * x) >> 31;
+    let mut acc = SINE_K7;
+    for k in [SINE_K5, SINE_K3, SINE_K1] {
+        acc = k + ((acc * x2) >> 31);
+    }
+    // Q30 result to Q31
+    ((acc * x) >> 30).clamp(i32::MIN as i64, i32::MAX as i64) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SawtoothOscillator, SineOscillator, SquareOscillator, TriangleOscillator};
+
+    fn assert_matches(mut fixed: FixedOscillator<48000>, mut float: impl Signal, tolerance: f64) {
+        for i in 0..2000 {
+            let a = fixed.next_sample();
+            let b = float.next_sample();
+            assert!((a - b).abs() < tolerance, "sample {}: {} vs {}", i, a, b);
+        }
+    }
+
+    #[test]
+    fn test_waveforms_match_float_oscillators() {
+        assert_matches(
+            FixedOscillator::new(FixedWaveform::Sine, 440.0),
+            SineOscillator::<48000>::new(440.0),
+            1e-5,
+        );
+        assert_matches(
+            FixedOscillator::new(FixedWaveform::Saw, 375.0),
+            SawtoothOscillator::<48000>::new(375.0),
+            1e-6,
+        );
+        assert_matches(
+            FixedOscillator::new(FixedWaveform::Square, 375.0),
+            SquareOscillator::<48000>::new(375.0),
+            1e-6,
+        );
+        assert_matches(
+            FixedOscillator::new(FixedWaveform::Triangle, 375.0),
+            TriangleOscillator::<48000>::new(375.0),
+            1e-6,
+        );
+    }
+
+    #[test]
+    fn test_sine_quarter_points() {
+        let mut osc = FixedOscillator::<4>::new(FixedWaveform::Sine, 1.0);
+        assert_eq!(osc.next_q15(), Q15::ZERO);
+        assert_eq!(osc.next_q15(), Q15::MAX);
+        assert_eq!(osc.next_q15(), Q15::ZERO);
+        assert_eq!(osc.next_q15(), Q15::MIN);
+    }
+
+    #[test]
+    fn test_frequency_and_negative_increments() {
+        let mut osc = FixedOscillator::<48000>::new(FixedWaveform::Saw, 1000.0);
+        assert!((osc.frequency() - 1000.0).abs() < 1e-3);
+        osc.set_frequency(-12000.0);
+        assert!((osc.frequency() + 12000.0).abs() < 1e-3);
+        osc.next_q31();
+        assert_eq!(osc.phase(), 0xC000_0000);
+    }
+}
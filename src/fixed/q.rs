@@ -0,0 +1,148 @@
+//! Q15 and Q31 fixed-point sample formats.
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+macro_rules! q_format {
+    ($name:ident, $int:ty, $wide:ty, $frac_bits:expr, $doc:expr) => {
+        #[doc = $doc]
+        ///
+        /// Arithmetic saturates instead of wrapping, so overdriving a
+        /// calculation clips like an analog stage rather than flipping sign.
+        /// Multiplication rounds to nearest.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+        pub struct $name(pub $int);
+
+        impl $name {
+            /// Number of fractional bits.
+            pub const FRAC_BITS: u32 = $frac_bits;
+            /// 0.0
+            pub const ZERO: Self = Self(0);
+            /// The largest value, one step below 1.0
+            pub const MAX: Self = Self(<$int>::MAX);
+            /// -1.0
+            pub const MIN: Self = Self(<$int>::MIN);
+
+            /// Converts from floating point, rounding to nearest and
+            /// saturating outside `[-1.0, 1.0)`.
+            pub fn from_f64(value: f64) -> Self {
+                let scaled = (value * (1u64 << $frac_bits) as f64).round();
+                Self(scaled.clamp(<$int>::MIN as f64, <$int>::MAX as f64) as $int)
+            }
+
+            /// Converts to floating point.
+            pub fn to_f64(self) -> f64 {
+                self.0 as f64 / (1u64 << $frac_bits) as f64
+            }
+
+            /// Returns the raw integer representation.
+            pub fn to_bits(self) -> $int {
+                self.0
+            }
+
+            fn saturate(value: $wide) -> Self {
+                Self(value.clamp(<$int>::MIN as $wide, <$int>::MAX as $wide) as $int)
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self(self.0.saturating_add(rhs.0))
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self(self.0.saturating_sub(rhs.0))
+            }
+        }
+
+        impl Mul for $name {
+            type Output = Self;
+
+            fn mul(self, rhs: Self) -> Self {
+                let product = self.0 as $wide * rhs.0 as $wide;
+                // Only -1.0 * -1.0 falls outside the range
+                Self::saturate((product + (1 << ($frac_bits - 1))) >> $frac_bits)
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self(self.0.saturating_neg())
+            }
+        }
+    };
+}
+
+q_format!(
+    Q15,
+    i16,
+    i32,
+    15,
+    "A Q1.15 fixed-point sample: 16 bits covering `[-1.0, 1.0)` in steps of 2^-15."
+);
+q_format!(
+    Q31,
+    i32,
+    i64,
+    31,
+    "A Q1.31 fixed-point sample: 32 bits covering `[-1.0, 1.0)` in steps of 2^-31."
+);
+
+impl Q15 {
+    /// Widens to Q31 without loss.
+    pub fn to_q31(self) -> Q31 {
+        Q31((self.0 as i32) << 16)
+    }
+}
+
+impl Q31 {
+    /// Narrows to Q15, rounding to nearest.
+    pub fn to_q15(self) -> Q15 {
+        Q15::saturate(((self.0 as i64 + (1 << 15)) >> 16) as i32)
+    }
+}
+
+impl From<Q15> for Q31 {
+    fn from(value: Q15) -> Self {
+        value.to_q31()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions_round_trip() {
+        for value in [-1.0, -0.5, 0.0, 0.25, 0.999] {
+            assert!((Q15::from_f64(value).to_f64() - value).abs() <= 0.5 / 32768.0);
+            assert!((Q31::from_f64(value).to_f64() - value).abs() < 1e-9);
+        }
+        assert_eq!(Q15::from_f64(1.0), Q15::MAX);
+        assert_eq!(Q31::from_f64(-3.0), Q31::MIN);
+        assert_eq!(Q15::from_f64(0.5).to_q31(), Q31::from_f64(0.5));
+        assert_eq!(Q31::from_f64(-0.25).to_q15(), Q15::from_f64(-0.25));
+        assert_eq!(Q31::MAX.to_q15(), Q15::MAX);
+    }
+
+    #[test]
+    fn test_arithmetic_saturates() {
+        let half = Q15::from_f64(0.5);
+        assert_eq!(half * half, Q15::from_f64(0.25));
+        assert_eq!(Q15::MIN * Q15::MIN, Q15::MAX);
+        assert_eq!(Q15::MAX + half, Q15::MAX);
+        assert_eq!(Q15::MIN - half, Q15::MIN);
+        assert_eq!(-Q15::MIN, Q15::MAX);
+
+        let third = Q31::from_f64(1.0 / 3.0);
+        assert!(((third * Q31::from_f64(-0.75)).to_f64() + 0.25).abs() < 1e-9);
+        assert_eq!(Q31::MAX + third, Q31::MAX);
+    }
+}
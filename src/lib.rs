@@ -7,6 +7,17 @@
 //!
 //! - `synth` (default): Enables synthesis components (oscillators, filters, effects, envelopes, noise)
 //! - `music`: Enables music theory abstractions (notes, scales, sequencers)
+//! - `playback`: Enables real-time audio output through cpal
+//! - `jack`: The JACK host for playback, with a choice of client name and port connections (enables `playback`)
+//! - `parallel`: Multi-threaded offline rendering of voices, stems and other independent signals (uses rayon)
+//! - `render`: Offline rendering of signals to WAV files
+//! - `flac`: FLAC export for offline renders (enables `render`)
+//! - `midi`: Parsing raw MIDI input and routing it to voices and parameters, and importing and exporting MIDI files
+//! - `midi-output`: Sending notes and MIDI clock to external hardware through midir (enables `midi`)
+//! - `simd`: Vectorized block processing for linear-interpolated wavetable oscillators
+//! - `fixed-point`: Integer Q15/Q31 oscillators, biquad filter and envelope for targets without a fast FPU
+//! - `soundfont`: Loading SoundFont 2 banks as key-zoned sample instruments
+//! - `bevy`: A Bevy plugin with spatial sound emitters and a music clock on game time (enables `music`)
 
 // Core module - always compiled
 pub mod core;
@@ -19,27 +30,60 @@ pub mod synthesis;
 #[cfg(feature = "music")]
 pub mod music;
 
+// MIDI module - requires midi feature
+#[cfg(feature = "midi")]
+pub mod midi;
+
+// Playback module - requires playback feature
+#[cfg(feature = "playback")]
+pub mod playback;
+
+// Render module - requires render feature
+#[cfg(feature = "render")]
+pub mod render;
+
+// Fixed-point module - requires fixed-point feature
+#[cfg(feature = "fixed-point")]
+pub mod fixed;
+
+// SoundFont module - requires soundfont feature
+#[cfg(feature = "soundfont")]
+pub mod soundfont;
+
+// Bevy module - requires bevy feature
+#[cfg(feature = "bevy")]
+pub mod bevy;
+
 // Re-export core types at the crate root (always available)
 pub use core::{
-    Abs, Add, AudioSignal, Clamp, ConstantSignal, Crossfade, Gain, Gate, Invert, Map, Max, Min,
-    Mix2, Mix3, Mix4, Multiply, Offset, Param, Pitched, Signal, SignalExt, SignalIterator,
+    Abs, Add, AudioFrameSignal, AudioSignal, Broadcast, ChannelMap, ChannelStrip, Clamp,
+    ConstantSignal, ControlRate, Crossfade, DebugGuard, Downmix, FrameProvider, FrameSignal,
+    FrameSignalExt, Gain, Gate, Invert, Map, Max, Min, Mix2, Mix3, Mix4, MixMode, Mixer,
+    MixerOutputs, Multiply, Offset, Param, Pitched, Remap, Route, Signal, SignalExt,
+    SignalIterator,
 };
 
 // Re-export synthesis types (only with synth feature)
 #[cfg(feature = "synth")]
 pub use synthesis::{
-    AudioSignalExt, BiquadFilter, Bitcrusher, Compressor, Curve, Delay, Distortion, FilterType,
-    InterpolationMode, Limiter, Oscillator, PinkNoise, PulseOscillator, SawtoothOscillator,
-    SineOscillator, SquareOscillator, Tremolo, TriangleOscillator, Vibrato, WavetableOscillator,
-    WhiteNoise,
+    AmpSim, AudioSignalExt, BiquadFilter, Bitcrusher, CabModel, CabSim, CalibrationNoise,
+    ChannelIdent, Compressor, CompressorBand, Curve, DcBlocker, Delay, Diffuser, Distortion,
+    EqBand, FilterType, Glide, HarmonicTremolo, InterpolationMode, LadderFilter, Lfo, LfoRetrigger,
+    LfoTrigger, LfoWaveform, Limiter, LoopMode, MacroControl, ModulatedOscillator, MultiSampler,
+    MultibandCompressor, Octaver, OnePoleHighpass, OnePoleLowpass, Oscillator, ParametricEq,
+    PhaseAccumulator, PingPongDelay, PinkNoise, PlateReverb, PluckedString, PulseOscillator,
+    ReferenceTone, Reverb, Sampler, SawtoothOscillator, SineOscillator, SinePrecision,
+    SpringReverb, SquareOscillator, SyncOscillator, ThreeBandCrossover, ToneStack, Tremolo,
+    TriangleOscillator, Vibrato, WavetableOscillator, WhiteNoise,
 };
 
 // Re-export music types (only with music feature)
 #[cfg(feature = "music")]
 pub use music::{
-    ADSR, AHD, AR, Envelope, EnvelopeState, Metronome, Pattern, PlayState, Sequencer,
-    StealingStrategy, Voice, VoiceAllocator,
-    core::{Note, NoteEvent, ParseError, Pitch},
+    ADSR, AHD, AR, Envelope, EnvelopeState, FmAlgorithm, FmOperator, FmVoice, Looper, LooperState,
+    Metronome, ModEnvelope, NoteSources, Pattern, PlayState, Sequencer, Slicer, StealingStrategy,
+    Voice, VoiceAllocator,
+    core::{Chord, ChordQuality, DegreeEvent, Key, Note, NoteEvent, ParseError, Pitch, Scale},
 };
 
 // Re-export the note! macro (only with music feature)
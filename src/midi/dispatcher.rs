@@ -0,0 +1,442 @@
+//! Routing MIDI events to voices and parameters.
+
+use super::event::{MidiEvent, MidiParseError};
+use crate::core::{ParamHandle, Snapshot};
+use crate::music::NoteTarget;
+
+/// Controller number of the "all notes off" channel mode message.
+const ALL_NOTES_OFF: u8 = 123;
+
+/// Controller number of the bank select MSB.
+const BANK_SELECT_MSB: u8 = 0;
+
+/// Controller number of the bank select LSB.
+const BANK_SELECT_LSB: u8 = 32;
+
+/// How bank select controllers choose the bank of a program change.
+///
+/// Bank select only takes effect with the next program change, as on
+/// hardware synths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BankSelect {
+    /// Bank = CC 0 * 128 + CC 32, for 16384 banks (the General MIDI 2 scheme)
+    #[default]
+    MsbLsb,
+    /// Bank = CC 0; CC 32 is an ordinary controller
+    Msb,
+    /// Bank = CC 32; CC 0 is an ordinary controller
+    Lsb,
+    /// Always bank 0; CC 0 and 32 are ordinary controllers
+    Ignore,
+}
+
+impl BankSelect {
+    fn uses(self, controller: u8) -> bool {
+        match self {
+            BankSelect::MsbLsb => controller == BANK_SELECT_MSB || controller == BANK_SELECT_LSB,
+            BankSelect::Msb => controller == BANK_SELECT_MSB,
+            BankSelect::Lsb => controller == BANK_SELECT_LSB,
+            BankSelect::Ignore => false,
+        }
+    }
+
+    fn bank(self, msb: u8, lsb: u8) -> u16 {
+        match self {
+            BankSelect::MsbLsb => msb as u16 * 128 + lsb as u16,
+            BankSelect::Msb => msb as u16,
+            BankSelect::Lsb => lsb as u16,
+            BankSelect::Ignore => 0,
+        }
+    }
+}
+
+/// A parameter driven by a 7-bit MIDI value.
+struct ControlMapping {
+    handle: ParamHandle,
+    min: f64,
+    max: f64,
+}
+
+impl ControlMapping {
+    fn set(&self, value: u8) {
+        let normalized = value as f64 / 127.0;
+        self.handle
+            .set(self.min + (self.max - self.min) * normalized);
+    }
+}
+
+/// Routes MIDI events to a [`NoteTarget`] and to parameter handles.
+///
+/// Notes go to the target; controllers, pitch bend and aftertouch set the
+/// [`ParamHandle`]s they are mapped to. Events on other channels (when a
+/// channel is set) and unmapped controllers are ignored. Controller 123 (all
+/// notes off) releases every note.
+///
+/// Program changes switch presets: the bank chosen by the bank select
+/// controllers (see [`BankSelect`]) and the program number pick a
+/// [`Snapshot`] from [`map_presets`](Self::map_presets) to recall, and are
+/// passed to the [`on_program_change`](Self::on_program_change) callback for
+/// anything else, such as swapping instruments.
+///
+/// The dispatcher never blocks or allocates while dispatching, so it can run
+/// in the MIDI callback or on the audio thread.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::core::{ParamHandle, Snapshot};
+/// use earworm::midi::MidiDispatcher;
+/// use earworm::music::VoiceAllocator;
+/// use earworm::{ADSR, SineOscillator};
+///
+/// let mut synth = VoiceAllocator::<44100, 8, _, _>::new(|| {
+///     let osc = SineOscillator::<44100>::new(440.0);
+///     (osc, ADSR::new(0.01, 0.1, 0.7, 0.3, 44100.0))
+/// });
+///
+/// // Two patches for a filter, in bank 1
+/// let cutoff = ParamHandle::new(1000.0);
+/// let resonance = ParamHandle::new(0.7);
+/// let mut presets = vec![Snapshot::from_values([1000.0, 0.7]); 128];
+/// presets.push(Snapshot::from_values([300.0, 4.0]));
+/// presets.push(Snapshot::from_values([6000.0, 1.0]));
+///
+/// let mut dispatcher =
+///     MidiDispatcher::new().map_presets(vec![cutoff.clone(), resonance.clone()], presets);
+///
+/// // Bank 1 (CC 0 = 0, CC 32 = 1), program 2
+/// dispatcher.dispatch_bytes(&[0xB0, 0, 0], &mut synth).unwrap();
+/// dispatcher.dispatch_bytes(&[0xB0, 32, 1], &mut synth).unwrap();
+/// dispatcher.dispatch_bytes(&[0xC0, 1], &mut synth).unwrap();
+/// assert_eq!(cutoff.get(), 6000.0);
+/// assert_eq!(dispatcher.program(), (1, 1));
+/// ```
+#[derive(Default)]
+pub struct MidiDispatcher {
+    channel: Option<u8>, // None = omni
+    controls: Vec<(u8, ControlMapping)>,
+    pitch_bend: Option<(ParamHandle, f64)>, // handle and range in semitones
+    aftertouch: Option<ControlMapping>,
+    bank_select: BankSelect,
+    bank_msb: u8,
+    bank_lsb: u8,
+    program: (u16, u8), // bank and program last selected
+    presets: Option<(Vec<ParamHandle>, Vec<Snapshot>)>, // indexed by bank * 128 + program
+    on_program_change: Option<Box<dyn FnMut(u16, u8) + Send>>,
+}
+
+impl MidiDispatcher {
+    /// Creates a dispatcher listening on all channels with nothing mapped.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only responds to events on `channel` (0-15).
+    pub fn with_channel(mut self, channel: u8) -> Self {
+        self.channel = Some(channel & 0x0F);
+        self
+    }
+
+    /// Maps a controller to a parameter.
+    ///
+    /// # Arguments
+    ///
+    /// * `controller` - Controller number (e.g. 1 = mod wheel, 74 = brightness)
+    /// * `handle` - Parameter to set
+    /// * `min` - Parameter value at controller value 0
+    /// * `max` - Parameter value at controller value 127
+    pub fn map_cc(mut self, controller: u8, handle: ParamHandle, min: f64, max: f64) -> Self {
+        self.controls
+            .push((controller, ControlMapping { handle, min, max }));
+        self
+    }
+
+    /// Maps the pitch wheel to a parameter holding the bend in semitones.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - Parameter to set, from `-range` to `+range`
+    /// * `range` - Bend at full wheel deflection in semitones (commonly 2)
+    pub fn map_pitch_bend(mut self, handle: ParamHandle, range: f64) -> Self {
+        self.pitch_bend = Some((handle, range));
+        self
+    }
+
+    /// Maps channel aftertouch to a parameter.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - Parameter to set
+    /// * `min` - Parameter value with no pressure
+    /// * `max` - Parameter value at full pressure
+    pub fn map_aftertouch(mut self, handle: ParamHandle, min: f64, max: f64) -> Self {
+        self.aftertouch = Some(ControlMapping { handle, min, max });
+        self
+    }
+
+    /// Sets how the bank select controllers choose the bank (default
+    /// [`BankSelect::MsbLsb`]).
+    pub fn with_bank_select(mut self, scheme: BankSelect) -> Self {
+        self.bank_select = scheme;
+        self
+    }
+
+    /// Maps program changes to presets.
+    ///
+    /// A program change recalls `presets[bank * 128 + program]` onto
+    /// `handles`; programs past the end of `presets` leave the parameters
+    /// alone. With [`BankSelect::Ignore`] only the first 128 presets are
+    /// reachable.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a preset doesn't have one value per handle.
+    pub fn map_presets(mut self, handles: Vec<ParamHandle>, presets: Vec<Snapshot>) -> Self {
+        assert!(
+            presets
+                .iter()
+                .all(|preset| preset.values().len() == handles.len()),
+            "presets must have one value per handle"
+        );
+        self.presets = Some((handles, presets));
+        self
+    }
+
+    /// Calls `callback` with the bank and program number of every program
+    /// change, after any mapped preset has been recalled.
+    ///
+    /// The callback runs wherever [`dispatch`](Self::dispatch) is called, so
+    /// it must not block if that is the audio thread.
+    pub fn on_program_change(mut self, callback: impl FnMut(u16, u8) + Send + 'static) -> Self {
+        self.on_program_change = Some(Box::new(callback));
+        self
+    }
+
+    /// Returns the bank and program of the last program change, (0, 0) until
+    /// the first one.
+    pub fn program(&self) -> (u16, u8) {
+        self.program
+    }
+
+    /// Routes one event.
+    pub fn dispatch<T: NoteTarget + ?Sized>(&mut self, event: MidiEvent, target: &mut T) {
+        if self
+            .channel
+            .is_some_and(|channel| channel != event.channel())
+        {
+            return;
+        }
+
+        match event {
+            MidiEvent::NoteOn { note, velocity, .. } => {
+                target.note_on(note, velocity as f64 / 127.0);
+            }
+            MidiEvent::NoteOff { note, .. } => target.note_off(note),
+            MidiEvent::ControlChange {
+                controller: ALL_NOTES_OFF,
+                ..
+            } => target.all_notes_off(),
+            MidiEvent::ControlChange {
+                controller, value, ..
+            } if self.bank_select.uses(controller) => {
+                if controller == BANK_SELECT_MSB {
+                    self.bank_msb = value;
+                } else {
+                    self.bank_lsb = value;
+                }
+            }
+            MidiEvent::ControlChange {
+                controller, value, ..
+            } => {
+                for (_, mapping) in self
+                    .controls
+                    .iter()
+                    .filter(|(mapped, _)| *mapped == controller)
+                {
+                    mapping.set(value);
+                }
+            }
+            MidiEvent::PitchBend { value, .. } => {
+                if let Some((handle, range)) = &self.pitch_bend {
+                    let normalized = if value < 0 {
+                        value as f64 / 8192.0
+                    } else {
+                        value as f64 / 8191.0
+                    };
+                    handle.set(normalized * range);
+                }
+            }
+            MidiEvent::ChannelAftertouch { pressure, .. } => {
+                if let Some(mapping) = &self.aftertouch {
+                    mapping.set(pressure);
+                }
+            }
+            MidiEvent::ProgramChange { program, .. } => {
+                let bank = self.bank_select.bank(self.bank_msb, self.bank_lsb);
+                self.program = (bank, program);
+                if let Some((handles, presets)) = &self.presets
+                    && let Some(preset) = presets.get(bank as usize * 128 + program as usize)
+                {
+                    preset.recall(handles);
+                }
+                if let Some(callback) = &mut self.on_program_change {
+                    callback(bank, program);
+                }
+            }
+            MidiEvent::PolyAftertouch { .. } => {}
+        }
+    }
+
+    /// Parses a raw message and routes it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the parse error if `bytes` isn't a valid channel message.
+    pub fn dispatch_bytes<T: NoteTarget + ?Sized>(
+        &mut self,
+        bytes: &[u8],
+        target: &mut T,
+    ) -> Result<(), MidiParseError> {
+        let event = MidiEvent::parse(bytes)?;
+        self.dispatch(event, target);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records the notes it receives.
+    #[derive(Default)]
+    struct Recorder {
+        playing: Vec<u8>,
+        last_velocity: f64,
+    }
+
+    impl NoteTarget for Recorder {
+        fn note_on(&mut self, note: u8, velocity: f64) {
+            self.playing.push(note);
+            self.last_velocity = velocity;
+        }
+
+        fn note_off(&mut self, note: u8) {
+            self.playing.retain(|&playing| playing != note);
+        }
+
+        fn all_notes_off(&mut self) {
+            self.playing.clear();
+        }
+    }
+
+    #[test]
+    fn test_notes_and_channel_filter() {
+        let mut dispatcher = MidiDispatcher::new().with_channel(2);
+        let mut target = Recorder::default();
+
+        dispatcher
+            .dispatch_bytes(&[0x92, 60, 127], &mut target)
+            .unwrap();
+        dispatcher
+            .dispatch_bytes(&[0x92, 64, 127], &mut target)
+            .unwrap();
+        dispatcher
+            .dispatch_bytes(&[0x90, 67, 127], &mut target)
+            .unwrap();
+        assert_eq!(target.playing, vec![60, 64]);
+        assert_eq!(target.last_velocity, 1.0);
+
+        dispatcher
+            .dispatch_bytes(&[0x82, 60, 0], &mut target)
+            .unwrap();
+        assert_eq!(target.playing, vec![64]);
+        dispatcher
+            .dispatch_bytes(&[0xB2, 123, 0], &mut target)
+            .unwrap();
+        assert!(target.playing.is_empty());
+    }
+
+    #[test]
+    fn test_controllers() {
+        let volume = ParamHandle::new(0.0);
+        let bend = ParamHandle::new(0.0);
+        let pressure = ParamHandle::new(0.0);
+        let mut dispatcher = MidiDispatcher::new()
+            .map_cc(7, volume.clone(), 0.0, 1.0)
+            .map_pitch_bend(bend.clone(), 2.0)
+            .map_aftertouch(pressure.clone(), 0.0, 10.0);
+        let mut target = Recorder::default();
+
+        dispatcher
+            .dispatch_bytes(&[0xB0, 7, 127], &mut target)
+            .unwrap();
+        dispatcher
+            .dispatch_bytes(&[0xB0, 8, 0], &mut target)
+            .unwrap();
+        assert_eq!(volume.get(), 1.0);
+
+        dispatcher
+            .dispatch_bytes(&[0xE0, 0, 0], &mut target)
+            .unwrap();
+        assert_eq!(bend.get(), -2.0);
+        dispatcher
+            .dispatch_bytes(&[0xE0, 0x7F, 0x7F], &mut target)
+            .unwrap();
+        assert_eq!(bend.get(), 2.0);
+
+        dispatcher.dispatch_bytes(&[0xD0, 0], &mut target).unwrap();
+        assert_eq!(pressure.get(), 0.0);
+    }
+
+    #[test]
+    fn test_program_change_recalls_preset_in_bank() {
+        let cutoff = ParamHandle::new(0.0);
+        // One preset per slot, holding its slot number
+        let presets = (0..260)
+            .map(|slot| Snapshot::from_values([slot as f64]))
+            .collect();
+        let selected = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = selected.clone();
+        let mut dispatcher = MidiDispatcher::new()
+            .map_presets(vec![cutoff.clone()], presets)
+            .on_program_change(move |bank, program| log.lock().unwrap().push((bank, program)));
+        let mut target = Recorder::default();
+
+        // Bank select waits for the program change
+        dispatcher
+            .dispatch_bytes(&[0xB0, 0, 0], &mut target)
+            .unwrap();
+        dispatcher
+            .dispatch_bytes(&[0xB0, 32, 2], &mut target)
+            .unwrap();
+        assert_eq!(cutoff.get(), 0.0);
+        dispatcher.dispatch_bytes(&[0xC0, 3], &mut target).unwrap();
+        assert_eq!(cutoff.get(), 259.0);
+        assert_eq!(dispatcher.program(), (2, 3));
+
+        // Past the last preset the parameters are left alone
+        dispatcher.dispatch_bytes(&[0xC0, 4], &mut target).unwrap();
+        assert_eq!(cutoff.get(), 259.0);
+        assert_eq!(*selected.lock().unwrap(), [(2, 3), (2, 4)]);
+    }
+
+    #[test]
+    fn test_bank_select_schemes() {
+        let volume = ParamHandle::new(0.0);
+        let mut msb = MidiDispatcher::new()
+            .with_bank_select(BankSelect::Msb)
+            .map_cc(32, volume.clone(), 0.0, 1.0);
+        let mut target = Recorder::default();
+        msb.dispatch_bytes(&[0xB0, 0, 5], &mut target).unwrap();
+        msb.dispatch_bytes(&[0xB0, 32, 127], &mut target).unwrap();
+        msb.dispatch_bytes(&[0xC0, 0], &mut target).unwrap();
+        assert_eq!(msb.program(), (5, 0));
+        // CC 32 is free for other uses under this scheme
+        assert_eq!(volume.get(), 1.0);
+
+        let mut ignore = MidiDispatcher::new().with_bank_select(BankSelect::Ignore);
+        ignore.dispatch_bytes(&[0xB0, 0, 5], &mut target).unwrap();
+        ignore.dispatch_bytes(&[0xC0, 9], &mut target).unwrap();
+        assert_eq!(ignore.program(), (0, 9));
+    }
+}
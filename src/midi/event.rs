@@ -0,0 +1,287 @@
+//! Typed MIDI channel messages.
+
+use std::fmt;
+
+/// Error type for parsing raw MIDI messages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MidiParseError {
+    /// The message was empty
+    Empty,
+    /// The first byte was not a status byte (running status is not supported)
+    MissingStatus(u8),
+    /// The message was shorter than its status byte requires
+    Truncated(u8),
+    /// A data byte had its high bit set
+    InvalidData(u8),
+    /// System messages (SysEx, clock, etc.) are not handled
+    Unsupported(u8),
+}
+
+impl fmt::Display for MidiParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MidiParseError::Empty => write!(f, "MIDI message is empty"),
+            MidiParseError::MissingStatus(b) => write!(f, "expected status byte, got {:#04x}", b),
+            MidiParseError::Truncated(s) => {
+                write!(f, "truncated MIDI message for status {:#04x}", s)
+            }
+            MidiParseError::InvalidData(b) => write!(f, "invalid MIDI data byte {:#04x}", b),
+            MidiParseError::Unsupported(s) => write!(f, "unsupported MIDI status {:#04x}", s),
+        }
+    }
+}
+
+impl std::error::Error for MidiParseError {}
+
+/// A MIDI channel message.
+///
+/// Channels are numbered 0-15 (shown as 1-16 on most hardware).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MidiEvent {
+    /// A key was pressed
+    NoteOn { channel: u8, note: u8, velocity: u8 },
+    /// A key was released (also produced by a note on with velocity 0)
+    NoteOff { channel: u8, note: u8, velocity: u8 },
+    /// Per-key pressure (polyphonic aftertouch)
+    PolyAftertouch { channel: u8, note: u8, pressure: u8 },
+    /// A controller (knob, wheel, pedal) moved
+    ControlChange {
+        channel: u8,
+        controller: u8,
+        value: u8,
+    },
+    /// A different program (patch) was selected
+    ProgramChange { channel: u8, program: u8 },
+    /// Whole-channel pressure (channel aftertouch)
+    ChannelAftertouch { channel: u8, pressure: u8 },
+    /// The pitch wheel moved; `value` runs from -8192 to 8191, centred on 0
+    PitchBend { channel: u8, value: i16 },
+}
+
+impl MidiEvent {
+    /// Parses one complete MIDI channel message.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for empty, truncated or malformed messages and for
+    /// system messages.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::midi::MidiEvent;
+    ///
+    /// let event = MidiEvent::parse(&[0x91, 64, 100]).unwrap();
+    /// assert_eq!(event, MidiEvent::NoteOn { channel: 1, note: 64, velocity: 100 });
+    ///
+    /// // Note on with zero velocity is a note off
+    /// let event = MidiEvent::parse(&[0x90, 64, 0]).unwrap();
+    /// assert!(matches!(event, MidiEvent::NoteOff { note: 64, .. }));
+    /// ```
+    pub fn parse(bytes: &[u8]) -> Result<Self, MidiParseError> {
+        let (&status, data) = bytes.split_first().ok_or(MidiParseError::Empty)?;
+        if status < 0x80 {
+            return Err(MidiParseError::MissingStatus(status));
+        }
+        if status >= 0xF0 {
+            return Err(MidiParseError::Unsupported(status));
+        }
+
+        let length = match status & 0xF0 {
+            0xC0 | 0xD0 => 1,
+            _ => 2,
+        };
+        let data = data
+            .get(..length)
+            .ok_or(MidiParseError::Truncated(status))?;
+        if let Some(&byte) = data.iter().find(|&&byte| byte >= 0x80) {
+            return Err(MidiParseError::InvalidData(byte));
+        }
+
+        let channel = status & 0x0F;
+        Ok(match status & 0xF0 {
+            0x80 => MidiEvent::NoteOff {
+                channel,
+                note: data[0],
+                velocity: data[1],
+            },
+            0x90 if data[1] == 0 => MidiEvent::NoteOff {
+                channel,
+                note: data[0],
+                velocity: 0,
+            },
+            0x90 => MidiEvent::NoteOn {
+                channel,
+                note: data[0],
+                velocity: data[1],
+            },
+            0xA0 => MidiEvent::PolyAftertouch {
+                channel,
+                note: data[0],
+                pressure: data[1],
+            },
+            0xB0 => MidiEvent::ControlChange {
+                channel,
+                controller: data[0],
+                value: data[1],
+            },
+            0xC0 => MidiEvent::ProgramChange {
+                channel,
+                program: data[0],
+            },
+            0xD0 => MidiEvent::ChannelAftertouch {
+                channel,
+                pressure: data[0],
+            },
+            _ => MidiEvent::PitchBend {
+                channel,
+                value: ((data[1] as i16) << 7 | data[0] as i16) - 8192,
+            },
+        })
+    }
+
+    /// Encodes the message, returning its bytes and how many of them are
+    /// used (2 for program change and channel aftertouch, otherwise 3).
+    ///
+    /// Out-of-range data is masked to 7 bits and the channel to 4.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::midi::MidiEvent;
+    ///
+    /// let (bytes, length) = MidiEvent::ProgramChange { channel: 2, program: 9 }.to_bytes();
+    /// assert_eq!(&bytes[..length], &[0xC2, 9]);
+    /// ```
+    pub fn to_bytes(&self) -> ([u8; 3], usize) {
+        let status = |kind: u8, channel: u8| kind | (channel & 0x0F);
+        match *self {
+            MidiEvent::NoteOn {
+                channel,
+                note,
+                velocity,
+            } => ([status(0x90, channel), note & 0x7F, velocity & 0x7F], 3),
+            MidiEvent::NoteOff {
+                channel,
+                note,
+                velocity,
+            } => ([status(0x80, channel), note & 0x7F, velocity & 0x7F], 3),
+            MidiEvent::PolyAftertouch {
+                channel,
+                note,
+                pressure,
+            } => ([status(0xA0, channel), note & 0x7F, pressure & 0x7F], 3),
+            MidiEvent::ControlChange {
+                channel,
+                controller,
+                value,
+            } => ([status(0xB0, channel), controller & 0x7F, value & 0x7F], 3),
+            MidiEvent::ProgramChange { channel, program } => {
+                ([status(0xC0, channel), program & 0x7F, 0], 2)
+            }
+            MidiEvent::ChannelAftertouch { channel, pressure } => {
+                ([status(0xD0, channel), pressure & 0x7F, 0], 2)
+            }
+            MidiEvent::PitchBend { channel, value } => {
+                let raw = (value.clamp(-8192, 8191) + 8192) as u16;
+                (
+                    [status(0xE0, channel), (raw & 0x7F) as u8, (raw >> 7) as u8],
+                    3,
+                )
+            }
+        }
+    }
+
+    /// Returns the channel (0-15) the message was sent on.
+    pub fn channel(&self) -> u8 {
+        match *self {
+            MidiEvent::NoteOn { channel, .. }
+            | MidiEvent::NoteOff { channel, .. }
+            | MidiEvent::PolyAftertouch { channel, .. }
+            | MidiEvent::ControlChange { channel, .. }
+            | MidiEvent::ProgramChange { channel, .. }
+            | MidiEvent::ChannelAftertouch { channel, .. }
+            | MidiEvent::PitchBend { channel, .. } => channel,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_messages() {
+        assert_eq!(
+            MidiEvent::parse(&[0xB3, 7, 90]),
+            Ok(MidiEvent::ControlChange {
+                channel: 3,
+                controller: 7,
+                value: 90
+            })
+        );
+        assert_eq!(
+            MidiEvent::parse(&[0xC0, 5]),
+            Ok(MidiEvent::ProgramChange {
+                channel: 0,
+                program: 5
+            })
+        );
+        assert_eq!(
+            MidiEvent::parse(&[0xDF, 64]),
+            Ok(MidiEvent::ChannelAftertouch {
+                channel: 15,
+                pressure: 64
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_pitch_bend() {
+        let bend = |lsb, msb| match MidiEvent::parse(&[0xE0, lsb, msb]) {
+            Ok(MidiEvent::PitchBend { value, .. }) => value,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(bend(0x00, 0x40), 0);
+        assert_eq!(bend(0x00, 0x00), -8192);
+        assert_eq!(bend(0x7F, 0x7F), 8191);
+    }
+
+    #[test]
+    fn test_to_bytes_round_trips() {
+        for bytes in [
+            &[0x91, 64, 100][..],
+            &[0x80, 60, 0x40],
+            &[0xAF, 1, 2],
+            &[0xB3, 7, 90],
+            &[0xC0, 5],
+            &[0xD2, 64],
+            &[0xE0, 0x00, 0x40],
+            &[0xE5, 0x7F, 0x7F],
+        ] {
+            let (encoded, length) = MidiEvent::parse(bytes).unwrap().to_bytes();
+            assert_eq!(&encoded[..length], bytes);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(MidiEvent::parse(&[]), Err(MidiParseError::Empty));
+        assert_eq!(
+            MidiEvent::parse(&[60, 100]),
+            Err(MidiParseError::MissingStatus(60))
+        );
+        assert_eq!(
+            MidiEvent::parse(&[0x90, 60]),
+            Err(MidiParseError::Truncated(0x90))
+        );
+        assert_eq!(
+            MidiEvent::parse(&[0x90, 60, 0x80]),
+            Err(MidiParseError::InvalidData(0x80))
+        );
+        assert_eq!(
+            MidiEvent::parse(&[0xF8]),
+            Err(MidiParseError::Unsupported(0xF8))
+        );
+    }
+}
@@ -0,0 +1,57 @@
+//! MIDI input and output.
+//!
+//! This module turns raw MIDI messages (as delivered by a MIDI input library
+//! such as `midir`) into typed [`MidiEvent`]s, and a [`MidiDispatcher`] routes
+//! those events to a voice allocator and to [`ParamHandle`](crate::core::ParamHandle)
+//! targets, switching presets on program and bank changes. [`MidiFile`] reads Standard MIDI Files and converts their notes
+//! into [`Pattern`](crate::music::Pattern)s and [`Song`](crate::music::Song)s,
+//! so existing clips can be played through earworm instruments, and saves
+//! patterns and songs as MIDI files for opening in a DAW.
+//!
+//! For sequencing hardware, a [`MidiChannel`] plays sequencer tracks on a
+//! channel of any [`MidiSink`], and [`MidiClock`] sends clock so external
+//! gear follows the tempo. With the `midi-output` feature, [`connect_output`]
+//! opens a system MIDI port as a sink.
+//!
+//! Requires the `midi` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use earworm::core::ParamHandle;
+//! use earworm::midi::MidiDispatcher;
+//! use earworm::music::VoiceAllocator;
+//! use earworm::{ADSR, SineOscillator};
+//!
+//! const SAMPLE_RATE: u32 = 44100;
+//!
+//! let mut synth = VoiceAllocator::<SAMPLE_RATE, 8, _, _>::new(|| {
+//!     let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+//!     let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+//!     (osc, env)
+//! });
+//!
+//! // Mod wheel (CC 1) controls a filter cutoff between 200Hz and 5kHz
+//! let cutoff = ParamHandle::new(1000.0);
+//! let mut dispatcher = MidiDispatcher::new().map_cc(1, cutoff.clone(), 200.0, 5000.0);
+//!
+//! // In the MIDI input callback:
+//! dispatcher.dispatch_bytes(&[0x90, 60, 100], &mut synth).unwrap(); // Note on
+//! dispatcher.dispatch_bytes(&[0xB0, 1, 127], &mut synth).unwrap(); // Mod wheel up
+//!
+//! assert!(synth.is_note_playing(60));
+//! assert_eq!(cutoff.get(), 5000.0);
+//! ```
+
+mod dispatcher;
+mod event;
+mod output;
+mod smf;
+
+pub use crate::music::NoteTarget;
+pub use dispatcher::{BankSelect, MidiDispatcher};
+pub use event::{MidiEvent, MidiParseError};
+pub use output::{MidiChannel, MidiClock, MidiOutputError, MidiSink};
+#[cfg(feature = "midi-output")]
+pub use output::{connect_output, output_ports};
+pub use smf::{MidiFile, MidiFileError, MidiNote, MidiTrack};
@@ -0,0 +1,365 @@
+//! Sending notes and clock to external MIDI devices.
+
+use super::MidiEvent;
+use crate::music::{Metronome, NoteTarget, TempoCurve};
+use std::fmt;
+
+/// Somewhere MIDI messages can be sent: an output port, or a byte buffer.
+///
+/// Sending is fire-and-forget, like a MIDI cable; a sink that can fail
+/// drops the message.
+pub trait MidiSink {
+    /// Sends one complete MIDI message.
+    fn send(&mut self, bytes: &[u8]);
+
+    /// Sends a channel message.
+    fn send_event(&mut self, event: MidiEvent) {
+        let (bytes, length) = event.to_bytes();
+        self.send(&bytes[..length]);
+    }
+}
+
+/// Appends each message, building a raw MIDI byte stream.
+impl MidiSink for Vec<u8> {
+    fn send(&mut self, bytes: &[u8]) {
+        self.extend_from_slice(bytes);
+    }
+}
+
+#[cfg(feature = "midi-output")]
+impl MidiSink for midir::MidiOutputConnection {
+    fn send(&mut self, bytes: &[u8]) {
+        let _ = midir::MidiOutputConnection::send(self, bytes);
+    }
+}
+
+/// One channel of a [`MidiSink`], as a [`NoteTarget`].
+///
+/// Pass it to [`Sequencer::tick_into`](crate::music::Sequencer::tick_into)
+/// in place of an instrument to play the pattern on external hardware.
+/// Several channels can share a port by borrowing it in turn.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::midi::MidiChannel;
+/// use earworm::music::{Pattern, Sequencer};
+/// use earworm::{NoteEvent, Pitch};
+///
+/// let mut bass = Pattern::new(4);
+/// bass.add_event(0, NoteEvent::from_pitch(Pitch::C, 2, 1.0, Some(0.1)));
+/// let mut drums = Pattern::new(4);
+/// drums.add_event(0, NoteEvent::from_midi(36, 127, Some(0.05)));
+///
+/// let mut bass_track = Sequencer::new(120.0, 4, 1000);
+/// bass_track.set_pattern(bass);
+/// bass_track.play();
+/// let mut drum_track = Sequencer::new(120.0, 4, 1000);
+/// drum_track.set_pattern(drums);
+/// drum_track.play();
+///
+/// // A MIDI output port; here a byte buffer stands in for one
+/// let mut port: Vec<u8> = Vec::new();
+/// for _ in 0..1000 {
+///     bass_track.tick_into(&mut MidiChannel::new(&mut port, 0));
+///     drum_track.tick_into(&mut MidiChannel::new(&mut port, 9));
+/// }
+/// assert_eq!(&port[..6], &[0x90, 36, 127, 0x99, 36, 127]);
+/// ```
+pub struct MidiChannel<'a, S: MidiSink + ?Sized> {
+    sink: &'a mut S,
+    channel: u8,
+}
+
+impl<'a, S: MidiSink + ?Sized> MidiChannel<'a, S> {
+    /// Sends on `channel` (0-15) of `sink`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `channel > 15`.
+    pub fn new(sink: &'a mut S, channel: u8) -> Self {
+        assert!(channel <= 15, "MIDI channel must be 0-15, got {}", channel);
+        Self { sink, channel }
+    }
+
+    /// Returns the channel (0-15).
+    pub fn channel(&self) -> u8 {
+        self.channel
+    }
+
+    /// Selects a program (patch) on the channel.
+    pub fn program_change(&mut self, program: u8) {
+        self.sink.send_event(MidiEvent::ProgramChange {
+            channel: self.channel,
+            program,
+        });
+    }
+
+    /// Sends a controller value on the channel.
+    pub fn control_change(&mut self, controller: u8, value: u8) {
+        self.sink.send_event(MidiEvent::ControlChange {
+            channel: self.channel,
+            controller,
+            value,
+        });
+    }
+}
+
+impl<S: MidiSink + ?Sized> NoteTarget for MidiChannel<'_, S> {
+    /// Sends a note on, with `velocity` scaled to 1-127.
+    fn note_on(&mut self, note: u8, velocity: f64) {
+        let velocity = (velocity.clamp(0.0, 1.0) * 127.0).round().max(1.0) as u8;
+        self.sink.send_event(MidiEvent::NoteOn {
+            channel: self.channel,
+            note,
+            velocity,
+        });
+    }
+
+    fn note_off(&mut self, note: u8) {
+        self.sink.send_event(MidiEvent::NoteOff {
+            channel: self.channel,
+            note,
+            velocity: 0,
+        });
+    }
+
+    /// Sends the All Notes Off controller (CC 123).
+    fn all_notes_off(&mut self) {
+        self.control_change(123, 0);
+    }
+}
+
+/// System real-time messages sent by [`MidiClock`].
+const CLOCK: u8 = 0xF8;
+const START: u8 = 0xFA;
+const CONTINUE: u8 = 0xFB;
+const STOP: u8 = 0xFC;
+
+/// Sends MIDI clock so external sequencers and arpeggiators follow
+/// earworm's tempo.
+///
+/// Clock runs at 24 pulses per beat. Call [`tick`](Self::tick) once per
+/// sample alongside the sequencers it accompanies (and give it the same
+/// tempo changes) to keep them in step. [`start`](Self::start) sends Start,
+/// and receivers count the first pulse, one pulse later, as the downbeat.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::midi::MidiClock;
+///
+/// // 125 BPM at 1kHz: a pulse every 20 samples
+/// let mut clock = MidiClock::new(125.0, 1000);
+/// let mut port: Vec<u8> = Vec::new();
+/// clock.start(&mut port);
+/// for _ in 0..40 {
+///     clock.tick(&mut port);
+/// }
+/// clock.stop(&mut port);
+/// assert_eq!(port, [0xFA, 0xF8, 0xF8, 0xFC]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MidiClock {
+    metronome: Metronome,
+    running: bool,
+}
+
+impl MidiClock {
+    /// Pulses per beat, fixed by the MIDI specification.
+    pub const PULSES_PER_BEAT: u32 = 24;
+
+    /// Creates a stopped clock at `bpm`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bpm` is <= 0.
+    pub fn new(bpm: f64, sample_rate: u32) -> Self {
+        Self {
+            metronome: Metronome::new(bpm, Self::PULSES_PER_BEAT, sample_rate),
+            running: false,
+        }
+    }
+
+    /// Sends Start and runs the clock from the top.
+    pub fn start<S: MidiSink + ?Sized>(&mut self, sink: &mut S) {
+        self.metronome.reset();
+        self.running = true;
+        sink.send(&[START]);
+    }
+
+    /// Sends Stop and pauses the clock.
+    pub fn stop<S: MidiSink + ?Sized>(&mut self, sink: &mut S) {
+        self.running = false;
+        sink.send(&[STOP]);
+    }
+
+    /// Sends Continue and runs the clock from where it stopped.
+    pub fn resume<S: MidiSink + ?Sized>(&mut self, sink: &mut S) {
+        self.running = true;
+        sink.send(&[CONTINUE]);
+    }
+
+    /// Returns `true` while the clock is sending pulses.
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Advances one sample, sending a pulse when one falls on it.
+    pub fn tick<S: MidiSink + ?Sized>(&mut self, sink: &mut S) {
+        if self.running && self.metronome.tick() {
+            sink.send(&[CLOCK]);
+        }
+    }
+
+    /// Sets the tempo, keeping the position within the current pulse.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bpm` is <= 0.
+    pub fn set_tempo(&mut self, bpm: f64) {
+        self.metronome.set_tempo(bpm);
+    }
+
+    /// Glides to `bpm` over `beats` beats.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bpm` is <= 0.
+    pub fn ramp_tempo(&mut self, bpm: f64, beats: f64, curve: TempoCurve) {
+        self.metronome.ramp_tempo(bpm, beats, curve);
+    }
+
+    /// Returns the current tempo in BPM.
+    pub fn tempo(&self) -> f64 {
+        self.metronome.tempo()
+    }
+}
+
+/// Error opening a MIDI output port.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MidiOutputError {
+    /// The system MIDI API could not be opened
+    Init(String),
+    /// No port has a name containing the requested text
+    PortNotFound(String),
+    /// The port was found but could not be connected to
+    Connect(String),
+}
+
+impl fmt::Display for MidiOutputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MidiOutputError::Init(e) => write!(f, "failed to open MIDI output: {}", e),
+            MidiOutputError::PortNotFound(name) => {
+                write!(f, "no MIDI output port matching '{}'", name)
+            }
+            MidiOutputError::Connect(e) => write!(f, "failed to connect to MIDI port: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for MidiOutputError {}
+
+/// Lists the names of the available MIDI output ports (requires the
+/// `midi-output` feature).
+///
+/// # Errors
+///
+/// Returns [`MidiOutputError::Init`] if the system MIDI API is unavailable.
+#[cfg(feature = "midi-output")]
+pub fn output_ports() -> Result<Vec<String>, MidiOutputError> {
+    let output =
+        midir::MidiOutput::new("earworm").map_err(|e| MidiOutputError::Init(e.to_string()))?;
+    Ok(output
+        .ports()
+        .iter()
+        .filter_map(|port| output.port_name(port).ok())
+        .collect())
+}
+
+/// Connects to the first MIDI output port whose name contains `name`
+/// (requires the `midi-output` feature).
+///
+/// The connection is a [`MidiSink`]; wrap it in a [`MidiChannel`] to play
+/// sequencer tracks on it.
+///
+/// # Errors
+///
+/// Returns an error if the MIDI API is unavailable, no port matches, or the
+/// connection fails.
+///
+/// # Examples
+///
+/// ```no_run
+/// use earworm::midi::{MidiChannel, MidiClock, connect_output};
+/// use earworm::music::{NoteTarget, Sequencer};
+///
+/// let mut synth = connect_output("Minilogue")?;
+/// let mut clock = MidiClock::new(120.0, 48000);
+/// let mut sequencer = Sequencer::new(120.0, 4, 48000);
+/// clock.start(&mut synth);
+/// sequencer.play();
+/// // Once per sample, e.g. from the audio callback:
+/// clock.tick(&mut synth);
+/// sequencer.tick_into(&mut MidiChannel::new(&mut synth, 0));
+/// # Ok::<(), earworm::midi::MidiOutputError>(())
+/// ```
+#[cfg(feature = "midi-output")]
+pub fn connect_output(name: &str) -> Result<midir::MidiOutputConnection, MidiOutputError> {
+    let output =
+        midir::MidiOutput::new("earworm").map_err(|e| MidiOutputError::Init(e.to_string()))?;
+    let port = output
+        .ports()
+        .into_iter()
+        .find(|port| output.port_name(port).is_ok_and(|n| n.contains(name)))
+        .ok_or_else(|| MidiOutputError::PortNotFound(name.to_string()))?;
+    output
+        .connect(&port, "earworm-out")
+        .map_err(|e| MidiOutputError::Connect(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_sends_notes() {
+        let mut port = Vec::new();
+        let mut channel = MidiChannel::new(&mut port, 3);
+        channel.note_on(60, 0.5);
+        channel.note_on(62, 0.0);
+        channel.note_off(60);
+        channel.all_notes_off();
+        assert_eq!(port, [0x93, 60, 64, 0x93, 62, 1, 0x83, 60, 0, 0xB3, 123, 0]);
+    }
+
+    #[test]
+    fn test_clock_pulses_and_transport() {
+        // 62.5 BPM at 1kHz: 40 samples per pulse
+        let mut clock = MidiClock::new(62.5, 1000);
+        let mut port = Vec::new();
+        for _ in 0..100 {
+            clock.tick(&mut port);
+        }
+        assert!(port.is_empty());
+
+        clock.start(&mut port);
+        for _ in 0..100 {
+            clock.tick(&mut port);
+        }
+        assert_eq!(port, [START, CLOCK, CLOCK]);
+
+        port.clear();
+        clock.stop(&mut port);
+        for _ in 0..100 {
+            clock.tick(&mut port);
+        }
+        clock.resume(&mut port);
+        for _ in 0..21 {
+            clock.tick(&mut port);
+        }
+        // Continues mid-pulse: the third pulse lands 20 samples in
+        assert_eq!(port, [STOP, CONTINUE, CLOCK]);
+    }
+}
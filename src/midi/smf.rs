@@ -0,0 +1,888 @@
+//! Standard MIDI File import and export.
+
+use super::event::{MidiEvent, MidiParseError};
+use crate::NoteEvent;
+use crate::music::core::Key;
+use crate::music::{Pattern, Song};
+use std::fmt;
+use std::path::Path;
+
+/// Resolution of exported files, in ticks per beat.
+const EXPORT_TICKS_PER_BEAT: u16 = 480;
+
+/// Error type for reading Standard MIDI Files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MidiFileError {
+    /// The file could not be read
+    Io(String),
+    /// The data does not start with an `MThd` header
+    NotMidi,
+    /// The data ended in the middle of a chunk or event
+    Truncated,
+    /// The file counts time in SMPTE frames rather than ticks per beat
+    SmpteTiming,
+    /// A data byte appeared where a status byte was needed
+    MissingStatus(u8),
+    /// A channel message was malformed
+    InvalidEvent(MidiParseError),
+}
+
+impl fmt::Display for MidiFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MidiFileError::Io(e) => write!(f, "I/O error: {}", e),
+            MidiFileError::NotMidi => write!(f, "not a Standard MIDI File"),
+            MidiFileError::Truncated => write!(f, "MIDI file is truncated"),
+            MidiFileError::SmpteTiming => write!(f, "SMPTE timing is not supported"),
+            MidiFileError::MissingStatus(b) => {
+                write!(f, "expected status byte, got {:#04x}", b)
+            }
+            MidiFileError::InvalidEvent(e) => write!(f, "invalid MIDI event: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for MidiFileError {}
+
+impl From<std::io::Error> for MidiFileError {
+    fn from(e: std::io::Error) -> Self {
+        MidiFileError::Io(e.to_string())
+    }
+}
+
+impl From<MidiParseError> for MidiFileError {
+    fn from(e: MidiParseError) -> Self {
+        MidiFileError::InvalidEvent(e)
+    }
+}
+
+/// A note read from a MIDI file, timed in ticks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MidiNote {
+    /// Tick the note starts on
+    pub tick: u64,
+    /// Length in ticks (0 if the note off came on the same tick)
+    pub length: u64,
+    /// Channel (0-15)
+    pub channel: u8,
+    /// MIDI note number
+    pub note: u8,
+    /// Note-on velocity (1-127)
+    pub velocity: u8,
+}
+
+/// The notes of one track of a MIDI file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MidiTrack {
+    name: Option<String>,
+    notes: Vec<MidiNote>,
+}
+
+impl MidiTrack {
+    /// Returns the track name, if the file gives one.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the track's notes, ordered by start tick.
+    pub fn notes(&self) -> &[MidiNote] {
+        &self.notes
+    }
+}
+
+/// A parsed Standard MIDI File.
+///
+/// Only notes are kept, along with the first tempo and time signature, which
+/// are used to convert ticks to steps and seconds. Later tempo changes,
+/// controllers and other events are ignored.
+///
+/// Going the other way, [`from_pattern`](Self::from_pattern) and
+/// [`from_song`](Self::from_song) turn music composed in code into a file
+/// that [`save`](Self::save) writes for opening in a DAW.
+///
+/// # Examples
+///
+/// ```no_run
+/// use earworm::midi::MidiFile;
+/// use earworm::music::Sequencer;
+///
+/// let file = MidiFile::open("clip.mid")?;
+///
+/// // All tracks on a 16th-note grid
+/// let pattern = file.to_pattern(None, 4);
+///
+/// let mut sequencer = Sequencer::new(file.tempo(), 4, 44100);
+/// sequencer.set_pattern(pattern);
+/// sequencer.play();
+/// # Ok::<(), earworm::midi::MidiFileError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MidiFile {
+    format: u16,
+    ticks_per_beat: u16,
+    tempo: Option<f64>,
+    beats_per_bar: Option<u32>,
+    tracks: Vec<MidiTrack>,
+}
+
+impl MidiFile {
+    /// Reads and parses the MIDI file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MidiFileError::Io`] if the file cannot be read, or a parse
+    /// error as for [`parse`](Self::parse).
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, MidiFileError> {
+        Self::parse(&std::fs::read(path)?)
+    }
+
+    /// Parses the bytes of a MIDI file (format 0, 1 or 2).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data isn't a MIDI file, is truncated or
+    /// malformed, or uses SMPTE timing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::midi::MidiFile;
+    ///
+    /// // Format 0, 96 ticks per beat, one quarter-note middle C
+    /// let bytes = [
+    ///     b"MThd".as_slice(), &[0, 0, 0, 6, 0, 0, 0, 1, 0, 96],
+    ///     b"MTrk", &[0, 0, 0, 12],
+    ///     &[0x00, 0x90, 60, 100, 0x60, 0x80, 60, 0, 0x00, 0xFF, 0x2F, 0x00],
+    /// ]
+    /// .concat();
+    ///
+    /// let file = MidiFile::parse(&bytes).unwrap();
+    /// let note = file.tracks()[0].notes()[0];
+    /// assert_eq!((note.note, note.velocity, note.length), (60, 100, 96));
+    /// ```
+    pub fn parse(bytes: &[u8]) -> Result<Self, MidiFileError> {
+        let mut reader = Reader { bytes, position: 0 };
+        if reader.take(4).ok() != Some(b"MThd".as_slice()) {
+            return Err(MidiFileError::NotMidi);
+        }
+        let header_length = reader.u32()? as usize;
+        let mut header = Reader {
+            bytes: reader.take(header_length)?,
+            position: 0,
+        };
+        let format = header.u16()?;
+        let track_count = header.u16()?;
+        let division = header.u16()?;
+        if division & 0x8000 != 0 {
+            return Err(MidiFileError::SmpteTiming);
+        }
+
+        let mut file = MidiFile {
+            format,
+            ticks_per_beat: division.max(1),
+            tempo: None,
+            beats_per_bar: None,
+            tracks: Vec::new(),
+        };
+        while file.tracks.len() < track_count as usize && reader.position < bytes.len() {
+            let id = reader.take(4)?;
+            let length = reader.u32()? as usize;
+            let data = reader.take(length)?;
+            // Unknown chunk types are skipped, as the format requires
+            if id == b"MTrk" {
+                let track = file.parse_track(data)?;
+                file.tracks.push(track);
+            }
+        }
+        Ok(file)
+    }
+
+    fn parse_track(&mut self, data: &[u8]) -> Result<MidiTrack, MidiFileError> {
+        let mut reader = Reader {
+            bytes: data,
+            position: 0,
+        };
+        let mut track = MidiTrack::default();
+        // Started notes as (channel, note, start tick, velocity)
+        let mut held: Vec<(u8, u8, u64, u8)> = Vec::new();
+        let mut running_status = None;
+        let mut tick = 0;
+
+        while reader.position < data.len() {
+            tick += reader.variable_length()?;
+            let mut status = reader.u8()?;
+            if status < 0x80 {
+                // Running status: the byte was the first data byte
+                status = running_status.ok_or(MidiFileError::MissingStatus(status))?;
+                reader.position -= 1;
+            }
+            match status {
+                0xFF => {
+                    running_status = None;
+                    let kind = reader.u8()?;
+                    let length = reader.variable_length()? as usize;
+                    let meta = reader.take(length)?;
+                    match (kind, meta) {
+                        (0x03, name) if track.name.is_none() => {
+                            track.name = Some(String::from_utf8_lossy(name).into_owned());
+                        }
+                        (0x51, &[a, b, c]) if self.tempo.is_none() => {
+                            let micros = u32::from_be_bytes([0, a, b, c]).max(1);
+                            self.tempo = Some(60_000_000.0 / micros as f64);
+                        }
+                        (0x58, &[numerator, ..]) if self.beats_per_bar.is_none() => {
+                            self.beats_per_bar = Some(numerator.max(1) as u32);
+                        }
+                        (0x2F, _) => break,
+                        _ => {}
+                    }
+                }
+                0xF0 | 0xF7 => {
+                    running_status = None;
+                    let length = reader.variable_length()? as usize;
+                    reader.take(length)?;
+                }
+                0xF1..=0xFE => return Err(MidiParseError::Unsupported(status).into()),
+                _ => {
+                    running_status = Some(status);
+                    let length = if matches!(status & 0xF0, 0xC0 | 0xD0) {
+                        1
+                    } else {
+                        2
+                    };
+                    let mut message = [status, 0, 0];
+                    message[1..=length].copy_from_slice(reader.take(length)?);
+                    match MidiEvent::parse(&message[..=length])? {
+                        MidiEvent::NoteOn {
+                            channel,
+                            note,
+                            velocity,
+                        } => held.push((channel, note, tick, velocity)),
+                        MidiEvent::NoteOff { channel, note, .. } => {
+                            // The earliest matching note ends first
+                            if let Some(index) = held
+                                .iter()
+                                .position(|&(c, n, ..)| c == channel && n == note)
+                            {
+                                let (channel, note, start, velocity) = held.remove(index);
+                                track.notes.push(MidiNote {
+                                    tick: start,
+                                    length: tick - start,
+                                    channel,
+                                    note,
+                                    velocity,
+                                });
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        // Notes never released end with the track
+        for (channel, note, start, velocity) in held {
+            track.notes.push(MidiNote {
+                tick: start,
+                length: tick - start,
+                channel,
+                note,
+                velocity,
+            });
+        }
+        track.notes.sort_by_key(|note| note.tick);
+        Ok(track)
+    }
+
+    /// Converts a pattern into a single-track file, one pass through the
+    /// pattern long.
+    ///
+    /// Steps become ticks at `steps_per_beat`, and note durations in seconds
+    /// become note lengths at `bpm`, which is also stored as the file's
+    /// tempo. Events without a duration last one step. Scale-degree events
+    /// are resolved in C major; export [`Pattern::resolved`] for another key.
+    /// Every event is written, whatever its [`Trigger`](crate::music::Trigger).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bpm` is not positive or `steps_per_beat` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use earworm::midi::MidiFile;
+    /// use earworm::music::Pattern;
+    /// use earworm::NoteEvent;
+    ///
+    /// let mut bass = Pattern::new(16);
+    /// bass.set_name("bass");
+    /// for step in (0..16).step_by(4) {
+    ///     bass.add_event(step, NoteEvent::from_midi(36, 110, Some(0.2)));
+    /// }
+    ///
+    /// MidiFile::from_pattern(&bass, 120.0, 4).save("bass.mid")?;
+    /// # Ok::<(), earworm::midi::MidiFileError>(())
+    /// ```
+    pub fn from_pattern(pattern: &Pattern, bpm: f64, steps_per_beat: u32) -> Self {
+        let events = pattern
+            .resolved(&Key::default())
+            .events()
+            .map(|(step, event)| (step, *event))
+            .collect();
+        Self::from_step_events(pattern.name(), events, bpm, steps_per_beat)
+    }
+
+    /// Converts a song into a single-track file holding one pass through
+    /// every section.
+    ///
+    /// Sections follow each other as a [`SongPlayer`](crate::music::SongPlayer)
+    /// plays them, with scale degrees resolved in `key` and over each
+    /// section's chords. Otherwise works like
+    /// [`from_pattern`](Self::from_pattern).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bpm` is not positive or `steps_per_beat` is 0.
+    pub fn from_song(song: &Song, key: &Key, bpm: f64, steps_per_beat: u32) -> Self {
+        let mut events = Vec::new();
+        let mut start = 0;
+        for (index, section) in song.sections().iter().enumerate() {
+            events.extend(
+                song.section_events(index, key)
+                    .into_iter()
+                    .map(|(step, event)| (start + step, event)),
+            );
+            start += song.patterns()[section.pattern].length() * section.repeats;
+        }
+        Self::from_step_events(None, events, bpm, steps_per_beat)
+    }
+
+    fn from_step_events(
+        name: Option<&str>,
+        events: Vec<(usize, NoteEvent)>,
+        bpm: f64,
+        steps_per_beat: u32,
+    ) -> Self {
+        assert!(bpm > 0.0, "bpm must be positive");
+        assert!(steps_per_beat > 0, "steps_per_beat must be greater than 0");
+        let ticks_per_beat = EXPORT_TICKS_PER_BEAT as f64;
+        let ticks_per_step = ticks_per_beat / steps_per_beat as f64;
+        let ticks_per_second = ticks_per_beat * bpm / 60.0;
+
+        let mut notes: Vec<MidiNote> = events
+            .into_iter()
+            .map(|(step, event)| {
+                let length = match event.duration {
+                    Some(seconds) => seconds.max(0.0) * ticks_per_second,
+                    None => ticks_per_step,
+                };
+                MidiNote {
+                    tick: (step as f64 * ticks_per_step).round() as u64,
+                    length: length.round() as u64,
+                    channel: 0,
+                    note: event.note.to_midi(),
+                    velocity: (event.velocity * 127.0).round().clamp(1.0, 127.0) as u8,
+                }
+            })
+            .collect();
+        notes.sort_by_key(|note| note.tick);
+
+        MidiFile {
+            format: 0,
+            ticks_per_beat: EXPORT_TICKS_PER_BEAT,
+            tempo: Some(bpm),
+            beats_per_bar: None,
+            tracks: vec![MidiTrack {
+                name: name.map(str::to_string),
+                notes,
+            }],
+        }
+    }
+
+    /// Encodes the file as Standard MIDI File bytes.
+    ///
+    /// One track is written as format 0, several as format 1. The tempo and
+    /// time signature, if known, go at the start of the first track.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::midi::MidiFile;
+    /// use earworm::music::Pattern;
+    /// use earworm::NoteEvent;
+    ///
+    /// let mut pattern = Pattern::new(4);
+    /// pattern.add_event(2, NoteEvent::from_midi(64, 90, None));
+    ///
+    /// let bytes = MidiFile::from_pattern(&pattern, 100.0, 4).to_bytes();
+    /// let file = MidiFile::parse(&bytes).unwrap();
+    /// assert_eq!(file.tempo().round(), 100.0);
+    /// assert_eq!(file.to_pattern(None, 4).events_at_step(2).len(), 1);
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let format: u16 = if self.tracks.len() == 1 { 0 } else { 1 };
+        let mut out = b"MThd".to_vec();
+        out.extend_from_slice(&6u32.to_be_bytes());
+        out.extend_from_slice(&format.to_be_bytes());
+        out.extend_from_slice(&(self.tracks.len() as u16).to_be_bytes());
+        out.extend_from_slice(&self.ticks_per_beat.to_be_bytes());
+
+        for (index, track) in self.tracks.iter().enumerate() {
+            // (tick, order, message); at equal ticks note offs come before
+            // note ons, except that a zero-length note ends after it starts
+            let mut events: Vec<(u64, u8, Vec<u8>)> = Vec::new();
+            if let Some(name) = &track.name {
+                events.push((0, 0, meta(0x03, name.as_bytes())));
+            }
+            if index == 0 {
+                if let Some(bpm) = self.tempo {
+                    let micros = (60_000_000.0 / bpm).round().clamp(1.0, 16_777_215.0) as u32;
+                    events.push((0, 0, meta(0x51, &micros.to_be_bytes()[1..])));
+                }
+                if let Some(beats) = self.beats_per_bar {
+                    events.push((0, 0, meta(0x58, &[beats.min(255) as u8, 2, 24, 8])));
+                }
+            }
+            for note in &track.notes {
+                let channel = note.channel & 0x0F;
+                events.push((note.tick, 2, vec![0x90 | channel, note.note, note.velocity]));
+                let order = if note.length == 0 { 3 } else { 1 };
+                events.push((
+                    note.tick + note.length,
+                    order,
+                    vec![0x80 | channel, note.note, 0],
+                ));
+            }
+            events.sort_by_key(|&(tick, order, _)| (tick, order));
+
+            let mut data = Vec::new();
+            let mut previous = 0;
+            for (tick, _, message) in events {
+                write_variable_length(&mut data, tick - previous);
+                data.extend_from_slice(&message);
+                previous = tick;
+            }
+            data.extend_from_slice(&[0x00, 0xFF, 0x2F, 0x00]);
+
+            out.extend_from_slice(b"MTrk");
+            out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            out.extend_from_slice(&data);
+        }
+        out
+    }
+
+    /// Writes the file to `path`, replacing any existing file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MidiFileError::Io`] if the file cannot be written.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), MidiFileError> {
+        std::fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Returns the file format: 0 (one track), 1 (simultaneous tracks) or 2
+    /// (independent sequences).
+    pub fn format(&self) -> u16 {
+        self.format
+    }
+
+    /// Returns the number of ticks in a beat (quarter note).
+    pub fn ticks_per_beat(&self) -> u16 {
+        self.ticks_per_beat
+    }
+
+    /// Returns the file's first tempo in BPM, or 120 if it sets none.
+    pub fn tempo(&self) -> f64 {
+        self.tempo.unwrap_or(120.0)
+    }
+
+    /// Returns the beats in a bar from the file's first time signature, or 4
+    /// if it sets none.
+    pub fn beats_per_bar(&self) -> u32 {
+        self.beats_per_bar.unwrap_or(4)
+    }
+
+    /// Returns the tracks, including tracks with no notes (such as the tempo
+    /// track of a format 1 file).
+    pub fn tracks(&self) -> &[MidiTrack] {
+        &self.tracks
+    }
+
+    /// Converts a track's notes into a pattern.
+    ///
+    /// Note starts are rounded to the nearest step, and note lengths become
+    /// durations in seconds at the file's [`tempo`](Self::tempo). The pattern
+    /// is a whole number of bars long, just enough to hold every note.
+    ///
+    /// # Arguments
+    ///
+    /// * `track` - Index of the track to convert, or `None` to merge all tracks
+    /// * `steps_per_beat` - Grid resolution (4 = 16th notes, 2 = 8th notes, etc.)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `track` is out of range or `steps_per_beat` is 0.
+    pub fn to_pattern(&self, track: Option<usize>, steps_per_beat: u32) -> Pattern {
+        assert!(steps_per_beat > 0, "steps_per_beat must be greater than 0");
+        let events = self.step_events(track, steps_per_beat);
+        let bar = (self.beats_per_bar() * steps_per_beat) as usize;
+        let end = events.iter().map(|(step, _)| step + 1).max().unwrap_or(1);
+        let mut pattern = Pattern::new(end.div_ceil(bar) * bar);
+        if let Some(name) = track.and_then(|t| self.tracks[t].name()) {
+            pattern.set_name(name);
+        }
+        for (step, event) in events {
+            pattern.add_event(step, event);
+        }
+        pattern
+    }
+
+    /// Converts a track's notes into a song of `bars_per_section`-bar
+    /// sections.
+    ///
+    /// Notes are placed as in [`to_pattern`](Self::to_pattern). Runs of
+    /// identical sections are played as one section with repeats, and a
+    /// section that returns later reuses the earlier pattern, so a clip with
+    /// a repeating structure becomes a compact arrangement. Sections are
+    /// named after the bar they start on ("bar 1", "bar 5", ...).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `track` is out of range, or if `steps_per_beat` or
+    /// `bars_per_section` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use earworm::midi::MidiFile;
+    /// use earworm::music::SongPlayer;
+    ///
+    /// let file = MidiFile::open("song.mid")?;
+    /// let song = file.to_song(None, 4, 4);
+    /// let mut player = SongPlayer::new(song, file.tempo(), 4, 44100)
+    ///     .with_beats_per_bar(file.beats_per_bar());
+    /// player.play();
+    /// # Ok::<(), earworm::midi::MidiFileError>(())
+    /// ```
+    pub fn to_song(
+        &self,
+        track: Option<usize>,
+        steps_per_beat: u32,
+        bars_per_section: usize,
+    ) -> Song {
+        assert!(
+            bars_per_section > 0,
+            "bars_per_section must be greater than 0"
+        );
+        let whole = self.to_pattern(track, steps_per_beat);
+        let section_length = bars_per_section * (self.beats_per_bar() * steps_per_beat) as usize;
+
+        let mut song = Song::new();
+        let mut chunks: Vec<Vec<(usize, NoteEvent)>> = Vec::new();
+        // (pattern index, first bar) of each section before folding repeats
+        let mut order = Vec::new();
+        for start in (0..whole.length()).step_by(section_length) {
+            let chunk: Vec<(usize, NoteEvent)> = whole
+                .events_in_steps(start..start + section_length)
+                .into_iter()
+                .map(|(step, event)| (step - start, *event))
+                .collect();
+            let index = match chunks.iter().position(|c| *c == chunk) {
+                Some(index) => index,
+                None => {
+                    let mut pattern = Pattern::new(section_length);
+                    for &(step, event) in &chunk {
+                        pattern.add_event(step, event);
+                    }
+                    chunks.push(chunk);
+                    song.add_pattern(pattern)
+                }
+            };
+            order.push((index, start / section_length * bars_per_section + 1));
+        }
+
+        for run in order.chunk_by(|a, b| a.0 == b.0) {
+            let (index, bar) = run[0];
+            song.add_section(format!("bar {}", bar), index, run.len());
+        }
+        song
+    }
+
+    /// The notes of `track` (or all tracks) as events on a grid, in start
+    /// order.
+    fn step_events(&self, track: Option<usize>, steps_per_beat: u32) -> Vec<(usize, NoteEvent)> {
+        let tracks = match track {
+            Some(index) => {
+                assert!(
+                    index < self.tracks.len(),
+                    "Track index {} out of bounds (file has {} tracks)",
+                    index,
+                    self.tracks.len()
+                );
+                &self.tracks[index..=index]
+            }
+            None => &self.tracks[..],
+        };
+        let ticks_per_step = self.ticks_per_beat as f64 / steps_per_beat as f64;
+        let seconds_per_tick = 60.0 / self.tempo() / self.ticks_per_beat as f64;
+
+        let mut notes: Vec<&MidiNote> = tracks.iter().flat_map(|t| &t.notes).collect();
+        notes.sort_by_key(|note| note.tick);
+        notes
+            .into_iter()
+            .map(|note| {
+                let step = (note.tick as f64 / ticks_per_step).round() as usize;
+                let duration = note.length as f64 * seconds_per_tick;
+                (
+                    step,
+                    NoteEvent::from_midi(note.note, note.velocity, Some(duration)),
+                )
+            })
+            .collect()
+    }
+}
+
+/// A meta event with status, type and length prefix.
+fn meta(kind: u8, data: &[u8]) -> Vec<u8> {
+    let mut event = vec![0xFF, kind];
+    write_variable_length(&mut event, data.len() as u64);
+    event.extend_from_slice(data);
+    event
+}
+
+/// Appends `value` as a variable-length quantity.
+fn write_variable_length(out: &mut Vec<u8>, value: u64) {
+    let mut groups = vec![(value & 0x7F) as u8];
+    let mut rest = value >> 7;
+    while rest > 0 {
+        groups.push(0x80 | (rest & 0x7F) as u8);
+        rest >>= 7;
+    }
+    out.extend(groups.iter().rev());
+}
+
+/// Reads big-endian values from a byte slice.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], MidiFileError> {
+        let end = self
+            .position
+            .checked_add(count)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or(MidiFileError::Truncated)?;
+        let slice = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, MidiFileError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, MidiFileError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, MidiFileError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// A variable-length quantity: 7 bits per byte, high bit set on all but
+    /// the last byte (at most 4 bytes).
+    fn variable_length(&mut self) -> Result<u64, MidiFileError> {
+        let mut value = 0;
+        for _ in 0..4 {
+            let byte = self.u8()?;
+            value = (value << 7) | (byte & 0x7F) as u64;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(MidiFileError::Truncated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(id: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        [id.as_slice(), &(data.len() as u32).to_be_bytes(), data].concat()
+    }
+
+    /// A format 1 file at 4 ticks per beat: a tempo track, and a named track
+    /// playing C4 and E4 each bar for `bars` bars, in running status.
+    fn two_track_file(bars: usize) -> Vec<u8> {
+        let tempo = [
+            &[0x00, 0xFF, 0x51, 0x03, 0x07, 0x53, 0x00][..], // 125 BPM
+            &[0x00, 0xFF, 0x58, 0x04, 0x03, 0x02, 0x18, 0x08], // 3/4
+            &[0x00, 0xFF, 0x2F, 0x00],
+        ]
+        .concat();
+        let mut notes = vec![0x00, 0xFF, 0x03, 0x04, b'l', b'e', b'a', b'd'];
+        for _ in 0..bars {
+            // C4 for a beat, then E4 (running status, off as velocity 0)
+            notes.extend([0x00, 0x90, 60, 100, 0x04, 60, 0, 0x00, 64, 80, 0x08, 64, 0]);
+        }
+        notes.extend([0x00, 0xFF, 0x2F, 0x00]);
+        [
+            chunk(b"MThd", &[0, 1, 0, 2, 0, 4]),
+            chunk(b"MTrk", &tempo),
+            chunk(b"XTRA", &[1, 2, 3]),
+            chunk(b"MTrk", &notes),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_parse_tracks_and_meta() {
+        let file = MidiFile::parse(&two_track_file(2)).unwrap();
+        assert_eq!((file.format(), file.ticks_per_beat()), (1, 4));
+        assert_eq!(file.tempo(), 125.0);
+        assert_eq!(file.beats_per_bar(), 3);
+        assert_eq!(file.tracks().len(), 2);
+        assert!(file.tracks()[0].notes().is_empty());
+
+        let lead = &file.tracks()[1];
+        assert_eq!(lead.name(), Some("lead"));
+        let notes: Vec<_> = lead
+            .notes()
+            .iter()
+            .map(|n| (n.tick, n.length, n.note, n.velocity))
+            .collect();
+        assert_eq!(
+            notes,
+            [
+                (0, 4, 60, 100),
+                (4, 8, 64, 80),
+                (12, 4, 60, 100),
+                (16, 8, 64, 80)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_pattern_quantizes_to_bars() {
+        let file = MidiFile::parse(&two_track_file(1)).unwrap();
+        let pattern = file.to_pattern(Some(1), 2);
+        assert_eq!(pattern.name(), Some("lead"));
+        // One 3/4 bar of 8th notes
+        assert_eq!(pattern.length(), 6);
+        let events: Vec<_> = pattern
+            .events()
+            .map(|(step, e)| (step, e.note.to_midi(), e.duration.unwrap()))
+            .collect();
+        assert_eq!(events, [(0, 60, 60.0 / 125.0), (2, 64, 120.0 / 125.0)]);
+        assert_eq!(file.to_pattern(None, 2).event_count(), 2);
+    }
+
+    #[test]
+    fn test_to_song_folds_repeats() {
+        let file = MidiFile::parse(&two_track_file(4)).unwrap();
+        let song = file.to_song(None, 4, 1);
+        assert_eq!(song.patterns().len(), 1);
+        assert_eq!(song.sections().len(), 1);
+        assert_eq!(song.sections()[0].repeats, 4);
+        assert_eq!(song.sections()[0].name, "bar 1");
+        assert_eq!(song.length_in_steps(), 48);
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        use crate::music::core::DegreeEvent;
+
+        let mut pattern = Pattern::new(8);
+        pattern.set_name("riff");
+        pattern.add_event(0, NoteEvent::from_midi(60, 100, Some(0.25)));
+        pattern.add_event(3, NoteEvent::from_midi(67, 127, None));
+        pattern.add_event(5, NoteEvent::from_midi(72, 64, Some(0.0)));
+        pattern.add_degree(5, DegreeEvent::new(2, 4, 0.5, Some(1.0)));
+
+        let file = MidiFile::parse(&MidiFile::from_pattern(&pattern, 120.0, 4).to_bytes()).unwrap();
+        assert_eq!((file.format(), file.tempo()), (0, 120.0));
+        let track = &file.tracks()[0];
+        assert_eq!(track.name(), Some("riff"));
+        let notes: Vec<_> = track
+            .notes()
+            .iter()
+            .map(|n| (n.tick, n.length, n.note, n.velocity))
+            .collect();
+        assert_eq!(
+            notes,
+            [
+                (0, 240, 60, 100),
+                (360, 120, 67, 127),
+                (600, 0, 72, 64),
+                (600, 960, 64, 64),
+            ]
+        );
+
+        let back = file.to_pattern(None, 4);
+        let steps: Vec<usize> = back.events().map(|(step, _)| step).collect();
+        assert_eq!(steps, [0, 3, 5, 5]);
+    }
+
+    #[test]
+    fn test_song_export_places_sections_in_order() {
+        let mut a = Pattern::new(4);
+        a.add_event(0, NoteEvent::from_midi(36, 100, None));
+        let mut b = Pattern::new(2);
+        b.add_event(1, NoteEvent::from_midi(38, 100, None));
+        let mut song = Song::new();
+        let a = song.add_pattern(a);
+        let b = song.add_pattern(b);
+        song.add_section("a", a, 2);
+        song.add_section("b", b, 1);
+
+        let file = MidiFile::from_song(&song, &Key::default(), 90.0, 2);
+        let ticks: Vec<u64> = file.tracks()[0].notes().iter().map(|n| n.tick).collect();
+        assert_eq!(ticks, [0, 960, 2160]);
+    }
+
+    #[test]
+    fn test_variable_length_encoding() {
+        for (value, bytes) in [
+            (0, vec![0x00]),
+            (0x7F, vec![0x7F]),
+            (0x80, vec![0x81, 0x00]),
+            (0x0FFF_FFFF, vec![0xFF, 0xFF, 0xFF, 0x7F]),
+        ] {
+            let mut out = Vec::new();
+            write_variable_length(&mut out, value);
+            assert_eq!(out, bytes);
+            let mut reader = Reader {
+                bytes: &out,
+                position: 0,
+            };
+            assert_eq!(reader.variable_length().unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(MidiFile::parse(b"RIFF"), Err(MidiFileError::NotMidi));
+        let smpte = chunk(b"MThd", &[0, 0, 0, 1, 0xE7, 40]);
+        assert_eq!(MidiFile::parse(&smpte), Err(MidiFileError::SmpteTiming));
+
+        let mut truncated = two_track_file(1);
+        truncated.truncate(truncated.len() - 5);
+        assert_eq!(MidiFile::parse(&truncated), Err(MidiFileError::Truncated));
+
+        let no_status = [
+            chunk(b"MThd", &[0, 0, 0, 1, 0, 96]),
+            chunk(b"MTrk", &[0x00, 60, 100]),
+        ]
+        .concat();
+        assert_eq!(
+            MidiFile::parse(&no_status),
+            Err(MidiFileError::MissingStatus(60))
+        );
+    }
+}
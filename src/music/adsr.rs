@@ -1,4 +1,5 @@
-//! ADSR (Attack, Decay, Sustain, Release) envelope generator.
+//! ADSR (Attack, Decay, Sustain, Release) envelope generator, with optional
+//! delay and hold stages.
 
 use super::envelope::{Envelope, EnvelopeState};
 use crate::synthesis::envelopes::Curve;
@@ -11,6 +12,11 @@ use crate::synthesis::envelopes::Curve;
 /// - **Sustain**: holds at sustain level until note off
 /// - **Release**: ramps from current level to 0
 ///
+/// Two optional stages turn it into a DAHDSR envelope: a **delay** before the
+/// attack (see [`with_delay`](Self::with_delay)) and a **hold** at peak level
+/// between attack and decay (see [`with_hold`](Self::with_hold)). Both
+/// default to zero.
+///
 /// # Examples
 ///
 /// ```
@@ -47,7 +53,9 @@ pub struct ADSR {
     release_start_level: f64, // level when release was triggered
 
     // Time parameters (in seconds)
+    delay_time: f64,
     attack_time: f64,
+    hold_time: f64,
     decay_time: f64,
     sustain_level: f64, // 0.0 to 1.0
     release_time: f64,
@@ -91,7 +99,9 @@ impl ADSR {
             phase_position: 0.0,
             current_level: 0.0,
             release_start_level: 0.0,
+            delay_time: 0.0,
             attack_time: attack_time.max(0.0),
+            hold_time: 0.0,
             decay_time: decay_time.max(0.0),
             sustain_level: sustain_level.clamp(0.0, 1.0),
             release_time: release_time.max(0.0),
@@ -102,6 +112,44 @@ impl ADSR {
         }
     }
 
+    /// Sets a delay before the attack starts, in seconds.
+    ///
+    /// The envelope outputs 0.0 for the delay time after each trigger. A
+    /// release during the delay ends the envelope without it ever sounding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::ADSR;
+    /// use earworm::music::envelope::{Envelope, EnvelopeState};
+    ///
+    /// // Pad layer that fades in 250ms after the note starts
+    /// let mut env = ADSR::new(0.5, 0.2, 0.8, 1.0, 44100.0).with_delay(0.25);
+    /// env.trigger(1.0);
+    /// assert_eq!(env.state(), EnvelopeState::Delay);
+    /// assert_eq!(env.next_sample(), 0.0);
+    /// ```
+    pub fn with_delay(mut self, delay_time: f64) -> Self {
+        self.delay_time = delay_time.max(0.0);
+        self
+    }
+
+    /// Sets how long the envelope holds at peak level after the attack, in
+    /// seconds, before the decay starts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::ADSR;
+    ///
+    /// // Punchy envelope that stays at full level for 30ms before decaying
+    /// let env = ADSR::new(0.002, 0.2, 0.4, 0.2, 44100.0).with_hold(0.03);
+    /// ```
+    pub fn with_hold(mut self, hold_time: f64) -> Self {
+        self.hold_time = hold_time.max(0.0);
+        self
+    }
+
     /// Sets the curve for the attack phase.
     ///
     /// # Examples
@@ -166,17 +214,33 @@ impl ADSR {
         self.current_level = 0.0;
         self.release_start_level = 0.0;
     }
+
+    /// Stage that follows the attack.
+    fn after_attack(&self) -> EnvelopeState {
+        if self.hold_time > 0.0 {
+            EnvelopeState::Hold
+        } else {
+            EnvelopeState::Decay
+        }
+    }
 }
 
 impl Envelope for ADSR {
     fn trigger(&mut self, _velocity: f64) {
         // For now, velocity is ignored. Future enhancement: scale peak level by velocity
-        self.state = EnvelopeState::Attack;
+        self.state = if self.delay_time > 0.0 {
+            EnvelopeState::Delay
+        } else {
+            EnvelopeState::Attack
+        };
         self.phase_position = 0.0;
     }
 
     fn release(&mut self) {
-        if !matches!(self.state, EnvelopeState::Idle) {
+        if matches!(self.state, EnvelopeState::Delay) {
+            // Released before the attack started, so the note never sounds
+            self.reset();
+        } else if !matches!(self.state, EnvelopeState::Idle) {
             self.state = EnvelopeState::Release;
             self.phase_position = 0.0;
             self.release_start_level = self.current_level;
@@ -195,14 +259,33 @@ impl Envelope for ADSR {
         self.state
     }
 
+    fn set_sustain_level(&mut self, level: f64) {
+        self.sustain_level = level.clamp(0.0, 1.0);
+    }
+
     fn next_sample(&mut self) -> f64 {
         match self.state {
             EnvelopeState::Idle => 0.0,
 
+            EnvelopeState::Delay => {
+                let progress = self.phase_position / (self.delay_time * self.sample_rate);
+
+                if progress >= 1.0 {
+                    // Delay complete, start the attack on this sample
+                    self.state = EnvelopeState::Attack;
+                    self.phase_position = 0.0;
+                    self.next_sample()
+                } else {
+                    self.phase_position += 1.0;
+                    self.current_level = 0.0;
+                    0.0
+                }
+            }
+
             EnvelopeState::Attack => {
                 if self.attack_time <= 0.0 {
                     // Skip attack if time is zero
-                    self.state = EnvelopeState::Decay;
+                    self.state = self.after_attack();
                     self.phase_position = 0.0;
                     self.current_level = 1.0;
                     return 1.0;
@@ -211,8 +294,8 @@ impl Envelope for ADSR {
                 let progress = self.phase_position / (self.attack_time * self.sample_rate);
 
                 if progress >= 1.0 {
-                    // Attack complete, move to decay
-                    self.state = EnvelopeState::Decay;
+                    // Attack complete, move to hold or decay
+                    self.state = self.after_attack();
                     self.phase_position = 0.0;
                     self.current_level = 1.0;
                     1.0
@@ -223,6 +306,21 @@ impl Envelope for ADSR {
                 }
             }
 
+            EnvelopeState::Hold => {
+                let progress = self.phase_position / (self.hold_time * self.sample_rate);
+
+                if progress >= 1.0 {
+                    // Hold complete, start the decay on this sample
+                    self.state = EnvelopeState::Decay;
+                    self.phase_position = 0.0;
+                    self.next_sample()
+                } else {
+                    self.phase_position += 1.0;
+                    self.current_level = 1.0;
+                    1.0
+                }
+            }
+
             EnvelopeState::Decay => {
                 if self.decay_time <= 0.0 {
                     // Skip decay if time is zero
@@ -634,4 +732,60 @@ mod tests {
             assert!((0.0..=1.0).contains(&sample));
         }
     }
+
+    #[test]
+    fn test_delay_stage() {
+        let mut env = ADSR::new(0.1, 0.0, 1.0, 0.0, SAMPLE_RATE).with_delay(0.2);
+        env.trigger(1.0);
+        assert_eq!(env.state(), EnvelopeState::Delay);
+        assert!(env.is_active());
+
+        // 20 samples of silence, then the attack starts without a gap
+        for _ in 0..20 {
+            assert_eq!(env.next_sample(), 0.0);
+        }
+        assert_eq!(env.next_sample(), 0.0);
+        assert_eq!(env.state(), EnvelopeState::Attack);
+        assert!(approx_eq(env.next_sample(), 0.1));
+    }
+
+    #[test]
+    fn test_release_during_delay_goes_idle() {
+        let mut env = ADSR::new(0.1, 0.1, 0.7, 0.5, SAMPLE_RATE).with_delay(0.2);
+        env.trigger(1.0);
+        env.next_sample();
+        env.release();
+        assert!(!env.is_active());
+        assert_eq!(env.next_sample(), 0.0);
+    }
+
+    #[test]
+    fn test_hold_stage() {
+        let mut env = ADSR::new(0.0, 1.0, 0.5, 0.0, SAMPLE_RATE).with_hold(0.1);
+        env.trigger(1.0);
+        assert_eq!(env.next_sample(), 1.0);
+        assert_eq!(env.state(), EnvelopeState::Hold);
+
+        for _ in 0..10 {
+            assert_eq!(env.next_sample(), 1.0);
+        }
+        assert_eq!(env.state(), EnvelopeState::Hold);
+
+        // Decay starts from the peak once the hold ends
+        assert_eq!(env.next_sample(), 1.0);
+        assert_eq!(env.state(), EnvelopeState::Decay);
+        assert!(approx_eq(env.next_sample(), 0.995));
+    }
+
+    #[test]
+    fn test_release_during_hold() {
+        let mut env = ADSR::new(0.0, 0.1, 0.5, 0.1, SAMPLE_RATE).with_hold(1.0);
+        env.trigger(1.0);
+        for _ in 0..5 {
+            env.next_sample();
+        }
+        env.release();
+        assert_eq!(env.state(), EnvelopeState::Release);
+        assert_eq!(env.next_sample(), 1.0);
+    }
 }
@@ -246,8 +246,8 @@ impl Envelope for AHD {
                 }
             }
 
-            // AHD doesn't use Release
-            EnvelopeState::Release => {
+            // AHD doesn't use Delay or Release, and reports its hold phase as Sustain
+            EnvelopeState::Delay | EnvelopeState::Hold | EnvelopeState::Release => {
                 // Shouldn't happen, but treat as decay
                 self.state = EnvelopeState::Decay;
                 self.phase_position = 0.0;
@@ -120,6 +120,24 @@
 //!    - **Quietest**: Steal the voice with the lowest envelope level
 //! 3. Trigger the stolen voice with the new note
 //!
+//! ## Unison
+//!
+//! With [`VoiceAllocator::with_unison`], each `note_on()` plays the note on a
+//! group of N voices, detuned evenly across a spread in cents. The group
+//! shares one age, so it is released together and stolen as a unit. Each
+//! sub-voice also gets a pan position and a gain (outer voices quieter with an
+//! amplitude spread); pan is heard through the [`StereoVoices`] wrapper. Each
+//! sub-voice is scaled by `1 / sqrt(N)` so a unison note is about as loud as
+//! a single voice.
+//!
+//! ## Inspection, Mute and Solo
+//!
+//! [`VoiceAllocator::voices`] reports each voice's note, age, velocity and
+//! envelope level as a [`VoiceInfo`], which is enough to watch stealing
+//! decisions or draw a voice display. Individual voices can be muted, soloed
+//! or trimmed by index; this only changes what is heard, so a muted voice
+//! keeps running and is still allocated and stolen as usual.
+//!
 //! ## Normalization
 //!
 //! To prevent clipping when mixing multiple voices:
@@ -167,7 +185,9 @@
 //! - Each voice maintains independent state (phase, envelope position, etc.)
 //! - Signal mixing is done in next_sample() - no separate mixing buffer needed
 
-use super::{envelope::Envelope, voice::Voice};
+use super::{core::Note, envelope::Envelope, voice::Voice};
+use crate::core::{AudioFrameSignal, BufferPool, DEFAULT_BLOCK_SIZE, FrameSignal, ParamHandle};
+use crate::synthesis::Curve;
 use crate::{AudioSignal, Pitched, Signal};
 
 /// Voice stealing strategy for when all voices are active.
@@ -182,6 +202,34 @@ pub enum StealingStrategy {
     Released,
 }
 
+/// A snapshot of one voice in a [`VoiceAllocator`], from [`VoiceAllocator::voices`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VoiceInfo {
+    /// Position of the voice in the pool (0 to VOICES - 1)
+    pub index: usize,
+    /// MIDI note held by the voice, or `None` once it has been released
+    pub note: Option<u8>,
+    /// Allocation counter at the voice's last note-on; higher is newer, and
+    /// voices of one unison group share an age
+    pub age: u64,
+    /// Velocity of the voice's last note (0.0 to 1.0)
+    pub velocity: f64,
+    /// Current envelope level
+    pub level: f64,
+    /// Whether the voice is sounding (its envelope is not idle)
+    pub active: bool,
+    /// Whether the voice is in its release phase
+    pub releasing: bool,
+    /// Stereo position from -1.0 (left) to 1.0 (right)
+    pub pan: f64,
+    /// Gain applied by [`VoiceAllocator::set_voice_gain`]
+    pub gain: f64,
+    /// Whether the voice is muted
+    pub muted: bool,
+    /// Whether the voice is soloed
+    pub soloed: bool,
+}
+
 /// State tracking for a single voice in the allocator.
 struct VoiceState<const SAMPLE_RATE: u32, S, E>
 where
@@ -192,6 +240,49 @@ where
     note: Option<u8>,
     age: u64,
     velocity: f64,
+    /// Output gain (unison amplitude spread and group normalization)
+    gain: f64,
+    /// Stereo position from -1.0 (left) to 1.0 (right)
+    pan: f64,
+    /// User gain set with `set_voice_gain`
+    trim: f64,
+    muted: bool,
+    soloed: bool,
+}
+
+impl<const SAMPLE_RATE: u32, S, E> VoiceState<SAMPLE_RATE, S, E>
+where
+    S: AudioSignal<SAMPLE_RATE> + Pitched,
+    E: Envelope,
+{
+    /// Gain the voice is mixed at; `soloing` is true if any voice is soloed.
+    fn output_gain(&self, soloing: bool) -> f64 {
+        if self.muted || (soloing && !self.soloed) {
+            0.0
+        } else {
+            self.gain * self.trim
+        }
+    }
+}
+
+/// Unison settings for a [`VoiceAllocator`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Unison {
+    voices: usize,
+    detune_cents: f64,
+    stereo_spread: f64,
+    amplitude_spread: f64,
+}
+
+impl Unison {
+    /// Position of sub-voice `index` within the group, from -1.0 to 1.0.
+    fn position(&self, index: usize) -> f64 {
+        if self.voices < 2 {
+            0.0
+        } else {
+            2.0 * index as f64 / (self.voices - 1) as f64 - 1.0
+        }
+    }
 }
 
 /// Voice allocator for polyphonic synthesis.
@@ -235,7 +326,12 @@ where
 {
     voices: [VoiceState<SAMPLE_RATE, S, E>; VOICES],
     strategy: StealingStrategy,
+    unison: Unison,
     age_counter: u64,
+    soloing: bool,
+    pitch_bend: f64,
+    mod_wheel: ParamHandle,
+    scratch: BufferPool,
 }
 
 impl<const SAMPLE_RATE: u32, const VOICES: usize, S, E> VoiceAllocator<SAMPLE_RATE, VOICES, S, E>
@@ -272,20 +368,36 @@ where
         F: FnMut() -> (S, E),
     {
         // Create array of voice states using the factory function
+        let mod_wheel = ParamHandle::new(0.0);
         let voices = std::array::from_fn(|_| {
             let (signal, envelope) = voice_factory();
             VoiceState {
-                voice: Voice::new(signal, envelope),
+                voice: Voice::new(signal, envelope).with_mod_wheel(mod_wheel.clone()),
                 note: None,
                 age: 0,
                 velocity: 0.0,
+                gain: 1.0,
+                pan: 0.0,
+                trim: 1.0,
+                muted: false,
+                soloed: false,
             }
         });
 
         Self {
             voices,
             strategy: StealingStrategy::default(),
+            unison: Unison {
+                voices: 1,
+                detune_cents: 0.0,
+                stereo_spread: 0.0,
+                amplitude_spread: 0.0,
+            },
             age_counter: 0,
+            soloing: false,
+            pitch_bend: 0.0,
+            mod_wheel,
+            scratch: BufferPool::with_buffers(1, DEFAULT_BLOCK_SIZE),
         }
     }
 
@@ -310,6 +422,193 @@ where
         self
     }
 
+    /// Bends the pitch of every voice by `semitones`, including notes started
+    /// later.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{ADSR, SineOscillator};
+    /// use earworm::music::VoiceAllocator;
+    ///
+    /// const SAMPLE_RATE: u32 = 44100;
+    ///
+    /// let mut allocator = VoiceAllocator::<SAMPLE_RATE, 4, _, _>::new(|| {
+    ///     let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+    ///     let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+    ///     (osc, env)
+    /// });
+    ///
+    /// // A MIDI pitch bend message (0 to 16383) with a +/-2 semitone range
+    /// let value = 12288;
+    /// allocator.set_pitch_bend((value as f64 - 8192.0) / 8192.0 * 2.0);
+    /// assert_eq!(allocator.pitch_bend(), 1.0);
+    /// ```
+    pub fn set_pitch_bend(&mut self, semitones: f64) {
+        self.pitch_bend = semitones;
+        for state in self.voices.iter_mut() {
+            state.voice.set_pitch_bend(semitones);
+        }
+    }
+
+    /// Returns the pitch bend in semitones.
+    pub fn pitch_bend(&self) -> f64 {
+        self.pitch_bend
+    }
+
+    /// Uses `handle` as the modulation wheel of every voice (builder style).
+    ///
+    /// Voices are built before the allocator exists, so create the handle
+    /// first, route clones of it into each voice's signal from the factory,
+    /// then pass it here so [`set_mod_amount`](Self::set_mod_amount) moves it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::core::ParamHandle;
+    /// use earworm::music::VoiceAllocator;
+    /// use earworm::{ADSR, Lfo, LfoWaveform, SawtoothOscillator, SignalExt};
+    ///
+    /// const SAMPLE_RATE: u32 = 44100;
+    ///
+    /// // The mod wheel sets the depth of a 5 Hz vibrato, up to 8 Hz
+    /// let wheel = ParamHandle::new(0.0);
+    /// let mut synth = VoiceAllocator::<SAMPLE_RATE, 8, _, _>::new(|| {
+    ///     let depth = wheel.clone().gain(8.0);
+    ///     let vibrato = Lfo::<SAMPLE_RATE>::new(LfoWaveform::Sine, 5.0).gain(depth);
+    ///     let osc = SawtoothOscillator::<SAMPLE_RATE>::with_frequency_mod(440.0, vibrato);
+    ///     let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+    ///     (osc, env)
+    /// })
+    /// .with_mod_wheel(wheel.clone());
+    ///
+    /// // A MIDI CC 1 message
+    /// synth.set_mod_amount(64.0 / 127.0);
+    /// assert_eq!(wheel.get(), 64.0 / 127.0);
+    /// ```
+    pub fn with_mod_wheel(mut self, handle: ParamHandle) -> Self {
+        for state in self.voices.iter_mut() {
+            state.voice.set_mod_wheel(handle.clone());
+        }
+        self.mod_wheel = handle;
+        self
+    }
+
+    /// Returns the modulation wheel shared by every voice, as a signal.
+    pub fn mod_wheel(&self) -> ParamHandle {
+        self.mod_wheel.clone()
+    }
+
+    /// Sets the modulation wheel position, clamped to 0.0 to 1.0.
+    pub fn set_mod_amount(&mut self, amount: f64) {
+        self.mod_wheel.set(amount.clamp(0.0, 1.0));
+    }
+
+    /// Returns the modulation wheel position.
+    pub fn mod_amount(&self) -> f64 {
+        self.mod_wheel.get()
+    }
+
+    /// Enables portamento on every voice (builder style).
+    ///
+    /// A voice glides when a note starts while it is still sounding (see
+    /// [`Voice::set_portamento`]). With one voice (`VOICES = 1`) that gives
+    /// classic mono-synth portamento between overlapping notes; with more,
+    /// only stolen voices glide.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{ADSR, Curve, SawtoothOscillator};
+    /// use earworm::music::VoiceAllocator;
+    ///
+    /// const SAMPLE_RATE: u32 = 44100;
+    ///
+    /// // Monophonic lead with a 60 ms slide
+    /// let mut lead = VoiceAllocator::<SAMPLE_RATE, 1, _, _>::new(|| {
+    ///     let osc = SawtoothOscillator::<SAMPLE_RATE>::new(440.0);
+    ///     let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+    ///     (osc, env)
+    /// })
+    /// .with_portamento(0.06, Curve::Exponential(2.0));
+    ///
+    /// lead.note_on(60, 0.8);
+    /// lead.note_on(67, 0.8); // glides up a fifth
+    /// ```
+    pub fn with_portamento(mut self, glide_time: f64, curve: Curve) -> Self {
+        self.set_portamento(glide_time, curve);
+        self
+    }
+
+    /// Sets the portamento time (seconds; 0.0 disables) and curve of every voice.
+    pub fn set_portamento(&mut self, glide_time: f64, curve: Curve) {
+        for state in self.voices.iter_mut() {
+            state.voice.set_portamento(glide_time, curve);
+        }
+    }
+
+    /// Plays every note on a group of detuned voices (builder style).
+    ///
+    /// See [`set_unison`](Self::set_unison).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{ADSR, SawtoothOscillator};
+    /// use earworm::music::VoiceAllocator;
+    ///
+    /// const SAMPLE_RATE: u32 = 44100;
+    ///
+    /// // Supersaw: 7 saws per note spread over 40 cents, two notes at a time
+    /// let mut allocator = VoiceAllocator::<SAMPLE_RATE, 14, _, _>::new(|| {
+    ///     let osc = SawtoothOscillator::<SAMPLE_RATE>::new(440.0);
+    ///     let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+    ///     (osc, env)
+    /// })
+    /// .with_unison(7, 40.0)
+    /// .with_unison_spread(0.8, 0.3);
+    ///
+    /// allocator.note_on(60, 0.8);
+    /// assert_eq!(allocator.active_voice_count(), 7);
+    /// ```
+    pub fn with_unison(mut self, voices: usize, detune_cents: f64) -> Self {
+        self.set_unison(voices, detune_cents);
+        self
+    }
+
+    /// Sets how many voices play each note and how far apart they are detuned.
+    ///
+    /// The voices are detuned evenly from `-detune_cents / 2` to
+    /// `+detune_cents / 2`. Takes effect from the next `note_on()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `voices` - Voices per note, clamped to 1..=VOICES (1 disables unison)
+    /// * `detune_cents` - Total detune spread in cents
+    pub fn set_unison(&mut self, voices: usize, detune_cents: f64) {
+        self.unison.voices = voices.clamp(1, VOICES.max(1));
+        self.unison.detune_cents = detune_cents;
+    }
+
+    /// Sets the stereo and amplitude spread of unison voices (builder style).
+    ///
+    /// # Arguments
+    ///
+    /// * `stereo` - 0.0 keeps every voice centred; 1.0 pans the outermost
+    ///   voices hard left and right
+    /// * `amplitude` - 0.0 plays every voice equally loud; 1.0 fades the
+    ///   outermost voices out completely, leaving the centre voices dominant
+    pub fn with_unison_spread(mut self, stereo: f64, amplitude: f64) -> Self {
+        self.unison.stereo_spread = stereo.clamp(0.0, 1.0);
+        self.unison.amplitude_spread = amplitude.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Returns the number of voices played per note.
+    pub fn unison_voices(&self) -> usize {
+        self.unison.voices
+    }
+
     /// Triggers a note with the given MIDI note number and velocity.
     ///
     /// If a free voice is available, it is used. Otherwise, a voice is stolen
@@ -337,24 +636,44 @@ where
     /// allocator.note_on(60, 0.8); // Middle C at 80% velocity
     /// ```
     pub fn note_on(&mut self, note: u8, velocity: f64) {
-        // Find a voice to use
-        let voice_idx = self.find_voice_to_use();
-
-        // Increment age counter
+        // Increment age counter; a unison group shares one age
         self.age_counter = self.age_counter.wrapping_add(1);
 
-        // Activate the voice
-        let state = &mut self.voices[voice_idx];
-        state.note = Some(note);
-        state.age = self.age_counter;
-        state.velocity = velocity;
-        state.voice.note_on(note, velocity);
+        let unison = self.unison;
+        let group_gain = 1.0 / (unison.voices as f64).sqrt();
+        let mut claimed = [false; VOICES];
+        let mut stealing = None;
+        for index in 0..unison.voices {
+            // Find a voice to use
+            let voice_idx = self.find_voice_to_use(&claimed, stealing);
+            claimed[voice_idx] = true;
+            if self.voices[voice_idx].voice.is_active() {
+                stealing = Some(self.voices[voice_idx].age);
+            }
+
+            let position = unison.position(index);
+            let cents = position * unison.detune_cents / 2.0;
+            let frequency = Note::midi_to_freq(note) * (cents / 1200.0).exp2();
+
+            // Activate the voice
+            let state = &mut self.voices[voice_idx];
+            state.note = Some(note);
+            state.age = self.age_counter;
+            state.velocity = velocity;
+            state.gain = group_gain * (1.0 - unison.amplitude_spread * position.abs());
+            state.pan = position * unison.stereo_spread;
+            if unison.voices == 1 {
+                state.voice.note_on(note, velocity);
+            } else {
+                state.voice.note_on(frequency, velocity);
+            }
+        }
     }
 
     /// Releases the note with the given MIDI note number.
     ///
     /// If multiple voices are playing the same note, only the first one found
-    /// is released.
+    /// (with the rest of its unison group) is released.
     ///
     /// # Examples
     ///
@@ -374,8 +693,20 @@ where
     /// allocator.note_off(60);
     /// ```
     pub fn note_off(&mut self, note: u8) {
-        // Find the first voice playing this note
-        if let Some(state) = self.voices.iter_mut().find(|v| v.note == Some(note)) {
+        // Find the first voice playing this note, then release its whole group
+        let Some(age) = self
+            .voices
+            .iter()
+            .find(|v| v.note == Some(note))
+            .map(|v| v.age)
+        else {
+            return;
+        };
+        for state in self
+            .voices
+            .iter_mut()
+            .filter(|v| v.note == Some(note) && v.age == age)
+        {
             state.voice.note_off();
             state.note = None;
         }
@@ -434,7 +765,8 @@ where
 
     /// Returns the number of currently active voices.
     ///
-    /// A voice is considered active if its envelope is active (not idle).
+    /// A voice is considered active if its envelope is active (not idle). Each
+    /// voice of a unison group counts separately.
     ///
     /// # Examples
     ///
@@ -458,52 +790,175 @@ where
         self.voices.iter().filter(|v| v.voice.is_active()).count()
     }
 
-    /// Finds a voice to use for a new note.
+    /// Returns a snapshot of every voice in the pool, in index order.
+    ///
+    /// Filter on [`VoiceInfo::active`] to see only sounding voices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{ADSR, SineOscillator};
+    /// use earworm::music::VoiceAllocator;
+    ///
+    /// const SAMPLE_RATE: u32 = 44100;
+    ///
+    /// let mut allocator = VoiceAllocator::<SAMPLE_RATE, 4, _, _>::new(|| {
+    ///     let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+    ///     let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+    ///     (osc, env)
+    /// });
+    ///
+    /// allocator.note_on(60, 0.8);
+    /// allocator.note_on(64, 0.6);
+    ///
+    /// for info in allocator.voices().filter(|v| v.active) {
+    ///     println!("voice {}: note {:?}, level {:.2}", info.index, info.note, info.level);
+    /// }
+    /// ```
+    pub fn voices(&self) -> impl Iterator<Item = VoiceInfo> + '_ {
+        self.voices
+            .iter()
+            .enumerate()
+            .map(|(index, state)| VoiceInfo {
+                index,
+                note: state.note,
+                age: state.age,
+                velocity: state.velocity,
+                level: state.voice.envelope_level(),
+                active: state.voice.is_active(),
+                releasing: state.voice.is_releasing(),
+                pan: state.pan,
+                gain: state.trim,
+                muted: state.muted,
+                soloed: state.soloed,
+            })
+    }
+
+    /// Returns a snapshot of the voice at `index`, or `None` if out of range.
+    pub fn voice_info(&self, index: usize) -> Option<VoiceInfo> {
+        self.voices().nth(index)
+    }
+
+    /// Mutes or unmutes the voice at `index`.
+    ///
+    /// A muted voice keeps playing silently, so notes allocated to it are not
+    /// heard until it is unmuted. Out-of-range indices are ignored.
+    pub fn set_voice_muted(&mut self, index: usize, muted: bool) {
+        if let Some(state) = self.voices.get_mut(index) {
+            state.muted = muted;
+        }
+    }
+
+    /// Solos or unsolos the voice at `index`.
+    ///
+    /// While any voice is soloed, only soloed voices are heard. Out-of-range
+    /// indices are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{ADSR, SineOscillator};
+    /// use earworm::music::VoiceAllocator;
+    ///
+    /// const SAMPLE_RATE: u32 = 44100;
+    ///
+    /// let mut allocator = VoiceAllocator::<SAMPLE_RATE, 4, _, _>::new(|| {
+    ///     let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+    ///     let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+    ///     (osc, env)
+    /// });
+    ///
+    /// allocator.note_on(60, 0.8);
+    /// allocator.note_on(67, 0.8);
+    ///
+    /// // Listen to the voice playing G4 on its own
+    /// let index = allocator.voices().find(|v| v.note == Some(67)).unwrap().index;
+    /// allocator.set_voice_solo(index, true);
+    /// ```
+    pub fn set_voice_solo(&mut self, index: usize, soloed: bool) {
+        if let Some(state) = self.voices.get_mut(index) {
+            state.soloed = soloed;
+        }
+        self.soloing = self.voices.iter().any(|v| v.soloed);
+    }
+
+    /// Clears mute, solo and gain on every voice.
+    pub fn reset_voice_mix(&mut self) {
+        for state in self.voices.iter_mut() {
+            state.trim = 1.0;
+            state.muted = false;
+            state.soloed = false;
+        }
+        self.soloing = false;
+    }
+
+    /// Sets the gain of the voice at `index` (1.0 is unchanged).
+    ///
+    /// Applied on top of unison gain and persists across notes. Out-of-range
+    /// indices are ignored.
+    pub fn set_voice_gain(&mut self, index: usize, gain: f64) {
+        if let Some(state) = self.voices.get_mut(index) {
+            state.trim = gain;
+        }
+    }
+
+    /// Finds a voice to use for a new note, skipping voices already `claimed`.
     ///
     /// Priority:
     /// 1. Inactive voice (envelope idle)
-    /// 2. Voice to steal based on strategy
-    fn find_voice_to_use(&self) -> usize {
+    /// 2. Another voice of the group being stolen (with age `stealing`), so
+    ///    unison groups are stolen whole
+    /// 3. Voice to steal based on strategy
+    fn find_voice_to_use(&self, claimed: &[bool; VOICES], stealing: Option<u64>) -> usize {
+        let unclaimed = || {
+            self.voices
+                .iter()
+                .enumerate()
+                .filter(|(idx, _)| !claimed[*idx])
+        };
+
         // First, try to find an inactive voice
-        if let Some((idx, _)) = self
-            .voices
-            .iter()
-            .enumerate()
-            .find(|(_, v)| !v.voice.is_active())
-        {
+        if let Some((idx, _)) = unclaimed().find(|(_, v)| !v.voice.is_active()) {
+            return idx;
+        }
+
+        // Finish stealing a partly stolen unison group
+        if let Some((idx, _)) = unclaimed().find(|(_, v)| Some(v.age) == stealing) {
             return idx;
         }
 
         // All voices are active, need to steal one based on strategy
-        self.find_voice_to_steal()
+        self.find_voice_to_steal(claimed)
     }
 
     /// Finds a voice to steal based on the current stealing strategy.
     ///
-    /// This is only called when all voices are active.
-    fn find_voice_to_steal(&self) -> usize {
+    /// This is only called when all unclaimed voices are active.
+    fn find_voice_to_steal(&self, claimed: &[bool; VOICES]) -> usize {
         match self.strategy {
-            StealingStrategy::Oldest => self.find_oldest_voice(),
-            StealingStrategy::Quietest => self.find_quietest_voice(),
-            StealingStrategy::Released => self.find_released_or_oldest_voice(),
+            StealingStrategy::Oldest => self.find_oldest_voice(claimed),
+            StealingStrategy::Quietest => self.find_quietest_voice(claimed),
+            StealingStrategy::Released => self.find_released_or_oldest_voice(claimed),
         }
     }
 
     /// Finds the oldest voice (lowest age counter).
-    fn find_oldest_voice(&self) -> usize {
+    fn find_oldest_voice(&self, claimed: &[bool; VOICES]) -> usize {
         self.voices
             .iter()
             .enumerate()
+            .filter(|(idx, _)| !claimed[*idx])
             .min_by_key(|(_, v)| v.age)
             .map(|(idx, _)| idx)
-            .unwrap() // Safe because VOICES > 0
+            .unwrap() // Safe because fewer than VOICES are claimed
     }
 
     /// Finds the quietest voice (lowest envelope level).
-    fn find_quietest_voice(&self) -> usize {
+    fn find_quietest_voice(&self, claimed: &[bool; VOICES]) -> usize {
         self.voices
             .iter()
             .enumerate()
+            .filter(|(idx, _)| !claimed[*idx])
             .min_by(|(_, a), (_, b)| {
                 a.voice
                     .envelope_level()
@@ -511,29 +966,74 @@ where
                     .unwrap_or(std::cmp::Ordering::Equal)
             })
             .map(|(idx, _)| idx)
-            .unwrap() // Safe because VOICES > 0
+            .unwrap() // Safe because fewer than VOICES are claimed
     }
 
     /// Finds a voice in release phase, or falls back to oldest.
-    fn find_released_or_oldest_voice(&self) -> usize {
-        // Find all voices in their final decay/release phase
-        let released_voices: Vec<(usize, &VoiceState<SAMPLE_RATE, S, E>)> = self
-            .voices
+    fn find_released_or_oldest_voice(&self, claimed: &[bool; VOICES]) -> usize {
+        // Steal the oldest voice in its final decay/release phase
+        self.voices
             .iter()
             .enumerate()
-            .filter(|(_, v)| v.voice.is_releasing())
-            .collect();
-
-        if !released_voices.is_empty() {
-            // Steal the oldest voice in release/decay phase
-            released_voices
-                .iter()
-                .min_by_key(|(_, v)| v.age)
-                .map(|(idx, _)| *idx)
-                .unwrap()
-        } else {
+            .filter(|(idx, v)| !claimed[*idx] && v.voice.is_releasing())
+            .min_by_key(|(_, v)| v.age)
+            .map(|(idx, _)| idx)
             // No voices releasing, fall back to oldest
-            self.find_oldest_voice()
+            .unwrap_or_else(|| self.find_oldest_voice(claimed))
+    }
+
+    /// Renders one voice into `block`, applying its unison gain, mute and solo.
+    fn render_voice(state: &mut VoiceState<SAMPLE_RATE, S, E>, block: &mut [f64], soloing: bool) {
+        state.voice.process(block);
+        let gain = state.output_gain(soloing);
+        if gain != 1.0 {
+            for sample in block.iter_mut() {
+                *sample *= gain;
+            }
+        }
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl<const SAMPLE_RATE: u32, const VOICES: usize, S, E> VoiceAllocator<SAMPLE_RATE, VOICES, S, E>
+where
+    S: AudioSignal<SAMPLE_RATE> + Pitched + Send,
+    E: Envelope + Send,
+{
+    /// Renders all voices on multiple threads, then mixes them into `buffer`.
+    ///
+    /// Produces the same output as [`Signal::process`], but spreads the voices
+    /// across the available cores. Intended for offline rendering of long
+    /// buffers; thread startup makes it unsuitable for realtime callbacks.
+    ///
+    /// Requires the `parallel` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{ADSR, SineOscillator};
+    /// use earworm::music::VoiceAllocator;
+    ///
+    /// let mut allocator = VoiceAllocator::<44100, 8, _, _>::new(|| {
+    ///     let osc = SineOscillator::<44100>::new(440.0);
+    ///     let env = ADSR::new(0.01, 0.1, 0.7, 0.3, 44100.0);
+    ///     (osc, env)
+    /// });
+    /// allocator.note_on(60, 0.8);
+    /// allocator.note_on(64, 0.8);
+    ///
+    /// let mut bounce = vec![0.0; 44100];
+    /// allocator.process_parallel(&mut bounce);
+    /// ```
+    pub fn process_parallel(&mut self, buffer: &mut [f64]) {
+        let soloing = self.soloing;
+        crate::core::render_parallel_with(&mut self.voices, buffer, |state, block| {
+            Self::render_voice(state, block, soloing)
+        });
+
+        let scale = 1.0 / (VOICES as f64).sqrt();
+        for sample in buffer.iter_mut() {
+            *sample *= scale;
         }
     }
 }
@@ -546,7 +1046,12 @@ where
 {
     fn next_sample(&mut self) -> f64 {
         // Sum all voice outputs
-        let sum: f64 = self.voices.iter_mut().map(|v| v.voice.next_sample()).sum();
+        let soloing = self.soloing;
+        let sum: f64 = self
+            .voices
+            .iter_mut()
+            .map(|v| v.voice.next_sample() * v.output_gain(soloing))
+            .sum();
 
         // Normalize by sqrt(VOICES) to prevent clipping
         // This assumes some phase cancellation between voices
@@ -558,20 +1063,30 @@ where
         buffer.fill(0.0);
 
         // Mix each voice into the buffer
-        let mut voice_buffer = vec![0.0; buffer.len()];
+        let mut voice_buffer = self.scratch.acquire(buffer.len());
         for voice_state in self.voices.iter_mut() {
-            voice_state.voice.process(&mut voice_buffer);
+            Self::render_voice(voice_state, &mut voice_buffer, self.soloing);
             for (out, &voice_sample) in buffer.iter_mut().zip(voice_buffer.iter()) {
                 *out += voice_sample;
             }
         }
 
+        self.scratch.release(voice_buffer);
+
         // Normalize
         let scale = 1.0 / (VOICES as f64).sqrt();
         for sample in buffer.iter_mut() {
             *sample *= scale;
         }
     }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        for voice_state in self.voices.iter_mut() {
+            voice_state.voice.prepare(max_block_size, sample_rate);
+        }
+        // One scratch buffer per process() call, sized for the largest block
+        self.scratch.reserve(1, max_block_size);
+    }
 }
 
 impl<const SAMPLE_RATE: u32, const VOICES: usize, S, E> AudioSignal<SAMPLE_RATE>
@@ -582,6 +1097,98 @@ where
 {
 }
 
+/// Stereo output for a [`VoiceAllocator`], honouring each voice's pan.
+///
+/// The allocator itself is a mono [`Signal`]; wrap it to hear the stereo
+/// spread of unison voices. Voices are panned with a balance law, so a
+/// centred voice is at full level in both channels and the stereo output of
+/// an unpanned allocator matches its mono output on each side.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{ADSR, FrameSignal, SawtoothOscillator};
+/// use earworm::music::{StereoVoices, VoiceAllocator};
+///
+/// const SAMPLE_RATE: u32 = 44100;
+///
+/// let allocator = VoiceAllocator::<SAMPLE_RATE, 8, _, _>::new(|| {
+///     let osc = SawtoothOscillator::<SAMPLE_RATE>::new(440.0);
+///     let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+///     (osc, env)
+/// })
+/// .with_unison(4, 25.0)
+/// .with_unison_spread(1.0, 0.0);
+///
+/// let mut stereo = StereoVoices::new(allocator);
+/// stereo.allocator_mut().note_on(57, 0.9);
+/// let [left, right] = stereo.next_frame();
+/// ```
+pub struct StereoVoices<const SAMPLE_RATE: u32, const VOICES: usize, S, E>
+where
+    S: AudioSignal<SAMPLE_RATE> + Pitched,
+    E: Envelope,
+{
+    allocator: VoiceAllocator<SAMPLE_RATE, VOICES, S, E>,
+}
+
+impl<const SAMPLE_RATE: u32, const VOICES: usize, S, E> StereoVoices<SAMPLE_RATE, VOICES, S, E>
+where
+    S: AudioSignal<SAMPLE_RATE> + Pitched,
+    E: Envelope,
+{
+    /// Wraps an allocator for stereo output.
+    pub fn new(allocator: VoiceAllocator<SAMPLE_RATE, VOICES, S, E>) -> Self {
+        Self { allocator }
+    }
+
+    /// Returns a reference to the wrapped allocator.
+    pub fn allocator(&self) -> &VoiceAllocator<SAMPLE_RATE, VOICES, S, E> {
+        &self.allocator
+    }
+
+    /// Returns a mutable reference to the wrapped allocator, for playing notes.
+    pub fn allocator_mut(&mut self) -> &mut VoiceAllocator<SAMPLE_RATE, VOICES, S, E> {
+        &mut self.allocator
+    }
+
+    /// Unwraps the allocator.
+    pub fn into_inner(self) -> VoiceAllocator<SAMPLE_RATE, VOICES, S, E> {
+        self.allocator
+    }
+}
+
+impl<const SAMPLE_RATE: u32, const VOICES: usize, S, E> FrameSignal<2>
+    for StereoVoices<SAMPLE_RATE, VOICES, S, E>
+where
+    S: AudioSignal<SAMPLE_RATE> + Pitched,
+    E: Envelope,
+{
+    fn next_frame(&mut self) -> [f64; 2] {
+        let mut frame = [0.0; 2];
+        let soloing = self.allocator.soloing;
+        for state in self.allocator.voices.iter_mut() {
+            let sample = state.voice.next_sample() * state.output_gain(soloing);
+            frame[0] += sample * (1.0 - state.pan).min(1.0);
+            frame[1] += sample * (1.0 + state.pan).min(1.0);
+        }
+        let scale = 1.0 / (VOICES as f64).sqrt();
+        frame.map(|sample| sample * scale)
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        Signal::prepare(&mut self.allocator, max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, const VOICES: usize, S, E> AudioFrameSignal<SAMPLE_RATE, 2>
+    for StereoVoices<SAMPLE_RATE, VOICES, S, E>
+where
+    S: AudioSignal<SAMPLE_RATE> + Pitched,
+    E: Envelope,
+{
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -589,6 +1196,15 @@ mod tests {
 
     const SAMPLE_RATE: u32 = 44100;
 
+    fn create_test_allocator<const VOICES: usize>()
+    -> VoiceAllocator<SAMPLE_RATE, VOICES, SineOscillator<SAMPLE_RATE>, ADSR> {
+        VoiceAllocator::new(|| {
+            let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+            let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+            (osc, env)
+        })
+    }
+
     #[test]
     fn test_creation() {
         let allocator = VoiceAllocator::<SAMPLE_RATE, 4, _, _>::new(|| {
@@ -787,6 +1403,88 @@ mod tests {
         assert!(buffer.iter().any(|&s| s.abs() > 0.01));
     }
 
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn test_process_parallel_matches_process() {
+        let make = || {
+            let mut allocator = VoiceAllocator::<SAMPLE_RATE, 6, _, _>::new(|| {
+                let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+                let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+                (osc, env)
+            });
+            for note in [48, 55, 60, 64, 67] {
+                allocator.note_on(note, 0.7);
+            }
+            allocator
+        };
+        let mut sequential = make();
+        let mut parallel = make();
+
+        let mut expected = vec![0.0; 2048];
+        sequential.process(&mut expected);
+        let mut buffer = vec![0.0; 2048];
+        parallel.process_parallel(&mut buffer);
+
+        for (a, b) in buffer.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_process_reuses_scratch_buffer() {
+        let mut allocator = VoiceAllocator::<SAMPLE_RATE, 4, _, _>::new(|| {
+            let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+            let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+            (osc, env)
+        });
+        allocator.note_on(60, 0.8);
+
+        let allocations = allocator.scratch.allocations();
+        let mut buffer = vec![0.0; DEFAULT_BLOCK_SIZE];
+        for _ in 0..10 {
+            allocator.process(&mut buffer);
+        }
+        assert_eq!(allocator.scratch.allocations(), allocations);
+    }
+
+    #[test]
+    fn test_prepare_sizes_scratch_for_large_blocks() {
+        let mut allocator = VoiceAllocator::<SAMPLE_RATE, 2, _, _>::new(|| {
+            let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+            let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+            (osc, env)
+        });
+        allocator.prepare(4096, SAMPLE_RATE);
+
+        let allocations = allocator.scratch.allocations();
+        let mut buffer = vec![0.0; 4096];
+        allocator.process(&mut buffer);
+        assert_eq!(allocator.scratch.allocations(), allocations);
+    }
+
+    #[test]
+    fn test_process_matches_next_sample() {
+        let make = || {
+            let mut allocator = VoiceAllocator::<SAMPLE_RATE, 4, _, _>::new(|| {
+                let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+                let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+                (osc, env)
+            });
+            allocator.note_on(60, 0.8);
+            allocator.note_on(67, 0.5);
+            allocator
+        };
+        let mut per_sample = make();
+        let mut block = make();
+
+        let expected: Vec<f64> = (0..130).map(|_| per_sample.next_sample()).collect();
+        let mut buffer = vec![0.0; 130];
+        block.process(&mut buffer);
+        for (a, b) in buffer.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+
     #[test]
     fn test_stealing_strategy_oldest() {
         let mut allocator = VoiceAllocator::<SAMPLE_RATE, 3, _, _>::new(|| {
@@ -809,4 +1507,230 @@ mod tests {
         assert!(allocator.is_note_playing(64));
         assert!(allocator.is_note_playing(65));
     }
+
+    fn unison_allocator<const VOICES: usize>(
+        voices: usize,
+    ) -> VoiceAllocator<SAMPLE_RATE, VOICES, SineOscillator<SAMPLE_RATE>, ADSR> {
+        VoiceAllocator::new(|| {
+            let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+            let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+            (osc, env)
+        })
+        .with_unison(voices, 30.0)
+        .with_strategy(StealingStrategy::Oldest)
+    }
+
+    #[test]
+    fn test_unison_detunes_group_evenly() {
+        let mut allocator = unison_allocator::<8>(3);
+        allocator.note_on(69, 0.8);
+        assert_eq!(allocator.active_voice_count(), 3);
+
+        let mut freqs: Vec<f64> = allocator
+            .voices
+            .iter()
+            .filter(|v| v.note.is_some())
+            .map(|v| v.voice.frequency())
+            .collect();
+        freqs.sort_by(f64::total_cmp);
+        let cents = |f: f64| 1200.0 * (f / 440.0).log2();
+        assert!((cents(freqs[0]) + 15.0).abs() < 1e-9);
+        assert!(cents(freqs[1]).abs() < 1e-9);
+        assert!((cents(freqs[2]) - 15.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unison_note_off_releases_whole_group() {
+        let mut allocator = unison_allocator::<8>(3);
+        allocator.note_on(60, 0.8);
+        allocator.note_on(64, 0.8);
+        allocator.note_off(60);
+        assert!(!allocator.is_note_playing(60));
+        assert_eq!(
+            allocator.voices.iter().filter(|v| v.note.is_some()).count(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_unison_steals_groups_as_a_unit() {
+        let mut allocator = unison_allocator::<6>(3);
+        allocator.note_on(60, 0.8);
+        allocator.note_on(64, 0.8);
+        allocator.note_on(67, 0.8);
+
+        // The oldest group (60) is stolen whole; 64 keeps all its voices
+        assert!(!allocator.is_note_playing(60));
+        let count = |note| {
+            allocator
+                .voices
+                .iter()
+                .filter(|v| v.note == Some(note))
+                .count()
+        };
+        assert_eq!(count(64), 3);
+        assert_eq!(count(67), 3);
+    }
+
+    #[test]
+    fn test_unison_clamps_to_voice_count() {
+        let allocator = unison_allocator::<4>(16);
+        assert_eq!(allocator.unison_voices(), 4);
+        let allocator = unison_allocator::<4>(0);
+        assert_eq!(allocator.unison_voices(), 1);
+    }
+
+    #[test]
+    fn test_unison_spread_sets_gain_and_pan() {
+        let mut allocator = unison_allocator::<4>(3).with_unison_spread(1.0, 0.5);
+        allocator.note_on(60, 0.8);
+        let mut voices: Vec<(f64, f64)> = allocator.voices[..3]
+            .iter()
+            .map(|v| (v.pan, v.gain * 3f64.sqrt()))
+            .collect();
+        voices.sort_by(|a, b| a.0.total_cmp(&b.0));
+        assert_eq!(voices, vec![(-1.0, 0.5), (0.0, 1.0), (1.0, 0.5)]);
+    }
+
+    #[test]
+    fn test_stereo_voices_pan() {
+        let mut stereo = StereoVoices::new(unison_allocator::<4>(2).with_unison_spread(1.0, 0.0));
+        stereo.allocator_mut().note_on(60, 0.8);
+        let (mut left, mut right) = (0.0, 0.0);
+        for _ in 0..2000 {
+            let [l, r] = stereo.next_frame();
+            left += l.abs();
+            right += r.abs();
+        }
+        // Each channel hears one hard-panned voice plus nothing from the other
+        assert!(left > 0.0 && right > 0.0);
+        assert!((left - right).abs() / left < 0.1);
+    }
+
+    #[test]
+    fn test_stereo_matches_mono_without_spread() {
+        let mut mono = unison_allocator::<4>(1);
+        let mut stereo = StereoVoices::new(unison_allocator::<4>(1));
+        mono.note_on(60, 0.8);
+        stereo.allocator_mut().note_on(60, 0.8);
+        for _ in 0..500 {
+            let m = mono.next_sample();
+            assert_eq!(stereo.next_frame(), [m, m]);
+        }
+    }
+
+    #[test]
+    fn test_mono_portamento_glides_between_notes() {
+        let mut lead = VoiceAllocator::<SAMPLE_RATE, 1, _, _>::new(|| {
+            let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+            let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+            (osc, env)
+        })
+        .with_portamento(0.05, Curve::Linear);
+
+        lead.note_on(57, 0.8);
+        lead.next_sample();
+        lead.note_on(69, 0.8);
+        lead.next_sample();
+        assert!(lead.voices[0].voice.is_gliding());
+        let frequency = lead.voices[0].voice.frequency();
+        assert!(frequency > 220.0 && frequency < 440.0);
+    }
+
+    #[test]
+    fn test_voices_reports_allocation() {
+        let mut allocator = create_test_allocator::<4>();
+        allocator.note_on(60, 0.8);
+        allocator.note_on(64, 0.5);
+        for _ in 0..100 {
+            allocator.next_sample();
+        }
+        allocator.note_off(60);
+
+        let infos: Vec<VoiceInfo> = allocator.voices().collect();
+        assert_eq!(infos.len(), 4);
+        assert_eq!(infos.iter().filter(|v| v.active).count(), 2);
+
+        let released = infos.iter().find(|v| v.releasing).unwrap();
+        assert_eq!(released.note, None);
+        let held = infos.iter().find(|v| v.note == Some(64)).unwrap();
+        assert_eq!(held.velocity, 0.5);
+        assert!(held.age > released.age);
+        assert!(held.level > 0.0);
+        assert_eq!(allocator.voice_info(held.index), Some(*held));
+        assert_eq!(allocator.voice_info(4), None);
+    }
+
+    #[test]
+    fn test_mute_solo_and_gain() {
+        let mut allocator = create_test_allocator::<2>();
+        allocator.note_on(60, 1.0);
+        allocator.note_on(72, 1.0);
+        let low = allocator
+            .voices()
+            .find(|v| v.note == Some(60))
+            .unwrap()
+            .index;
+        let high = 1 - low;
+
+        // Reference: only the low note
+        let mut reference = create_test_allocator::<2>();
+        reference.note_on(60, 1.0);
+
+        allocator.set_voice_muted(high, true);
+        let mut buffer = vec![0.0; 256];
+        allocator.process(&mut buffer);
+        for &sample in &buffer {
+            assert!((sample - reference.next_sample()).abs() < 1e-9);
+        }
+
+        // Soloing overrides the other voice even when unmuted
+        allocator.set_voice_muted(high, false);
+        allocator.set_voice_solo(low, true);
+        assert!(allocator.voice_info(low).unwrap().soloed);
+        for _ in 0..256 {
+            assert!((allocator.next_sample() - reference.next_sample()).abs() < 1e-9);
+        }
+
+        allocator.set_voice_gain(low, 0.5);
+        for _ in 0..256 {
+            assert!((allocator.next_sample() - 0.5 * reference.next_sample()).abs() < 1e-9);
+        }
+
+        allocator.reset_voice_mix();
+        assert!(
+            allocator
+                .voices()
+                .all(|v| !v.muted && !v.soloed && v.gain == 1.0)
+        );
+    }
+
+    #[test]
+    fn test_pitch_bend_reaches_all_voices() {
+        let mut allocator = create_test_allocator::<4>();
+        allocator.note_on(57, 0.8);
+        allocator.set_pitch_bend(12.0);
+        allocator.note_on(69, 0.8);
+
+        let mut frequencies: Vec<f64> = allocator
+            .voices
+            .iter()
+            .filter(|v| v.note.is_some())
+            .map(|v| v.voice.frequency())
+            .collect();
+        frequencies.sort_by(f64::total_cmp);
+        assert!((frequencies[0] - 440.0).abs() < 1e-9);
+        assert!((frequencies[1] - 880.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_mod_wheel_shared_by_voices() {
+        let wheel = ParamHandle::new(0.0);
+        let mut allocator = create_test_allocator::<4>().with_mod_wheel(wheel.clone());
+        allocator.set_mod_amount(0.5);
+        assert_eq!(wheel.get(), 0.5);
+        assert!(allocator.voices.iter().all(|v| v.voice.mod_amount() == 0.5));
+        allocator.set_mod_amount(-1.0);
+        assert_eq!(allocator.mod_amount(), 0.0);
+    }
 }
@@ -216,8 +216,11 @@ impl Envelope for AR {
                 }
             }
 
-            // AR doesn't use Decay or Sustain, but we need to handle them for the enum
-            EnvelopeState::Decay | EnvelopeState::Sustain => {
+            // AR doesn't use Delay, Hold, Decay or Sustain, but we need to handle them for the enum
+            EnvelopeState::Delay
+            | EnvelopeState::Hold
+            | EnvelopeState::Decay
+            | EnvelopeState::Sustain => {
                 // Shouldn't happen, but if it does, treat as release
                 self.state = EnvelopeState::Release;
                 self.phase_position = 0.0;
@@ -0,0 +1,516 @@
+//! Offline rendering of patterns and song sections to loopable audio clips.
+
+use super::{
+    command::NoteTarget,
+    core::{Key, NoteEvent},
+    metronome::Metronome,
+    pattern::Pattern,
+    song::Song,
+};
+use crate::core::{AudioSignal, Signal};
+
+/// Longest tail captured after the last loop, in seconds.
+const MAX_TAIL: f64 = 10.0;
+
+/// Window over which the tail must stay below [`SILENCE_THRESHOLD`] to end.
+const SILENCE_WINDOW: f64 = 0.1;
+
+/// Silence needed to end the tail of an effect chain, in seconds. Longer than
+/// [`SILENCE_WINDOW`] so the gap before a delay's echo isn't taken as the end.
+const EFFECT_SILENCE_WINDOW: f64 = 2.0;
+
+/// Peak level treated as silence (-80dB).
+const SILENCE_THRESHOLD: f64 = 1e-4;
+
+/// Renders `loops` passes of `pattern` through `instrument` into a seamless loop.
+///
+/// Notes start on their step, sample-accurately, and are released after their
+/// duration (one step if they have none). After the last pass the instrument
+/// keeps rendering until its release and effect tails die away, and that tail
+/// is mixed back into the start of the clip. The clip is therefore exactly
+/// `loops * pattern.length()` steps long and sounds the same when played on
+/// repeat as the pattern would playing continuously, without a click or gap at
+/// the loop point.
+///
+/// The instrument should start out silent; it is left with every note released.
+///
+/// # Arguments
+///
+/// * `pattern` - Pattern to render; note pitches are rounded to MIDI notes.
+///   Scale-degree events are resolved in C major; bounce
+///   [`pattern.resolved(&key)`](Pattern::resolved) for another key
+/// * `instrument` - Instrument to play the notes on
+/// * `metronome` - Tempo and step resolution (its position is ignored)
+/// * `loops` - Number of times to play the pattern
+///
+/// # Examples
+///
+/// ```
+/// use earworm::music::{Metronome, Pattern, VoiceAllocator, bounce_pattern};
+/// use earworm::{ADSR, NoteEvent, Pitch, SineOscillator};
+///
+/// const SAMPLE_RATE: u32 = 44100;
+///
+/// let mut pattern = Pattern::new(16);
+/// pattern.add_event(0, NoteEvent::from_pitch(Pitch::C, 3, 0.8, Some(0.2)));
+/// pattern.add_event(8, NoteEvent::from_pitch(Pitch::G, 3, 0.8, Some(0.2)));
+///
+/// let mut synth = VoiceAllocator::<SAMPLE_RATE, 4, _, _>::new(|| {
+///     let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+///     let env = ADSR::new(0.01, 0.1, 0.7, 0.5, SAMPLE_RATE as f64);
+///     (osc, env)
+/// });
+///
+/// // Two bars of 16th notes at 120 BPM
+/// let metronome = Metronome::new(120.0, 4, SAMPLE_RATE);
+/// let clip = bounce_pattern(&pattern, &mut synth, &metronome, 2);
+/// assert_eq!(clip.len(), 2 * 88200);
+/// ```
+pub fn bounce_pattern<const SAMPLE_RATE: u32, I>(
+    pattern: &Pattern,
+    instrument: &mut I,
+    metronome: &Metronome,
+    loops: usize,
+) -> Vec<f64>
+where
+    I: AudioSignal<SAMPLE_RATE> + NoteTarget,
+{
+    let resolved = pattern.resolved(&Key::default());
+    let events = (0..loops).flat_map(|pass| {
+        resolved
+            .events()
+            .map(move |(step, event)| (pass * pattern.length() + step, *event))
+    });
+    let schedule = Schedule::new::<SAMPLE_RATE>(events, pattern.length() * loops, metronome);
+    if schedule.length == 0 {
+        return Vec::new();
+    }
+
+    let output = schedule.play(instrument);
+    wrap_tail(output, schedule.length)
+}
+
+/// Renders an arrangement section, run through an effect chain, into a
+/// seamless loop.
+///
+/// The section plays its pattern for its number of repeats, with degree
+/// events resolved in `key` and chord-following patterns over the section's
+/// chord track, just as a [`SongPlayer`](super::SongPlayer) would play it.
+/// The instrument's dry output is fed through the chain `effects` builds
+/// around its [`BounceInput`], and the chain runs on past the end until its
+/// reverb and delay tails die away. That tail is mixed back into the start
+/// of the clip, so the loop's first beat carries the echoes of its last one
+/// instead of starting dry. The tail ends after two seconds of silence, so
+/// echoes spaced further apart than that are cut off.
+///
+/// The clip is `repeats * pattern length` steps long. Pass `|dry| dry` for
+/// no effects.
+///
+/// # Arguments
+///
+/// * `song` - Song holding the section
+/// * `section` - Index of the section to render
+/// * `instrument` - Instrument to play the notes on (should start silent)
+/// * `effects` - Builds the effect chain around the dry instrument output
+/// * `metronome` - Tempo and step resolution (its position is ignored)
+/// * `key` - Key that scale-degree events are resolved in
+///
+/// # Panics
+///
+/// Panics if `section` is not a valid section index.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::music::core::Key;
+/// use earworm::music::{Metronome, Pattern, Song, VoiceAllocator, bounce_section};
+/// use earworm::{ADSR, AudioSignalExt, NoteEvent, Pitch, SineOscillator};
+///
+/// const SAMPLE_RATE: u32 = 44100;
+///
+/// let mut stab = Pattern::new(16);
+/// stab.add_event(12, NoteEvent::from_pitch(Pitch::E, 4, 0.8, Some(0.1)));
+///
+/// let mut song = Song::new();
+/// let stab = song.add_pattern(stab);
+/// let hook = song.add_section("hook", stab, 1);
+///
+/// let mut synth = VoiceAllocator::<SAMPLE_RATE, 4, _, _>::new(|| {
+///     let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+///     let env = ADSR::new(0.01, 0.1, 0.7, 0.2, SAMPLE_RATE as f64);
+///     (osc, env)
+/// });
+///
+/// // The delay's echoes of the last stab wrap around to the top of the loop
+/// let metronome = Metronome::new(120.0, 4, SAMPLE_RATE);
+/// let clip = bounce_section(
+///     &song,
+///     hook,
+///     &mut synth,
+///     |dry| dry.delay(0.5, 0.375, 0.5, 0.4),
+///     &metronome,
+///     &Key::default(),
+/// );
+/// assert_eq!(clip.len(), 88200);
+/// ```
+pub fn bounce_section<const SAMPLE_RATE: u32, I, E, F>(
+    song: &Song,
+    section: usize,
+    instrument: &mut I,
+    effects: F,
+    metronome: &Metronome,
+    key: &Key,
+) -> Vec<f64>
+where
+    I: AudioSignal<SAMPLE_RATE> + NoteTarget,
+    E: AudioSignal<SAMPLE_RATE>,
+    F: FnOnce(BounceInput<SAMPLE_RATE>) -> E,
+{
+    let events = song.section_events(section, key);
+    let info = &song.sections()[section];
+    let steps = song.patterns()[info.pattern].length() * info.repeats;
+    let schedule = Schedule::new::<SAMPLE_RATE>(events, steps, metronome);
+    if schedule.length == 0 {
+        return Vec::new();
+    }
+
+    let dry = schedule.play(instrument);
+    let dry_length = dry.len();
+    let mut chain = effects(BounceInput {
+        samples: dry,
+        position: 0,
+    });
+    let output = render_until_silent::<SAMPLE_RATE>(dry_length, EFFECT_SILENCE_WINDOW, || {
+        chain.next_sample()
+    });
+    wrap_tail(output, schedule.length)
+}
+
+/// Renders a whole song, every section in order, into one clip.
+///
+/// The sections play back to back as a non-looping
+/// [`SongPlayer`](super::SongPlayer) would play them, with degree events
+/// resolved in `key` or over each section's chord track. The clip starts at
+/// the first step of the first section and runs on after the last step until
+/// the instrument's release dies away, so it is at least
+/// [`song.length_in_steps()`](Song::length_in_steps) steps long. Unlike
+/// [`bounce_pattern`], the tail is left at the end rather than wrapped.
+///
+/// # Arguments
+///
+/// * `song` - Song to render
+/// * `instrument` - Instrument to play the notes on (should start silent)
+/// * `metronome` - Tempo and step resolution (its position is ignored)
+/// * `key` - Key that scale-degree events are resolved in
+///
+/// # Examples
+///
+/// ```
+/// use earworm::music::core::Key;
+/// use earworm::music::{Metronome, Pattern, Song, VoiceAllocator, bounce_song};
+/// use earworm::{ADSR, NoteEvent, Pitch, SineOscillator};
+///
+/// const SAMPLE_RATE: u32 = 44100;
+///
+/// let mut riff = Pattern::new(16);
+/// riff.add_event(0, NoteEvent::from_pitch(Pitch::A, 2, 0.9, Some(0.2)));
+///
+/// let mut song = Song::new();
+/// let riff = song.add_pattern(riff);
+/// song.add_section("verse", riff, 2);
+/// song.add_section("outro", riff, 1);
+///
+/// let mut synth = VoiceAllocator::<SAMPLE_RATE, 4, _, _>::new(|| {
+///     let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+///     let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+///     (osc, env)
+/// });
+///
+/// // Three bars at 120 BPM, plus the last note's release
+/// let metronome = Metronome::new(120.0, 4, SAMPLE_RATE);
+/// let clip = bounce_song(&song, &mut synth, &metronome, &Key::default());
+/// assert!(clip.len() >= 3 * 88200);
+/// ```
+pub fn bounce_song<const SAMPLE_RATE: u32, I>(
+    song: &Song,
+    instrument: &mut I,
+    metronome: &Metronome,
+    key: &Key,
+) -> Vec<f64>
+where
+    I: AudioSignal<SAMPLE_RATE> + NoteTarget,
+{
+    let events = (0..song.sections().len()).flat_map(|section| {
+        let start = song.section_start(section);
+        song.section_events(section, key)
+            .into_iter()
+            .map(move |(step, event)| (start + step, event))
+    });
+    let schedule = Schedule::new::<SAMPLE_RATE>(events, song.length_in_steps(), metronome);
+    if schedule.length == 0 {
+        return Vec::new();
+    }
+    schedule.play(instrument)
+}
+
+/// The dry instrument output handed to the effect chain of
+/// [`bounce_section`].
+///
+/// Plays the rendered samples, then silence for as long as the effects keep
+/// ringing.
+#[derive(Debug, Clone)]
+pub struct BounceInput<const SAMPLE_RATE: u32> {
+    samples: Vec<f64>,
+    position: usize,
+}
+
+impl<const SAMPLE_RATE: u32> Signal for BounceInput<SAMPLE_RATE> {
+    fn next_sample(&mut self) -> f64 {
+        let sample = self.samples.get(self.position).copied().unwrap_or(0.0);
+        self.position += 1;
+        sample
+    }
+}
+
+impl<const SAMPLE_RATE: u32> AudioSignal<SAMPLE_RATE> for BounceInput<SAMPLE_RATE> {}
+
+/// Note ons and offs at sample positions, and the clip length they loop over.
+struct Schedule {
+    /// (sample, note, velocity); a velocity of None is a note off
+    notes: Vec<(usize, u8, Option<f64>)>,
+    length: usize,
+}
+
+impl Schedule {
+    /// Schedules `events` given at step positions, for a clip `steps` long.
+    ///
+    /// Notes are released after their duration, or one step if they have
+    /// none.
+    fn new<const SAMPLE_RATE: u32>(
+        events: impl IntoIterator<Item = (usize, NoteEvent)>,
+        steps: usize,
+        metronome: &Metronome,
+    ) -> Self {
+        let rate = SAMPLE_RATE as f64;
+        let samples_per_step =
+            rate * 60.0 / (metronome.tempo() * metronome.steps_per_beat() as f64);
+        let length = (samples_per_step * steps as f64).round() as usize;
+
+        let mut notes = Vec::new();
+        for (step, event) in events {
+            let start = (step as f64 * samples_per_step).round();
+            let duration = event.duration.map_or(samples_per_step, |secs| secs * rate);
+            let note = event.note.to_midi();
+            notes.push((start as usize, note, Some(event.velocity)));
+            notes.push(((start + duration.max(1.0)).round() as usize, note, None));
+        }
+        // Note offs first, so a note retriggered on the step it ends on keeps sounding
+        notes.sort_by_key(|&(sample, _, velocity)| (sample, velocity.is_some()));
+        Self { notes, length }
+    }
+
+    /// Plays the schedule on `instrument` and renders until its tail dies
+    /// away, leaving every note released.
+    fn play<const SAMPLE_RATE: u32, I>(&self, instrument: &mut I) -> Vec<f64>
+    where
+        I: AudioSignal<SAMPLE_RATE> + NoteTarget,
+    {
+        let last_event = self.notes.last().map_or(0, |&(sample, ..)| sample + 1);
+        let mut pending = self.notes.iter().peekable();
+        let mut position = 0;
+        let min_length = self.length.max(last_event);
+        let output = render_until_silent::<SAMPLE_RATE>(min_length, SILENCE_WINDOW, || {
+            while let Some(&&(_, note, velocity)) =
+                pending.peek().filter(|(sample, ..)| *sample <= position)
+            {
+                match velocity {
+                    Some(velocity) => instrument.note_on(note, velocity),
+                    None => instrument.note_off(note),
+                }
+                pending.next();
+            }
+            position += 1;
+            instrument.next_sample()
+        });
+        instrument.all_notes_off();
+        output
+    }
+}
+
+/// Pulls samples from `next` for at least `min_length` samples, then until
+/// the output has been silent for `silence_window` seconds (or [`MAX_TAIL`]
+/// has passed).
+fn render_until_silent<const SAMPLE_RATE: u32>(
+    min_length: usize,
+    silence_window: f64,
+    mut next: impl FnMut() -> f64,
+) -> Vec<f64> {
+    let rate = SAMPLE_RATE as f64;
+    let max_length = min_length + (MAX_TAIL * rate) as usize;
+    let silence_window = (silence_window * rate) as usize;
+    let mut output = Vec::with_capacity(min_length);
+    let mut quiet_samples = 0;
+
+    while output.len() < max_length {
+        let sample = next();
+        output.push(sample);
+
+        quiet_samples = if sample.abs() < SILENCE_THRESHOLD {
+            quiet_samples + 1
+        } else {
+            0
+        };
+        if output.len() >= min_length && quiet_samples >= silence_window {
+            break;
+        }
+    }
+    output
+}
+
+/// Wraps everything past `length` around onto the start of the clip.
+fn wrap_tail(mut output: Vec<f64>, length: usize) -> Vec<f64> {
+    output.resize(output.len().max(length), 0.0);
+    let tail = output.split_off(length);
+    for (i, sample) in tail.into_iter().enumerate() {
+        output[i % length] += sample;
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::music::VoiceAllocator;
+    use crate::music::core::NoteEvent;
+    use crate::{ADSR, SineOscillator};
+
+    const SAMPLE_RATE: u32 = 8000;
+
+    fn synth(release: f64) -> impl AudioSignal<SAMPLE_RATE> + NoteTarget {
+        VoiceAllocator::<SAMPLE_RATE, 4, _, _>::new(move || {
+            let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+            let env = ADSR::new(0.001, 0.01, 1.0, release, SAMPLE_RATE as f64);
+            (osc, env)
+        })
+    }
+
+    /// 120 BPM quarter notes at 8kHz: 4000 samples per step.
+    fn metronome() -> Metronome {
+        Metronome::new(120.0, 1, SAMPLE_RATE)
+    }
+
+    fn peak(samples: &[f64]) -> f64 {
+        samples.iter().fold(0.0_f64, |max, s| max.max(s.abs()))
+    }
+
+    #[test]
+    fn test_notes_land_on_steps() {
+        let mut pattern = Pattern::new(4);
+        pattern.add_event(2, NoteEvent::from_midi(69, 100, Some(0.1)));
+        let clip = bounce_pattern(&pattern, &mut synth(0.01), &metronome(), 3);
+
+        assert_eq!(clip.len(), 3 * 4 * 4000);
+        for pass in 0..3 {
+            let start = (pass * 4 + 2) * 4000;
+            assert_eq!(peak(&clip[start - 100..start]), 0.0);
+            assert!(peak(&clip[start..start + 400]) > 0.2);
+        }
+    }
+
+    #[test]
+    fn test_tail_wraps_to_start() {
+        // A long release on the last step rings past the end of the clip
+        let mut pattern = Pattern::new(4);
+        pattern.add_event(3, NoteEvent::from_midi(69, 100, Some(0.2)));
+        let clip = bounce_pattern(&pattern, &mut synth(1.0), &metronome(), 1);
+
+        assert_eq!(clip.len(), 16000);
+        // The wrapped tail carries on at the level the clip ends at
+        let end = peak(&clip[clip.len() - 400..]);
+        let start = peak(&clip[..400]);
+        assert!(end > 0.05);
+        assert!(
+            start > end * 0.8 && start < end * 1.25,
+            "{} vs {}",
+            start,
+            end
+        );
+    }
+
+    #[test]
+    fn test_section_matches_pattern_bounce() {
+        let mut pattern = Pattern::new(4);
+        pattern.add_event(0, NoteEvent::from_midi(60, 100, Some(0.1)));
+        pattern.add_event(3, NoteEvent::from_midi(67, 100, Some(0.3)));
+        let mut song = Song::new();
+        let index = song.add_pattern(pattern.clone());
+        let section = song.add_section("a", index, 2);
+
+        let looped = bounce_pattern(&pattern, &mut synth(0.5), &metronome(), 2);
+        let section = bounce_section(
+            &song,
+            section,
+            &mut synth(0.5),
+            |dry| dry,
+            &metronome(),
+            &Key::default(),
+        );
+        assert_eq!(looped, section);
+    }
+
+    #[test]
+    fn test_song_plays_sections_back_to_back() {
+        let mut low = Pattern::new(2);
+        low.add_event(1, NoteEvent::from_midi(57, 100, Some(0.1)));
+        let mut high = Pattern::new(4);
+        high.add_event(3, NoteEvent::from_midi(69, 100, Some(0.1)));
+        let mut song = Song::new();
+        let low = song.add_pattern(low);
+        let high = song.add_pattern(high);
+        let first = song.add_section("a", low, 2);
+        song.add_section("b", high, 1);
+
+        let clip = bounce_song(&song, &mut synth(0.5), &metronome(), &Key::default());
+        let a = bounce_section(
+            &song,
+            first,
+            &mut synth(0.5),
+            |dry| dry,
+            &metronome(),
+            &Key::default(),
+        );
+        assert_eq!(peak(&clip[..4000]), 0.0);
+        assert_eq!(clip[4000..8000], a[4000..8000]);
+        // a's last note rings on into b instead of wrapping to a's start
+        assert!(peak(&clip[16000..16400]) > 0.01);
+        // b's last note is left ringing past the end of the song
+        assert!(peak(&clip[28000..28400]) > 0.2);
+        assert!(clip.len() > 8 * 4000);
+        assert!(peak(&clip[32000..32400]) > 0.01);
+    }
+
+    #[test]
+    fn test_effect_tail_wraps_to_start() {
+        use crate::AudioSignalExt;
+
+        // A single echo 0.6s after a note on the last step lands past the end
+        let mut pattern = Pattern::new(4);
+        pattern.add_event(3, NoteEvent::from_midi(69, 100, Some(0.05)));
+        let mut song = Song::new();
+        let index = song.add_pattern(pattern);
+        let section = song.add_section("a", index, 1);
+
+        let clip = bounce_section(
+            &song,
+            section,
+            &mut synth(0.01),
+            |dry| dry.delay(1.0, 0.6, 0.0, 0.5),
+            &metronome(),
+            &Key::default(),
+        );
+        assert_eq!(clip.len(), 16000);
+        // The echo starts 800 samples into the loop, after silence
+        assert!(peak(&clip[..700]) < 1e-3);
+        assert!(peak(&clip[800..1200]) > 0.1);
+    }
+}
@@ -0,0 +1,227 @@
+//! Commands for controlling voices and sequencers from another thread.
+//!
+//! These implement [`CommandTarget`] for the music types, so a
+//! [`VoiceAllocator`] or [`Sequencer`] living on the audio thread can be
+//! played and reconfigured through a [`command_queue`](crate::core::command_queue)
+//! instead of a shared `Mutex`. [`NoteTarget`] is the direct-call equivalent
+//! for code that plays notes on the same thread.
+
+use super::{
+    allocator::VoiceAllocator, envelope::Envelope, metronome::TempoCurve, pattern::Pattern,
+    sequencer::Sequencer,
+};
+use crate::core::CommandTarget;
+use crate::{AudioSignal, Pitched};
+
+/// Note commands for a [`VoiceAllocator`].
+///
+/// # Examples
+///
+/// ```
+/// use earworm::core::command_queue;
+/// use earworm::music::{NoteCommand, VoiceAllocator};
+/// use earworm::{ADSR, Signal, SineOscillator};
+///
+/// const SAMPLE_RATE: u32 = 44100;
+///
+/// let allocator = VoiceAllocator::<SAMPLE_RATE, 4, _, _>::new(|| {
+///     let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+///     let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+///     (osc, env)
+/// });
+///
+/// let (sender, receiver) = command_queue(64);
+/// let mut synth = receiver.control(allocator);
+///
+/// sender.send(NoteCommand::NoteOn { note: 60, velocity: 0.8 }).ok();
+/// synth.next_sample();
+/// assert!(synth.inner().is_note_playing(60));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoteCommand {
+    /// Start a note (MIDI note number, velocity 0.0-1.0)
+    NoteOn { note: u8, velocity: f64 },
+    /// Release a note (MIDI note number)
+    NoteOff { note: u8 },
+    /// Release every playing note
+    AllNotesOff,
+}
+
+impl<const SAMPLE_RATE: u32, const VOICES: usize, S, E> CommandTarget<NoteCommand>
+    for VoiceAllocator<SAMPLE_RATE, VOICES, S, E>
+where
+    S: AudioSignal<SAMPLE_RATE> + Pitched,
+    E: Envelope,
+{
+    fn apply(&mut self, command: NoteCommand) {
+        match command {
+            NoteCommand::NoteOn { note, velocity } => self.note_on(note, velocity),
+            NoteCommand::NoteOff { note } => self.note_off(note),
+            NoteCommand::AllNotesOff => self.all_notes_off(),
+        }
+    }
+}
+
+/// Something that can play notes, such as a [`VoiceAllocator`].
+///
+/// Implement this on your own instruments to drive them from a
+/// `MidiDispatcher` (with the `midi` feature) or [`bounce_pattern`](super::bounce_pattern).
+pub trait NoteTarget {
+    /// Starts a note at `velocity` (0.0-1.0).
+    fn note_on(&mut self, note: u8, velocity: f64);
+
+    /// Releases a note.
+    fn note_off(&mut self, note: u8);
+
+    /// Releases every note.
+    fn all_notes_off(&mut self);
+}
+
+impl<const SAMPLE_RATE: u32, const VOICES: usize, S, E> NoteTarget
+    for VoiceAllocator<SAMPLE_RATE, VOICES, S, E>
+where
+    S: AudioSignal<SAMPLE_RATE> + Pitched,
+    E: Envelope,
+{
+    fn note_on(&mut self, note: u8, velocity: f64) {
+        VoiceAllocator::note_on(self, note, velocity);
+    }
+
+    fn note_off(&mut self, note: u8) {
+        VoiceAllocator::note_off(self, note);
+    }
+
+    fn all_notes_off(&mut self) {
+        VoiceAllocator::all_notes_off(self);
+    }
+}
+
+/// Transport and pattern commands for a [`Sequencer`].
+///
+/// # Examples
+///
+/// ```
+/// use earworm::core::{CommandTarget, command_queue};
+/// use earworm::music::{Pattern, Sequencer, SequencerCommand};
+///
+/// let (sender, receiver) = command_queue(16);
+/// let mut sequencer = Sequencer::new(120.0, 4, 44100);
+///
+/// sender.send(SequencerCommand::SetPattern(Pattern::new(16))).ok();
+/// sender.send(SequencerCommand::Play).ok();
+///
+/// // On the audio thread, before ticking
+/// receiver.apply_pending(&mut sequencer);
+/// assert!(sequencer.is_playing());
+/// ```
+#[derive(Debug, Clone)]
+pub enum SequencerCommand {
+    /// Start playback
+    Play,
+    /// Stop playback
+    Stop,
+    /// Stop and return to step 0
+    Reset,
+    /// Change tempo (BPM, must be > 0)
+    SetTempo(f64),
+    /// Glide to a tempo (BPM, must be > 0) over a number of beats
+    RampTempo {
+        bpm: f64,
+        beats: f64,
+        curve: TempoCurve,
+    },
+    /// Swap in a new pattern.
+    ///
+    /// The replaced pattern is dropped on the audio thread; keep patterns
+    /// small or reuse them if deallocation there is a concern.
+    SetPattern(Pattern),
+    /// Switch to a new pattern on the next bar line
+    QueuePattern(Pattern),
+    /// Remove the current pattern
+    ClearPattern,
+    /// Turn fills on or off
+    SetFill(bool),
+}
+
+impl CommandTarget<SequencerCommand> for Sequencer {
+    fn apply(&mut self, command: SequencerCommand) {
+        match command {
+            SequencerCommand::Play => self.play(),
+            SequencerCommand::Stop => self.stop(),
+            SequencerCommand::Reset => self.reset(),
+            SequencerCommand::SetTempo(bpm) => self.set_tempo(bpm),
+            SequencerCommand::RampTempo { bpm, beats, curve } => self.ramp_tempo(bpm, beats, curve),
+            SequencerCommand::SetPattern(pattern) => self.set_pattern(pattern),
+            SequencerCommand::QueuePattern(pattern) => self.queue_pattern(pattern),
+            SequencerCommand::ClearPattern => self.clear_pattern(),
+            SequencerCommand::SetFill(fill) => self.set_fill(fill),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::command_queue;
+    use crate::{ADSR, SineOscillator};
+
+    const SAMPLE_RATE: u32 = 44100;
+
+    #[test]
+    fn test_note_commands() {
+        let mut allocator = VoiceAllocator::<SAMPLE_RATE, 4, _, _>::new(|| {
+            (
+                SineOscillator::<SAMPLE_RATE>::new(440.0),
+                ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64),
+            )
+        });
+        let (sender, receiver) = command_queue(8);
+
+        sender
+            .send(NoteCommand::NoteOn {
+                note: 60,
+                velocity: 1.0,
+            })
+            .unwrap();
+        sender
+            .send(NoteCommand::NoteOn {
+                note: 64,
+                velocity: 1.0,
+            })
+            .unwrap();
+        receiver.apply_pending(&mut allocator);
+        assert!(allocator.is_note_playing(60));
+        assert!(allocator.is_note_playing(64));
+
+        sender.send(NoteCommand::NoteOff { note: 60 }).unwrap();
+        receiver.apply_pending(&mut allocator);
+        assert!(!allocator.is_note_playing(60));
+
+        sender.send(NoteCommand::AllNotesOff).unwrap();
+        receiver.apply_pending(&mut allocator);
+        assert!(!allocator.is_note_playing(64));
+    }
+
+    #[test]
+    fn test_sequencer_commands() {
+        let mut sequencer = Sequencer::new(120.0, 4, SAMPLE_RATE);
+        let (sender, receiver) = command_queue(8);
+
+        sender
+            .send(SequencerCommand::SetPattern(Pattern::new(8)))
+            .unwrap();
+        sender.send(SequencerCommand::SetTempo(90.0)).unwrap();
+        sender.send(SequencerCommand::Play).unwrap();
+        receiver.apply_pending(&mut sequencer);
+
+        assert!(sequencer.is_playing());
+        assert_eq!(sequencer.tempo(), 90.0);
+        assert_eq!(sequencer.pattern().map(|p| p.length()), Some(8));
+
+        sender.send(SequencerCommand::Stop).unwrap();
+        sender.send(SequencerCommand::ClearPattern).unwrap();
+        receiver.apply_pending(&mut sequencer);
+        assert!(!sequencer.is_playing());
+        assert!(sequencer.pattern().is_none());
+    }
+}
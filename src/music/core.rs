@@ -86,6 +86,34 @@ impl Pitch {
         }
     }
 
+    /// Returns the pitch `semitone` semitones above C, wrapping at the octave.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::core::Pitch;
+    ///
+    /// assert_eq!(Pitch::from_semitone(9), Pitch::A);
+    /// assert_eq!(Pitch::from_semitone(61), Pitch::CSharp);
+    /// ```
+    pub fn from_semitone(semitone: u8) -> Pitch {
+        const PITCHES: [Pitch; 12] = [
+            Pitch::C,
+            Pitch::CSharp,
+            Pitch::D,
+            Pitch::DSharp,
+            Pitch::E,
+            Pitch::F,
+            Pitch::FSharp,
+            Pitch::G,
+            Pitch::GSharp,
+            Pitch::A,
+            Pitch::ASharp,
+            Pitch::B,
+        ];
+        PITCHES[(semitone % 12) as usize]
+    }
+
     /// Converts a note name and octave to a MIDI note number.
     ///
     /// MIDI note numbers range from 0-127, where:
@@ -266,6 +294,24 @@ impl Note {
         Self::new(Self::midi_to_freq(midi_note))
     }
 
+    /// Returns the nearest MIDI note number to this note's pitch.
+    ///
+    /// Pitches outside the MIDI range are clamped to 0-127.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::core::{Note, Pitch};
+    ///
+    /// assert_eq!(Note::from_pitch(Pitch::C, 4).to_midi(), 60);
+    /// assert_eq!(Note::new(445.0).to_midi(), 69); // Slightly sharp A4
+    /// ```
+    pub fn to_midi(&self) -> u8 {
+        (69.0 + 12.0 * (self.pitch / 440.0).log2())
+            .round()
+            .clamp(0.0, 127.0) as u8
+    }
+
     /// Creates a note from a pitch name and octave.
     ///
     /// # Examples
@@ -411,10 +457,554 @@ impl FromStr for Note {
     }
 }
 
+/// A musical scale: which intervals above the tonic belong to it.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::music::core::Scale;
+///
+/// assert_eq!(Scale::Major.intervals(), &[0, 2, 4, 5, 7, 9, 11]);
+/// assert_eq!(Scale::MinorPentatonic.intervals().len(), 5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Scale {
+    /// Major (Ionian)
+    #[default]
+    Major,
+    /// Natural minor (Aeolian)
+    Minor,
+    /// Dorian mode
+    Dorian,
+    /// Phrygian mode
+    Phrygian,
+    /// Lydian mode
+    Lydian,
+    /// Mixolydian mode
+    Mixolydian,
+    /// Locrian mode
+    Locrian,
+    /// Harmonic minor (raised 7th)
+    HarmonicMinor,
+    /// Melodic minor, ascending form (raised 6th and 7th)
+    MelodicMinor,
+    /// Major pentatonic
+    MajorPentatonic,
+    /// Minor pentatonic
+    MinorPentatonic,
+    /// All twelve semitones
+    Chromatic,
+}
+
+impl Scale {
+    /// Returns the scale's intervals in semitones above the tonic, starting with 0.
+    pub fn intervals(&self) -> &'static [u8] {
+        match self {
+            Scale::Major => &[0, 2, 4, 5, 7, 9, 11],
+            Scale::Minor => &[0, 2, 3, 5, 7, 8, 10],
+            Scale::Dorian => &[0, 2, 3, 5, 7, 9, 10],
+            Scale::Phrygian => &[0, 1, 3, 5, 7, 8, 10],
+            Scale::Lydian => &[0, 2, 4, 6, 7, 9, 11],
+            Scale::Mixolydian => &[0, 2, 4, 5, 7, 9, 10],
+            Scale::Locrian => &[0, 1, 3, 5, 6, 8, 10],
+            Scale::HarmonicMinor => &[0, 2, 3, 5, 7, 8, 11],
+            Scale::MelodicMinor => &[0, 2, 3, 5, 7, 9, 11],
+            Scale::MajorPentatonic => &[0, 2, 4, 7, 9],
+            Scale::MinorPentatonic => &[0, 3, 5, 7, 10],
+            Scale::Chromatic => &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+        }
+    }
+}
+
+/// A tonic and a scale, for turning scale degrees into notes.
+///
+/// Degrees count from 0 (the tonic) and continue past the end of the scale
+/// into the next octave, so in a major key degree 7 is the tonic an octave
+/// up and degree -1 is the leading tone below. The default key is C major.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::music::core::{Key, Pitch, Scale};
+///
+/// let key = Key::new(Pitch::A, Scale::Minor);
+/// assert_eq!(key.degree_to_midi(0, 4), 69); // A4
+/// assert_eq!(key.degree_to_midi(2, 4), 72); // C5
+/// assert_eq!(key.degree_to_midi(7, 4), 81); // A5
+/// assert_eq!(key.degree_to_midi(-1, 4), 67); // G4
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    /// The first degree of the scale
+    pub tonic: Pitch,
+    /// The scale built on the tonic
+    pub scale: Scale,
+}
+
+impl Default for Key {
+    fn default() -> Self {
+        Self::new(Pitch::C, Scale::Major)
+    }
+}
+
+impl Key {
+    /// Creates a key.
+    pub fn new(tonic: Pitch, scale: Scale) -> Self {
+        Self { tonic, scale }
+    }
+
+    /// Returns the MIDI note of scale degree `degree`, with degree 0 in
+    /// `octave` (middle C is octave 4).
+    ///
+    /// Results outside the MIDI range are clamped to 0..=127.
+    pub fn degree_to_midi(&self, degree: i32, octave: i8) -> u8 {
+        let intervals = self.scale.intervals();
+        let len = intervals.len() as i32;
+        let octaves = degree.div_euclid(len);
+        let interval = intervals[degree.rem_euclid(len) as usize] as i32;
+        let tonic = (octave as i32 + 1) * 12 + self.tonic.semitone_offset() as i32;
+        (tonic + octaves * 12 + interval).clamp(0, 127) as u8
+    }
+
+    /// Returns the note of scale degree `degree` (see
+    /// [`degree_to_midi`](Self::degree_to_midi)).
+    pub fn note(&self, degree: i32, octave: i8) -> Note {
+        Note::from_midi(self.degree_to_midi(degree, octave))
+    }
+}
+
+/// A note event given as a scale degree instead of a pitch.
+///
+/// The degree is turned into a note only when the event plays, using the key
+/// set on the [`Sequencer`](crate::music::Sequencer) or
+/// [`SongPlayer`](crate::music::SongPlayer), so changing the key or mode
+/// retunes every pattern written this way.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::music::core::{DegreeEvent, Key, Pitch, Scale};
+///
+/// // The third of the scale, in the octave of middle C
+/// let third = DegreeEvent::new(2, 4, 0.8, Some(0.25));
+/// let in_c = third.resolve(&Key::new(Pitch::C, Scale::Major));
+/// let in_c_minor = third.resolve(&Key::new(Pitch::C, Scale::Minor));
+/// assert_eq!(in_c.note.to_midi(), 64); // E
+/// assert_eq!(in_c_minor.note.to_midi(), 63); // E flat
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DegreeEvent {
+    /// Scale degree, 0 being the tonic (see [`Key`])
+    pub degree: i32,
+
+    /// Octave of degree 0
+    pub octave: i8,
+
+    /// The velocity/amplitude (typically 0.0 to 1.0)
+    pub velocity: f64,
+
+    /// Optional duration in seconds
+    pub duration: Option<f64>,
+}
+
+impl DegreeEvent {
+    /// Creates a new `DegreeEvent`.
+    pub fn new(degree: i32, octave: i8, velocity: f64, duration: Option<f64>) -> Self {
+        Self {
+            degree,
+            octave,
+            velocity,
+            duration,
+        }
+    }
+
+    /// Returns the note event this degree plays in `key`.
+    pub fn resolve(&self, key: &Key) -> NoteEvent {
+        NoteEvent::new(
+            key.note(self.degree, self.octave),
+            self.velocity,
+            self.duration,
+        )
+    }
+
+    /// Returns the note event this degree plays over `chord`, reading the
+    /// degree as a chord tone (see [`Chord::tone_to_midi`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::core::{Chord, ChordQuality, DegreeEvent, Pitch};
+    ///
+    /// let third = DegreeEvent::new(1, 3, 0.8, None);
+    /// let over_f = third.resolve_on_chord(&Chord::new(Pitch::F, ChordQuality::Major));
+    /// assert_eq!(over_f.note.to_midi(), 57); // A3
+    /// ```
+    pub fn resolve_on_chord(&self, chord: &Chord) -> NoteEvent {
+        NoteEvent::new(
+            Note::from_midi(chord.tone_to_midi(self.degree, self.octave)),
+            self.velocity,
+            self.duration,
+        )
+    }
+}
+
+/// The quality of a chord: which intervals are stacked above the root.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::music::core::ChordQuality;
+///
+/// assert_eq!(ChordQuality::Major.intervals(), &[0, 4, 7]);
+/// assert_eq!(ChordQuality::Dominant7.intervals(), &[0, 4, 7, 10]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChordQuality {
+    /// Major triad (1 3 5)
+    Major,
+    /// Minor triad (1 b3 5)
+    Minor,
+    /// Diminished triad (1 b3 b5)
+    Diminished,
+    /// Augmented triad (1 3 #5)
+    Augmented,
+    /// Suspended second (1 2 5)
+    Sus2,
+    /// Suspended fourth (1 4 5)
+    Sus4,
+    /// Major seventh (1 3 5 7)
+    Major7,
+    /// Minor seventh (1 b3 5 b7)
+    Minor7,
+    /// Dominant seventh (1 3 5 b7)
+    Dominant7,
+    /// Half-diminished seventh (1 b3 b5 b7)
+    HalfDiminished7,
+    /// Diminished seventh (1 b3 b5 bb7)
+    Diminished7,
+    /// Minor-major seventh (1 b3 5 7)
+    MinorMajor7,
+}
+
+impl ChordQuality {
+    /// Every chord quality, triads first.
+    pub const ALL: [ChordQuality; 12] = [
+        ChordQuality::Major,
+        ChordQuality::Minor,
+        ChordQuality::Diminished,
+        ChordQuality::Augmented,
+        ChordQuality::Sus2,
+        ChordQuality::Sus4,
+        ChordQuality::Major7,
+        ChordQuality::Minor7,
+        ChordQuality::Dominant7,
+        ChordQuality::HalfDiminished7,
+        ChordQuality::Diminished7,
+        ChordQuality::MinorMajor7,
+    ];
+
+    /// Returns the chord's intervals in semitones above the root, starting with 0.
+    pub fn intervals(&self) -> &'static [u8] {
+        match self {
+            ChordQuality::Major => &[0, 4, 7],
+            ChordQuality::Minor => &[0, 3, 7],
+            ChordQuality::Diminished => &[0, 3, 6],
+            ChordQuality::Augmented => &[0, 4, 8],
+            ChordQuality::Sus2 => &[0, 2, 7],
+            ChordQuality::Sus4 => &[0, 5, 7],
+            ChordQuality::Major7 => &[0, 4, 7, 11],
+            ChordQuality::Minor7 => &[0, 3, 7, 10],
+            ChordQuality::Dominant7 => &[0, 4, 7, 10],
+            ChordQuality::HalfDiminished7 => &[0, 3, 6, 10],
+            ChordQuality::Diminished7 => &[0, 3, 6, 9],
+            ChordQuality::MinorMajor7 => &[0, 3, 7, 11],
+        }
+    }
+}
+
+/// A chord built from a root pitch and a stack of intervals.
+///
+/// A chord has no octave of its own; it is voiced at a given octave when
+/// converted to notes or events, with the root in that octave. Inversions
+/// move the lowest notes up an octave.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::music::core::{Chord, ChordQuality, Pitch};
+///
+/// let c_major = Chord::new(Pitch::C, ChordQuality::Major);
+/// assert_eq!(c_major.midi_notes(4), vec![60, 64, 67]);
+///
+/// // First inversion: E G C
+/// let first = c_major.clone().with_inversion(1);
+/// assert_eq!(first.midi_notes(4), vec![64, 67, 72]);
+///
+/// // A custom stack: C add9
+/// let add9 = Chord::custom(Pitch::C, &[0, 4, 7, 14]);
+/// assert_eq!(add9.midi_notes(4), vec![60, 64, 67, 74]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chord {
+    root: Pitch,
+    intervals: Vec<u8>,
+    inversion: usize,
+}
+
+impl Chord {
+    /// Creates a chord from a root pitch and quality, in root position.
+    pub fn new(root: Pitch, quality: ChordQuality) -> Self {
+        Self::custom(root, quality.intervals())
+    }
+
+    /// Creates a chord from a root pitch and arbitrary intervals.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The chord root
+    /// * `intervals` - Semitones above the root for each chord tone; the root
+    ///   itself (0) is added if missing, and the stack is sorted and deduplicated
+    pub fn custom(root: Pitch, intervals: &[u8]) -> Self {
+        let mut intervals = intervals.to_vec();
+        intervals.push(0);
+        intervals.sort_unstable();
+        intervals.dedup();
+        Self {
+            root,
+            intervals,
+            inversion: 0,
+        }
+    }
+
+    /// Sets the inversion: the number of lowest chord tones moved up an octave.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `inversion` is not less than the number of chord tones.
+    pub fn with_inversion(mut self, inversion: usize) -> Self {
+        assert!(
+            inversion < self.intervals.len(),
+            "inversion {} out of range for a {}-note chord",
+            inversion,
+            self.intervals.len()
+        );
+        self.inversion = inversion;
+        self
+    }
+
+    /// Names the chord formed by a set of MIDI notes.
+    ///
+    /// Octaves and doublings are ignored: the notes' pitch classes must match
+    /// one of the [`ChordQuality`] chords exactly. The lowest note sets the
+    /// inversion. When the same pitch classes spell more than one chord (as
+    /// with augmented triads, or Csus2 and Gsus4), the one rooted on the
+    /// lowest note is preferred.
+    ///
+    /// Returns `None` for fewer than three distinct pitch classes or an
+    /// unrecognized set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::core::{Chord, ChordQuality, Pitch};
+    ///
+    /// // E G C: C major in first inversion
+    /// let chord = Chord::detect(&[64, 67, 72]).unwrap();
+    /// assert_eq!(chord, Chord::new(Pitch::C, ChordQuality::Major).with_inversion(1));
+    ///
+    /// assert_eq!(Chord::detect(&[60, 61, 62]), None);
+    /// ```
+    pub fn detect(midi_notes: &[u8]) -> Option<Chord> {
+        let bass = *midi_notes.iter().min()? % 12;
+        let mut classes: Vec<u8> = midi_notes.iter().map(|n| n % 12).collect();
+        classes.sort_unstable();
+        classes.dedup();
+        if classes.len() < 3 {
+            return None;
+        }
+
+        // Try the bass as root first, then the other pitch classes
+        let roots = std::iter::once(bass).chain(classes.iter().copied().filter(|&c| c != bass));
+        for root in roots {
+            let mut intervals: Vec<u8> = classes.iter().map(|c| (c + 12 - root) % 12).collect();
+            intervals.sort_unstable();
+            if let Some(quality) = ChordQuality::ALL
+                .into_iter()
+                .find(|quality| quality.intervals() == intervals.as_slice())
+            {
+                let bass_interval = (bass + 12 - root) % 12;
+                let inversion = quality
+                    .intervals()
+                    .iter()
+                    .position(|&i| i == bass_interval)
+                    .unwrap_or(0);
+                return Some(
+                    Chord::new(Pitch::from_semitone(root), quality).with_inversion(inversion),
+                );
+            }
+        }
+        None
+    }
+
+    /// Returns the root pitch.
+    pub fn root(&self) -> Pitch {
+        self.root
+    }
+
+    /// Returns the intervals above the root in root position.
+    pub fn intervals(&self) -> &[u8] {
+        &self.intervals
+    }
+
+    /// Returns the inversion.
+    pub fn inversion(&self) -> usize {
+        self.inversion
+    }
+
+    /// Returns the number of chord tones.
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    /// Returns `true` if the chord has no tones (never, since the root is always present).
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Returns the chord's MIDI note numbers, lowest first, with the root
+    /// position voiced from `octave`.
+    ///
+    /// Notes above MIDI 127 are clamped to 127.
+    pub fn midi_notes(&self, octave: i8) -> Vec<u8> {
+        let root = self.root.to_midi_note(octave) as u16;
+        let (lower, upper) = self.intervals.split_at(self.inversion);
+        upper
+            .iter()
+            .map(|&i| i as u16)
+            .chain(lower.iter().map(|&i| i as u16 + 12))
+            .map(|i| (root + i).min(127) as u8)
+            .collect()
+    }
+
+    /// Returns the MIDI note of chord tone `tone`, counting up from the root
+    /// in `octave` (0 is the root, 1 the next tone up, and so on).
+    ///
+    /// Tones past the top of the chord continue in the next octave, and
+    /// negative tones count down below the root. The inversion is ignored.
+    /// Results outside the MIDI range are clamped to 0..=127.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::core::{Chord, ChordQuality, Pitch};
+    ///
+    /// let c = Chord::new(Pitch::C, ChordQuality::Major);
+    /// assert_eq!(c.tone_to_midi(0, 4), 60);
+    /// assert_eq!(c.tone_to_midi(2, 4), 67);
+    /// assert_eq!(c.tone_to_midi(3, 4), 72);
+    /// assert_eq!(c.tone_to_midi(-1, 4), 55);
+    /// ```
+    pub fn tone_to_midi(&self, tone: i32, octave: i8) -> u8 {
+        let len = self.intervals.len() as i32;
+        let octaves = tone.div_euclid(len);
+        let interval = self.intervals[tone.rem_euclid(len) as usize] as i32;
+        let root = (octave as i32 + 1) * 12 + self.root.semitone_offset() as i32;
+        (root + octaves * 12 + interval).clamp(0, 127) as u8
+    }
+
+    /// Returns the chord's notes, lowest first.
+    pub fn notes(&self, octave: i8) -> Vec<Note> {
+        self.midi_notes(octave)
+            .into_iter()
+            .map(Note::from_midi)
+            .collect()
+    }
+
+    /// Returns one event per chord tone, all with the same velocity and duration.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::core::{Chord, ChordQuality, Pitch};
+    ///
+    /// let events = Chord::new(Pitch::A, ChordQuality::Minor7).to_events(3, 0.7, Some(1.0));
+    /// assert_eq!(events.len(), 4);
+    /// assert!(events.iter().all(|e| e.velocity == 0.7));
+    /// ```
+    pub fn to_events(&self, octave: i8, velocity: f64, duration: Option<f64>) -> Vec<NoteEvent> {
+        self.notes(octave)
+            .into_iter()
+            .map(|note| NoteEvent::new(note, velocity, duration))
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_key_degrees() {
+        let key = Key::new(Pitch::D, Scale::Dorian);
+        let notes: Vec<u8> = (0..8).map(|degree| key.degree_to_midi(degree, 4)).collect();
+        assert_eq!(notes, vec![62, 64, 65, 67, 69, 71, 72, 74]);
+
+        // Pentatonic degrees wrap every five
+        let key = Key::new(Pitch::C, Scale::MinorPentatonic);
+        assert_eq!(key.degree_to_midi(5, 3), 60);
+        assert_eq!(key.degree_to_midi(-5, 3), 36);
+        assert_eq!(key.degree_to_midi(-1, 3), 46);
+
+        // Clamped to the MIDI range
+        assert_eq!(key.degree_to_midi(100, 9), 127);
+        assert_eq!(key.degree_to_midi(-100, -1), 0);
+        assert_eq!(Key::default(), Key::new(Pitch::C, Scale::Major));
+    }
+
+    #[test]
+    fn test_chord_detect_round_trips() {
+        for root in 0..12 {
+            for quality in ChordQuality::ALL {
+                let chord = Chord::new(Pitch::from_semitone(root), quality);
+                for inversion in 0..chord.len() {
+                    let voiced = chord.clone().with_inversion(inversion);
+                    let notes = voiced.midi_notes(3);
+                    let detected = Chord::detect(&notes).unwrap();
+                    // Symmetric and suspended chords can be spelled from more
+                    // than one root, but must give the same notes and bass
+                    let ambiguous = matches!(
+                        quality,
+                        ChordQuality::Augmented
+                            | ChordQuality::Diminished7
+                            | ChordQuality::Sus2
+                            | ChordQuality::Sus4
+                    );
+                    if !ambiguous {
+                        assert_eq!(detected, voiced);
+                    }
+                    let classes = |notes: Vec<u8>| {
+                        let mut classes: Vec<u8> = notes.iter().map(|n| n % 12).collect();
+                        classes.sort_unstable();
+                        (notes[0] % 12, classes)
+                    };
+                    assert_eq!(classes(detected.midi_notes(3)), classes(notes));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_chord_detect_ignores_octaves_and_doublings() {
+        // C2 G3 C4 E4 G4: open-voiced C major
+        let chord = Chord::detect(&[36, 55, 60, 64, 67]).unwrap();
+        assert_eq!(chord, Chord::new(Pitch::C, ChordQuality::Major));
+        // C D G is Csus2 over C, Gsus4 over G
+        assert_eq!(Chord::detect(&[60, 62, 67]).unwrap().root(), Pitch::C);
+        assert_eq!(Chord::detect(&[55, 60, 62]).unwrap().root(), Pitch::G);
+        assert_eq!(Chord::detect(&[60, 64]), None);
+        assert_eq!(Chord::detect(&[]), None);
+    }
+
     #[test]
     fn test_new_note() {
         let note = Note::new(440.0);
@@ -656,4 +1246,63 @@ mod tests {
     }
 
     // Note: Tests for the note! macro are in tests/note_macro.rs
+
+    #[test]
+    fn test_chord_qualities() {
+        let root = |q| Chord::new(Pitch::C, q).midi_notes(4);
+        assert_eq!(root(ChordQuality::Minor), vec![60, 63, 67]);
+        assert_eq!(root(ChordQuality::Diminished), vec![60, 63, 66]);
+        assert_eq!(root(ChordQuality::Augmented), vec![60, 64, 68]);
+        assert_eq!(root(ChordQuality::Sus4), vec![60, 65, 67]);
+        assert_eq!(root(ChordQuality::Major7), vec![60, 64, 67, 71]);
+        assert_eq!(root(ChordQuality::Diminished7), vec![60, 63, 66, 69]);
+    }
+
+    #[test]
+    fn test_chord_inversions() {
+        let g7 = Chord::new(Pitch::G, ChordQuality::Dominant7);
+        assert_eq!(g7.midi_notes(3), vec![55, 59, 62, 65]);
+        assert_eq!(
+            g7.clone().with_inversion(2).midi_notes(3),
+            vec![62, 65, 67, 71]
+        );
+        assert_eq!(
+            g7.clone().with_inversion(3).midi_notes(3),
+            vec![65, 67, 71, 74]
+        );
+        assert_eq!(g7.with_inversion(3).inversion(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "inversion 3 out of range for a 3-note chord")]
+    fn test_chord_inversion_out_of_range() {
+        Chord::new(Pitch::C, ChordQuality::Major).with_inversion(3);
+    }
+
+    #[test]
+    fn test_custom_chord_normalizes_intervals() {
+        let chord = Chord::custom(Pitch::D, &[7, 4, 7]);
+        assert_eq!(chord.intervals(), &[0, 4, 7]);
+        assert_eq!(chord.len(), 3);
+        assert_eq!(chord, Chord::new(Pitch::D, ChordQuality::Major));
+    }
+
+    #[test]
+    fn test_chord_to_events() {
+        let events = Chord::new(Pitch::A, ChordQuality::Minor).to_events(4, 0.5, Some(0.25));
+        let midi: Vec<u8> = events.iter().map(|e| e.note.to_midi()).collect();
+        assert_eq!(midi, vec![69, 72, 76]);
+        assert!((events[0].note.pitch - 440.0).abs() < 1e-9);
+        assert!(
+            events
+                .iter()
+                .all(|e| e.velocity == 0.5 && e.duration == Some(0.25))
+        );
+    }
+
+    #[test]
+    fn test_chord_clamps_to_midi_range() {
+        let chord = Chord::new(Pitch::B, ChordQuality::Major7);
+        assert_eq!(chord.midi_notes(9), vec![127, 127, 127, 127]);
+    }
 }
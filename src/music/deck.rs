@@ -0,0 +1,536 @@
+//! DJ-style decks and a crossfader for mixing two sequenced parts live.
+//!
+//! A [`Deck`] pairs something that plays notes in time (a
+//! [`Sequencer`](super::Sequencer) or [`SongPlayer`](super::SongPlayer)) with
+//! the instrument it plays, and is itself a signal. A [`DjMixer`] blends two
+//! decks with an equal-power crossfader and can sync one deck to the other:
+//! the follower takes the leader's tempo, ramps included, and is nudged onto
+//! the leader's bar phase so their downbeats line up.
+
+use super::{
+    command::NoteTarget, core::NoteEvent, metronome::Metronome, scheduler::NoteScheduler,
+    sequencer::Sequencer, song::SongPlayer,
+};
+use crate::core::{AudioSignal, Param, Signal};
+use std::f64::consts::FRAC_PI_2;
+
+/// Something a [`Deck`] plays: a player stepped by a [`Metronome`] that
+/// returns note events as it goes.
+///
+/// Implemented for [`Sequencer`] and [`SongPlayer`].
+pub trait DeckPlayer {
+    /// Advances one sample, returning the events of a step if one starts.
+    fn tick(&mut self) -> Option<Vec<NoteEvent>>;
+
+    /// Returns true if the player is playing.
+    fn is_playing(&self) -> bool;
+
+    /// Returns the metronome that times the steps.
+    fn metronome(&self) -> &Metronome;
+
+    /// Returns the metronome for retiming.
+    fn metronome_mut(&mut self) -> &mut Metronome;
+}
+
+impl DeckPlayer for Sequencer {
+    fn tick(&mut self) -> Option<Vec<NoteEvent>> {
+        Sequencer::tick(self)
+    }
+
+    fn is_playing(&self) -> bool {
+        Sequencer::is_playing(self)
+    }
+
+    fn metronome(&self) -> &Metronome {
+        Sequencer::metronome(self)
+    }
+
+    fn metronome_mut(&mut self) -> &mut Metronome {
+        Sequencer::metronome_mut(self)
+    }
+}
+
+impl DeckPlayer for SongPlayer {
+    fn tick(&mut self) -> Option<Vec<NoteEvent>> {
+        SongPlayer::tick(self)
+    }
+
+    fn is_playing(&self) -> bool {
+        SongPlayer::is_playing(self)
+    }
+
+    fn metronome(&self) -> &Metronome {
+        SongPlayer::metronome(self)
+    }
+
+    fn metronome_mut(&mut self) -> &mut Metronome {
+        SongPlayer::metronome_mut(self)
+    }
+}
+
+/// A player and the instrument it plays, rendered as one signal.
+///
+/// Every sample the player is ticked and the events it returns are played on
+/// the instrument, each released after its duration (or after one step if it
+/// has none), then the instrument renders the sample.
+///
+/// # Type Parameters
+///
+/// * `SAMPLE_RATE` - Sample rate in Hz
+/// * `P` - Player (see [`DeckPlayer`])
+/// * `T` - Instrument, e.g. a [`VoiceAllocator`](super::VoiceAllocator)
+pub struct Deck<const SAMPLE_RATE: u32, P, T>
+where
+    P: DeckPlayer,
+    T: AudioSignal<SAMPLE_RATE> + NoteTarget,
+{
+    player: P,
+    instrument: T,
+    scheduler: NoteScheduler,
+}
+
+impl<const SAMPLE_RATE: u32, P, T> Deck<SAMPLE_RATE, P, T>
+where
+    P: DeckPlayer,
+    T: AudioSignal<SAMPLE_RATE> + NoteTarget,
+{
+    /// Creates a deck playing `instrument` from `player`.
+    pub fn new(player: P, instrument: T) -> Self {
+        Self {
+            player,
+            instrument,
+            scheduler: NoteScheduler::new(SAMPLE_RATE),
+        }
+    }
+
+    /// Returns the player.
+    pub fn player(&self) -> &P {
+        &self.player
+    }
+
+    /// Returns the player, e.g. to start or stop it.
+    pub fn player_mut(&mut self) -> &mut P {
+        &mut self.player
+    }
+
+    /// Returns the instrument.
+    pub fn instrument(&self) -> &T {
+        &self.instrument
+    }
+
+    /// Returns the instrument for adjustment.
+    pub fn instrument_mut(&mut self) -> &mut T {
+        &mut self.instrument
+    }
+
+    /// Releases every note on the instrument, e.g. after stopping the player.
+    pub fn all_notes_off(&mut self) {
+        self.scheduler.all_notes_off(&mut self.instrument);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, P, T> Signal for Deck<SAMPLE_RATE, P, T>
+where
+    P: DeckPlayer,
+    T: AudioSignal<SAMPLE_RATE> + NoteTarget,
+{
+    fn next_sample(&mut self) -> f64 {
+        self.scheduler.tick(&mut self.instrument);
+        if let Some(events) = self.player.tick() {
+            let metronome = self.player.metronome();
+            let step_seconds = 60.0 / (metronome.tempo() * metronome.steps_per_beat() as f64);
+            for event in &events {
+                let event = NoteEvent {
+                    duration: Some(event.duration.unwrap_or(step_seconds)),
+                    ..*event
+                };
+                self.scheduler.play(&mut self.instrument, &event);
+            }
+        }
+        self.instrument.next_sample()
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.instrument.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, P, T> AudioSignal<SAMPLE_RATE> for Deck<SAMPLE_RATE, P, T>
+where
+    P: DeckPlayer,
+    T: AudioSignal<SAMPLE_RATE> + NoteTarget,
+{
+}
+
+/// One of the two decks of a [`DjMixer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeckSide {
+    /// The deck heard with the crossfader at 0.0
+    A,
+    /// The deck heard with the crossfader at 1.0
+    B,
+}
+
+/// Two decks blended by a crossfader, with tempo and phase sync.
+///
+/// The crossfader runs from 0.0 (deck A only) to 1.0 (deck B only) with an
+/// equal-power curve, so the loudness holds steady through the fade; in the
+/// middle both decks play at -3 dB.
+///
+/// [`sync`](Self::sync) makes one deck the leader. From then on the other
+/// deck plays at the leader's tempo, following its tempo changes and ramps.
+/// When sync is engaged, and again whenever the follower starts playing, the
+/// follower is moved to the nearest position with the same phase as the
+/// leader within the quantum (a bar of 4 beats by default), so their beats
+/// and downbeats fall on the same samples. While synced, tempo changes made
+/// on the follower itself are overridden.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{ADSR, NoteEvent, Signal, SineOscillator};
+/// use earworm::music::{Deck, DeckSide, DjMixer, Pattern, Sequencer, VoiceAllocator};
+///
+/// const SAMPLE_RATE: u32 = 44100;
+///
+/// let synth = || {
+///     VoiceAllocator::<SAMPLE_RATE, 4, _, _>::new(|| {
+///         let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+///         let env = ADSR::new(0.005, 0.1, 0.6, 0.1, SAMPLE_RATE as f64);
+///         (osc, env)
+///     })
+/// };
+/// let sequencer = |bpm, note| {
+///     let mut pattern = Pattern::new(4);
+///     pattern.add_event(0, NoteEvent::from_midi(note, 100, None));
+///     let mut sequencer = Sequencer::new(bpm, 1, SAMPLE_RATE);
+///     sequencer.set_pattern(pattern);
+///     sequencer.play();
+///     sequencer
+/// };
+///
+/// let deck_a = Deck::new(sequencer(124.0, 48), synth());
+/// let deck_b = Deck::new(sequencer(118.0, 55), synth());
+/// let mut mixer = DjMixer::new(deck_a, deck_b).with_crossfader(0.0);
+///
+/// // Match deck B to deck A, then fade it in
+/// mixer.sync(DeckSide::A);
+/// mixer.set_crossfader(0.5);
+/// let sample = mixer.next_sample();
+/// assert_eq!(mixer.deck_b().player().tempo(), 124.0);
+/// ```
+pub struct DjMixer<const SAMPLE_RATE: u32, PA, TA, PB, TB>
+where
+    PA: DeckPlayer,
+    TA: AudioSignal<SAMPLE_RATE> + NoteTarget,
+    PB: DeckPlayer,
+    TB: AudioSignal<SAMPLE_RATE> + NoteTarget,
+{
+    a: Deck<SAMPLE_RATE, PA, TA>,
+    b: Deck<SAMPLE_RATE, PB, TB>,
+    crossfader: Param,
+    leader: Option<DeckSide>,
+    quantum: f64,               // beats the follower's phase is matched within
+    follower_was_playing: bool, // to align the follower when it starts
+}
+
+impl<const SAMPLE_RATE: u32, PA, TA, PB, TB> DjMixer<SAMPLE_RATE, PA, TA, PB, TB>
+where
+    PA: DeckPlayer,
+    TA: AudioSignal<SAMPLE_RATE> + NoteTarget,
+    PB: DeckPlayer,
+    TB: AudioSignal<SAMPLE_RATE> + NoteTarget,
+{
+    /// Creates a mixer with the crossfader centered and sync off.
+    pub fn new(a: Deck<SAMPLE_RATE, PA, TA>, b: Deck<SAMPLE_RATE, PB, TB>) -> Self {
+        Self {
+            a,
+            b,
+            crossfader: Param::fixed(0.5),
+            leader: None,
+            quantum: 4.0,
+            follower_was_playing: false,
+        }
+    }
+
+    /// Sets the crossfader position, fixed or modulated (builder style).
+    pub fn with_crossfader(mut self, position: impl Into<Param>) -> Self {
+        self.crossfader = position.into();
+        self
+    }
+
+    /// Sets the number of beats the follower's phase is matched within
+    /// (builder style). 4 lines up bars of 4/4; 1 lines up beats only.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `beats` is <= 0.
+    pub fn with_quantum(mut self, beats: f64) -> Self {
+        assert!(beats > 0.0, "quantum must be greater than 0");
+        self.quantum = beats;
+        self
+    }
+
+    /// Sets the crossfader position, from 0.0 (deck A) to 1.0 (deck B).
+    pub fn set_crossfader(&mut self, position: impl Into<Param>) {
+        self.crossfader = position.into();
+    }
+
+    /// Returns deck A.
+    pub fn deck_a(&self) -> &Deck<SAMPLE_RATE, PA, TA> {
+        &self.a
+    }
+
+    /// Returns deck A for adjustment.
+    pub fn deck_a_mut(&mut self) -> &mut Deck<SAMPLE_RATE, PA, TA> {
+        &mut self.a
+    }
+
+    /// Returns deck B.
+    pub fn deck_b(&self) -> &Deck<SAMPLE_RATE, PB, TB> {
+        &self.b
+    }
+
+    /// Returns deck B for adjustment.
+    pub fn deck_b_mut(&mut self) -> &mut Deck<SAMPLE_RATE, PB, TB> {
+        &mut self.b
+    }
+
+    /// Syncs the other deck to `leader`, matching its tempo and phase at
+    /// once.
+    pub fn sync(&mut self, leader: DeckSide) {
+        self.leader = Some(leader);
+        self.align();
+        self.follower_was_playing = self.follower_playing();
+    }
+
+    /// Stops syncing; both decks keep their current tempo.
+    pub fn unsync(&mut self) {
+        self.leader = None;
+    }
+
+    /// Returns the deck the other is synced to, if sync is on.
+    pub fn leader(&self) -> Option<DeckSide> {
+        self.leader
+    }
+
+    /// Returns the leader's and the follower's metronomes.
+    fn metronomes(&mut self) -> Option<(&Metronome, &mut Metronome)> {
+        match self.leader? {
+            DeckSide::A => Some((self.a.player.metronome(), self.b.player.metronome_mut())),
+            DeckSide::B => Some((self.b.player.metronome(), self.a.player.metronome_mut())),
+        }
+    }
+
+    fn follower_playing(&self) -> bool {
+        match self.leader {
+            Some(DeckSide::A) => self.b.player.is_playing(),
+            Some(DeckSide::B) => self.a.player.is_playing(),
+            None => false,
+        }
+    }
+
+    /// Moves the follower to the leader's tempo and the nearest position in
+    /// phase with it.
+    fn align(&mut self) {
+        let quantum = self.quantum;
+        let Some((leader, follower)) = self.metronomes() else {
+            return;
+        };
+        let lead = leader.beat_position();
+        let offset = ((follower.beat_position() - lead) / quantum).round() * quantum;
+        let mut beat = lead + offset;
+        if beat < 0.0 {
+            beat += quantum;
+        }
+        follower.sync_to_beat(leader.tempo(), beat);
+    }
+
+    /// Gives the follower the leader's tempo.
+    fn follow_tempo(&mut self) {
+        if let Some((leader, follower)) = self.metronomes()
+            && follower.tempo() != leader.tempo()
+        {
+            follower.set_tempo(leader.tempo());
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32, PA, TA, PB, TB> Signal for DjMixer<SAMPLE_RATE, PA, TA, PB, TB>
+where
+    PA: DeckPlayer,
+    TA: AudioSignal<SAMPLE_RATE> + NoteTarget,
+    PB: DeckPlayer,
+    TB: AudioSignal<SAMPLE_RATE> + NoteTarget,
+{
+    fn next_sample(&mut self) -> f64 {
+        if self.leader.is_some() {
+            let playing = self.follower_playing();
+            if playing && !self.follower_was_playing {
+                self.align();
+            }
+            self.follower_was_playing = playing;
+        }
+
+        // The leader ticks first so the follower runs at its tempo for this
+        // sample, ramps included
+        let (a, b) = match self.leader {
+            Some(DeckSide::B) => {
+                let b = self.b.next_sample();
+                self.follow_tempo();
+                (self.a.next_sample(), b)
+            }
+            _ => {
+                let a = self.a.next_sample();
+                self.follow_tempo();
+                (a, self.b.next_sample())
+            }
+        };
+
+        let position = self.crossfader.value().clamp(0.0, 1.0) * FRAC_PI_2;
+        a * position.cos() + b * position.sin()
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.a.prepare(max_block_size, sample_rate);
+        self.b.prepare(max_block_size, sample_rate);
+        self.crossfader.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, PA, TA, PB, TB> AudioSignal<SAMPLE_RATE>
+    for DjMixer<SAMPLE_RATE, PA, TA, PB, TB>
+where
+    PA: DeckPlayer,
+    TA: AudioSignal<SAMPLE_RATE> + NoteTarget,
+    PB: DeckPlayer,
+    TB: AudioSignal<SAMPLE_RATE> + NoteTarget,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::music::Pattern;
+
+    // 60 BPM quarter-note steps at 8 Hz: 8 samples per step
+    const SAMPLE_RATE: u32 = 8;
+
+    /// Outputs 1.0 while a note is held and logs when notes start.
+    #[derive(Default)]
+    struct Gate {
+        sample: u64,
+        held: usize,
+        starts: Vec<u64>,
+    }
+
+    impl Signal for Gate {
+        fn next_sample(&mut self) -> f64 {
+            self.sample += 1;
+            if self.held > 0 { 1.0 } else { 0.0 }
+        }
+    }
+
+    impl AudioSignal<SAMPLE_RATE> for Gate {}
+
+    impl NoteTarget for Gate {
+        fn note_on(&mut self, _note: u8, _velocity: f64) {
+            self.starts.push(self.sample);
+            self.held += 1;
+        }
+        fn note_off(&mut self, _note: u8) {
+            self.held = self.held.saturating_sub(1);
+        }
+        fn all_notes_off(&mut self) {
+            self.held = 0;
+        }
+    }
+
+    /// A deck playing a note on the first of every 4 steps.
+    fn deck(bpm: f64) -> Deck<SAMPLE_RATE, Sequencer, Gate> {
+        let mut pattern = Pattern::new(4);
+        pattern.add_event(0, NoteEvent::from_midi(60, 100, None));
+        let mut sequencer = Sequencer::new(bpm, 1, SAMPLE_RATE);
+        sequencer.set_pattern(pattern);
+        sequencer.play();
+        Deck::new(sequencer, Gate::default())
+    }
+
+    #[test]
+    fn test_deck_plays_instrument() {
+        let mut deck = deck(60.0);
+        let samples: Vec<f64> = (0..48).map(|_| deck.next_sample()).collect();
+        assert_eq!(deck.instrument().starts, [7, 39]);
+        // Held for one step
+        assert_eq!(
+            samples[6..16],
+            [0.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn test_crossfader_is_equal_power() {
+        let held = |mixer: &mut DjMixer<SAMPLE_RATE, Sequencer, Gate, Sequencer, Gate>| {
+            mixer.deck_a_mut().instrument_mut().held = 1;
+            mixer.deck_b_mut().instrument_mut().held = 1;
+            mixer.next_sample()
+        };
+        let mut mixer = DjMixer::new(deck(60.0), deck(60.0)).with_crossfader(0.0);
+        assert_eq!(held(&mut mixer), 1.0);
+        mixer.set_crossfader(1.0);
+        assert!((held(&mut mixer) - 1.0).abs() < 1e-12);
+
+        // Each deck at -3 dB in the middle
+        mixer.deck_b_mut().player_mut().stop();
+        mixer.deck_b_mut().all_notes_off();
+        mixer.set_crossfader(0.5);
+        mixer.deck_a_mut().instrument_mut().held = 1;
+        let level = mixer.next_sample();
+        assert!((level - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_sync_matches_tempo_and_phase() {
+        let mut mixer = DjMixer::new(deck(60.0), deck(45.0));
+        for _ in 0..20 {
+            mixer.next_sample();
+        }
+        mixer.sync(DeckSide::A);
+        assert_eq!(mixer.leader(), Some(DeckSide::A));
+        assert_eq!(mixer.deck_b().player().tempo(), 60.0);
+        for _ in 20..100 {
+            mixer.next_sample();
+        }
+        // Deck B had started a bar at sample 10 (45 BPM: 32/3 samples per
+        // step); after sync both bars start together
+        assert_eq!(mixer.deck_a().instrument().starts, [7, 39, 71]);
+        assert_eq!(mixer.deck_b().instrument().starts, [10, 39, 71]);
+
+        // The follower follows tempo changes on the leader
+        mixer.deck_a_mut().player_mut().set_tempo(120.0);
+        mixer.next_sample();
+        assert_eq!(mixer.deck_b().player().tempo(), 120.0);
+
+        mixer.unsync();
+        mixer.deck_a_mut().player_mut().set_tempo(90.0);
+        mixer.next_sample();
+        assert_eq!(mixer.deck_b().player().tempo(), 120.0);
+    }
+
+    #[test]
+    fn test_follower_aligns_when_started() {
+        let mut mixer = DjMixer::new(deck(60.0), deck(60.0));
+        mixer.deck_b_mut().player_mut().stop();
+        mixer.sync(DeckSide::A);
+        // Start deck B a beat and a half into deck A's bar
+        for _ in 0..12 {
+            mixer.next_sample();
+        }
+        mixer.deck_b_mut().player_mut().play();
+        for _ in 12..80 {
+            mixer.next_sample();
+        }
+        assert_eq!(mixer.deck_a().instrument().starts, [7, 39, 71]);
+        assert_eq!(mixer.deck_b().instrument().starts, [39, 71]);
+    }
+}
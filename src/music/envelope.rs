@@ -7,8 +7,12 @@
 pub enum EnvelopeState {
     /// Envelope is not active
     Idle,
+    /// Delay phase - waiting at zero before the attack starts
+    Delay,
     /// Attack phase - ramping up to peak
     Attack,
+    /// Hold phase - holding at peak before the decay starts
+    Hold,
     /// Decay phase - ramping down from peak to sustain
     Decay,
     /// Sustain phase - holding at sustain level
@@ -115,4 +119,11 @@ pub trait Envelope {
     fn is_releasing(&self) -> bool {
         matches!(self.state(), EnvelopeState::Release)
     }
+
+    /// Sets the sustain level (0.0 to 1.0), for envelopes that have one.
+    ///
+    /// Takes effect immediately, including during decay and sustain, which
+    /// lets the sustain level be modulated. Envelopes without a sustain stage
+    /// ignore it (the default).
+    fn set_sustain_level(&mut self, _level: f64) {}
 }
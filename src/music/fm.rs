@@ -0,0 +1,507 @@
+//! FM synthesis with operators wired in DX-style algorithms.
+//!
+//! An [`FmOperator`] is a sine oscillator running at a ratio of the note's
+//! frequency, with its own level, self-feedback and optional envelope. An
+//! [`FmAlgorithm`] says which operators modulate which and which are heard,
+//! and an [`FmVoice`] runs a set of operators through an algorithm.
+//!
+//! Modulation is phase modulation, as on the classic Yamaha synths: a
+//! modulator's output, in radians, is added to the phase of the operators it
+//! feeds. A modulator's level is therefore its modulation index, while a
+//! carrier's level is its output amplitude.
+
+use super::envelope::Envelope;
+use crate::{AudioSignal, Param, Pitched, Signal};
+use std::f64::consts::TAU;
+
+/// One sine operator of an [`FmVoice`].
+///
+/// # Examples
+///
+/// ```
+/// use earworm::music::FmOperator;
+/// use earworm::ADSR;
+///
+/// // A modulator an octave up whose index falls from 3 to 0.5 over 300 ms
+/// let modulator = FmOperator::new(2.0)
+///     .with_level(3.0)
+///     .with_envelope(ADSR::new(0.0, 0.3, 0.17, 0.2, 44100.0));
+/// ```
+pub struct FmOperator {
+    ratio: f64,
+    level: Param,
+    feedback: f64,
+    envelope: Option<Box<dyn Envelope + Send>>,
+    phase: f64,
+    history: [f64; 2],
+}
+
+impl FmOperator {
+    /// Creates an operator at full level, without feedback or an envelope.
+    ///
+    /// # Arguments
+    ///
+    /// * `ratio` - Frequency as a multiple of the note's frequency
+    pub fn new(ratio: f64) -> Self {
+        Self {
+            ratio,
+            level: Param::Fixed(1.0),
+            feedback: 0.0,
+            envelope: None,
+            phase: 0.0,
+            history: [0.0; 2],
+        }
+    }
+
+    /// Sets the level (builder style).
+    ///
+    /// For a carrier this is the output amplitude; for a modulator it is the
+    /// modulation index, the peak phase deviation in radians it causes.
+    pub fn with_level(mut self, level: impl Into<Param>) -> Self {
+        self.level = level.into();
+        self
+    }
+
+    /// Sets how strongly the operator modulates itself (builder style).
+    ///
+    /// Feedback is a modulation index like a level; around 1.0 it bends the
+    /// sine toward a sawtooth, and higher values turn it into noise.
+    pub fn with_feedback(mut self, feedback: f64) -> Self {
+        self.feedback = feedback;
+        self
+    }
+
+    /// Shapes the operator's level with an envelope (builder style).
+    ///
+    /// The envelope is triggered and released with the notes of the
+    /// [`FmVoice`]. Without one, the operator plays at a constant level.
+    pub fn with_envelope(mut self, envelope: impl Envelope + Send + 'static) -> Self {
+        self.envelope = Some(Box::new(envelope));
+        self
+    }
+
+    /// Returns the frequency ratio.
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    /// Sets the frequency ratio.
+    pub fn set_ratio(&mut self, ratio: f64) {
+        self.ratio = ratio;
+    }
+
+    /// Generates the next output for a note at `frequency`, with `modulation`
+    /// radians of phase modulation from other operators.
+    fn next(&mut self, frequency: f64, modulation: f64, sample_rate: f64) -> f64 {
+        let envelope = self.envelope.as_mut().map_or(1.0, |env| env.next_sample());
+        let level = self.level.value();
+        // Averaging the last two outputs keeps high feedback from oscillating
+        let feedback = self.feedback * 0.5 * (self.history[0] + self.history[1]);
+        let output = (self.phase * TAU + modulation + feedback).sin() * level * envelope;
+
+        self.history = [output, self.history[0]];
+        self.phase = (self.phase + frequency * self.ratio / sample_rate).rem_euclid(1.0);
+        output
+    }
+
+    fn note_on(&mut self) {
+        self.phase = 0.0;
+        self.history = [0.0; 2];
+        if let Some(envelope) = &mut self.envelope {
+            envelope.trigger(1.0);
+        }
+    }
+
+    fn note_off(&mut self) {
+        if let Some(envelope) = &mut self.envelope {
+            envelope.release();
+        }
+    }
+}
+
+/// The routing between the operators of an [`FmVoice`].
+///
+/// Operators are numbered from 0, and each may only modulate operators with
+/// a lower number, so every algorithm is computed in one pass from the last
+/// operator down. Carriers are the operators that are heard.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::music::FmAlgorithm;
+///
+/// // 3 -> 2 -> 0 and 1 -> 0, heard through operator 0
+/// let algorithm = FmAlgorithm::new(4)
+///     .with_modulation(3, 2)
+///     .with_modulation(2, 0)
+///     .with_modulation(1, 0)
+///     .with_carrier(0);
+/// assert_eq!(algorithm.carriers(), &[0]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FmAlgorithm {
+    operators: usize,
+    carriers: Vec<usize>,
+    routes: Vec<(usize, usize)>,
+}
+
+impl FmAlgorithm {
+    /// Creates an algorithm for `operators` operators with no routing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `operators` is 0.
+    pub fn new(operators: usize) -> Self {
+        assert!(operators > 0, "an FM algorithm needs at least one operator");
+        Self {
+            operators,
+            carriers: Vec::new(),
+            routes: Vec::new(),
+        }
+    }
+
+    /// A single chain: each operator modulates the one below it, and
+    /// operator 0 is the only carrier.
+    pub fn stack(operators: usize) -> Self {
+        (1..operators)
+            .fold(Self::new(operators), |algorithm, op| {
+                algorithm.with_modulation(op, op - 1)
+            })
+            .with_carrier(0)
+    }
+
+    /// Every operator is a carrier with no modulation (additive synthesis).
+    pub fn parallel(operators: usize) -> Self {
+        (0..operators).fold(Self::new(operators), Self::with_carrier)
+    }
+
+    /// Every other operator modulates operator 0, the only carrier.
+    pub fn branch(operators: usize) -> Self {
+        (1..operators)
+            .fold(Self::new(operators), |algorithm, op| {
+                algorithm.with_modulation(op, 0)
+            })
+            .with_carrier(0)
+    }
+
+    /// Independent two-operator stacks: 1 -> 0, 3 -> 2, and so on, with the
+    /// even operators as carriers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `operators` is odd.
+    pub fn pairs(operators: usize) -> Self {
+        assert!(
+            operators.is_multiple_of(2),
+            "pairs need an even number of operators, got {}",
+            operators
+        );
+        (0..operators / 2).fold(Self::new(operators), |algorithm, pair| {
+            algorithm
+                .with_modulation(2 * pair + 1, 2 * pair)
+                .with_carrier(2 * pair)
+        })
+    }
+
+    /// Routes operator `from` into the phase of operator `to` (builder style).
+    ///
+    /// # Panics
+    ///
+    /// Panics if either operator is out of range or `from` is not higher
+    /// than `to`.
+    pub fn with_modulation(mut self, from: usize, to: usize) -> Self {
+        assert!(
+            from < self.operators,
+            "operator {} out of range for a {}-operator algorithm",
+            from,
+            self.operators
+        );
+        assert!(
+            to < from,
+            "operator {} can only modulate lower-numbered operators, not {}",
+            from,
+            to
+        );
+        if !self.routes.contains(&(from, to)) {
+            self.routes.push((from, to));
+        }
+        self
+    }
+
+    /// Makes operator `op` a carrier (builder style).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `op` is out of range.
+    pub fn with_carrier(mut self, op: usize) -> Self {
+        assert!(
+            op < self.operators,
+            "operator {} out of range for a {}-operator algorithm",
+            op,
+            self.operators
+        );
+        if !self.carriers.contains(&op) {
+            self.carriers.push(op);
+            self.carriers.sort_unstable();
+        }
+        self
+    }
+
+    /// Returns the number of operators.
+    pub fn operators(&self) -> usize {
+        self.operators
+    }
+
+    /// Returns the carriers, in ascending order.
+    pub fn carriers(&self) -> &[usize] {
+        &self.carriers
+    }
+
+    /// Returns the (modulator, target) routes.
+    pub fn routes(&self) -> &[(usize, usize)] {
+        &self.routes
+    }
+}
+
+/// A pitched FM voice: operators running through an [`FmAlgorithm`].
+///
+/// The carriers are mixed and divided by their count, so a patch with all
+/// carriers at level 1.0 stays within [-1.0, 1.0]. As a [`Pitched`] signal
+/// it restarts operator phases and triggers operator envelopes on every
+/// note-on, and releases them on note-off, so it works as the signal of a
+/// [`Voice`](super::Voice) or [`VoiceAllocator`](super::VoiceAllocator).
+///
+/// # Examples
+///
+/// ```
+/// use earworm::music::{FmAlgorithm, FmOperator, FmVoice, VoiceAllocator};
+/// use earworm::ADSR;
+///
+/// const SAMPLE_RATE: u32 = 44100;
+///
+/// // Electric piano: a 1:1 pair for the body and a 14:1 pair for the tine
+/// let mut piano = VoiceAllocator::<SAMPLE_RATE, 8, _, _>::new(|| {
+///     let sr = SAMPLE_RATE as f64;
+///     let fm = FmVoice::<SAMPLE_RATE>::new(
+///         FmAlgorithm::pairs(4),
+///         [
+///             FmOperator::new(1.0).with_envelope(ADSR::new(0.001, 1.5, 0.3, 0.4, sr)),
+///             FmOperator::new(1.0)
+///                 .with_level(1.8)
+///                 .with_envelope(ADSR::new(0.0, 1.0, 0.2, 0.4, sr)),
+///             FmOperator::new(1.0)
+///                 .with_level(0.4)
+///                 .with_envelope(ADSR::new(0.001, 0.3, 0.0, 0.2, sr)),
+///             FmOperator::new(14.0)
+///                 .with_level(1.2)
+///                 .with_envelope(ADSR::new(0.0, 0.1, 0.0, 0.1, sr)),
+///         ],
+///     );
+///     (fm, ADSR::new(0.001, 0.0, 1.0, 0.4, sr))
+/// });
+///
+/// piano.note_on(60, 0.8);
+/// ```
+pub struct FmVoice<const SAMPLE_RATE: u32> {
+    algorithm: FmAlgorithm,
+    operators: Vec<FmOperator>,
+    outputs: Vec<f64>,
+    frequency: f64,
+}
+
+impl<const SAMPLE_RATE: u32> FmVoice<SAMPLE_RATE> {
+    /// Creates a voice at 440 Hz.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of operators doesn't match the algorithm.
+    pub fn new(algorithm: FmAlgorithm, operators: impl IntoIterator<Item = FmOperator>) -> Self {
+        let operators: Vec<FmOperator> = operators.into_iter().collect();
+        assert_eq!(
+            operators.len(),
+            algorithm.operators(),
+            "the algorithm has {} operators but {} were given",
+            algorithm.operators(),
+            operators.len()
+        );
+        Self {
+            outputs: vec![0.0; operators.len()],
+            algorithm,
+            operators,
+            frequency: 440.0,
+        }
+    }
+
+    /// Returns the algorithm.
+    pub fn algorithm(&self) -> &FmAlgorithm {
+        &self.algorithm
+    }
+
+    /// Returns a mutable reference to operator `index`, or `None` if out of
+    /// range.
+    pub fn operator_mut(&mut self, index: usize) -> Option<&mut FmOperator> {
+        self.operators.get_mut(index)
+    }
+}
+
+impl<const SAMPLE_RATE: u32> Signal for FmVoice<SAMPLE_RATE> {
+    fn next_sample(&mut self) -> f64 {
+        // Modulators always have higher numbers, so work downwards
+        for op in (0..self.operators.len()).rev() {
+            let modulation: f64 = self
+                .algorithm
+                .routes
+                .iter()
+                .filter(|(_, to)| *to == op)
+                .map(|(from, _)| self.outputs[*from])
+                .sum();
+            self.outputs[op] =
+                self.operators[op].next(self.frequency, modulation, SAMPLE_RATE as f64);
+        }
+
+        let carriers = &self.algorithm.carriers;
+        if carriers.is_empty() {
+            return 0.0;
+        }
+        let sum: f64 = carriers.iter().map(|&op| self.outputs[op]).sum();
+        sum / carriers.len() as f64
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        for operator in &mut self.operators {
+            operator.level.prepare(max_block_size, sample_rate);
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32> AudioSignal<SAMPLE_RATE> for FmVoice<SAMPLE_RATE> {}
+
+impl<const SAMPLE_RATE: u32> Pitched for FmVoice<SAMPLE_RATE> {
+    fn set_frequency(&mut self, freq: f64) {
+        self.frequency = freq;
+    }
+
+    fn frequency(&self) -> f64 {
+        self.frequency
+    }
+
+    fn restart(&mut self) {
+        for operator in &mut self.operators {
+            operator.note_on();
+        }
+    }
+
+    fn release(&mut self) {
+        for operator in &mut self.operators {
+            operator.note_off();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ADSR;
+    use crate::core::ParamHandle;
+
+    const SR: u32 = 1000;
+
+    #[test]
+    fn test_single_carrier_is_a_sine() {
+        let mut fm = FmVoice::<SR>::new(FmAlgorithm::stack(1), [FmOperator::new(2.0)]);
+        fm.set_frequency(50.0);
+        for i in 0..100 {
+            let expected = (i as f64 * 100.0 / SR as f64 * TAU).sin();
+            assert!((fm.next_sample() - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_modulation_index_is_a_param() {
+        let index = ParamHandle::new(0.0);
+        let mut fm = FmVoice::<SR>::new(
+            FmAlgorithm::stack(2),
+            [
+                FmOperator::new(1.0),
+                FmOperator::new(3.0).with_level(index.clone()),
+            ],
+        );
+        fm.set_frequency(50.0);
+        let mut plain = FmVoice::<SR>::new(FmAlgorithm::stack(1), [FmOperator::new(1.0)]);
+        plain.set_frequency(50.0);
+
+        // A silent modulator leaves the carrier a pure sine
+        for _ in 0..50 {
+            assert!((fm.next_sample() - plain.next_sample()).abs() < 1e-9);
+        }
+        index.set(2.0);
+        let differs = (0..50).any(|_| (fm.next_sample() - plain.next_sample()).abs() > 0.1);
+        assert!(differs);
+    }
+
+    #[test]
+    fn test_parallel_carriers_are_normalized() {
+        let mut fm = FmVoice::<SR>::new(
+            FmAlgorithm::parallel(3),
+            [
+                FmOperator::new(1.0),
+                FmOperator::new(1.0),
+                FmOperator::new(1.0),
+            ],
+        );
+        fm.set_frequency(250.0);
+        fm.next_sample();
+        assert!((fm.next_sample() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_feedback_changes_the_waveform() {
+        let mut fm = FmVoice::<SR>::new(
+            FmAlgorithm::stack(1),
+            [FmOperator::new(1.0).with_feedback(1.0)],
+        );
+        fm.set_frequency(10.0);
+        let mut plain = FmVoice::<SR>::new(FmAlgorithm::stack(1), [FmOperator::new(1.0)]);
+        plain.set_frequency(10.0);
+        let differs = (0..100).any(|_| (fm.next_sample() - plain.next_sample()).abs() > 0.05);
+        assert!(differs);
+    }
+
+    #[test]
+    fn test_operator_envelopes_follow_notes() {
+        let env = ADSR::new(0.0, 0.0, 1.0, 0.01, SR as f64);
+        let mut fm = FmVoice::<SR>::new(
+            FmAlgorithm::stack(1),
+            [FmOperator::new(1.0).with_envelope(env)],
+        );
+        fm.set_frequency(250.0);
+        assert_eq!(fm.next_sample(), 0.0);
+        assert_eq!(fm.next_sample(), 0.0);
+
+        fm.restart();
+        fm.next_sample();
+        assert!((fm.next_sample() - 1.0).abs() < 1e-9);
+
+        fm.release();
+        for _ in 0..20 {
+            fm.next_sample();
+        }
+        assert!((0..8).all(|_| fm.next_sample() == 0.0));
+    }
+
+    #[test]
+    fn test_algorithm_presets() {
+        assert_eq!(FmAlgorithm::stack(3).routes(), &[(1, 0), (2, 1)]);
+        assert_eq!(FmAlgorithm::branch(3).routes(), &[(1, 0), (2, 0)]);
+        let pairs = FmAlgorithm::pairs(4);
+        assert_eq!(pairs.routes(), &[(1, 0), (3, 2)]);
+        assert_eq!(pairs.carriers(), &[0, 2]);
+        assert_eq!(FmAlgorithm::parallel(2).carriers(), &[0, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "operator 1 can only modulate lower-numbered operators, not 2")]
+    fn test_upward_modulation_panics() {
+        let _ = FmAlgorithm::new(3).with_modulation(1, 2);
+    }
+}
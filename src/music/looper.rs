@@ -0,0 +1,470 @@
+//! Bar-quantized live looper.
+
+use super::metronome::Metronome;
+use crate::core::{AudioSignal, Signal, debug_assert_finite};
+
+/// Transport state of a [`Looper`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LooperState {
+    /// Nothing recorded yet
+    Empty,
+    /// Waiting for the next bar line to start recording
+    Armed,
+    /// Recording the first layer; the loop length grows bar by bar
+    Recording,
+    /// Playing the loop back
+    Playing,
+    /// Playing the loop back while recording a new layer on top
+    Overdubbing,
+    /// Loop recorded but silent
+    Stopped,
+}
+
+/// Live looper synced to a [`Metronome`].
+///
+/// Records its input into a loop and plays it back underneath the live
+/// signal, which always passes through. Recording starts on a bar line and
+/// ends on a bar line, so the loop is always a whole number of bars and stays
+/// locked to the tempo. Overdubs go into separate layers that can be undone
+/// one at a time.
+///
+/// Every layer buffer is allocated up front, so the looper never allocates
+/// while running. Once all [`with_max_overdubs`](Self::with_max_overdubs)
+/// layers are in use, a new overdub merges the oldest one into the base
+/// layer, after which it can no longer be undone.
+///
+/// Transport changes that must land on the grid (starting and closing the
+/// first recording, starting playback from stopped) are deferred until the
+/// next bar line; overdubbing starts and stops immediately.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{Looper, LooperState, SineOscillator};
+/// use earworm::music::Metronome;
+///
+/// let input = SineOscillator::<44100>::new(220.0);
+/// // 4/4 at 120 BPM, loops of up to 8 bars
+/// let metronome = Metronome::new(120.0, 4, 44100);
+/// let mut looper = Looper::new(input, metronome, 4, 8);
+///
+/// looper.record();
+/// assert_eq!(looper.state(), LooperState::Recording);
+/// ```
+pub struct Looper<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
+    source: S,
+    metronome: Metronome,
+    beats_per_bar: u32,
+    max_length: usize, // samples available for the first layer
+    layers: Vec<Vec<f64>>,
+    spare_layers: Vec<Vec<f64>>, // preallocated buffers for future overdubs
+    position: usize,             // playback position within the loop
+    state: LooperState,
+    pending: Option<LooperState>, // transition waiting for the next bar line
+    at_bar_line: bool,            // the current sample starts a bar
+}
+
+/// Overdub layers a [`Looper`] keeps for undo unless configured otherwise.
+const DEFAULT_MAX_OVERDUBS: usize = 4;
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Looper<SAMPLE_RATE, S> {
+    /// Creates a new, empty looper.
+    ///
+    /// Buffers for the first layer and four overdubs (see
+    /// [`with_max_overdubs`](Self::with_max_overdubs)) are allocated up front so recording never allocates on the audio thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Live input to record and pass through
+    /// * `metronome` - Clock defining the bar grid (should run at `SAMPLE_RATE`)
+    /// * `beats_per_bar` - Time signature numerator (must be > 0)
+    /// * `max_bars` - Longest loop that can be recorded (must be > 0)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `beats_per_bar` or `max_bars` is 0.
+    pub fn new(source: S, metronome: Metronome, beats_per_bar: u32, max_bars: usize) -> Self {
+        assert!(beats_per_bar > 0, "beats_per_bar must be greater than 0");
+        assert!(max_bars > 0, "max_bars must be greater than 0");
+
+        let bar_length = SAMPLE_RATE as f64 * 60.0 / metronome.tempo() * beats_per_bar as f64;
+        let max_length = (bar_length * max_bars as f64).ceil() as usize;
+        let at_bar_line = metronome.phase(beats_per_bar as f64) < 1e-9;
+
+        Self {
+            source,
+            metronome,
+            beats_per_bar,
+            max_length,
+            layers: Vec::new(),
+            spare_layers: Vec::new(),
+            position: 0,
+            state: LooperState::Empty,
+            pending: None,
+            at_bar_line,
+        }
+        .with_max_overdubs(DEFAULT_MAX_OVERDUBS)
+    }
+
+    /// Sets how many overdub layers can be undone (builder style).
+    ///
+    /// Reallocates every layer buffer and discards anything recorded, so call
+    /// this before handing the looper to the audio thread.
+    pub fn with_max_overdubs(mut self, max_overdubs: usize) -> Self {
+        self.layers = Vec::with_capacity(max_overdubs + 1);
+        self.layers.push(Vec::with_capacity(self.max_length));
+        self.spare_layers = (0..max_overdubs)
+            .map(|_| Vec::with_capacity(self.max_length))
+            .collect();
+        self.position = 0;
+        self.state = LooperState::Empty;
+        self.pending = None;
+        self
+    }
+
+    /// Returns the transport state.
+    pub fn state(&self) -> LooperState {
+        self.state
+    }
+
+    /// Returns the number of recorded layers (the first recording plus overdubs).
+    pub fn layer_count(&self) -> usize {
+        if self.loop_length() == 0 {
+            0
+        } else {
+            self.layers.len()
+        }
+    }
+
+    /// Returns the loop length in samples (0 until the first recording has started).
+    pub fn loop_length(&self) -> usize {
+        self.layers[0].len()
+    }
+
+    /// Starts recording the first layer at the next bar line.
+    ///
+    /// Recording starts immediately if the metronome is sitting on a bar line.
+    /// Has no effect once a loop exists; use [`overdub`](Self::overdub) or
+    /// [`clear`](Self::clear) first.
+    pub fn record(&mut self) {
+        if self.state == LooperState::Empty {
+            self.state = LooperState::Armed;
+            if self.at_bar_line {
+                self.state = LooperState::Recording;
+            }
+        }
+    }
+
+    /// Starts playback.
+    ///
+    /// While recording the first layer, the loop is closed at the next bar
+    /// line and playback starts from its beginning. While overdubbing, the
+    /// current layer is kept and recording stops. From stopped, playback
+    /// restarts from the top of the loop at the next bar line.
+    ///
+    /// Has no effect while recording until at least one sample has been
+    /// captured.
+    pub fn play(&mut self) {
+        match self.state {
+            LooperState::Recording if self.loop_length() == 0 => {}
+            LooperState::Recording | LooperState::Stopped => {
+                self.pending = Some(LooperState::Playing)
+            }
+            LooperState::Overdubbing => self.state = LooperState::Playing,
+            _ => {}
+        }
+    }
+
+    /// Starts recording a new layer on top of the playing loop.
+    ///
+    /// While recording the first layer, the loop is closed at the next bar
+    /// line and overdubbing continues straight into the second pass. As with
+    /// [`play`](Self::play), nothing happens before a sample has been captured.
+    pub fn overdub(&mut self) {
+        match self.state {
+            LooperState::Recording if self.loop_length() == 0 => {}
+            LooperState::Recording => self.pending = Some(LooperState::Overdubbing),
+            LooperState::Playing => {
+                self.push_overdub_layer();
+                self.state = LooperState::Overdubbing;
+            }
+            _ => {}
+        }
+    }
+
+    /// Stops playback and rewinds to the top of the loop.
+    ///
+    /// Stopping while recording the first layer closes the loop at the next
+    /// bar line without playing it; if nothing has been captured yet the
+    /// looper returns to [`LooperState::Empty`].
+    pub fn stop(&mut self) {
+        match self.state {
+            LooperState::Recording if self.loop_length() == 0 => {
+                self.state = LooperState::Empty;
+                self.pending = None;
+            }
+            LooperState::Recording => self.pending = Some(LooperState::Stopped),
+            LooperState::Playing | LooperState::Overdubbing => {
+                self.state = LooperState::Stopped;
+                self.pending = None;
+                self.position = 0;
+            }
+            LooperState::Armed => self.state = LooperState::Empty,
+            _ => {}
+        }
+    }
+
+    /// Removes the most recent overdub layer.
+    ///
+    /// Returns `false` if there was no overdub to remove. An overdub still in
+    /// progress is discarded and the looper returns to playing.
+    pub fn undo(&mut self) -> bool {
+        if self.layers.len() < 2 {
+            return false;
+        }
+        if let Some(mut layer) = self.layers.pop() {
+            layer.clear();
+            self.spare_layers.push(layer);
+        }
+        if self.state == LooperState::Overdubbing {
+            self.state = LooperState::Playing;
+        }
+        true
+    }
+
+    /// Erases every layer and returns to [`LooperState::Empty`].
+    pub fn clear(&mut self) {
+        while self.undo() {}
+        self.layers[0].clear();
+        self.position = 0;
+        self.state = LooperState::Empty;
+        self.pending = None;
+    }
+
+    /// Applies a deferred transition when a bar line is reached.
+    fn on_bar_line(&mut self) {
+        if self.state == LooperState::Armed {
+            self.state = LooperState::Recording;
+            return;
+        }
+        let Some(next) = self.pending.take() else {
+            return;
+        };
+        self.position = 0;
+        self.state = next;
+        if next == LooperState::Overdubbing {
+            self.push_overdub_layer();
+        }
+    }
+
+    /// Starts a silent overdub layer from the preallocated spares.
+    ///
+    /// With no spare left, the oldest overdub is merged into the base layer
+    /// and its buffer reused.
+    fn push_overdub_layer(&mut self) {
+        let length = self.loop_length();
+        let mut layer = match self.spare_layers.pop() {
+            Some(layer) => layer,
+            None if self.layers.len() > 1 => {
+                let oldest = self.layers.remove(1);
+                for (base, sample) in self.layers[0].iter_mut().zip(&oldest) {
+                    *base += sample;
+                }
+                oldest
+            }
+            // No overdub layers at all: record straight into the base layer
+            None => return,
+        };
+        layer.clear();
+        layer.resize(length, 0.0);
+        self.layers.push(layer);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for Looper<SAMPLE_RATE, S> {
+    fn next_sample(&mut self) -> f64 {
+        let input = self.source.next_sample();
+
+        if self.at_bar_line {
+            self.on_bar_line();
+        }
+
+        let looped = match self.state {
+            LooperState::Recording => {
+                self.layers[0].push(input);
+                if self.loop_length() >= self.max_length {
+                    // Out of room: close the loop here
+                    self.pending = None;
+                    self.position = 0;
+                    self.state = LooperState::Playing;
+                }
+                0.0
+            }
+            LooperState::Playing | LooperState::Overdubbing => {
+                let looped = self.layers.iter().map(|layer| layer[self.position]).sum();
+                if self.state == LooperState::Overdubbing
+                    && let Some(layer) = self.layers.last_mut()
+                {
+                    layer[self.position] += input;
+                }
+                self.position = (self.position + 1) % self.loop_length();
+                looped
+            }
+            _ => 0.0,
+        };
+
+        let steps_per_bar = (self.metronome.steps_per_beat() * self.beats_per_bar) as u64;
+        self.at_bar_line =
+            self.metronome.tick() && self.metronome.current_step().is_multiple_of(steps_per_bar);
+
+        let output = input + looped;
+        debug_assert_finite("Looper", input, output);
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE>
+    for Looper<SAMPLE_RATE, S>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConstantSignal;
+
+    /// 4/4 at 120 BPM and 8kHz: one bar is exactly 16000 samples.
+    const BAR: usize = 16000;
+
+    fn looper(level: f64) -> Looper<8000, impl AudioSignal<8000>> {
+        Looper::new(
+            ConstantSignal::<8000>(level),
+            Metronome::new(120.0, 4, 8000),
+            4,
+            4,
+        )
+    }
+
+    fn run<S: Signal>(signal: &mut S, samples: usize) -> Vec<f64> {
+        signal.iter().take(samples).collect()
+    }
+
+    #[test]
+    fn test_loop_length_is_quantized_to_bars() {
+        let mut looper = looper(0.5);
+        looper.record();
+        run(&mut looper, BAR + 1000);
+        looper.play();
+        assert_eq!(looper.state(), LooperState::Recording);
+
+        // Recording continues to the end of the second bar
+        run(&mut looper, BAR - 1000);
+        assert_eq!(looper.loop_length(), 2 * BAR);
+        let output = run(&mut looper, 10);
+        assert_eq!(looper.state(), LooperState::Playing);
+        assert!(output.iter().all(|s| (s - 1.0).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_record_waits_for_bar_line() {
+        let mut looper = looper(0.5);
+        run(&mut looper, 100);
+        looper.record();
+        assert_eq!(looper.state(), LooperState::Armed);
+        run(&mut looper, BAR - 100);
+        assert_eq!(looper.state(), LooperState::Armed);
+        run(&mut looper, 1);
+        assert_eq!(looper.state(), LooperState::Recording);
+    }
+
+    #[test]
+    fn test_overdub_and_undo() {
+        let mut looper = looper(0.5);
+        looper.record();
+        run(&mut looper, 100);
+        looper.overdub();
+        run(&mut looper, 2 * BAR);
+        looper.play();
+        assert_eq!(looper.layer_count(), 2);
+
+        // Input + first layer + overdub
+        assert!((run(&mut looper, 1)[0] - 1.5).abs() < 1e-12);
+        assert!(looper.undo());
+        assert!((run(&mut looper, 1)[0] - 1.0).abs() < 1e-12);
+        assert!(!looper.undo());
+    }
+
+    #[test]
+    fn test_stops_recording_when_full() {
+        let mut looper = looper(0.5);
+        looper.record();
+        run(&mut looper, 5 * BAR);
+        assert_eq!(looper.state(), LooperState::Playing);
+        assert_eq!(looper.loop_length(), 4 * BAR);
+    }
+
+    #[test]
+    fn test_play_before_first_sample_is_ignored() {
+        let mut looper = looper(0.5);
+        looper.record();
+        assert_eq!(looper.state(), LooperState::Recording);
+        looper.play();
+        looper.overdub();
+
+        // Nothing was captured, so the loop keeps recording instead of closing
+        assert!((run(&mut looper, 1)[0] - 0.5).abs() < 1e-12);
+        assert_eq!(looper.state(), LooperState::Recording);
+        assert_eq!(looper.loop_length(), 1);
+    }
+
+    #[test]
+    fn test_stop_before_first_sample_empties() {
+        let mut looper = looper(0.5);
+        looper.record();
+        looper.stop();
+        assert_eq!(looper.state(), LooperState::Empty);
+        looper.play();
+        run(&mut looper, BAR + 1);
+        assert_eq!(looper.state(), LooperState::Empty);
+    }
+
+    #[test]
+    fn test_overdubs_beyond_limit_merge_oldest_layer() {
+        let mut looper = looper(0.5).with_max_overdubs(1);
+        looper.record();
+        run(&mut looper, 100);
+        looper.overdub();
+        run(&mut looper, 2 * BAR);
+        looper.play();
+        looper.overdub();
+        assert_eq!(looper.state(), LooperState::Overdubbing);
+        run(&mut looper, BAR);
+        looper.play();
+
+        // Base + merged first overdub, plus the second overdub
+        assert_eq!(looper.layer_count(), 2);
+        assert!((run(&mut looper, 1)[0] - 2.0).abs() < 1e-12);
+        assert!(looper.undo());
+        assert!((run(&mut looper, 1)[0] - 1.5).abs() < 1e-12);
+        assert!(!looper.undo());
+    }
+
+    #[test]
+    fn test_stop_and_clear() {
+        let mut looper = looper(0.5);
+        looper.record();
+        run(&mut looper, 10);
+        looper.stop();
+        run(&mut looper, BAR);
+        assert_eq!(looper.state(), LooperState::Stopped);
+        assert!(run(&mut looper, 10).iter().all(|s| (s - 0.5).abs() < 1e-12));
+
+        looper.clear();
+        assert_eq!(looper.state(), LooperState::Empty);
+        assert_eq!(looper.layer_count(), 0);
+    }
+}
@@ -2,6 +2,51 @@
 //!
 //! The `Metronome` provides sample-accurate timing for sequencers and rhythm-based
 //! musical applications. It converts musical time (beats, steps) to audio time (samples).
+//! Tempo can jump with [`Metronome::set_tempo`] or glide to a new value over a
+//! number of beats with [`Metronome::ramp_tempo`].
+//!
+//! To follow another clock, read its tempo and beat time once per audio
+//! buffer and pass them to [`Metronome::sync_to_beat`]. Compare against it with
+//! [`Metronome::beat_position`] and [`Metronome::phase`]. earworm does not talk
+//! to any clock protocol itself. In particular there is no Ableton Link
+//! support: a Link binding would have to supply the session's tempo and beat
+//! time through these calls.
+
+/// The shape of a tempo ramp.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TempoCurve {
+    /// The tempo changes by the same number of BPM every beat
+    #[default]
+    Linear,
+    /// The tempo changes by the same ratio every beat, which sounds even
+    /// across large changes
+    Exponential,
+}
+
+/// A tempo ramp in progress.
+#[derive(Debug, Clone, Copy)]
+struct TempoRamp {
+    start_bpm: f64,
+    target_bpm: f64,
+    /// Length of the ramp in beats
+    beats: f64,
+    /// Beats elapsed since the ramp started
+    elapsed: f64,
+    curve: TempoCurve,
+}
+
+impl TempoRamp {
+    /// Tempo after `elapsed` beats.
+    fn tempo_at(&self, elapsed: f64) -> f64 {
+        let progress = (elapsed / self.beats).min(1.0);
+        match self.curve {
+            TempoCurve::Linear => self.start_bpm + (self.target_bpm - self.start_bpm) * progress,
+            TempoCurve::Exponential => {
+                self.start_bpm * (self.target_bpm / self.start_bpm).powf(progress)
+            }
+        }
+    }
+}
 
 /// A sample-accurate musical metronome.
 ///
@@ -63,6 +108,8 @@ pub struct Metronome {
     sample_accumulator: f64,
     /// Current step number (wraps based on pattern length)
     current_step: u64,
+    /// Tempo ramp in progress, if any
+    ramp: Option<TempoRamp>,
 }
 
 impl Metronome {
@@ -102,6 +149,7 @@ impl Metronome {
             samples_per_step,
             sample_accumulator: 0.0,
             current_step: 0,
+            ramp: None,
         }
     }
 
@@ -138,6 +186,12 @@ impl Metronome {
     /// assert!(samples > 5500 && samples < 5525);
     /// ```
     pub fn tick(&mut self) -> bool {
+        if let Some(mut ramp) = self.ramp {
+            ramp.elapsed += self.bpm / 60.0 / self.sample_rate as f64;
+            self.retime(ramp.tempo_at(ramp.elapsed));
+            self.ramp = (ramp.elapsed < ramp.beats).then_some(ramp);
+        }
+
         self.sample_accumulator += 1.0;
 
         if self.sample_accumulator >= self.samples_per_step {
@@ -193,6 +247,10 @@ impl Metronome {
 
     /// Sets the tempo in BPM.
     ///
+    /// The position within the current step is kept, so the next step comes
+    /// after the rest of the step at the new tempo rather than early or late.
+    /// Cancels any tempo ramp in progress.
+    ///
     /// # Arguments
     ///
     /// * `bpm` - New tempo in beats per minute (must be > 0)
@@ -211,9 +269,85 @@ impl Metronome {
     /// ```
     pub fn set_tempo(&mut self, bpm: f64) {
         assert!(bpm > 0.0, "BPM must be greater than 0");
-        self.bpm = bpm;
-        self.samples_per_step =
+        self.ramp = None;
+        self.retime(bpm);
+    }
+
+    /// Glides the tempo from its current value to `bpm` over the next
+    /// `beats` beats.
+    ///
+    /// The tempo is recalculated every sample, so steps keep landing exactly
+    /// where the changing tempo puts them through accelerandos and
+    /// ritardandos. Starting a new ramp replaces the one in progress, from
+    /// wherever it had reached. Zero beats changes the tempo at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `bpm` - Tempo at the end of the ramp (must be > 0)
+    /// * `beats` - Length of the ramp in beats (must be >= 0)
+    /// * `curve` - How the tempo moves between the two
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bpm` is <= 0 or `beats` is negative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::{Metronome, TempoCurve};
+    ///
+    /// let mut metronome = Metronome::new(90.0, 4, 44100);
+    ///
+    /// // Speed up to 150 BPM over two bars of 4/4
+    /// metronome.ramp_tempo(150.0, 8.0, TempoCurve::Exponential);
+    /// while metronome.beat_position() < 4.0 {
+    ///     metronome.tick();
+    /// }
+    /// assert!(metronome.tempo() > 110.0 && metronome.tempo() < 120.0);
+    ///
+    /// while metronome.is_ramping() {
+    ///     metronome.tick();
+    /// }
+    /// assert_eq!(metronome.tempo(), 150.0);
+    /// ```
+    pub fn ramp_tempo(&mut self, bpm: f64, beats: f64, curve: TempoCurve) {
+        assert!(bpm > 0.0, "BPM must be greater than 0");
+        assert!(beats >= 0.0, "ramp length must not be negative");
+        if beats == 0.0 {
+            self.set_tempo(bpm);
+            return;
+        }
+        self.ramp = Some(TempoRamp {
+            start_bpm: self.bpm,
+            target_bpm: bpm,
+            beats,
+            elapsed: 0.0,
+            curve,
+        });
+    }
+
+    /// Returns `true` while a tempo ramp is in progress.
+    pub fn is_ramping(&self) -> bool {
+        self.ramp.is_some()
+    }
+
+    /// Returns the tempo the ramp in progress is heading for, if any.
+    pub fn ramp_target(&self) -> Option<f64> {
+        self.ramp.map(|ramp| ramp.target_bpm)
+    }
+
+    /// Stops a tempo ramp in progress, holding the tempo it had reached.
+    pub fn cancel_ramp(&mut self) {
+        self.ramp = None;
+    }
+
+    /// Changes the tempo, keeping the position within the current step.
+    fn retime(&mut self, bpm: f64) {
+        let samples_per_step =
             Self::calculate_samples_per_step(bpm, self.steps_per_beat, self.sample_rate);
+        self.sample_accumulator *= samples_per_step / self.samples_per_step;
+        self.bpm = bpm;
+        self.samples_per_step = samples_per_step;
     }
 
     /// Returns the current tempo in BPM.
@@ -243,6 +377,101 @@ impl Metronome {
     pub fn steps_per_beat(&self) -> u32 {
         self.steps_per_beat
     }
+
+    /// Returns the current musical position in beats, including the fractional
+    /// progress towards the next step.
+    ///
+    /// This is on the same scale as the beat time an external clock passes to
+    /// [`sync_to_beat`](Self::sync_to_beat), so the two can be compared
+    /// directly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::Metronome;
+    ///
+    /// let mut metronome = Metronome::new(120.0, 4, 44100);
+    ///
+    /// // Half a second at 120 BPM is exactly one beat
+    /// for _ in 0..22050 {
+    ///     metronome.tick();
+    /// }
+    /// assert!((metronome.beat_position() - 1.0).abs() < 1e-9);
+    /// ```
+    pub fn beat_position(&self) -> f64 {
+        let steps = self.current_step as f64 + self.sample_accumulator / self.samples_per_step;
+        steps / self.steps_per_beat as f64
+    }
+
+    /// Returns the phase of the metronome within a cycle of `quantum` beats.
+    ///
+    /// The result is in `[0, quantum)`. With a quantum of 4 this is the
+    /// position within a 4/4 bar.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `quantum` is <= 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::Metronome;
+    ///
+    /// let mut metronome = Metronome::new(120.0, 4, 44100);
+    /// metronome.sync_to_beat(120.0, 5.5);
+    /// assert!((metronome.phase(4.0) - 1.5).abs() < 1e-9);
+    /// ```
+    pub fn phase(&self, quantum: f64) -> f64 {
+        assert!(quantum > 0.0, "quantum must be greater than 0");
+        self.beat_position().rem_euclid(quantum)
+    }
+
+    /// Locks the metronome to an external clock's tempo and beat position.
+    ///
+    /// Call this from the audio callback with the tempo and beat time reported
+    /// by the external clock for the start of the buffer. The step counter and sub-step accumulator are
+    /// moved so that [`beat_position`](Self::beat_position) matches `beat`.
+    ///
+    /// Negative beat positions (count-in before the session's beat zero) are
+    /// clamped to 0. Cancels any tempo ramp in progress.
+    ///
+    /// # Arguments
+    ///
+    /// * `bpm` - Tempo of the external clock (must be > 0)
+    /// * `beat` - Beat position of the external clock at this sample
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bpm` is <= 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::Metronome;
+    ///
+    /// let mut metronome = Metronome::new(120.0, 4, 44100);
+    ///
+    /// // The external clock is at 128 BPM and currently 2.25 beats in
+    /// metronome.sync_to_beat(128.0, 2.25);
+    /// assert_eq!(metronome.tempo(), 128.0);
+    /// assert_eq!(metronome.current_step(), 9);
+    /// ```
+    pub fn sync_to_beat(&mut self, bpm: f64, beat: f64) {
+        self.set_tempo(bpm);
+
+        let steps = beat.max(0.0) * self.steps_per_beat as f64;
+        // A beat within rounding error of a step boundary is on it, so the
+        // step isn't reached a second time
+        let nearest = steps.round();
+        let steps = if (steps - nearest).abs() < 1e-9 {
+            nearest
+        } else {
+            steps
+        };
+        let whole = steps.floor();
+        self.current_step = whole as u64;
+        self.sample_accumulator = (steps - whole) * self.samples_per_step;
+    }
 }
 
 #[cfg(test)]
@@ -422,6 +651,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_beat_position_and_phase() {
+        let mut metronome = Metronome::new(120.0, 4, SAMPLE_RATE);
+        assert_eq!(metronome.beat_position(), 0.0);
+
+        // 2.5 seconds at 120 BPM = 5 beats
+        for _ in 0..(SAMPLE_RATE * 5 / 2) {
+            metronome.tick();
+        }
+        assert!((metronome.beat_position() - 5.0).abs() < 1e-9);
+        assert!((metronome.phase(4.0) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sync_to_beat() {
+        let mut metronome = Metronome::new(120.0, 4, SAMPLE_RATE);
+        metronome.sync_to_beat(140.0, 3.1);
+
+        assert_eq!(metronome.tempo(), 140.0);
+        assert_eq!(metronome.current_step(), 12);
+        assert!((metronome.beat_position() - 3.1).abs() < 1e-9);
+
+        // The next step boundary lands on beat 3.25
+        while !metronome.tick() {}
+        assert_eq!(metronome.current_step(), 13);
+        assert!((metronome.beat_position() - 3.25).abs() < 1e-3);
+
+        // Negative positions clamp to the start
+        metronome.sync_to_beat(140.0, -1.0);
+        assert_eq!(metronome.beat_position(), 0.0);
+    }
+
     #[test]
     fn test_step_wrapping() {
         let mut metronome = Metronome::new(120.0, 4, SAMPLE_RATE);
@@ -439,4 +700,60 @@ mod tests {
         while !metronome.tick() {}
         assert_eq!(metronome.current_step(), 0); // Wrapped
     }
+
+    #[test]
+    fn test_set_tempo_keeps_step_phase() {
+        let mut metronome = Metronome::new(120.0, 4, SAMPLE_RATE);
+        for _ in 0..3000 {
+            metronome.tick();
+        }
+        let position = metronome.beat_position();
+        metronome.set_tempo(240.0);
+        assert!((metronome.beat_position() - position).abs() < 1e-12);
+        // The rest of the step at double speed, not an instant step
+        assert!(!metronome.tick());
+    }
+
+    /// Seconds taken to play `beats` beats from the start.
+    fn seconds_to_beat(metronome: &mut Metronome, beats: f64) -> f64 {
+        let mut samples = 0;
+        while metronome.beat_position() < beats {
+            metronome.tick();
+            samples += 1;
+        }
+        samples as f64 / SAMPLE_RATE as f64
+    }
+
+    #[test]
+    fn test_ramps_follow_their_curves() {
+        // 120 to 240 BPM over 4 beats; durations from integrating 60 / bpm
+        let mut linear = Metronome::new(120.0, 4, SAMPLE_RATE);
+        linear.ramp_tempo(240.0, 4.0, TempoCurve::Linear);
+        assert_eq!(linear.ramp_target(), Some(240.0));
+        let expected = 2.0 * std::f64::consts::LN_2;
+        assert!((seconds_to_beat(&mut linear, 4.0) - expected).abs() < 1e-3);
+        linear.tick();
+        assert!(!linear.is_ramping());
+        assert_eq!(linear.tempo(), 240.0);
+
+        let mut exponential = Metronome::new(120.0, 4, SAMPLE_RATE);
+        exponential.ramp_tempo(240.0, 4.0, TempoCurve::Exponential);
+        let expected = 1.0 / std::f64::consts::LN_2;
+        assert!((seconds_to_beat(&mut exponential, 4.0) - expected).abs() < 1e-3);
+
+        // Halfway through, the curves differ as expected
+        let mut linear = Metronome::new(120.0, 4, SAMPLE_RATE);
+        linear.ramp_tempo(240.0, 4.0, TempoCurve::Linear);
+        seconds_to_beat(&mut linear, 2.0);
+        assert!((linear.tempo() - 180.0).abs() < 0.1);
+        let mut exponential = Metronome::new(120.0, 4, SAMPLE_RATE);
+        exponential.ramp_tempo(240.0, 4.0, TempoCurve::Exponential);
+        seconds_to_beat(&mut exponential, 2.0);
+        assert!((exponential.tempo() - 120.0 * 2f64.sqrt()).abs() < 0.1);
+
+        exponential.cancel_ramp();
+        let held = exponential.tempo();
+        exponential.tick();
+        assert_eq!(exponential.tempo(), held);
+    }
 }
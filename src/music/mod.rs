@@ -2,20 +2,46 @@ mod adsr;
 mod ahd;
 mod allocator;
 mod ar;
+mod bounce;
+mod command;
 pub mod core;
+mod deck;
 pub mod envelope;
+mod fm;
 pub mod frequency;
+mod looper;
 mod metronome;
+mod mod_envelope;
+mod note_sources;
 mod pattern;
+mod pattern_editor;
+mod scheduler;
 mod sequencer;
+mod slicer;
+mod song;
+mod track;
+mod transport;
 mod voice;
 
 pub use adsr::ADSR;
 pub use ahd::AHD;
-pub use allocator::{StealingStrategy, VoiceAllocator};
+pub use allocator::{StealingStrategy, StereoVoices, VoiceAllocator, VoiceInfo};
 pub use ar::AR;
+pub use bounce::{BounceInput, bounce_pattern, bounce_section, bounce_song};
+pub use command::{NoteCommand, NoteTarget, SequencerCommand};
+pub use deck::{Deck, DeckPlayer, DeckSide, DjMixer};
 pub use envelope::{Envelope, EnvelopeState};
-pub use metronome::Metronome;
-pub use pattern::Pattern;
+pub use fm::{FmAlgorithm, FmOperator, FmVoice};
+pub use looper::{Looper, LooperState};
+pub use metronome::{Metronome, TempoCurve};
+pub use mod_envelope::{EnvelopeGate, ModEnvelope};
+pub use note_sources::{KeyTrack, NoteSources};
+pub use pattern::{Pattern, StepSummary, Trigger, TriggerCondition};
+pub use pattern_editor::{PatternEdit, PatternEditError, PatternEditor};
+pub use scheduler::NoteScheduler;
 pub use sequencer::{PlayState, Sequencer};
+pub use slicer::Slicer;
+pub use song::{ChordTrack, Section, Song, SongPlayer};
+pub use track::Track;
+pub use transport::{BarPosition, TimeSignature, Transport, TransportTick};
 pub use voice::Voice;
@@ -0,0 +1,240 @@
+//! Envelopes as modulation sources.
+
+use super::envelope::Envelope;
+use crate::{Param, Signal};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A handle for opening and closing a [`ModEnvelope`] from another part of
+/// the graph.
+///
+/// A modulation envelope usually ends up inside the signal it modulates (as a
+/// [`Param`]), out of reach of the code that plays notes. Pass a handle to
+/// [`Voice::with_mod_envelope`](super::Voice::with_mod_envelope) to have the
+/// voice's notes drive it, or call [`open`](Self::open) and
+/// [`close`](Self::close) yourself. Both are lock-free and take effect on the
+/// envelope's next sample.
+#[derive(Debug, Clone, Default)]
+pub struct EnvelopeGate {
+    // Note-on count in the upper bits, gate open in the lowest bit
+    state: Arc<AtomicU64>,
+}
+
+impl EnvelopeGate {
+    /// Triggers the envelope (a note-on).
+    pub fn open(&self) {
+        let _ = self
+            .state
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |state| {
+                Some((((state >> 1) + 1) << 1) | 1)
+            });
+    }
+
+    /// Releases the envelope (a note-off).
+    pub fn close(&self) {
+        self.state.fetch_and(!1, Ordering::Relaxed);
+    }
+
+    /// Returns true if the gate is open.
+    pub fn is_open(&self) -> bool {
+        self.state.load(Ordering::Relaxed) & 1 == 1
+    }
+
+    fn load(&self) -> u64 {
+        self.state.load(Ordering::Relaxed)
+    }
+}
+
+/// An envelope wrapped as a signal for modulating parameters.
+///
+/// Outputs the envelope level scaled by a depth, optionally inverted, which
+/// covers the usual filter-envelope controls: a depth of 0.5 opens the filter
+/// half as far, and an inverted envelope sweeps it down instead of up. The
+/// depth and the sustain level accept [`Param`]s, so velocity or a mod wheel
+/// can drive them.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::music::{ModEnvelope, NoteSources, Voice};
+/// use earworm::{ADSR, SawtoothOscillator, SignalExt};
+///
+/// const SAMPLE_RATE: u32 = 44100;
+///
+/// // A pitch envelope that drops 24 Hz and recovers, deeper on harder notes
+/// let sources = NoteSources::new();
+/// let pitch_env = ModEnvelope::new(ADSR::new(0.0, 0.08, 0.0, 0.1, SAMPLE_RATE as f64))
+///     .with_depth(sources.velocity().gain(24.0))
+///     .with_invert(true);
+/// let gate = pitch_env.trigger();
+///
+/// let osc = SawtoothOscillator::<SAMPLE_RATE>::with_frequency_mod(110.0, pitch_env);
+/// let env = ADSR::new(0.005, 0.2, 0.6, 0.3, SAMPLE_RATE as f64);
+/// let mut voice = Voice::new(osc, env)
+///     .with_note_sources(sources)
+///     .with_mod_envelope(gate);
+///
+/// voice.note_on(45u8, 1.0);
+/// ```
+pub struct ModEnvelope<E: Envelope> {
+    envelope: E,
+    depth: Param,
+    invert: bool,
+    sustain: Option<Param>,
+    gate: EnvelopeGate,
+    seen_triggers: u64,
+    open: bool,
+}
+
+impl<E: Envelope> ModEnvelope<E> {
+    /// Wraps an envelope at full depth.
+    pub fn new(envelope: E) -> Self {
+        Self {
+            envelope,
+            depth: Param::Fixed(1.0),
+            invert: false,
+            sustain: None,
+            gate: EnvelopeGate::default(),
+            seen_triggers: 0,
+            open: false,
+        }
+    }
+
+    /// Scales the envelope's output (builder style).
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - Output at the envelope's peak (fixed or modulated)
+    pub fn with_depth(mut self, depth: impl Into<Param>) -> Self {
+        self.depth = depth.into();
+        self
+    }
+
+    /// Negates the output, so the envelope pulls its target down (builder
+    /// style).
+    pub fn with_invert(mut self, invert: bool) -> Self {
+        self.invert = invert;
+        self
+    }
+
+    /// Drives the envelope's sustain level from a parameter (builder style).
+    ///
+    /// The level is updated every sample, so changing it while a note is
+    /// held moves the sustained output. Envelopes without a sustain stage
+    /// ignore it.
+    pub fn with_sustain(mut self, sustain: impl Into<Param>) -> Self {
+        self.sustain = Some(sustain.into());
+        self
+    }
+
+    /// Returns a handle that triggers and releases this envelope.
+    pub fn trigger(&self) -> EnvelopeGate {
+        self.gate.clone()
+    }
+
+    /// Returns a reference to the wrapped envelope.
+    pub fn envelope(&self) -> &E {
+        &self.envelope
+    }
+
+    /// Returns a mutable reference to the wrapped envelope.
+    pub fn envelope_mut(&mut self) -> &mut E {
+        &mut self.envelope
+    }
+}
+
+impl<E: Envelope> Signal for ModEnvelope<E> {
+    fn next_sample(&mut self) -> f64 {
+        let state = self.gate.load();
+        let triggers = state >> 1;
+        if triggers != self.seen_triggers {
+            self.seen_triggers = triggers;
+            self.envelope.trigger(1.0);
+            self.open = true;
+        }
+        if self.open && state & 1 == 0 {
+            self.open = false;
+            self.envelope.release();
+        }
+
+        if let Some(sustain) = &mut self.sustain {
+            self.envelope.set_sustain_level(sustain.value());
+        }
+        let depth = self.depth.value();
+        let level = self.envelope.next_sample() * depth;
+        if self.invert { -level } else { level }
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.depth.prepare(max_block_size, sample_rate);
+        if let Some(sustain) = &mut self.sustain {
+            sustain.prepare(max_block_size, sample_rate);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ADSR;
+    use crate::core::ParamHandle;
+
+    const SAMPLE_RATE: f64 = 100.0;
+
+    #[test]
+    fn test_gate_triggers_and_releases() {
+        let mut env = ModEnvelope::new(ADSR::new(0.0, 0.0, 0.5, 0.1, SAMPLE_RATE));
+        let gate = env.trigger();
+        assert_eq!(env.next_sample(), 0.0);
+
+        gate.open();
+        assert!(gate.is_open());
+        assert_eq!(env.next_sample(), 1.0);
+        assert_eq!(env.next_sample(), 0.5);
+
+        gate.close();
+        env.next_sample();
+        assert!(env.envelope().is_releasing());
+        for _ in 0..20 {
+            env.next_sample();
+        }
+        assert!(!env.envelope().is_active());
+    }
+
+    #[test]
+    fn test_retrigger_while_open() {
+        let mut env = ModEnvelope::new(ADSR::new(0.1, 0.0, 0.5, 0.1, SAMPLE_RATE));
+        let gate = env.trigger();
+        gate.open();
+        for _ in 0..5 {
+            env.next_sample();
+        }
+        gate.open();
+        // Attack restarts from the beginning
+        assert_eq!(env.next_sample(), 0.0);
+    }
+
+    #[test]
+    fn test_depth_and_invert() {
+        let depth = ParamHandle::new(0.5);
+        let mut env = ModEnvelope::new(ADSR::new(0.0, 0.0, 1.0, 0.0, SAMPLE_RATE))
+            .with_depth(depth.clone())
+            .with_invert(true);
+        env.trigger().open();
+        assert_eq!(env.next_sample(), -0.5);
+        depth.set(2.0);
+        assert_eq!(env.next_sample(), -2.0);
+    }
+
+    #[test]
+    fn test_modulated_sustain() {
+        let sustain = ParamHandle::new(0.25);
+        let mut env = ModEnvelope::new(ADSR::new(0.0, 0.0, 1.0, 0.0, SAMPLE_RATE))
+            .with_sustain(sustain.clone());
+        env.trigger().open();
+        env.next_sample();
+        assert_eq!(env.next_sample(), 0.25);
+        sustain.set(0.75);
+        assert_eq!(env.next_sample(), 0.75);
+    }
+}
@@ -0,0 +1,236 @@
+//! Per-voice note number, velocity and random values as modulation sources.
+
+use crate::core::{ParamHandle, RngContext, splitmix64_mix};
+use crate::{Param, Signal};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The note number and velocity of a voice's current note, as modulation sources.
+///
+/// Attach to a voice with [`Voice::with_note_sources`](super::Voice::with_note_sources);
+/// every note-on then updates the values, and any signal built from these
+/// sources follows along. Use them as [`Param`]s anywhere in the voice's signal
+/// chain: key tracking on a filter cutoff, velocity on an LFO's depth, and so on.
+///
+/// The note number is fractional for pitches between semitones. Before the
+/// first note-on it is 60 (middle C), and the velocity and random value are 0.0.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::music::{NoteSources, Voice};
+/// use earworm::{ADSR, Lfo, LfoWaveform, SawtoothOscillator, SignalExt};
+///
+/// const SAMPLE_RATE: u32 = 44100;
+///
+/// let sources = NoteSources::new();
+///
+/// // Vibrato at 4 Hz around middle C, faster on higher notes,
+/// // and deeper the harder a note is played
+/// let rate = sources.key_track(60.0, 0.5).gain(4.0);
+/// let depth = sources.velocity().gain(6.0);
+/// let vibrato = Lfo::<SAMPLE_RATE>::new(LfoWaveform::Sine, rate).gain(depth);
+///
+/// let osc = SawtoothOscillator::<SAMPLE_RATE>::with_frequency_mod(220.0, vibrato);
+/// let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+/// let mut voice = Voice::new(osc, env).with_note_sources(sources.clone());
+///
+/// voice.note_on(72u8, 0.5);
+/// assert_eq!(sources.note().get(), 72.0);
+/// assert_eq!(sources.velocity().get(), 0.5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct NoteSources {
+    note: ParamHandle,
+    velocity: ParamHandle,
+    random: ParamHandle,
+    rng_state: Arc<AtomicU64>,
+}
+
+impl Default for NoteSources {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NoteSources {
+    /// Creates sources at note 60 and velocity 0.0, with a randomly seeded
+    /// random source.
+    pub fn new() -> Self {
+        Self::with_seed(rand::random())
+    }
+
+    /// Creates sources whose random values follow a fixed sequence.
+    ///
+    /// Sources with the same seed produce the same random value for each
+    /// successive note, which keeps offline renders reproducible.
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            note: ParamHandle::new(60.0),
+            velocity: ParamHandle::new(0.0),
+            random: ParamHandle::new(0.0),
+            rng_state: Arc::new(AtomicU64::new(seed)),
+        }
+    }
+
+    /// Creates sources seeded from `context`, so their random values are
+    /// reproduced along with the rest of a piece.
+    pub fn from_context(context: &mut RngContext) -> Self {
+        Self::with_seed(context.next_seed())
+    }
+
+    /// Returns the current note number as a signal.
+    pub fn note(&self) -> ParamHandle {
+        self.note.clone()
+    }
+
+    /// Returns the current note's velocity (0.0 to 1.0) as a signal.
+    pub fn velocity(&self) -> ParamHandle {
+        self.velocity.clone()
+    }
+
+    /// Returns a random value (-1.0 to 1.0) drawn at each note-on as a signal.
+    ///
+    /// The value is held for the whole note, so scaling it by a small amount
+    /// humanizes pitch, cutoff or level differently for every note.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::{NoteSources, Voice};
+    /// use earworm::{ADSR, SawtoothOscillator, SignalExt};
+    ///
+    /// const SAMPLE_RATE: u32 = 44100;
+    ///
+    /// // Each note is up to 2 Hz off pitch, like a slightly unstable analog oscillator
+    /// let sources = NoteSources::with_seed(7);
+    /// let drift = sources.random().gain(2.0);
+    /// let osc = SawtoothOscillator::<SAMPLE_RATE>::with_frequency_mod(220.0, drift);
+    /// let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+    /// let mut voice = Voice::new(osc, env).with_note_sources(sources.clone());
+    ///
+    /// voice.note_on(57u8, 0.8);
+    /// let value = sources.random().get();
+    /// assert!((-1.0..=1.0).contains(&value));
+    /// ```
+    pub fn random(&self) -> ParamHandle {
+        self.random.clone()
+    }
+
+    /// Returns a key tracking multiplier.
+    ///
+    /// Outputs 1.0 at `reference_note` and changes by a factor of
+    /// `2^amount` per octave, so multiplying a frequency parameter by it makes
+    /// that parameter follow the keyboard.
+    ///
+    /// # Arguments
+    ///
+    /// * `reference_note` - Note number at which the output is 1.0
+    /// * `amount` - Tracking amount: 1.0 follows the keyboard exactly, 0.5
+    ///   moves half an octave per octave, 0.0 disables tracking, and negative
+    ///   values track inversely
+    pub fn key_track(&self, reference_note: f64, amount: impl Into<Param>) -> KeyTrack {
+        KeyTrack {
+            note: self.note.clone(),
+            reference_note,
+            amount: amount.into(),
+        }
+    }
+
+    /// Records a note-on.
+    pub(crate) fn note_on(&self, frequency: f64, velocity: f64) {
+        self.note.set(69.0 + 12.0 * (frequency / 440.0).log2());
+        self.velocity.set(velocity);
+        self.random.set(self.next_random());
+    }
+
+    /// Advances the shared generator (SplitMix64) and returns a value in [-1.0, 1.0].
+    fn next_random(&self) -> f64 {
+        let state = self
+            .rng_state
+            .fetch_add(0x9E37_79B9_7F4A_7C15, Ordering::Relaxed)
+            .wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let z = splitmix64_mix(state);
+        // 53 random bits mapped onto [0, 1], then to [-1, 1]
+        (z >> 11) as f64 / ((1u64 << 53) - 1) as f64 * 2.0 - 1.0
+    }
+}
+
+/// A key tracking multiplier created by [`NoteSources::key_track`].
+pub struct KeyTrack {
+    note: ParamHandle,
+    reference_note: f64,
+    amount: Param,
+}
+
+impl Signal for KeyTrack {
+    fn next_sample(&mut self) -> f64 {
+        let amount = self.amount.value();
+        let semitones = self.note.get() - self.reference_note;
+        (amount * semitones / 12.0).exp2()
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.amount.prepare(max_block_size, sample_rate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_note_on_updates_sources() {
+        let sources = NoteSources::new();
+        let mut note = sources.note();
+        let mut velocity = sources.velocity();
+        assert_eq!(note.next_sample(), 60.0);
+        assert_eq!(velocity.next_sample(), 0.0);
+
+        sources.note_on(440.0, 0.8);
+        assert!((note.next_sample() - 69.0).abs() < 1e-9);
+        assert_eq!(velocity.next_sample(), 0.8);
+
+        // Quarter-tone sharp of A4
+        sources.note_on(440.0 * 2f64.powf(0.5 / 12.0), 0.8);
+        assert!((note.next_sample() - 69.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_key_track_amounts() {
+        let sources = NoteSources::new();
+        let mut full = sources.key_track(60.0, 1.0);
+        let mut half = sources.key_track(60.0, 0.5);
+        let mut inverse = sources.key_track(60.0, -1.0);
+        assert_eq!(full.next_sample(), 1.0);
+
+        sources.note_on(crate::music::core::Note::midi_to_freq(72), 1.0);
+        assert!((full.next_sample() - 2.0).abs() < 1e-9);
+        assert!((half.next_sample() - 2f64.sqrt()).abs() < 1e-9);
+        assert!((inverse.next_sample() - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_random_held_per_note_and_seeded() {
+        let a = NoteSources::with_seed(42);
+        let b = NoteSources::with_seed(42);
+        let mut random = a.random();
+        assert_eq!(random.next_sample(), 0.0);
+
+        let mut values = Vec::new();
+        for _ in 0..100 {
+            a.note_on(440.0, 1.0);
+            b.note_on(440.0, 1.0);
+            let value = random.next_sample();
+            // Held until the next note
+            assert_eq!(random.next_sample(), value);
+            assert_eq!(b.random().get(), value);
+            assert!((-1.0..=1.0).contains(&value));
+            values.push(value);
+        }
+
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        assert!(mean.abs() < 0.25);
+        assert!(values.iter().any(|&v| v < -0.5) && values.iter().any(|&v| v > 0.5));
+    }
+}
@@ -4,7 +4,8 @@
 //! divided into discrete steps. This is the foundation for step sequencers, drum machines,
 //! and pattern-based composition.
 
-use super::core::NoteEvent;
+use super::core::{Chord, DegreeEvent, Key, NoteEvent};
+use std::ops::RangeBounds;
 
 /// A step-based musical pattern.
 ///
@@ -23,6 +24,21 @@ use super::core::NoteEvent;
 /// - One bar of 8th notes (Metronome with `steps_per_beat=2`)
 /// - Four bars of quarter notes (Metronome with `steps_per_beat=1`)
 ///
+/// # Scale Degrees
+///
+/// Besides fixed-pitch [`NoteEvent`]s, a pattern can hold [`DegreeEvent`]s,
+/// which give notes as degrees of a scale. They are resolved with the key set
+/// on whatever plays the pattern, so one pattern can be replayed in any key or
+/// mode. See [`add_degree`](Self::add_degree).
+///
+/// # Parameter Locks
+///
+/// Steps can also carry parameter locks: named values such as
+/// `"filter.cutoff" = 0.3` that apply only while that step plays, so an
+/// instrument can change its sound from step to step. The names are usually
+/// [`ParamRegistry`](crate::core::ParamRegistry) IDs. See
+/// [`set_lock`](Self::set_lock).
+///
 /// # Pattern Length
 ///
 /// The pattern has a fixed length in steps. When played by a sequencer, it will
@@ -57,9 +73,16 @@ pub struct Pattern {
     description: Option<String>,
     /// Length of the pattern in steps
     length: usize,
-    /// Events stored as (step_index, NoteEvent) tuples
+    /// Events stored as (step_index, NoteEvent, Trigger) tuples
     /// Invariant: step_index < length
-    events: Vec<(usize, NoteEvent)>,
+    events: Vec<(usize, NoteEvent, Trigger)>,
+    /// Scale-degree events, with the same invariant
+    degrees: Vec<(usize, DegreeEvent)>,
+    /// Whether degree events follow a song's chord track
+    follows_chords: bool,
+    /// Parameter locks as (step_index, parameter, value), at most one per
+    /// parameter and step
+    locks: Vec<(usize, String, f64)>,
 }
 
 impl Pattern {
@@ -94,6 +117,9 @@ impl Pattern {
             description: None,
             length,
             events: Vec::new(),
+            degrees: Vec::new(),
+            follows_chords: false,
+            locks: Vec::new(),
         }
     }
 
@@ -174,7 +200,8 @@ impl Pattern {
         self.length
     }
 
-    /// Returns the total number of events in the pattern.
+    /// Returns the total number of events in the pattern, including
+    /// scale-degree events.
     ///
     /// # Examples
     ///
@@ -189,7 +216,7 @@ impl Pattern {
     /// assert_eq!(pattern.event_count(), 1);
     /// ```
     pub fn event_count(&self) -> usize {
-        self.events.len()
+        self.events.len() + self.degrees.len()
     }
 
     /// Adds an event at the specified step.
@@ -222,10 +249,96 @@ impl Pattern {
             step,
             self.length
         );
-        self.events.push((step, event));
+        self.events.push((step, event, Trigger::default()));
+    }
+
+    /// Adds an event that only plays when `trigger` allows it.
+    ///
+    /// The [`Sequencer`](super::Sequencer) evaluates the trigger each time
+    /// the step comes round; other readers of the pattern, such as
+    /// [`events_at_step`](Self::events_at_step), see the event unconditionally.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` >= pattern length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::{Pattern, Trigger, TriggerCondition};
+    /// use earworm::NoteEvent;
+    ///
+    /// let hat = NoteEvent::from_midi(42, 90, Some(0.05));
+    /// let mut drums = Pattern::new(16);
+    ///
+    /// // Ghost hats that play half the time, and a crash on every 4th loop
+    /// drums.add_triggered_event(6, hat, Trigger::probability(0.5));
+    /// drums.add_triggered_event(
+    ///     0,
+    ///     NoteEvent::from_midi(49, 110, None),
+    ///     Trigger::when(TriggerCondition::Cycle { pass: 0, every: 4 }),
+    /// );
+    /// assert_eq!(drums.event_count(), 2);
+    /// ```
+    pub fn add_triggered_event(&mut self, step: usize, event: NoteEvent, trigger: Trigger) {
+        assert!(
+            step < self.length,
+            "Step index {} out of bounds (pattern length is {})",
+            step,
+            self.length
+        );
+        self.events.push((step, event, trigger));
+    }
+
+    /// Returns the events at the specified step with their triggers.
+    pub fn triggered_events_at_step(&self, step: usize) -> Vec<(&NoteEvent, &Trigger)> {
+        self.events
+            .iter()
+            .filter(|(s, ..)| *s == step)
+            .map(|(_, event, trigger)| (event, trigger))
+            .collect()
+    }
+
+    /// Adds every tone of a chord at the specified step.
+    ///
+    /// # Arguments
+    ///
+    /// * `step` - Step index (0-based, must be < pattern length)
+    /// * `chord` - The chord to add
+    /// * `octave` - Octave the chord is voiced from (see [`Chord::midi_notes`])
+    /// * `velocity` - Velocity for every chord tone
+    /// * `duration` - Optional duration in seconds for every chord tone
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` >= pattern length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::Pattern;
+    /// use earworm::music::core::{Chord, ChordQuality, Pitch};
+    ///
+    /// let mut pattern = Pattern::new(16);
+    /// pattern.add_chord(0, &Chord::new(Pitch::C, ChordQuality::Major), 4, 0.8, Some(1.0));
+    /// pattern.add_chord(8, &Chord::new(Pitch::A, ChordQuality::Minor), 3, 0.8, Some(1.0));
+    /// assert_eq!(pattern.event_count(), 6);
+    /// ```
+    pub fn add_chord(
+        &mut self,
+        step: usize,
+        chord: &Chord,
+        octave: i8,
+        velocity: f64,
+        duration: Option<f64>,
+    ) {
+        for event in chord.to_events(octave, velocity, duration) {
+            self.add_event(step, event);
+        }
     }
 
-    /// Removes all events at the specified step.
+    /// Removes all events at the specified step, including scale-degree events,
+    /// along with the step's parameter locks.
     ///
     /// # Arguments
     ///
@@ -233,7 +346,7 @@ impl Pattern {
     ///
     /// # Returns
     ///
-    /// The number of events removed.
+    /// The number of events removed (locks are not counted).
     ///
     /// # Examples
     ///
@@ -250,12 +363,14 @@ impl Pattern {
     /// assert_eq!(pattern.event_count(), 0);
     /// ```
     pub fn clear_step(&mut self, step: usize) -> usize {
-        let original_len = self.events.len();
-        self.events.retain(|(s, _)| *s != step);
-        original_len - self.events.len()
+        let original_len = self.event_count();
+        self.events.retain(|(s, ..)| *s != step);
+        self.degrees.retain(|(s, _)| *s != step);
+        self.locks.retain(|(s, ..)| *s != step);
+        original_len - self.event_count()
     }
 
-    /// Clears all events from the pattern.
+    /// Clears all events and parameter locks from the pattern.
     ///
     /// # Examples
     ///
@@ -272,6 +387,8 @@ impl Pattern {
     /// ```
     pub fn clear(&mut self) {
         self.events.clear();
+        self.degrees.clear();
+        self.locks.clear();
     }
 
     /// Returns all events at the specified step.
@@ -303,8 +420,8 @@ impl Pattern {
     pub fn events_at_step(&self, step: usize) -> Vec<&NoteEvent> {
         self.events
             .iter()
-            .filter(|(s, _)| *s == step)
-            .map(|(_, event)| event)
+            .filter(|(s, ..)| *s == step)
+            .map(|(_, event, _)| event)
             .collect()
     }
 
@@ -327,13 +444,13 @@ impl Pattern {
     /// }
     /// ```
     pub fn events(&self) -> impl Iterator<Item = (usize, &NoteEvent)> {
-        self.events.iter().map(|(step, event)| (*step, event))
+        self.events.iter().map(|(step, event, _)| (*step, event))
     }
 
     /// Changes the pattern length.
     ///
-    /// If the new length is shorter than the current length, events beyond the
-    /// new length are removed. If longer, no events are added.
+    /// If the new length is shorter than the current length, events and
+    /// parameter locks beyond the new length are removed. If longer, no events are added.
     ///
     /// # Arguments
     ///
@@ -361,7 +478,9 @@ impl Pattern {
     pub fn set_length(&mut self, new_length: usize) {
         assert!(new_length > 0, "Pattern length must be greater than 0");
         self.length = new_length;
-        self.events.retain(|(step, _)| *step < new_length);
+        self.events.retain(|(step, ..)| *step < new_length);
+        self.degrees.retain(|(step, _)| *step < new_length);
+        self.locks.retain(|(step, ..)| *step < new_length);
     }
 
     /// Returns true if the pattern has no events.
@@ -379,7 +498,473 @@ impl Pattern {
     /// assert!(!pattern.is_empty());
     /// ```
     pub fn is_empty(&self) -> bool {
-        self.events.is_empty()
+        self.events.is_empty() && self.degrees.is_empty()
+    }
+
+    /// Adds a scale-degree event at the specified step.
+    ///
+    /// The degree becomes a note when the pattern plays, in the key set on
+    /// the [`Sequencer`](super::Sequencer) or [`SongPlayer`](super::SongPlayer).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` >= pattern length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::core::{DegreeEvent, Key, Pitch, Scale};
+    /// use earworm::music::Pattern;
+    ///
+    /// // Root, third, fifth, octave
+    /// let mut arpeggio = Pattern::new(4);
+    /// for (step, degree) in [0, 2, 4, 7].into_iter().enumerate() {
+    ///     arpeggio.add_degree(step, DegreeEvent::new(degree, 4, 0.8, Some(0.1)));
+    /// }
+    ///
+    /// let minor = Key::new(Pitch::A, Scale::Minor);
+    /// let third = arpeggio.events_at_step_in_key(1, &minor);
+    /// assert_eq!(third[0].note.to_midi(), 72); // C5
+    /// ```
+    pub fn add_degree(&mut self, step: usize, event: DegreeEvent) {
+        assert!(
+            step < self.length,
+            "Step index {} out of bounds (pattern length is {})",
+            step,
+            self.length
+        );
+        self.degrees.push((step, event));
+    }
+
+    /// Returns the scale-degree events at the specified step.
+    pub fn degree_events_at_step(&self, step: usize) -> Vec<&DegreeEvent> {
+        self.degrees
+            .iter()
+            .filter(|(s, _)| *s == step)
+            .map(|(_, event)| event)
+            .collect()
+    }
+
+    /// Locks `param` to `value` while `step` plays, replacing any earlier lock
+    /// of the same parameter on that step.
+    ///
+    /// Locks are independent of notes: a step can lock parameters without
+    /// playing anything, to change the tail of a note from an earlier step.
+    ///
+    /// # Arguments
+    ///
+    /// * `step` - Step index (0-based, must be < pattern length)
+    /// * `param` - Parameter name, usually a [`ParamRegistry`](crate::core::ParamRegistry) ID
+    /// * `value` - Value the parameter takes on this step
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` >= pattern length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::Pattern;
+    /// use earworm::NoteEvent;
+    ///
+    /// let mut pattern = Pattern::new(16);
+    /// pattern.add_event(4, NoteEvent::from_midi(36, 100, Some(0.1)));
+    /// pattern.set_lock(4, "filter.cutoff", 0.3);
+    /// pattern.set_lock(4, "amp.decay", 0.1);
+    ///
+    /// assert_eq!(pattern.locks_at_step(4), [("filter.cutoff", 0.3), ("amp.decay", 0.1)]);
+    /// assert!(pattern.locks_at_step(5).is_empty());
+    /// ```
+    pub fn set_lock(&mut self, step: usize, param: impl Into<String>, value: f64) {
+        assert!(
+            step < self.length,
+            "Step index {} out of bounds (pattern length is {})",
+            step,
+            self.length
+        );
+        let param = param.into();
+        match self
+            .locks
+            .iter_mut()
+            .find(|(s, p, _)| *s == step && *p == param)
+        {
+            Some(lock) => lock.2 = value,
+            None => self.locks.push((step, param, value)),
+        }
+    }
+
+    /// Removes the lock of `param` on `step`, returning its value.
+    pub fn remove_lock(&mut self, step: usize, param: &str) -> Option<f64> {
+        let index = self
+            .locks
+            .iter()
+            .position(|(s, p, _)| *s == step && p == param)?;
+        Some(self.locks.remove(index).2)
+    }
+
+    /// Returns the parameter locks at the specified step as (parameter,
+    /// value) pairs, in the order they were first set.
+    pub fn locks_at_step(&self, step: usize) -> Vec<(&str, f64)> {
+        self.locks
+            .iter()
+            .filter(|(s, ..)| *s == step)
+            .map(|(_, param, value)| (param.as_str(), *value))
+            .collect()
+    }
+
+    /// Returns an iterator over all (step, parameter, value) locks in the
+    /// pattern.
+    pub fn locks(&self) -> impl Iterator<Item = (usize, &str, f64)> {
+        self.locks
+            .iter()
+            .map(|(step, param, value)| (*step, param.as_str(), *value))
+    }
+
+    /// Returns an iterator over all (step, degree event) pairs in the pattern,
+    /// in the order they were added.
+    pub fn degree_events(&self) -> impl Iterator<Item = (usize, &DegreeEvent)> {
+        self.degrees.iter().map(|(step, event)| (*step, event))
+    }
+
+    /// Returns every event at the specified step as a [`NoteEvent`], with
+    /// scale-degree events resolved in `key`.
+    ///
+    /// Fixed-pitch events come first, then degree events, each in the order
+    /// they were added.
+    pub fn events_at_step_in_key(&self, step: usize, key: &Key) -> Vec<NoteEvent> {
+        let notes = self.events_at_step(step).into_iter().copied();
+        let degrees = self
+            .degree_events_at_step(step)
+            .into_iter()
+            .map(|event| event.resolve(key));
+        notes.chain(degrees).collect()
+    }
+
+    /// Returns every event at the specified step as a [`NoteEvent`], with
+    /// scale-degree events read as tones of `chord` (see
+    /// [`DegreeEvent::resolve_on_chord`]).
+    ///
+    /// Fixed-pitch events are unaffected.
+    pub fn events_at_step_on_chord(&self, step: usize, chord: &Chord) -> Vec<NoteEvent> {
+        let notes = self.events_at_step(step).into_iter().copied();
+        let degrees = self
+            .degree_events_at_step(step)
+            .into_iter()
+            .map(|event| event.resolve_on_chord(chord));
+        notes.chain(degrees).collect()
+    }
+
+    /// Marks the pattern as a harmonic follower.
+    ///
+    /// When a [`SongPlayer`](super::SongPlayer) plays a follower in a section
+    /// with a [`ChordTrack`](super::ChordTrack), its scale-degree events are
+    /// read as tones of the current chord instead of degrees of the key:
+    /// degree 0 is the chord root, 1 the next chord tone, and so on. Steps
+    /// before the first chord change still resolve in the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::Pattern;
+    ///
+    /// let mut bass = Pattern::new(16);
+    /// assert!(!bass.follows_chords());
+    /// bass.set_follows_chords(true);
+    /// assert!(bass.follows_chords());
+    /// ```
+    pub fn set_follows_chords(&mut self, follows: bool) {
+        self.follows_chords = follows;
+    }
+
+    /// Returns `true` if the pattern's degree events follow a chord track.
+    pub fn follows_chords(&self) -> bool {
+        self.follows_chords
+    }
+
+    /// Returns a copy of the pattern with every scale-degree event resolved
+    /// in `key` to a fixed-pitch event.
+    ///
+    /// Useful for code that only reads [`events`](Self::events), such as
+    /// [`bounce_pattern`](super::bounce_pattern).
+    pub fn resolved(&self, key: &Key) -> Pattern {
+        let mut pattern = self.clone();
+        for (step, event) in pattern.degrees.drain(..) {
+            pattern
+                .events
+                .push((step, event.resolve(key), Trigger::default()));
+        }
+        pattern
+    }
+
+    /// Returns the events whose step falls in `steps`, ordered by step.
+    ///
+    /// Events at the same step keep the order they were added in.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{NoteEvent, Pitch};
+    /// use earworm::music::Pattern;
+    ///
+    /// let mut pattern = Pattern::new(16);
+    /// pattern.add_event(12, NoteEvent::from_pitch(Pitch::G, 4, 0.8, None));
+    /// pattern.add_event(2, NoteEvent::from_pitch(Pitch::C, 4, 0.8, None));
+    /// pattern.add_event(5, NoteEvent::from_pitch(Pitch::E, 4, 0.8, None));
+    ///
+    /// let steps: Vec<usize> = pattern.events_in_steps(0..8).iter().map(|(s, _)| *s).collect();
+    /// assert_eq!(steps, vec![2, 5]);
+    /// ```
+    pub fn events_in_steps(&self, steps: impl RangeBounds<usize>) -> Vec<(usize, &NoteEvent)> {
+        self.sorted_events(|step, _| steps.contains(&step))
+    }
+
+    /// Returns the events whose MIDI note falls in `notes`, ordered by step.
+    ///
+    /// Events are matched by [`Note::to_midi`](super::core::Note::to_midi),
+    /// so slightly detuned notes count as their nearest MIDI note.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{NoteEvent, Pitch};
+    /// use earworm::music::Pattern;
+    ///
+    /// let mut pattern = Pattern::new(16);
+    /// pattern.add_event(0, NoteEvent::from_pitch(Pitch::C, 2, 0.8, None)); // MIDI 36
+    /// pattern.add_event(4, NoteEvent::from_pitch(Pitch::C, 4, 0.8, None)); // MIDI 60
+    ///
+    /// // Just the bass line
+    /// assert_eq!(pattern.events_in_notes(..48).len(), 1);
+    /// ```
+    pub fn events_in_notes(&self, notes: impl RangeBounds<u8>) -> Vec<(usize, &NoteEvent)> {
+        self.sorted_events(|_, event| notes.contains(&event.note.to_midi()))
+    }
+
+    /// Returns the event closest to `step`, with its step.
+    ///
+    /// When events are equally far before and after `step`, the earlier one
+    /// wins; among events at the same step, the first added wins. Distance
+    /// does not wrap around the end of the pattern.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{NoteEvent, Pitch};
+    /// use earworm::music::Pattern;
+    ///
+    /// let mut pattern = Pattern::new(16);
+    /// pattern.add_event(4, NoteEvent::from_pitch(Pitch::C, 4, 0.8, None));
+    /// pattern.add_event(10, NoteEvent::from_pitch(Pitch::E, 4, 0.8, None));
+    ///
+    /// assert_eq!(pattern.nearest_event(8).map(|(step, _)| step), Some(10));
+    /// assert_eq!(pattern.nearest_event(7).map(|(step, _)| step), Some(4));
+    /// ```
+    pub fn nearest_event(&self, step: usize) -> Option<(usize, &NoteEvent)> {
+        self.events().min_by_key(|&(s, _)| (s.abs_diff(step), s))
+    }
+
+    /// Summarizes one step: its notes and, if they form one, its chord.
+    ///
+    /// Returns `None` for an empty step.
+    pub fn step_summary(&self, step: usize) -> Option<StepSummary> {
+        StepSummary::from_events(step, self.events_at_step(step))
+    }
+
+    /// Summarizes every step that has events, in step order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::Pattern;
+    /// use earworm::music::core::{Chord, ChordQuality, Pitch};
+    ///
+    /// let mut pattern = Pattern::new(16);
+    /// pattern.add_chord(0, &Chord::new(Pitch::C, ChordQuality::Major), 4, 0.8, None);
+    /// pattern.add_chord(8, &Chord::new(Pitch::A, ChordQuality::Minor7), 3, 0.6, None);
+    ///
+    /// let chords: Vec<_> = pattern
+    ///     .step_summaries()
+    ///     .into_iter()
+    ///     .map(|summary| (summary.step, summary.chord.map(|c| c.root())))
+    ///     .collect();
+    /// assert_eq!(chords, vec![(0, Some(Pitch::C)), (8, Some(Pitch::A))]);
+    /// ```
+    pub fn step_summaries(&self) -> Vec<StepSummary> {
+        let mut steps: Vec<usize> = self.events.iter().map(|(step, ..)| *step).collect();
+        steps.sort_unstable();
+        steps.dedup();
+        steps
+            .into_iter()
+            .filter_map(|step| self.step_summary(step))
+            .collect()
+    }
+
+    fn sorted_events(&self, keep: impl Fn(usize, &NoteEvent) -> bool) -> Vec<(usize, &NoteEvent)> {
+        let mut events: Vec<(usize, &NoteEvent)> = self
+            .events()
+            .filter(|&(step, event)| keep(step, event))
+            .collect();
+        // Stable, so same-step events stay in insertion order
+        events.sort_by_key(|(step, _)| *step);
+        events
+    }
+
+    /// Inserts an event at `index` in the event list (for edit history, which
+    /// needs to restore events in their original order).
+    pub(crate) fn insert_event_at(
+        &mut self,
+        index: usize,
+        step: usize,
+        event: NoteEvent,
+        trigger: Trigger,
+    ) {
+        debug_assert!(step < self.length);
+        self.events.insert(index, (step, event, trigger));
+    }
+
+    /// Removes and returns the event at `index` in the event list.
+    pub(crate) fn remove_event_at(&mut self, index: usize) -> (usize, NoteEvent, Trigger) {
+        self.events.remove(index)
+    }
+
+    /// Inserts a degree event at `index` in the degree event list.
+    pub(crate) fn insert_degree_at(&mut self, index: usize, step: usize, event: DegreeEvent) {
+        debug_assert!(step < self.length);
+        self.degrees.insert(index, (step, event));
+    }
+
+    /// Removes and returns the degree event at `index` in the degree event list.
+    pub(crate) fn remove_degree_at(&mut self, index: usize) -> (usize, DegreeEvent) {
+        self.degrees.remove(index)
+    }
+}
+
+/// A condition on which loops of the pattern an event plays in.
+///
+/// Loops are counted by the [`Sequencer`](super::Sequencer) from 0, starting
+/// again when it is reset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TriggerCondition {
+    /// Every loop
+    #[default]
+    Always,
+    /// Loop `pass` of every `every` loops (so `{ pass: 3, every: 4 }` is the
+    /// last loop of each group of four)
+    Cycle {
+        /// Which loop of the cycle plays, from 0
+        pass: u32,
+        /// Cycle length in loops (treated as 1 if 0)
+        every: u32,
+    },
+    /// Only while a fill is on (see [`Sequencer::set_fill`](super::Sequencer::set_fill))
+    Fill,
+    /// Only while no fill is on
+    NotFill,
+    /// Only on the first loop
+    First,
+    /// On every loop except the first
+    NotFirst,
+}
+
+impl TriggerCondition {
+    /// Returns `true` if the condition holds on loop `pass`.
+    pub fn is_met(&self, pass: u64, fill: bool) -> bool {
+        match *self {
+            TriggerCondition::Always => true,
+            TriggerCondition::Cycle { pass: at, every } => pass % every.max(1) as u64 == at as u64,
+            TriggerCondition::Fill => fill,
+            TriggerCondition::NotFill => !fill,
+            TriggerCondition::First => pass == 0,
+            TriggerCondition::NotFirst => pass != 0,
+        }
+    }
+}
+
+/// When a pattern event plays: a condition, then a chance.
+///
+/// Events added with [`Pattern::add_event`] always play. Use
+/// [`Pattern::add_triggered_event`] for probabilistic or conditional events.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trigger {
+    /// Chance of playing when the condition holds (0.0-1.0)
+    pub probability: f64,
+    /// Which loops the event can play in
+    pub condition: TriggerCondition,
+}
+
+impl Default for Trigger {
+    fn default() -> Self {
+        Self {
+            probability: 1.0,
+            condition: TriggerCondition::Always,
+        }
+    }
+}
+
+impl Trigger {
+    /// A trigger that plays with the given chance on every loop.
+    pub fn probability(probability: f64) -> Self {
+        Self {
+            probability: probability.clamp(0.0, 1.0),
+            ..Self::default()
+        }
+    }
+
+    /// A trigger that always plays when `condition` holds.
+    pub fn when(condition: TriggerCondition) -> Self {
+        Self {
+            condition,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the chance of playing when the condition holds (builder style).
+    pub fn with_probability(mut self, probability: f64) -> Self {
+        self.probability = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Returns `true` if the event plays on loop `pass`, given `roll`, a
+    /// uniform random number in [0, 1).
+    pub fn fires(&self, pass: u64, fill: bool, roll: f64) -> bool {
+        self.condition.is_met(pass, fill) && roll < self.probability
+    }
+}
+
+/// The notes sounding at one step of a [`Pattern`], from
+/// [`Pattern::step_summary`] and [`Pattern::step_summaries`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepSummary {
+    /// Step index
+    pub step: usize,
+    /// Number of events at the step
+    pub event_count: usize,
+    /// Distinct MIDI notes at the step, lowest first
+    pub midi_notes: Vec<u8>,
+    /// Highest velocity at the step
+    pub max_velocity: f64,
+    /// The chord the notes form, if any (see [`Chord::detect`])
+    pub chord: Option<Chord>,
+}
+
+impl StepSummary {
+    fn from_events(step: usize, events: Vec<&NoteEvent>) -> Option<Self> {
+        if events.is_empty() {
+            return None;
+        }
+        let mut midi_notes: Vec<u8> = events.iter().map(|e| e.note.to_midi()).collect();
+        midi_notes.sort_unstable();
+        midi_notes.dedup();
+        Some(Self {
+            step,
+            event_count: events.len(),
+            chord: Chord::detect(&midi_notes),
+            midi_notes,
+            max_velocity: events
+                .iter()
+                .map(|e| e.velocity)
+                .fold(f64::NEG_INFINITY, f64::max),
+        })
     }
 }
 
@@ -569,4 +1154,142 @@ mod tests {
         assert_eq!(pattern.events_at_step(0).len(), 2); // Kick + hihat
         assert_eq!(pattern.events_at_step(4).len(), 2); // Snare + hihat
     }
+
+    #[test]
+    fn test_add_chord() {
+        use crate::music::core::ChordQuality;
+
+        let mut pattern = Pattern::new(8);
+        let chord = Chord::new(Pitch::F, ChordQuality::Major).with_inversion(1);
+        pattern.add_chord(2, &chord, 4, 0.6, None);
+
+        let midi: Vec<u8> = pattern
+            .events_at_step(2)
+            .iter()
+            .map(|e| e.note.to_midi())
+            .collect();
+        assert_eq!(midi, vec![69, 72, 77]);
+        assert_eq!(pattern.event_count(), 3);
+    }
+
+    #[test]
+    fn test_range_and_nearest_queries() {
+        let mut pattern = Pattern::new(16);
+        let low = NoteEvent::from_midi(36, 100, None);
+        let mid = NoteEvent::from_midi(60, 100, None);
+        let high = NoteEvent::from_midi(84, 100, None);
+        pattern.add_event(9, high);
+        pattern.add_event(3, mid);
+        pattern.add_event(9, low);
+        pattern.add_event(15, mid);
+
+        let steps = |events: Vec<(usize, &NoteEvent)>| -> Vec<(usize, u8)> {
+            events
+                .into_iter()
+                .map(|(s, e)| (s, e.note.to_midi()))
+                .collect()
+        };
+        assert_eq!(
+            steps(pattern.events_in_steps(3..=9)),
+            vec![(3, 60), (9, 84), (9, 36)]
+        );
+        assert_eq!(steps(pattern.events_in_steps(10..15)), vec![]);
+        assert_eq!(
+            steps(pattern.events_in_notes(48..=72)),
+            vec![(3, 60), (15, 60)]
+        );
+
+        assert_eq!(pattern.nearest_event(6).map(|(s, _)| s), Some(3));
+        // Equidistant from 3 and 9: the earlier step wins
+        assert_eq!(pattern.nearest_event(6).unwrap().1, &mid);
+        assert_eq!(pattern.nearest_event(9).unwrap().1, &high);
+        assert_eq!(pattern.nearest_event(13).map(|(s, _)| s), Some(15));
+        assert!(Pattern::new(4).nearest_event(0).is_none());
+    }
+
+    #[test]
+    fn test_step_summaries() {
+        use crate::music::core::ChordQuality;
+
+        let mut pattern = Pattern::new(16);
+        let g7 = Chord::new(Pitch::G, ChordQuality::Dominant7).with_inversion(2);
+        pattern.add_chord(4, &g7, 3, 0.5, None);
+        pattern.add_event(4, NoteEvent::from_pitch(Pitch::G, 2, 0.9, None));
+        pattern.add_event(0, NoteEvent::from_pitch(Pitch::C, 3, 0.7, None));
+        pattern.add_event(0, NoteEvent::from_pitch(Pitch::C, 3, 0.4, None));
+
+        let summaries = pattern.step_summaries();
+        assert_eq!(summaries.len(), 2);
+
+        let single = &summaries[0];
+        assert_eq!((single.step, single.event_count), (0, 2));
+        assert_eq!(single.midi_notes, vec![48]);
+        assert_eq!(single.max_velocity, 0.7);
+        assert_eq!(single.chord, None);
+
+        // The added low G moves the bass, making it a root-position G7
+        let chord = summaries[1].chord.as_ref().unwrap();
+        assert_eq!(chord, &Chord::new(Pitch::G, ChordQuality::Dominant7));
+        assert_eq!(summaries[1].max_velocity, 0.9);
+        assert_eq!(pattern.step_summary(4).as_ref(), Some(&summaries[1]));
+        assert_eq!(pattern.step_summary(1), None);
+    }
+
+    #[test]
+    fn test_degree_events() {
+        use crate::music::core::{DegreeEvent, Key, Scale};
+
+        let mut pattern = Pattern::new(8);
+        pattern.add_event(0, NoteEvent::from_pitch(Pitch::C, 2, 0.9, None));
+        pattern.add_degree(0, DegreeEvent::new(2, 4, 0.8, None));
+        pattern.add_degree(5, DegreeEvent::new(-1, 4, 0.6, None));
+        assert_eq!(pattern.event_count(), 3);
+        assert_eq!(pattern.events().count(), 1);
+        assert_eq!(pattern.degree_events_at_step(0).len(), 1);
+
+        // Fixed notes ignore the key, degrees follow it
+        let c_major = Key::default();
+        let d_minor = Key::new(Pitch::D, Scale::Minor);
+        let midi = |step, key: &Key| -> Vec<u8> {
+            pattern
+                .events_at_step_in_key(step, key)
+                .iter()
+                .map(|e| e.note.to_midi())
+                .collect()
+        };
+        assert_eq!(midi(0, &c_major), vec![36, 64]);
+        assert_eq!(midi(0, &d_minor), vec![36, 65]);
+        assert_eq!(midi(5, &c_major), vec![59]);
+        assert_eq!(midi(5, &d_minor), vec![60]);
+
+        let resolved = pattern.resolved(&d_minor);
+        assert_eq!(resolved.degree_events().count(), 0);
+        assert_eq!(resolved.event_count(), 3);
+        assert_eq!(resolved.events_at_step(5)[0].note.to_midi(), 60);
+
+        pattern.set_length(4);
+        assert_eq!(pattern.event_count(), 2);
+        assert_eq!(pattern.clear_step(0), 2);
+        assert!(pattern.is_empty());
+    }
+
+    #[test]
+    fn test_parameter_locks() {
+        let mut pattern = Pattern::new(8);
+        pattern.set_lock(2, "cutoff", 0.3);
+        pattern.set_lock(2, "decay", 0.1);
+        pattern.set_lock(2, "cutoff", 0.5);
+        pattern.set_lock(6, "cutoff", 0.9);
+        assert_eq!(pattern.locks_at_step(2), [("cutoff", 0.5), ("decay", 0.1)]);
+        // Locks are not events
+        assert!(pattern.is_empty());
+
+        assert_eq!(pattern.remove_lock(2, "decay"), Some(0.1));
+        assert_eq!(pattern.remove_lock(2, "decay"), None);
+
+        pattern.set_length(4);
+        assert_eq!(pattern.locks().collect::<Vec<_>>(), [(2, "cutoff", 0.5)]);
+        pattern.clear_step(2);
+        assert_eq!(pattern.locks().count(), 0);
+    }
 }
@@ -0,0 +1,659 @@
+//! Undoable editing of patterns.
+//!
+//! A [`PatternEditor`] owns a [`Pattern`] and changes it only through
+//! [`PatternEdit`] commands, recording how to reverse each one. That gives
+//! step-sequencer and piano-roll editors undo and redo without keeping their
+//! own history. Undo restores the pattern exactly, including the order of
+//! events within a step.
+
+use super::core::{DegreeEvent, NoteEvent};
+use super::pattern::{Pattern, Trigger};
+use std::fmt;
+
+/// A reversible change to a [`Pattern`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatternEdit {
+    /// Adds `event` at `step`
+    Add {
+        /// Step to add the event at
+        step: usize,
+        /// Event to add
+        event: NoteEvent,
+    },
+    /// Removes the first event at `step` equal to `event`
+    Remove {
+        /// Step holding the event
+        step: usize,
+        /// Event to remove
+        event: NoteEvent,
+    },
+    /// Moves the first event at `from` equal to `event` to step `to`,
+    /// keeping its [`Trigger`]
+    Move {
+        /// Step holding the event
+        from: usize,
+        /// Step to move it to
+        to: usize,
+        /// Event to move
+        event: NoteEvent,
+    },
+    /// Replaces the first event at `step` equal to `old` with `new` (for
+    /// velocity, duration or pitch changes), keeping its [`Trigger`]
+    Replace {
+        /// Step holding the event
+        step: usize,
+        /// Event to replace
+        old: NoteEvent,
+        /// Replacement event
+        new: NoteEvent,
+    },
+    /// Adds a scale-degree event at `step`
+    AddDegree {
+        /// Step to add the event at
+        step: usize,
+        /// Event to add
+        event: DegreeEvent,
+    },
+    /// Removes the first scale-degree event at `step` equal to `event`
+    RemoveDegree {
+        /// Step holding the event
+        step: usize,
+        /// Event to remove
+        event: DegreeEvent,
+    },
+    /// Removes every event at `step`, including scale-degree events
+    ClearStep {
+        /// Step to clear
+        step: usize,
+    },
+    /// Changes the pattern length, removing events beyond the new end
+    SetLength {
+        /// New length in steps
+        length: usize,
+    },
+}
+
+/// Why a [`PatternEdit`] could not be applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternEditError {
+    /// The step is outside the pattern
+    StepOutOfRange {
+        /// Requested step
+        step: usize,
+        /// Pattern length
+        length: usize,
+    },
+    /// No matching event exists at the step
+    EventNotFound {
+        /// Step that was searched
+        step: usize,
+    },
+    /// Patterns must have at least one step
+    ZeroLength,
+}
+
+impl fmt::Display for PatternEditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatternEditError::StepOutOfRange { step, length } => write!(
+                f,
+                "step {} out of bounds (pattern length is {})",
+                step, length
+            ),
+            PatternEditError::EventNotFound { step } => {
+                write!(f, "no matching event at step {}", step)
+            }
+            PatternEditError::ZeroLength => write!(f, "pattern length must be greater than 0"),
+        }
+    }
+}
+
+impl std::error::Error for PatternEditError {}
+
+/// Primitive changes that edits are made of. Each one has an exact inverse.
+#[derive(Debug, Clone)]
+enum Op {
+    Insert {
+        index: usize,
+        step: usize,
+        event: NoteEvent,
+        trigger: Trigger,
+    },
+    Remove {
+        index: usize,
+    },
+    InsertDegree {
+        index: usize,
+        step: usize,
+        event: DegreeEvent,
+    },
+    RemoveDegree {
+        index: usize,
+    },
+    SetLength(usize),
+}
+
+impl Op {
+    /// Applies the op and returns its inverse.
+    fn apply(self, pattern: &mut Pattern) -> Op {
+        match self {
+            Op::Insert {
+                index,
+                step,
+                event,
+                trigger,
+            } => {
+                pattern.insert_event_at(index, step, event, trigger);
+                Op::Remove { index }
+            }
+            Op::Remove { index } => {
+                let (step, event, trigger) = pattern.remove_event_at(index);
+                Op::Insert {
+                    index,
+                    step,
+                    event,
+                    trigger,
+                }
+            }
+            Op::InsertDegree { index, step, event } => {
+                pattern.insert_degree_at(index, step, event);
+                Op::RemoveDegree { index }
+            }
+            Op::RemoveDegree { index } => {
+                let (step, event) = pattern.remove_degree_at(index);
+                Op::InsertDegree { index, step, event }
+            }
+            Op::SetLength(length) => {
+                let previous = pattern.length();
+                pattern.set_length(length);
+                Op::SetLength(previous)
+            }
+        }
+    }
+}
+
+/// A [`Pattern`] with undo and redo.
+///
+/// Edits can be applied one at a time with [`apply`](Self::apply), or as a
+/// group that undoes in one step with [`apply_group`](Self::apply_group).
+/// Applying a new edit clears the redo history.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::music::{Pattern, PatternEdit, PatternEditor};
+/// use earworm::{NoteEvent, Pitch};
+///
+/// let kick = NoteEvent::from_pitch(Pitch::C, 2, 0.9, Some(0.1));
+/// let mut editor = PatternEditor::new(Pattern::new(16));
+///
+/// editor.apply(PatternEdit::Add { step: 0, event: kick })?;
+/// editor.apply(PatternEdit::Move { from: 0, to: 4, event: kick })?;
+/// assert_eq!(editor.pattern().events_at_step(4).len(), 1);
+///
+/// editor.undo();
+/// assert_eq!(editor.pattern().events_at_step(0).len(), 1);
+/// editor.redo();
+/// assert_eq!(editor.pattern().events_at_step(4).len(), 1);
+/// # Ok::<(), earworm::music::PatternEditError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct PatternEditor {
+    pattern: Pattern,
+    // Each entry holds one undo step's edits and the inverse ops that undo it
+    undo: Vec<(Vec<PatternEdit>, Vec<Op>)>,
+    redo: Vec<Vec<PatternEdit>>,
+}
+
+impl PatternEditor {
+    /// Starts editing `pattern` with empty history.
+    pub fn new(pattern: Pattern) -> Self {
+        Self {
+            pattern,
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+
+    /// Returns the pattern in its current state.
+    pub fn pattern(&self) -> &Pattern {
+        &self.pattern
+    }
+
+    /// Stops editing and returns the pattern.
+    pub fn into_pattern(self) -> Pattern {
+        self.pattern
+    }
+
+    /// Applies one edit as its own undo step.
+    ///
+    /// On error the pattern is unchanged.
+    pub fn apply(&mut self, edit: PatternEdit) -> Result<(), PatternEditError> {
+        self.apply_group([edit])
+    }
+
+    /// Applies several edits as a single undo step.
+    ///
+    /// Edits apply in order, each seeing the result of the previous ones. If
+    /// any edit fails, the ones before it are rolled back and the pattern is
+    /// unchanged. An empty group does nothing and adds no history.
+    pub fn apply_group(
+        &mut self,
+        edits: impl IntoIterator<Item = PatternEdit>,
+    ) -> Result<(), PatternEditError> {
+        let edits: Vec<PatternEdit> = edits.into_iter().collect();
+        if edits.is_empty() {
+            return Ok(());
+        }
+        let inverse = self.perform(&edits)?;
+        self.undo.push((edits, inverse));
+        self.redo.clear();
+        Ok(())
+    }
+
+    /// Reverts the most recent undo step. Returns `false` if there is nothing
+    /// to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some((edits, inverse)) = self.undo.pop() else {
+            return false;
+        };
+        self.revert(inverse);
+        self.redo.push(edits);
+        true
+    }
+
+    /// Reapplies the most recently undone step. Returns `false` if there is
+    /// nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(edits) = self.redo.pop() else {
+            return false;
+        };
+        // Redo replays edits on the exact state they were first applied to,
+        // so they cannot fail
+        let inverse = self
+            .perform(&edits)
+            .expect("redo applies to the state the edits were made on");
+        self.undo.push((edits, inverse));
+        true
+    }
+
+    /// Returns `true` if [`undo`](Self::undo) would change the pattern.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Returns `true` if [`redo`](Self::redo) would change the pattern.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Forgets all undo and redo history, keeping the pattern as it is.
+    pub fn clear_history(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Applies `edits`, returning the inverse ops in application order. On
+    /// error, rolls back and leaves the pattern unchanged.
+    fn perform(&mut self, edits: &[PatternEdit]) -> Result<Vec<Op>, PatternEditError> {
+        let mut inverse = Vec::new();
+        for edit in edits {
+            if let Err(error) = self.perform_one(edit, &mut inverse) {
+                self.revert(inverse);
+                return Err(error);
+            }
+        }
+        Ok(inverse)
+    }
+
+    fn perform_one(
+        &mut self,
+        edit: &PatternEdit,
+        inverse: &mut Vec<Op>,
+    ) -> Result<(), PatternEditError> {
+        match *edit {
+            PatternEdit::Add { step, event } => {
+                self.check_step(step)?;
+                let index = self.pattern.events().count();
+                let trigger = Trigger::default();
+                self.run(
+                    Op::Insert {
+                        index,
+                        step,
+                        event,
+                        trigger,
+                    },
+                    inverse,
+                );
+            }
+            PatternEdit::Remove { step, event } => {
+                let index = self.find(step, &event)?;
+                self.run(Op::Remove { index }, inverse);
+            }
+            PatternEdit::Move { from, to, event } => {
+                self.check_step(to)?;
+                let index = self.find(from, &event)?;
+                let trigger = self.remove(index, inverse);
+                self.run(
+                    Op::Insert {
+                        index,
+                        step: to,
+                        event,
+                        trigger,
+                    },
+                    inverse,
+                );
+            }
+            PatternEdit::Replace { step, old, new } => {
+                let index = self.find(step, &old)?;
+                let trigger = self.remove(index, inverse);
+                self.run(
+                    Op::Insert {
+                        index,
+                        step,
+                        event: new,
+                        trigger,
+                    },
+                    inverse,
+                );
+            }
+            PatternEdit::AddDegree { step, event } => {
+                self.check_step(step)?;
+                let index = self.pattern.degree_events().count();
+                self.run(Op::InsertDegree { index, step, event }, inverse);
+            }
+            PatternEdit::RemoveDegree { step, event } => {
+                let index = self
+                    .pattern
+                    .degree_events()
+                    .position(|(s, e)| s == step && *e == event)
+                    .ok_or(PatternEditError::EventNotFound { step })?;
+                self.run(Op::RemoveDegree { index }, inverse);
+            }
+            PatternEdit::ClearStep { step } => {
+                self.check_step(step)?;
+                self.remove_where(|s| s == step, inverse);
+            }
+            PatternEdit::SetLength { length } => {
+                if length == 0 {
+                    return Err(PatternEditError::ZeroLength);
+                }
+                // Remove out-of-range events explicitly so undo restores them
+                self.remove_where(|s| s >= length, inverse);
+                self.run(Op::SetLength(length), inverse);
+            }
+        }
+        Ok(())
+    }
+
+    fn run(&mut self, op: Op, inverse: &mut Vec<Op>) {
+        inverse.push(op.apply(&mut self.pattern));
+    }
+
+    /// Removes the event at `index` and returns its trigger, so edits that
+    /// put it back keep it.
+    fn remove(&mut self, index: usize, inverse: &mut Vec<Op>) -> Trigger {
+        self.run(Op::Remove { index }, inverse);
+        match inverse.last() {
+            Some(Op::Insert { trigger, .. }) => *trigger,
+            _ => unreachable!("removing an event records its insertion"),
+        }
+    }
+
+    /// Undoes inverse ops collected by `perform`, last first.
+    fn revert(&mut self, inverse: Vec<Op>) {
+        for op in inverse.into_iter().rev() {
+            op.apply(&mut self.pattern);
+        }
+    }
+
+    /// Removes every event whose step matches, last index first so earlier
+    /// indices stay valid.
+    fn remove_where(&mut self, matches: impl Fn(usize) -> bool, inverse: &mut Vec<Op>) {
+        let indices: Vec<usize> = self
+            .pattern
+            .events()
+            .enumerate()
+            .filter(|(_, (step, _))| matches(*step))
+            .map(|(index, _)| index)
+            .collect();
+        for index in indices.into_iter().rev() {
+            self.run(Op::Remove { index }, inverse);
+        }
+
+        let indices: Vec<usize> = self
+            .pattern
+            .degree_events()
+            .enumerate()
+            .filter(|(_, (step, _))| matches(*step))
+            .map(|(index, _)| index)
+            .collect();
+        for index in indices.into_iter().rev() {
+            self.run(Op::RemoveDegree { index }, inverse);
+        }
+    }
+
+    fn check_step(&self, step: usize) -> Result<(), PatternEditError> {
+        let length = self.pattern.length();
+        if step < length {
+            Ok(())
+        } else {
+            Err(PatternEditError::StepOutOfRange { step, length })
+        }
+    }
+
+    fn find(&self, step: usize, event: &NoteEvent) -> Result<usize, PatternEditError> {
+        self.pattern
+            .events()
+            .position(|(s, e)| s == step && e == event)
+            .ok_or(PatternEditError::EventNotFound { step })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::music::core::Pitch;
+
+    fn note(pitch: Pitch, velocity: f64) -> NoteEvent {
+        NoteEvent::from_pitch(pitch, 4, velocity, Some(0.25))
+    }
+
+    /// The pattern's events and degree events in list order.
+    #[allow(clippy::type_complexity)]
+    fn snapshot(
+        editor: &PatternEditor,
+    ) -> (usize, Vec<(usize, NoteEvent)>, Vec<(usize, DegreeEvent)>) {
+        let pattern = editor.pattern();
+        (
+            pattern.length(),
+            pattern.events().map(|(s, e)| (s, *e)).collect(),
+            pattern.degree_events().map(|(s, e)| (s, *e)).collect(),
+        )
+    }
+
+    fn seeded() -> PatternEditor {
+        let mut pattern = Pattern::new(8);
+        pattern.add_event(0, note(Pitch::C, 0.8));
+        pattern.add_event(2, note(Pitch::E, 0.8));
+        pattern.add_event(0, note(Pitch::G, 0.8));
+        pattern.add_event(6, note(Pitch::B, 0.8));
+        pattern.add_degree(0, DegreeEvent::new(2, 4, 0.8, None));
+        pattern.add_degree(7, DegreeEvent::new(4, 4, 0.8, None));
+        PatternEditor::new(pattern)
+    }
+
+    #[test]
+    fn test_every_edit_undoes_exactly() {
+        let edits = [
+            PatternEdit::Add {
+                step: 3,
+                event: note(Pitch::D, 0.5),
+            },
+            PatternEdit::Remove {
+                step: 0,
+                event: note(Pitch::C, 0.8),
+            },
+            PatternEdit::Move {
+                from: 2,
+                to: 7,
+                event: note(Pitch::E, 0.8),
+            },
+            PatternEdit::Replace {
+                step: 0,
+                old: note(Pitch::G, 0.8),
+                new: note(Pitch::G, 0.3),
+            },
+            PatternEdit::AddDegree {
+                step: 0,
+                event: DegreeEvent::new(-1, 3, 0.5, None),
+            },
+            PatternEdit::RemoveDegree {
+                step: 0,
+                event: DegreeEvent::new(2, 4, 0.8, None),
+            },
+            PatternEdit::ClearStep { step: 0 },
+            PatternEdit::SetLength { length: 4 },
+            PatternEdit::SetLength { length: 32 },
+        ];
+        for edit in edits {
+            let mut editor = seeded();
+            let before = snapshot(&editor);
+            editor.apply(edit.clone()).unwrap();
+            let after = snapshot(&editor);
+            assert_ne!(before, after, "{:?} changed nothing", edit);
+
+            assert!(editor.undo());
+            assert_eq!(snapshot(&editor), before, "undo of {:?}", edit);
+            assert!(editor.redo());
+            assert_eq!(snapshot(&editor), after, "redo of {:?}", edit);
+        }
+    }
+
+    #[test]
+    fn test_history_order_and_redo_cleared() {
+        let mut editor = seeded();
+        let original = snapshot(&editor);
+        assert!(!editor.can_undo() && !editor.can_redo());
+
+        editor.apply(PatternEdit::ClearStep { step: 0 }).unwrap();
+        let cleared = snapshot(&editor);
+        editor
+            .apply(PatternEdit::Add {
+                step: 1,
+                event: note(Pitch::A, 1.0),
+            })
+            .unwrap();
+
+        assert!(editor.undo());
+        assert_eq!(snapshot(&editor), cleared);
+        assert!(editor.undo());
+        assert_eq!(snapshot(&editor), original);
+        assert!(!editor.undo());
+
+        // A new edit discards the redo history
+        assert!(editor.redo());
+        editor.apply(PatternEdit::SetLength { length: 2 }).unwrap();
+        assert!(!editor.can_redo());
+        assert!(!editor.redo());
+    }
+
+    #[test]
+    fn test_group_is_one_step_and_atomic() {
+        let mut editor = seeded();
+        let original = snapshot(&editor);
+        let chord = [Pitch::C, Pitch::E, Pitch::G].map(|pitch| PatternEdit::Add {
+            step: 4,
+            event: note(pitch, 0.7),
+        });
+        editor.apply_group(chord).unwrap();
+        assert_eq!(editor.pattern().events_at_step(4).len(), 3);
+        editor.undo();
+        assert_eq!(snapshot(&editor), original);
+
+        // The failing second edit rolls back the first
+        let result = editor.apply_group([
+            PatternEdit::ClearStep { step: 0 },
+            PatternEdit::Remove {
+                step: 5,
+                event: note(Pitch::C, 0.8),
+            },
+        ]);
+        assert_eq!(result, Err(PatternEditError::EventNotFound { step: 5 }));
+        assert_eq!(snapshot(&editor), original);
+        assert!(editor.can_redo());
+    }
+
+    #[test]
+    fn test_errors_leave_pattern_unchanged() {
+        let mut editor = seeded();
+        let original = snapshot(&editor);
+        let errors = [
+            (
+                PatternEdit::Add {
+                    step: 8,
+                    event: note(Pitch::C, 0.8),
+                },
+                PatternEditError::StepOutOfRange { step: 8, length: 8 },
+            ),
+            (
+                PatternEdit::Move {
+                    from: 0,
+                    to: 9,
+                    event: note(Pitch::C, 0.8),
+                },
+                PatternEditError::StepOutOfRange { step: 9, length: 8 },
+            ),
+            (
+                PatternEdit::Replace {
+                    step: 0,
+                    old: note(Pitch::C, 0.5),
+                    new: note(Pitch::C, 0.9),
+                },
+                PatternEditError::EventNotFound { step: 0 },
+            ),
+            (
+                PatternEdit::SetLength { length: 0 },
+                PatternEditError::ZeroLength,
+            ),
+        ];
+        for (edit, expected) in errors {
+            assert_eq!(editor.apply(edit), Err(expected));
+            assert_eq!(snapshot(&editor), original);
+        }
+        assert!(!editor.can_undo());
+        assert_eq!(
+            PatternEditError::ZeroLength.to_string(),
+            "pattern length must be greater than 0"
+        );
+    }
+
+    #[test]
+    fn test_edits_keep_triggers() {
+        let ghost = Trigger::probability(0.25);
+        let mut pattern = Pattern::new(4);
+        pattern.add_triggered_event(0, note(Pitch::C, 0.8), ghost);
+        let mut editor = PatternEditor::new(pattern);
+
+        editor
+            .apply_group(vec![
+                PatternEdit::Move {
+                    from: 0,
+                    to: 2,
+                    event: note(Pitch::C, 0.8),
+                },
+                PatternEdit::Replace {
+                    step: 2,
+                    old: note(Pitch::C, 0.8),
+                    new: note(Pitch::C, 0.3),
+                },
+            ])
+            .unwrap();
+        let triggered = editor.pattern().triggered_events_at_step(2);
+        assert_eq!(triggered, [(&note(Pitch::C, 0.3), &ghost)]);
+
+        editor.undo();
+        let triggered = editor.pattern().triggered_events_at_step(0);
+        assert_eq!(triggered, [(&note(Pitch::C, 0.8), &ghost)]);
+    }
+}
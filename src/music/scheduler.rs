@@ -0,0 +1,206 @@
+//! Note-off scheduling for timed notes.
+//!
+//! [`NoteEvent`]s carry a duration, but a [`NoteTarget`] only understands
+//! note on and note off. A [`NoteScheduler`] sits between the two: it starts
+//! each note immediately and releases it once its duration has elapsed,
+//! counted in samples by calling [`tick`](NoteScheduler::tick) once per
+//! sample.
+
+use super::{command::NoteTarget, core::NoteEvent};
+
+/// Releases notes after their durations elapse.
+///
+/// Each note number has at most one pending release: retriggering a note
+/// that is still held replaces its release time, so the earlier event can't
+/// cut the new one short.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::music::{NoteScheduler, VoiceAllocator};
+/// use earworm::{ADSR, NoteEvent, Pitch, SineOscillator};
+///
+/// const SAMPLE_RATE: u32 = 44100;
+///
+/// let mut synth = VoiceAllocator::<SAMPLE_RATE, 4, _, _>::new(|| {
+///     let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+///     let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+///     (osc, env)
+/// });
+/// let mut scheduler = NoteScheduler::new(SAMPLE_RATE);
+///
+/// // A quarter-second note
+/// let event = NoteEvent::from_pitch(Pitch::A, 4, 0.8, Some(0.25));
+/// scheduler.play(&mut synth, &event);
+/// assert!(synth.is_note_playing(69));
+///
+/// for _ in 0..SAMPLE_RATE / 4 {
+///     scheduler.tick(&mut synth);
+/// }
+/// assert_eq!(scheduler.pending(), 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct NoteScheduler {
+    sample_rate: u32,
+    /// Samples ticked so far
+    now: u64,
+    /// (release sample, MIDI note), one entry per held note
+    releases: Vec<(u64, u8)>,
+}
+
+impl NoteScheduler {
+    /// Creates a scheduler with no held notes.
+    ///
+    /// # Arguments
+    ///
+    /// * `sample_rate` - Audio sample rate in Hz, used to convert durations
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            now: 0,
+            releases: Vec::new(),
+        }
+    }
+
+    /// Returns the sample rate durations are converted with.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Starts `event` on `target` and schedules its note off.
+    ///
+    /// Events without a duration are held until
+    /// [`all_notes_off`](Self::all_notes_off).
+    pub fn play<T: NoteTarget>(&mut self, target: &mut T, event: &NoteEvent) {
+        let note = event.note.to_midi();
+        match event.duration {
+            Some(seconds) => {
+                let samples = (seconds * self.sample_rate as f64).round().max(1.0);
+                self.note_on_for(target, note, event.velocity, samples as u64);
+            }
+            None => {
+                self.cancel(note);
+                target.note_on(note, event.velocity);
+            }
+        }
+    }
+
+    /// Starts `note` on `target` and releases it after `samples` ticks.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - Instrument to play
+    /// * `note` - MIDI note number
+    /// * `velocity` - Velocity (0.0-1.0)
+    /// * `samples` - Gate length in samples (at least 1)
+    pub fn note_on_for<T: NoteTarget>(
+        &mut self,
+        target: &mut T,
+        note: u8,
+        velocity: f64,
+        samples: u64,
+    ) {
+        self.cancel(note);
+        target.note_on(note, velocity);
+        self.releases.push((self.now + samples.max(1), note));
+    }
+
+    /// Advances one sample, releasing notes whose duration has elapsed.
+    pub fn tick<T: NoteTarget>(&mut self, target: &mut T) {
+        self.now += 1;
+        let now = self.now;
+        self.releases.retain(|&(due, note)| {
+            if due <= now {
+                target.note_off(note);
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    /// Releases every note on `target` and clears all scheduled note offs.
+    pub fn all_notes_off<T: NoteTarget>(&mut self, target: &mut T) {
+        self.releases.clear();
+        target.all_notes_off();
+    }
+
+    /// Returns the number of notes waiting to be released.
+    pub fn pending(&self) -> usize {
+        self.releases.len()
+    }
+
+    /// Forgets the pending release of `note`, if any.
+    fn cancel(&mut self, note: u8) {
+        self.releases.retain(|&(_, n)| n != note);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records calls as (sample, note, velocity); a velocity of None is a note off.
+    #[derive(Default)]
+    struct Recorder {
+        sample: u64,
+        calls: Vec<(u64, u8, Option<f64>)>,
+    }
+
+    impl NoteTarget for Recorder {
+        fn note_on(&mut self, note: u8, velocity: f64) {
+            self.calls.push((self.sample, note, Some(velocity)));
+        }
+
+        fn note_off(&mut self, note: u8) {
+            self.calls.push((self.sample, note, None));
+        }
+
+        fn all_notes_off(&mut self) {
+            self.calls.push((self.sample, 0, None));
+        }
+    }
+
+    fn run(scheduler: &mut NoteScheduler, target: &mut Recorder, samples: u64) {
+        for _ in 0..samples {
+            target.sample += 1;
+            scheduler.tick(target);
+        }
+    }
+
+    #[test]
+    fn test_releases_after_duration() {
+        let mut scheduler = NoteScheduler::new(100);
+        let mut target = Recorder::default();
+        scheduler.play(&mut target, &NoteEvent::from_midi(60, 100, Some(0.05)));
+        scheduler.note_on_for(&mut target, 64, 0.5, 2);
+        assert_eq!(scheduler.pending(), 2);
+
+        run(&mut scheduler, &mut target, 10);
+        let offs: Vec<_> = target.calls.iter().filter(|c| c.2.is_none()).collect();
+        assert_eq!(offs, [&(2, 64, None), &(5, 60, None)]);
+        assert_eq!(scheduler.pending(), 0);
+    }
+
+    #[test]
+    fn test_retrigger_replaces_release() {
+        let mut scheduler = NoteScheduler::new(100);
+        let mut target = Recorder::default();
+        scheduler.note_on_for(&mut target, 60, 0.8, 3);
+        run(&mut scheduler, &mut target, 2);
+        scheduler.note_on_for(&mut target, 60, 0.8, 3);
+        run(&mut scheduler, &mut target, 10);
+
+        // One note off, three samples after the retrigger
+        let offs: Vec<_> = target.calls.iter().filter(|c| c.2.is_none()).collect();
+        assert_eq!(offs, [&(5, 60, None)]);
+
+        // Held notes wait for all_notes_off
+        scheduler.play(&mut target, &NoteEvent::from_midi(62, 100, None));
+        run(&mut scheduler, &mut target, 100);
+        assert_eq!(target.calls.last(), Some(&(12, 62, Some(100.0 / 127.0))));
+        assert_eq!(scheduler.pending(), 0);
+        scheduler.all_notes_off(&mut target);
+        assert_eq!(target.calls.last(), Some(&(112, 0, None)));
+    }
+}
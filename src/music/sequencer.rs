@@ -1,9 +1,18 @@
 //! Musical sequencer for pattern-based playback.
 //!
-//! The `Sequencer` combines a `Metronome` (for timing) with one or more `Pattern`s
+//! The `Sequencer` combines a `Transport` (for timing) with one or more `Pattern`s
 //! (for note data) to trigger musical events in sync with audio sample generation.
+//! Pattern changes can wait for the transport's next bar line.
 
-use super::{core::NoteEvent, metronome::Metronome, pattern::Pattern};
+use super::{
+    command::NoteTarget,
+    core::{Key, NoteEvent},
+    metronome::{Metronome, TempoCurve},
+    pattern::Pattern,
+    scheduler::NoteScheduler,
+    transport::{BarPosition, TimeSignature, Transport},
+};
+use crate::core::{ParamRegistry, splitmix64};
 
 /// Playback state of the sequencer.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -16,16 +25,21 @@ pub enum PlayState {
 
 /// A musical sequencer that plays patterns in time.
 ///
-/// The sequencer combines timing (via `Metronome`) with musical content (via `Pattern`)
+/// The sequencer combines timing (via [`Transport`]) with musical content (via `Pattern`)
 /// to trigger note events at the correct sample times. It maintains transport state
 /// (play/stop) and handles pattern looping.
 ///
 /// # Architecture
 ///
-/// - **Metronome**: Provides sample-accurate timing and step advancement
+/// - **Transport**: Provides sample-accurate timing, step advancement and bar lines
 /// - **Pattern**: Contains the musical events to play at each step
 /// - **Sequencer**: Coordinates them, returning events when it's time to trigger them
 ///
+/// [`set_pattern`](Self::set_pattern) swaps the pattern at once;
+/// [`queue_pattern`](Self::queue_pattern) waits for the next bar line of the
+/// transport's [time signature](Self::set_time_signature) and starts the new
+/// pattern from its first step, so live pattern changes stay in time.
+///
 /// # Usage Pattern
 ///
 /// In your audio callback, call `tick()` once per sample. When `tick()` returns events,
@@ -61,18 +75,37 @@ pub enum PlayState {
 /// ```
 #[derive(Debug, Clone)]
 pub struct Sequencer {
-    /// The metronome that provides timing
-    metronome: Metronome,
+    /// The transport that provides timing and bar lines
+    transport: Transport,
     /// The currently active pattern (if any)
     pattern: Option<Pattern>,
+    /// Pattern to switch to at the next bar line
+    queued: Option<Pattern>,
+    /// Absolute step the active pattern's step 0 lines up with
+    pattern_start: u64,
     /// Current playback state
     state: PlayState,
+    /// Key used to resolve scale-degree events
+    key: Key,
+    /// Note offs for events played by `tick_into`
+    scheduler: NoteScheduler,
+    /// Whether fill-conditioned events play
+    fill: bool,
+    /// Random state for event probabilities (splitmix64)
+    rng_state: u64,
+    /// Parameter locks of the step most recently reached
+    locks: Vec<(String, f64)>,
+    /// Values locked parameters had before `apply_locks` changed them
+    lock_bases: Vec<(String, f64)>,
+    /// Whether a step was reached since `apply_locks` last ran
+    locks_changed: bool,
 }
 
 impl Sequencer {
     /// Creates a new sequencer with the given tempo and step resolution.
     ///
-    /// The sequencer starts in `Stopped` state with no pattern loaded.
+    /// The sequencer starts in `Stopped` state with no pattern loaded, in
+    /// C major.
     ///
     /// # Arguments
     ///
@@ -90,9 +123,18 @@ impl Sequencer {
     /// ```
     pub fn new(bpm: f64, steps_per_beat: u32, sample_rate: u32) -> Self {
         Self {
-            metronome: Metronome::new(bpm, steps_per_beat, sample_rate),
+            transport: Transport::new(bpm, steps_per_beat, sample_rate),
             pattern: None,
+            queued: None,
+            pattern_start: 0,
             state: PlayState::Stopped,
+            key: Key::default(),
+            scheduler: NoteScheduler::new(sample_rate),
+            fill: false,
+            rng_state: rand::random(),
+            locks: Vec::new(),
+            lock_bases: Vec::new(),
+            locks_changed: false,
         }
     }
 
@@ -111,6 +153,59 @@ impl Sequencer {
         self.pattern = Some(pattern);
     }
 
+    /// Switches to `pattern` at the next bar line, starting from its first
+    /// step.
+    ///
+    /// Replaces any pattern already queued. With no pattern loaded, or before
+    /// the first step, the pattern is loaded at once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::{Pattern, Sequencer};
+    /// use earworm::NoteEvent;
+    ///
+    /// let mut verse = Pattern::new(16);
+    /// verse.add_event(0, NoteEvent::from_midi(36, 100, None));
+    /// let mut chorus = Pattern::new(16);
+    /// chorus.add_event(0, NoteEvent::from_midi(38, 100, None));
+    ///
+    /// // 16th-note steps in 4/4: a bar is 16 steps
+    /// let mut sequencer = Sequencer::new(120.0, 4, 44100);
+    /// sequencer.set_pattern(verse);
+    /// sequencer.play();
+    /// while sequencer.current_step() < 5 {
+    ///     sequencer.tick();
+    /// }
+    ///
+    /// sequencer.queue_pattern(chorus);
+    /// while sequencer.queued_pattern().is_some() {
+    ///     sequencer.tick();
+    /// }
+    /// // The chorus started on the bar line at step 16
+    /// assert_eq!(sequencer.current_step(), 17);
+    /// assert_eq!(sequencer.pattern_step(), Some(1));
+    /// ```
+    pub fn queue_pattern(&mut self, pattern: Pattern) {
+        if self.pattern.is_none() || self.transport.metronome().current_step() == 0 {
+            self.pattern = Some(pattern);
+            self.pattern_start = 0;
+            self.queued = None;
+        } else {
+            self.queued = Some(pattern);
+        }
+    }
+
+    /// Returns the pattern queued to start at the next bar line.
+    pub fn queued_pattern(&self) -> Option<&Pattern> {
+        self.queued.as_ref()
+    }
+
+    /// Cancels a queued pattern change.
+    pub fn cancel_queued(&mut self) {
+        self.queued = None;
+    }
+
     /// Returns a reference to the current pattern, if any.
     ///
     /// # Examples
@@ -142,6 +237,7 @@ impl Sequencer {
     /// ```
     pub fn clear_pattern(&mut self) {
         self.pattern = None;
+        self.queued = None;
     }
 
     /// Starts playback.
@@ -179,6 +275,9 @@ impl Sequencer {
 
     /// Resets the sequencer to step 0.
     ///
+    /// Parameter locks of the current step are dropped (and restored by the
+    /// next [`apply_locks`](Self::apply_locks)).
+    ///
     /// # Examples
     ///
     /// ```
@@ -188,7 +287,10 @@ impl Sequencer {
     /// sequencer.reset();
     /// ```
     pub fn reset(&mut self) {
-        self.metronome.reset();
+        self.transport.reset();
+        self.pattern_start = 0;
+        self.locks.clear();
+        self.locks_changed = true;
     }
 
     /// Returns true if the sequencer is currently playing.
@@ -235,7 +337,7 @@ impl Sequencer {
     /// assert_eq!(sequencer.current_step(), 0);
     /// ```
     pub fn current_step(&self) -> u64 {
-        self.metronome.current_step()
+        self.transport.metronome().current_step()
     }
 
     /// Returns the current step within the pattern (wraps at pattern length).
@@ -256,7 +358,7 @@ impl Sequencer {
     pub fn pattern_step(&self) -> Option<usize> {
         self.pattern
             .as_ref()
-            .map(|p| (self.metronome.current_step() % p.length() as u64) as usize)
+            .map(|p| (self.pattern_position() % p.length() as u64) as usize)
     }
 
     /// Sets the tempo in BPM.
@@ -271,7 +373,41 @@ impl Sequencer {
     /// assert_eq!(sequencer.tempo(), 140.0);
     /// ```
     pub fn set_tempo(&mut self, bpm: f64) {
-        self.metronome.set_tempo(bpm);
+        self.transport.set_tempo(bpm);
+    }
+
+    /// Glides the tempo to `bpm` over the next `beats` beats (see
+    /// [`Metronome::ramp_tempo`]).
+    ///
+    /// The ramp advances only while the sequencer plays.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bpm` is <= 0 or `beats` is negative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::{Pattern, Sequencer, TempoCurve};
+    ///
+    /// let mut sequencer = Sequencer::new(120.0, 4, 44100);
+    /// sequencer.set_pattern(Pattern::new(16));
+    /// sequencer.play();
+    ///
+    /// // Ritardando to 80 BPM over one bar
+    /// sequencer.ramp_tempo(80.0, 4.0, TempoCurve::Linear);
+    /// while sequencer.is_ramping() {
+    ///     sequencer.tick();
+    /// }
+    /// assert_eq!(sequencer.tempo(), 80.0);
+    /// ```
+    pub fn ramp_tempo(&mut self, bpm: f64, beats: f64, curve: TempoCurve) {
+        self.transport.ramp_tempo(bpm, beats, curve);
+    }
+
+    /// Returns `true` while a tempo ramp is in progress.
+    pub fn is_ramping(&self) -> bool {
+        self.transport.metronome().is_ramping()
     }
 
     /// Returns the current tempo in BPM.
@@ -285,13 +421,117 @@ impl Sequencer {
     /// assert_eq!(sequencer.tempo(), 120.0);
     /// ```
     pub fn tempo(&self) -> f64 {
-        self.metronome.tempo()
+        self.transport.tempo()
+    }
+
+    /// Returns the metronome that times the steps.
+    pub fn metronome(&self) -> &Metronome {
+        self.transport.metronome()
+    }
+
+    /// Returns the metronome, e.g. to [`sync_to_beat`](Metronome::sync_to_beat)
+    /// with an external clock.
+    pub fn metronome_mut(&mut self) -> &mut Metronome {
+        self.transport.metronome_mut()
+    }
+
+    /// Returns the transport, for its bar:beat:step position and bar lines.
+    pub fn transport(&self) -> &Transport {
+        &self.transport
+    }
+
+    /// Returns the position of the step playing now.
+    pub fn position(&self) -> BarPosition {
+        self.transport.position()
+    }
+
+    /// Changes the time signature that bar lines follow, from the next bar
+    /// line (see [`Transport::set_time_signature`]).
+    pub fn set_time_signature(&mut self, signature: TimeSignature) {
+        self.transport.set_time_signature(signature);
+    }
+
+    /// Returns the time signature of the current bar.
+    pub fn time_signature(&self) -> TimeSignature {
+        self.transport.time_signature()
+    }
+
+    /// Sets the key that scale-degree events are resolved in.
+    ///
+    /// Takes effect from the next step, so changing the key or mode retunes
+    /// the playing pattern without editing it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::core::{Key, Pitch, Scale};
+    /// use earworm::music::Sequencer;
+    ///
+    /// let mut sequencer = Sequencer::new(120.0, 4, 44100);
+    /// sequencer.set_key(Key::new(Pitch::D, Scale::Dorian));
+    /// assert_eq!(sequencer.key().scale, Scale::Dorian);
+    /// ```
+    pub fn set_key(&mut self, key: Key) {
+        self.key = key;
+    }
+
+    /// Returns the key that scale-degree events are resolved in.
+    pub fn key(&self) -> Key {
+        self.key
+    }
+
+    /// Seeds the random source used for event probabilities.
+    ///
+    /// Sequencers with the same seed and pattern make the same choices, which
+    /// keeps offline renders of generative patterns reproducible. Take the
+    /// seed from an [`RngContext`](crate::core::RngContext) to tie it to the
+    /// rest of a piece.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::{Pattern, Sequencer, Trigger};
+    /// use earworm::NoteEvent;
+    ///
+    /// let mut pattern = Pattern::new(4);
+    /// pattern.add_triggered_event(0, NoteEvent::from_midi(42, 90, None), Trigger::probability(0.5));
+    ///
+    /// let play = |seed| {
+    ///     let mut sequencer = Sequencer::new(120.0, 4, 44100);
+    ///     sequencer.set_pattern(pattern.clone());
+    ///     sequencer.set_seed(seed);
+    ///     sequencer.play();
+    ///     (0..100_000).filter(|_| sequencer.tick().is_some()).count()
+    /// };
+    /// assert_eq!(play(7), play(7));
+    /// ```
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng_state = seed;
+    }
+
+    /// Turns fills on or off.
+    ///
+    /// Events with a [`TriggerCondition::Fill`](super::TriggerCondition::Fill)
+    /// condition only play while a fill is on, and
+    /// [`NotFill`](super::TriggerCondition::NotFill) events only while it is off.
+    pub fn set_fill(&mut self, fill: bool) {
+        self.fill = fill;
+    }
+
+    /// Returns `true` while a fill is on.
+    pub fn is_fill(&self) -> bool {
+        self.fill
     }
 
     /// Advances the sequencer by one sample.
     ///
     /// If the sequencer is playing and a step boundary is crossed, returns the events
     /// that should be triggered at this step. Otherwise returns `None`.
+    /// Scale-degree events are resolved in the current [`key`](Self::key).
+    /// Events with a [`Trigger`](super::Trigger) play only when its condition
+    /// holds for the current loop and its probability roll succeeds.
+    /// The step's parameter locks are available from [`locks`](Self::locks)
+    /// afterwards, even when the step plays no notes.
     ///
     /// # Returns
     ///
@@ -328,18 +568,59 @@ impl Sequencer {
             return None;
         }
 
-        // If no pattern, just advance metronome but return no events
-        let pattern = self.pattern.as_ref()?;
+        // If no pattern, don't advance either
+        self.pattern.as_ref()?;
 
-        // Advance metronome - returns true on step boundary
-        if self.metronome.tick() {
-            // Get current step within pattern (with wrapping)
+        // Advance transport - flags a step boundary
+        let tick = self.transport.tick();
+        if tick.step {
             // current_step() has already been incremented by tick(), so subtract 1
-            let step = ((self.metronome.current_step() - 1) % pattern.length() as u64) as usize;
+            let started = self.transport.metronome().current_step() - 1;
+            if tick.bar
+                && let Some(queued) = self.queued.take()
+            {
+                self.pattern = Some(queued);
+                self.pattern_start = started;
+            }
+            if started < self.pattern_start {
+                // An external clock moved the metronome back past the switch
+                self.pattern_start = 0;
+            }
+            let pattern = self.pattern.as_ref()?;
 
-            // Get events at this step and copy them (NoteEvent is Copy)
-            let events: Vec<NoteEvent> =
-                pattern.events_at_step(step).into_iter().copied().collect();
+            // Get current step within pattern (with wrapping)
+            let position = started - self.pattern_start;
+            let step = (position % pattern.length() as u64) as usize;
+            let pass = position / pattern.length() as u64;
+
+            self.locks.clear();
+            self.locks.extend(
+                pattern
+                    .locks_at_step(step)
+                    .into_iter()
+                    .map(|(param, value)| (param.to_string(), value)),
+            );
+            self.locks_changed = true;
+
+            let mut events = Vec::new();
+            for (event, trigger) in pattern.triggered_events_at_step(step) {
+                // Only roll for events that need it, so adding a chance to one
+                // event doesn't change what the others do
+                let roll = if trigger.probability < 1.0 {
+                    next_random(&mut self.rng_state)
+                } else {
+                    0.0
+                };
+                if trigger.fires(pass, self.fill, roll) {
+                    events.push(*event);
+                }
+            }
+            events.extend(
+                pattern
+                    .degree_events_at_step(step)
+                    .into_iter()
+                    .map(|event| event.resolve(&self.key)),
+            );
 
             if !events.is_empty() {
                 return Some(events);
@@ -348,6 +629,168 @@ impl Sequencer {
 
         None
     }
+
+    /// Returns the parameter locks of the step most recently reached, as
+    /// (parameter, value) pairs.
+    ///
+    /// Locks last until the next step, so an instrument can read these after
+    /// every [`tick`](Self::tick) and apply them itself, or use
+    /// [`apply_locks`](Self::apply_locks).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::{Pattern, Sequencer};
+    /// use earworm::NoteEvent;
+    ///
+    /// let mut pattern = Pattern::new(4);
+    /// pattern.add_event(0, NoteEvent::from_midi(36, 100, None));
+    /// pattern.set_lock(0, "decay", 0.1);
+    ///
+    /// let mut sequencer = Sequencer::new(120.0, 4, 44100);
+    /// sequencer.set_pattern(pattern);
+    /// sequencer.play();
+    /// while sequencer.tick().is_none() {}
+    /// assert_eq!(sequencer.locks(), [("decay".to_string(), 0.1)]);
+    /// ```
+    pub fn locks(&self) -> &[(String, f64)] {
+        &self.locks
+    }
+
+    /// Sets the current step's parameter locks in `registry`, and puts back
+    /// the previous values of parameters locked on earlier steps.
+    ///
+    /// Only does work when a new step has been reached, so it can be called
+    /// after every [`tick`](Self::tick). Locks naming IDs that aren't in the
+    /// registry are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::core::{ParamHandle, ParamRegistry};
+    /// use earworm::music::{Pattern, Sequencer};
+    ///
+    /// let registry = ParamRegistry::new();
+    /// let cutoff = ParamHandle::new(2000.0).named(&registry, "filter.cutoff");
+    ///
+    /// // A dark second step
+    /// let mut pattern = Pattern::new(4);
+    /// pattern.set_lock(1, "filter.cutoff", 300.0);
+    ///
+    /// let mut sequencer = Sequencer::new(120.0, 4, 44100);
+    /// sequencer.set_pattern(pattern);
+    /// sequencer.play();
+    ///
+    /// let mut cutoffs = Vec::new();
+    /// while sequencer.current_step() < 3 {
+    ///     sequencer.tick();
+    ///     sequencer.apply_locks(&registry);
+    ///     cutoffs.push(cutoff.get());
+    /// }
+    /// assert!(cutoffs.contains(&300.0));
+    /// assert_eq!(cutoff.get(), 2000.0);
+    /// ```
+    pub fn apply_locks(&mut self, registry: &ParamRegistry) {
+        if !self.locks_changed {
+            return;
+        }
+        self.locks_changed = false;
+
+        let locks = &self.locks;
+        self.lock_bases.retain(|(param, base)| {
+            let still_locked = locks.iter().any(|(p, _)| p == param);
+            if !still_locked {
+                registry.set(param, *base);
+            }
+            still_locked
+        });
+        for (param, value) in &self.locks {
+            if !self.lock_bases.iter().any(|(p, _)| p == param)
+                && let Some(base) = registry.get(param)
+            {
+                self.lock_bases.push((param.clone(), base));
+            }
+            registry.set(param, *value);
+        }
+    }
+
+    /// Advances the sequencer by one sample, playing events on `target`.
+    ///
+    /// Like [`tick`](Self::tick), but starts each event's note directly and
+    /// releases it once its duration has elapsed, so notes are gated without
+    /// any bookkeeping by the caller. Events without a duration last one step.
+    /// Note offs that come due on a step are sent before that step's note ons,
+    /// and pending note offs are still sent while the sequencer is stopped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::{Pattern, Sequencer, VoiceAllocator};
+    /// use earworm::{ADSR, NoteEvent, Pitch, Signal, SineOscillator};
+    ///
+    /// const SAMPLE_RATE: u32 = 44100;
+    ///
+    /// let mut synth = VoiceAllocator::<SAMPLE_RATE, 4, _, _>::new(|| {
+    ///     let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+    ///     let env = ADSR::new(0.01, 0.1, 0.7, 0.2, SAMPLE_RATE as f64);
+    ///     (osc, env)
+    /// });
+    ///
+    /// let mut pattern = Pattern::new(16);
+    /// pattern.add_event(0, NoteEvent::from_pitch(Pitch::C, 4, 0.8, Some(0.1)));
+    ///
+    /// let mut sequencer = Sequencer::new(120.0, 4, SAMPLE_RATE);
+    /// sequencer.set_pattern(pattern);
+    /// sequencer.play();
+    ///
+    /// for _ in 0..SAMPLE_RATE / 5 {
+    ///     sequencer.tick_into(&mut synth);
+    ///     let _sample = synth.next_sample();
+    /// }
+    /// assert_eq!(sequencer.pending_note_offs(), 1);
+    /// ```
+    pub fn tick_into<T: NoteTarget>(&mut self, target: &mut T) {
+        self.scheduler.tick(target);
+        let Some(events) = self.tick() else {
+            return;
+        };
+        let metronome = self.transport.metronome();
+        let step_seconds = 60.0 / (metronome.tempo() * metronome.steps_per_beat() as f64);
+        for event in &events {
+            let event = NoteEvent {
+                duration: Some(event.duration.unwrap_or(step_seconds)),
+                ..*event
+            };
+            self.scheduler.play(target, &event);
+        }
+    }
+
+    /// Releases every note on `target` and drops the note offs scheduled by
+    /// [`tick_into`](Self::tick_into).
+    ///
+    /// Call this after [`stop`](Self::stop) to silence held notes at once.
+    pub fn all_notes_off<T: NoteTarget>(&mut self, target: &mut T) {
+        self.scheduler.all_notes_off(target);
+    }
+
+    /// Returns the number of notes played by [`tick_into`](Self::tick_into)
+    /// that are still waiting for their note off.
+    pub fn pending_note_offs(&self) -> usize {
+        self.scheduler.pending()
+    }
+
+    /// Steps started since the active pattern's step 0 lined up.
+    fn pattern_position(&self) -> u64 {
+        self.transport
+            .metronome()
+            .current_step()
+            .saturating_sub(self.pattern_start)
+    }
+}
+
+/// Advances a splitmix64 state and returns a uniform value in [0, 1).
+fn next_random(state: &mut u64) -> f64 {
+    (splitmix64(state) >> 11) as f64 / (1u64 << 53) as f64
 }
 
 #[cfg(test)]
@@ -539,4 +982,203 @@ mod tests {
             assert!(sequencer.tick().is_none());
         }
     }
+
+    #[test]
+    fn test_key_change_retunes_degrees() {
+        use crate::music::core::{DegreeEvent, Key, Scale};
+
+        let mut sequencer = Sequencer::new(120.0, 4, SAMPLE_RATE);
+        let mut pattern = Pattern::new(1);
+        pattern.add_degree(0, DegreeEvent::new(2, 4, 0.8, None));
+        sequencer.set_pattern(pattern);
+        sequencer.play();
+
+        let next_note = |sequencer: &mut Sequencer| loop {
+            if let Some(events) = sequencer.tick() {
+                return events[0].note.to_midi();
+            }
+        };
+        assert_eq!(next_note(&mut sequencer), 64); // E4 in C major
+        sequencer.set_key(Key::new(Pitch::C, Scale::Minor));
+        assert_eq!(next_note(&mut sequencer), 63); // E flat
+        sequencer.set_key(Key::new(Pitch::A, Scale::Major));
+        assert_eq!(next_note(&mut sequencer), 73); // C sharp, a third above A4
+    }
+
+    #[test]
+    fn test_tick_into_gates_notes() {
+        use crate::music::NoteTarget;
+
+        #[derive(Default)]
+        struct Gates {
+            sample: usize,
+            log: Vec<(usize, u8, bool)>,
+        }
+
+        impl NoteTarget for Gates {
+            fn note_on(&mut self, note: u8, _velocity: f64) {
+                self.log.push((self.sample, note, true));
+            }
+            fn note_off(&mut self, note: u8) {
+                self.log.push((self.sample, note, false));
+            }
+            fn all_notes_off(&mut self) {}
+        }
+
+        // 120 BPM quarter-note steps at 8 Hz: 4 samples per step
+        let mut sequencer = Sequencer::new(120.0, 1, 8);
+        let mut pattern = Pattern::new(2);
+        pattern.add_event(0, NoteEvent::from_midi(60, 100, Some(0.25)));
+        pattern.add_event(1, NoteEvent::from_midi(62, 100, None));
+        sequencer.set_pattern(pattern);
+        sequencer.play();
+
+        let mut gates = Gates::default();
+        for sample in 0..12 {
+            gates.sample = sample;
+            sequencer.tick_into(&mut gates);
+        }
+        // 60 lasts 2 samples; 62 has no duration so it lasts one step and is
+        // released just before 60 starts again
+        assert_eq!(
+            gates.log,
+            [
+                (3, 60, true),
+                (5, 60, false),
+                (7, 62, true),
+                (11, 62, false),
+                (11, 60, true),
+            ]
+        );
+
+        // Stopping still lets pending notes end
+        sequencer.stop();
+        for sample in 12..20 {
+            gates.sample = sample;
+            sequencer.tick_into(&mut gates);
+        }
+        assert_eq!(gates.log.last(), Some(&(13, 60, false)));
+        assert_eq!(sequencer.pending_note_offs(), 0);
+    }
+
+    #[test]
+    fn test_triggers_by_loop_fill_and_chance() {
+        use crate::music::{Trigger, TriggerCondition};
+
+        // 120 BPM quarter-note steps at 2 Hz: one sample per step
+        let mut sequencer = Sequencer::new(120.0, 1, 2);
+        let mut pattern = Pattern::new(1);
+        let conditional = |midi, condition| {
+            (
+                NoteEvent::from_midi(midi, 100, None),
+                Trigger::when(condition),
+            )
+        };
+        for (event, trigger) in [
+            conditional(36, TriggerCondition::Cycle { pass: 1, every: 3 }),
+            conditional(38, TriggerCondition::First),
+            conditional(40, TriggerCondition::Fill),
+        ] {
+            pattern.add_triggered_event(0, event, trigger);
+        }
+        pattern.add_triggered_event(
+            0,
+            NoteEvent::from_midi(42, 100, None),
+            Trigger::probability(0.5),
+        );
+        sequencer.set_pattern(pattern);
+        sequencer.set_seed(1234);
+        sequencer.play();
+
+        let mut loops = Vec::new();
+        for pass in 0..400 {
+            sequencer.set_fill(pass == 5);
+            let notes: Vec<u8> = sequencer
+                .tick()
+                .unwrap_or_default()
+                .iter()
+                .map(|e| e.note.to_midi())
+                .collect();
+            loops.push(notes);
+        }
+
+        let played = |midi| loops.iter().filter(|notes| notes.contains(&midi)).count();
+        assert!(loops[0].contains(&38) && !loops[1].contains(&38));
+        assert!(loops[1].contains(&36) && loops[4].contains(&36));
+        assert_eq!(played(36), 133);
+        assert_eq!(played(40), 1);
+        assert!(loops[5].contains(&40));
+        let hats = played(42);
+        assert!((150..250).contains(&hats), "{} hats", hats);
+    }
+
+    #[test]
+    fn test_parameter_locks_apply_and_restore() {
+        let registry = ParamRegistry::new();
+        registry.register("cutoff", &crate::core::ParamHandle::new(1.0));
+        registry.register("decay", &crate::core::ParamHandle::new(0.5));
+
+        let mut pattern = Pattern::new(4);
+        pattern.add_event(0, NoteEvent::from_midi(60, 100, None));
+        pattern.set_lock(0, "cutoff", 0.2);
+        pattern.set_lock(1, "cutoff", 0.4);
+        pattern.set_lock(1, "decay", 0.1);
+        pattern.set_lock(1, "missing", 3.0);
+
+        let mut sequencer = Sequencer::new(120.0, 4, 1000);
+        sequencer.set_pattern(pattern);
+        sequencer.play();
+
+        // One entry per step: (cutoff, decay) while the step plays
+        let mut values = Vec::new();
+        let mut step = sequencer.current_step();
+        while values.len() < 5 {
+            sequencer.tick();
+            sequencer.apply_locks(&registry);
+            if sequencer.current_step() != step {
+                step = sequencer.current_step();
+                values.push((registry.get("cutoff"), registry.get("decay")));
+            }
+        }
+        let expected = [(0.2, 0.5), (0.4, 0.1), (1.0, 0.5), (1.0, 0.5), (0.2, 0.5)];
+        assert_eq!(values, expected.map(|(c, d)| (Some(c), Some(d))));
+        assert!(!registry.contains("missing"));
+
+        // Step 0 has a note, step 1 only locks
+        assert_eq!(sequencer.locks(), [("cutoff".to_string(), 0.2)]);
+        sequencer.reset();
+        sequencer.apply_locks(&registry);
+        assert!(sequencer.locks().is_empty());
+        assert_eq!(registry.get("cutoff"), Some(1.0));
+    }
+
+    #[test]
+    fn test_queued_pattern_waits_for_bar_line() {
+        let mut first = Pattern::new(2);
+        first.add_event(0, NoteEvent::from_pitch(Pitch::C, 4, 0.8, None));
+        let mut second = Pattern::new(4);
+        second.add_event(0, NoteEvent::from_pitch(Pitch::E, 4, 0.8, None));
+
+        // One step per beat in 3/4, so bar lines fall on steps 0, 3, 6...
+        let mut sequencer = Sequencer::new(120.0, 1, 1000);
+        sequencer.set_time_signature(TimeSignature::THREE_FOUR);
+        sequencer.set_pattern(first);
+        sequencer.play();
+        while sequencer.tick().is_none() {}
+
+        // Queue mid-bar: the old pattern keeps playing until the bar line
+        sequencer.queue_pattern(second);
+        assert!(sequencer.queued_pattern().is_some());
+        let mut played = Vec::new();
+        while played.len() < 3 {
+            if let Some(events) = sequencer.tick() {
+                played.push((sequencer.current_step() - 1, events[0].note.pitch));
+            }
+        }
+        let c = NoteEvent::from_pitch(Pitch::C, 4, 0.8, None).note.pitch;
+        let e = NoteEvent::from_pitch(Pitch::E, 4, 0.8, None).note.pitch;
+        assert_eq!(played, [(2, c), (3, e), (7, e)]);
+        assert!(sequencer.queued_pattern().is_none());
+        assert_eq!(sequencer.position().bar, 2);
+    }
 }
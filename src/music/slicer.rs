@@ -0,0 +1,211 @@
+//! Tempo-synced trance gate.
+
+use super::metronome::Metronome;
+use crate::core::{AudioSignal, Signal, debug_assert_finite};
+
+/// Default fade-in at the start of each open step, in seconds.
+const DEFAULT_ATTACK: f64 = 0.002;
+
+/// Default fade-out at the end of each open step, in seconds.
+const DEFAULT_RELEASE: f64 = 0.01;
+
+/// Slicer (trance gate) that chops its input to a step pattern.
+///
+/// Each step of the pattern is a gain level from 0.0 (closed) to 1.0 (open),
+/// and the steps advance in time with a [`Metronome`], so the gate stays
+/// locked to the tempo of the rest of the track. Every step fades in over the
+/// attack time and out over the release time, which keeps the cuts free of
+/// clicks; adjacent open steps are still re-articulated, which is what gives
+/// the effect its rhythmic pump. The pattern loops once its last step ends.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{SawtoothOscillator, Slicer};
+/// use earworm::music::Metronome;
+///
+/// let pad = SawtoothOscillator::<44100>::new(110.0);
+/// // 16th-note gate at 128 BPM
+/// let metronome = Metronome::new(128.0, 4, 44100);
+/// let mut slicer = Slicer::new(pad, metronome, &[1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.5]);
+/// ```
+pub struct Slicer<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
+    source: S,
+    metronome: Metronome,
+    pattern: Vec<f64>, // gain per step, 0.0 to 1.0
+    attack: f64,       // seconds
+    release: f64,      // seconds
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Slicer<SAMPLE_RATE, S> {
+    /// Creates a new slicer.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Input signal to gate
+    /// * `metronome` - Clock driving the steps (should run at `SAMPLE_RATE`)
+    /// * `pattern` - Gain of each step (0.0 = closed, 1.0 = open)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` is empty.
+    pub fn new(source: S, metronome: Metronome, pattern: &[f64]) -> Self {
+        assert!(!pattern.is_empty(), "pattern must have at least one step");
+        Self {
+            source,
+            metronome,
+            pattern: pattern.iter().map(|level| level.clamp(0.0, 1.0)).collect(),
+            attack: DEFAULT_ATTACK,
+            release: DEFAULT_RELEASE,
+        }
+    }
+
+    /// Sets the fade-in and fade-out applied to every step.
+    ///
+    /// Longer times soften the gate from a hard chop into a pulsing swell.
+    /// Fades are shortened automatically when they don't fit in one step.
+    ///
+    /// # Arguments
+    ///
+    /// * `attack` - Fade-in at the start of each step in seconds
+    /// * `release` - Fade-out at the end of each step in seconds
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{SineOscillator, Slicer};
+    /// use earworm::music::Metronome;
+    ///
+    /// let pad = SineOscillator::<44100>::new(220.0);
+    /// let metronome = Metronome::new(120.0, 4, 44100);
+    /// let slicer = Slicer::trance(pad, metronome).with_envelope(0.005, 0.05);
+    /// ```
+    pub fn with_envelope(mut self, attack: f64, release: f64) -> Self {
+        self.attack = attack.max(0.0);
+        self.release = release.max(0.0);
+        self
+    }
+
+    /// Creates a classic syncopated trance gate.
+    ///
+    /// Settings: 16 steps `x.xx.x.xx.xx.x.x`, 2ms attack, 10ms release
+    pub fn trance(source: S, metronome: Metronome) -> Self {
+        Self::new(
+            source,
+            metronome,
+            &[
+                1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.0, 1.0,
+            ],
+        )
+    }
+
+    /// Creates a straight on/off chop on every other step.
+    ///
+    /// Settings: 2 steps `x.`, 2ms attack, 10ms release
+    pub fn chop(source: S, metronome: Metronome) -> Self {
+        Self::new(source, metronome, &[1.0, 0.0])
+    }
+
+    /// Creates a sidechain-style pump that ducks the start of each beat.
+    ///
+    /// Settings: 4 steps at 0.2, 0.6, 0.9, 1.0, 10ms attack, 5ms release
+    pub fn pump(source: S, metronome: Metronome) -> Self {
+        Self::new(source, metronome, &[0.2, 0.6, 0.9, 1.0]).with_envelope(0.01, 0.005)
+    }
+
+    /// Returns the step pattern.
+    pub fn pattern(&self) -> &[f64] {
+        &self.pattern
+    }
+
+    /// Returns the metronome, e.g. to [`sync_to_beat`](Metronome::sync_to_beat)
+    /// with an external clock.
+    pub fn metronome_mut(&mut self) -> &mut Metronome {
+        &mut self.metronome
+    }
+
+    /// Gate gain for the current position of the metronome.
+    fn gain(&self) -> f64 {
+        let steps = self.metronome.beat_position() * self.metronome.steps_per_beat() as f64;
+        let level = self.pattern[steps as u64 as usize % self.pattern.len()];
+
+        // Fade in from the start of the step and out towards its end
+        let step_time = 60.0 / (self.metronome.tempo() * self.metronome.steps_per_beat() as f64);
+        let elapsed = steps.fract() * step_time;
+        let remaining = step_time - elapsed;
+        let fade_in = if self.attack > 0.0 {
+            (elapsed / self.attack.min(step_time / 2.0)).min(1.0)
+        } else {
+            1.0
+        };
+        let fade_out = if self.release > 0.0 {
+            (remaining / self.release.min(step_time / 2.0)).min(1.0)
+        } else {
+            1.0
+        };
+        level * fade_in * fade_out
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for Slicer<SAMPLE_RATE, S> {
+    fn next_sample(&mut self) -> f64 {
+        let input = self.source.next_sample();
+        let output = input * self.gain();
+        self.metronome.tick();
+        debug_assert_finite("Slicer", input, output);
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE>
+    for Slicer<SAMPLE_RATE, S>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConstantSignal;
+
+    /// 120 BPM in quarter notes: exactly 22050 samples per step.
+    fn metronome() -> Metronome {
+        Metronome::new(120.0, 1, 44100)
+    }
+
+    #[test]
+    fn test_follows_pattern() {
+        let mut slicer = Slicer::new(ConstantSignal::<44100>(1.0), metronome(), &[1.0, 0.0, 0.5]);
+        let output: Vec<f64> = slicer.iter().take(22050 * 4).collect();
+        assert!((output[11025] - 1.0).abs() < 1e-9);
+        assert_eq!(output[22050 + 11025], 0.0);
+        assert!((output[44100 + 11025] - 0.5).abs() < 1e-9);
+        // Pattern loops
+        assert!((output[66150 + 11025] - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_steps_fade_in_and_out() {
+        let mut slicer = Slicer::new(ConstantSignal::<44100>(1.0), metronome(), &[1.0])
+            .with_envelope(0.01, 0.01);
+        let output: Vec<f64> = slicer.iter().take(44100).collect();
+        assert_eq!(output[0], 0.0);
+        assert!((output[220] - 0.5).abs() < 0.01);
+        assert!(output[22049] < 0.01);
+        // Consecutive open steps are re-articulated without a jump
+        let largest_jump = output
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).abs())
+            .fold(0.0_f64, f64::max);
+        assert!(largest_jump < 0.01);
+    }
+
+    #[test]
+    #[should_panic(expected = "pattern must have at least one step")]
+    fn test_empty_pattern() {
+        Slicer::new(ConstantSignal::<44100>(1.0), metronome(), &[]);
+    }
+}
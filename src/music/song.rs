@@ -0,0 +1,809 @@
+//! Multi-pattern arrangements.
+//!
+//! A [`Sequencer`](super::Sequencer) loops one pattern forever. A [`Song`]
+//! strings patterns together into sections (intro, verse, chorus, ...), each
+//! repeating its pattern a set number of times, and a [`SongPlayer`] plays
+//! the sections in order. While playing, a different section can be queued
+//! to start at the next bar line, for live arrangement.
+//!
+//! Each section can carry a [`ChordTrack`]. Patterns marked with
+//! [`Pattern::set_follows_chords`] play their scale-degree events as tones of
+//! the current chord, so a section can be reharmonized without editing the
+//! patterns it plays.
+
+use super::{
+    core::{Chord, Key, NoteEvent},
+    metronome::{Metronome, TempoCurve},
+    pattern::Pattern,
+    sequencer::PlayState,
+};
+
+/// Chord changes over the course of a [`Section`].
+///
+/// Each change gives the step, counted from the start of the section across
+/// all its repeats, at which a chord takes over. A chord lasts until the next
+/// change.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::music::ChordTrack;
+/// use earworm::music::core::{Chord, ChordQuality, Pitch};
+///
+/// // I - IV, two bars each in 16th notes
+/// let track = ChordTrack::new()
+///     .with_chord(0, Chord::new(Pitch::C, ChordQuality::Major))
+///     .with_chord(32, Chord::new(Pitch::F, ChordQuality::Major));
+///
+/// assert_eq!(track.chord_at(31).unwrap().root(), Pitch::C);
+/// assert_eq!(track.chord_at(40).unwrap().root(), Pitch::F);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChordTrack {
+    // Sorted by step, at most one change per step
+    changes: Vec<(usize, Chord)>,
+}
+
+impl ChordTrack {
+    /// Creates an empty chord track.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a chord change at `step` (builder style).
+    pub fn with_chord(mut self, step: usize, chord: Chord) -> Self {
+        self.set_chord(step, chord);
+        self
+    }
+
+    /// Sets the chord that starts at `step`, replacing any change already
+    /// there.
+    pub fn set_chord(&mut self, step: usize, chord: Chord) {
+        match self.changes.binary_search_by_key(&step, |(s, _)| *s) {
+            Ok(index) => self.changes[index].1 = chord,
+            Err(index) => self.changes.insert(index, (step, chord)),
+        }
+    }
+
+    /// Removes the chord change at `step`, returning its chord.
+    pub fn remove_chord(&mut self, step: usize) -> Option<Chord> {
+        let index = self.changes.binary_search_by_key(&step, |(s, _)| *s).ok()?;
+        Some(self.changes.remove(index).1)
+    }
+
+    /// Returns the chord sounding at `step`, or `None` before the first change.
+    pub fn chord_at(&self, step: usize) -> Option<&Chord> {
+        let index = self.changes.partition_point(|(s, _)| *s <= step);
+        index.checked_sub(1).map(|i| &self.changes[i].1)
+    }
+
+    /// Returns the (step, chord) changes in step order.
+    pub fn changes(&self) -> &[(usize, Chord)] {
+        &self.changes
+    }
+
+    /// Returns `true` if the track has no chord changes.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// One part of a [`Song`]: a pattern played a number of times in a row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    /// Section name, such as "verse" or "chorus"
+    pub name: String,
+    /// Index of the section's pattern in the song
+    pub pattern: usize,
+    /// How many times the pattern plays (at least 1)
+    pub repeats: usize,
+    /// Chords that harmonic-follower patterns play over (empty by default)
+    pub chords: ChordTrack,
+}
+
+/// Patterns arranged into an ordered list of sections.
+///
+/// Patterns are added once and can be used by any number of sections, so a
+/// chorus that returns three times is stored once.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::music::{Pattern, Song};
+///
+/// let mut song = Song::new();
+/// let verse = song.add_pattern(Pattern::new(16));
+/// let chorus = song.add_pattern(Pattern::new(32));
+///
+/// song.add_section("verse", verse, 2);
+/// song.add_section("chorus", chorus, 1);
+/// song.add_section("verse", verse, 2);
+///
+/// assert_eq!(song.sections().len(), 3);
+/// assert_eq!(song.length_in_steps(), 16 * 2 + 32 + 16 * 2);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Song {
+    patterns: Vec<Pattern>,
+    sections: Vec<Section>,
+}
+
+impl Song {
+    /// Creates a song with no patterns or sections.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a pattern and returns its index for use in sections.
+    pub fn add_pattern(&mut self, pattern: Pattern) -> usize {
+        self.patterns.push(pattern);
+        self.patterns.len() - 1
+    }
+
+    /// Appends a section and returns its index.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Section name
+    /// * `pattern` - Index returned by [`add_pattern`](Self::add_pattern)
+    /// * `repeats` - How many times the pattern plays (must be > 0)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` is not a valid pattern index or `repeats` is 0.
+    pub fn add_section(
+        &mut self,
+        name: impl Into<String>,
+        pattern: usize,
+        repeats: usize,
+    ) -> usize {
+        assert!(
+            pattern < self.patterns.len(),
+            "Pattern index {} out of bounds (song has {} patterns)",
+            pattern,
+            self.patterns.len()
+        );
+        assert!(repeats > 0, "Section repeats must be greater than 0");
+        self.sections.push(Section {
+            name: name.into(),
+            pattern,
+            repeats,
+            chords: ChordTrack::new(),
+        });
+        self.sections.len() - 1
+    }
+
+    /// Sets the chord track of section `section`.
+    ///
+    /// Patterns that [follow chords](Pattern::set_follows_chords) play their
+    /// scale-degree events over these chords whenever the section plays.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `section` is not a valid section index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::core::{Chord, ChordQuality, DegreeEvent, Pitch};
+    /// use earworm::music::{ChordTrack, Pattern, Song};
+    ///
+    /// // A root-fifth bass line that follows the chords
+    /// let mut bass = Pattern::new(4);
+    /// bass.add_degree(0, DegreeEvent::new(0, 2, 0.9, None));
+    /// bass.add_degree(2, DegreeEvent::new(2, 2, 0.9, None));
+    /// bass.set_follows_chords(true);
+    ///
+    /// let mut song = Song::new();
+    /// let bass = song.add_pattern(bass);
+    /// let verse = song.add_section("verse", bass, 4);
+    /// let chorus = song.add_section("chorus", bass, 4);
+    ///
+    /// // Same pattern, different harmony
+    /// song.set_chords(
+    ///     verse,
+    ///     ChordTrack::new()
+    ///         .with_chord(0, Chord::new(Pitch::A, ChordQuality::Minor))
+    ///         .with_chord(8, Chord::new(Pitch::F, ChordQuality::Major)),
+    /// );
+    /// song.set_chords(
+    ///     chorus,
+    ///     ChordTrack::new().with_chord(0, Chord::new(Pitch::C, ChordQuality::Major)),
+    /// );
+    /// ```
+    pub fn set_chords(&mut self, section: usize, chords: ChordTrack) {
+        assert!(
+            section < self.sections.len(),
+            "Section index {} out of bounds (song has {} sections)",
+            section,
+            self.sections.len()
+        );
+        self.sections[section].chords = chords;
+    }
+
+    /// Returns the patterns, in the order they were added.
+    pub fn patterns(&self) -> &[Pattern] {
+        &self.patterns
+    }
+
+    /// Returns the pattern at `index`.
+    pub fn pattern(&self, index: usize) -> Option<&Pattern> {
+        self.patterns.get(index)
+    }
+
+    /// Returns the pattern at `index` for editing.
+    ///
+    /// Changes apply to every section that uses the pattern, including one
+    /// that is playing.
+    pub fn pattern_mut(&mut self, index: usize) -> Option<&mut Pattern> {
+        self.patterns.get_mut(index)
+    }
+
+    /// Returns the sections, in playing order.
+    pub fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+
+    /// Returns every event section `section` plays, resolved in `key`, as
+    /// (step, event) pairs with steps counted from the start of the section
+    /// across all its repeats.
+    ///
+    /// Patterns that follow chords are resolved over the section's chord
+    /// track, as in a [`SongPlayer`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `section` is not a valid section index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::core::{DegreeEvent, Key};
+    /// use earworm::music::{Pattern, Song};
+    ///
+    /// let mut riff = Pattern::new(4);
+    /// riff.add_degree(1, DegreeEvent::new(4, 3, 0.8, None));
+    ///
+    /// let mut song = Song::new();
+    /// let riff = song.add_pattern(riff);
+    /// let verse = song.add_section("verse", riff, 2);
+    ///
+    /// let events = song.section_events(verse, &Key::default());
+    /// let steps: Vec<usize> = events.iter().map(|(step, _)| *step).collect();
+    /// assert_eq!(steps, [1, 5]);
+    /// assert_eq!(events[0].1.note.to_midi(), 55); // G3
+    /// ```
+    pub fn section_events(&self, section: usize, key: &Key) -> Vec<(usize, NoteEvent)> {
+        let section = &self.sections[section];
+        let length = self.patterns[section.pattern].length();
+        (0..section.repeats)
+            .flat_map(|repeat| (0..length).map(move |step| (repeat, step)))
+            .flat_map(|(repeat, step)| {
+                self.events_at(section, repeat, step, key)
+                    .into_iter()
+                    .map(move |event| (repeat * length + step, event))
+            })
+            .collect()
+    }
+
+    /// The events `section` plays at `step` of its `repeat`th pass.
+    fn events_at(
+        &self,
+        section: &Section,
+        repeat: usize,
+        step: usize,
+        key: &Key,
+    ) -> Vec<NoteEvent> {
+        let pattern = &self.patterns[section.pattern];
+        let position = repeat * pattern.length() + step;
+        let chord = pattern
+            .follows_chords()
+            .then(|| section.chords.chord_at(position))
+            .flatten();
+        match chord {
+            Some(chord) => pattern.events_at_step_on_chord(step, chord),
+            None => pattern.events_at_step_in_key(step, key),
+        }
+    }
+
+    /// Returns the step at which section `section` starts, counted from the
+    /// start of the first section.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `section` is not a valid section index.
+    pub fn section_start(&self, section: usize) -> usize {
+        assert!(
+            section < self.sections.len(),
+            "Section index {} out of bounds (song has {} sections)",
+            section,
+            self.sections.len()
+        );
+        self.sections[..section]
+            .iter()
+            .map(|section| self.patterns[section.pattern].length() * section.repeats)
+            .sum()
+    }
+
+    /// Returns the number of steps in one pass through every section.
+    pub fn length_in_steps(&self) -> usize {
+        self.sections
+            .iter()
+            .map(|section| self.patterns[section.pattern].length() * section.repeats)
+            .sum()
+    }
+}
+
+/// Plays a [`Song`] section by section.
+///
+/// Works like a [`Sequencer`](super::Sequencer): call [`tick`](Self::tick)
+/// once per sample and trigger the events it returns. Each section plays
+/// its pattern from step 0 for the given number of repeats before moving on.
+/// After the last section the player either stops or, with
+/// [`with_looping`](Self::with_looping), starts again from the first.
+///
+/// [`queue_section`](Self::queue_section) jumps to another section at the
+/// next bar line (counted from the start of playback), so switches land in
+/// time even in the middle of a pattern.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::music::{Pattern, Song, SongPlayer};
+/// use earworm::{NoteEvent, Pitch};
+///
+/// let mut intro = Pattern::new(16);
+/// intro.add_event(0, NoteEvent::from_pitch(Pitch::C, 2, 0.9, Some(0.1)));
+/// let mut groove = Pattern::new(16);
+/// groove.add_event(0, NoteEvent::from_pitch(Pitch::C, 2, 0.9, Some(0.1)));
+/// groove.add_event(8, NoteEvent::from_pitch(Pitch::G, 2, 0.9, Some(0.1)));
+///
+/// let mut song = Song::new();
+/// let intro = song.add_pattern(intro);
+/// let groove = song.add_pattern(groove);
+/// song.add_section("intro", intro, 1);
+/// song.add_section("groove", groove, 4);
+///
+/// // 120 BPM, 16th-note steps
+/// let mut player = SongPlayer::new(song, 120.0, 4, 44100);
+/// player.play();
+/// for _ in 0..44100 * 3 {
+///     if let Some(_events) = player.tick() {
+///         // voice_allocator.note_on(...) for each event
+///     }
+/// }
+/// assert_eq!(player.current_section(), Some(1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SongPlayer {
+    song: Song,
+    metronome: Metronome,
+    state: PlayState,
+    beats_per_bar: u32,
+    looping: bool,
+    key: Key,
+    // Position of the next step to play; `section` is None once the song
+    // has finished
+    section: Option<usize>,
+    repeat: usize,
+    step: usize,
+    queued: Option<usize>,
+    // Song step of the step last played
+    played: Option<usize>,
+}
+
+impl SongPlayer {
+    /// Creates a stopped player positioned at the start of the song.
+    ///
+    /// Bars default to 4 beats, the song does not loop and scale-degree
+    /// events are resolved in C major.
+    ///
+    /// # Arguments
+    ///
+    /// * `song` - The song to play
+    /// * `bpm` - Tempo in beats per minute
+    /// * `steps_per_beat` - Step subdivision (4 = 16th notes, 2 = 8th notes, etc.)
+    /// * `sample_rate` - Audio sample rate in Hz
+    pub fn new(song: Song, bpm: f64, steps_per_beat: u32, sample_rate: u32) -> Self {
+        let section = (!song.sections.is_empty()).then_some(0);
+        Self {
+            song,
+            metronome: Metronome::new(bpm, steps_per_beat, sample_rate),
+            state: PlayState::Stopped,
+            beats_per_bar: 4,
+            looping: false,
+            key: Key::default(),
+            section,
+            repeat: 0,
+            step: 0,
+            queued: None,
+            played: None,
+        }
+    }
+
+    /// Sets the number of beats in a bar, used to quantize queued section
+    /// changes (builder style).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `beats_per_bar` is 0.
+    pub fn with_beats_per_bar(mut self, beats_per_bar: u32) -> Self {
+        assert!(beats_per_bar > 0, "beats_per_bar must be greater than 0");
+        self.beats_per_bar = beats_per_bar;
+        self
+    }
+
+    /// Sets whether the song starts over after its last section (builder style).
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Returns the song.
+    pub fn song(&self) -> &Song {
+        &self.song
+    }
+
+    /// Returns the song's patterns for editing.
+    ///
+    /// Sections are fixed once the player is created, since removing one
+    /// could leave the play position dangling.
+    pub fn patterns_mut(&mut self) -> &mut [Pattern] {
+        &mut self.song.patterns
+    }
+
+    /// Starts playback.
+    pub fn play(&mut self) {
+        self.state = PlayState::Playing;
+    }
+
+    /// Stops playback, keeping the position.
+    pub fn stop(&mut self) {
+        self.state = PlayState::Stopped;
+    }
+
+    /// Returns to the start of the first section and clears any queued change.
+    pub fn reset(&mut self) {
+        self.metronome.reset();
+        self.section = (!self.song.sections.is_empty()).then_some(0);
+        self.repeat = 0;
+        self.step = 0;
+        self.queued = None;
+        self.played = None;
+    }
+
+    /// Returns true if the player is currently playing.
+    pub fn is_playing(&self) -> bool {
+        self.state == PlayState::Playing
+    }
+
+    /// Returns the current playback state.
+    pub fn state(&self) -> PlayState {
+        self.state
+    }
+
+    /// Returns `true` once a non-looping song has played its last section.
+    pub fn is_finished(&self) -> bool {
+        self.section.is_none()
+    }
+
+    /// Returns the index of the section that plays the next step, or `None`
+    /// once the song has finished.
+    pub fn current_section(&self) -> Option<usize> {
+        self.section
+    }
+
+    /// Returns which repeat of the current section is playing, from 0.
+    pub fn current_repeat(&self) -> usize {
+        self.repeat
+    }
+
+    /// Returns the pattern step that plays next.
+    pub fn pattern_step(&self) -> usize {
+        self.step
+    }
+
+    /// Returns the step that started at the last step boundary, counted from
+    /// the start of the first section as the song is laid out.
+    ///
+    /// Returns `None` before the first step and once the song has finished.
+    ///
+    /// Follows jumps made by [`queue_section`](Self::queue_section), so it
+    /// gives the position in a render of the whole song that matches what
+    /// is playing.
+    pub fn song_step(&self) -> Option<usize> {
+        self.played
+    }
+
+    /// Jumps to section `index` at the next bar line.
+    ///
+    /// Replaces any change already queued. Queuing a section after the song
+    /// has finished restarts playback from it at the next bar.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not a valid section index.
+    pub fn queue_section(&mut self, index: usize) {
+        assert!(
+            index < self.song.sections.len(),
+            "Section index {} out of bounds (song has {} sections)",
+            index,
+            self.song.sections.len()
+        );
+        self.queued = Some(index);
+    }
+
+    /// Returns the section queued to start at the next bar line.
+    pub fn queued_section(&self) -> Option<usize> {
+        self.queued
+    }
+
+    /// Cancels a queued section change.
+    pub fn cancel_queued(&mut self) {
+        self.queued = None;
+    }
+
+    /// Sets the tempo in BPM.
+    pub fn set_tempo(&mut self, bpm: f64) {
+        self.metronome.set_tempo(bpm);
+    }
+
+    /// Glides the tempo to `bpm` over the next `beats` beats (see
+    /// [`Metronome::ramp_tempo`]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bpm` is <= 0 or `beats` is negative.
+    pub fn ramp_tempo(&mut self, bpm: f64, beats: f64, curve: TempoCurve) {
+        self.metronome.ramp_tempo(bpm, beats, curve);
+    }
+
+    /// Returns `true` while a tempo ramp is in progress.
+    pub fn is_ramping(&self) -> bool {
+        self.metronome.is_ramping()
+    }
+
+    /// Returns the current tempo in BPM.
+    pub fn tempo(&self) -> f64 {
+        self.metronome.tempo()
+    }
+
+    /// Returns the metronome that times the steps.
+    pub fn metronome(&self) -> &Metronome {
+        &self.metronome
+    }
+
+    /// Returns the metronome, e.g. to [`sync_to_beat`](Metronome::sync_to_beat)
+    /// with an external clock.
+    ///
+    /// Queued section changes land on bar lines counted by this metronome.
+    pub fn metronome_mut(&mut self) -> &mut Metronome {
+        &mut self.metronome
+    }
+
+    /// Sets the key that scale-degree events are resolved in, from the next
+    /// step on. Every pattern in the song follows the change.
+    pub fn set_key(&mut self, key: Key) {
+        self.key = key;
+    }
+
+    /// Returns the key that scale-degree events are resolved in.
+    pub fn key(&self) -> Key {
+        self.key
+    }
+
+    /// Advances the player by one sample.
+    ///
+    /// Returns the events to trigger when a step boundary is crossed and the
+    /// step has events; otherwise returns `None`. Scale-degree events are
+    /// resolved in the current [`key`](Self::key), or over the section's
+    /// current chord for patterns that follow chords.
+    pub fn tick(&mut self) -> Option<Vec<NoteEvent>> {
+        if self.state != PlayState::Playing || !self.metronome.tick() {
+            return None;
+        }
+
+        // current_step() has already been incremented by tick()
+        let absolute_step = self.metronome.current_step() - 1;
+        let steps_per_bar = (self.metronome.steps_per_beat() * self.beats_per_bar) as u64;
+        if absolute_step.is_multiple_of(steps_per_bar)
+            && let Some(queued) = self.queued.take()
+        {
+            self.section = Some(queued);
+            self.repeat = 0;
+            self.step = 0;
+        }
+
+        let Some(index) = self.section else {
+            self.played = None;
+            return None;
+        };
+        let section = &self.song.sections[index];
+        let pattern = &self.song.patterns[section.pattern];
+        // The pattern may have been shortened while playing
+        let step = self.step % pattern.length();
+        let events = self.song.events_at(section, self.repeat, step, &self.key);
+        self.played = Some(self.song.section_start(index) + self.repeat * pattern.length() + step);
+
+        self.step = step + 1;
+        if self.step == pattern.length() {
+            self.step = 0;
+            self.repeat += 1;
+            if self.repeat == section.repeats {
+                self.repeat = 0;
+                let next = self.section.map_or(0, |s| s + 1);
+                self.section = if next < self.song.sections.len() {
+                    Some(next)
+                } else if self.looping {
+                    Some(0)
+                } else {
+                    None
+                };
+            }
+        }
+
+        (!events.is_empty()).then_some(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // One step per beat, one sample per step
+    const SAMPLE_RATE: u32 = 2;
+    const BPM: f64 = 120.0;
+
+    /// A pattern whose every step plays `midi`.
+    fn pattern(length: usize, midi: u8) -> Pattern {
+        let mut pattern = Pattern::new(length);
+        for step in 0..length {
+            pattern.add_event(step, NoteEvent::from_midi(midi, 100, None));
+        }
+        pattern
+    }
+
+    /// The MIDI note played at each of the next `steps` steps (0 for none).
+    fn play(player: &mut SongPlayer, steps: usize) -> Vec<u8> {
+        (0..steps)
+            .map(|_| player.tick().map_or(0, |events| events[0].note.to_midi()))
+            .collect()
+    }
+
+    fn two_section_song() -> Song {
+        let mut song = Song::new();
+        let a = song.add_pattern(pattern(2, 60));
+        let b = song.add_pattern(pattern(3, 70));
+        song.add_section("a", a, 2);
+        song.add_section("b", b, 1);
+        song
+    }
+
+    #[test]
+    fn test_sections_play_in_order_then_stop() {
+        let mut player = SongPlayer::new(two_section_song(), BPM, 1, SAMPLE_RATE);
+        player.play();
+        assert_eq!(play(&mut player, 7), [60, 60, 60, 60, 70, 70, 70]);
+        assert_eq!(player.song_step(), Some(6));
+        assert_eq!(play(&mut player, 2), [0, 0]);
+        assert_eq!(player.song_step(), None);
+        assert!(player.is_finished());
+        assert_eq!(player.current_section(), None);
+
+        player.reset();
+        assert_eq!(player.current_section(), Some(0));
+        assert_eq!(play(&mut player, 1), [60]);
+    }
+
+    #[test]
+    fn test_looping_and_position() {
+        let mut player =
+            SongPlayer::new(two_section_song(), BPM, 1, SAMPLE_RATE).with_looping(true);
+        player.play();
+        play(&mut player, 3);
+        assert_eq!(player.current_section(), Some(0));
+        assert_eq!(player.current_repeat(), 1);
+        assert_eq!(player.pattern_step(), 1);
+        assert_eq!(play(&mut player, 6), [60, 70, 70, 70, 60, 60]);
+        assert!(!player.is_finished());
+    }
+
+    #[test]
+    fn test_queued_section_waits_for_bar_line() {
+        let mut song = Song::new();
+        let a = song.add_pattern(pattern(8, 60));
+        let b = song.add_pattern(pattern(8, 70));
+        song.add_section("a", a, 4);
+        song.add_section("b", b, 1);
+
+        // Bars of 3 steps, so the switch lands mid-pattern
+        let mut player = SongPlayer::new(song, BPM, 1, SAMPLE_RATE).with_beats_per_bar(3);
+        player.play();
+        play(&mut player, 1);
+        assert_eq!(player.song_step(), Some(0));
+        player.queue_section(1);
+        assert_eq!(player.queued_section(), Some(1));
+        assert_eq!(play(&mut player, 3), [60, 60, 70]);
+        assert_eq!(player.queued_section(), None);
+        assert_eq!(player.current_section(), Some(1));
+        assert_eq!(player.pattern_step(), 1);
+        // Section b starts after a's 4 repeats of 8 steps
+        assert_eq!(player.song().section_start(1), 32);
+        assert_eq!(player.song_step(), Some(32));
+
+        // Cancelling keeps the arrangement going
+        player.queue_section(0);
+        player.cancel_queued();
+        assert_eq!(play(&mut player, 3), [70, 70, 70]);
+    }
+
+    #[test]
+    fn test_stopped_and_empty() {
+        let mut player = SongPlayer::new(two_section_song(), BPM, 1, SAMPLE_RATE);
+        assert_eq!(play(&mut player, 4), [0, 0, 0, 0]);
+        assert_eq!(player.pattern_step(), 0);
+        assert_eq!(player.song_step(), None);
+
+        let mut empty = SongPlayer::new(Song::new(), BPM, 1, SAMPLE_RATE);
+        empty.play();
+        assert!(empty.is_finished());
+        assert_eq!(play(&mut empty, 4), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Section repeats must be greater than 0")]
+    fn test_zero_repeats_panics() {
+        let mut song = Song::new();
+        let a = song.add_pattern(Pattern::new(4));
+        song.add_section("a", a, 0);
+    }
+
+    #[test]
+    fn test_followers_play_section_chords() {
+        use crate::music::core::{ChordQuality, DegreeEvent, Pitch};
+
+        // Root then second degree of whatever is playing
+        let mut arp = Pattern::new(2);
+        arp.add_degree(0, DegreeEvent::new(0, 4, 0.8, None));
+        arp.add_degree(1, DegreeEvent::new(1, 4, 0.8, None));
+        let fixed = arp.clone();
+        arp.set_follows_chords(true);
+
+        let mut song = Song::new();
+        let arp = song.add_pattern(arp);
+        let fixed = song.add_pattern(fixed);
+        let verse = song.add_section("verse", arp, 3);
+        song.add_section("plain", fixed, 1);
+        song.set_chords(
+            verse,
+            ChordTrack::new()
+                .with_chord(2, Chord::new(Pitch::A, ChordQuality::Minor))
+                .with_chord(4, Chord::new(Pitch::F, ChordQuality::Major)),
+        );
+
+        let mut player = SongPlayer::new(song, BPM, 1, SAMPLE_RATE);
+        player.play();
+        // No chord yet, then Am, then F; the non-follower stays in the key
+        assert_eq!(play(&mut player, 8), [60, 62, 69, 72, 65, 69, 60, 62]);
+    }
+
+    #[test]
+    fn test_chord_track_edits() {
+        use crate::music::core::{ChordQuality, Pitch};
+
+        let c = Chord::new(Pitch::C, ChordQuality::Major);
+        let g = Chord::new(Pitch::G, ChordQuality::Major);
+        let mut track = ChordTrack::new()
+            .with_chord(8, g.clone())
+            .with_chord(0, g.clone());
+        track.set_chord(0, c.clone());
+        assert_eq!(track.changes(), &[(0, c.clone()), (8, g.clone())]);
+        assert_eq!(track.chord_at(7), Some(&c));
+        assert_eq!(track.remove_chord(8), Some(g));
+        assert_eq!(track.remove_chord(8), None);
+        assert_eq!(track.chord_at(100), Some(&c));
+        track.remove_chord(0);
+        assert!(track.is_empty());
+        assert_eq!(track.chord_at(0), None);
+    }
+}
@@ -0,0 +1,386 @@
+//! Arrangement tracks that can be frozen to audio.
+//!
+//! A [`Track`] is one part of an arrangement: a [`SongPlayer`] and the
+//! instrument it plays, rendered as one signal like a [`Deck`]. Freezing a
+//! track renders its whole song through the instrument once, with
+//! [`bounce_song`](super::bounce_song), and from then on plays that clip in
+//! step with the player instead of running the instrument. Keeping the
+//! heaviest parts of a big arrangement frozen keeps it within the CPU budget.
+//! Editing a frozen track's patterns or instrument, or changing its tempo or
+//! key, unfreezes it so the change is heard.
+
+use super::{
+    bounce::bounce_song, command::NoteTarget, core::Key, deck::Deck, pattern::Pattern,
+    song::SongPlayer,
+};
+use crate::core::{AudioSignal, Signal};
+
+/// A frozen track's clip and play position.
+struct Frozen {
+    clip: Vec<f64>,
+    samples_per_step: f64,
+    // Next sample of the clip to play
+    position: usize,
+}
+
+/// A song part and its instrument, which can be frozen to a rendered clip.
+///
+/// While live, a track plays exactly like a [`Deck`] of the same player and
+/// instrument. [`freeze`](Self::freeze) renders the song and the track then
+/// plays the clip instead, following the player: each step plays the part
+/// of the clip for the song step the player is at, so jumps made with
+/// [`queue_section`](Self::queue_section) and looping stay in time. The
+/// instrument is not run while frozen.
+///
+/// The clip is rendered at the tempo and key the player has when frozen, so
+/// [`set_tempo`](Self::set_tempo) and [`set_key`](Self::set_key) unfreeze
+/// the track when they change either. So do
+/// [`patterns_mut`](Self::patterns_mut) and
+/// [`instrument_mut`](Self::instrument_mut), since the clip would no longer
+/// match. Call [`freeze`](Self::freeze) again once the edits are done.
+///
+/// A frozen track cuts off when stopped, while a live one lets its notes
+/// ring out, and a jump cuts off the notes ringing before it.
+///
+/// # Type Parameters
+///
+/// * `SAMPLE_RATE` - Sample rate in Hz
+/// * `T` - Instrument, e.g. a [`VoiceAllocator`](super::VoiceAllocator)
+///
+/// # Examples
+///
+/// ```
+/// use earworm::music::{Pattern, Song, SongPlayer, Track, VoiceAllocator};
+/// use earworm::{ADSR, NoteEvent, Pitch, Signal, SineOscillator};
+///
+/// const SAMPLE_RATE: u32 = 44100;
+///
+/// let mut bass = Pattern::new(16);
+/// bass.add_event(0, NoteEvent::from_pitch(Pitch::E, 2, 0.9, Some(0.2)));
+/// bass.add_event(8, NoteEvent::from_pitch(Pitch::B, 2, 0.9, Some(0.2)));
+/// let mut song = Song::new();
+/// let bass = song.add_pattern(bass);
+/// song.add_section("verse", bass, 4);
+///
+/// let synth = VoiceAllocator::<SAMPLE_RATE, 4, _, _>::new(|| {
+///     let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+///     let env = ADSR::new(0.01, 0.1, 0.7, 0.2, SAMPLE_RATE as f64);
+///     (osc, env)
+/// });
+/// let mut track = Track::new(SongPlayer::new(song, 120.0, 4, SAMPLE_RATE), synth);
+///
+/// // Render the bass line once, then play it back from the clip
+/// track.freeze();
+/// track.play();
+/// let _sample = track.next_sample();
+/// assert!(track.is_frozen());
+///
+/// // Editing the pattern brings the synth back
+/// track.patterns_mut()[bass].add_event(4, NoteEvent::from_pitch(Pitch::G, 2, 0.9, None));
+/// assert!(!track.is_frozen());
+/// ```
+pub struct Track<const SAMPLE_RATE: u32, T>
+where
+    T: AudioSignal<SAMPLE_RATE> + NoteTarget,
+{
+    deck: Deck<SAMPLE_RATE, SongPlayer, T>,
+    frozen: Option<Frozen>,
+}
+
+impl<const SAMPLE_RATE: u32, T> Track<SAMPLE_RATE, T>
+where
+    T: AudioSignal<SAMPLE_RATE> + NoteTarget,
+{
+    /// Creates a live track playing `instrument` from `player`.
+    pub fn new(player: SongPlayer, instrument: T) -> Self {
+        Self {
+            deck: Deck::new(player, instrument),
+            frozen: None,
+        }
+    }
+
+    /// Renders the whole song through the instrument and plays the result
+    /// from now on.
+    ///
+    /// Every note on the instrument is released first; freeze while the
+    /// track is stopped and silent, since anything still ringing ends up in
+    /// the clip. This renders the song faster than real time but still
+    /// takes a while for a long song, so call it off the audio thread.
+    /// Freezing a frozen track renders it again.
+    pub fn freeze(&mut self) {
+        let player = self.deck.player();
+        let song = player.song().clone();
+        let metronome = player.metronome().clone();
+        let key = player.key();
+        let samples_per_step =
+            SAMPLE_RATE as f64 * 60.0 / (metronome.tempo() * metronome.steps_per_beat() as f64);
+
+        self.deck.all_notes_off();
+        let clip = bounce_song(&song, self.deck.instrument_mut(), &metronome, &key);
+        // Silent until the next step boundary places it
+        let position = clip.len();
+        self.frozen = Some(Frozen {
+            clip,
+            samples_per_step,
+            position,
+        });
+    }
+
+    /// Drops the rendered clip and goes back to playing the instrument.
+    ///
+    /// The instrument picks up from the next step; notes that started
+    /// before are not replayed.
+    pub fn unfreeze(&mut self) {
+        self.frozen = None;
+    }
+
+    /// Returns `true` while the track plays a rendered clip.
+    pub fn is_frozen(&self) -> bool {
+        self.frozen.is_some()
+    }
+
+    /// Returns the rendered clip while frozen.
+    pub fn frozen_clip(&self) -> Option<&[f64]> {
+        self.frozen.as_ref().map(|frozen| frozen.clip.as_slice())
+    }
+
+    /// Returns the player.
+    pub fn player(&self) -> &SongPlayer {
+        self.deck.player()
+    }
+
+    /// Returns the song's patterns for editing, unfreezing the track.
+    pub fn patterns_mut(&mut self) -> &mut [Pattern] {
+        self.unfreeze();
+        self.deck.player_mut().patterns_mut()
+    }
+
+    /// Returns the instrument.
+    pub fn instrument(&self) -> &T {
+        self.deck.instrument()
+    }
+
+    /// Returns the instrument for adjustment, unfreezing the track.
+    pub fn instrument_mut(&mut self) -> &mut T {
+        self.unfreeze();
+        self.deck.instrument_mut()
+    }
+
+    /// Starts playback.
+    pub fn play(&mut self) {
+        self.deck.player_mut().play();
+    }
+
+    /// Stops playback, keeping the position.
+    pub fn stop(&mut self) {
+        self.deck.player_mut().stop();
+    }
+
+    /// Returns to the start of the first section (see [`SongPlayer::reset`]).
+    pub fn reset(&mut self) {
+        self.deck.player_mut().reset();
+    }
+
+    /// Jumps to section `index` at the next bar line (see
+    /// [`SongPlayer::queue_section`]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not a valid section index.
+    pub fn queue_section(&mut self, index: usize) {
+        self.deck.player_mut().queue_section(index);
+    }
+
+    /// Cancels a queued section change.
+    pub fn cancel_queued(&mut self) {
+        self.deck.player_mut().cancel_queued();
+    }
+
+    /// Sets the tempo in BPM, unfreezing the track if it changes.
+    pub fn set_tempo(&mut self, bpm: f64) {
+        if bpm != self.player().tempo() {
+            self.unfreeze();
+        }
+        self.deck.player_mut().set_tempo(bpm);
+    }
+
+    /// Sets the key that scale-degree events are resolved in, unfreezing the
+    /// track if it changes.
+    pub fn set_key(&mut self, key: Key) {
+        if key != self.player().key() {
+            self.unfreeze();
+        }
+        self.deck.player_mut().set_key(key);
+    }
+
+    /// Releases every note on the instrument, e.g. after stopping a live
+    /// track.
+    pub fn all_notes_off(&mut self) {
+        self.deck.all_notes_off();
+    }
+}
+
+impl<const SAMPLE_RATE: u32, T> Signal for Track<SAMPLE_RATE, T>
+where
+    T: AudioSignal<SAMPLE_RATE> + NoteTarget,
+{
+    fn next_sample(&mut self) -> f64 {
+        let Some(frozen) = &mut self.frozen else {
+            return self.deck.next_sample();
+        };
+        let player = self.deck.player_mut();
+        if !player.is_playing() {
+            return 0.0;
+        }
+
+        // The clip already holds the step's notes
+        let step = player.metronome().current_step();
+        player.tick();
+        if player.metronome().current_step() != step
+            && let Some(song_step) = player.song_step()
+        {
+            frozen.position = (song_step as f64 * frozen.samples_per_step).round() as usize;
+        }
+        let sample = frozen.clip.get(frozen.position).copied().unwrap_or(0.0);
+        frozen.position += 1;
+        sample
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.deck.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, T> AudioSignal<SAMPLE_RATE> for Track<SAMPLE_RATE, T> where
+    T: AudioSignal<SAMPLE_RATE> + NoteTarget
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::music::core::NoteEvent;
+    use crate::music::{Song, VoiceAllocator};
+    use crate::{ADSR, SineOscillator};
+
+    // 120 BPM quarter notes: 4000 samples per step
+    const SAMPLE_RATE: u32 = 8000;
+    const STEP: usize = 4000;
+
+    fn synth() -> impl AudioSignal<SAMPLE_RATE> + NoteTarget {
+        VoiceAllocator::<SAMPLE_RATE, 4, _, _>::new(|| {
+            let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+            let env = ADSR::new(0.001, 0.01, 1.0, 0.2, SAMPLE_RATE as f64);
+            (osc, env)
+        })
+    }
+
+    /// Two sections of different notes, each note ringing into the next step.
+    fn song() -> Song {
+        let mut low = Pattern::new(2);
+        low.add_event(0, NoteEvent::from_midi(57, 100, Some(0.3)));
+        let mut high = Pattern::new(2);
+        high.add_event(1, NoteEvent::from_midi(69, 100, Some(0.3)));
+        let mut song = Song::new();
+        let low = song.add_pattern(low);
+        let high = song.add_pattern(high);
+        song.add_section("low", low, 2);
+        song.add_section("high", high, 2);
+        song
+    }
+
+    fn track() -> Track<SAMPLE_RATE, impl AudioSignal<SAMPLE_RATE> + NoteTarget> {
+        let player = SongPlayer::new(song(), 120.0, 1, SAMPLE_RATE).with_beats_per_bar(2);
+        Track::new(player, synth())
+    }
+
+    fn render(
+        track: &mut Track<SAMPLE_RATE, impl AudioSignal<SAMPLE_RATE> + NoteTarget>,
+        samples: usize,
+    ) -> Vec<f64> {
+        (0..samples).map(|_| track.next_sample()).collect()
+    }
+
+    fn peak(samples: &[f64]) -> f64 {
+        samples.iter().fold(0.0_f64, |max, s| max.max(s.abs()))
+    }
+
+    #[test]
+    fn test_frozen_matches_live() {
+        let mut live = track();
+        let mut frozen = track();
+        frozen.freeze();
+        assert!(frozen.is_frozen());
+        live.play();
+        frozen.play();
+
+        // The whole song and the last note's release. Oscillator phases
+        // differ, since the live synth's oscillators run from the first
+        // sample, so compare levels a quarter step at a time
+        let live = render(&mut live, 10 * STEP);
+        let frozen = render(&mut frozen, 10 * STEP);
+        for (i, (a, b)) in live
+            .chunks(STEP / 4)
+            .zip(frozen.chunks(STEP / 4))
+            .enumerate()
+        {
+            let (a, b) = (peak(a), peak(b));
+            assert!(
+                (a - b).abs() < 0.02,
+                "window {}: live {} frozen {}",
+                i,
+                a,
+                b
+            );
+        }
+        assert!(peak(&frozen[8 * STEP..9 * STEP]) > 0.1);
+    }
+
+    #[test]
+    fn test_frozen_follows_queued_jump() {
+        let mut track = track();
+        track.freeze();
+        track.play();
+        // The first step starts on the last sample of the first step's length
+        render(&mut track, STEP - 1);
+        let clip = track.frozen_clip().unwrap().to_vec();
+        assert_eq!(render(&mut track, STEP), clip[..STEP]);
+
+        // Bars are 2 steps, so low's second step plays, then high starts at
+        // song step 4 instead of low's second repeat
+        track.queue_section(1);
+        let output = render(&mut track, 3 * STEP);
+        assert_eq!(output[..STEP], clip[STEP..2 * STEP]);
+        assert_eq!(output[STEP..], clip[4 * STEP..6 * STEP]);
+        assert_eq!(track.player().song_step(), Some(5));
+    }
+
+    #[test]
+    fn test_edits_unfreeze() {
+        let mut track = track();
+        track.freeze();
+        track.set_tempo(120.0);
+        track.set_key(Key::default());
+        assert!(track.is_frozen());
+
+        track.set_tempo(100.0);
+        assert!(!track.is_frozen());
+        track.freeze();
+        track.patterns_mut()[0].clear_step(0);
+        assert!(!track.is_frozen());
+        track.freeze();
+        track.instrument_mut();
+        assert!(!track.is_frozen());
+    }
+
+    #[test]
+    fn test_stopped_frozen_track_is_silent() {
+        let mut track = track();
+        track.freeze();
+        track.play();
+        render(&mut track, STEP + 100);
+        track.stop();
+        assert_eq!(peak(&render(&mut track, 100)), 0.0);
+        assert_eq!(track.player().metronome().current_step(), 1);
+    }
+}
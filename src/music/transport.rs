@@ -0,0 +1,437 @@
+//! Bars, beats and time signatures on top of the metronome.
+//!
+//! A [`Metronome`] only counts steps. A [`Transport`] groups those steps into
+//! beats and bars according to a [`TimeSignature`], reports the song
+//! position as bar:beat:step, and flags the start of every beat and bar as
+//! it ticks, so pattern switches can be quantized to bar lines and a UI can
+//! show where the song is. The [`Sequencer`](super::Sequencer) runs on a
+//! transport and uses it to switch patterns on the bar.
+
+use super::metronome::{Metronome, TempoCurve};
+use std::fmt;
+use std::sync::Arc;
+
+/// A time signature such as 4/4, 3/4 or 7/8.
+///
+/// The tempo counts the signature's unit: 7/8 at 210 BPM plays 210 eighth
+/// notes a minute, so a bar lasts two seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeSignature {
+    beats: u32,
+    unit: u32,
+}
+
+impl TimeSignature {
+    /// Common time.
+    pub const FOUR_FOUR: Self = Self { beats: 4, unit: 4 };
+    /// Waltz time.
+    pub const THREE_FOUR: Self = Self { beats: 3, unit: 4 };
+    /// Compound duple time.
+    pub const SIX_EIGHT: Self = Self { beats: 6, unit: 8 };
+
+    /// Creates a time signature of `beats` beats of `unit` notes per bar.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `beats` is 0 or `unit` is not a power of two.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::music::TimeSignature;
+    ///
+    /// let seven_eight = TimeSignature::new(7, 8);
+    /// assert_eq!(seven_eight.beats(), 7);
+    /// assert_eq!(seven_eight.to_string(), "7/8");
+    /// ```
+    pub fn new(beats: u32, unit: u32) -> Self {
+        assert!(beats > 0, "beats per bar must be greater than 0");
+        assert!(unit.is_power_of_two(), "beat unit must be a power of two");
+        Self { beats, unit }
+    }
+
+    /// Returns the number of beats in a bar (the numerator).
+    pub fn beats(&self) -> u32 {
+        self.beats
+    }
+
+    /// Returns the note value of one beat (the denominator).
+    pub fn unit(&self) -> u32 {
+        self.unit
+    }
+}
+
+impl Default for TimeSignature {
+    fn default() -> Self {
+        Self::FOUR_FOUR
+    }
+}
+
+impl fmt::Display for TimeSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.beats, self.unit)
+    }
+}
+
+/// A song position in bars, beats and steps, all counted from 0.
+///
+/// Displays the way DAWs show it, counted from 1 (`1:1:1` is the downbeat of
+/// the first bar).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BarPosition {
+    /// Bar since the transport started
+    pub bar: u64,
+    /// Beat within the bar
+    pub beat: u32,
+    /// Step within the beat
+    pub step: u32,
+}
+
+impl fmt::Display for BarPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.bar + 1, self.beat + 1, self.step + 1)
+    }
+}
+
+/// What a call to [`Transport::tick`] crossed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransportTick {
+    /// A step started on this sample
+    pub step: bool,
+    /// The step is the first of a beat
+    pub beat: bool,
+    /// The step is the first of a bar
+    pub bar: bool,
+}
+
+/// A metronome that counts bars and beats in a time signature.
+///
+/// Steps are played the same way the [`Sequencer`](super::Sequencer) plays
+/// them: the first [`tick`](Self::tick) that crosses a step boundary starts
+/// step 0, which is also the downbeat of bar 0. Time signature changes wait
+/// for the next bar line so bars are never cut short.
+///
+/// If the metronome jumps, for example through
+/// [`Metronome::sync_to_beat`], bars are counted again from step 0 in the
+/// current time signature.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::music::{TimeSignature, Transport};
+///
+/// // A waltz at 180 BPM in eighth-note steps
+/// let mut transport =
+///     Transport::new(180.0, 2, 44100).with_time_signature(TimeSignature::THREE_FOUR);
+///
+/// let mut bars = 0;
+/// let mut beats = 0;
+/// for _ in 0..44100 * 2 {
+///     let tick = transport.tick();
+///     bars += tick.bar as u32;
+///     beats += tick.beat as u32;
+/// }
+/// // Six beats in two seconds, two bars of three
+/// assert_eq!(beats, 6);
+/// assert_eq!(bars, 2);
+/// assert_eq!(transport.position().to_string(), "2:3:2");
+/// ```
+#[derive(Clone)]
+pub struct Transport {
+    metronome: Metronome,
+    signature: TimeSignature,
+    // Signature to switch to at the next bar line
+    pending: Option<TimeSignature>,
+    // Absolute step the current bar started on
+    bar_start: u64,
+    position: BarPosition,
+    started: bool,
+    on_bar: Option<Arc<dyn Fn(u64) + Send + Sync>>,
+}
+
+impl Transport {
+    /// Creates a transport in 4/4.
+    ///
+    /// # Arguments
+    ///
+    /// * `bpm` - Tempo in beats per minute (must be > 0)
+    /// * `steps_per_beat` - Number of steps per beat (must be > 0)
+    /// * `sample_rate` - Audio sample rate in Hz
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bpm` or `steps_per_beat` is <= 0.
+    pub fn new(bpm: f64, steps_per_beat: u32, sample_rate: u32) -> Self {
+        Self {
+            metronome: Metronome::new(bpm, steps_per_beat, sample_rate),
+            signature: TimeSignature::default(),
+            pending: None,
+            bar_start: 0,
+            position: BarPosition::default(),
+            started: false,
+            on_bar: None,
+        }
+    }
+
+    /// Sets the starting time signature (builder style).
+    pub fn with_time_signature(mut self, signature: TimeSignature) -> Self {
+        self.signature = signature;
+        self
+    }
+
+    /// Calls `callback` with the bar number at the start of every bar
+    /// (builder style).
+    ///
+    /// The callback runs inside [`tick`](Self::tick), usually on the audio
+    /// thread, so it must be cheap and must not block. Clones of the
+    /// transport share it.
+    pub fn on_bar(mut self, callback: impl Fn(u64) + Send + Sync + 'static) -> Self {
+        self.on_bar = Some(Arc::new(callback));
+        self
+    }
+
+    /// Changes the time signature at the next bar line.
+    ///
+    /// Before the first step it takes effect at once.
+    pub fn set_time_signature(&mut self, signature: TimeSignature) {
+        if self.started {
+            self.pending = Some(signature);
+        } else {
+            self.signature = signature;
+        }
+    }
+
+    /// Returns the time signature of the current bar.
+    pub fn time_signature(&self) -> TimeSignature {
+        self.signature
+    }
+
+    /// Advances the transport by one sample.
+    ///
+    /// Returns which boundaries the sample crossed; all flags are `false`
+    /// between steps.
+    pub fn tick(&mut self) -> TransportTick {
+        if !self.metronome.tick() {
+            return TransportTick::default();
+        }
+
+        // current_step() has already been incremented by tick()
+        let step = self.metronome.current_step() - 1;
+        let expected = self.bar_start + self.steps_into_bar() + 1;
+        if self.started && step != expected {
+            // The metronome jumped: count bars from step 0 again
+            let steps_per_bar = self.steps_per_bar();
+            self.position.bar = step / steps_per_bar;
+            self.bar_start = step - step % steps_per_bar;
+        } else if self.started && step - self.bar_start >= self.steps_per_bar() {
+            self.bar_start = step;
+            self.position.bar += 1;
+            if let Some(signature) = self.pending.take() {
+                self.signature = signature;
+            }
+        }
+        self.started = true;
+
+        let steps_per_beat = self.metronome.steps_per_beat() as u64;
+        let into_bar = step - self.bar_start;
+        self.position.beat = (into_bar / steps_per_beat) as u32;
+        self.position.step = (into_bar % steps_per_beat) as u32;
+
+        let bar = into_bar == 0;
+        if bar && let Some(callback) = &self.on_bar {
+            callback(self.position.bar);
+        }
+        TransportTick {
+            step: true,
+            beat: self.position.step == 0,
+            bar,
+        }
+    }
+
+    /// Returns the position of the step playing now.
+    pub fn position(&self) -> BarPosition {
+        self.position
+    }
+
+    /// Returns the number of steps in a bar of the current time signature.
+    pub fn steps_per_bar(&self) -> u64 {
+        self.signature.beats as u64 * self.metronome.steps_per_beat() as u64
+    }
+
+    /// Returns how many more steps start before the next bar line, including
+    /// its downbeat.
+    ///
+    /// Useful for quantizing a change to the bar: it is 1 on the last step of
+    /// a bar.
+    pub fn steps_to_next_bar(&self) -> u64 {
+        if !self.started {
+            return 1;
+        }
+        self.steps_per_bar() - self.steps_into_bar()
+    }
+
+    /// Steps from the start of the current bar to the step playing now.
+    fn steps_into_bar(&self) -> u64 {
+        let steps_per_beat = self.metronome.steps_per_beat() as u64;
+        self.position.beat as u64 * steps_per_beat + self.position.step as u64
+    }
+
+    /// Returns to the start of bar 0, keeping the tempo and time signature.
+    ///
+    /// A pending time signature change is applied at once.
+    pub fn reset(&mut self) {
+        self.metronome.reset();
+        if let Some(signature) = self.pending.take() {
+            self.signature = signature;
+        }
+        self.bar_start = 0;
+        self.position = BarPosition::default();
+        self.started = false;
+    }
+
+    /// Sets the tempo in BPM (see [`Metronome::set_tempo`]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bpm` is <= 0.
+    pub fn set_tempo(&mut self, bpm: f64) {
+        self.metronome.set_tempo(bpm);
+    }
+
+    /// Glides the tempo to `bpm` over the next `beats` beats (see
+    /// [`Metronome::ramp_tempo`]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bpm` is <= 0 or `beats` is negative.
+    pub fn ramp_tempo(&mut self, bpm: f64, beats: f64, curve: TempoCurve) {
+        self.metronome.ramp_tempo(bpm, beats, curve);
+    }
+
+    /// Returns the current tempo in BPM.
+    pub fn tempo(&self) -> f64 {
+        self.metronome.tempo()
+    }
+
+    /// Returns the underlying metronome.
+    pub fn metronome(&self) -> &Metronome {
+        &self.metronome
+    }
+
+    /// Returns the underlying metronome, e.g. to
+    /// [`sync_to_beat`](Metronome::sync_to_beat) with an external clock.
+    pub fn metronome_mut(&mut self) -> &mut Metronome {
+        &mut self.metronome
+    }
+}
+
+impl fmt::Debug for Transport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Transport")
+            .field("metronome", &self.metronome)
+            .field("signature", &self.signature)
+            .field("pending", &self.pending)
+            .field("position", &self.position)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    // One step per beat, one sample per step
+    const SAMPLE_RATE: u32 = 60;
+    const BPM: f64 = 3600.0;
+
+    /// Ticks to the next step, returning its flags.
+    fn next_step(transport: &mut Transport) -> TransportTick {
+        loop {
+            let tick = transport.tick();
+            if tick.step {
+                return tick;
+            }
+        }
+    }
+
+    #[test]
+    fn test_seven_eight_bars() {
+        let mut transport =
+            Transport::new(BPM, 2, SAMPLE_RATE).with_time_signature(TimeSignature::new(7, 8));
+        assert_eq!(transport.steps_per_bar(), 14);
+
+        let tick = next_step(&mut transport);
+        assert!(tick.bar && tick.beat);
+        assert_eq!(transport.position().to_string(), "1:1:1");
+
+        for _ in 0..13 {
+            let tick = next_step(&mut transport);
+            assert!(!tick.bar);
+        }
+        assert_eq!(
+            transport.position(),
+            BarPosition {
+                bar: 0,
+                beat: 6,
+                step: 1
+            }
+        );
+        assert_eq!(transport.steps_to_next_bar(), 1);
+
+        let tick = next_step(&mut transport);
+        assert!(tick.bar);
+        assert_eq!(transport.position().bar, 1);
+    }
+
+    #[test]
+    fn test_signature_change_waits_for_bar_line() {
+        let bars = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&bars);
+        let mut transport =
+            Transport::new(BPM, 1, SAMPLE_RATE).on_bar(move |bar| seen.lock().unwrap().push(bar));
+
+        next_step(&mut transport);
+        next_step(&mut transport);
+        transport.set_time_signature(TimeSignature::THREE_FOUR);
+        assert_eq!(transport.time_signature(), TimeSignature::FOUR_FOUR);
+        assert_eq!(transport.steps_to_next_bar(), 3);
+
+        // The rest of the 4/4 bar, then bars of three
+        let starts: Vec<bool> = (0..8).map(|_| next_step(&mut transport).bar).collect();
+        assert_eq!(
+            starts,
+            [false, false, true, false, false, true, false, false]
+        );
+        assert_eq!(transport.time_signature(), TimeSignature::THREE_FOUR);
+        assert_eq!(*bars.lock().unwrap(), vec![0, 1, 2]);
+
+        transport.reset();
+        assert_eq!(transport.position(), BarPosition::default());
+        assert!(next_step(&mut transport).bar);
+    }
+
+    #[test]
+    fn test_jump_recounts_bars() {
+        let mut transport = Transport::new(BPM, 1, SAMPLE_RATE);
+        for _ in 0..6 {
+            next_step(&mut transport);
+        }
+        assert_eq!(transport.position().to_string(), "2:2:1");
+
+        // Back to beat 2 of the first bar, then ahead to the third bar
+        transport.metronome_mut().sync_to_beat(BPM, 1.0);
+        assert!(!next_step(&mut transport).bar);
+        assert_eq!(transport.position().to_string(), "1:2:1");
+        transport.metronome_mut().sync_to_beat(BPM, 8.0);
+        assert!(next_step(&mut transport).bar);
+        assert_eq!(transport.position().to_string(), "3:1:1");
+        assert_eq!(transport.steps_to_next_bar(), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "beat unit must be a power of two")]
+    fn test_invalid_unit() {
+        TimeSignature::new(4, 3);
+    }
+}
@@ -3,7 +3,12 @@
 use super::{
     envelope::{Envelope, EnvelopeState},
     frequency::Frequency,
+    mod_envelope::EnvelopeGate,
+    note_sources::NoteSources,
 };
+use crate::core::ParamHandle;
+use crate::synthesis::oscillators::GlideState;
+use crate::synthesis::{Curve, LfoTrigger};
 use crate::{AudioSignal, Pitched, Signal};
 
 /// A voice combines a pitched signal source with an envelope.
@@ -55,6 +60,19 @@ where
 {
     signal: S,
     envelope: E,
+    lfo_triggers: Vec<LfoTrigger>,
+    mod_envelopes: Vec<EnvelopeGate>,
+    note_sources: Option<NoteSources>,
+    held: bool,
+    legato: bool,
+    legato_retrigger: bool,
+    glide_legato_only: bool,
+    portamento_time: f64,
+    portamento: Option<GlideState>,
+    /// Frequency of the current note before pitch bend
+    note_frequency: f64,
+    pitch_bend: f64,
+    mod_wheel: ParamHandle,
 }
 
 impl<const SAMPLE_RATE: u32, S, E> Voice<SAMPLE_RATE, S, E>
@@ -82,7 +100,231 @@ where
     /// let voice = Voice::new(osc, env);
     /// ```
     pub fn new(signal: S, envelope: E) -> Self {
-        Self { signal, envelope }
+        let note_frequency = signal.frequency();
+        Self {
+            signal,
+            envelope,
+            lfo_triggers: Vec::new(),
+            mod_envelopes: Vec::new(),
+            note_sources: None,
+            held: false,
+            legato: false,
+            legato_retrigger: true,
+            glide_legato_only: false,
+            portamento_time: 0.0,
+            portamento: None,
+            note_frequency,
+            pitch_bend: 0.0,
+            mod_wheel: ParamHandle::new(0.0),
+        }
+    }
+
+    /// Retriggers an LFO on every note-on (builder style).
+    ///
+    /// Only LFOs in [`LfoRetrigger::Note`](crate::LfoRetrigger::Note) mode
+    /// restart; the handle comes from [`Lfo::trigger`](crate::Lfo::trigger).
+    /// Add one handle per LFO inside the voice's signal.
+    pub fn with_lfo(mut self, trigger: LfoTrigger) -> Self {
+        self.lfo_triggers.push(trigger);
+        self
+    }
+
+    /// Triggers and releases a [`ModEnvelope`](super::ModEnvelope) along
+    /// with the voice's own envelope (builder style).
+    ///
+    /// The handle comes from [`ModEnvelope::trigger`](super::ModEnvelope::trigger).
+    /// Add one handle per modulation envelope inside the voice's signal.
+    pub fn with_mod_envelope(mut self, gate: EnvelopeGate) -> Self {
+        self.mod_envelopes.push(gate);
+        self
+    }
+
+    /// Publishes each note's number, velocity and random value to `sources`
+    /// (builder style).
+    ///
+    /// Signals built from the sources (key tracking, velocity) and used inside
+    /// this voice's signal then follow the notes it plays.
+    pub fn with_note_sources(mut self, sources: NoteSources) -> Self {
+        self.note_sources = Some(sources);
+        self
+    }
+
+    /// Sets whether legato notes retrigger the envelope (builder style).
+    ///
+    /// A note is legato when it starts while the previous note is still held
+    /// and sounding. With retriggering off (mono-synth style), a legato note
+    /// only changes pitch and the envelope carries on from where it is.
+    /// Defaults to `true`: every note restarts the envelope.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{ADSR, SineOscillator, Signal};
+    /// use earworm::music::{Voice, envelope::EnvelopeState};
+    ///
+    /// let osc = SineOscillator::<44100>::new(440.0);
+    /// let env = ADSR::new(0.01, 0.1, 0.7, 0.3, 44100.0);
+    /// let mut voice = Voice::new(osc, env).with_legato_retrigger(false);
+    ///
+    /// voice.note_on(60u8, 0.8);
+    /// for _ in 0..44100 {
+    ///     voice.next_sample();
+    /// }
+    /// voice.note_on(62u8, 0.8); // played before releasing 60
+    /// assert!(voice.is_legato());
+    /// assert_eq!(voice.envelope_state(), EnvelopeState::Sustain);
+    /// ```
+    pub fn with_legato_retrigger(mut self, retrigger: bool) -> Self {
+        self.legato_retrigger = retrigger;
+        self
+    }
+
+    /// Sets whether the signal only glides between legato notes (builder style).
+    ///
+    /// When enabled, non-legato notes move the signal straight to their pitch
+    /// with [`Pitched::jump_to_frequency`], so a [`Glide`](crate::Glide) only
+    /// slides between overlapping notes. Defaults to `false`.
+    pub fn with_glide_legato_only(mut self, legato_only: bool) -> Self {
+        self.glide_legato_only = legato_only;
+        self
+    }
+
+    /// Enables portamento (builder style).
+    ///
+    /// See [`set_portamento`](Self::set_portamento).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{ADSR, Curve, SawtoothOscillator, Signal};
+    /// use earworm::music::Voice;
+    ///
+    /// let osc = SawtoothOscillator::<44100>::new(220.0);
+    /// let env = ADSR::new(0.01, 0.1, 0.7, 0.3, 44100.0);
+    /// let mut voice = Voice::new(osc, env).with_portamento(0.08, Curve::Linear);
+    ///
+    /// voice.note_on(220.0, 0.8);
+    /// voice.next_sample();
+    /// voice.note_on(330.0, 0.8); // slides up over 80 ms
+    /// voice.next_sample();
+    /// assert!(voice.is_gliding());
+    /// ```
+    pub fn with_portamento(mut self, glide_time: f64, curve: Curve) -> Self {
+        self.set_portamento(glide_time, curve);
+        self
+    }
+
+    /// Sets the portamento time and curve.
+    ///
+    /// When a note starts while the voice is still sounding, the signal's
+    /// frequency slides from the old pitch to the new one over `glide_time`
+    /// seconds, in the log-frequency domain and shaped by `curve`. Notes on a
+    /// silent voice start at their pitch. Combined with
+    /// [`with_glide_legato_only`](Self::with_glide_legato_only), only legato
+    /// notes glide.
+    ///
+    /// # Arguments
+    ///
+    /// * `glide_time` - Slide time in seconds; 0.0 disables portamento
+    /// * `curve` - Shape of the slide
+    pub fn set_portamento(&mut self, glide_time: f64, curve: Curve) {
+        self.portamento_time = glide_time.max(0.0);
+        if self.portamento_time == 0.0 {
+            self.portamento = None;
+        } else {
+            let mut state = GlideState::new(self.note_frequency, curve);
+            if let Some(current) = &self.portamento {
+                state.jump_to(current.target());
+            }
+            self.portamento = Some(state);
+        }
+    }
+
+    /// Returns the portamento time in seconds (0.0 when disabled).
+    pub fn portamento_time(&self) -> f64 {
+        self.portamento_time
+    }
+
+    /// Returns true while the voice is sliding between notes.
+    pub fn is_gliding(&self) -> bool {
+        self.portamento.as_ref().is_some_and(GlideState::is_gliding)
+    }
+
+    /// Bends the pitch of the current and future notes by `semitones`.
+    ///
+    /// The bend applies on top of the note's pitch (and any portamento), takes
+    /// effect immediately, and stays until changed. Note sources still report
+    /// the unbent note.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{ADSR, SineOscillator};
+    /// use earworm::music::Voice;
+    ///
+    /// const SAMPLE_RATE: u32 = 44100;
+    ///
+    /// let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+    /// let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+    /// let mut voice = Voice::new(osc, env);
+    ///
+    /// voice.note_on(220.0, 0.8);
+    /// voice.set_pitch_bend(12.0);
+    /// assert!((voice.frequency() - 440.0).abs() < 1e-9);
+    /// ```
+    pub fn set_pitch_bend(&mut self, semitones: f64) {
+        self.pitch_bend = semitones;
+        if !self.is_gliding() {
+            self.signal
+                .set_frequency(self.note_frequency * self.bend_ratio());
+        }
+    }
+
+    /// Returns the pitch bend in semitones.
+    pub fn pitch_bend(&self) -> f64 {
+        self.pitch_bend
+    }
+
+    /// Uses `handle` as this voice's modulation wheel (builder style).
+    ///
+    /// Route clones of the same handle into the voice's signal (vibrato depth,
+    /// filter cutoff, ...) so [`set_mod_amount`](Self::set_mod_amount) reaches
+    /// them. [`VoiceAllocator`](super::VoiceAllocator) shares one handle
+    /// across all of its voices.
+    pub fn with_mod_wheel(mut self, handle: ParamHandle) -> Self {
+        self.set_mod_wheel(handle);
+        self
+    }
+
+    /// Uses `handle` as this voice's modulation wheel.
+    pub fn set_mod_wheel(&mut self, handle: ParamHandle) {
+        self.mod_wheel = handle;
+    }
+
+    /// Returns the modulation wheel as a signal (0.0 to 1.0).
+    pub fn mod_wheel(&self) -> ParamHandle {
+        self.mod_wheel.clone()
+    }
+
+    /// Sets the modulation wheel position, clamped to 0.0 to 1.0.
+    pub fn set_mod_amount(&mut self, amount: f64) {
+        self.mod_wheel.set(amount.clamp(0.0, 1.0));
+    }
+
+    /// Returns the modulation wheel position.
+    pub fn mod_amount(&self) -> f64 {
+        self.mod_wheel.get()
+    }
+
+    /// Frequency multiplier for the current pitch bend.
+    fn bend_ratio(&self) -> f64 {
+        (self.pitch_bend / 12.0).exp2()
+    }
+
+    /// Returns true if the most recent note started legato, while the
+    /// previous note was still held.
+    pub fn is_legato(&self) -> bool {
+        self.legato
     }
 
     /// Triggers a note with the given pitch and velocity.
@@ -114,8 +356,41 @@ where
     /// ```
     pub fn note_on(&mut self, pitch: impl Into<Frequency>, velocity: f64) {
         let freq = pitch.into();
-        self.signal.set_frequency(freq.as_f64());
-        self.envelope.trigger(velocity);
+        self.legato = self.held && self.envelope.is_active();
+        self.held = true;
+        if let Some(sources) = &self.note_sources {
+            sources.note_on(freq.as_f64(), velocity);
+        }
+        let glide = if self.glide_legato_only {
+            self.legato
+        } else {
+            self.envelope.is_active()
+        };
+        self.note_frequency = freq.as_f64();
+        let bent = self.note_frequency * self.bend_ratio();
+        if let Some(portamento) = &mut self.portamento {
+            if glide {
+                let length = (self.portamento_time * SAMPLE_RATE as f64).round() as usize;
+                portamento.glide_to(self.note_frequency, length);
+            } else {
+                portamento.jump_to(self.note_frequency);
+                self.signal.jump_to_frequency(bent);
+            }
+        } else if self.glide_legato_only && !self.legato {
+            self.signal.jump_to_frequency(bent);
+        } else {
+            self.signal.set_frequency(bent);
+        }
+        if !self.legato || self.legato_retrigger {
+            self.signal.restart();
+            self.envelope.trigger(velocity);
+            for gate in &self.mod_envelopes {
+                gate.open();
+            }
+        }
+        for trigger in &self.lfo_triggers {
+            trigger.fire();
+        }
     }
 
     /// Releases the note, starting the envelope's release phase.
@@ -137,7 +412,12 @@ where
     /// voice.note_off();
     /// ```
     pub fn note_off(&mut self) {
+        self.held = false;
+        self.signal.release();
         self.envelope.release();
+        for gate in &self.mod_envelopes {
+            gate.close();
+        }
     }
 
     /// Returns true if the voice is currently active.
@@ -175,6 +455,11 @@ where
         self.envelope.is_active()
     }
 
+    /// Returns the frequency the voice's signal is playing, in Hz.
+    pub fn frequency(&self) -> f64 {
+        self.signal.frequency()
+    }
+
     /// Returns the current envelope level.
     ///
     /// This is useful for voice stealing strategies that need to compare
@@ -257,10 +542,21 @@ where
     E: Envelope,
 {
     fn next_sample(&mut self) -> f64 {
+        if let Some(portamento) = &mut self.portamento
+            && portamento.is_gliding()
+        {
+            let ratio = (self.pitch_bend / 12.0).exp2();
+            self.signal
+                .set_frequency(portamento.next_frequency() * ratio);
+        }
         let signal_sample = self.signal.next_sample();
         let envelope_sample = self.envelope.next_sample();
         signal_sample * envelope_sample
     }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.signal.prepare(max_block_size, sample_rate);
+    }
 }
 
 impl<const SAMPLE_RATE: u32, S, E> AudioSignal<SAMPLE_RATE> for Voice<SAMPLE_RATE, S, E>
@@ -367,4 +663,223 @@ mod tests {
         }
         assert!((final_sample).abs() < 0.01); // Should be near zero after release
     }
+
+    #[test]
+    fn test_note_on_retriggers_lfos() {
+        use crate::{Lfo, LfoRetrigger, LfoWaveform};
+
+        let lfo = Lfo::<SAMPLE_RATE>::new(LfoWaveform::Saw, 5.0)
+            .with_retrigger(LfoRetrigger::Note)
+            .with_crossfade(0.0);
+        let trigger = lfo.trigger();
+        let mut probe = Lfo::<SAMPLE_RATE>::new(LfoWaveform::Saw, 5.0)
+            .with_retrigger(LfoRetrigger::Note)
+            .with_crossfade(0.0);
+        let probe_trigger = probe.trigger();
+
+        let osc = SineOscillator::<SAMPLE_RATE>::with_frequency_mod(440.0, lfo);
+        let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+        let mut voice = Voice::new(osc, env)
+            .with_lfo(trigger)
+            .with_lfo(probe_trigger);
+
+        for _ in 0..100 {
+            voice.next_sample();
+            probe.next_sample();
+        }
+        voice.note_on(440.0, 0.8);
+        assert_eq!(probe.next_sample(), -1.0);
+    }
+
+    #[test]
+    fn test_legato_detection() {
+        let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+        let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+        let mut voice = Voice::new(osc, env);
+
+        voice.note_on(60u8, 0.8);
+        assert!(!voice.is_legato());
+        voice.note_on(62u8, 0.8);
+        assert!(voice.is_legato());
+
+        // Released notes aren't legato, even while the release is sounding
+        voice.note_off();
+        voice.note_on(64u8, 0.8);
+        assert!(!voice.is_legato());
+    }
+
+    #[test]
+    fn test_legato_retrigger_default_restarts_envelope() {
+        let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+        let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+        let mut voice = Voice::new(osc, env);
+
+        voice.note_on(60u8, 0.8);
+        for _ in 0..SAMPLE_RATE {
+            voice.next_sample();
+        }
+        voice.note_on(62u8, 0.8);
+        assert_eq!(voice.envelope_state(), EnvelopeState::Attack);
+    }
+
+    #[test]
+    fn test_glide_legato_only() {
+        use crate::Glide;
+
+        let osc = Glide::new(SineOscillator::<SAMPLE_RATE>::new(440.0), 0.1);
+        let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+        let mut voice = Voice::new(osc, env).with_glide_legato_only(true);
+
+        // The first note jumps, the legato one glides
+        voice.note_on(220.0, 0.8);
+        voice.next_sample();
+        assert_eq!(voice.signal.current_frequency(), 220.0);
+        voice.note_on(330.0, 0.8);
+        voice.next_sample();
+        assert!(voice.signal.is_gliding());
+
+        // A detached note jumps again
+        voice.note_off();
+        voice.note_on(440.0, 0.8);
+        assert!(!voice.signal.is_gliding());
+        assert_eq!(voice.signal.current_frequency(), 440.0);
+    }
+
+    #[test]
+    fn test_portamento_slides_between_overlapping_notes() {
+        let osc = SineOscillator::<1000>::new(100.0);
+        let env = ADSR::new(0.01, 0.1, 0.7, 0.3, 1000.0);
+        let mut voice = Voice::new(osc, env).with_portamento(0.1, Curve::Linear);
+        assert_eq!(voice.portamento_time(), 0.1);
+
+        // First note on a silent voice starts at its pitch
+        voice.note_on(100.0, 0.8);
+        assert!(!voice.is_gliding());
+        voice.next_sample();
+        assert_eq!(voice.frequency(), 100.0);
+
+        // Halfway through an octave-and-more glide is the geometric mean
+        voice.note_on(400.0, 0.8);
+        for _ in 0..50 {
+            voice.next_sample();
+        }
+        assert!((voice.frequency() - 200.0).abs() < 1e-9);
+        for _ in 0..50 {
+            voice.next_sample();
+        }
+        assert!(!voice.is_gliding());
+        assert!((voice.frequency() - 400.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_portamento_legato_only() {
+        let osc = SineOscillator::<1000>::new(100.0);
+        let env = ADSR::new(0.01, 0.1, 0.7, 0.3, 1000.0);
+        let mut voice = Voice::new(osc, env)
+            .with_portamento(0.1, Curve::Linear)
+            .with_glide_legato_only(true);
+
+        voice.note_on(100.0, 0.8);
+        voice.next_sample();
+        voice.note_off();
+        // Released but still sounding: not legato, so no glide
+        voice.note_on(300.0, 0.8);
+        assert!(!voice.is_gliding());
+        assert_eq!(voice.frequency(), 300.0);
+
+        voice.note_on(150.0, 0.8);
+        voice.next_sample();
+        assert!(voice.is_gliding());
+    }
+
+    #[test]
+    fn test_zero_portamento_disables() {
+        let osc = SineOscillator::<1000>::new(100.0);
+        let env = ADSR::new(0.01, 0.1, 0.7, 0.3, 1000.0);
+        let mut voice = Voice::new(osc, env).with_portamento(0.1, Curve::Linear);
+        voice.set_portamento(0.0, Curve::Linear);
+        voice.note_on(100.0, 0.8);
+        voice.next_sample();
+        voice.note_on(200.0, 0.8);
+        assert!(!voice.is_gliding());
+        assert_eq!(voice.frequency(), 200.0);
+    }
+
+    #[test]
+    fn test_pitch_bend_applies_to_current_and_new_notes() {
+        let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+        let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+        let mut voice = Voice::new(osc, env);
+
+        voice.note_on(440.0, 0.8);
+        voice.set_pitch_bend(-2.0);
+        let expected = 440.0 * (-2.0f64 / 12.0).exp2();
+        assert!((voice.frequency() - expected).abs() < 1e-9);
+
+        voice.note_on(220.0, 0.8);
+        assert!((voice.frequency() - expected / 2.0).abs() < 1e-9);
+
+        voice.set_pitch_bend(0.0);
+        assert!((voice.frequency() - 220.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pitch_bend_during_portamento() {
+        let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+        let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+        let mut voice = Voice::new(osc, env).with_portamento(0.01, Curve::Linear);
+
+        voice.note_on(220.0, 0.8);
+        voice.next_sample();
+        voice.note_on(440.0, 0.8);
+        voice.set_pitch_bend(12.0);
+        for _ in 0..1000 {
+            voice.next_sample();
+        }
+        assert!(!voice.is_gliding());
+        assert!((voice.frequency() - 880.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_mod_wheel_is_shared_and_clamped() {
+        let wheel = ParamHandle::new(0.0);
+        let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+        let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+        let mut voice = Voice::new(osc, env).with_mod_wheel(wheel.clone());
+
+        voice.set_mod_amount(0.25);
+        assert_eq!(wheel.get(), 0.25);
+        voice.set_mod_amount(3.0);
+        assert_eq!(voice.mod_amount(), 1.0);
+        assert!(voice.mod_wheel().same_as(&wheel));
+    }
+
+    #[test]
+    fn test_note_on_restarts_sampler() {
+        let sampler = crate::Sampler::<SAMPLE_RATE>::new(vec![1.0; 100], SAMPLE_RATE, 69);
+        let env = ADSR::new(0.0, 0.0, 1.0, 0.0, SAMPLE_RATE as f64);
+        let mut voice = Voice::new(sampler, env);
+
+        voice.note_on(69u8, 1.0);
+        for _ in 0..200 {
+            voice.next_sample();
+        }
+        assert_eq!(voice.next_sample(), 0.0);
+
+        voice.note_on(69u8, 1.0);
+        assert_eq!(voice.next_sample(), 1.0);
+    }
+
+    #[test]
+    fn test_voice_drives_mod_envelope_gate() {
+        let gate = EnvelopeGate::default();
+        let osc = SineOscillator::<SAMPLE_RATE>::new(440.0);
+        let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+        let mut voice = Voice::new(osc, env).with_mod_envelope(gate.clone());
+
+        voice.note_on(440.0, 0.8);
+        assert!(gate.is_open());
+        voice.note_off();
+        assert!(!gate.is_open());
+    }
 }
@@ -0,0 +1,254 @@
+//! Where the device's playback actually is, for syncing to heard audio.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// The state written by the audio callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct Snapshot {
+    /// Frames rendered before the latest callback
+    frame: u64,
+    /// Frames the latest callback rendered
+    frames: u64,
+    /// Time from the latest callback to its first frame reaching the DAC
+    latency: Duration,
+    /// When the latest callback ran, relative to the clock's epoch
+    at: Duration,
+}
+
+struct Shared {
+    epoch: Instant,
+    sample_rate: AtomicU32,
+    // Sequence lock: odd while the callback is writing
+    sequence: AtomicU64,
+    frame: AtomicU64,
+    frames: AtomicU64,
+    latency_nanos: AtomicU64,
+    at_nanos: AtomicU64,
+}
+
+/// A handle on an [`AudioOutput`](super::AudioOutput)'s timing, for keeping
+/// visuals and external gear in step with what is heard.
+///
+/// Signals run ahead of the speakers by the output latency: a metronome
+/// step rendered now is heard a buffer or more later. Every audio callback
+/// records how many frames have been rendered and the latency the driver
+/// reports (from cpal's stream timestamps), and the clock uses them to say
+/// which frame is leaving the DAC right now ([`heard_frame`](Self::heard_frame))
+/// and when a given frame will ([`instant_of`](Self::instant_of)).
+///
+/// Frame numbers count from the start of the stream, the same as a
+/// [`Metronome`](crate::music::Metronome) or sequencer ticked once per frame
+/// from the first callback. So a UI can convert the heard frame to a beat,
+/// and MIDI sent when a step is rendered can be held back by
+/// [`latency`](Self::latency) to land with the audio.
+///
+/// Handles are cheap to clone and safe to read from any thread. Reading
+/// never blocks the audio thread.
+///
+/// # Examples
+///
+/// ```no_run
+/// use earworm::SineOscillator;
+/// use earworm::playback::{AudioOutput, OutputOptions};
+///
+/// let output = AudioOutput::start(SineOscillator::<48000>::new(440.0), &OutputOptions::default())?;
+/// let clock = output.clock();
+///
+/// // On the UI thread: at 120 BPM, which beat is being heard?
+/// let beat = clock.heard_seconds() * 120.0 / 60.0;
+/// println!("beat {:.2}, latency {:?}", beat, clock.latency());
+/// # Ok::<(), earworm::playback::PlaybackError>(())
+/// ```
+#[derive(Clone)]
+pub struct StreamClock {
+    shared: Arc<Shared>,
+}
+
+impl StreamClock {
+    pub(super) fn new(sample_rate: u32) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                epoch: Instant::now(),
+                sample_rate: AtomicU32::new(sample_rate),
+                sequence: AtomicU64::new(0),
+                frame: AtomicU64::new(0),
+                frames: AtomicU64::new(0),
+                latency_nanos: AtomicU64::new(0),
+                at_nanos: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Records a callback that ran at `at` and is about to render `frames`
+    /// frames, the first of which reaches the DAC `latency` later. Called
+    /// from the audio thread only.
+    pub(super) fn record(&self, at: Instant, frames: u64, latency: Duration) {
+        let shared = &*self.shared;
+        let previous = self.snapshot();
+        shared.sequence.fetch_add(1, Ordering::AcqRel);
+        shared
+            .frame
+            .store(previous.frame + previous.frames, Ordering::Relaxed);
+        shared.frames.store(frames, Ordering::Relaxed);
+        shared
+            .latency_nanos
+            .store(latency.as_nanos() as u64, Ordering::Relaxed);
+        let since_epoch = at.saturating_duration_since(shared.epoch);
+        shared
+            .at_nanos
+            .store(since_epoch.as_nanos() as u64, Ordering::Relaxed);
+        shared.sequence.fetch_add(1, Ordering::Release);
+    }
+
+    /// Reads a consistent copy of the callback state.
+    fn snapshot(&self) -> Snapshot {
+        let shared = &*self.shared;
+        loop {
+            let before = shared.sequence.load(Ordering::Acquire);
+            if !before.is_multiple_of(2) {
+                std::hint::spin_loop();
+                continue;
+            }
+            let snapshot = Snapshot {
+                frame: shared.frame.load(Ordering::Relaxed),
+                frames: shared.frames.load(Ordering::Relaxed),
+                latency: Duration::from_nanos(shared.latency_nanos.load(Ordering::Relaxed)),
+                at: Duration::from_nanos(shared.at_nanos.load(Ordering::Relaxed)),
+            };
+            if shared.sequence.load(Ordering::Acquire) == before {
+                return snapshot;
+            }
+        }
+    }
+
+    /// Sets the device rate frames are counted at, when the stream is
+    /// opened.
+    pub(super) fn set_sample_rate(&self, sample_rate: u32) {
+        self.shared
+            .sample_rate
+            .store(sample_rate, Ordering::Relaxed);
+    }
+
+    /// Returns the device's sample rate in Hz, which frames are counted at.
+    pub fn sample_rate(&self) -> u32 {
+        self.shared.sample_rate.load(Ordering::Relaxed)
+    }
+
+    /// Returns the output latency the driver last reported: how long after
+    /// a callback its first frame is heard.
+    ///
+    /// Zero until the first callback, and on drivers that don't report it.
+    pub fn latency(&self) -> Duration {
+        self.snapshot().latency
+    }
+
+    /// Returns the output latency in frames.
+    pub fn latency_frames(&self) -> u64 {
+        (self.latency().as_secs_f64() * self.sample_rate() as f64).round() as u64
+    }
+
+    /// Returns the number of frames handed to the device so far.
+    pub fn rendered_frames(&self) -> u64 {
+        let snapshot = self.snapshot();
+        snapshot.frame + snapshot.frames
+    }
+
+    /// Returns the frame being heard now.
+    pub fn heard_frame(&self) -> u64 {
+        self.heard_frame_at(Instant::now())
+    }
+
+    /// Returns the time in seconds since the first frame was heard.
+    pub fn heard_seconds(&self) -> f64 {
+        self.heard_frame() as f64 / self.sample_rate() as f64
+    }
+
+    /// Returns the frame heard at `now`, estimated from the latest callback.
+    ///
+    /// Between callbacks the position advances with the wall clock; it never
+    /// goes past the frames rendered so far, and is 0 before the first frame
+    /// plays.
+    pub fn heard_frame_at(&self, now: Instant) -> u64 {
+        let snapshot = self.snapshot();
+        if snapshot.frames == 0 && snapshot.frame == 0 {
+            return 0;
+        }
+        let heard_at = self.shared.epoch + snapshot.at + snapshot.latency;
+        let rate = self.sample_rate() as f64;
+        let heard = match now.checked_duration_since(heard_at) {
+            Some(played) => snapshot.frame + (played.as_secs_f64() * rate) as u64,
+            // Still hearing earlier buffers
+            None => {
+                let ahead = heard_at.duration_since(now);
+                snapshot
+                    .frame
+                    .saturating_sub((ahead.as_secs_f64() * rate).ceil() as u64)
+            }
+        };
+        heard.min(snapshot.frame + snapshot.frames)
+    }
+
+    /// Returns when `frame` will be (or was) heard, estimated from the
+    /// latest callback.
+    ///
+    /// Schedule MIDI or visual events for a rendered frame at this instant to
+    /// line them up with the audio.
+    pub fn instant_of(&self, frame: u64) -> Instant {
+        let snapshot = self.snapshot();
+        let heard = self.shared.epoch + snapshot.at + snapshot.latency;
+        let rate = self.sample_rate() as f64;
+        if frame >= snapshot.frame {
+            heard + Duration::from_secs_f64((frame - snapshot.frame) as f64 / rate)
+        } else {
+            heard
+                .checked_sub(Duration::from_secs_f64(
+                    (snapshot.frame - frame) as f64 / rate,
+                ))
+                .unwrap_or(heard)
+        }
+    }
+}
+
+impl std::fmt::Debug for StreamClock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let snapshot = self.snapshot();
+        f.debug_struct("StreamClock")
+            .field("sample_rate", &self.sample_rate())
+            .field("rendered_frames", &(snapshot.frame + snapshot.frames))
+            .field("latency", &snapshot.latency)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heard_frame_trails_by_latency() {
+        let clock = StreamClock::new(1000);
+        let start = clock.shared.epoch;
+        assert_eq!(clock.heard_frame_at(start), 0);
+
+        // Two 100-frame buffers, each heard 50ms after its callback
+        let latency = Duration::from_millis(50);
+        clock.record(start, 100, latency);
+        clock.record(start + Duration::from_millis(100), 100, latency);
+        assert_eq!(clock.rendered_frames(), 200);
+        assert_eq!(clock.latency_frames(), 50);
+
+        let at = |ms| start + Duration::from_millis(ms);
+        // 120ms: the second buffer's callback ran at 100ms, so frame 100 is
+        // heard at 150ms and we are 30 frames before it
+        assert_eq!(clock.heard_frame_at(at(120)), 70);
+        assert_eq!(clock.heard_frame_at(at(170)), 120);
+        // Capped at what has been rendered
+        assert_eq!(clock.heard_frame_at(at(1000)), 200);
+
+        assert_eq!(clock.instant_of(100), at(150));
+        assert_eq!(clock.instant_of(150), at(200));
+        assert_eq!(clock.instant_of(0), at(50));
+    }
+}
@@ -0,0 +1,59 @@
+//! Error type for audio playback.
+
+use std::fmt;
+
+/// Errors that can occur while opening or running an audio output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlaybackError {
+    /// The requested audio host/backend is not available on this system
+    HostUnavailable(String),
+    /// No output device matched the request
+    NoDevice(String),
+    /// The device does not support the requested configuration
+    UnsupportedConfig(String),
+    /// The backend failed to build or start the stream
+    Stream(String),
+}
+
+impl fmt::Display for PlaybackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlaybackError::HostUnavailable(s) => write!(f, "audio host unavailable: {}", s),
+            PlaybackError::NoDevice(s) => write!(f, "no output device: {}", s),
+            PlaybackError::UnsupportedConfig(s) => write!(f, "unsupported output config: {}", s),
+            PlaybackError::Stream(s) => write!(f, "audio stream error: {}", s),
+        }
+    }
+}
+
+impl std::error::Error for PlaybackError {}
+
+impl From<cpal::BuildStreamError> for PlaybackError {
+    fn from(err: cpal::BuildStreamError) -> Self {
+        PlaybackError::Stream(err.to_string())
+    }
+}
+
+impl From<cpal::PlayStreamError> for PlaybackError {
+    fn from(err: cpal::PlayStreamError) -> Self {
+        PlaybackError::Stream(err.to_string())
+    }
+}
+
+impl From<cpal::DevicesError> for PlaybackError {
+    fn from(err: cpal::DevicesError) -> Self {
+        PlaybackError::NoDevice(err.to_string())
+    }
+}
+
+impl From<cpal::SupportedStreamConfigsError> for PlaybackError {
+    fn from(err: cpal::SupportedStreamConfigsError) -> Self {
+        PlaybackError::UnsupportedConfig(err.to_string())
+    }
+}
+
+impl From<cpal::DefaultStreamConfigError> for PlaybackError {
+    fn from(err: cpal::DefaultStreamConfigError) -> Self {
+        PlaybackError::UnsupportedConfig(err.to_string())
+    }
+}
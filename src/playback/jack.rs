@@ -0,0 +1,81 @@
+//! JACK client naming and port connections.
+//!
+//! cpal's generic host API can't name the JACK client or choose where its
+//! ports connect, so with the `jack` feature the output device is created
+//! through cpal's JACK host directly and the connections are made with a
+//! short-lived helper client.
+
+use super::{OutputOptions, PlaybackError};
+use cpal::platform::JackDevice;
+
+/// Client name used when [`OutputOptions::client_name`] is not set.
+const DEFAULT_CLIENT_NAME: &str = "earworm";
+
+/// Creates the JACK output device for `options`.
+///
+/// Ports are connected to the system playback ports automatically unless
+/// [`OutputOptions::ports`] names others.
+pub(super) fn output_device(options: &OutputOptions) -> Result<cpal::Device, PlaybackError> {
+    let name = options
+        .client_name
+        .as_deref()
+        .unwrap_or(DEFAULT_CLIENT_NAME);
+    JackDevice::default_output_device(name, options.ports.is_empty(), false)
+        .map(cpal::Device::from)
+        .map_err(PlaybackError::HostUnavailable)
+}
+
+/// Connects output port `n` of `client` to `ports[n]`.
+///
+/// `client` is the name JACK gave the stream's client, which is the device
+/// name cpal reports.
+pub(super) fn connect_ports(client: &str, ports: &[String]) -> Result<(), PlaybackError> {
+    if ports.is_empty() {
+        return Ok(());
+    }
+
+    let (helper, _) = jack::Client::new(
+        &format!("{}_connect", client),
+        jack::ClientOptions::NO_START_SERVER,
+    )
+    .map_err(|err| PlaybackError::HostUnavailable(err.to_string()))?;
+
+    for (index, destination) in ports.iter().enumerate() {
+        let source = output_port_name(client, index);
+        helper
+            .connect_ports_by_name(&source, destination)
+            .map_err(|err| {
+                PlaybackError::Stream(format!(
+                    "could not connect {} to {}: {}",
+                    source, destination, err
+                ))
+            })?;
+    }
+    Ok(())
+}
+
+/// Returns the full name of the stream's output port for `channel`.
+fn output_port_name(client: &str, channel: usize) -> String {
+    format!("{}:out_{}", client, channel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_port_names() {
+        assert_eq!(output_port_name("earworm_out", 0), "earworm_out:out_0");
+        assert_eq!(output_port_name("synth_out", 3), "synth_out:out_3");
+    }
+
+    #[test]
+    fn test_no_ports_needs_no_server() {
+        assert!(connect_ports("earworm_out", &[]).is_ok());
+    }
+
+    #[test]
+    fn test_jack_host_is_compiled_in() {
+        assert!(cpal::available_hosts().contains(&cpal::HostId::Jack));
+    }
+}
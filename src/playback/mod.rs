@@ -0,0 +1,34 @@
+//! Real-time audio playback.
+//!
+//! This module opens an audio device and plays any [`AudioSignal`](crate::AudioSignal)
+//! on it. It wraps [cpal](https://docs.rs/cpal) and lets you choose the host
+//! backend (the platform default, JACK, or any other cpal host by name), the
+//! output device, the channel count and which hardware outputs the signal's
+//! channels go to, and negotiates the buffer size, sample format and sample
+//! rate with the device. A [`StreamClock`] reports the
+//! output latency and which frame is being heard, so UIs and MIDI output can
+//! follow the audio rather than run ahead of it.
+//!
+//! Requires the `playback` feature. The `jack` feature adds the JACK host,
+//! with a choice of client name and of the ports the output connects to.
+
+mod clock;
+mod error;
+#[cfg(all(
+    feature = "jack",
+    any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd"
+    )
+))]
+mod jack;
+mod output;
+
+pub use clock::StreamClock;
+pub use error::PlaybackError;
+pub use output::{
+    AudioOutput, Backend, OutputFormat, OutputOptions, StreamInfo, available_backends,
+    output_devices,
+};
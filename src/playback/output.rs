@@ -0,0 +1,1113 @@
+//! Real-time audio output built on cpal.
+
+use super::{PlaybackError, StreamClock};
+use crate::core::{AudioFrameSignal, AudioSignal, LogLevel, RtLogger};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{
+    BufferSize, FromSample, Sample, SampleFormat, SampleRate, SizedSample, StreamConfig,
+    SupportedBufferSize,
+};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Audio host (driver API) to open the output on.
+///
+/// On Linux, [`Backend::Jack`] routes earworm into a JACK graph, where its
+/// output ports can be connected to mixers, recorders, or other software.
+/// PipeWire exposes the JACK API, so this also works under PipeWire via
+/// `pw-jack`. The JACK host is only compiled in with the `jack` feature
+/// (Linux and the BSDs), which also enables
+/// [`OutputOptions::with_client_name`] and [`OutputOptions::with_ports`].
+/// Without it, selecting this backend fails with
+/// [`PlaybackError::HostUnavailable`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Backend {
+    /// The platform's default host (ALSA on Linux, CoreAudio on macOS, WASAPI on Windows)
+    #[default]
+    Default,
+    /// The JACK Audio Connection Kit (also PipeWire's JACK interface)
+    Jack,
+    /// Any host by its cpal name (case-insensitive), e.g. `"ALSA"` or `"ASIO"`
+    Named(String),
+}
+
+impl Backend {
+    fn host(&self) -> Result<cpal::Host, PlaybackError> {
+        let name = match self {
+            Backend::Default => return Ok(cpal::default_host()),
+            Backend::Jack => "JACK",
+            Backend::Named(name) => name.as_str(),
+        };
+
+        let id = cpal::available_hosts()
+            .into_iter()
+            .find(|id| id.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| PlaybackError::HostUnavailable(name.to_string()))?;
+
+        cpal::host_from_id(id).map_err(|err| PlaybackError::HostUnavailable(err.to_string()))
+    }
+}
+
+/// Sample format of the stream written to the device.
+///
+/// The signal is always computed in `f64` and converted as it is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// 32-bit float
+    #[default]
+    F32,
+    /// 64-bit float
+    F64,
+    /// Signed 32-bit integer
+    I32,
+    /// Signed 16-bit integer
+    I16,
+    /// Unsigned 16-bit integer
+    U16,
+    /// Unsigned 8-bit integer
+    U8,
+}
+
+impl OutputFormat {
+    /// The formats tried when none are requested, best first.
+    pub const PREFERRED: [OutputFormat; 6] = [
+        OutputFormat::F32,
+        OutputFormat::I32,
+        OutputFormat::I16,
+        OutputFormat::F64,
+        OutputFormat::U16,
+        OutputFormat::U8,
+    ];
+
+    fn to_cpal(self) -> SampleFormat {
+        match self {
+            OutputFormat::F32 => SampleFormat::F32,
+            OutputFormat::F64 => SampleFormat::F64,
+            OutputFormat::I32 => SampleFormat::I32,
+            OutputFormat::I16 => SampleFormat::I16,
+            OutputFormat::U16 => SampleFormat::U16,
+            OutputFormat::U8 => SampleFormat::U8,
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_cpal(), f)
+    }
+}
+
+/// Options for opening an [`AudioOutput`].
+///
+/// # Examples
+///
+/// ```
+/// use earworm::playback::{Backend, OutputOptions};
+///
+/// // Four output ports on a JACK client named "synth"
+/// let options = OutputOptions::default()
+///     .with_backend(Backend::Jack)
+///     .with_client_name("synth")
+///     .with_channels(4);
+/// assert_eq!(options.channels, Some(4));
+/// ```
+#[derive(Debug, Clone)]
+pub struct OutputOptions {
+    /// Host to open the device on
+    pub backend: Backend,
+    /// Output device name; `None` selects the host's default device
+    pub device: Option<String>,
+    /// Number of output channels (ports); `None` uses the device default
+    pub channels: Option<u16>,
+    /// Where to report stream errors; `None` prints them to stderr
+    pub logger: Option<RtLogger>,
+    /// Rebuild the stream when the device disappears or, with no device
+    /// named, when the system default output changes (on by default)
+    pub reconnect: bool,
+    /// Frames per device buffer; `None` lets the driver choose
+    pub buffer_size: Option<u32>,
+    /// Device rates to fall back to, in order, when the device can't run at
+    /// the signal's rate; the signal is then resampled on the fly
+    pub sample_rates: Vec<u32>,
+    /// Sample formats to accept, in order of preference; empty tries
+    /// [`OutputFormat::PREFERRED`]
+    pub sample_formats: Vec<OutputFormat>,
+    /// Device channel (0-based) for each signal channel; `None` writes
+    /// signal channel `n` to device channel `n`
+    pub channel_map: Option<Vec<u16>>,
+    /// JACK client name; `None` uses `"earworm"`. JACK appends `_out`, and
+    /// a number if the name is taken
+    pub client_name: Option<String>,
+    /// JACK ports to connect output channel `n` to, in channel order; empty
+    /// connects to the system playback ports
+    pub ports: Vec<String>,
+}
+
+impl Default for OutputOptions {
+    fn default() -> Self {
+        Self {
+            backend: Backend::Default,
+            device: None,
+            channels: None,
+            logger: None,
+            reconnect: true,
+            buffer_size: None,
+            sample_rates: Vec::new(),
+            sample_formats: Vec::new(),
+            channel_map: None,
+            client_name: None,
+            ports: Vec::new(),
+        }
+    }
+}
+
+impl OutputOptions {
+    /// Sets the backend to open the output on.
+    pub fn with_backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Selects an output device by name.
+    pub fn with_device(mut self, device: impl Into<String>) -> Self {
+        self.device = Some(device.into());
+        self
+    }
+
+    /// Sets the number of output channels.
+    pub fn with_channels(mut self, channels: u16) -> Self {
+        self.channels = Some(channels);
+        self
+    }
+
+    /// Reports stream errors through a real-time safe logger instead of
+    /// printing them.
+    ///
+    /// Records are tagged with the logger's source name.
+    pub fn with_logger(mut self, logger: RtLogger) -> Self {
+        self.logger = Some(logger);
+        self
+    }
+
+    /// Turns stream recovery on or off.
+    ///
+    /// With it off, playback stops for good when the device goes away.
+    pub fn with_reconnect(mut self, reconnect: bool) -> Self {
+        self.reconnect = reconnect;
+        self
+    }
+
+    /// Requests a fixed buffer size in frames, trading latency (smaller)
+    /// against robustness to scheduling hiccups (larger).
+    ///
+    /// Opening fails if the device can't use the size.
+    pub fn with_buffer_size(mut self, frames: u32) -> Self {
+        self.buffer_size = Some(frames);
+        self
+    }
+
+    /// Sets device rates to fall back to when the device can't run at the
+    /// signal's own rate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::playback::OutputOptions;
+    ///
+    /// // A 44.1kHz signal on a 48kHz-only interface is resampled
+    /// let options = OutputOptions::default().with_sample_rates([48000, 96000]);
+    /// assert_eq!(options.sample_rates, [48000, 96000]);
+    /// ```
+    pub fn with_sample_rates(mut self, rates: impl IntoIterator<Item = u32>) -> Self {
+        self.sample_rates = rates.into_iter().collect();
+        self
+    }
+
+    /// Sets the sample formats to accept, most preferred first.
+    pub fn with_sample_formats(mut self, formats: impl IntoIterator<Item = OutputFormat>) -> Self {
+        self.sample_formats = formats.into_iter().collect();
+        self
+    }
+
+    /// Sends the signal to specific device channels, one entry per signal
+    /// channel, counting from 0.
+    ///
+    /// Device channels nothing is routed to are silent, and channels routed
+    /// from several signal channels get their sum. A mono signal is sent to
+    /// every listed channel. Unless [`channels`](Self::channels) is set the
+    /// stream opens with the device's default channel count, so on a
+    /// multi-output interface every hardware output is available. Samples
+    /// are written unfiltered, DC included, so DC-coupled outputs can carry
+    /// control voltages.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::playback::OutputOptions;
+    ///
+    /// // A stereo signal on outputs 3 and 4
+    /// let options = OutputOptions::default().with_channel_map([2, 3]);
+    /// assert_eq!(options.channel_map, Some(vec![2, 3]));
+    /// ```
+    pub fn with_channel_map(mut self, channels: impl IntoIterator<Item = u16>) -> Self {
+        self.channel_map = Some(channels.into_iter().collect());
+        self
+    }
+
+    /// Sets the name the stream's JACK client registers under.
+    ///
+    /// Only [`Backend::Jack`] uses this; other backends ignore it. Requires
+    /// the `jack` feature.
+    pub fn with_client_name(mut self, name: impl Into<String>) -> Self {
+        self.client_name = Some(name.into());
+        self
+    }
+
+    /// Connects the stream's JACK output ports to the named ports, one per
+    /// output channel, instead of the system playback ports.
+    ///
+    /// The stream's own ports are named `<client>_out:out_0`,
+    /// `<client>_out:out_1`, and so on. Opening fails if a port doesn't exist
+    /// or more ports are named than the stream has channels. Only
+    /// [`Backend::Jack`] uses this; other backends ignore it. Requires the
+    /// `jack` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::playback::{Backend, OutputOptions};
+    ///
+    /// // Feed a stereo signal into a recorder rather than the speakers
+    /// let options = OutputOptions::default()
+    ///     .with_backend(Backend::Jack)
+    ///     .with_client_name("synth")
+    ///     .with_ports(["recorder:in_1", "recorder:in_2"]);
+    /// assert_eq!(options.ports, ["recorder:in_1", "recorder:in_2"]);
+    /// ```
+    pub fn with_ports(mut self, ports: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.ports = ports.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+/// Returns the names of the audio hosts compiled in and available on this system.
+///
+/// Any of these can be passed to [`Backend::Named`].
+pub fn available_backends() -> Vec<String> {
+    cpal::available_hosts()
+        .into_iter()
+        .map(|id| id.name().to_string())
+        .collect()
+}
+
+/// Returns the names of the output devices offered by a backend.
+///
+/// # Errors
+///
+/// Returns an error if the backend is unavailable or cannot enumerate devices.
+pub fn output_devices(backend: &Backend) -> Result<Vec<String>, PlaybackError> {
+    let host = backend.host()?;
+    Ok(host
+        .output_devices()?
+        .filter_map(|device| device.name().ok())
+        .collect())
+}
+
+/// A running audio output stream.
+///
+/// The signal is moved onto the audio thread and pulled once per frame. A mono
+/// signal ([`start`](Self::start)) is written to every output channel; a
+/// multi-channel signal ([`start_frames`](Self::start_frames)) is written one
+/// channel per device channel. Audio plays until the `AudioOutput` is dropped.
+///
+/// The stream is opened at the signal's own sample rate when the device
+/// supports it. Otherwise the first rate in
+/// [`sample_rates`](OutputOptions::sample_rates) that the device offers with
+/// the requested channel count and an accepted format is used, and the
+/// signal is resampled to it on the audio thread by linear interpolation.
+/// Resampling only happens in that case; if neither the signal's rate nor
+/// any fallback rate is supported, opening fails with
+/// [`PlaybackError::UnsupportedConfig`]. [`StreamInfo::sample_rate`] reports
+/// the rate the device ended up at.
+///
+/// Unless [`reconnect`](OutputOptions::reconnect) is turned off, a
+/// supervisor thread watches the stream: when the device is unplugged, or
+/// the system default output changes while no device was named, it rebuilds
+/// the stream on the current device and the signal carries on where it left
+/// off, fading in over a few milliseconds. If no usable device is available
+/// it keeps retrying until one appears.
+///
+/// # Examples
+///
+/// ```no_run
+/// use earworm::SineOscillator;
+/// use earworm::playback::{AudioOutput, OutputOptions};
+///
+/// let osc = SineOscillator::<44100>::new(440.0);
+/// let output = AudioOutput::start(osc, &OutputOptions::default())?;
+/// std::thread::sleep(std::time::Duration::from_secs(1));
+/// drop(output);
+/// # Ok::<(), earworm::playback::PlaybackError>(())
+/// ```
+pub struct AudioOutput {
+    supervisor: Option<JoinHandle<()>>,
+    shared: Arc<OutputState>,
+    clock: StreamClock,
+    sample_rate: u32,
+    channels: u16,
+}
+
+/// The device and settings an [`AudioOutput`]'s stream was opened with.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct StreamInfo {
+    /// Device name
+    pub device: String,
+    /// Rate the device runs at, which differs from the signal's when a
+    /// fallback rate was used
+    pub sample_rate: u32,
+    /// Format written to the device
+    pub format: OutputFormat,
+    /// Frames per buffer, if one was requested
+    pub buffer_size: Option<u32>,
+}
+
+/// State shared between an [`AudioOutput`], its supervisor thread and the
+/// stream callbacks.
+struct OutputState {
+    stop: AtomicBool,
+    lost: AtomicBool,
+    reconnects: AtomicU64,
+    info: Mutex<StreamInfo>,
+}
+
+/// How often the supervisor checks the stream and the default device.
+const SUPERVISOR_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Fade-in after the stream is rebuilt, in seconds.
+const RECONNECT_FADE: f64 = 0.01;
+
+/// Pulls frames from the signal, resampling to the device rate, routing
+/// channels and fading in after a reconnect.
+struct Renderer<G> {
+    fill: G,
+    fade_length: usize,
+    fade_remaining: usize,
+    // Signal frames per device frame
+    ratio: f64,
+    position: f64,
+    previous: Vec<f64>,
+    next: Vec<f64>,
+    // Device channel for each signal channel; empty passes channels through
+    routes: Vec<usize>,
+    routed: Vec<f64>,
+}
+
+impl<G: FnMut(&mut [f64])> Renderer<G> {
+    fn new(fill: G, fade_length: usize) -> Self {
+        Self {
+            fill,
+            fade_length,
+            fade_remaining: 0,
+            ratio: 1.0,
+            position: 0.0,
+            previous: Vec::new(),
+            next: Vec::new(),
+            routes: Vec::new(),
+            routed: Vec::new(),
+        }
+    }
+
+    /// Prepares for a stream of `channels` channels with the signal at
+    /// `signal_rate` and the device at `device_rate`, routing signal
+    /// channels through `channel_map` if given.
+    fn configure(
+        &mut self,
+        channels: usize,
+        signal_rate: u32,
+        device_rate: u32,
+        channel_map: Option<&[u16]>,
+    ) {
+        self.routes = channel_map
+            .unwrap_or_default()
+            .iter()
+            .map(|&channel| channel as usize)
+            .collect();
+        let signal_channels = channel_map.map_or(channels, <[u16]>::len);
+        self.routed = vec![0.0; signal_channels];
+        self.ratio = signal_rate as f64 / device_rate as f64;
+        self.position = 1.0;
+        self.previous = vec![0.0; signal_channels];
+        self.next = vec![0.0; signal_channels];
+    }
+
+    fn render(&mut self, frame: &mut [f64]) {
+        if self.routes.is_empty() {
+            self.render_signal(frame);
+        } else {
+            let mut routed = std::mem::take(&mut self.routed);
+            self.render_signal(&mut routed);
+            frame.fill(0.0);
+            for (&route, value) in self.routes.iter().zip(&routed) {
+                frame[route] += value;
+            }
+            self.routed = routed;
+        }
+        if self.fade_remaining > 0 {
+            let gain = 1.0 - self.fade_remaining as f64 / self.fade_length as f64;
+            frame.iter_mut().for_each(|sample| *sample *= gain);
+            self.fade_remaining -= 1;
+        }
+    }
+
+    /// Renders one frame of the signal's own channels at the device rate.
+    fn render_signal(&mut self, frame: &mut [f64]) {
+        if self.ratio == 1.0 {
+            (self.fill)(frame);
+        } else {
+            // Linear interpolation between signal frames
+            while self.position >= 1.0 {
+                std::mem::swap(&mut self.previous, &mut self.next);
+                (self.fill)(&mut self.next);
+                self.position -= 1.0;
+            }
+            for ((out, a), b) in frame.iter_mut().zip(&self.previous).zip(&self.next) {
+                *out = a + self.position * (b - a);
+            }
+            self.position += self.ratio;
+        }
+    }
+
+    fn fade_in(&mut self) {
+        self.fade_remaining = self.fade_length;
+    }
+}
+
+impl AudioOutput {
+    /// Opens an output and starts playing `signal`.
+    ///
+    /// # Arguments
+    ///
+    /// * `signal` - The signal to play; it is moved to the audio thread
+    /// * `options` - Backend, device, and channel selection
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backend or device is unavailable, if the device
+    /// cannot run at `SAMPLE_RATE` with the requested channel count, or if the
+    /// stream fails to start.
+    pub fn start<const SAMPLE_RATE: u32, S>(
+        mut signal: S,
+        options: &OutputOptions,
+    ) -> Result<Self, PlaybackError>
+    where
+        S: AudioSignal<SAMPLE_RATE> + Send + 'static,
+    {
+        Self::open(SAMPLE_RATE, None, options, move |frame: &mut [f64]| {
+            frame.fill(signal.next_sample());
+        })
+    }
+
+    /// Opens an output and starts playing a multi-channel signal.
+    ///
+    /// Frame channel `n` is written to device channel `n`, or to the device
+    /// channel given for it by [`OutputOptions::with_channel_map`]. Unless
+    /// overridden in `options`, the stream is opened with `CHANNELS` channels;
+    /// if the device has more channels the extra ones are silent, and if it
+    /// has fewer the extra frame channels are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`start`](Self::start).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use earworm::SineOscillator;
+    /// use earworm::playback::{AudioOutput, OutputOptions};
+    ///
+    /// // A different tone on each of four channels
+    /// let quad = [220.0, 330.0, 440.0, 550.0].map(SineOscillator::<48000>::new);
+    /// let output = AudioOutput::start_frames(quad, &OutputOptions::default())?;
+    /// # Ok::<(), earworm::playback::PlaybackError>(())
+    /// ```
+    pub fn start_frames<const SAMPLE_RATE: u32, const CHANNELS: usize, F>(
+        mut frames: F,
+        options: &OutputOptions,
+    ) -> Result<Self, PlaybackError>
+    where
+        F: AudioFrameSignal<SAMPLE_RATE, CHANNELS> + Send + 'static,
+    {
+        let channels = u16::try_from(CHANNELS)
+            .map_err(|_| PlaybackError::UnsupportedConfig(format!("{} channels", CHANNELS)))?;
+        Self::open(
+            SAMPLE_RATE,
+            Some(channels),
+            options,
+            move |frame: &mut [f64]| {
+                let next = frames.next_frame();
+                for (out, value) in frame
+                    .iter_mut()
+                    .zip(next.iter().chain(std::iter::repeat(&0.0)))
+                {
+                    *out = *value;
+                }
+            },
+        )
+    }
+
+    /// Opens the device described by `options` on a supervisor thread
+    /// and drives `fill` once per frame.
+    fn open<G>(
+        sample_rate: u32,
+        default_channels: Option<u16>,
+        options: &OutputOptions,
+        fill: G,
+    ) -> Result<Self, PlaybackError>
+    where
+        G: FnMut(&mut [f64]) + Send + 'static,
+    {
+        let shared = Arc::new(OutputState {
+            stop: AtomicBool::new(false),
+            lost: AtomicBool::new(false),
+            reconnects: AtomicU64::new(0),
+            info: Mutex::new(StreamInfo::default()),
+        });
+        let clock = StreamClock::new(sample_rate);
+        let renderer = Arc::new(Mutex::new(Renderer::new(
+            fill,
+            (RECONNECT_FADE * sample_rate as f64) as usize,
+        )));
+
+        // cpal streams can't move between threads, so the supervisor owns
+        // the stream from the start and reports how opening went
+        let (opened, result) = mpsc::channel();
+        let supervisor = {
+            let options = options.clone();
+            let shared = Arc::clone(&shared);
+            let clock = clock.clone();
+            thread::Builder::new()
+                .name("earworm-output".into())
+                .spawn(move || {
+                    let connect = |channels| {
+                        connect(&options, sample_rate, channels, &renderer, &clock, &shared)
+                    };
+                    let (mut stream, channels) = match connect(default_channels) {
+                        Ok((stream, channels)) => {
+                            let _ = opened.send(Ok(channels));
+                            (Some(stream), channels)
+                        }
+                        Err(err) => {
+                            let _ = opened.send(Err(err));
+                            return;
+                        }
+                    };
+                    if !options.reconnect {
+                        while !shared.stop.load(Ordering::Acquire) {
+                            thread::park();
+                        }
+                        return;
+                    }
+
+                    while !shared.stop.load(Ordering::Acquire) {
+                        thread::park_timeout(SUPERVISOR_INTERVAL);
+                        let lost = shared.lost.swap(false, Ordering::AcqRel);
+                        // A JACK client has no default device to follow
+                        let moved = options.device.is_none()
+                            && options.backend != Backend::Jack
+                            && default_device_name(&options.backend).is_some_and(|name| {
+                                shared.info.lock().is_ok_and(|info| info.device != name)
+                            });
+                        if stream.is_some() && !lost && !moved {
+                            continue;
+                        }
+
+                        drop(stream.take());
+                        if let Ok(mut renderer) = renderer.lock() {
+                            renderer.fade_in();
+                        }
+                        // On failure, try again on the next check
+                        if let Ok((rebuilt, _)) = connect(Some(channels)) {
+                            stream = Some(rebuilt);
+                            shared.reconnects.fetch_add(1, Ordering::Relaxed);
+                            if let Some(logger) = &options.logger {
+                                logger.log(LogLevel::Info, "output stream rebuilt");
+                            }
+                        }
+                    }
+                })
+                .map_err(|err| PlaybackError::Stream(err.to_string()))?
+        };
+
+        let channels = match result.recv() {
+            Ok(Ok(channels)) => channels,
+            Ok(Err(err)) => {
+                let _ = supervisor.join();
+                return Err(err);
+            }
+            Err(_) => return Err(PlaybackError::Stream("output thread failed".into())),
+        };
+        Ok(Self {
+            supervisor: Some(supervisor),
+            shared,
+            clock,
+            sample_rate,
+            channels,
+        })
+    }
+
+    /// Returns the name of the device being played on.
+    ///
+    /// This changes when the stream is rebuilt on another device.
+    pub fn device_name(&self) -> String {
+        self.stream_info().device
+    }
+
+    /// Returns the device, rate, format and buffer size the stream runs
+    /// with.
+    ///
+    /// These change when the stream is rebuilt on another device.
+    pub fn stream_info(&self) -> StreamInfo {
+        self.shared
+            .info
+            .lock()
+            .map(|info| info.clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns the signal's sample rate in Hz.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Returns the number of output channels.
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    /// Returns how many times the stream has been rebuilt after losing its
+    /// device or following a new default device.
+    pub fn reconnects(&self) -> u64 {
+        self.shared.reconnects.load(Ordering::Relaxed)
+    }
+
+    /// Returns a handle on the stream's timing, for syncing visuals and
+    /// MIDI to the audio as it is heard.
+    pub fn clock(&self) -> StreamClock {
+        self.clock.clone()
+    }
+
+    /// Returns the output latency the driver last reported.
+    pub fn latency(&self) -> Duration {
+        self.clock.latency()
+    }
+}
+
+impl Drop for AudioOutput {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::Release);
+        if let Some(supervisor) = self.supervisor.take() {
+            supervisor.thread().unpark();
+            let _ = supervisor.join();
+        }
+    }
+}
+
+/// Returns the name of the backend's current default output device.
+fn default_device_name(backend: &Backend) -> Option<String> {
+    backend.host().ok()?.default_output_device()?.name().ok()
+}
+
+/// Finds the output device described by `options`.
+fn output_device(options: &OutputOptions) -> Result<cpal::Device, PlaybackError> {
+    #[cfg(all(
+        feature = "jack",
+        any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd"
+        )
+    ))]
+    if options.backend == Backend::Jack {
+        return super::jack::output_device(options);
+    }
+
+    let host = options.backend.host()?;
+    match &options.device {
+        None => host
+            .default_output_device()
+            .ok_or_else(|| PlaybackError::NoDevice("no default output device".into())),
+        Some(name) => host
+            .output_devices()?
+            .find(|device| device.name().is_ok_and(|n| &n == name))
+            .ok_or_else(|| PlaybackError::NoDevice(name.clone())),
+    }
+}
+
+/// Opens the device described by `options` and starts a stream rendering
+/// through `renderer`, returning it and its channel count.
+fn connect<G>(
+    options: &OutputOptions,
+    sample_rate: u32,
+    default_channels: Option<u16>,
+    renderer: &Arc<Mutex<Renderer<G>>>,
+    clock: &StreamClock,
+    shared: &Arc<OutputState>,
+) -> Result<(cpal::Stream, u16), PlaybackError>
+where
+    G: FnMut(&mut [f64]) + Send + 'static,
+{
+    let device = output_device(options)?;
+
+    // A channel map addresses the device's own outputs, so it gets all of
+    // them rather than as many as the signal has
+    let default_channels = default_channels.filter(|_| options.channel_map.is_none());
+    let channels = match options.channels.or(default_channels) {
+        Some(channels) => channels,
+        None => device.default_output_config()?.channels(),
+    };
+    let channel_map = options.channel_map.as_deref();
+    if let Some(&channel) = channel_map
+        .unwrap_or_default()
+        .iter()
+        .find(|&&channel| channel >= channels)
+    {
+        return Err(PlaybackError::UnsupportedConfig(format!(
+            "channel map routes to output {} but the stream has {} channels",
+            channel, channels
+        )));
+    }
+    if options.ports.len() > channels as usize {
+        return Err(PlaybackError::UnsupportedConfig(format!(
+            "{} ports named but the stream has {} channels",
+            options.ports.len(),
+            channels
+        )));
+    }
+    let (config, format) = select_config(&device, sample_rate, channels, options)?;
+    let device_rate = config.sample_rate.0;
+    if let Ok(mut renderer) = renderer.lock() {
+        renderer.configure(channels as usize, sample_rate, device_rate, channel_map);
+    }
+    clock.set_sample_rate(device_rate);
+
+    let context = StreamContext {
+        renderer: Arc::clone(renderer),
+        clock: clock.clone(),
+        shared: Arc::clone(shared),
+        logger: options.logger.clone(),
+    };
+    let stream = match format {
+        OutputFormat::F32 => build_stream::<f32, G>(&device, &config, context)?,
+        OutputFormat::F64 => build_stream::<f64, G>(&device, &config, context)?,
+        OutputFormat::I32 => build_stream::<i32, G>(&device, &config, context)?,
+        OutputFormat::I16 => build_stream::<i16, G>(&device, &config, context)?,
+        OutputFormat::U16 => build_stream::<u16, G>(&device, &config, context)?,
+        OutputFormat::U8 => build_stream::<u8, G>(&device, &config, context)?,
+    };
+    stream.play()?;
+
+    #[cfg(all(
+        feature = "jack",
+        any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd"
+        )
+    ))]
+    if options.backend == Backend::Jack {
+        super::jack::connect_ports(&device.name().unwrap_or_default(), &options.ports)?;
+    }
+
+    if let Ok(mut info) = shared.info.lock() {
+        *info = StreamInfo {
+            device: device.name().unwrap_or_default(),
+            sample_rate: device_rate,
+            format,
+            buffer_size: options.buffer_size,
+        };
+    }
+    Ok((stream, channels))
+}
+
+/// Picks a stream config with `channels`, at `sample_rate` or else the
+/// first fallback rate the device supports, in the most preferred format.
+fn select_config(
+    device: &cpal::Device,
+    sample_rate: u32,
+    channels: u16,
+    options: &OutputOptions,
+) -> Result<(StreamConfig, OutputFormat), PlaybackError> {
+    let ranges: Vec<_> = device.supported_output_configs()?.collect();
+    let formats: &[OutputFormat] = if options.sample_formats.is_empty() {
+        &OutputFormat::PREFERRED
+    } else {
+        &options.sample_formats
+    };
+
+    let rates = std::iter::once(sample_rate).chain(options.sample_rates.iter().copied());
+    let chosen = rates
+        .flat_map(|rate| formats.iter().map(move |&format| (rate, format)))
+        .find_map(|(rate, format)| {
+            ranges
+                .iter()
+                .find(|range| {
+                    range.channels() == channels
+                        && range.min_sample_rate().0 <= rate
+                        && range.max_sample_rate().0 >= rate
+                        && range.sample_format() == format.to_cpal()
+                })
+                .map(|range| (range, rate, format))
+        });
+
+    let Some((range, rate, format)) = chosen else {
+        let offered: Vec<String> = ranges
+            .iter()
+            .map(|range| {
+                format!(
+                    "{}ch {}-{} Hz {}",
+                    range.channels(),
+                    range.min_sample_rate().0,
+                    range.max_sample_rate().0,
+                    range.sample_format()
+                )
+            })
+            .collect();
+        return Err(PlaybackError::UnsupportedConfig(format!(
+            "{} channels at {} Hz in {} (device offers {})",
+            channels,
+            sample_rate,
+            formats
+                .iter()
+                .map(OutputFormat::to_string)
+                .collect::<Vec<_>>()
+                .join("/"),
+            offered.join(", ")
+        )));
+    };
+
+    let mut config: StreamConfig = range.with_sample_rate(SampleRate(rate)).into();
+    if let Some(frames) = options.buffer_size {
+        if let SupportedBufferSize::Range { min, max } = range.buffer_size()
+            && !(*min..=*max).contains(&frames)
+        {
+            return Err(PlaybackError::UnsupportedConfig(format!(
+                "buffer size of {} frames (device allows {}-{})",
+                frames, min, max
+            )));
+        }
+        config.buffer_size = BufferSize::Fixed(frames);
+    }
+    Ok((config, format))
+}
+
+/// What a stream callback needs besides the device config.
+struct StreamContext<G> {
+    renderer: Arc<Mutex<Renderer<G>>>,
+    clock: StreamClock,
+    shared: Arc<OutputState>,
+    logger: Option<RtLogger>,
+}
+
+fn build_stream<T, G>(
+    device: &cpal::Device,
+    config: &StreamConfig,
+    context: StreamContext<G>,
+) -> Result<cpal::Stream, PlaybackError>
+where
+    T: Sample + SizedSample + FromSample<f64>,
+    G: FnMut(&mut [f64]) + Send + 'static,
+{
+    let StreamContext {
+        renderer,
+        clock,
+        shared,
+        logger,
+    } = context;
+    let channels = config.channels as usize;
+    let mut frame = vec![0.0; channels];
+    let stream = device.build_output_stream(
+        config,
+        move |data: &mut [T], info: &cpal::OutputCallbackInfo| {
+            let timestamp = info.timestamp();
+            let latency = timestamp
+                .playback
+                .duration_since(&timestamp.callback)
+                .unwrap_or_default();
+            clock.record(Instant::now(), (data.len() / channels) as u64, latency);
+            // Only contended for a moment while the stream is being rebuilt
+            let Ok(mut renderer) = renderer.try_lock() else {
+                data.fill(T::EQUILIBRIUM);
+                return;
+            };
+            for out in data.chunks_mut(channels) {
+                renderer.render(&mut frame);
+                for (sample, value) in out.iter_mut().zip(frame.iter()) {
+                    *sample = T::from_sample(*value);
+                }
+            }
+        },
+        move |err| {
+            if matches!(err, cpal::StreamError::DeviceNotAvailable) {
+                shared.lost.store(true, Ordering::Release);
+            }
+            match &logger {
+                Some(logger) => {
+                    logger.log(LogLevel::Error, stream_error_message(&err));
+                }
+                None => eprintln!("Audio stream error: {}", err),
+            }
+        },
+        None,
+    )?;
+    Ok(stream)
+}
+
+/// Describes a stream error without allocating, for the real-time logger.
+fn stream_error_message(err: &cpal::StreamError) -> &'static str {
+    match err {
+        cpal::StreamError::DeviceNotAvailable => "output device no longer available",
+        cpal::StreamError::BackendSpecific { .. } => "backend stream error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_backend_is_available() {
+        assert!(Backend::Default.host().is_ok());
+    }
+
+    #[test]
+    fn test_unknown_backend() {
+        let err = Backend::Named("NotARealHost".into()).host().err();
+        assert_eq!(
+            err,
+            Some(PlaybackError::HostUnavailable("NotARealHost".into()))
+        );
+    }
+
+    #[test]
+    fn test_renderer_fades_in_after_reconnect() {
+        let mut renderer = Renderer::new(|frame: &mut [f64]| frame.fill(1.0), 4);
+        let mut frame = [0.0; 2];
+        renderer.render(&mut frame);
+        assert_eq!(frame, [1.0, 1.0]);
+
+        renderer.fade_in();
+        let gains: Vec<f64> = (0..6)
+            .map(|_| {
+                renderer.render(&mut frame);
+                frame[1]
+            })
+            .collect();
+        assert_eq!(gains, [0.0, 0.25, 0.5, 0.75, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_renderer_resamples_to_device_rate() {
+        let mut count = 0.0;
+        let mut renderer = Renderer::new(
+            move |frame: &mut [f64]| {
+                count += 1.0;
+                frame.fill(count);
+            },
+            0,
+        );
+        // Signal at half the device rate: every other frame is interpolated
+        renderer.configure(1, 24000, 48000, None);
+        let mut frame = [0.0];
+        let out: Vec<f64> = (0..5)
+            .map(|_| {
+                renderer.render(&mut frame);
+                frame[0]
+            })
+            .collect();
+        assert_eq!(out, [0.0, 0.5, 1.0, 1.5, 2.0]);
+    }
+
+    #[test]
+    fn test_renderer_routes_channels() {
+        let mut renderer = Renderer::new(
+            |frame: &mut [f64]| {
+                frame[0] = 1.0;
+                frame[1] = 2.0;
+            },
+            0,
+        );
+        renderer.configure(4, 48000, 48000, Some(&[3, 1]));
+        let mut frame = [9.0; 4];
+        renderer.render(&mut frame);
+        assert_eq!(frame, [0.0, 2.0, 0.0, 1.0]);
+
+        // Both signal channels onto one output
+        renderer.configure(2, 48000, 48000, Some(&[0, 0]));
+        let mut frame = [0.0; 2];
+        renderer.render(&mut frame);
+        assert_eq!(frame, [3.0, 0.0]);
+    }
+
+    #[test]
+    fn test_options_builder() {
+        let options = OutputOptions::default()
+            .with_backend(Backend::Jack)
+            .with_device("system")
+            .with_channels(8);
+        assert_eq!(options.backend, Backend::Jack);
+        assert_eq!(options.device.as_deref(), Some("system"));
+        assert_eq!(options.channels, Some(8));
+        assert!(options.logger.is_none());
+        assert!(options.reconnect);
+        assert!(!options.clone().with_reconnect(false).reconnect);
+        assert!(options.sample_formats.is_empty());
+
+        let options = options
+            .with_buffer_size(256)
+            .with_sample_formats([OutputFormat::I16, OutputFormat::F32]);
+        assert_eq!(options.buffer_size, Some(256));
+        assert_eq!(
+            options.sample_formats,
+            [OutputFormat::I16, OutputFormat::F32]
+        );
+        assert_eq!(OutputFormat::I16.to_string(), "i16");
+
+        let (logger, _drain) = crate::core::rt_log(4);
+        let options = options.with_logger(logger.with_source("output"));
+        assert_eq!(options.logger.unwrap().source(), "output");
+    }
+
+    #[test]
+    fn test_jack_client_and_ports() {
+        let options = OutputOptions::default();
+        assert_eq!(options.client_name, None);
+        assert!(options.ports.is_empty());
+
+        let options = options
+            .with_backend(Backend::Jack)
+            .with_client_name("synth")
+            .with_ports(["system:playback_1", "system:playback_2"]);
+        assert_eq!(options.client_name.as_deref(), Some("synth"));
+        assert_eq!(options.ports, ["system:playback_1", "system:playback_2"]);
+    }
+
+    #[cfg(not(feature = "jack"))]
+    #[test]
+    fn test_jack_unavailable_without_feature() {
+        assert_eq!(
+            Backend::Jack.host().err(),
+            Some(PlaybackError::HostUnavailable("JACK".into()))
+        );
+    }
+
+    #[cfg(all(feature = "jack", target_os = "linux"))]
+    #[test]
+    fn test_jack_backend_selects_jack_host() {
+        assert_eq!(Backend::Jack.host().unwrap().id(), cpal::HostId::Jack);
+    }
+}
@@ -0,0 +1,38 @@
+//! Error type for offline rendering.
+
+use std::fmt;
+
+/// Errors that can occur while writing rendered audio to disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenderError {
+    /// The output file could not be created or written
+    Io(String),
+    /// The encoder rejected the audio format or data
+    Format(String),
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderError::Io(s) => write!(f, "render I/O error: {}", s),
+            RenderError::Format(s) => write!(f, "render format error: {}", s),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+impl From<std::io::Error> for RenderError {
+    fn from(err: std::io::Error) -> Self {
+        RenderError::Io(err.to_string())
+    }
+}
+
+impl From<hound::Error> for RenderError {
+    fn from(err: hound::Error) -> Self {
+        match err {
+            hound::Error::IoError(e) => RenderError::Io(e.to_string()),
+            other => RenderError::Format(other.to_string()),
+        }
+    }
+}
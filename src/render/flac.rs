@@ -0,0 +1,679 @@
+//! Streaming FLAC output.
+//!
+//! A small native encoder: each block is stored as a constant, verbatim or
+//! fixed-predictor subframe with Rice-coded residuals, whichever is smallest.
+//! That falls short of a reference encoder's compression but is lossless and
+//! needs no extra dependencies.
+
+use super::RenderError;
+use super::wav::{BLOCK_SIZE, seconds_to_samples};
+use crate::AudioSignal;
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Samples per FLAC frame.
+const FRAME_SIZE: usize = 4096;
+
+/// Byte offset of the STREAMINFO block's contents in the file.
+const STREAMINFO_OFFSET: u64 = 8;
+
+/// Integer sample depth for FLAC files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FlacDepth {
+    /// 16-bit samples
+    #[default]
+    Int16,
+    /// 24-bit samples
+    Int24,
+}
+
+impl FlacDepth {
+    fn bits(self) -> u32 {
+        match self {
+            FlacDepth::Int16 => 16,
+            FlacDepth::Int24 => 24,
+        }
+    }
+
+    fn quantize(self, sample: f64) -> i32 {
+        let max = ((1 << (self.bits() - 1)) - 1) as f64;
+        (sample.clamp(-1.0, 1.0) * max).round() as i32
+    }
+}
+
+/// A streaming sink that pulls samples from a signal and writes them to a
+/// mono FLAC file.
+///
+/// Works like [`WavWriter`](super::WavWriter), but the file is losslessly
+/// compressed, which makes renders smaller to share. Samples clip to
+/// [-1.0, 1.0].
+///
+/// Call [`finalize`](Self::finalize) when done to write the last frame and
+/// the stream length. A writer dropped without finalizing leaves an
+/// incomplete file.
+///
+/// Requires the `flac` feature.
+///
+/// # Examples
+///
+/// ```no_run
+/// use earworm::SineOscillator;
+/// use earworm::render::{FlacDepth, FlacWriter};
+///
+/// let osc = SineOscillator::<44100>::new(440.0);
+/// let mut writer = FlacWriter::create("tone.flac", osc, FlacDepth::Int24)?;
+/// writer.render_seconds(2.0)?;
+/// writer.finalize()?;
+/// # Ok::<(), earworm::render::RenderError>(())
+/// ```
+pub struct FlacWriter<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
+    signal: S,
+    file: BufWriter<File>,
+    depth: FlacDepth,
+    buffer: Vec<f64>,
+    frame: Vec<i32>,
+    frames_written: u64,
+    samples_written: usize,
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> FlacWriter<SAMPLE_RATE, S> {
+    /// Creates the file at `path` and prepares `signal` for rendering.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Output file; it is overwritten if it exists
+    /// * `signal` - The signal to render
+    /// * `depth` - Sample depth
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RenderError::Io`] if the file cannot be created, or
+    /// [`RenderError::Format`] if `SAMPLE_RATE` is beyond what FLAC can store.
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        mut signal: S,
+        depth: FlacDepth,
+    ) -> Result<Self, RenderError> {
+        if SAMPLE_RATE == 0 || SAMPLE_RATE >= 1 << 20 {
+            return Err(RenderError::Format(format!(
+                "FLAC cannot store a sample rate of {} Hz",
+                SAMPLE_RATE
+            )));
+        }
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(b"fLaC")?;
+        // STREAMINFO is the only (so last) metadata block
+        file.write_all(&[0x80, 0, 0, 34])?;
+        file.write_all(&stream_info(SAMPLE_RATE, depth, 0, FRAME_SIZE as u16))?;
+        signal.prepare(BLOCK_SIZE, SAMPLE_RATE);
+        Ok(Self {
+            signal,
+            file,
+            depth,
+            buffer: vec![0.0; BLOCK_SIZE],
+            frame: Vec::with_capacity(FRAME_SIZE),
+            frames_written: 0,
+            samples_written: 0,
+        })
+    }
+
+    /// Pulls `samples` samples from the signal and encodes them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails.
+    pub fn render(&mut self, samples: usize) -> Result<(), RenderError> {
+        let mut remaining = samples;
+        while remaining > 0 {
+            let n = remaining.min(BLOCK_SIZE);
+            self.signal.process(&mut self.buffer[..n]);
+            for i in 0..n {
+                self.frame.push(self.depth.quantize(self.buffer[i]));
+                if self.frame.len() == FRAME_SIZE {
+                    self.write_frame()?;
+                }
+            }
+            remaining -= n;
+            self.samples_written += n;
+        }
+        Ok(())
+    }
+
+    /// Pulls `seconds` worth of samples from the signal and encodes them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails.
+    pub fn render_seconds(&mut self, seconds: f64) -> Result<(), RenderError> {
+        self.render(seconds_to_samples(seconds, SAMPLE_RATE))
+    }
+
+    /// Returns the number of samples written so far.
+    pub fn samples_written(&self) -> usize {
+        self.samples_written
+    }
+
+    /// Returns a mutable reference to the signal, for changing it between
+    /// chunks.
+    pub fn signal_mut(&mut self) -> &mut S {
+        &mut self.signal
+    }
+
+    /// Writes the final frame and stream length, closes the file, and
+    /// returns the signal.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails.
+    pub fn finalize(mut self) -> Result<S, RenderError> {
+        if !self.frame.is_empty() {
+            self.write_frame()?;
+        }
+        // Every frame but the last is FRAME_SIZE long
+        let block_size = FRAME_SIZE.min(self.samples_written.max(1)) as u16;
+        let info = stream_info(
+            SAMPLE_RATE,
+            self.depth,
+            self.samples_written as u64,
+            block_size,
+        );
+        self.file.seek(SeekFrom::Start(STREAMINFO_OFFSET))?;
+        self.file.write_all(&info)?;
+        self.file.flush()?;
+        Ok(self.signal)
+    }
+
+    fn write_frame(&mut self) -> Result<(), RenderError> {
+        let bytes = encode_frame(&self.frame, self.frames_written, self.depth.bits());
+        self.file.write_all(&bytes)?;
+        self.frames_written += 1;
+        self.frame.clear();
+        Ok(())
+    }
+}
+
+/// Renders `duration` seconds of `signal` to a 16-bit mono FLAC file.
+///
+/// Use [`FlacWriter`] for 24-bit files or to render in chunks.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be created or written.
+///
+/// # Examples
+///
+/// ```no_run
+/// use earworm::SineOscillator;
+/// use earworm::render::render_to_flac;
+///
+/// let osc = SineOscillator::<48000>::new(440.0);
+/// render_to_flac(osc, 2.0, "a440.flac")?;
+/// # Ok::<(), earworm::render::RenderError>(())
+/// ```
+pub fn render_to_flac<const SAMPLE_RATE: u32, S, P>(
+    signal: S,
+    duration: f64,
+    path: P,
+) -> Result<(), RenderError>
+where
+    S: AudioSignal<SAMPLE_RATE>,
+    P: AsRef<Path>,
+{
+    let mut writer = FlacWriter::create(path, signal, FlacDepth::Int16)?;
+    writer.render_seconds(duration)?;
+    writer.finalize()?;
+    Ok(())
+}
+
+/// The 34-byte STREAMINFO block. Frame sizes and the MD5 signature are left
+/// as 0 ("unknown"), which the format allows.
+fn stream_info(sample_rate: u32, depth: FlacDepth, total_samples: u64, block_size: u16) -> Vec<u8> {
+    let mut bits = BitWriter::default();
+    bits.write(block_size as u64, 16);
+    bits.write(block_size as u64, 16);
+    bits.write(0, 24);
+    bits.write(0, 24);
+    bits.write(sample_rate as u64, 20);
+    bits.write(0, 3); // channels - 1
+    bits.write(depth.bits() as u64 - 1, 5);
+    bits.write(total_samples, 36);
+    bits.write(0, 64);
+    bits.write(0, 64);
+    bits.into_bytes()
+}
+
+/// Encodes one mono frame with a fixed block size.
+fn encode_frame(samples: &[i32], frame_number: u64, bits_per_sample: u32) -> Vec<u8> {
+    let mut bits = BitWriter::default();
+    bits.write(0xFFF8, 16); // sync code, fixed block size
+    bits.write(0b0111, 4); // block size - 1 follows as 16 bits
+    bits.write(0b0000, 4); // sample rate from STREAMINFO
+    bits.write(0b0000, 4); // mono
+    bits.write(if bits_per_sample == 16 { 0b100 } else { 0b110 }, 3);
+    bits.write(0, 1);
+    for byte in utf8_number(frame_number) {
+        bits.write(byte as u64, 8);
+    }
+    bits.write(samples.len() as u64 - 1, 16);
+    let crc = crc8(&bits.bytes);
+    bits.write(crc as u64, 8);
+
+    encode_subframe(&mut bits, samples, bits_per_sample);
+    bits.align();
+    let crc = crc16(&bits.bytes);
+    bits.write(crc as u64, 16);
+    bits.into_bytes()
+}
+
+/// Writes the smallest of a constant, fixed-predictor or verbatim subframe.
+fn encode_subframe(bits: &mut BitWriter, samples: &[i32], bps: u32) {
+    if samples.iter().all(|&s| s == samples[0]) {
+        bits.write(0b0000_0000, 8);
+        bits.write_signed(samples[0] as i64, bps);
+        return;
+    }
+
+    let verbatim_bits = samples.len() as u64 * bps as u64;
+    let best = (0..=4usize.min(samples.len() - 1))
+        .map(|order| {
+            let residuals = fixed_residuals(samples, order);
+            let (parameter, cost) = rice_parameter(&residuals);
+            // Header, warm-up samples, coding method, partition order, parameter
+            let total = 8 + order as u64 * bps as u64 + 2 + 4 + 4 + cost;
+            (total, order, parameter, residuals)
+        })
+        .min_by_key(|(total, ..)| *total);
+
+    match best {
+        Some((total, order, parameter, residuals)) if total < verbatim_bits + 8 => {
+            bits.write(0b0001_0000 | (order as u64) << 1, 8);
+            for &warmup in &samples[..order] {
+                bits.write_signed(warmup as i64, bps);
+            }
+            bits.write(0b00, 2); // Rice coding, 4-bit parameters
+            bits.write(0, 4); // one partition
+            bits.write(parameter as u64, 4);
+            for &residual in &residuals {
+                let folded = ((residual << 1) ^ (residual >> 63)) as u64;
+                bits.write_unary(folded >> parameter);
+                bits.write(folded & ((1 << parameter) - 1), parameter);
+            }
+        }
+        _ => {
+            bits.write(0b0000_0010, 8);
+            for &sample in samples {
+                bits.write_signed(sample as i64, bps);
+            }
+        }
+    }
+}
+
+/// Residuals of the fixed polynomial predictor of `order` (after the warm-up
+/// samples).
+fn fixed_residuals(samples: &[i32], order: usize) -> Vec<i64> {
+    let x = |i: usize| samples[i] as i64;
+    (order..samples.len())
+        .map(|n| match order {
+            0 => x(n),
+            1 => x(n) - x(n - 1),
+            2 => x(n) - 2 * x(n - 1) + x(n - 2),
+            3 => x(n) - 3 * x(n - 1) + 3 * x(n - 2) - x(n - 3),
+            _ => x(n) - 4 * x(n - 1) + 6 * x(n - 2) - 4 * x(n - 3) + x(n - 4),
+        })
+        .collect()
+}
+
+/// Picks the Rice parameter (0-14) that codes `residuals` in the fewest bits,
+/// returning it with that bit count.
+fn rice_parameter(residuals: &[i64]) -> (u32, u64) {
+    let folded: Vec<u64> = residuals
+        .iter()
+        .map(|&r| ((r << 1) ^ (r >> 63)) as u64)
+        .collect();
+    (0..15)
+        .map(|k| {
+            let quotients: u64 = folded.iter().map(|&u| u >> k).sum();
+            (k, quotients + folded.len() as u64 * (k as u64 + 1))
+        })
+        .min_by_key(|&(_, cost)| cost)
+        .unwrap_or((0, 0))
+}
+
+/// The frame number in FLAC's extended UTF-8 coding.
+fn utf8_number(n: u64) -> Vec<u8> {
+    if n < 0x80 {
+        return vec![n as u8];
+    }
+    let continuation_bytes = match n {
+        0..0x800 => 1,
+        0x800..0x1_0000 => 2,
+        0x1_0000..0x20_0000 => 3,
+        0x20_0000..0x400_0000 => 4,
+        0x400_0000..0x8000_0000 => 5,
+        _ => 6,
+    };
+    let lead_marker = !(0xFFu8 >> (continuation_bytes + 1));
+    let mut bytes = vec![lead_marker | (n >> (6 * continuation_bytes)) as u8];
+    for i in (0..continuation_bytes).rev() {
+        bytes.push(0x80 | ((n >> (6 * i)) & 0x3F) as u8);
+    }
+    bytes
+}
+
+/// CRC-8 with polynomial x^8 + x^2 + x + 1, as used by frame headers.
+fn crc8(bytes: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    for &byte in bytes {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// CRC-16 with polynomial x^16 + x^15 + x^2 + 1, as used by whole frames.
+fn crc16(bytes: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for &byte in bytes {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x8005
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// Packs values most significant bit first.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    current: u8,
+    filled: u32,
+}
+
+impl BitWriter {
+    /// Writes the low `count` bits of `value`.
+    fn write(&mut self, value: u64, count: u32) {
+        for i in (0..count).rev() {
+            self.current = (self.current << 1) | ((value >> i) & 1) as u8;
+            self.filled += 1;
+            if self.filled == 8 {
+                self.bytes.push(self.current);
+                self.current = 0;
+                self.filled = 0;
+            }
+        }
+    }
+
+    /// Writes `value` as a `count`-bit two's complement number.
+    fn write_signed(&mut self, value: i64, count: u32) {
+        self.write(value as u64 & ((1 << count) - 1), count);
+    }
+
+    /// Writes `value` zeros followed by a one.
+    fn write_unary(&mut self, value: u64) {
+        for _ in 0..value {
+            self.write(0, 1);
+        }
+        self.write(1, 1);
+    }
+
+    /// Pads with zeros to the next byte boundary.
+    fn align(&mut self) {
+        if self.filled > 0 {
+            self.write(0, 8 - self.filled);
+        }
+    }
+
+    fn into_bytes(mut self) -> Vec<u8> {
+        self.align();
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstantSignal, Signal, SineOscillator};
+
+    struct BitReader<'a> {
+        bytes: &'a [u8],
+        position: usize,
+    }
+
+    impl BitReader<'_> {
+        fn read(&mut self, count: u32) -> u64 {
+            let mut value = 0;
+            for _ in 0..count {
+                let bit = (self.bytes[self.position / 8] >> (7 - self.position % 8)) & 1;
+                value = (value << 1) | bit as u64;
+                self.position += 1;
+            }
+            value
+        }
+
+        fn read_signed(&mut self, count: u32) -> i64 {
+            let value = self.read(count) as i64;
+            (value << (64 - count)) >> (64 - count)
+        }
+    }
+
+    /// Decodes files written by this encoder (and only the features it uses),
+    /// checking both CRCs of every frame.
+    fn decode(bytes: &[u8]) -> (u32, u32, Vec<i64>) {
+        assert_eq!(&bytes[..4], b"fLaC");
+        let mut info = BitReader {
+            bytes: &bytes[8..42],
+            position: 0,
+        };
+        info.read(80);
+        let sample_rate = info.read(20) as u32;
+        info.read(3);
+        let bps = info.read(5) as u32 + 1;
+        let total = info.read(36) as usize;
+
+        let mut samples = Vec::new();
+        let mut offset = 42;
+        while samples.len() < total {
+            let mut frame = BitReader {
+                bytes: &bytes[offset..],
+                position: 0,
+            };
+            assert_eq!(frame.read(16), 0xFFF8);
+            frame.read(16);
+            let lead = frame.read(8) as u8;
+            for _ in 0..lead.leading_ones().saturating_sub(1) {
+                frame.read(8);
+            }
+            let block_size = frame.read(16) as usize + 1;
+            let header_len = frame.position / 8;
+            assert_eq!(frame.read(8) as u8, crc8(&frame.bytes[..header_len]));
+
+            let kind = frame.read(8) >> 1;
+            match kind {
+                0 => {
+                    let value = frame.read_signed(bps);
+                    samples.extend(std::iter::repeat_n(value, block_size));
+                }
+                1 => samples.extend((0..block_size).map(|_| frame.read_signed(bps))),
+                8..=12 => {
+                    let order = (kind - 8) as usize;
+                    let mut block: Vec<i64> = (0..order).map(|_| frame.read_signed(bps)).collect();
+                    assert_eq!(frame.read(6), 0);
+                    let k = frame.read(4) as u32;
+                    while block.len() < block_size {
+                        let mut quotient = 0;
+                        while frame.read(1) == 0 {
+                            quotient += 1;
+                        }
+                        let folded = (quotient << k) | frame.read(k);
+                        let residual = (folded >> 1) as i64 ^ -((folded & 1) as i64);
+                        let n = block.len();
+                        let x = |i: usize| block[i];
+                        let prediction = match order {
+                            0 => 0,
+                            1 => x(n - 1),
+                            2 => 2 * x(n - 1) - x(n - 2),
+                            3 => 3 * x(n - 1) - 3 * x(n - 2) + x(n - 3),
+                            _ => 4 * x(n - 1) - 6 * x(n - 2) + 4 * x(n - 3) - x(n - 4),
+                        };
+                        block.push(prediction + residual);
+                    }
+                    samples.extend(block);
+                }
+                other => panic!("unexpected subframe type {}", other),
+            }
+            let end = frame.position.div_ceil(8);
+            let crc = u16::from_be_bytes([frame.bytes[end], frame.bytes[end + 1]]);
+            assert_eq!(crc, crc16(&frame.bytes[..end]));
+            offset += end + 2;
+        }
+        assert_eq!(offset, bytes.len());
+        (sample_rate, bps, samples)
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("earworm-{}-{}.flac", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_crc_check_values() {
+        assert_eq!(crc8(b"123456789"), 0xF4);
+        assert_eq!(crc16(b"123456789"), 0xFEE8);
+    }
+
+    #[test]
+    fn test_utf8_frame_numbers() {
+        assert_eq!(utf8_number(0x7F), [0x7F]);
+        assert_eq!(utf8_number(0x80), [0xC2, 0x80]);
+        assert_eq!(utf8_number(0x20AC), [0xE2, 0x82, 0xAC]);
+        assert_eq!(utf8_number(0x1_F600), [0xF0, 0x9F, 0x98, 0x80]);
+    }
+
+    #[test]
+    fn test_constant_file_matches_known_bytes() {
+        // Four samples of 0.5 at 16 bits and 8 kHz, laid out by hand from the
+        // format specification
+        let path = temp_path("golden");
+        let mut writer =
+            FlacWriter::create(&path, ConstantSignal::<8000>(0.5), FlacDepth::Int16).unwrap();
+        writer.render(4).unwrap();
+        writer.finalize().unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        #[rustfmt::skip]
+        let expected: &[u8] = &[
+            b'f', b'L', b'a', b'C',
+            // Last metadata block, STREAMINFO, 34 bytes
+            0x80, 0x00, 0x00, 0x22,
+            // Block sizes 4/4, frame sizes unknown
+            0x00, 0x04, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            // 8000 Hz, 1 channel, 16 bits, 4 samples
+            0x01, 0xF4, 0x00, 0xF0, 0x00, 0x00, 0x00, 0x04,
+            // MD5 unknown
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            // Frame header: sync, 16-bit block size, mono, 16 bits, frame 0,
+            // block size - 1 = 3, CRC-8
+            0xFF, 0xF8, 0x70, 0x08, 0x00, 0x00, 0x03, 0x13,
+            // CONSTANT subframe of round(0.5 * 32767) = 16384
+            0x00, 0x40, 0x00,
+            // CRC-16
+            0x0E, 0xF3,
+        ];
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn test_independent_decoder_reads_every_subframe_type() {
+        let path = temp_path("claxon");
+        let mut writer =
+            FlacWriter::create(&path, ConstantSignal::<8000>(0.0), FlacDepth::Int24).unwrap();
+        // Constant frame, then a tone (fixed predictor), then white noise,
+        // which no predictor helps (verbatim)
+        writer.render(FRAME_SIZE).unwrap();
+        let mut expected = vec![0i32; FRAME_SIZE];
+        let mut osc = SineOscillator::<8000>::new(110.0);
+        let tone: Vec<f64> = (0..FRAME_SIZE).map(|_| osc.next_sample()).collect();
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        let noise: Vec<f64> = (0..FRAME_SIZE + 100)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+            })
+            .collect();
+        for block in [&tone, &noise] {
+            for &sample in block.iter() {
+                writer.signal_mut().0 = sample;
+                writer.render(1).unwrap();
+                expected.push(FlacDepth::Int24.quantize(sample));
+            }
+        }
+        writer.finalize().unwrap();
+        // The subframe header follows the eight-byte frame header
+        let subframe_type = |frame: usize| {
+            let block = &expected[frame * FRAME_SIZE..(frame + 1) * FRAME_SIZE];
+            encode_frame(block, frame as u64, 24)[8]
+        };
+        assert_eq!(subframe_type(0), 0x00);
+        assert_eq!(subframe_type(1) & 0xF0, 0x10);
+        assert_eq!(subframe_type(2), 0x02);
+
+        let mut reader = claxon::FlacReader::open(&path).unwrap();
+        let info = reader.streaminfo();
+        assert_eq!((info.sample_rate, info.channels), (8000, 1));
+        assert_eq!(info.bits_per_sample, 24);
+        assert_eq!(info.samples, Some(expected.len() as u64));
+        let decoded: Vec<i32> = reader.samples().map(|s| s.unwrap()).collect();
+        assert_eq!(decoded, expected);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_round_trip_is_lossless() {
+        for depth in [FlacDepth::Int16, FlacDepth::Int24] {
+            let path = temp_path("round-trip");
+            // Several frames plus a short one, with silence and clipping
+            let osc = SineOscillator::<8000>::new(110.0);
+            let mut writer = FlacWriter::create(&path, osc, depth).unwrap();
+            writer.render(10_000).unwrap();
+            writer.finalize().unwrap();
+            let mut writer = FlacWriter::create(&path, ConstantSignal::<8000>(2.0), depth).unwrap();
+            writer.render(100).unwrap();
+            writer.finalize().unwrap();
+            let (_, _, clipped) = decode(&std::fs::read(&path).unwrap());
+            let max = (1i64 << (depth.bits() - 1)) - 1;
+            assert_eq!(clipped, vec![max; 100]);
+
+            let osc = SineOscillator::<8000>::new(110.0);
+            let mut writer = FlacWriter::create(&path, osc, depth).unwrap();
+            writer.render(10_000).unwrap();
+            writer.finalize().unwrap();
+            let bytes = std::fs::read(&path).unwrap();
+            let (rate, bps, decoded) = decode(&bytes);
+            assert_eq!((rate, bps), (8000, depth.bits()));
+
+            let mut expected = SineOscillator::<8000>::new(110.0);
+            assert_eq!(decoded.len(), 10_000);
+            for &sample in &decoded {
+                assert_eq!(sample, depth.quantize(expected.next_sample()) as i64);
+            }
+            // A pure tone predicts well
+            assert!(bytes.len() < 10_000 * depth.bits() as usize / 8 * 3 / 4);
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+}
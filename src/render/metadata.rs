@@ -0,0 +1,330 @@
+//! Metadata chunks for rendered WAV files.
+
+use super::RenderError;
+use std::fs::OpenOptions;
+use std::io::{Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Descriptive and musical information stored alongside rendered audio.
+///
+/// Written as standard RIFF chunks that samplers and DAWs read when the file
+/// is imported:
+///
+/// - title and artist in a `LIST`/`INFO` chunk
+/// - tempo and meter in an `acid` chunk, so the clip stretches to the
+///   project tempo
+/// - loop points in a `smpl` chunk
+/// - markers in `cue ` and `LIST`/`adtl` chunks
+///
+/// # Examples
+///
+/// ```no_run
+/// use earworm::SineOscillator;
+/// use earworm::render::{WavFormat, WavMetadata, WavWriter};
+///
+/// let metadata = WavMetadata::new()
+///     .with_title("Pad loop")
+///     .with_artist("earworm")
+///     .with_tempo(120.0)
+///     .with_full_loop()
+///     .with_bar_markers();
+///
+/// let osc = SineOscillator::<44100>::new(220.0);
+/// let mut writer = WavWriter::create("pad.wav", osc, WavFormat::Int24)?.with_metadata(metadata);
+/// writer.render_seconds(4.0)?;
+/// writer.finalize()?;
+/// # Ok::<(), earworm::render::RenderError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct WavMetadata {
+    title: Option<String>,
+    artist: Option<String>,
+    tempo: Option<f64>,
+    beats_per_bar: u16,
+    loop_range: Option<LoopRange>,
+    cues: Vec<(u32, String)>,
+    bar_markers: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LoopRange {
+    Whole,
+    Samples(u32, u32),
+}
+
+impl Default for WavMetadata {
+    fn default() -> Self {
+        Self {
+            title: None,
+            artist: None,
+            tempo: None,
+            beats_per_bar: 4,
+            loop_range: None,
+            cues: Vec::new(),
+            bar_markers: false,
+        }
+    }
+}
+
+impl WavMetadata {
+    /// Creates empty metadata, in 4/4 once a tempo is set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the title (builder style).
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets the artist (builder style).
+    pub fn with_artist(mut self, artist: impl Into<String>) -> Self {
+        self.artist = Some(artist.into());
+        self
+    }
+
+    /// Sets the tempo in BPM (builder style).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bpm` is not positive.
+    pub fn with_tempo(mut self, bpm: f64) -> Self {
+        assert!(bpm > 0.0, "tempo must be positive");
+        self.tempo = Some(bpm);
+        self
+    }
+
+    /// Sets the number of beats in a bar, for the meter and bar markers
+    /// (builder style).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `beats_per_bar` is 0.
+    pub fn with_beats_per_bar(mut self, beats_per_bar: u16) -> Self {
+        assert!(beats_per_bar > 0, "beats_per_bar must be greater than 0");
+        self.beats_per_bar = beats_per_bar;
+        self
+    }
+
+    /// Loops the whole file (builder style).
+    pub fn with_full_loop(mut self) -> Self {
+        self.loop_range = Some(LoopRange::Whole);
+        self
+    }
+
+    /// Loops samples `start..end` (builder style).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `end <= start`.
+    pub fn with_loop(mut self, start: u32, end: u32) -> Self {
+        assert!(end > start, "loop end must be after its start");
+        self.loop_range = Some(LoopRange::Samples(start, end));
+        self
+    }
+
+    /// Adds a named marker at sample `position` (builder style).
+    pub fn with_cue(mut self, position: u32, label: impl Into<String>) -> Self {
+        self.cues.push((position, label.into()));
+        self
+    }
+
+    /// Adds a marker at every bar line, labelled "Bar 1", "Bar 2" and so on
+    /// (builder style).
+    ///
+    /// Bars are measured with the tempo and beats per bar, so this does
+    /// nothing unless a tempo is set.
+    pub fn with_bar_markers(mut self) -> Self {
+        self.bar_markers = true;
+        self
+    }
+
+    /// Appends the chunks to a finalized WAV file holding `samples` mono
+    /// samples, and fixes up the RIFF size.
+    pub(super) fn append_to(
+        &self,
+        path: &Path,
+        sample_rate: u32,
+        samples: usize,
+    ) -> Result<(), RenderError> {
+        let chunks = self.chunks(sample_rate, samples as u32);
+        if chunks.is_empty() {
+            return Ok(());
+        }
+        let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+        let end = file.seek(SeekFrom::End(0))?;
+        file.write_all(&chunks)?;
+        let riff_size = (end + chunks.len() as u64 - 8) as u32;
+        file.seek(SeekFrom::Start(4))?;
+        file.write_all(&riff_size.to_le_bytes())?;
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Encodes every chunk that has something to say.
+    fn chunks(&self, sample_rate: u32, samples: u32) -> Vec<u8> {
+        let mut out = Vec::new();
+
+        let mut info = b"INFO".to_vec();
+        for (id, text) in [(b"INAM", &self.title), (b"IART", &self.artist)] {
+            if let Some(text) = text {
+                chunk(&mut info, id, &zero_terminated(text));
+            }
+        }
+        if info.len() > 4 {
+            chunk(&mut out, b"LIST", &info);
+        }
+
+        if let Some(bpm) = self.tempo {
+            let beats = samples as f64 / sample_rate as f64 * bpm / 60.0;
+            let mut acid = Vec::new();
+            // Root note set, stretch on
+            acid.extend_from_slice(&0x06u32.to_le_bytes());
+            acid.extend_from_slice(&60u16.to_le_bytes());
+            acid.extend_from_slice(&0x8000u16.to_le_bytes());
+            acid.extend_from_slice(&0f32.to_le_bytes());
+            acid.extend_from_slice(&(beats.round() as u32).to_le_bytes());
+            acid.extend_from_slice(&4u16.to_le_bytes());
+            acid.extend_from_slice(&self.beats_per_bar.to_le_bytes());
+            acid.extend_from_slice(&(bpm as f32).to_le_bytes());
+            chunk(&mut out, b"acid", &acid);
+        }
+
+        if let Some(range) = self.loop_range {
+            let (start, end) = match range {
+                LoopRange::Whole => (0, samples),
+                LoopRange::Samples(start, end) => (start, end.min(samples)),
+            };
+            if end > start {
+                let period = (1e9 / sample_rate as f64).round() as u32;
+                let mut smpl = Vec::new();
+                // Manufacturer, product, sample period, unity note, pitch
+                // fraction, SMPTE format and offset, loop count, sampler data
+                for value in [0, 0, period, 60, 0, 0, 0, 1, 0] {
+                    smpl.extend_from_slice(&u32::to_le_bytes(value));
+                }
+                // Cue ID, forward loop, first and last sample, fraction,
+                // infinite play count
+                for value in [0, 0, start, end - 1, 0, 0] {
+                    smpl.extend_from_slice(&u32::to_le_bytes(value));
+                }
+                chunk(&mut out, b"smpl", &smpl);
+            }
+        }
+
+        let mut cues = self.cues.clone();
+        if self.bar_markers
+            && let Some(bpm) = self.tempo
+        {
+            let bar = sample_rate as f64 * 60.0 / bpm * self.beats_per_bar as f64;
+            let bars = (0..)
+                .map(|i| ((i as f64 * bar).round() as u32, i + 1))
+                .take_while(|&(position, _)| position < samples.max(1));
+            cues.extend(bars.map(|(position, n)| (position, format!("Bar {}", n))));
+        }
+        if !cues.is_empty() {
+            let mut cue = (cues.len() as u32).to_le_bytes().to_vec();
+            let mut labels = b"adtl".to_vec();
+            for (id, (position, label)) in (1u32..).zip(&cues) {
+                cue.extend_from_slice(&id.to_le_bytes());
+                cue.extend_from_slice(&position.to_le_bytes());
+                cue.extend_from_slice(b"data");
+                cue.extend_from_slice(&0u32.to_le_bytes());
+                cue.extend_from_slice(&0u32.to_le_bytes());
+                cue.extend_from_slice(&position.to_le_bytes());
+
+                let mut labl = id.to_le_bytes().to_vec();
+                labl.extend_from_slice(&zero_terminated(label));
+                chunk(&mut labels, b"labl", &labl);
+            }
+            chunk(&mut out, b"cue ", &cue);
+            chunk(&mut out, b"LIST", &labels);
+        }
+
+        out
+    }
+}
+
+/// Appends a RIFF chunk, padded to an even length.
+fn chunk(out: &mut Vec<u8>, id: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(id);
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out.extend_from_slice(data);
+    if data.len() % 2 == 1 {
+        out.push(0);
+    }
+}
+
+fn zero_terminated(text: &str) -> Vec<u8> {
+    let mut bytes = text.as_bytes().to_vec();
+    bytes.push(0);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The top-level chunks of a RIFF file as (id, data).
+    fn chunks(bytes: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let mut out = Vec::new();
+        let mut i = 12;
+        while i + 8 <= bytes.len() {
+            let id = String::from_utf8_lossy(&bytes[i..i + 4]).into_owned();
+            let size = u32::from_le_bytes(bytes[i + 4..i + 8].try_into().unwrap()) as usize;
+            out.push((id, bytes[i + 8..i + 8 + size].to_vec()));
+            i += 8 + size + size % 2;
+        }
+        out
+    }
+
+    fn u32_at(data: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_chunks_are_readable() {
+        let path = std::env::temp_dir().join(format!("earworm-meta-{}.wav", std::process::id()));
+        let spec = super::super::WavFormat::Int16.spec(1000);
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for _ in 0..4000 {
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        // 120 BPM at 1kHz: 2000 samples per 4/4 bar
+        WavMetadata::new()
+            .with_title("Loop")
+            .with_tempo(120.0)
+            .with_loop(0, 2000)
+            .with_bar_markers()
+            .append_to(&path, 1000, 4000)
+            .unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(u32_at(&bytes, 4) as usize, bytes.len() - 8);
+        let chunks = chunks(&bytes);
+        let ids: Vec<&str> = chunks.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(
+            ids,
+            ["fmt ", "data", "LIST", "acid", "smpl", "cue ", "LIST"]
+        );
+
+        assert_eq!(&chunks[2].1, b"INFOINAM\x05\0\0\0Loop\0\0");
+        let acid = &chunks[3].1;
+        assert_eq!(u32_at(acid, 12), 8);
+        assert_eq!(f32::from_le_bytes(acid[20..24].try_into().unwrap()), 120.0);
+        let smpl = &chunks[4].1;
+        assert_eq!((u32_at(smpl, 44), u32_at(smpl, 48)), (0, 1999));
+        let cue = &chunks[5].1;
+        assert_eq!(u32_at(cue, 0), 2);
+        assert_eq!((u32_at(cue, 8), u32_at(cue, 32)), (0, 2000));
+        assert!(chunks[6].1.windows(6).any(|w| w == b"Bar 2\0"));
+
+        // Still a valid WAV
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.len(), 4000);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
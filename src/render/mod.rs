@@ -0,0 +1,29 @@
+//! Offline rendering to audio files.
+//!
+//! This module bounces any [`AudioSignal`](crate::AudioSignal) to a WAV file
+//! without opening an audio device. Use [`render_to_wav`] for a fixed-length
+//! render in one call, or [`WavWriter`] to pull samples in chunks (for example
+//! while advancing a sequencer between blocks). [`StemRenderer`] writes several
+//! signals to one file each in a single pass, for mixing in a DAW.
+//! [`WavMetadata`] adds title, tempo, loop points and markers to the files.
+//!
+//! With the `flac` feature, [`FlacWriter`] and [`render_to_flac`] write
+//! losslessly compressed FLAC files instead, for sharing renders directly.
+//! There is no OGG/Vorbis output: it is deferred until a Vorbis encoder can
+//! be added as a dependency.
+//!
+//! Requires the `render` feature.
+
+mod error;
+#[cfg(feature = "flac")]
+mod flac;
+mod metadata;
+mod stems;
+mod wav;
+
+pub use error::RenderError;
+#[cfg(feature = "flac")]
+pub use flac::{FlacDepth, FlacWriter, render_to_flac};
+pub use metadata::WavMetadata;
+pub use stems::StemRenderer;
+pub use wav::{WavFormat, WavWriter, render_to_wav};
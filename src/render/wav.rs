@@ -0,0 +1,442 @@
+//! Streaming WAV output.
+
+use super::{RenderError, WavMetadata};
+use crate::AudioSignal;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+/// Block size used when pulling samples from the signal.
+pub(super) const BLOCK_SIZE: usize = 512;
+
+/// Bytes of the 4 GiB RIFF size limit left for the header and metadata
+/// chunks.
+const HEADER_ROOM: u64 = 1 << 20;
+
+/// Sample encoding for rendered WAV files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WavFormat {
+    /// 16-bit signed integer PCM
+    Int16,
+    /// 24-bit signed integer PCM
+    Int24,
+    /// 32-bit IEEE float (no clipping)
+    #[default]
+    Float32,
+}
+
+impl WavFormat {
+    /// Returns the most samples a mono file in this format can hold.
+    ///
+    /// WAV stores sizes as 32-bit numbers, which caps a file at about 4 GiB
+    /// of sample data: around 6 hours of 32-bit float audio at 48kHz, or 8
+    /// hours of 24-bit audio.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::render::WavFormat;
+    ///
+    /// let hours = WavFormat::Float32.max_samples() as f64 / 48000.0 / 3600.0;
+    /// assert!(hours > 6.0 && hours < 6.3);
+    /// ```
+    pub fn max_samples(self) -> usize {
+        let bytes_per_sample = match self {
+            WavFormat::Int16 => 2,
+            WavFormat::Int24 => 3,
+            WavFormat::Float32 => 4,
+        };
+        ((u32::MAX as u64 - HEADER_ROOM) / bytes_per_sample) as usize
+    }
+
+    /// Fails if a file of `samples` samples would exceed
+    /// [`max_samples`](Self::max_samples).
+    pub(super) fn check_length(self, samples: usize) -> Result<(), RenderError> {
+        if samples > self.max_samples() {
+            return Err(RenderError::Format(format!(
+                "{} samples exceed the WAV size limit of {} samples",
+                samples,
+                self.max_samples()
+            )));
+        }
+        Ok(())
+    }
+
+    pub(super) fn spec(self, sample_rate: u32) -> hound::WavSpec {
+        let (bits_per_sample, sample_format) = match self {
+            WavFormat::Int16 => (16, hound::SampleFormat::Int),
+            WavFormat::Int24 => (24, hound::SampleFormat::Int),
+            WavFormat::Float32 => (32, hound::SampleFormat::Float),
+        };
+        hound::WavSpec {
+            channels: 1,
+            sample_rate,
+            bits_per_sample,
+            sample_format,
+        }
+    }
+
+    /// Encodes `samples` to `writer`, clipping integer formats.
+    pub(super) fn write<W>(
+        self,
+        writer: &mut hound::WavWriter<W>,
+        samples: &[f64],
+    ) -> Result<(), RenderError>
+    where
+        W: std::io::Write + std::io::Seek,
+    {
+        for &sample in samples {
+            match self {
+                WavFormat::Int16 => {
+                    let v = sample.clamp(-1.0, 1.0) * i16::MAX as f64;
+                    writer.write_sample(v.round() as i16)?;
+                }
+                WavFormat::Int24 => {
+                    let v = sample.clamp(-1.0, 1.0) * 8_388_607.0;
+                    writer.write_sample(v.round() as i32)?;
+                }
+                WavFormat::Float32 => writer.write_sample(sample as f32)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A streaming sink that pulls samples from a signal and writes them to a
+/// mono WAV file.
+///
+/// The file's sample rate is the signal's `SAMPLE_RATE`. Samples are pulled
+/// in blocks with [`Signal::process`](crate::Signal::process), so block-based
+/// signals render at full speed. Integer formats clip to [-1.0, 1.0]; the
+/// float format stores samples as-is.
+///
+/// Call [`finalize`](Self::finalize) when done to write the WAV header. If
+/// the writer is dropped instead, the header is still written but any error
+/// is lost, along with any [metadata](Self::with_metadata).
+///
+/// # Long Renders
+///
+/// Memory use doesn't grow with the length of the render: samples go to
+/// disk a block at a time, so a generative piece can run for hours. Use
+/// [`with_flush_interval`](Self::with_flush_interval) to keep the file
+/// playable while it is written, in case the render is interrupted. WAV
+/// files are limited to about 4 GiB (see [`WavFormat::max_samples`]); the
+/// `flac` feature's `FlacWriter` has no practical limit.
+///
+/// # Examples
+///
+/// ```no_run
+/// use earworm::{Pitched, SineOscillator};
+/// use earworm::render::{WavFormat, WavWriter};
+///
+/// let osc = SineOscillator::<44100>::new(440.0);
+/// let mut writer = WavWriter::create("tone.wav", osc, WavFormat::Int16)?;
+/// writer.render_seconds(0.5)?;
+/// writer.signal_mut().set_frequency(660.0);
+/// writer.render_seconds(0.5)?;
+/// writer.finalize()?;
+/// # Ok::<(), earworm::render::RenderError>(())
+/// ```
+pub struct WavWriter<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
+    signal: S,
+    writer: hound::WavWriter<BufWriter<File>>,
+    format: WavFormat,
+    buffer: Vec<f64>,
+    samples_written: usize,
+    path: PathBuf,
+    metadata: Option<WavMetadata>,
+    /// Samples between header updates, if checkpointing
+    flush_interval: Option<usize>,
+    /// Samples written since the last header update
+    since_flush: usize,
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> WavWriter<SAMPLE_RATE, S> {
+    /// Creates the file at `path` and prepares `signal` for rendering.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Output file; it is overwritten if it exists
+    /// * `signal` - The signal to render
+    /// * `format` - Sample encoding
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RenderError::Io`] if the file cannot be created.
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        mut signal: S,
+        format: WavFormat,
+    ) -> Result<Self, RenderError> {
+        let path = path.as_ref().to_path_buf();
+        let writer = hound::WavWriter::create(&path, format.spec(SAMPLE_RATE))?;
+        signal.prepare(BLOCK_SIZE, SAMPLE_RATE);
+        Ok(Self {
+            signal,
+            writer,
+            format,
+            buffer: vec![0.0; BLOCK_SIZE],
+            samples_written: 0,
+            path,
+            metadata: None,
+            flush_interval: None,
+            since_flush: 0,
+        })
+    }
+
+    /// Writes `metadata` into the file when it is finalized (builder style).
+    pub fn with_metadata(mut self, metadata: WavMetadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Updates the WAV header every `seconds` of rendered audio (builder
+    /// style).
+    ///
+    /// Between updates the file on disk is a valid WAV holding everything
+    /// written up to the last one, so a long render that crashes or is
+    /// killed still leaves a playable file. Each update costs two seeks and
+    /// a flush, so intervals of a few seconds or more are best.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `seconds` is not positive.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use earworm::render::{WavFormat, WavWriter};
+    /// use earworm::{SignalExt, WhiteNoise};
+    ///
+    /// // Three hours of gentle noise, checkpointed every minute
+    /// let noise = WhiteNoise::<48000>::new().gain(0.1);
+    /// let mut writer =
+    ///     WavWriter::create("long.wav", noise, WavFormat::Int24)?.with_flush_interval(60.0);
+    /// writer.render_seconds(3.0 * 3600.0)?;
+    /// writer.finalize()?;
+    /// # Ok::<(), earworm::render::RenderError>(())
+    /// ```
+    pub fn with_flush_interval(mut self, seconds: f64) -> Self {
+        assert!(seconds > 0.0, "flush interval must be positive");
+        self.flush_interval = Some(seconds_to_samples(seconds, SAMPLE_RATE).max(1));
+        self
+    }
+
+    /// Pulls `samples` samples from the signal and writes them to the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails, or [`RenderError::Format`] without
+    /// writing anything if the file would grow past
+    /// [`WavFormat::max_samples`].
+    pub fn render(&mut self, samples: usize) -> Result<(), RenderError> {
+        self.format
+            .check_length(self.samples_written.saturating_add(samples))?;
+        let mut remaining = samples;
+        while remaining > 0 {
+            let n = remaining.min(BLOCK_SIZE);
+            let block = &mut self.buffer[..n];
+            self.signal.process(block);
+            self.format.write(&mut self.writer, block)?;
+            remaining -= n;
+            self.samples_written += n;
+
+            if let Some(interval) = self.flush_interval {
+                self.since_flush += n;
+                if self.since_flush >= interval {
+                    self.writer.flush()?;
+                    self.since_flush = 0;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Pulls `seconds` worth of samples from the signal and writes them.
+    ///
+    /// The duration is rounded to the nearest whole sample; negative
+    /// durations write nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails.
+    pub fn render_seconds(&mut self, seconds: f64) -> Result<(), RenderError> {
+        self.render(seconds_to_samples(seconds, SAMPLE_RATE))
+    }
+
+    /// Returns the number of samples written so far.
+    pub fn samples_written(&self) -> usize {
+        self.samples_written
+    }
+
+    /// Returns a mutable reference to the signal, for changing it between
+    /// chunks.
+    pub fn signal_mut(&mut self) -> &mut S {
+        &mut self.signal
+    }
+
+    /// Writes the WAV header and any metadata, closes the file, and returns
+    /// the signal.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the header or metadata cannot be written.
+    pub fn finalize(self) -> Result<S, RenderError> {
+        self.writer.finalize()?;
+        if let Some(metadata) = &self.metadata {
+            metadata.append_to(&self.path, SAMPLE_RATE, self.samples_written)?;
+        }
+        Ok(self.signal)
+    }
+}
+
+/// Renders `duration` seconds of `signal` to a mono WAV file.
+///
+/// Writes 32-bit float samples at the signal's sample rate. Use
+/// [`WavWriter`] for other formats or to render in chunks.
+///
+/// # Arguments
+///
+/// * `signal` - The signal to render
+/// * `duration` - Length of the render in seconds
+/// * `path` - Output file; it is overwritten if it exists
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be created or written.
+///
+/// # Examples
+///
+/// ```no_run
+/// use earworm::SineOscillator;
+/// use earworm::render::render_to_wav;
+///
+/// let osc = SineOscillator::<48000>::new(440.0);
+/// render_to_wav(osc, 2.0, "a440.wav")?;
+/// # Ok::<(), earworm::render::RenderError>(())
+/// ```
+pub fn render_to_wav<const SAMPLE_RATE: u32, S, P>(
+    signal: S,
+    duration: f64,
+    path: P,
+) -> Result<(), RenderError>
+where
+    S: AudioSignal<SAMPLE_RATE>,
+    P: AsRef<Path>,
+{
+    let mut writer = WavWriter::create(path, signal, WavFormat::Float32)?;
+    writer.render_seconds(duration)?;
+    writer.finalize()?;
+    Ok(())
+}
+
+pub(super) fn seconds_to_samples(seconds: f64, sample_rate: u32) -> usize {
+    (seconds * sample_rate as f64).round().max(0.0) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstantSignal, Signal, SineOscillator};
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("earworm-{}-{}.wav", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_render_to_wav_writes_float_samples() {
+        let path = temp_path("render-float");
+        let osc = SineOscillator::<8000>::new(100.0);
+        render_to_wav(osc, 0.25, &path).unwrap();
+
+        let mut reader = hound::WavReader::open(&path).unwrap();
+        let spec = reader.spec();
+        assert_eq!(spec.sample_rate, 8000);
+        assert_eq!(spec.channels, 1);
+        assert_eq!(spec.sample_format, hound::SampleFormat::Float);
+
+        let samples: Vec<f32> = reader.samples::<f32>().map(Result::unwrap).collect();
+        let mut expected = SineOscillator::<8000>::new(100.0);
+        assert_eq!(samples.len(), 2000);
+        for &s in &samples {
+            assert!((s as f64 - expected.next_sample()).abs() < 1e-6);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_int_formats_clip() {
+        for (format, max) in [
+            (WavFormat::Int16, i16::MAX as i32),
+            (WavFormat::Int24, 8_388_607),
+        ] {
+            let path = temp_path("render-clip");
+            let signal = ConstantSignal::<8000>(2.0);
+            let mut writer = WavWriter::create(&path, signal, format).unwrap();
+            writer.render(10).unwrap();
+            writer.finalize().unwrap();
+
+            let mut reader = hound::WavReader::open(&path).unwrap();
+            let samples: Vec<i32> = reader.samples::<i32>().map(Result::unwrap).collect();
+            assert_eq!(samples, vec![max; 10]);
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_streaming_chunks_are_contiguous() {
+        let path = temp_path("render-chunks");
+        let osc = SineOscillator::<8000>::new(100.0);
+        let mut writer = WavWriter::create(&path, osc, WavFormat::Float32).unwrap();
+        writer.render(700).unwrap();
+        writer.render_seconds(0.1).unwrap();
+        assert_eq!(writer.samples_written(), 1500);
+        writer.finalize().unwrap();
+
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.len(), 1500);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_flush_interval_keeps_file_readable() {
+        let path = temp_path("render-flush");
+        let osc = SineOscillator::<8000>::new(100.0);
+        let mut writer = WavWriter::create(&path, osc, WavFormat::Int16)
+            .unwrap()
+            .with_flush_interval(0.1);
+        // The header is updated after the block that crosses 800 samples
+        writer.render(700).unwrap();
+        writer.render(300).unwrap();
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.len(), 1000);
+        writer.finalize().unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_render_past_size_limit_fails_up_front() {
+        let path = temp_path("render-limit");
+        let mut writer =
+            WavWriter::create(&path, ConstantSignal::<8000>(0.0), WavFormat::Int16).unwrap();
+        writer.render(10).unwrap();
+        let too_many = WavFormat::Int16.max_samples() - 5;
+        assert!(matches!(
+            writer.render(too_many),
+            Err(RenderError::Format(_))
+        ));
+        assert_eq!(writer.samples_written(), 10);
+        writer.finalize().unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_create_fails_for_missing_directory() {
+        let path = std::env::temp_dir()
+            .join("earworm-no-such-dir")
+            .join("out.wav");
+        let result = WavWriter::create(&path, ConstantSignal::<8000>(0.0), WavFormat::Int16);
+        assert!(matches!(result, Err(RenderError::Io(_))));
+    }
+}
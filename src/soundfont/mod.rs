@@ -0,0 +1,33 @@
+//! SoundFont 2 instruments.
+//!
+//! [`SoundFont`] reads `.sf2` banks and turns their presets into
+//! [`MultiSampler`](crate::MultiSampler)s, so the many free General MIDI
+//! banks can stand in for realistic instruments: each preset zone becomes a
+//! key zone playing its sample with the bank's root key, tuning, loop points
+//! and attenuation. Envelopes, filters and modulators in the bank are not
+//! applied; pair the sampler with earworm's own envelope and filters instead.
+//!
+//! Requires the `soundfont` feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use earworm::soundfont::SoundFont;
+//! use earworm::{Pitched, Signal};
+//!
+//! const SAMPLE_RATE: u32 = 44100;
+//!
+//! let bank = SoundFont::open("banks/general_user.sf2")?;
+//! // General MIDI program 1, acoustic grand piano, played at velocity 100
+//! let piano = bank.preset(0, 0).expect("bank has a piano");
+//! let mut keys = bank.sampler::<SAMPLE_RATE>(piano, 100);
+//!
+//! keys.set_frequency(261.63); // middle C
+//! keys.restart();
+//! let first = keys.next_sample();
+//! # Ok::<(), earworm::soundfont::SoundFontError>(())
+//! ```
+
+mod sf2;
+
+pub use sf2::{Preset, SoundFont, SoundFontError};
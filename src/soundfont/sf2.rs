@@ -0,0 +1,727 @@
+//! Reading SoundFont 2 banks and building samplers from their presets.
+
+use crate::{MultiSampler, Sampler};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Error reading a SoundFont file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SoundFontError {
+    /// The file could not be read
+    Io(String),
+    /// The data is not a RIFF `sfbk` file
+    NotSoundFont,
+    /// The data ended in the middle of a chunk or record
+    Truncated,
+    /// A required chunk is absent
+    MissingChunk(&'static str),
+    /// A record points outside the data it indexes
+    InvalidIndex(&'static str),
+}
+
+impl fmt::Display for SoundFontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SoundFontError::Io(e) => write!(f, "I/O error: {}", e),
+            SoundFontError::NotSoundFont => write!(f, "not a SoundFont 2 file"),
+            SoundFontError::Truncated => write!(f, "SoundFont file is truncated"),
+            SoundFontError::MissingChunk(id) => write!(f, "missing '{}' chunk", id),
+            SoundFontError::InvalidIndex(id) => write!(f, "'{}' index out of range", id),
+        }
+    }
+}
+
+impl std::error::Error for SoundFontError {}
+
+impl From<std::io::Error> for SoundFontError {
+    fn from(e: std::io::Error) -> Self {
+        SoundFontError::Io(e.to_string())
+    }
+}
+
+// Generator operators used when building samplers
+const START_OFFSET: usize = 0;
+const END_OFFSET: usize = 1;
+const LOOP_START_OFFSET: usize = 2;
+const LOOP_END_OFFSET: usize = 3;
+const START_COARSE_OFFSET: usize = 4;
+const END_COARSE_OFFSET: usize = 12;
+const INSTRUMENT: usize = 41;
+const KEY_RANGE: usize = 43;
+const VELOCITY_RANGE: usize = 44;
+const LOOP_START_COARSE_OFFSET: usize = 45;
+const INITIAL_ATTENUATION: usize = 48;
+const LOOP_END_COARSE_OFFSET: usize = 50;
+const COARSE_TUNE: usize = 51;
+const FINE_TUNE: usize = 52;
+const SAMPLE_ID: usize = 53;
+const SAMPLE_MODES: usize = 54;
+const OVERRIDING_ROOT_KEY: usize = 58;
+const GENERATOR_COUNT: usize = 61;
+
+/// The generator amounts a zone sets, indexed by operator.
+type Generators = [Option<u16>; GENERATOR_COUNT];
+
+/// A preset or instrument zone: its generators and, for all but global
+/// zones, the instrument or sample it plays.
+#[derive(Debug, Clone, PartialEq)]
+struct Zone {
+    generators: Generators,
+}
+
+impl Zone {
+    fn get(&self, operator: usize) -> Option<u16> {
+        self.generators[operator]
+    }
+
+    fn signed(&self, operator: usize) -> i32 {
+        self.get(operator).map_or(0, |amount| amount as i16 as i32)
+    }
+
+    /// A `lo..=hi` range generator, full range if unset.
+    fn range(&self, operator: usize) -> (u8, u8) {
+        self.get(operator).map_or((0, 127), |amount| {
+            ((amount & 0xFF) as u8, (amount >> 8) as u8)
+        })
+    }
+
+    /// Overlays `local` on this (global) zone.
+    fn merged(&self, local: &Zone) -> Zone {
+        let mut generators = self.generators;
+        for (generator, value) in generators.iter_mut().zip(local.generators) {
+            if value.is_some() {
+                *generator = value;
+            }
+        }
+        Zone { generators }
+    }
+}
+
+/// The zones of a preset or instrument, split into the optional global zone
+/// and the zones that play something.
+#[derive(Debug, Clone, PartialEq, Default)]
+struct Zones {
+    global: Option<Zone>,
+    zones: Vec<Zone>,
+}
+
+/// A playable sound in a [`SoundFont`], selected by bank and program number
+/// like a General MIDI patch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preset {
+    name: String,
+    bank: u16,
+    program: u16,
+    zones: Zones,
+}
+
+impl Preset {
+    /// Returns the preset name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the MIDI bank number (128 holds percussion kits in General
+    /// MIDI banks).
+    pub fn bank(&self) -> u16 {
+        self.bank
+    }
+
+    /// Returns the MIDI program number (0-127).
+    pub fn program(&self) -> u16 {
+        self.program
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct SampleHeader {
+    start: u32,
+    end: u32,
+    loop_start: u32,
+    loop_end: u32,
+    sample_rate: u32,
+    original_pitch: u8,
+    pitch_correction: i8,
+    sample_type: u16,
+}
+
+/// A parsed SoundFont 2 bank.
+///
+/// Holds the 16-bit sample data and the preset and instrument zones, and
+/// builds a [`MultiSampler`] for any preset on demand with
+/// [`sampler`](Self::sampler). Samplers built from the same bank share no
+/// data with it, so the bank can be dropped once the instruments are made.
+///
+/// Stereo presets play through a single channel, as [`MultiSampler`] is
+/// mono. Samples stored in ROM are skipped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoundFont {
+    name: Option<String>,
+    data: Vec<i16>,
+    presets: Vec<Preset>,
+    instruments: Vec<Zones>,
+    samples: Vec<SampleHeader>,
+}
+
+impl SoundFont {
+    /// Reads and parses the SoundFont file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SoundFontError::Io`] if the file cannot be read, or a parse
+    /// error as for [`parse`](Self::parse).
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, SoundFontError> {
+        Self::parse(&std::fs::read(path)?)
+    }
+
+    /// Parses the bytes of a SoundFont 2 file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data isn't a SoundFont, is truncated, lacks
+    /// the sample data or preset chunks, or has records indexing past the
+    /// end of the tables they refer to.
+    pub fn parse(bytes: &[u8]) -> Result<Self, SoundFontError> {
+        let mut reader = Reader { bytes, position: 0 };
+        if reader.take(4).ok() != Some(b"RIFF".as_slice()) {
+            return Err(SoundFontError::NotSoundFont);
+        }
+        let length = reader.u32()? as usize;
+        let mut riff = Reader {
+            bytes: reader.take(length.min(bytes.len() - reader.position))?,
+            position: 0,
+        };
+        if riff.take(4)? != b"sfbk" {
+            return Err(SoundFontError::NotSoundFont);
+        }
+
+        let mut name = None;
+        let mut data = None;
+        let mut hydra: HashMap<[u8; 4], &[u8]> = HashMap::new();
+        while let Some((id, body)) = riff.chunk()? {
+            if id != *b"LIST" || body.len() < 4 {
+                continue;
+            }
+            let mut list = Reader {
+                bytes: &body[4..],
+                position: 0,
+            };
+            while let Some((id, body)) = list.chunk()? {
+                match &id {
+                    b"INAM" => {
+                        let text = body.split(|&b| b == 0).next().unwrap_or_default();
+                        name = Some(String::from_utf8_lossy(text).into_owned());
+                    }
+                    b"smpl" => {
+                        data = Some(
+                            body.chunks_exact(2)
+                                .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+                                .collect(),
+                        );
+                    }
+                    _ => {
+                        hydra.insert(id, body);
+                    }
+                }
+            }
+        }
+
+        let table = |id: &'static str| {
+            hydra
+                .get(id.as_bytes())
+                .copied()
+                .ok_or(SoundFontError::MissingChunk(id))
+        };
+        let preset_headers = records(table("phdr")?, 38)?;
+        let preset_bags = bags(table("pbag")?)?;
+        let preset_generators = generators(table("pgen")?)?;
+        let instrument_headers = records(table("inst")?, 22)?;
+        let instrument_bags = bags(table("ibag")?)?;
+        let instrument_generators = generators(table("igen")?)?;
+
+        // Each header's zones run up to the next header's; the last header
+        // of each table is a terminator that only marks the end
+        let mut presets = Vec::new();
+        for pair in preset_headers.windows(2) {
+            let mut header = Reader {
+                bytes: pair[0],
+                position: 20,
+            };
+            let program = header.u16()?;
+            let bank = header.u16()?;
+            let first = header.u16()? as usize;
+            let last = u16::from_le_bytes([pair[1][24], pair[1][25]]) as usize;
+            presets.push(Preset {
+                name: record_name(pair[0]),
+                bank,
+                program,
+                zones: zones(
+                    &preset_bags,
+                    &preset_generators,
+                    first..last,
+                    INSTRUMENT,
+                    "pbag",
+                )?,
+            });
+        }
+        let mut instruments = Vec::new();
+        for pair in instrument_headers.windows(2) {
+            let first = u16::from_le_bytes([pair[0][20], pair[0][21]]) as usize;
+            let last = u16::from_le_bytes([pair[1][20], pair[1][21]]) as usize;
+            instruments.push(zones(
+                &instrument_bags,
+                &instrument_generators,
+                first..last,
+                SAMPLE_ID,
+                "ibag",
+            )?);
+        }
+
+        let mut samples = Vec::new();
+        for record in records(table("shdr")?, 46)? {
+            let mut header = Reader {
+                bytes: record,
+                position: 20,
+            };
+            samples.push(SampleHeader {
+                start: header.u32()?,
+                end: header.u32()?,
+                loop_start: header.u32()?,
+                loop_end: header.u32()?,
+                sample_rate: header.u32()?,
+                original_pitch: header.u8()?,
+                pitch_correction: header.u8()? as i8,
+                sample_type: {
+                    header.u16()?; // sample link
+                    header.u16()?
+                },
+            });
+        }
+        samples.pop();
+
+        presets.sort_by_key(|preset| (preset.bank, preset.program));
+        Ok(SoundFont {
+            name,
+            data: data.ok_or(SoundFontError::MissingChunk("smpl"))?,
+            presets,
+            instruments,
+            samples,
+        })
+    }
+
+    /// Returns the bank name, if the file gives one.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the presets, ordered by bank and program.
+    pub fn presets(&self) -> &[Preset] {
+        &self.presets
+    }
+
+    /// Returns the preset at `bank` and `program`, if the bank has one.
+    pub fn preset(&self, bank: u16, program: u16) -> Option<&Preset> {
+        self.presets
+            .iter()
+            .find(|preset| preset.bank == bank && preset.program == program)
+    }
+
+    /// Builds a key-zoned sampler playing `preset` at `velocity`.
+    ///
+    /// Every zone whose velocity range covers `velocity` becomes a key zone
+    /// with the sample's root key, tuning, loop and attenuation. Velocity
+    /// layers are chosen here rather than per note, so build one sampler per
+    /// layer to switch layers while playing. Zones pointing at missing
+    /// instruments or samples are skipped.
+    pub fn sampler<const SAMPLE_RATE: u32>(
+        &self,
+        preset: &Preset,
+        velocity: u8,
+    ) -> MultiSampler<SAMPLE_RATE> {
+        let mut sampler = MultiSampler::new();
+        // Recordings already converted, by sample range, so zones that share
+        // a sample share its data
+        let mut recordings: HashMap<(usize, usize), Arc<[f64]>> = HashMap::new();
+        let covers = |(low, high): (u8, u8), value: u8| low <= value && value <= high;
+
+        for preset_zone in &preset.zones.zones {
+            let preset_zone = match &preset.zones.global {
+                Some(global) => global.merged(preset_zone),
+                None => preset_zone.clone(),
+            };
+            let Some(instrument) = preset_zone
+                .get(INSTRUMENT)
+                .and_then(|index| self.instruments.get(index as usize))
+            else {
+                continue;
+            };
+            if !covers(preset_zone.range(VELOCITY_RANGE), velocity) {
+                continue;
+            }
+
+            for zone in &instrument.zones {
+                let zone = match &instrument.global {
+                    Some(global) => global.merged(zone),
+                    None => zone.clone(),
+                };
+                let Some(header) = zone
+                    .get(SAMPLE_ID)
+                    .and_then(|index| self.samples.get(index as usize))
+                else {
+                    continue;
+                };
+                if !covers(zone.range(VELOCITY_RANGE), velocity)
+                    || header.sample_type & 0x8000 != 0
+                    || header.sample_rate == 0
+                {
+                    continue;
+                }
+
+                // Key ranges at both levels must include the note
+                let (preset_low, preset_high) = preset_zone.range(KEY_RANGE);
+                let (low, high) = zone.range(KEY_RANGE);
+                let (low, high) = (low.max(preset_low), high.min(preset_high).min(127));
+                if low > high {
+                    continue;
+                }
+
+                let offset = |base: u32, fine: usize, coarse: usize| {
+                    (base as i64 + zone.signed(fine) as i64 + 32768 * zone.signed(coarse) as i64)
+                        .clamp(0, self.data.len() as i64) as usize
+                };
+                let start = offset(header.start, START_OFFSET, START_COARSE_OFFSET);
+                let end = offset(header.end, END_OFFSET, END_COARSE_OFFSET);
+                if start >= end {
+                    continue;
+                }
+                let recording = recordings
+                    .entry((start, end))
+                    .or_insert_with(|| {
+                        self.data[start..end]
+                            .iter()
+                            .map(|&s| s as f64 / 32768.0)
+                            .collect()
+                    })
+                    .clone();
+
+                let root = match zone.get(OVERRIDING_ROOT_KEY) {
+                    Some(key) if key <= 127 => key as u8,
+                    _ if header.original_pitch <= 127 => header.original_pitch,
+                    _ => 60,
+                };
+                let cents = 100 * (zone.signed(COARSE_TUNE) + preset_zone.signed(COARSE_TUNE))
+                    + zone.signed(FINE_TUNE)
+                    + preset_zone.signed(FINE_TUNE)
+                    + header.pitch_correction as i32;
+                let mut voice = Sampler::<SAMPLE_RATE>::new(recording, header.sample_rate, root)
+                    .with_tuning(cents as f64);
+
+                // Sample modes 1 and 3 loop; loop points are relative to
+                // the whole sample data, so shift them into the recording
+                if matches!(zone.get(SAMPLE_MODES), Some(1 | 3)) {
+                    let loop_start = offset(
+                        header.loop_start,
+                        LOOP_START_OFFSET,
+                        LOOP_START_COARSE_OFFSET,
+                    );
+                    let loop_end = offset(header.loop_end, LOOP_END_OFFSET, LOOP_END_COARSE_OFFSET);
+                    if start <= loop_start && loop_start < loop_end && loop_end <= end {
+                        voice = voice.with_loop(loop_start - start, loop_end - start);
+                    }
+                }
+
+                // Attenuation is in centibels, summed across both levels
+                let attenuation = (zone.signed(INITIAL_ATTENUATION)
+                    + preset_zone.signed(INITIAL_ATTENUATION))
+                .max(0);
+                let gain = 10f64.powf(-attenuation as f64 / 200.0);
+                sampler.add_zone(low, high, voice, gain);
+            }
+        }
+        sampler
+    }
+}
+
+/// Splits a table into fixed-size records.
+fn records(table: &[u8], size: usize) -> Result<Vec<&[u8]>, SoundFontError> {
+    if !table.len().is_multiple_of(size) {
+        return Err(SoundFontError::Truncated);
+    }
+    Ok(table.chunks_exact(size).collect())
+}
+
+/// The first generator index of each bag, terminator included.
+fn bags(table: &[u8]) -> Result<Vec<usize>, SoundFontError> {
+    Ok(records(table, 4)?
+        .into_iter()
+        .map(|record| u16::from_le_bytes([record[0], record[1]]) as usize)
+        .collect())
+}
+
+/// Each generator's operator and amount, terminator included.
+fn generators(table: &[u8]) -> Result<Vec<(u16, u16)>, SoundFontError> {
+    Ok(records(table, 4)?
+        .into_iter()
+        .map(|record| {
+            (
+                u16::from_le_bytes([record[0], record[1]]),
+                u16::from_le_bytes([record[2], record[3]]),
+            )
+        })
+        .collect())
+}
+
+/// Collects the zones of bags `range`. A first zone without the `terminal`
+/// generator (instrument or sample ID) is the global zone.
+fn zones(
+    bags: &[usize],
+    generators: &[(u16, u16)],
+    range: std::ops::Range<usize>,
+    terminal: usize,
+    table: &'static str,
+) -> Result<Zones, SoundFontError> {
+    if range.start > range.end || range.end >= bags.len() {
+        return Err(SoundFontError::InvalidIndex(table));
+    }
+    let mut zones = Zones::default();
+    for bag in range {
+        let (first, last) = (bags[bag], bags[bag + 1]);
+        let Some(list) = generators.get(first..last) else {
+            return Err(SoundFontError::InvalidIndex(table));
+        };
+        let mut zone = Zone {
+            generators: [None; GENERATOR_COUNT],
+        };
+        for &(operator, amount) in list {
+            if let Some(generator) = zone.generators.get_mut(operator as usize) {
+                *generator = Some(amount);
+            }
+        }
+        if zone.get(terminal).is_some() {
+            zones.zones.push(zone);
+        } else if zones.global.is_none() && zones.zones.is_empty() {
+            zones.global = Some(zone);
+        }
+    }
+    Ok(zones)
+}
+
+/// A zero-padded 20-byte name at the start of a record.
+fn record_name(record: &[u8]) -> String {
+    let name = record[..20].split(|&b| b == 0).next().unwrap_or_default();
+    String::from_utf8_lossy(name).trim_end().to_string()
+}
+
+/// A RIFF chunk's ID and body.
+type Chunk<'a> = ([u8; 4], &'a [u8]);
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], SoundFontError> {
+        let end = self
+            .position
+            .checked_add(count)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or(SoundFontError::Truncated)?;
+        let slice = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, SoundFontError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, SoundFontError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, SoundFontError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// The next RIFF chunk's ID and body, skipping the pad byte after odd
+    /// lengths, or `None` at the end.
+    fn chunk(&mut self) -> Result<Option<Chunk<'a>>, SoundFontError> {
+        if self.position >= self.bytes.len() {
+            return Ok(None);
+        }
+        let id = self.take(4)?;
+        let length = self.u32()? as usize;
+        let body = self.take(length)?;
+        if !length.is_multiple_of(2) && self.position < self.bytes.len() {
+            self.position += 1;
+        }
+        Ok(Some(([id[0], id[1], id[2], id[3]], body)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LoopMode, Pitched, Signal};
+
+    /// A RIFF chunk, padded to an even length.
+    fn chunk(id: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let pad = &[0][..data.len() % 2];
+        [id.as_slice(), &(data.len() as u32).to_le_bytes(), data, pad].concat()
+    }
+
+    fn list(kind: &[u8; 4], chunks: &[Vec<u8>]) -> Vec<u8> {
+        chunk(b"LIST", &[kind.as_slice(), &chunks.concat()].concat())
+    }
+
+    fn name(text: &str) -> Vec<u8> {
+        let mut bytes = text.as_bytes().to_vec();
+        bytes.resize(20, 0);
+        bytes
+    }
+
+    fn generator(operator: u16, amount: u16) -> Vec<u8> {
+        [operator.to_le_bytes(), amount.to_le_bytes()].concat()
+    }
+
+    fn sample_header(
+        label: &str,
+        start: u32,
+        end: u32,
+        loop_points: (u32, u32),
+        root: u8,
+    ) -> Vec<u8> {
+        [
+            name(label),
+            [start, end, loop_points.0, loop_points.1, 1000]
+                .iter()
+                .flat_map(|v| v.to_le_bytes())
+                .collect(),
+            vec![root, 0, 0, 0, 1, 0],
+        ]
+        .concat()
+    }
+
+    /// A bank with one preset (bank 0, program 5) of one instrument that
+    /// plays a constant "low" sample up to key 59 and a looping "high"
+    /// sample above, quieter by 6dB.
+    fn test_bank() -> Vec<u8> {
+        let mut data = vec![8192i16; 100];
+        data.extend([16384i16; 100]);
+        let smpl: Vec<u8> = data.iter().flat_map(|s| s.to_le_bytes()).collect();
+
+        let phdr = [
+            name("Test Keys"),
+            [5u16, 0, 0].iter().flat_map(|v| v.to_le_bytes()).collect(),
+            vec![0; 12],
+            name("EOP"),
+            [0u16, 0, 1].iter().flat_map(|v| v.to_le_bytes()).collect(),
+            vec![0; 12],
+        ]
+        .concat();
+        let pbag = [0u16, 0, 1, 0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect::<Vec<_>>();
+        let pgen = [generator(INSTRUMENT as u16, 0), generator(0, 0)].concat();
+        let inst = [name("Keys"), vec![0, 0], name("EOI"), vec![3, 0]].concat();
+        // A global zone setting the velocity range, then two sample zones
+        let ibag = [0u16, 0, 1, 0, 3, 0, 7, 0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect::<Vec<_>>();
+        let igen = [
+            generator(VELOCITY_RANGE as u16, 1 | (127 << 8)),
+            generator(KEY_RANGE as u16, 59 << 8),
+            generator(SAMPLE_ID as u16, 0),
+            generator(KEY_RANGE as u16, 60 | (127 << 8)),
+            generator(INITIAL_ATTENUATION as u16, 60),
+            generator(SAMPLE_MODES as u16, 1),
+            generator(SAMPLE_ID as u16, 1),
+            generator(0, 0),
+        ]
+        .concat();
+        let shdr = [
+            sample_header("low", 0, 100, (0, 0), 48),
+            sample_header("high", 100, 200, (150, 190), 72),
+            sample_header("EOS", 0, 0, (0, 0), 0),
+        ]
+        .concat();
+
+        let body = [
+            b"sfbk".to_vec(),
+            list(
+                b"INFO",
+                &[chunk(b"ifil", &[2, 0, 1, 0]), chunk(b"INAM", b"Tiny\0")],
+            ),
+            list(b"sdta", &[chunk(b"smpl", &smpl)]),
+            list(
+                b"pdta",
+                &[
+                    chunk(b"phdr", &phdr),
+                    chunk(b"pbag", &pbag),
+                    chunk(b"pmod", &[0; 10]),
+                    chunk(b"pgen", &pgen),
+                    chunk(b"inst", &inst),
+                    chunk(b"ibag", &ibag),
+                    chunk(b"imod", &[0; 10]),
+                    chunk(b"igen", &igen),
+                    chunk(b"shdr", &shdr),
+                ],
+            ),
+        ]
+        .concat();
+        chunk(b"RIFF", &body)
+    }
+
+    #[test]
+    fn test_parse_presets() {
+        let bank = SoundFont::parse(&test_bank()).unwrap();
+        assert_eq!(bank.name(), Some("Tiny"));
+        assert_eq!(bank.presets().len(), 1);
+        let preset = bank.preset(0, 5).unwrap();
+        assert_eq!(preset.name(), "Test Keys");
+        assert!(bank.preset(0, 0).is_none());
+
+        assert_eq!(
+            SoundFont::parse(b"RIFF\x04\0\0\0WAVE"),
+            Err(SoundFontError::NotSoundFont)
+        );
+        assert_eq!(
+            SoundFont::parse(&chunk(b"RIFF", b"sfbk")),
+            Err(SoundFontError::MissingChunk("phdr"))
+        );
+    }
+
+    #[test]
+    fn test_sampler_maps_zones() {
+        let bank = SoundFont::parse(&test_bank()).unwrap();
+        let mut keys = bank.sampler::<1000>(bank.preset(0, 5).unwrap(), 100);
+        let ranges: Vec<_> = keys.zones().iter().map(|zone| zone.keys()).collect();
+        assert_eq!(ranges, [(0, 59), (60, 127)]);
+        let high = keys.zones()[1].sampler();
+        assert_eq!(high.loop_mode(), LoopMode::Loop);
+        assert_eq!(high.loop_points(), (50, 90));
+
+        keys.set_frequency(110.0);
+        keys.restart();
+        assert_eq!(keys.next_sample(), 0.25);
+
+        // -6dB on the upper zone
+        keys.set_frequency(880.0);
+        keys.restart();
+        assert!((keys.next_sample() - 0.5 * 10f64.powf(-0.3)).abs() < 1e-12);
+
+        // No zone covers velocity 0
+        assert!(
+            bank.sampler::<1000>(&bank.presets()[0], 0)
+                .zones()
+                .is_empty()
+        );
+    }
+}
@@ -4,9 +4,9 @@
 
 use crate::core::{AudioSignal, Param};
 use crate::synthesis::effects::{
-    Bitcrusher, Compressor, Delay, Distortion, Limiter, Tremolo, Vibrato,
+    Bitcrusher, CabModel, CabSim, Compressor, Delay, Distortion, Limiter, Tremolo, Vibrato,
 };
-use crate::synthesis::filters::BiquadFilter;
+use crate::synthesis::filters::{BiquadFilter, DcBlocker, OnePoleLowpass};
 
 /// Extension trait providing convenient filter and effect methods for audio signals.
 ///
@@ -255,6 +255,24 @@ pub trait AudioSignalExt<const SAMPLE_RATE: u32>: AudioSignal<SAMPLE_RATE> + Siz
         Distortion::new(self, drive, mix)
     }
 
+    /// Runs this audio signal through a guitar cabinet simulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - Cabinet voicing
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{AudioSignalExt, CabModel, SawtoothOscillator};
+    ///
+    /// let osc = SawtoothOscillator::<44100>::new(110.0);
+    /// let mut amp = osc.distortion(10.0, 1.0).cab_sim(CabModel::Combo1x12);
+    /// ```
+    fn cab_sim(self, model: CabModel) -> CabSim<SAMPLE_RATE, Self> {
+        CabSim::new(self, model)
+    }
+
     // ===== Dynamics Processing =====
 
     /// Applies a compressor to control the dynamic range of this audio signal.
@@ -340,6 +358,43 @@ pub trait AudioSignalExt<const SAMPLE_RATE: u32>: AudioSignal<SAMPLE_RATE> + Siz
     ) -> Bitcrusher<SAMPLE_RATE, Self> {
         Bitcrusher::new(self, bit_depth, sample_rate_reduction)
     }
+
+    /// Smooths this signal with a one-pole low-pass filter.
+    ///
+    /// Useful for taking the steps out of control signals. The output starts
+    /// at the first input sample and then follows changes, settling to 63% of
+    /// a step after `time_constant` seconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `time_constant` - Smoothing time in seconds (0.0 disables smoothing)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{AudioSignalExt, Lfo, LfoWaveform};
+    ///
+    /// // A square LFO with softened edges
+    /// let lfo = Lfo::<44100>::new(LfoWaveform::Square, 4.0);
+    /// let mut smooth = lfo.smooth(0.03);
+    /// ```
+    fn smooth(self, time_constant: f64) -> OnePoleLowpass<SAMPLE_RATE, Self> {
+        OnePoleLowpass::from_time_constant(self, time_constant)
+    }
+
+    /// Removes DC offset from this signal with a 10 Hz [`DcBlocker`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{AudioSignalExt, SignalExt, SineOscillator};
+    ///
+    /// let rectified = SineOscillator::<44100>::new(220.0).abs();
+    /// let mut centered = rectified.dc_block();
+    /// ```
+    fn dc_block(self) -> DcBlocker<SAMPLE_RATE, Self> {
+        DcBlocker::new(self)
+    }
 }
 
 // Blanket implementation for all AudioSignal types
@@ -0,0 +1,342 @@
+//! Test signals for checking output levels and channel routing.
+//!
+//! These are the signals installers reach for when lining up a system: a
+//! [`ReferenceTone`] and band-limited [`CalibrationNoise`] at a known level
+//! for setting gain and checking meters, and a [`ChannelIdent`] sequence that
+//! says which speaker is which without needing a recorded voice.
+//!
+//! All three default to the usual -20 dBFS reference level, measured as RMS
+//! relative to a full-scale sine (AES17), so the tone and the noise read the
+//! same on an RMS meter.
+
+use crate::core::{AudioFrameSignal, AudioSignal, FrameSignal, RngContext, SeededRng, Signal};
+use crate::synthesis::filters::{Biquad, FilterType, biquad_coefficients};
+use crate::synthesis::noise::PinkNoise;
+use rand::Rng;
+use std::f64::consts::{FRAC_1_SQRT_2, TAU};
+
+/// Reference level of the calibration signals in dBFS.
+pub const REFERENCE_LEVEL_DBFS: f64 = -20.0;
+
+/// Frequency of the reference tone and identification beeps in Hz.
+pub const REFERENCE_FREQUENCY: f64 = 1000.0;
+
+/// Edges of the calibration noise band in Hz.
+const NOISE_BAND: (f64, f64) = (500.0, 2000.0);
+
+/// RMS of [`PinkNoise`] after the band filters, measured at 44.1-96kHz.
+const BAND_NOISE_RMS: f64 = 0.040;
+
+/// Length of an identification beep, and of the gap after it, in seconds.
+const BEEP_LENGTH: f64 = 0.15;
+
+/// Pause between channels in the identification sequence, in seconds.
+const CHANNEL_GAP: f64 = 0.75;
+
+/// Fade at each end of a beep, in seconds, so beeps don't click.
+const BEEP_RAMP: f64 = 0.005;
+
+/// Converts a dBFS level to the peak amplitude of a sine at that level.
+fn sine_amplitude(level_dbfs: f64) -> f64 {
+    10.0_f64.powf(level_dbfs / 20.0)
+}
+
+/// A steady sine at a calibrated level, by default the 1kHz, -20 dBFS
+/// line-up tone.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{ReferenceTone, Signal};
+///
+/// let mut tone = ReferenceTone::<48000>::new();
+/// // -20 dBFS: a peak amplitude of 0.1
+/// let peak = (0..480).map(|_| tone.next_sample().abs()).fold(0.0, f64::max);
+/// assert!((peak - 0.1).abs() < 1e-3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReferenceTone<const SAMPLE_RATE: u32> {
+    phase: f64,
+    increment: f64,
+    amplitude: f64,
+}
+
+impl<const SAMPLE_RATE: u32> ReferenceTone<SAMPLE_RATE> {
+    /// Creates a 1kHz tone at -20 dBFS.
+    pub fn new() -> Self {
+        Self::with_level(REFERENCE_FREQUENCY, REFERENCE_LEVEL_DBFS)
+    }
+
+    /// Creates a tone at `frequency` Hz and `level_dbfs` dBFS.
+    pub fn with_level(frequency: f64, level_dbfs: f64) -> Self {
+        Self {
+            phase: 0.0,
+            increment: frequency / SAMPLE_RATE as f64,
+            amplitude: sine_amplitude(level_dbfs),
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32> Default for ReferenceTone<SAMPLE_RATE> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const SAMPLE_RATE: u32> Signal for ReferenceTone<SAMPLE_RATE> {
+    fn next_sample(&mut self) -> f64 {
+        let sample = (self.phase * TAU).sin() * self.amplitude;
+        self.phase = (self.phase + self.increment).fract();
+        sample
+    }
+}
+
+impl<const SAMPLE_RATE: u32> AudioSignal<SAMPLE_RATE> for ReferenceTone<SAMPLE_RATE> {}
+
+/// Pink noise limited to 500Hz-2kHz at a calibrated RMS level, as used for
+/// aligning speaker levels (SMPTE RP 200).
+///
+/// Band-limiting keeps the noise out of the room modes and tweeter
+/// resonances that make full-band noise read differently from speaker to
+/// speaker. The level is -20 dBFS RMS by default; being noise, any short
+/// stretch of it reads a little above or below that.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{CalibrationNoise, Signal};
+///
+/// let mut noise = CalibrationNoise::<48000>::new();
+/// let sample = noise.next_sample();
+/// ```
+pub struct CalibrationNoise<const SAMPLE_RATE: u32, R: Rng = rand::rngs::ThreadRng> {
+    noise: PinkNoise<SAMPLE_RATE, R>,
+    filters: [Biquad; 4], // two high-pass then two low-pass sections
+    gain: f64,
+}
+
+impl<const SAMPLE_RATE: u32> CalibrationNoise<SAMPLE_RATE, rand::rngs::ThreadRng> {
+    /// Creates band-limited pink noise at -20 dBFS RMS.
+    pub fn new() -> Self {
+        Self::with_rng(rand::thread_rng())
+    }
+}
+
+impl<const SAMPLE_RATE: u32> Default for CalibrationNoise<SAMPLE_RATE, rand::rngs::ThreadRng> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const SAMPLE_RATE: u32> CalibrationNoise<SAMPLE_RATE, SeededRng> {
+    /// Creates calibration noise seeded from `context`.
+    pub fn from_context(context: &mut RngContext) -> Self {
+        Self::with_rng(context.rng())
+    }
+}
+
+impl<const SAMPLE_RATE: u32, R: Rng> CalibrationNoise<SAMPLE_RATE, R> {
+    /// Creates calibration noise with a custom RNG.
+    pub fn with_rng(rng: R) -> Self {
+        let (low, high) = NOISE_BAND;
+        let section = |filter_type, frequency| {
+            Biquad::new(biquad_coefficients(
+                filter_type,
+                frequency,
+                FRAC_1_SQRT_2,
+                SAMPLE_RATE,
+            ))
+        };
+        let mut noise = Self {
+            noise: PinkNoise::with_rng(rng),
+            filters: [
+                section(FilterType::HighPass, low),
+                section(FilterType::HighPass, low),
+                section(FilterType::LowPass, high),
+                section(FilterType::LowPass, high),
+            ],
+            gain: 1.0,
+        };
+        noise.set_level(REFERENCE_LEVEL_DBFS);
+        noise
+    }
+
+    /// Sets the RMS level in dBFS (relative to a full-scale sine).
+    pub fn with_level(mut self, level_dbfs: f64) -> Self {
+        self.set_level(level_dbfs);
+        self
+    }
+
+    /// Sets the RMS level in dBFS (relative to a full-scale sine).
+    pub fn set_level(&mut self, level_dbfs: f64) {
+        let rms = sine_amplitude(level_dbfs) * FRAC_1_SQRT_2;
+        self.gain = rms / BAND_NOISE_RMS;
+    }
+}
+
+impl<const SAMPLE_RATE: u32, R: Rng> Signal for CalibrationNoise<SAMPLE_RATE, R> {
+    fn next_sample(&mut self) -> f64 {
+        let noise = self.noise.next_sample();
+        let band = self.filters.iter_mut().fold(noise, |x, f| f.process(x));
+        band * self.gain
+    }
+}
+
+impl<const SAMPLE_RATE: u32, R: Rng> AudioSignal<SAMPLE_RATE> for CalibrationNoise<SAMPLE_RATE, R> {}
+
+/// Channel identification sequence: each channel in turn beeps as many
+/// times as its number.
+///
+/// Channel 1 (index 0) beeps once, channel 2 twice, and so on, with a pause
+/// between channels; after the last channel the sequence starts over. Only
+/// the channel being identified sounds, so listening at each speaker (or
+/// counting beeps on a meter) confirms the routing without a recorded voice.
+/// Beeps are 1kHz at -20 dBFS.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{ChannelIdent, FrameSignal};
+///
+/// // Walk around a quad rig: 1 beep front left, 2 front right, ...
+/// let mut ident = ChannelIdent::<48000, 4>::new();
+/// assert_eq!(ident.current_channel(), 0);
+/// let frame = ident.next_frame();
+/// assert_eq!(&frame[1..], &[0.0, 0.0, 0.0]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChannelIdent<const SAMPLE_RATE: u32, const CHANNELS: usize> {
+    tone: ReferenceTone<SAMPLE_RATE>,
+    channel: usize,
+    position: usize, // samples into the current channel's turn
+    beep_length: usize,
+    ramp_length: usize,
+    gap_length: usize,
+}
+
+impl<const SAMPLE_RATE: u32, const CHANNELS: usize> ChannelIdent<SAMPLE_RATE, CHANNELS> {
+    /// Creates a sequence starting on the first channel.
+    pub fn new() -> Self {
+        let samples = |seconds: f64| (seconds * SAMPLE_RATE as f64).round() as usize;
+        Self {
+            tone: ReferenceTone::new(),
+            channel: 0,
+            position: 0,
+            beep_length: samples(BEEP_LENGTH).max(1),
+            ramp_length: samples(BEEP_RAMP).max(1),
+            gap_length: samples(CHANNEL_GAP),
+        }
+    }
+
+    /// Returns the index of the channel being identified.
+    pub fn current_channel(&self) -> usize {
+        self.channel
+    }
+
+    /// Returns the length of `channel`'s turn in samples.
+    fn turn_length(&self, channel: usize) -> usize {
+        (channel + 1) * 2 * self.beep_length + self.gap_length
+    }
+
+    /// Returns the beep envelope at the current position.
+    fn envelope(&self) -> f64 {
+        let beeps = (self.channel + 1) * 2 * self.beep_length;
+        if self.position >= beeps {
+            return 0.0;
+        }
+        // Beeps and gaps alternate, beep first
+        if !(self.position / self.beep_length).is_multiple_of(2) {
+            return 0.0;
+        }
+        let into = self.position % self.beep_length;
+        let remaining = self.beep_length - into;
+        let ramp = into.min(remaining) as f64 / self.ramp_length as f64;
+        ramp.min(1.0)
+    }
+}
+
+impl<const SAMPLE_RATE: u32, const CHANNELS: usize> Default
+    for ChannelIdent<SAMPLE_RATE, CHANNELS>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const SAMPLE_RATE: u32, const CHANNELS: usize> FrameSignal<CHANNELS>
+    for ChannelIdent<SAMPLE_RATE, CHANNELS>
+{
+    fn next_frame(&mut self) -> [f64; CHANNELS] {
+        let mut frame = [0.0; CHANNELS];
+        let sample = self.tone.next_sample() * self.envelope();
+        if let Some(out) = frame.get_mut(self.channel) {
+            *out = sample;
+        }
+
+        self.position += 1;
+        if self.position >= self.turn_length(self.channel) {
+            self.position = 0;
+            self.channel = (self.channel + 1) % CHANNELS.max(1);
+        }
+        frame
+    }
+}
+
+impl<const SAMPLE_RATE: u32, const CHANNELS: usize> AudioFrameSignal<SAMPLE_RATE, CHANNELS>
+    for ChannelIdent<SAMPLE_RATE, CHANNELS>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn rms(samples: impl Iterator<Item = f64>) -> f64 {
+        let (sum, count) = samples.fold((0.0, 0), |(sum, n), x| (sum + x * x, n + 1));
+        (sum / count as f64).sqrt()
+    }
+
+    #[test]
+    fn test_tone_and_noise_read_the_same() {
+        let reference = 0.1 * FRAC_1_SQRT_2;
+        let mut tone = ReferenceTone::<48000>::new();
+        let tone_rms = rms((0..48000).map(|_| tone.next_sample()));
+        assert!((tone_rms - reference).abs() < 1e-4, "tone {}", tone_rms);
+
+        let rng = rand::rngs::StdRng::seed_from_u64(3);
+        let mut noise = CalibrationNoise::<48000, _>::with_rng(rng);
+        let noise_rms = rms((0..48000 * 10).map(|_| noise.next_sample()));
+        // Within 0.5dB
+        let error_db = 20.0 * (noise_rms / reference).log10();
+        assert!(error_db.abs() < 0.5, "noise off by {} dB", error_db);
+    }
+
+    #[test]
+    fn test_ident_beeps_channel_number_times() {
+        let mut ident = ChannelIdent::<8000, 3>::new();
+        let mut beeps = [0; 3];
+        // Samples since each channel last sounded; the tone crosses zero
+        // within a beep, so a beep starts after a long silence
+        let mut silent = [usize::MAX; 3];
+        // One full cycle: 1 + 2 + 3 beeps with gaps of 150ms, plus three
+        // 750ms pauses
+        for _ in 0..(6 * 2 * 1200 + 3 * 6000) {
+            let channel = ident.current_channel();
+            let frame = ident.next_frame();
+            for (index, sample) in frame.iter().enumerate() {
+                if sample.abs() > 1e-9 {
+                    assert_eq!(index, channel);
+                    if silent[index] > 100 {
+                        beeps[index] += 1;
+                    }
+                    silent[index] = 0;
+                } else {
+                    silent[index] = silent[index].saturating_add(1);
+                }
+            }
+        }
+        assert_eq!(beeps, [1, 2, 3]);
+        assert_eq!(ident.current_channel(), 0);
+    }
+}
@@ -0,0 +1,211 @@
+//! Guitar amp simulation composed from preamp, tone stack and cabinet stages.
+
+use super::cab_sim::{CabModel, CabSim};
+use super::tone_stack::ToneStack;
+use crate::core::{AudioSignal, Param, Signal, debug_assert_finite};
+use crate::synthesis::filters::{Biquad, FilterType, biquad_coefficients};
+
+/// Operating-point offset of each preamp stage; makes the clipping asymmetric
+/// like a real triode and adds even harmonics.
+const STAGE_BIAS: [f64; 3] = [0.2, -0.15, 0.1];
+
+/// Fixed gain between preamp stages.
+const INTERSTAGE_GAIN: f64 = 1.5;
+
+/// Coupling-capacitor highpass and Miller-capacitance lowpass corners in Hz.
+const COUPLING_FREQ: f64 = 30.0;
+const MILLER_FREQ: f64 = 7000.0;
+
+/// Cascaded tube-style gain stages: highpass, biased tanh clipper, lowpass.
+struct Preamp<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
+    source: S,
+    gain: Param, // preamp gain knob, 0.0 to 1.0
+    stages: [(Biquad, Biquad); 3],
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Preamp<SAMPLE_RATE, S> {
+    fn new(source: S, gain: Param) -> Self {
+        let highpass = biquad_coefficients(FilterType::HighPass, COUPLING_FREQ, 0.707, SAMPLE_RATE);
+        let lowpass = biquad_coefficients(FilterType::LowPass, MILLER_FREQ, 0.707, SAMPLE_RATE);
+        Self {
+            source,
+            gain,
+            stages: std::array::from_fn(|_| (Biquad::new(highpass), Biquad::new(lowpass))),
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for Preamp<SAMPLE_RATE, S> {
+    fn next_sample(&mut self) -> f64 {
+        // Knob maps exponentially from clean (x0.25) to saturated (x250)
+        let drive = 0.25 * 10.0_f64.powf(self.gain.value().clamp(0.0, 1.0) * 3.0);
+        let mut sample = self.source.next_sample() * drive;
+
+        for ((highpass, lowpass), bias) in self.stages.iter_mut().zip(STAGE_BIAS) {
+            let coupled = highpass.process(sample);
+            let clipped = (coupled + bias).tanh() - bias.tanh();
+            sample = lowpass.process(clipped) * INTERSTAGE_GAIN;
+        }
+        let output = sample / INTERSTAGE_GAIN;
+        debug_assert_finite("AmpSim preamp", drive, output);
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.gain.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE>
+    for Preamp<SAMPLE_RATE, S>
+{
+}
+
+/// Guitar amplifier simulation.
+///
+/// A composed effect chaining the building blocks of a guitar amp:
+///
+/// 1. Preamp: input gain into three cascaded, asymmetrically biased soft
+///    clipping stages with coupling highpasses and gentle lowpasses between
+///    them, so distortion builds up progressively as gain increases
+/// 2. [`ToneStack`]: passive-style bass/mid/treble EQ
+/// 3. [`CabSim`]: speaker cabinet voicing
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{AmpSim, CabModel, SawtoothOscillator};
+///
+/// let guitar = SawtoothOscillator::<44100>::new(110.0);
+/// // Gain, bass, mid, treble, cabinet
+/// let mut amp = AmpSim::new(guitar, 0.6, 0.5, 0.5, 0.6, CabModel::Stack4x12);
+///
+/// let guitar = SawtoothOscillator::<44100>::new(110.0);
+/// let mut lead = AmpSim::lead(guitar);
+/// ```
+pub struct AmpSim<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
+    chain: CabSim<SAMPLE_RATE, ToneStack<SAMPLE_RATE, Preamp<SAMPLE_RATE, S>>>,
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AmpSim<SAMPLE_RATE, S> {
+    /// Creates a new amp simulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Input signal (a clean, guitar-level tone around ±1.0)
+    /// * `gain` - Preamp gain knob (0.0 = clean, 0.5 = crunch, 1.0 = saturated)
+    /// * `bass` - Tone stack bass knob (0.0-1.0)
+    /// * `mid` - Tone stack mid knob (0.0-1.0)
+    /// * `treble` - Tone stack treble knob (0.0-1.0)
+    /// * `cabinet` - Cabinet voicing
+    pub fn new(
+        source: S,
+        gain: impl Into<Param>,
+        bass: impl Into<Param>,
+        mid: impl Into<Param>,
+        treble: impl Into<Param>,
+        cabinet: CabModel,
+    ) -> Self {
+        let preamp = Preamp::new(source, gain.into());
+        let tone = ToneStack::new(preamp, bass, mid, treble);
+        Self {
+            chain: CabSim::new(tone, cabinet),
+        }
+    }
+
+    /// Creates a clean amp (sparkly, barely breaking up).
+    ///
+    /// Settings: gain 0.1, bass 0.5, mid 0.6, treble 0.6, 1x12 combo
+    pub fn clean(source: S) -> Self {
+        Self::new(source, 0.1, 0.5, 0.6, 0.6, CabModel::Combo1x12)
+    }
+
+    /// Creates a crunchy rhythm amp (classic rock breakup).
+    ///
+    /// Settings: gain 0.5, bass 0.6, mid 0.6, treble 0.55, vintage 2x10
+    pub fn crunch(source: S) -> Self {
+        Self::new(source, 0.5, 0.6, 0.6, 0.55, CabModel::Vintage2x10)
+    }
+
+    /// Creates a high-gain lead amp (saturated, scooped, sustaining).
+    ///
+    /// Settings: gain 0.85, bass 0.6, mid 0.4, treble 0.65, 4x12 stack
+    pub fn lead(source: S) -> Self {
+        Self::new(source, 0.85, 0.6, 0.4, 0.65, CabModel::Stack4x12)
+    }
+
+    /// Returns the cabinet model.
+    pub fn cabinet(&self) -> CabModel {
+        self.chain.model()
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for AmpSim<SAMPLE_RATE, S> {
+    fn next_sample(&mut self) -> f64 {
+        // Each stage of the chain checks its own output
+        self.chain.next_sample()
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.chain.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE>
+    for AmpSim<SAMPLE_RATE, S>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SineOscillator;
+
+    /// Fraction of the steady-state output power at the fundamental `freq`.
+    fn fundamental_share<S: Signal>(amp: &mut S, freq: f64) -> f64 {
+        let output: Vec<f64> = amp.iter().take(22050).collect();
+        let tail = &output[11025..];
+        let omega = std::f64::consts::TAU * freq / 44100.0;
+        let (re, im) = tail
+            .iter()
+            .enumerate()
+            .fold((0.0, 0.0), |(re, im), (n, s)| {
+                (
+                    re + s * (omega * n as f64).cos(),
+                    im + s * (omega * n as f64).sin(),
+                )
+            });
+        let fundamental = 2.0 * (re * re + im * im) / (tail.len() * tail.len()) as f64;
+        let total = tail.iter().map(|s| s * s).sum::<f64>() / tail.len() as f64;
+        fundamental / total
+    }
+
+    #[test]
+    fn test_gain_adds_harmonics() {
+        let sine = || SineOscillator::<44100>::new(220.0);
+        let mut clean = AmpSim::new(sine(), 0.0, 0.5, 0.75, 0.5, CabModel::Combo1x12);
+        let mut driven = AmpSim::new(sine(), 1.0, 0.5, 0.75, 0.5, CabModel::Combo1x12);
+        let clean = fundamental_share(&mut clean, 220.0);
+        let driven = fundamental_share(&mut driven, 220.0);
+        assert!(clean > 0.95, "Clean amp distorts: {}", clean);
+        assert!(driven < 0.9, "Driven amp is too clean: {}", driven);
+    }
+
+    #[test]
+    fn test_presets_are_bounded() {
+        let sine = || SineOscillator::<44100>::new(110.0);
+        for mut amp in [
+            AmpSim::clean(sine()),
+            AmpSim::crunch(sine()),
+            AmpSim::lead(sine()),
+        ] {
+            let peak = amp
+                .iter()
+                .take(44100)
+                .fold(0.0_f64, |max, s| max.max(s.abs()));
+            assert!(peak > 0.01 && peak < 4.0, "Peak {}", peak);
+        }
+        assert_eq!(AmpSim::lead(sine()).cabinet(), CabModel::Stack4x12);
+    }
+}
@@ -1,6 +1,6 @@
 //! Bitcrusher effect for lo-fi digital degradation.
 
-use crate::core::{AudioSignal, Param, Signal};
+use crate::core::{AudioSignal, Param, Signal, debug_assert_finite};
 
 /// Bitcrusher effect that reduces sample rate and bit depth.
 ///
@@ -36,6 +36,54 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Bitcrusher<SAMPLE_RATE
             held_sample: 0.0,
         }
     }
+
+    /// Creates a subtle "lo-fi" preset.
+    ///
+    /// Settings: half sample rate, 12-bit depth
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{SineOscillator, Bitcrusher};
+    ///
+    /// let audio = SineOscillator::<44100>::new(440.0);
+    /// let mut crusher = Bitcrusher::lofi(audio);
+    /// ```
+    pub fn lofi(source: S) -> Self {
+        Self::new(source, 2.0, 12.0)
+    }
+
+    /// Creates an "8-bit" preset reminiscent of early game consoles.
+    ///
+    /// Settings: quarter sample rate, 8-bit depth
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{SineOscillator, Bitcrusher};
+    ///
+    /// let audio = SineOscillator::<44100>::new(440.0);
+    /// let mut crusher = Bitcrusher::eight_bit(audio);
+    /// ```
+    pub fn eight_bit(source: S) -> Self {
+        Self::new(source, 4.0, 8.0)
+    }
+
+    /// Creates a heavily "crushed" preset for aggressive digital distortion.
+    ///
+    /// Settings: 1/16 sample rate, 4-bit depth
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{SineOscillator, Bitcrusher};
+    ///
+    /// let audio = SineOscillator::<44100>::new(440.0);
+    /// let mut crusher = Bitcrusher::crushed(audio);
+    /// ```
+    pub fn crushed(source: S) -> Self {
+        Self::new(source, 16.0, 4.0)
+    }
 }
 
 impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for Bitcrusher<SAMPLE_RATE, S> {
@@ -51,7 +99,16 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for Bitcrusher<
         self.hold_counter += 1.0;
 
         let levels = 2.0_f64.powf(self.bit_depth.value());
-        (self.held_sample * levels).round() / levels
+        let output = (self.held_sample * levels).round() / levels;
+        debug_assert_finite("Bitcrusher", current_sample, output);
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.sample_rate_reduction
+            .prepare(max_block_size, sample_rate);
+        self.bit_depth.prepare(max_block_size, sample_rate);
     }
 }
 
@@ -234,4 +291,26 @@ mod tests {
             crusher.next_sample();
         }
     }
+
+    #[test]
+    fn test_presets() {
+        let ramp: Vec<f64> = (0..32).map(|i| i as f64 / 32.0).collect();
+
+        // Each preset holds for its reduction factor and quantizes to its depth
+        let cases = [
+            (Bitcrusher::lofi as fn(_) -> _, 2, 12.0),
+            (Bitcrusher::eight_bit, 4, 8.0),
+            (Bitcrusher::crushed, 16, 4.0),
+        ];
+
+        for (preset, hold, bits) in cases {
+            let mut crusher: Bitcrusher<44100, _> = preset(TestSignal::new(ramp.clone()));
+            let levels = 2.0_f64.powf(bits);
+            for (i, _) in ramp.iter().enumerate() {
+                let held = ramp[i - i % hold];
+                let expected = (held * levels).round() / levels;
+                assert!((crusher.next_sample() - expected).abs() < 1e-12);
+            }
+        }
+    }
 }
@@ -0,0 +1,171 @@
+//! Guitar cabinet simulation from a few fixed filters.
+
+use crate::core::{AudioSignal, Signal, debug_assert_finite};
+use crate::synthesis::filters::{Biquad, FilterType, biquad_coefficients, peaking_coefficients};
+
+/// Speaker cabinet voicing used by [`CabSim`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CabModel {
+    /// Open-back 1x12 combo: loose low end, bright and airy top
+    Combo1x12,
+    /// Closed-back 4x12 stack: tight thump, scooped low mids, strong presence
+    Stack4x12,
+    /// Vintage 2x10: thin lows, pronounced midrange honk, early top-end rolloff
+    Vintage2x10,
+}
+
+/// One filter stage of a cabinet response.
+enum Stage {
+    /// Standard biquad shape at (frequency, Q)
+    Shape(FilterType, f64, f64),
+    /// Peaking EQ at (frequency, Q, gain in dB)
+    Peak(f64, f64, f64),
+}
+
+impl CabModel {
+    /// Filter stages approximating the model's frequency response.
+    fn stages(self) -> &'static [Stage] {
+        use FilterType::{HighPass, LowPass};
+        use Stage::{Peak, Shape};
+
+        match self {
+            CabModel::Combo1x12 => &[
+                Shape(HighPass, 75.0, 0.707),
+                Peak(110.0, 1.2, 3.0),
+                Peak(2500.0, 1.0, 4.0),
+                Shape(LowPass, 6000.0, 0.707),
+                Shape(LowPass, 6000.0, 0.707),
+            ],
+            CabModel::Stack4x12 => &[
+                Shape(HighPass, 70.0, 0.9),
+                Peak(100.0, 1.5, 5.0),
+                Peak(400.0, 1.0, -4.0),
+                Peak(2000.0, 1.2, 5.0),
+                Shape(LowPass, 4500.0, 0.707),
+                Shape(LowPass, 4500.0, 0.707),
+            ],
+            CabModel::Vintage2x10 => &[
+                Shape(HighPass, 110.0, 0.707),
+                Peak(800.0, 0.9, 4.0),
+                Peak(1800.0, 1.5, 2.0),
+                Shape(LowPass, 3800.0, 0.707),
+                Shape(LowPass, 3800.0, 0.707),
+            ],
+        }
+    }
+}
+
+/// Guitar speaker cabinet simulation without impulse responses.
+///
+/// Approximates the response of a miked cabinet with a handful of fixed
+/// filters: a highpass for the speaker's low-end limit, peaks and dips for
+/// cabinet resonance and cone breakup, and a steep lowpass where the speaker
+/// stops reproducing highs. Put it after [`Distortion`](crate::Distortion) to
+/// turn fizzy clipping into a playable guitar tone.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{CabModel, CabSim, Distortion, SawtoothOscillator};
+///
+/// let guitar = SawtoothOscillator::<44100>::new(110.0);
+/// let driven = Distortion::new(guitar, 12.0, 1.0);
+/// let mut amp = CabSim::new(driven, CabModel::Stack4x12);
+/// ```
+pub struct CabSim<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
+    source: S,
+    model: CabModel,
+    filters: Vec<Biquad>,
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> CabSim<SAMPLE_RATE, S> {
+    /// Creates a new cabinet simulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Input signal (usually a distorted guitar-like tone)
+    /// * `model` - Cabinet voicing
+    pub fn new(source: S, model: CabModel) -> Self {
+        let filters = model
+            .stages()
+            .iter()
+            .map(|stage| {
+                Biquad::new(match *stage {
+                    Stage::Shape(filter_type, freq, q) => {
+                        biquad_coefficients(filter_type, freq, q, SAMPLE_RATE)
+                    }
+                    Stage::Peak(freq, q, gain_db) => {
+                        peaking_coefficients(freq, q, gain_db, SAMPLE_RATE)
+                    }
+                })
+            })
+            .collect();
+
+        Self {
+            source,
+            model,
+            filters,
+        }
+    }
+
+    /// Returns the cabinet model.
+    pub fn model(&self) -> CabModel {
+        self.model
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for CabSim<SAMPLE_RATE, S> {
+    fn next_sample(&mut self) -> f64 {
+        let input = self.source.next_sample();
+        let output = self
+            .filters
+            .iter_mut()
+            .fold(input, |sample, filter| filter.process(sample));
+        debug_assert_finite("CabSim", input, output);
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE>
+    for CabSim<SAMPLE_RATE, S>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SineOscillator;
+
+    /// Steady-state RMS of a sine at `freq` through the model.
+    fn response(model: CabModel, freq: f64) -> f64 {
+        let mut cab = CabSim::new(SineOscillator::<44100>::new(freq), model);
+        let output: Vec<f64> = cab.iter().take(22050).collect();
+        let tail = &output[11025..];
+        (tail.iter().map(|s| s * s).sum::<f64>() / tail.len() as f64).sqrt()
+    }
+
+    #[test]
+    fn test_models_are_band_limited() {
+        for model in [
+            CabModel::Combo1x12,
+            CabModel::Stack4x12,
+            CabModel::Vintage2x10,
+        ] {
+            let mid = response(model, 1000.0);
+            assert!(mid > 0.3, "{:?} mid {}", model, mid);
+            assert!(response(model, 12000.0) < mid * 0.05, "{:?} highs", model);
+            assert!(response(model, 30.0) < mid * 0.25, "{:?} lows", model);
+        }
+    }
+
+    #[test]
+    fn test_models_differ() {
+        let combo = response(CabModel::Combo1x12, 5000.0);
+        let vintage = response(CabModel::Vintage2x10, 5000.0);
+        assert!(combo > vintage * 1.5);
+    }
+}
@@ -1,6 +1,6 @@
 //! Compressor effect for dynamic range control.
 
-use crate::core::{AudioSignal, Param, Signal};
+use crate::core::{AudioSignal, Param, Signal, debug_assert_finite};
 
 /// Compressor effect for controlling dynamic range.
 ///
@@ -31,14 +31,12 @@ use crate::core::{AudioSignal, Param, Signal};
 /// ```
 pub struct Compressor<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
     source: S,
-    threshold: Param,     // threshold level (linear, 0.0-1.0)
-    ratio: Param,         // compression ratio (1.0 = no compression, higher = more compression)
-    attack: Param,        // attack time in seconds
-    release: Param,       // release time in seconds
-    knee: Param,          // knee width in dB (0 = hard knee)
-    current_gain: f64,    // current gain reduction multiplier
-    rms_buffer: Vec<f64>, // circular buffer for RMS calculation
-    rms_index: usize,     // current position in RMS buffer
+    threshold: Param, // threshold level (linear, 0.0-1.0)
+    ratio: Param,     // compression ratio (1.0 = no compression, higher = more compression)
+    attack: Param,    // attack time in seconds
+    release: Param,   // release time in seconds
+    knee: Param,      // knee width in dB (0 = hard knee)
+    detector: GainComputer,
 }
 
 impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Compressor<SAMPLE_RATE, S> {
@@ -70,9 +68,6 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Compressor<SAMPLE_RATE
         release: impl Into<Param>,
         knee: impl Into<Param>,
     ) -> Self {
-        // Use 10ms RMS window
-        let rms_window_size = ((SAMPLE_RATE as f64) * 0.01) as usize;
-
         Self {
             source,
             threshold: threshold.into(),
@@ -80,9 +75,7 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Compressor<SAMPLE_RATE
             attack: attack.into(),
             release: release.into(),
             knee: knee.into(),
-            current_gain: 1.0,
-            rms_buffer: vec![0.0; rms_window_size],
-            rms_index: 0,
+            detector: GainComputer::new(SAMPLE_RATE),
         }
     }
 
@@ -134,6 +127,53 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Compressor<SAMPLE_RATE
         Self::new(source, 0.7, 2.0, 0.01, 0.3, 12.0)
     }
 
+    /// Creates a "drum bus" compressor for a full drum submix.
+    ///
+    /// Slow attack lets transients through while the fast release pumps with the groove.
+    ///
+    /// Settings: threshold 0.4, ratio 4:1, attack 20ms, release 80ms, soft knee 3dB
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{SineOscillator, Compressor};
+    ///
+    /// let audio = SineOscillator::<44100>::new(440.0);
+    /// let mut comp = Compressor::drum_bus(audio);
+    /// ```
+    pub fn drum_bus(source: S) -> Self {
+        Self::new(source, 0.4, 4.0, 0.02, 0.08, 3.0)
+    }
+
+    /// Gets the current gain reduction multiplier (0.0-1.0).
+    /// 1.0 means no reduction, 0.5 means -6dB reduction, etc.
+    pub fn current_gain(&self) -> f64 {
+        self.detector.current_gain
+    }
+}
+
+/// RMS level detection and smoothed gain reduction, shared by [`Compressor`]
+/// and the bands of a [`MultibandCompressor`](super::MultibandCompressor).
+#[derive(Debug, Clone)]
+pub(super) struct GainComputer {
+    sample_rate: f64,
+    current_gain: f64,    // current gain reduction multiplier
+    rms_buffer: Vec<f64>, // circular buffer for RMS calculation
+    rms_index: usize,     // current position in RMS buffer
+}
+
+impl GainComputer {
+    pub(super) fn new(sample_rate: u32) -> Self {
+        // Use 10ms RMS window
+        let rms_window_size = ((sample_rate as f64) * 0.01) as usize;
+        Self {
+            sample_rate: sample_rate as f64,
+            current_gain: 1.0,
+            rms_buffer: vec![0.0; rms_window_size],
+            rms_index: 0,
+        }
+    }
+
     /// Converts linear amplitude to decibels.
     fn lin_to_db(linear: f64) -> f64 {
         20.0 * linear.max(0.0001).log10()
@@ -150,17 +190,22 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Compressor<SAMPLE_RATE
         (sum / self.rms_buffer.len() as f64).sqrt()
     }
 
-    /// Gets the current gain reduction multiplier (0.0-1.0).
-    /// 1.0 means no reduction, 0.5 means -6dB reduction, etc.
-    pub fn current_gain(&self) -> f64 {
+    /// Gets the current gain reduction multiplier.
+    pub(super) fn current_gain(&self) -> f64 {
         self.current_gain
     }
-}
-
-impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for Compressor<SAMPLE_RATE, S> {
-    fn next_sample(&mut self) -> f64 {
-        let input = self.source.next_sample();
 
+    /// Feeds one input sample to the detector and returns the gain to apply
+    /// to it.
+    pub(super) fn process(
+        &mut self,
+        input: f64,
+        threshold: f64,
+        ratio: f64,
+        attack: f64,
+        release: f64,
+        knee: f64,
+    ) -> f64 {
         // Update RMS buffer
         self.rms_buffer[self.rms_index] = input.abs();
         self.rms_index = (self.rms_index + 1) % self.rms_buffer.len();
@@ -168,12 +213,12 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for Compressor<
         // Get current RMS level
         let rms_level = self.calculate_rms();
 
-        // Get parameter values
-        let threshold = self.threshold.value().max(0.0001);
-        let ratio = self.ratio.value().max(1.0);
-        let attack_time = self.attack.value().max(0.0001);
-        let release_time = self.release.value().max(0.0001);
-        let knee_db = self.knee.value().max(0.0);
+        // Clamp parameter values
+        let threshold = threshold.max(0.0001);
+        let ratio = ratio.max(1.0);
+        let attack_time = attack.max(0.0001);
+        let release_time = release.max(0.0001);
+        let knee_db = knee.max(0.0);
 
         // Convert to dB
         let input_db = Self::lin_to_db(rms_level);
@@ -219,11 +264,37 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for Compressor<
             release_time
         };
 
-        let coeff = 1.0 - (-1.0 / (time_constant * SAMPLE_RATE as f64)).exp();
+        let coeff = 1.0 - (-1.0 / (time_constant * self.sample_rate)).exp();
         self.current_gain += (target_gain - self.current_gain) * coeff;
+        self.current_gain
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for Compressor<SAMPLE_RATE, S> {
+    fn next_sample(&mut self) -> f64 {
+        let input = self.source.next_sample();
+        let gain = self.detector.process(
+            input,
+            self.threshold.value(),
+            self.ratio.value(),
+            self.attack.value(),
+            self.release.value(),
+            self.knee.value(),
+        );
 
         // Apply compression
-        input * self.current_gain
+        let output = input * gain;
+        debug_assert_finite("Compressor", input, output);
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.threshold.prepare(max_block_size, sample_rate);
+        self.ratio.prepare(max_block_size, sample_rate);
+        self.attack.prepare(max_block_size, sample_rate);
+        self.release.prepare(max_block_size, sample_rate);
+        self.knee.prepare(max_block_size, sample_rate);
     }
 }
 
@@ -298,4 +369,42 @@ mod tests {
         fn assert_audio_signal<T: AudioSignal<44100>>(_: T) {}
         assert_audio_signal(comp);
     }
+
+    /// Feeds the same constant level through `preset` and an equivalent `new`
+    /// call and checks the outputs agree.
+    fn assert_matches_new(
+        preset: fn(ConstantSignal<44100>) -> Compressor<44100, ConstantSignal<44100>>,
+        settings: (f64, f64, f64, f64, f64),
+    ) {
+        let (threshold, ratio, attack, release, knee) = settings;
+        let mut a = preset(ConstantSignal::<44100>(0.9));
+        let mut b = Compressor::new(
+            ConstantSignal::<44100>(0.9),
+            threshold,
+            ratio,
+            attack,
+            release,
+            knee,
+        );
+        for _ in 0..4410 {
+            assert_eq!(a.next_sample(), b.next_sample());
+        }
+    }
+
+    #[test]
+    fn test_presets_match_settings() {
+        assert_matches_new(Compressor::vocal, (0.5, 3.0, 0.005, 0.1, 6.0));
+        assert_matches_new(Compressor::punch, (0.6, 4.0, 0.03, 0.15, 0.0));
+        assert_matches_new(Compressor::glue, (0.7, 2.0, 0.01, 0.3, 12.0));
+        assert_matches_new(Compressor::drum_bus, (0.4, 4.0, 0.02, 0.08, 3.0));
+    }
+
+    #[test]
+    fn test_drum_bus_compresses_hot_signal() {
+        let mut comp = Compressor::drum_bus(ConstantSignal::<44100>(0.9));
+        for _ in 0..44100 {
+            comp.next_sample();
+        }
+        assert!(comp.current_gain() < 0.7, "Gain: {}", comp.current_gain());
+    }
 }
@@ -1,20 +1,36 @@
 //! Delay effect with feedback and dry/wet mix.
 
-use crate::core::{AudioSignal, Param, Signal};
+use super::delay_line::{DelayLine, Smoothed};
+use crate::core::{AudioSignal, Param, Signal, debug_assert_finite};
+
+/// Default time constant (in seconds) for smoothing delay time changes.
+pub(crate) const DEFAULT_TIME_SMOOTHING: f64 = 0.02;
+
+/// Time constant (in seconds) for fading freeze in and out without clicks.
+pub(crate) const FREEZE_SMOOTHING: f64 = 0.01;
 
 /// Delay effect with feedback and dry/wet mix.
 ///
 /// Stores input samples in a ring buffer and plays them back after a specified time.
 /// Feedback creates repeating echoes.
+///
+/// The delay time can be modulated. Reads between buffer samples are linearly
+/// interpolated and time changes are smoothed, so sweeping the time bends the
+/// pitch of the echoes like a tape delay instead of clicking.
+///
+/// A freeze control (see [`with_freeze`](Delay::with_freeze)) holds whatever is
+/// in the buffer as an endless loop.
 pub struct Delay<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
     source: S,
-    buffer: Vec<f64>,
-    write_pos: usize,
+    line: DelayLine,
+    current_delay: Smoothed, // smoothed delay time in samples
+    freeze_amount: Smoothed, // smoothed freeze control, 0.0 to 1.0
 
     // Parameters
     delay_time: Param, // delay time in seconds
     feedback: Param,   // 0.0 to ~0.95 (higher = more repeats, >1.0 = infinite/growing)
     mix: Param,        // dry/wet mix, 0.0 = all dry, 1.0 = all wet
+    freeze: Param,     // 0.0 = normal, 1.0 = hold the buffer contents forever
 }
 
 impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Delay<SAMPLE_RATE, S> {
@@ -34,18 +50,66 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Delay<SAMPLE_RATE, S>
         feedback: impl Into<Param>,
         mix: impl Into<Param>,
     ) -> Self {
-        let buffer_size = (max_delay_time * SAMPLE_RATE as f64).ceil() as usize + 1;
-
         Self {
             source,
-            buffer: vec![0.0; buffer_size],
-            write_pos: 0,
+            line: DelayLine::with_max_time(max_delay_time, SAMPLE_RATE),
+            current_delay: Smoothed::new(DEFAULT_TIME_SMOOTHING, SAMPLE_RATE),
+            freeze_amount: Smoothed::new(FREEZE_SMOOTHING, SAMPLE_RATE),
             delay_time: delay_time.into(),
             feedback: feedback.into(),
             mix: mix.into(),
+            freeze: Param::Fixed(0.0),
         }
     }
 
+    /// Sets how quickly the delay follows changes to its time parameter.
+    ///
+    /// `smoothing_time` is the time constant in seconds (default 20ms). Larger
+    /// values glide more slowly between times, giving a more pronounced
+    /// tape-style pitch bend; 0.0 follows the parameter immediately.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{Delay, SignalExt, SineOscillator};
+    ///
+    /// let audio = SineOscillator::<44100>::new(440.0);
+    /// // Sweep the delay between 10ms and 30ms for a chorus-like wobble
+    /// let time = SineOscillator::<44100>::new(0.5).gain(0.01).offset(0.02);
+    /// let mut delay = Delay::new(audio, 0.05, time, 0.0, 0.5).with_time_smoothing(0.1);
+    /// ```
+    pub fn with_time_smoothing(mut self, smoothing_time: f64) -> Self {
+        self.current_delay.set_time(smoothing_time, SAMPLE_RATE);
+        self
+    }
+
+    /// Adds a freeze control that turns the buffer into an infinite loop.
+    ///
+    /// While frozen (1.0) the input is muted and feedback is raised to 1.0, so
+    /// the current buffer contents repeat without decaying. The dry signal's
+    /// share of the output is handed to the loop so the drone plays at the
+    /// level of the dry + wet signal it replaces. Changes are faded over 10ms;
+    /// values between 0.0 and 1.0 give a partial freeze.
+    ///
+    /// # Arguments
+    ///
+    /// * `freeze` - Freeze amount (0.0 = normal, 1.0 = frozen); use a gate
+    ///   signal to freeze and release live
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{Delay, SineOscillator};
+    ///
+    /// let audio = SineOscillator::<44100>::new(220.0);
+    /// // Hold a 500ms loop of the sine as a drone
+    /// let mut drone = Delay::new(audio, 0.5, 0.5, 0.4, 0.5).with_freeze(1.0);
+    /// ```
+    pub fn with_freeze(mut self, freeze: impl Into<Param>) -> Self {
+        self.freeze = freeze.into();
+        self
+    }
+
     /// Creates a simple echo effect.
     ///
     /// # Arguments
@@ -63,6 +127,27 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Delay<SAMPLE_RATE, S>
     pub fn slapback(source: S) -> Self {
         Self::new(source, 0.2, 0.075, 0.3, 0.4)
     }
+
+    /// Creates a doubler (very short delay, no feedback).
+    ///
+    /// Thickens a part by layering a 30ms copy underneath the original.
+    pub fn doubler(source: S) -> Self {
+        Self::new(source, 0.05, 0.03, 0.0, 0.5)
+    }
+
+    /// Creates a dub-style delay (long, heavily repeating echoes).
+    ///
+    /// Settings: 375ms delay (dotted eighth at 120 BPM), feedback 0.7, mix 0.45
+    pub fn dub(source: S) -> Self {
+        Self::new(source, 0.5, 0.375, 0.7, 0.45)
+    }
+
+    /// Creates an ambient delay (long, washy tail tucked under the dry signal).
+    ///
+    /// Settings: 600ms delay, feedback 0.8, mix 0.3
+    pub fn ambient(source: S) -> Self {
+        Self::new(source, 1.0, 0.6, 0.8, 0.3)
+    }
 }
 
 impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for Delay<SAMPLE_RATE, S> {
@@ -73,25 +158,34 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for Delay<SAMPL
         let delay_time = self.delay_time.value().max(0.0);
         let feedback = self.feedback.value().clamp(0.0, 0.99); // Prevent runaway feedback
         let mix = self.mix.value().clamp(0.0, 1.0);
+        let frozen = self.freeze_amount.next(self.freeze.value().clamp(0.0, 1.0));
 
-        // Calculate delay in samples
-        let delay_samples = (delay_time * SAMPLE_RATE as f64) as usize;
-        let delay_samples = delay_samples.min(self.buffer.len() - 1);
-
-        // Calculate read position
-        let read_pos = (self.write_pos + self.buffer.len() - delay_samples) % self.buffer.len();
+        // Freezing mutes the input, holds the loop at unity feedback, and moves
+        // the dry share of the output onto the loop
+        let input_gain = 1.0 - frozen;
+        let feedback = feedback + (1.0 - feedback) * frozen;
+        let wet = mix + (1.0 - mix) * frozen;
 
-        // Read delayed sample
-        let delayed = self.buffer[read_pos];
+        // Smooth the delay time (in samples) toward its target
+        let target = (delay_time * SAMPLE_RATE as f64).clamp(1.0, self.line.max_delay());
+        let delay_samples = self.current_delay.next(target);
 
-        // Write input + feedback to buffer
-        self.buffer[self.write_pos] = input + delayed * feedback;
-
-        // Advance write position
-        self.write_pos = (self.write_pos + 1) % self.buffer.len();
+        // Read between the two nearest buffer samples, then write input + feedback
+        let delayed = self.line.read(delay_samples);
+        self.line.write(input * input_gain + delayed * feedback);
 
         // Mix dry and wet signals
-        input * (1.0 - mix) + delayed * mix
+        let output = input * (1.0 - mix) * input_gain + delayed * wet;
+        debug_assert_finite("Delay", input, output);
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.delay_time.prepare(max_block_size, sample_rate);
+        self.feedback.prepare(max_block_size, sample_rate);
+        self.mix.prepare(max_block_size, sample_rate);
+        self.freeze.prepare(max_block_size, sample_rate);
     }
 }
 
@@ -99,3 +193,173 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RAT
     for Delay<SAMPLE_RATE, S>
 {
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Single-sample impulse at the start of the signal
+    struct Impulse<const SAMPLE_RATE: u32> {
+        fired: bool,
+    }
+
+    impl<const SAMPLE_RATE: u32> Impulse<SAMPLE_RATE> {
+        fn new() -> Self {
+            Self { fired: false }
+        }
+    }
+
+    impl<const SAMPLE_RATE: u32> Signal for Impulse<SAMPLE_RATE> {
+        fn next_sample(&mut self) -> f64 {
+            if self.fired {
+                0.0
+            } else {
+                self.fired = true;
+                1.0
+            }
+        }
+    }
+
+    impl<const SAMPLE_RATE: u32> AudioSignal<SAMPLE_RATE> for Impulse<SAMPLE_RATE> {}
+
+    /// Returns the sample indices and values of the first `count` echoes.
+    fn echoes<S: Signal>(delay: &mut S, samples: usize, count: usize) -> Vec<(usize, f64)> {
+        delay
+            .iter()
+            .take(samples)
+            .enumerate()
+            .skip(1)
+            .filter(|(_, v)| v.abs() > 1e-9)
+            .take(count)
+            .collect()
+    }
+
+    #[test]
+    fn test_slapback_preset() {
+        let mut delay = Delay::<1000, _>::slapback(Impulse::new());
+        let echoes = echoes(&mut delay, 400, 2);
+        assert_eq!(echoes[0].0, 75);
+        assert!((echoes[0].1 - 0.4).abs() < 1e-9);
+        assert_eq!(echoes[1].0, 150);
+        assert!((echoes[1].1 - 0.4 * 0.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_doubler_preset_has_single_echo() {
+        let mut delay = Delay::<1000, _>::doubler(Impulse::new());
+        let echoes = echoes(&mut delay, 400, 2);
+        assert_eq!(echoes.len(), 1);
+        assert_eq!(echoes[0].0, 30);
+        assert!((echoes[0].1 - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_dub_preset() {
+        let mut delay = Delay::<1000, _>::dub(Impulse::new());
+        let echoes = echoes(&mut delay, 2000, 3);
+        assert_eq!(echoes.len(), 3);
+        assert_eq!(echoes[0].0, 375);
+        assert_eq!(echoes[2].0, 1125);
+        assert!((echoes[2].1 - 0.45 * 0.7 * 0.7).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ambient_preset() {
+        let mut delay = Delay::<1000, _>::ambient(Impulse::new());
+        let echoes = echoes(&mut delay, 2000, 2);
+        assert_eq!(echoes[0].0, 600);
+        assert!((echoes[0].1 - 0.3).abs() < 1e-9);
+        assert!((echoes[1].1 - 0.3 * 0.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fractional_delay_interpolates() {
+        let mut delay = Delay::<1000, _>::new(Impulse::new(), 0.01, 0.0025, 0.0, 1.0);
+        let output: Vec<f64> = delay.iter().take(5).collect();
+        assert!(output[0].abs() < 1e-12);
+        assert!((output[2] - 0.5).abs() < 1e-9);
+        assert!((output[3] - 0.5).abs() < 1e-9);
+        assert!(output[4].abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_time_changes_are_smoothed() {
+        use crate::{SignalExt, SineOscillator};
+
+        // Jump the delay time from 10ms to 20ms after 0.1s
+        let mut n = 0;
+        let time = crate::ConstantSignal::<44100>(0.0).map(move |_| {
+            n += 1;
+            if n < 4410 { 0.01 } else { 0.02 }
+        });
+        let source = SineOscillator::<44100>::new(220.0);
+        let mut smoothed = Delay::new(source, 0.05, time, 0.0, 1.0).with_time_smoothing(0.05);
+
+        // A 220 Hz sine changes by at most ~0.032 per sample; a hard jump in
+        // read position would produce a much larger step
+        let mut previous = smoothed.next_sample();
+        for _ in 0..8820 {
+            let sample = smoothed.next_sample();
+            assert!((sample - previous).abs() < 0.05, "Discontinuity");
+            previous = sample;
+        }
+    }
+
+    #[test]
+    fn test_without_smoothing_time_jumps() {
+        use crate::SignalExt;
+
+        // Input is the sample index, so the output reveals the delay in samples
+        let mut index = -1.0;
+        let ramp = crate::ConstantSignal::<1000>(0.0).map(move |_| {
+            index += 1.0;
+            index
+        });
+        let mut n = 0;
+        let time = crate::ConstantSignal::<1000>(0.0).map(move |_| {
+            n += 1;
+            if n <= 10 { 0.002 } else { 0.005 }
+        });
+        let mut delay = Delay::<1000, _>::new(ramp, 0.01, time, 0.0, 1.0).with_time_smoothing(0.0);
+
+        let output: Vec<f64> = delay.iter().take(12).collect();
+        assert_eq!(output[9], 7.0);
+        assert_eq!(output[10], 5.0);
+        assert_eq!(output[11], 6.0);
+    }
+
+    #[test]
+    fn test_freeze_holds_loop() {
+        use crate::SignalExt;
+
+        // Impulse every 10 samples, frozen from the start
+        let mut n = 0;
+        let pulses = crate::ConstantSignal::<1000>(0.0).map(move |_| {
+            n += 1;
+            if n % 10 == 1 { 1.0 } else { 0.0 }
+        });
+        let mut n = 0;
+        let freeze = crate::ConstantSignal::<1000>(0.0).map(move |_| {
+            n += 1;
+            if n <= 20 { 0.0 } else { 1.0 }
+        });
+        let mut delay = Delay::<1000, _>::new(pulses, 0.02, 0.02, 0.0, 1.0).with_freeze(freeze);
+
+        // Let the freeze fade in, then the loop must repeat exactly every 20 samples
+        let output: Vec<f64> = delay.iter().take(500).collect();
+        let loop_a = &output[400..420];
+        let loop_b = &output[480..500];
+        for (a, b) in loop_a.iter().zip(loop_b) {
+            assert!((a - b).abs() < 1e-9);
+        }
+        assert!(loop_a.iter().any(|s| s.abs() > 0.1), "Loop decayed");
+    }
+
+    #[test]
+    fn test_freeze_mutes_input() {
+        let mut delay = Delay::<1000, _>::new(crate::ConstantSignal(1.0), 0.05, 0.05, 0.0, 0.5)
+            .with_freeze(1.0);
+        let output: Vec<f64> = delay.iter().take(200).collect();
+        assert!(output.iter().all(|s| s.abs() < 1e-12));
+    }
+}
@@ -0,0 +1,121 @@
+//! Ring-buffer delay line shared by the delay-based effects.
+
+/// A fixed-length ring buffer with fractional reads.
+///
+/// Delays are measured relative to the next write: a delay of 1.0 returns the
+/// most recently written sample.
+pub(crate) struct DelayLine {
+    buffer: Vec<f64>,
+    write_pos: usize,
+}
+
+impl DelayLine {
+    /// Creates a delay line holding up to `max_delay_samples` samples of history.
+    pub(crate) fn new(max_delay_samples: usize) -> Self {
+        Self {
+            buffer: vec![0.0; max_delay_samples.max(1) + 1],
+            write_pos: 0,
+        }
+    }
+
+    /// Creates a delay line long enough for `max_delay_time` seconds at `sample_rate`.
+    pub(crate) fn with_max_time(max_delay_time: f64, sample_rate: u32) -> Self {
+        Self::new((max_delay_time.max(0.0) * sample_rate as f64).ceil() as usize)
+    }
+
+    /// Longest delay that can be read, in samples.
+    pub(crate) fn max_delay(&self) -> f64 {
+        (self.buffer.len() - 1) as f64
+    }
+
+    /// Reads the sample `delay` samples back, linearly interpolating between
+    /// neighbours. `delay` is clamped to `[1, max_delay]`.
+    pub(crate) fn read(&self, delay: f64) -> f64 {
+        let len = self.buffer.len();
+        let delay = delay.clamp(1.0, self.max_delay());
+        let whole = delay.floor() as usize;
+        let frac = delay - whole as f64;
+        let newer = self.buffer[(self.write_pos + len - whole) % len];
+        let older = self.buffer[(self.write_pos + len - whole - 1) % len];
+        newer + (older - newer) * frac
+    }
+
+    /// Writes a sample and advances the write position.
+    pub(crate) fn write(&mut self, sample: f64) {
+        self.buffer[self.write_pos] = sample;
+        self.write_pos = (self.write_pos + 1) % self.buffer.len();
+    }
+}
+
+/// One-pole smoothing of a control value (e.g. a delay time) so that changes
+/// glide instead of jump.
+pub(crate) struct Smoothed {
+    current: Option<f64>,
+    coeff: f64,
+}
+
+impl Smoothed {
+    /// Creates a smoother with a time constant of `smoothing_time` seconds
+    /// (0.0 follows the target immediately).
+    pub(crate) fn new(smoothing_time: f64, sample_rate: u32) -> Self {
+        let mut smoother = Self {
+            current: None,
+            coeff: 1.0,
+        };
+        smoother.set_time(smoothing_time, sample_rate);
+        smoother
+    }
+
+    /// Changes the smoothing time constant.
+    pub(crate) fn set_time(&mut self, smoothing_time: f64, sample_rate: u32) {
+        self.coeff = if smoothing_time <= 0.0 {
+            1.0
+        } else {
+            1.0 - (-1.0 / (smoothing_time * sample_rate as f64)).exp()
+        };
+    }
+
+    /// Moves one sample toward `target` and returns the smoothed value.
+    ///
+    /// The first call jumps straight to the target.
+    pub(crate) fn next(&mut self, target: f64) -> f64 {
+        let value = match self.current {
+            Some(current) => current + (target - current) * self.coeff,
+            None => target,
+        };
+        self.current = Some(value);
+        value
+    }
+}
+
+/// Schroeder allpass built on a [`DelayLine`].
+///
+/// Passes every frequency at unity gain while smearing transients in time,
+/// which is what makes chains of them useful for diffusion.
+pub(crate) struct Allpass {
+    line: DelayLine,
+}
+
+impl Allpass {
+    /// Creates an allpass supporting delays up to `max_delay_samples` samples.
+    pub(crate) fn new(max_delay_samples: usize) -> Self {
+        Self {
+            line: DelayLine::new(max_delay_samples),
+        }
+    }
+
+    /// Creates an allpass supporting delays up to `max_delay_time` seconds.
+    pub(crate) fn with_max_time(max_delay_time: f64, sample_rate: u32) -> Self {
+        Self {
+            line: DelayLine::with_max_time(max_delay_time, sample_rate),
+        }
+    }
+
+    /// Processes one sample with the given delay (in samples) and gain.
+    pub(crate) fn process(&mut self, input: f64, delay: f64, gain: f64) -> f64 {
+        let delayed = self.line.read(delay);
+        let state = input + gain * delayed;
+        self.line.write(state);
+        delayed - gain * state
+    }
+}
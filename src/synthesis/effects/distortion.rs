@@ -1,6 +1,6 @@
 //! Distortion effect with drive and dry/wet mix.
 
-use crate::core::{AudioSignal, Param, Signal};
+use crate::core::{AudioSignal, Param, Signal, debug_assert_finite};
 
 /// Distortion effect that applies gain and clipping to create harmonic distortion.
 ///
@@ -101,7 +101,15 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for Distortion<
         let wet = wet * 0.7;
 
         // Mix dry and wet signals
-        dry * (1.0 - mix) + wet * mix
+        let output = dry * (1.0 - mix) + wet * mix;
+        debug_assert_finite("Distortion", dry, output);
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.drive.prepare(max_block_size, sample_rate);
+        self.mix.prepare(max_block_size, sample_rate);
     }
 }
 
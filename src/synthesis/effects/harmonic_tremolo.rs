@@ -0,0 +1,154 @@
+//! Harmonic tremolo (band-split amplitude modulation).
+
+use super::tremolo::tremolo_gain;
+use crate::core::{AudioSignal, Param, Signal, debug_assert_finite};
+use crate::synthesis::filters::Crossover;
+
+/// Crossover frequency used by [`HarmonicTremolo::with_rate`] and the presets.
+const DEFAULT_CROSSOVER: f64 = 800.0;
+
+/// Harmonic tremolo, the vintage brown-panel amp effect.
+///
+/// The signal is split into low and high bands and each band is tremoloed with
+/// the opposite LFO phase: as the lows swell the highs dip, and vice versa.
+/// Overall level stays nearly constant, giving a swirling, phaser-like throb
+/// rather than the on/off pulse of a plain [`Tremolo`](crate::Tremolo).
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{HarmonicTremolo, SineOscillator};
+///
+/// let audio = SineOscillator::<44100>::new(440.0);
+/// let lfo = SineOscillator::<44100>::new(4.0);
+/// // Split at 800Hz, 70% depth
+/// let mut tremolo = HarmonicTremolo::new(audio, lfo, 0.7, 800.0);
+/// ```
+pub struct HarmonicTremolo<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
+    source: S,
+    crossover: Crossover,
+
+    // Parameters
+    modulator: Param, // LFO in [-1, 1]; +1 favours the low band
+    depth: Param,     // 0.0 = no effect, 1.0 = each band fully cut in turn
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> HarmonicTremolo<SAMPLE_RATE, S> {
+    /// Creates a new harmonic tremolo.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Input audio signal
+    /// * `modulator` - Modulation source in [-1, 1] (typically a 2-8 Hz LFO)
+    /// * `depth` - Modulation depth (0.0 = no effect, 1.0 = full)
+    /// * `crossover_freq` - Frequency in Hz splitting the low and high bands
+    pub fn new(
+        source: S,
+        modulator: impl Into<Param>,
+        depth: impl Into<Param>,
+        crossover_freq: f64,
+    ) -> Self {
+        Self {
+            source,
+            crossover: Crossover::new(crossover_freq, SAMPLE_RATE),
+            modulator: modulator.into(),
+            depth: depth.into(),
+        }
+    }
+
+    /// Creates a harmonic tremolo with a fixed rate (uses internal sine LFO)
+    /// and an 800Hz crossover.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Input audio signal
+    /// * `rate` - Tremolo rate in Hz
+    /// * `depth` - Modulation depth (0.0-1.0)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{HarmonicTremolo, SineOscillator};
+    ///
+    /// let audio = SineOscillator::<44100>::new(440.0);
+    /// let mut tremolo = HarmonicTremolo::with_rate(audio, 4.0, 0.7);
+    /// ```
+    pub fn with_rate(source: S, rate: f64, depth: impl Into<Param>) -> Self {
+        let lfo = crate::synthesis::oscillators::SineOscillator::<SAMPLE_RATE>::new(rate);
+        Self::new(source, lfo, depth, DEFAULT_CROSSOVER)
+    }
+
+    /// Creates a classic brown-panel amp harmonic tremolo.
+    ///
+    /// Settings: 4 Hz, depth 0.7, 800 Hz crossover
+    pub fn vintage(source: S) -> Self {
+        Self::with_rate(source, 4.0, 0.7)
+    }
+
+    /// Creates a slow, deep swirl.
+    ///
+    /// Settings: 1.5 Hz, depth 1.0, 800 Hz crossover
+    pub fn swirl(source: S) -> Self {
+        Self::with_rate(source, 1.5, 1.0)
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal
+    for HarmonicTremolo<SAMPLE_RATE, S>
+{
+    fn next_sample(&mut self) -> f64 {
+        let input = self.source.next_sample();
+
+        // Get current parameter values
+        let depth = self.depth.value().clamp(0.0, 1.0);
+        let mod_value = self.modulator.value();
+
+        // Opposite LFO phases on the two bands
+        let (low, high) = self.crossover.split(input);
+        let output = low * tremolo_gain(mod_value, depth) + high * tremolo_gain(-mod_value, depth);
+        debug_assert_finite("HarmonicTremolo", input, output);
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.modulator.prepare(max_block_size, sample_rate);
+        self.depth.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE>
+    for HarmonicTremolo<SAMPLE_RATE, S>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SineOscillator;
+
+    /// Steady-state peak of a sine at `freq` with the modulator held at `mod_value`.
+    fn peak(freq: f64, mod_value: f64, depth: f64) -> f64 {
+        let mut tremolo =
+            HarmonicTremolo::new(SineOscillator::<44100>::new(freq), mod_value, depth, 800.0);
+        let output: Vec<f64> = tremolo.iter().take(22050).collect();
+        output[11025..]
+            .iter()
+            .fold(0.0_f64, |max, s| max.max(s.abs()))
+    }
+
+    #[test]
+    fn test_bands_move_in_opposite_directions() {
+        assert!(peak(100.0, 1.0, 1.0) > 0.95);
+        assert!(peak(6000.0, 1.0, 1.0) < 0.05);
+        assert!(peak(100.0, -1.0, 1.0) < 0.05);
+        assert!(peak(6000.0, -1.0, 1.0) > 0.95);
+    }
+
+    #[test]
+    fn test_zero_depth_is_flat() {
+        for freq in [100.0, 800.0, 6000.0] {
+            assert!((peak(freq, -1.0, 0.0) - 1.0).abs() < 0.01, "{} Hz", freq);
+        }
+    }
+}
@@ -1,6 +1,6 @@
 //! Limiter effect for preventing clipping.
 
-use crate::core::{AudioSignal, Param, Signal};
+use crate::core::{AudioSignal, Param, Signal, debug_assert_finite};
 
 /// Limiter effect that prevents audio from exceeding a threshold.
 ///
@@ -141,7 +141,15 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for Limiter<SAM
         }
 
         // Apply gain reduction
-        input * self.current_gain
+        let output = input * self.current_gain;
+        debug_assert_finite("Limiter", input, output);
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.threshold.prepare(max_block_size, sample_rate);
+        self.release.prepare(max_block_size, sample_rate);
     }
 }
 
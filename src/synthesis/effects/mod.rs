@@ -1,20 +1,44 @@
 //! Audio effects for signal processing.
 //!
-//! This module provides time-based and modulation effects that can be applied
+//! This module provides time-based, spatial and modulation effects that can be applied
 //! to any signal source.
 
+mod amp_sim;
 mod bitcrusher;
+mod cab_sim;
 mod compressor;
 mod delay;
+mod delay_line;
+mod diffuser;
 mod distortion;
+mod harmonic_tremolo;
 mod limiter;
+mod multiband_compressor;
+mod octaver;
+mod ping_pong;
+mod plate_reverb;
+mod reverb;
+mod spring_reverb;
+mod tone_stack;
 mod tremolo;
 mod vibrato;
 
+pub use amp_sim::AmpSim;
 pub use bitcrusher::Bitcrusher;
+pub use cab_sim::{CabModel, CabSim};
 pub use compressor::Compressor;
 pub use delay::Delay;
+pub(crate) use delay_line::DelayLine;
+pub use diffuser::Diffuser;
 pub use distortion::Distortion;
+pub use harmonic_tremolo::HarmonicTremolo;
 pub use limiter::Limiter;
+pub use multiband_compressor::{CompressorBand, MultibandCompressor};
+pub use octaver::Octaver;
+pub use ping_pong::PingPongDelay;
+pub use plate_reverb::PlateReverb;
+pub use reverb::Reverb;
+pub use spring_reverb::SpringReverb;
+pub use tone_stack::ToneStack;
 pub use tremolo::Tremolo;
 pub use vibrato::Vibrato;
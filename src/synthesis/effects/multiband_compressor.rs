@@ -0,0 +1,260 @@
+//! Multiband compressor for mastering-style dynamics.
+
+use super::compressor::GainComputer;
+use crate::core::{AudioSignal, Param, Signal, debug_assert_finite};
+use crate::synthesis::filters::BandSplitter;
+
+/// Compression settings for one band of a [`MultibandCompressor`].
+///
+/// The parameters mean the same as on a [`Compressor`](super::Compressor),
+/// plus a makeup gain applied to the band after compression. The default
+/// band is transparent: threshold 1.0, ratio 1:1, no makeup.
+pub struct CompressorBand {
+    threshold: Param, // threshold level (linear, 0.0-1.0)
+    ratio: Param,     // compression ratio (1.0 = no compression)
+    attack: Param,    // attack time in seconds
+    release: Param,   // release time in seconds
+    knee: Param,      // knee width in dB (0 = hard knee)
+    makeup: Param,    // linear gain applied after compression
+}
+
+impl CompressorBand {
+    /// Creates band settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - Threshold level (0.0-1.0 linear)
+    /// * `ratio` - Compression ratio (1.0 = no compression, 4.0 = 4:1)
+    /// * `attack` - Attack time in seconds
+    /// * `release` - Release time in seconds
+    /// * `knee` - Knee width in dB (0 = hard knee)
+    pub fn new(
+        threshold: impl Into<Param>,
+        ratio: impl Into<Param>,
+        attack: impl Into<Param>,
+        release: impl Into<Param>,
+        knee: impl Into<Param>,
+    ) -> Self {
+        Self {
+            threshold: threshold.into(),
+            ratio: ratio.into(),
+            attack: attack.into(),
+            release: release.into(),
+            knee: knee.into(),
+            makeup: Param::fixed(1.0),
+        }
+    }
+
+    /// Sets the linear gain applied to the band after compression.
+    pub fn with_makeup(mut self, gain: impl Into<Param>) -> Self {
+        self.makeup = gain.into();
+        self
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.threshold.prepare(max_block_size, sample_rate);
+        self.ratio.prepare(max_block_size, sample_rate);
+        self.attack.prepare(max_block_size, sample_rate);
+        self.release.prepare(max_block_size, sample_rate);
+        self.knee.prepare(max_block_size, sample_rate);
+        self.makeup.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl Default for CompressorBand {
+    fn default() -> Self {
+        Self::new(1.0, 1.0, 0.01, 0.1, 0.0)
+    }
+}
+
+/// Three-band compressor.
+///
+/// The signal is split into low, mid and high bands by a Linkwitz-Riley
+/// crossover (see [`ThreeBandCrossover`](crate::ThreeBandCrossover)), each
+/// band is compressed with its own [`CompressorBand`] settings, and the bands
+/// are summed. Taming a boomy low end no longer ducks the vocals and cymbals
+/// with it, which makes it the usual last stage on a full mix.
+///
+/// Bands start out transparent; with every band left at its default the
+/// output matches the input in level.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{CompressorBand, MultibandCompressor, SawtoothOscillator};
+///
+/// let mix = SawtoothOscillator::<44100>::new(55.0);
+/// // Hold the lows below 150Hz down hard, leave mids alone, tame the top
+/// let mut master = MultibandCompressor::new(mix, 150.0, 4000.0)
+///     .with_low(CompressorBand::new(0.4, 6.0, 0.02, 0.2, 6.0).with_makeup(1.4))
+///     .with_high(CompressorBand::new(0.5, 3.0, 0.002, 0.08, 6.0));
+/// ```
+pub struct MultibandCompressor<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
+    source: S,
+    splitter: BandSplitter,
+    bands: [CompressorBand; 3],   // low, mid, high settings
+    detectors: [GainComputer; 3], // per-band level detection
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> MultibandCompressor<SAMPLE_RATE, S> {
+    /// Creates a multiband compressor with transparent bands.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Input audio signal
+    /// * `low_freq` - Crossover between the low and mid bands in Hz
+    /// * `high_freq` - Crossover between the mid and high bands in Hz
+    ///
+    /// # Panics
+    ///
+    /// Panics if `low_freq >= high_freq`.
+    pub fn new(source: S, low_freq: f64, high_freq: f64) -> Self {
+        Self {
+            source,
+            splitter: BandSplitter::new(low_freq, high_freq, SAMPLE_RATE),
+            bands: Default::default(),
+            detectors: std::array::from_fn(|_| GainComputer::new(SAMPLE_RATE)),
+        }
+    }
+
+    /// Creates a gentle mastering preset.
+    ///
+    /// Settings: crossovers at 120Hz and 2.5kHz; lows 0.5 threshold, 3:1,
+    /// 30ms attack, 200ms release; mids 0.6, 2:1, 10ms, 150ms; highs 0.5,
+    /// 2.5:1, 3ms, 80ms; 6dB soft knee on every band
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{MultibandCompressor, SineOscillator};
+    ///
+    /// let mix = SineOscillator::<44100>::new(440.0);
+    /// let mut master = MultibandCompressor::mastering(mix);
+    /// ```
+    pub fn mastering(source: S) -> Self {
+        Self::new(source, 120.0, 2500.0)
+            .with_low(CompressorBand::new(0.5, 3.0, 0.03, 0.2, 6.0))
+            .with_mid(CompressorBand::new(0.6, 2.0, 0.01, 0.15, 6.0))
+            .with_high(CompressorBand::new(0.5, 2.5, 0.003, 0.08, 6.0))
+    }
+
+    /// Sets the low band's compression.
+    pub fn with_low(mut self, band: CompressorBand) -> Self {
+        self.bands[0] = band;
+        self
+    }
+
+    /// Sets the mid band's compression.
+    pub fn with_mid(mut self, band: CompressorBand) -> Self {
+        self.bands[1] = band;
+        self
+    }
+
+    /// Sets the high band's compression.
+    pub fn with_high(mut self, band: CompressorBand) -> Self {
+        self.bands[2] = band;
+        self
+    }
+
+    /// Gets each band's current gain reduction multiplier, `[low, mid, high]`
+    /// (1.0 = no reduction).
+    pub fn band_gains(&self) -> [f64; 3] {
+        std::array::from_fn(|band| self.detectors[band].current_gain())
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal
+    for MultibandCompressor<SAMPLE_RATE, S>
+{
+    fn next_sample(&mut self) -> f64 {
+        let input = self.source.next_sample();
+        let split = self.splitter.split(input);
+
+        let mut output = 0.0;
+        for ((sample, band), detector) in split
+            .into_iter()
+            .zip(&mut self.bands)
+            .zip(&mut self.detectors)
+        {
+            let gain = detector.process(
+                sample,
+                band.threshold.value(),
+                band.ratio.value(),
+                band.attack.value(),
+                band.release.value(),
+                band.knee.value(),
+            );
+            output += sample * gain * band.makeup.value();
+        }
+
+        debug_assert_finite("MultibandCompressor", input, output);
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        for band in &mut self.bands {
+            band.prepare(max_block_size, sample_rate);
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE>
+    for MultibandCompressor<SAMPLE_RATE, S>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::SignalExt;
+    use crate::synthesis::oscillators::SineOscillator;
+
+    fn peak(signal: &mut impl Signal, skip: usize, length: usize) -> f64 {
+        for _ in 0..skip {
+            signal.next_sample();
+        }
+        (0..length).fold(0.0_f64, |max, _| max.max(signal.next_sample().abs()))
+    }
+
+    #[test]
+    fn test_default_bands_are_transparent() {
+        let source = SineOscillator::<44100>::new(60.0).add(SineOscillator::<44100>::new(5000.0));
+        let mut comp = MultibandCompressor::new(source, 200.0, 3000.0);
+        let level = peak(&mut comp, 22050, 4410);
+        let dry = peak(
+            &mut SineOscillator::<44100>::new(60.0).add(SineOscillator::<44100>::new(5000.0)),
+            22050,
+            4410,
+        );
+        assert!((level - dry).abs() < 0.05, "{} vs {}", level, dry);
+        assert_eq!(comp.band_gains(), [1.0; 3]);
+    }
+
+    #[test]
+    fn test_bands_compress_independently() {
+        // A loud bass line under a quiet treble tone; only the low band is set
+        // to compress
+        let bass = || SineOscillator::<44100>::new(60.0).gain(0.9);
+        let treble = || SineOscillator::<44100>::new(6000.0).gain(0.2);
+        let mut comp = MultibandCompressor::new(bass().add(treble()), 200.0, 3000.0)
+            .with_low(CompressorBand::new(0.2, 10.0, 0.001, 0.1, 0.0))
+            .with_high(CompressorBand::new(0.2, 10.0, 0.001, 0.1, 0.0));
+        peak(&mut comp, 22050, 0);
+
+        let [low, mid, high] = comp.band_gains();
+        assert!(low < 0.5, "low gain {}", low);
+        assert_eq!(mid, 1.0);
+        // The treble sits below the threshold and is left alone
+        assert!(high > 0.95, "high gain {}", high);
+    }
+
+    #[test]
+    fn test_makeup_gain_scales_band() {
+        let mut comp =
+            MultibandCompressor::new(SineOscillator::<44100>::new(1000.0), 200.0, 3000.0)
+                .with_mid(CompressorBand::default().with_makeup(0.5));
+        let level = peak(&mut comp, 22050, 4410);
+        assert!((level - 0.5).abs() < 0.02, "level {}", level);
+    }
+}
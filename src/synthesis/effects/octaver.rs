@@ -0,0 +1,187 @@
+//! Sub-octave generator using flip-flop frequency division.
+
+use crate::core::{AudioSignal, Param, Signal, debug_assert_finite};
+use crate::synthesis::filters::{Biquad, FilterType, biquad_coefficients};
+
+/// Lowpass applied before zero-crossing detection so harmonics don't retrigger it.
+const TRACKING_CUTOFF: f64 = 800.0;
+
+/// Lowpass that rounds off the square-wave sub octaves.
+const VOICE_CUTOFF: f64 = 1500.0;
+
+/// Envelope follower release time in seconds.
+const ENVELOPE_RELEASE: f64 = 0.05;
+
+/// Crossing hysteresis as a fraction of the current envelope.
+const HYSTERESIS: f64 = 0.1;
+
+/// Octave divider: adds tones one and two octaves below the input.
+///
+/// Works like the classic analog octave pedals. The input is lowpassed and
+/// each rising zero crossing toggles a flip-flop, giving a square wave at half
+/// the input frequency; a second flip-flop divides again for two octaves down.
+/// The squares follow the input's envelope and are smoothed before being mixed
+/// under the dry signal. Tracking works best on monophonic lines such as bass.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{Octaver, SawtoothOscillator};
+///
+/// let bass = SawtoothOscillator::<44100>::new(110.0);
+/// // Dry at full level, -1 octave at 70%, -2 octaves at 30%
+/// let mut octaver = Octaver::new(bass, 1.0, 0.7, 0.3);
+/// ```
+pub struct Octaver<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
+    source: S,
+    tracking_filter: Biquad,
+    voice_filters: [Biquad; 2], // smoothing for -1 and -2 octave voices
+    envelope: f64,
+    release_coeff: f64,
+    armed: bool, // input has gone below the lower hysteresis threshold
+    flip_flops: [bool; 2],
+
+    // Parameters
+    dry: Param,      // level of the original signal
+    octave_1: Param, // level of the -1 octave voice
+    octave_2: Param, // level of the -2 octave voice
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Octaver<SAMPLE_RATE, S> {
+    /// Creates a new octaver.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Input signal (monophonic works best)
+    /// * `dry` - Level of the original signal (0.0-1.0)
+    /// * `octave_1` - Level of the tone one octave down (0.0-1.0)
+    /// * `octave_2` - Level of the tone two octaves down (0.0-1.0)
+    pub fn new(
+        source: S,
+        dry: impl Into<Param>,
+        octave_1: impl Into<Param>,
+        octave_2: impl Into<Param>,
+    ) -> Self {
+        let lowpass = |cutoff| {
+            Biquad::new(biquad_coefficients(
+                FilterType::LowPass,
+                cutoff,
+                0.707,
+                SAMPLE_RATE,
+            ))
+        };
+        Self {
+            source,
+            tracking_filter: lowpass(TRACKING_CUTOFF),
+            voice_filters: [lowpass(VOICE_CUTOFF), lowpass(VOICE_CUTOFF)],
+            envelope: 0.0,
+            release_coeff: (-1.0 / (ENVELOPE_RELEASE * SAMPLE_RATE as f64)).exp(),
+            armed: false,
+            flip_flops: [false; 2],
+            dry: dry.into(),
+            octave_1: octave_1.into(),
+            octave_2: octave_2.into(),
+        }
+    }
+
+    /// Creates a bass octaver (-1 octave blended under the dry signal).
+    ///
+    /// Settings: dry 1.0, -1 octave 0.8, -2 octaves 0.0
+    pub fn bass(source: S) -> Self {
+        Self::new(source, 1.0, 0.8, 0.0)
+    }
+
+    /// Creates a sub-heavy octaver (both octaves, dry tucked under).
+    ///
+    /// Settings: dry 0.5, -1 octave 0.8, -2 octaves 0.6
+    pub fn sub(source: S) -> Self {
+        Self::new(source, 0.5, 0.8, 0.6)
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for Octaver<SAMPLE_RATE, S> {
+    fn next_sample(&mut self) -> f64 {
+        let input = self.source.next_sample();
+
+        // Get current parameter values
+        let dry = self.dry.value().max(0.0);
+        let octave_1 = self.octave_1.value().max(0.0);
+        let octave_2 = self.octave_2.value().max(0.0);
+
+        // Envelope follower: instant attack, exponential release
+        let level = input.abs();
+        self.envelope = if level > self.envelope {
+            level
+        } else {
+            level + (self.envelope - level) * self.release_coeff
+        };
+
+        // Rising zero crossing (with hysteresis) toggles the divider chain
+        let tracked = self.tracking_filter.process(input);
+        let threshold = self.envelope * HYSTERESIS;
+        if tracked < -threshold {
+            self.armed = true;
+        } else if self.armed && tracked > threshold {
+            self.armed = false;
+            self.flip_flops[0] = !self.flip_flops[0];
+            if self.flip_flops[0] {
+                self.flip_flops[1] = !self.flip_flops[1];
+            }
+        }
+
+        let square = |high: bool| if high { self.envelope } else { -self.envelope };
+        let sub_1 = self.voice_filters[0].process(square(self.flip_flops[0]));
+        let sub_2 = self.voice_filters[1].process(square(self.flip_flops[1]));
+
+        let output = input * dry + sub_1 * octave_1 + sub_2 * octave_2;
+        debug_assert_finite("Octaver", input, output);
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.dry.prepare(max_block_size, sample_rate);
+        self.octave_1.prepare(max_block_size, sample_rate);
+        self.octave_2.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE>
+    for Octaver<SAMPLE_RATE, S>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SawtoothOscillator, SineOscillator};
+
+    /// Counts rising zero crossings in the second half of one second of output.
+    fn rising_crossings<S: Signal>(signal: &mut S) -> usize {
+        let output: Vec<f64> = signal.iter().take(44100).collect();
+        output[22050..]
+            .windows(2)
+            .filter(|pair| pair[0] <= 0.0 && pair[1] > 0.0)
+            .count()
+    }
+
+    #[test]
+    fn test_divides_by_two_and_four() {
+        let mut one_down = Octaver::new(SineOscillator::<44100>::new(200.0), 0.0, 1.0, 0.0);
+        let mut two_down = Octaver::new(SineOscillator::<44100>::new(200.0), 0.0, 0.0, 1.0);
+        assert!(rising_crossings(&mut one_down).abs_diff(50) <= 1);
+        assert!(rising_crossings(&mut two_down).abs_diff(25) <= 1);
+    }
+
+    #[test]
+    fn test_harmonics_do_not_retrigger() {
+        let mut octaver = Octaver::new(SawtoothOscillator::<44100>::new(110.0), 0.0, 1.0, 0.0);
+        assert!(rising_crossings(&mut octaver).abs_diff(27) <= 1);
+    }
+
+    #[test]
+    fn test_silence_stays_silent() {
+        let mut octaver = Octaver::sub(crate::ConstantSignal::<44100>(0.0));
+        assert!(octaver.iter().take(4410).all(|s| s.abs() < 1e-12));
+    }
+}
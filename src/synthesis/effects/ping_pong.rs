@@ -0,0 +1,253 @@
+//! Stereo ping-pong delay.
+
+use super::delay::{DEFAULT_TIME_SMOOTHING, FREEZE_SMOOTHING};
+use super::delay_line::{DelayLine, Smoothed};
+use crate::core::{
+    AudioFrameSignal, AudioSignal, ChannelMap, FrameSignal, FrameSignalExt, Param, Remap,
+    debug_assert_finite,
+};
+
+/// Stereo delay whose echoes bounce between the left and right channels.
+///
+/// Each channel has its own delay line. `feedback` sends a channel's echo back
+/// into the same channel, while `cross_feedback` sends it into the opposite
+/// channel. With only cross-feedback, an echo alternates sides on every repeat;
+/// mixing in straight feedback widens the pattern into overlapping echoes.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{FrameSignal, PingPongDelay, SineOscillator};
+///
+/// let stereo = [
+///     SineOscillator::<44100>::new(440.0),
+///     SineOscillator::<44100>::new(440.0),
+/// ];
+/// let mut delay = PingPongDelay::new(stereo, 1.0, 0.25, 0.0, 0.6, 0.4);
+/// let [left, right] = delay.next_frame();
+/// ```
+pub struct PingPongDelay<const SAMPLE_RATE: u32, F: AudioFrameSignal<SAMPLE_RATE, 2>> {
+    source: F,
+    left: DelayLine,
+    right: DelayLine,
+    current_delay: Smoothed, // smoothed delay time in samples, shared by both lines
+    freeze_amount: Smoothed, // smoothed freeze control, 0.0 to 1.0
+
+    // Parameters
+    delay_time: Param,     // delay time in seconds
+    feedback: Param,       // echo fed back into the same channel
+    cross_feedback: Param, // echo fed into the opposite channel
+    mix: Param,            // dry/wet mix, 0.0 = all dry, 1.0 = all wet
+    freeze: Param,         // 0.0 = normal, 1.0 = hold the buffer contents forever
+}
+
+impl<const SAMPLE_RATE: u32, F: AudioFrameSignal<SAMPLE_RATE, 2>> PingPongDelay<SAMPLE_RATE, F> {
+    /// Creates a new ping-pong delay.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Stereo input signal
+    /// * `max_delay_time` - Maximum delay time in seconds (determines buffer size)
+    /// * `delay_time` - Initial/modulated delay time in seconds
+    /// * `feedback` - Same-channel feedback (0.0-0.95)
+    /// * `cross_feedback` - Opposite-channel feedback (0.0-0.95)
+    /// * `mix` - Dry/wet mix (0.0 = all dry/original, 1.0 = all wet/delayed)
+    pub fn new(
+        source: F,
+        max_delay_time: f64,
+        delay_time: impl Into<Param>,
+        feedback: impl Into<Param>,
+        cross_feedback: impl Into<Param>,
+        mix: impl Into<Param>,
+    ) -> Self {
+        Self {
+            source,
+            left: DelayLine::with_max_time(max_delay_time, SAMPLE_RATE),
+            right: DelayLine::with_max_time(max_delay_time, SAMPLE_RATE),
+            current_delay: Smoothed::new(DEFAULT_TIME_SMOOTHING, SAMPLE_RATE),
+            freeze_amount: Smoothed::new(FREEZE_SMOOTHING, SAMPLE_RATE),
+            delay_time: delay_time.into(),
+            feedback: feedback.into(),
+            cross_feedback: cross_feedback.into(),
+            mix: mix.into(),
+            freeze: Param::Fixed(0.0),
+        }
+    }
+
+    /// Sets how quickly the delay follows changes to its time parameter.
+    ///
+    /// See [`Delay::with_time_smoothing`](crate::Delay::with_time_smoothing).
+    pub fn with_time_smoothing(mut self, smoothing_time: f64) -> Self {
+        self.current_delay.set_time(smoothing_time, SAMPLE_RATE);
+        self
+    }
+
+    /// Adds a freeze control that turns both buffers into an infinite loop.
+    ///
+    /// Works like [`Delay::with_freeze`](crate::Delay::with_freeze). While
+    /// frozen, feedback and cross-feedback are rescaled to sum to 1.0 so the
+    /// echo keeps its stereo pattern without decaying (pure cross-feedback if
+    /// both are zero).
+    pub fn with_freeze(mut self, freeze: impl Into<Param>) -> Self {
+        self.freeze = freeze.into();
+        self
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>>
+    PingPongDelay<SAMPLE_RATE, Remap<[S; 1], 1, 2>>
+{
+    /// Creates a classic ping-pong delay from a mono signal.
+    ///
+    /// The input enters on the left only and every echo crosses to the other
+    /// side, so repeats alternate left, right, left, ...
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Mono input signal
+    /// * `delay_time` - Time between echoes in seconds
+    /// * `feedback` - Level of each repeat relative to the previous one (0.0-0.95)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{FrameSignal, PingPongDelay, SineOscillator};
+    ///
+    /// let audio = SineOscillator::<44100>::new(440.0);
+    /// let mut delay = PingPongDelay::from_mono(audio, 0.3, 0.5);
+    /// let [left, right] = delay.next_frame();
+    /// ```
+    pub fn from_mono(source: S, delay_time: f64, feedback: f64) -> Self {
+        let stereo = [source].remap(ChannelMap::new([Some(0), None]));
+        Self::new(stereo, delay_time, delay_time, 0.0, feedback, 0.5)
+    }
+}
+
+impl<const SAMPLE_RATE: u32, F: AudioFrameSignal<SAMPLE_RATE, 2>> FrameSignal<2>
+    for PingPongDelay<SAMPLE_RATE, F>
+{
+    fn next_frame(&mut self) -> [f64; 2] {
+        let [in_left, in_right] = self.source.next_frame();
+
+        // Get current parameter values
+        let delay_time = self.delay_time.value().max(0.0);
+        let feedback = self.feedback.value().clamp(0.0, 0.99);
+        let cross_feedback = self.cross_feedback.value().clamp(0.0, 0.99);
+        let mix = self.mix.value().clamp(0.0, 1.0);
+        let frozen = self.freeze_amount.next(self.freeze.value().clamp(0.0, 1.0));
+
+        // Freezing mutes the input and moves the loop gain to exactly 1.0
+        let total = feedback + cross_feedback;
+        let (held_feedback, held_cross) = if total > 0.0 {
+            (feedback / total, cross_feedback / total)
+        } else {
+            (0.0, 1.0)
+        };
+        let input_gain = 1.0 - frozen;
+        let feedback = feedback + (held_feedback - feedback) * frozen;
+        let cross_feedback = cross_feedback + (held_cross - cross_feedback) * frozen;
+        let wet = mix + (1.0 - mix) * frozen;
+        let (in_left, in_right) = (in_left * input_gain, in_right * input_gain);
+
+        let target = (delay_time * SAMPLE_RATE as f64).clamp(1.0, self.left.max_delay());
+        let delay_samples = self.current_delay.next(target);
+
+        let delayed_left = self.left.read(delay_samples);
+        let delayed_right = self.right.read(delay_samples);
+        self.left
+            .write(in_left + delayed_left * feedback + delayed_right * cross_feedback);
+        self.right
+            .write(in_right + delayed_right * feedback + delayed_left * cross_feedback);
+
+        let out_left = in_left * (1.0 - mix) + delayed_left * wet;
+        let out_right = in_right * (1.0 - mix) + delayed_right * wet;
+        debug_assert_finite("PingPongDelay", in_left, out_left);
+        debug_assert_finite("PingPongDelay", in_right, out_right);
+        [out_left, out_right]
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.delay_time.prepare(max_block_size, sample_rate);
+        self.feedback.prepare(max_block_size, sample_rate);
+        self.cross_feedback.prepare(max_block_size, sample_rate);
+        self.mix.prepare(max_block_size, sample_rate);
+        self.freeze.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, F: AudioFrameSignal<SAMPLE_RATE, 2>> AudioFrameSignal<SAMPLE_RATE, 2>
+    for PingPongDelay<SAMPLE_RATE, F>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstantSignal, SignalExt};
+
+    /// Single-sample impulse at the start of the signal.
+    fn impulse() -> impl AudioSignal<1000> {
+        let mut fired = false;
+        ConstantSignal::<1000>(0.0).map(move |_| {
+            let value = if fired { 0.0 } else { 1.0 };
+            fired = true;
+            value
+        })
+    }
+
+    /// Returns `(index, channel, value)` for every non-zero wet sample.
+    fn echoes<F: FrameSignal<2>>(delay: &mut F, frames: usize) -> Vec<(usize, usize, f64)> {
+        let mut echoes = Vec::new();
+        for index in 0..frames {
+            for (channel, value) in delay.next_frame().into_iter().enumerate() {
+                if index > 0 && value.abs() > 1e-9 {
+                    echoes.push((index, channel, value));
+                }
+            }
+        }
+        echoes
+    }
+
+    #[test]
+    fn test_from_mono_alternates_channels() {
+        let mut delay = PingPongDelay::<1000, _>::from_mono(impulse(), 0.1, 0.5);
+        let echoes = echoes(&mut delay, 350);
+        assert_eq!(echoes.len(), 3);
+        assert_eq!((echoes[0].0, echoes[0].1), (100, 0));
+        assert_eq!((echoes[1].0, echoes[1].1), (200, 1));
+        assert_eq!((echoes[2].0, echoes[2].1), (300, 0));
+        assert!((echoes[0].2 - 0.5).abs() < 1e-9);
+        assert!((echoes[1].2 - 0.25).abs() < 1e-9);
+        assert!((echoes[2].2 - 0.125).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_straight_feedback_stays_on_channel() {
+        let stereo = [impulse()].remap(ChannelMap::new([Some(0), None]));
+        let mut delay = PingPongDelay::<1000, _>::new(stereo, 0.1, 0.05, 0.5, 0.0, 1.0);
+        let echoes = echoes(&mut delay, 180);
+        assert_eq!(echoes.len(), 3);
+        assert!(echoes.iter().all(|&(_, channel, _)| channel == 0));
+        assert!((echoes[2].2 - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_freeze_keeps_bouncing() {
+        let mut n = 0;
+        let freeze = ConstantSignal::<1000>(0.0).map(move |_| {
+            n += 1;
+            if n <= 150 { 0.0 } else { 1.0 }
+        });
+        let mut delay =
+            PingPongDelay::<1000, _>::from_mono(impulse(), 0.1, 0.5).with_freeze(freeze);
+        let frames: Vec<[f64; 2]> = (0..2000).map(|_| delay.next_frame()).collect();
+
+        // Once frozen the echo alternates sides at a constant level
+        let late_left = frames[1900][0];
+        let late_right = frames[1800][1];
+        assert!(late_left.abs() > 0.1);
+        assert!((late_left - frames[1700][0]).abs() < 1e-9);
+        assert!((late_right - frames[1600][1]).abs() < 1e-9);
+    }
+}
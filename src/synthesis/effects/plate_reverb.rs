@@ -0,0 +1,210 @@
+//! Plate reverb modelled on the Dattorro figure-eight tank.
+
+use super::delay_line::{Allpass, DelayLine};
+use super::diffuser::DiffusionNetwork;
+use super::reverb::damp;
+use crate::core::{AudioSignal, Param, Signal, debug_assert_finite};
+
+/// Tank allpass times in seconds: (first, second) for each half.
+const TANK_ALLPASS_TIMES: [(f64, f64); 2] = [(0.0226, 0.0605), (0.0305, 0.0892)];
+
+/// Tank delay times in seconds: (first, second) for each half.
+const TANK_DELAY_TIMES: [(f64, f64); 2] = [(0.1496, 0.1250), (0.1417, 0.1063)];
+
+/// Output tap times in seconds, read from the first delay of each half.
+const OUTPUT_TAPS: [f64; 2] = [0.0089, 0.0999];
+
+/// Depth (seconds) and rate (Hz) of the slow wobble on the first tank allpasses.
+const MODULATION_DEPTH: f64 = 0.0005;
+const MODULATION_RATE: f64 = 1.0;
+
+/// One half of the figure-eight tank.
+struct TankHalf {
+    first_allpass: Allpass,
+    first_delay: DelayLine,
+    second_allpass: Allpass,
+    second_delay: DelayLine,
+    damping_state: f64,
+    tail: f64, // output of the second delay, fed to the other half
+}
+
+/// Plate reverb.
+///
+/// Models the bright, dense wash of a steel plate: an input diffuser feeds two
+/// cross-coupled halves of a tank, each a modulated allpass, a delay, a damping
+/// filter, a second allpass and a second delay. The echo density builds up
+/// almost immediately, which suits vocals and snares.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{PlateReverb, SineOscillator};
+///
+/// let audio = SineOscillator::<44100>::new(440.0);
+/// // 2.5 second tail, light damping, 30% wet
+/// let mut plate = PlateReverb::new(audio, 2.5, 0.2, 0.3);
+/// ```
+pub struct PlateReverb<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
+    source: S,
+    diffuser: DiffusionNetwork<SAMPLE_RATE>,
+    tank: [TankHalf; 2],
+    modulation_phase: f64, // 0.0 to 1.0
+
+    // Parameters
+    decay_time: Param, // RT60 in seconds
+    damping: Param,    // high-frequency damping, 0.0 = bright, 1.0 = dark
+    mix: Param,        // dry/wet mix, 0.0 = all dry, 1.0 = all wet
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> PlateReverb<SAMPLE_RATE, S> {
+    /// Creates a new plate reverb.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Input signal
+    /// * `decay_time` - Time in seconds for the tail to fall by 60dB
+    /// * `damping` - High-frequency damping (0.0 = bright, 1.0 = dark)
+    /// * `mix` - Dry/wet mix (0.0 = all dry/original, 1.0 = all wet/reverb)
+    pub fn new(
+        source: S,
+        decay_time: impl Into<Param>,
+        damping: impl Into<Param>,
+        mix: impl Into<Param>,
+    ) -> Self {
+        let half = |i: usize| {
+            let (first_allpass, second_allpass) = TANK_ALLPASS_TIMES[i];
+            let (first_delay, second_delay) = TANK_DELAY_TIMES[i];
+            TankHalf {
+                first_allpass: Allpass::with_max_time(
+                    first_allpass + MODULATION_DEPTH,
+                    SAMPLE_RATE,
+                ),
+                first_delay: DelayLine::with_max_time(first_delay, SAMPLE_RATE),
+                second_allpass: Allpass::with_max_time(second_allpass, SAMPLE_RATE),
+                second_delay: DelayLine::with_max_time(second_delay, SAMPLE_RATE),
+                damping_state: 0.0,
+                tail: 0.0,
+            }
+        };
+
+        Self {
+            source,
+            diffuser: DiffusionNetwork::new(),
+            tank: [half(0), half(1)],
+            modulation_phase: 0.0,
+            decay_time: decay_time.into(),
+            damping: damping.into(),
+            mix: mix.into(),
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for PlateReverb<SAMPLE_RATE, S> {
+    fn next_sample(&mut self) -> f64 {
+        let input = self.source.next_sample();
+
+        // Get current parameter values
+        let decay_time = self.decay_time.value().max(0.01);
+        let damping = self.damping.value().clamp(0.0, 1.0) * 0.95;
+        let mix = self.mix.value().clamp(0.0, 1.0);
+
+        // Gain is applied twice per half, so each application covers half of
+        // that half's share of the 60dB decay
+        let half_loop: f64 = TANK_ALLPASS_TIMES[0].0
+            + TANK_ALLPASS_TIMES[0].1
+            + TANK_DELAY_TIMES[0].0
+            + TANK_DELAY_TIMES[0].1;
+        let gain = 10.0_f64.powf(-3.0 * half_loop / (2.0 * decay_time));
+
+        let diffused = self.diffuser.process(input, 1.0, 0.7);
+
+        let wobble = (self.modulation_phase * std::f64::consts::TAU).sin() * MODULATION_DEPTH;
+        self.modulation_phase =
+            (self.modulation_phase + MODULATION_RATE / SAMPLE_RATE as f64) % 1.0;
+
+        // Each half is fed by the other's tail from the previous sample
+        let tails = [self.tank[1].tail, self.tank[0].tail];
+        let rate = SAMPLE_RATE as f64;
+        for (i, half) in self.tank.iter_mut().enumerate() {
+            let (first_allpass, second_allpass) = TANK_ALLPASS_TIMES[i];
+            let (first_delay, second_delay) = TANK_DELAY_TIMES[i];
+            // Opposite wobble on each half keeps the modulation from sounding like chorus
+            let wobble = if i == 0 { wobble } else { -wobble };
+
+            let fed = diffused + tails[i] * gain;
+            let smeared = half
+                .first_allpass
+                .process(fed, (first_allpass + wobble) * rate, 0.7);
+            let delayed = half.first_delay.read(first_delay * rate);
+            half.first_delay.write(smeared);
+
+            let damped = damp(&mut half.damping_state, delayed, damping) * gain;
+            let smeared = half
+                .second_allpass
+                .process(damped, second_allpass * rate, 0.5);
+            half.tail = half.second_delay.read(second_delay * rate);
+            half.second_delay.write(smeared);
+        }
+
+        // Tap both halves at a few points for a dense, decorrelated output
+        let reverb = 0.6
+            * (self.tank[0].first_delay.read(OUTPUT_TAPS[0] * rate)
+                + self.tank[1].first_delay.read(OUTPUT_TAPS[1] * rate)
+                - self.tank[0].tail
+                - self.tank[1].tail);
+
+        let output = input * (1.0 - mix) + reverb * mix;
+        debug_assert_finite("PlateReverb", input, output);
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.decay_time.prepare(max_block_size, sample_rate);
+        self.damping.prepare(max_block_size, sample_rate);
+        self.mix.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE>
+    for PlateReverb<SAMPLE_RATE, S>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstantSignal, SignalExt};
+
+    fn impulse() -> impl AudioSignal<44100> {
+        let mut fired = false;
+        ConstantSignal::<44100>(0.0).map(move |_| {
+            let value = if fired { 0.0 } else { 1.0 };
+            fired = true;
+            value
+        })
+    }
+
+    fn rms(samples: &[f64]) -> f64 {
+        (samples.iter().map(|s| s * s).sum::<f64>() / samples.len() as f64).sqrt()
+    }
+
+    #[test]
+    fn test_tail_decays_with_decay_time() {
+        let mut short = PlateReverb::new(impulse(), 0.5, 0.2, 1.0);
+        let mut long = PlateReverb::new(impulse(), 4.0, 0.2, 1.0);
+        let short: Vec<f64> = short.iter().take(66150).collect();
+        let long: Vec<f64> = long.iter().take(66150).collect();
+
+        assert!(rms(&long[22050..44100]) > 1e-4, "Long plate died out");
+        assert!(rms(&short[44100..]) < rms(&long[44100..]) * 1e-2);
+    }
+
+    #[test]
+    fn test_dense_early_response() {
+        let mut plate = PlateReverb::new(impulse(), 2.0, 0.2, 1.0);
+        let output: Vec<f64> = plate.iter().take(22050).collect();
+        let dense = output[8820..].iter().filter(|s| s.abs() > 1e-5).count();
+        assert!(dense > 10000, "Only {} non-silent samples", dense);
+    }
+}
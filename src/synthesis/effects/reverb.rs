@@ -0,0 +1,251 @@
+//! Algorithmic reverb built from a diffuser and a feedback delay network.
+
+use super::delay::FREEZE_SMOOTHING;
+use super::delay_line::{DelayLine, Smoothed};
+use super::diffuser::DiffusionNetwork;
+use crate::core::{AudioSignal, Param, Signal, debug_assert_finite};
+
+/// Feedback delay line lengths in seconds.
+const LINE_TIMES: [f64; 4] = [0.0297, 0.0371, 0.0411, 0.0437];
+
+/// Values below this are flushed to zero so decaying tails don't go denormal.
+const DENORMAL_THRESHOLD: f64 = 1e-30;
+
+/// One-pole lowpass used for damping inside reverb feedback loops.
+///
+/// `damping` of 0.0 passes the input unchanged. The state is flushed to zero
+/// once it gets tiny so decaying tails don't go denormal.
+pub(crate) fn damp(state: &mut f64, input: f64, damping: f64) -> f64 {
+    *state += (1.0 - damping) * (input - *state);
+    if state.abs() < DENORMAL_THRESHOLD {
+        *state = 0.0;
+    }
+    *state
+}
+
+/// Algorithmic reverb.
+///
+/// The input passes through a [`Diffuser`](crate::Diffuser)-style allpass
+/// network for early reflections, then into a four-line feedback delay network
+/// whose lines are mixed through a Householder matrix. A one-pole lowpass in
+/// each line makes high frequencies die away faster, like absorption in a real
+/// room.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{Reverb, SineOscillator};
+///
+/// let audio = SineOscillator::<44100>::new(440.0);
+/// // 2 second tail, moderate damping, 30% wet
+/// let mut reverb = Reverb::new(audio, 2.0, 0.4, 0.3);
+/// ```
+pub struct Reverb<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
+    source: S,
+    diffuser: DiffusionNetwork<SAMPLE_RATE>,
+    lines: [DelayLine; 4],
+    damping_state: [f64; 4], // lowpass state per line
+    line_gains: [f64; 4],    // per-line feedback gain for the cached decay time
+    cached_decay: f64,       // decay time `line_gains` were computed for
+    freeze_amount: Smoothed, // smoothed freeze control, 0.0 to 1.0
+
+    // Parameters
+    decay_time: Param, // RT60 in seconds (time for the tail to fall by 60dB)
+    damping: Param,    // high-frequency damping, 0.0 = bright, 1.0 = dark
+    mix: Param,        // dry/wet mix, 0.0 = all dry, 1.0 = all wet
+    size: Param,       // diffuser size multiplier
+    diffusion: Param,  // diffuser allpass gain
+    freeze: Param,     // 0.0 = normal, 1.0 = hold the tail forever
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Reverb<SAMPLE_RATE, S> {
+    /// Creates a new reverb.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Input signal
+    /// * `decay_time` - Time in seconds for the tail to fall by 60dB
+    /// * `damping` - High-frequency damping (0.0 = bright, 1.0 = dark)
+    /// * `mix` - Dry/wet mix (0.0 = all dry/original, 1.0 = all wet/reverb)
+    pub fn new(
+        source: S,
+        decay_time: impl Into<Param>,
+        damping: impl Into<Param>,
+        mix: impl Into<Param>,
+    ) -> Self {
+        Self {
+            source,
+            diffuser: DiffusionNetwork::new(),
+            lines: LINE_TIMES.map(|time| DelayLine::with_max_time(time, SAMPLE_RATE)),
+            damping_state: [0.0; 4],
+            line_gains: [0.0; 4],
+            cached_decay: f64::NAN,
+            freeze_amount: Smoothed::new(FREEZE_SMOOTHING, SAMPLE_RATE),
+            decay_time: decay_time.into(),
+            damping: damping.into(),
+            mix: mix.into(),
+            size: Param::Fixed(1.0),
+            diffusion: Param::Fixed(0.7),
+            freeze: Param::Fixed(0.0),
+        }
+    }
+
+    /// Sets the early-reflection diffuser's controls.
+    ///
+    /// See [`Diffuser::new`](crate::Diffuser::new) for the ranges. Defaults are
+    /// size 1.0 and diffusion 0.7; diffusion 0.0 leaves audible discrete echoes.
+    pub fn with_diffusion(mut self, size: impl Into<Param>, diffusion: impl Into<Param>) -> Self {
+        self.size = size.into();
+        self.diffusion = diffusion.into();
+        self
+    }
+
+    /// Adds a freeze control that holds the current tail indefinitely.
+    ///
+    /// Works like [`Delay::with_freeze`](crate::Delay::with_freeze): while
+    /// frozen the input is muted and the network neither decays nor damps.
+    pub fn with_freeze(mut self, freeze: impl Into<Param>) -> Self {
+        self.freeze = freeze.into();
+        self
+    }
+
+    /// Creates a concert hall reverb (long, smooth tail).
+    ///
+    /// Settings: 3.5s decay, damping 0.3, mix 0.35, wide diffusion
+    pub fn hall(source: S) -> Self {
+        Self::new(source, 3.5, 0.3, 0.35).with_diffusion(1.5, 0.75)
+    }
+
+    /// Creates a small room reverb (short, darker tail).
+    ///
+    /// Settings: 0.6s decay, damping 0.5, mix 0.25
+    pub fn room(source: S) -> Self {
+        Self::new(source, 0.6, 0.5, 0.25)
+    }
+
+    /// Recomputes per-line feedback gains when the decay time changes.
+    fn update_gains(&mut self, decay_time: f64) {
+        if decay_time == self.cached_decay {
+            return;
+        }
+        self.cached_decay = decay_time;
+        for (gain, time) in self.line_gains.iter_mut().zip(LINE_TIMES) {
+            // Each pass through a line must lose time / decay_time of 60dB
+            *gain = 10.0_f64.powf(-3.0 * time / decay_time);
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for Reverb<SAMPLE_RATE, S> {
+    fn next_sample(&mut self) -> f64 {
+        let input = self.source.next_sample();
+
+        // Get current parameter values
+        let decay_time = self.decay_time.value().max(0.01);
+        let damping = self.damping.value().clamp(0.0, 1.0);
+        let mix = self.mix.value().clamp(0.0, 1.0);
+        let frozen = self.freeze_amount.next(self.freeze.value().clamp(0.0, 1.0));
+        self.update_gains(decay_time);
+
+        // Freezing mutes the input, disables decay and damping, and moves the
+        // dry share of the output onto the tail
+        let input_gain = 1.0 - frozen;
+        let damping = damping * (1.0 - frozen) * 0.95;
+        let wet = mix + (1.0 - mix) * frozen;
+
+        let size = self.size.value();
+        let diffusion = self.diffusion.value();
+        let diffused = self.diffuser.process(input * input_gain, size, diffusion);
+
+        // Read and damp each line
+        let mut outputs = [0.0; 4];
+        for (i, line) in self.lines.iter().enumerate() {
+            let delayed = line.read(line.max_delay());
+            let damped = damp(&mut self.damping_state[i], delayed, damping);
+            let gain = self.line_gains[i] + (1.0 - self.line_gains[i]) * frozen;
+            outputs[i] = damped * gain;
+        }
+
+        // Householder feedback matrix: lossless mixing between all lines
+        let sum: f64 = outputs.iter().sum();
+        for (line, &output) in self.lines.iter_mut().zip(outputs.iter()) {
+            line.write(diffused + output - sum * 0.5);
+        }
+
+        let reverb = self.damping_state.iter().sum::<f64>() * 0.25;
+        let output = input * (1.0 - mix) * input_gain + reverb * wet;
+        debug_assert_finite("Reverb", input, output);
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.decay_time.prepare(max_block_size, sample_rate);
+        self.damping.prepare(max_block_size, sample_rate);
+        self.mix.prepare(max_block_size, sample_rate);
+        self.size.prepare(max_block_size, sample_rate);
+        self.diffusion.prepare(max_block_size, sample_rate);
+        self.freeze.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE>
+    for Reverb<SAMPLE_RATE, S>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstantSignal, SignalExt};
+
+    fn impulse() -> impl AudioSignal<44100> {
+        let mut fired = false;
+        ConstantSignal::<44100>(0.0).map(move |_| {
+            let value = if fired { 0.0 } else { 1.0 };
+            fired = true;
+            value
+        })
+    }
+
+    /// RMS level of each consecutive `window`-sample block.
+    fn rms_blocks<S: Signal>(signal: &mut S, seconds: f64, window: usize) -> Vec<f64> {
+        let samples: Vec<f64> = signal.iter().take((seconds * 44100.0) as usize).collect();
+        samples
+            .chunks(window)
+            .map(|block| (block.iter().map(|s| s * s).sum::<f64>() / block.len() as f64).sqrt())
+            .collect()
+    }
+
+    #[test]
+    fn test_tail_decays_at_rt60() {
+        let mut reverb = Reverb::new(impulse(), 1.0, 0.0, 1.0);
+        let levels = rms_blocks(&mut reverb, 2.5, 4410);
+        // 60dB per second: 1s apart should differ by roughly 60dB
+        let drop_db = 20.0 * (levels[5] / levels[15]).log10();
+        assert!((drop_db - 60.0).abs() < 10.0, "Dropped {}dB", drop_db);
+    }
+
+    #[test]
+    fn test_freeze_holds_tail() {
+        let mut n = 0;
+        let freeze = ConstantSignal::<44100>(0.0).map(move |_| {
+            n += 1;
+            if n <= 2205 { 0.0 } else { 1.0 }
+        });
+        let mut reverb = Reverb::new(impulse(), 0.5, 0.5, 1.0).with_freeze(freeze);
+        let levels = rms_blocks(&mut reverb, 3.0, 4410);
+        assert!(levels[2] > 1e-4);
+        assert!((levels[29] / levels[2] - 1.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_presets() {
+        let mut hall = Reverb::hall(impulse());
+        let mut room = Reverb::room(impulse());
+        let hall_levels = rms_blocks(&mut hall, 1.5, 4410);
+        let room_levels = rms_blocks(&mut room, 1.5, 4410);
+        // The hall rings on long after the room has died away
+        assert!(hall_levels[10] > room_levels[10] * 100.0);
+    }
+}
@@ -0,0 +1,169 @@
+//! Spring reverb modelled with dispersive allpass chains.
+
+use super::delay_line::{Allpass, DelayLine};
+use super::reverb::damp;
+use crate::core::{AudioSignal, Param, Signal, debug_assert_finite};
+
+/// Round-trip times in seconds of the two springs (slightly detuned).
+const SPRING_TIMES: [f64; 2] = [0.0412, 0.0533];
+
+/// Number of allpass stages per spring; more stages give a longer chirp.
+const DISPERSION_STAGES: usize = 48;
+
+/// Delay of each dispersion stage in samples (stretches the chirp downwards).
+const STAGE_DELAY: usize = 2;
+
+/// Allpass coefficient of each dispersion stage.
+const DISPERSION: f64 = 0.6;
+
+/// Fixed damping that gives springs their band-limited, dark sound.
+const SPRING_DAMPING: f64 = 0.55;
+
+/// One spring: a dispersive allpass chain inside a feedback delay.
+struct Spring {
+    chain: Vec<Allpass>,
+    line: DelayLine,
+    time: f64,
+    damping_state: f64,
+}
+
+impl Spring {
+    fn new(time: f64, sample_rate: u32) -> Self {
+        Self {
+            chain: (0..DISPERSION_STAGES)
+                .map(|_| Allpass::new(STAGE_DELAY))
+                .collect(),
+            line: DelayLine::with_max_time(time, sample_rate),
+            time,
+            damping_state: 0.0,
+        }
+    }
+
+    fn process(&mut self, input: f64, gain: f64, sample_rate: f64) -> f64 {
+        let returned = self.line.read(self.time * sample_rate);
+        let mut wave = input + returned * gain;
+        for stage in self.chain.iter_mut() {
+            wave = stage.process(wave, STAGE_DELAY as f64, DISPERSION);
+        }
+        let wave = damp(&mut self.damping_state, wave, SPRING_DAMPING);
+        self.line.write(wave);
+        returned
+    }
+}
+
+/// Spring reverb.
+///
+/// Imitates the tank found in guitar amps and dub mixing desks. Each spring is
+/// a long chain of allpass filters inside a feedback delay; the chain delays
+/// frequencies by different amounts (dispersion), so every echo arrives as a
+/// descending chirp, the characteristic "boing". Two slightly different
+/// springs are averaged, as in a real tank.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{SineOscillator, SpringReverb};
+///
+/// let audio = SineOscillator::<44100>::new(220.0);
+/// // 2 second tail, 40% wet
+/// let mut spring = SpringReverb::new(audio, 2.0, 0.4);
+/// ```
+pub struct SpringReverb<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
+    source: S,
+    springs: [Spring; 2],
+
+    // Parameters
+    decay_time: Param, // RT60 in seconds
+    mix: Param,        // dry/wet mix, 0.0 = all dry, 1.0 = all wet
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> SpringReverb<SAMPLE_RATE, S> {
+    /// Creates a new spring reverb.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Input signal
+    /// * `decay_time` - Time in seconds for the tail to fall by 60dB
+    /// * `mix` - Dry/wet mix (0.0 = all dry/original, 1.0 = all wet/reverb)
+    pub fn new(source: S, decay_time: impl Into<Param>, mix: impl Into<Param>) -> Self {
+        Self {
+            source,
+            springs: SPRING_TIMES.map(|time| Spring::new(time, SAMPLE_RATE)),
+            decay_time: decay_time.into(),
+            mix: mix.into(),
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for SpringReverb<SAMPLE_RATE, S> {
+    fn next_sample(&mut self) -> f64 {
+        let input = self.source.next_sample();
+
+        // Get current parameter values
+        let decay_time = self.decay_time.value().max(0.01);
+        let mix = self.mix.value().clamp(0.0, 1.0);
+
+        let rate = SAMPLE_RATE as f64;
+        let reverb = self
+            .springs
+            .iter_mut()
+            .map(|spring| {
+                let gain = 10.0_f64.powf(-3.0 * spring.time / decay_time);
+                spring.process(input, gain, rate)
+            })
+            .sum::<f64>()
+            * 0.5;
+
+        let output = input * (1.0 - mix) + reverb * mix;
+        debug_assert_finite("SpringReverb", input, output);
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.decay_time.prepare(max_block_size, sample_rate);
+        self.mix.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE>
+    for SpringReverb<SAMPLE_RATE, S>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstantSignal, SignalExt};
+
+    fn impulse() -> impl AudioSignal<44100> {
+        let mut fired = false;
+        ConstantSignal::<44100>(0.0).map(move |_| {
+            let value = if fired { 0.0 } else { 1.0 };
+            fired = true;
+            value
+        })
+    }
+
+    #[test]
+    fn test_echo_is_dispersed() {
+        let mut spring = SpringReverb::new(impulse(), 2.0, 1.0);
+        let output: Vec<f64> = spring.iter().take(4000).collect();
+        // The first echo is smeared into a chirp rather than a single click
+        let peak = output.iter().fold(0.0_f64, |max, s| max.max(s.abs()));
+        let spread = output.iter().filter(|s| s.abs() > peak * 0.05).count();
+        assert!(peak > 0.0);
+        assert!(peak < 0.5, "Peak {} looks like an undispersed click", peak);
+        assert!(spread > 40, "Only {} significant samples", spread);
+    }
+
+    #[test]
+    fn test_tail_decays() {
+        let mut spring = SpringReverb::new(impulse(), 0.5, 1.0);
+        let output: Vec<f64> = spring.iter().take(88200).collect();
+        let early: f64 = output[..4410].iter().map(|s| s * s).sum();
+        let late: f64 = output[44100..].iter().map(|s| s * s).sum();
+        assert!(early > 0.0);
+        assert!(late < early * 1e-6);
+    }
+}
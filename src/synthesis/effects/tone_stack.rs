@@ -0,0 +1,147 @@
+//! Bass/mid/treble tone controls modelled on a passive guitar amp tone stack.
+
+use crate::core::{AudioSignal, Param, Signal, debug_assert_finite};
+use crate::synthesis::filters::{Biquad, peaking_coefficients, shelf_coefficients};
+
+/// Corner frequencies of the three bands in Hz.
+const BASS_FREQ: f64 = 120.0;
+const MID_FREQ: f64 = 650.0;
+const TREBLE_FREQ: f64 = 2500.0;
+
+/// Boost or cut in dB at the ends of each knob's travel.
+const RANGE_DB: f64 = 12.0;
+
+/// Mid cut in dB with all knobs at noon, as in a passive stack.
+const PASSIVE_SCOOP_DB: f64 = 6.0;
+
+/// Three-band tone stack with amp-style knobs.
+///
+/// Each knob runs from 0.0 to 1.0 and moves its band by ±12dB. Like the
+/// passive stacks in classic guitar amps, the midrange is scooped a little
+/// even with every knob at noon (0.5); turn the mid knob up to about 0.75 for a
+/// flat response.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{SawtoothOscillator, ToneStack};
+///
+/// let guitar = SawtoothOscillator::<44100>::new(110.0);
+/// // Bass up a touch, mids scooped, treble bright
+/// let mut tone = ToneStack::new(guitar, 0.6, 0.3, 0.7);
+/// ```
+pub struct ToneStack<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
+    source: S,
+    filters: [Biquad; 3],  // bass shelf, mid peak, treble shelf
+    knob_values: [f64; 3], // knob positions the filters were designed for
+
+    // Parameters
+    bass: Param,   // 0.0 to 1.0
+    mid: Param,    // 0.0 to 1.0
+    treble: Param, // 0.0 to 1.0
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> ToneStack<SAMPLE_RATE, S> {
+    /// Creates a new tone stack.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Input signal
+    /// * `bass` - Low shelf knob around 120Hz (0.0-1.0)
+    /// * `mid` - Mid peak knob around 650Hz (0.0-1.0)
+    /// * `treble` - High shelf knob around 2.5kHz (0.0-1.0)
+    pub fn new(
+        source: S,
+        bass: impl Into<Param>,
+        mid: impl Into<Param>,
+        treble: impl Into<Param>,
+    ) -> Self {
+        let mut stack = Self {
+            source,
+            filters: std::array::from_fn(|_| Biquad::new([1.0, 0.0, 0.0, 0.0, 0.0])),
+            knob_values: [f64::NAN; 3],
+            bass: bass.into(),
+            mid: mid.into(),
+            treble: treble.into(),
+        };
+        let knobs = [stack.bass.value(), stack.mid.value(), stack.treble.value()];
+        stack.update_filters(knobs);
+        stack
+    }
+
+    /// Redesigns the band filters whose knob moved.
+    fn update_filters(&mut self, knobs: [f64; 3]) {
+        for (band, &knob) in knobs.iter().enumerate() {
+            let knob = knob.clamp(0.0, 1.0);
+            if knob == self.knob_values[band] {
+                continue;
+            }
+            self.knob_values[band] = knob;
+
+            let gain_db = (knob - 0.5) * 2.0 * RANGE_DB;
+            self.filters[band].set_coefficients(match band {
+                0 => shelf_coefficients(BASS_FREQ, gain_db, false, SAMPLE_RATE),
+                1 => peaking_coefficients(MID_FREQ, 0.7, gain_db - PASSIVE_SCOOP_DB, SAMPLE_RATE),
+                _ => shelf_coefficients(TREBLE_FREQ, gain_db, true, SAMPLE_RATE),
+            });
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for ToneStack<SAMPLE_RATE, S> {
+    fn next_sample(&mut self) -> f64 {
+        let input = self.source.next_sample();
+        let knobs = [self.bass.value(), self.mid.value(), self.treble.value()];
+        self.update_filters(knobs);
+
+        let output = self
+            .filters
+            .iter_mut()
+            .fold(input, |sample, filter| filter.process(sample));
+        debug_assert_finite("ToneStack", input, output);
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.bass.prepare(max_block_size, sample_rate);
+        self.mid.prepare(max_block_size, sample_rate);
+        self.treble.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE>
+    for ToneStack<SAMPLE_RATE, S>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SineOscillator;
+
+    /// Steady-state gain in dB of a sine at `freq` through the stack.
+    fn response_db(freq: f64, bass: f64, mid: f64, treble: f64) -> f64 {
+        let mut stack = ToneStack::new(SineOscillator::<44100>::new(freq), bass, mid, treble);
+        let output: Vec<f64> = stack.iter().take(22050).collect();
+        let tail = &output[11025..];
+        let rms = (tail.iter().map(|s| s * s).sum::<f64>() / tail.len() as f64).sqrt();
+        20.0 * (rms * 2.0_f64.sqrt()).log10()
+    }
+
+    #[test]
+    fn test_noon_scoops_mids() {
+        let mid = response_db(650.0, 0.5, 0.5, 0.5);
+        assert!((mid + PASSIVE_SCOOP_DB).abs() < 1.5, "Mid {}dB", mid);
+        assert!(response_db(40.0, 0.5, 0.5, 0.5).abs() < 1.0);
+        assert!(response_db(10000.0, 0.5, 0.5, 0.5).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_knobs_move_their_bands() {
+        assert!(response_db(50.0, 1.0, 0.5, 0.5) > 9.0);
+        assert!(response_db(50.0, 0.0, 0.5, 0.5) < -9.0);
+        assert!(response_db(8000.0, 0.5, 0.5, 1.0) > 9.0);
+        assert!(response_db(8000.0, 0.5, 0.5, 0.0) < -9.0);
+    }
+}
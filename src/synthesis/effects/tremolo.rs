@@ -1,6 +1,25 @@
 //! Tremolo effect (amplitude modulation).
 
-use crate::core::{AudioSignal, Param, Signal};
+use crate::core::{AudioSignal, Param, Signal, debug_assert_finite};
+
+/// Converts a modulator value in `[-1, 1]` to a gain multiplier.
+pub(crate) fn tremolo_gain(mod_value: f64, depth: f64) -> f64 {
+    // Convert modulator from [-1, 1] to a gain multiplier
+    // depth=0: gain always 1.0 (no effect)
+    // depth=1: gain varies from 0.0 to 1.0 (full tremolo)
+    // Formula: gain = 1.0 - depth * (1.0 - (mod_value + 1.0) / 2.0)
+    //        = 1.0 - depth * (1.0 - 0.5 - mod_value/2.0)
+    //        = 1.0 - depth * (0.5 - mod_value/2.0)
+    //        = 1.0 - depth/2.0 + depth*mod_value/2.0
+    //        = 1.0 - depth/2.0 * (1.0 - mod_value)
+    // Actually, let's use a simpler formula:
+    // gain = 1.0 - depth/2.0 + depth/2.0 * mod_value
+    //      = 1.0 + depth/2.0 * (mod_value - 1.0)
+    // When mod_value = 1: gain = 1.0
+    // When mod_value = -1: gain = 1.0 - depth
+    // When mod_value = 0: gain = 1.0 - depth/2.0
+    1.0 + depth / 2.0 * (mod_value - 1.0)
+}
 
 /// Tremolo effect that modulates the amplitude of an audio signal.
 ///
@@ -78,6 +97,54 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Tremolo<SAMPLE_RATE, S
         let lfo = crate::synthesis::oscillators::SineOscillator::<SAMPLE_RATE>::new(rate);
         Self::new(source, lfo, depth)
     }
+
+    /// Creates a gentle, slow tremolo.
+    ///
+    /// Settings: 3 Hz, depth 0.3
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{SineOscillator, Tremolo};
+    ///
+    /// let audio = SineOscillator::<44100>::new(440.0);
+    /// let mut tremolo = Tremolo::gentle(audio);
+    /// ```
+    pub fn gentle(source: S) -> Self {
+        Self::with_rate(source, 3.0, 0.3)
+    }
+
+    /// Creates a classic amp-style tremolo.
+    ///
+    /// Settings: 5.5 Hz, depth 0.6
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{SineOscillator, Tremolo};
+    ///
+    /// let audio = SineOscillator::<44100>::new(440.0);
+    /// let mut tremolo = Tremolo::vintage(audio);
+    /// ```
+    pub fn vintage(source: S) -> Self {
+        Self::with_rate(source, 5.5, 0.6)
+    }
+
+    /// Creates a fast, choppy tremolo that fully cuts the signal each cycle.
+    ///
+    /// Settings: 10 Hz, depth 1.0
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{SineOscillator, Tremolo};
+    ///
+    /// let audio = SineOscillator::<44100>::new(440.0);
+    /// let mut tremolo = Tremolo::choppy(audio);
+    /// ```
+    pub fn choppy(source: S) -> Self {
+        Self::with_rate(source, 10.0, 1.0)
+    }
 }
 
 impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for Tremolo<SAMPLE_RATE, S> {
@@ -88,23 +155,17 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for Tremolo<SAM
         // Get modulator value (expected in range [-1, 1])
         let mod_value = self.modulator.value();
 
-        // Convert modulator from [-1, 1] to a gain multiplier
-        // depth=0: gain always 1.0 (no effect)
-        // depth=1: gain varies from 0.0 to 1.0 (full tremolo)
-        // Formula: gain = 1.0 - depth * (1.0 - (mod_value + 1.0) / 2.0)
-        //        = 1.0 - depth * (1.0 - 0.5 - mod_value/2.0)
-        //        = 1.0 - depth * (0.5 - mod_value/2.0)
-        //        = 1.0 - depth/2.0 + depth*mod_value/2.0
-        //        = 1.0 - depth/2.0 * (1.0 - mod_value)
-        // Actually, let's use a simpler formula:
-        // gain = 1.0 - depth/2.0 + depth/2.0 * mod_value
-        //      = 1.0 + depth/2.0 * (mod_value - 1.0)
-        // When mod_value = 1: gain = 1.0
-        // When mod_value = -1: gain = 1.0 - depth
-        // When mod_value = 0: gain = 1.0 - depth/2.0
-        let gain = 1.0 + depth / 2.0 * (mod_value - 1.0);
-
-        input * gain
+        let gain = tremolo_gain(mod_value, depth);
+
+        let output = input * gain;
+        debug_assert_finite("Tremolo", input, output);
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.modulator.prepare(max_block_size, sample_rate);
+        self.depth.prepare(max_block_size, sample_rate);
     }
 }
 
@@ -112,3 +173,32 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RAT
     for Tremolo<SAMPLE_RATE, S>
 {
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConstantSignal;
+
+    /// Returns the (min, max) gain a preset applies over one second.
+    fn gain_range(
+        preset: fn(ConstantSignal<44100>) -> Tremolo<44100, ConstantSignal<44100>>,
+    ) -> (f64, f64) {
+        let mut tremolo = preset(ConstantSignal::<44100>(1.0));
+        (0..44100)
+            .map(|_| tremolo.next_sample())
+            .fold((f64::MAX, f64::MIN), |(lo, hi), v| (lo.min(v), hi.max(v)))
+    }
+
+    #[test]
+    fn test_presets_depth() {
+        for (preset, depth) in [
+            (Tremolo::gentle as fn(_) -> _, 0.3),
+            (Tremolo::vintage, 0.6),
+            (Tremolo::choppy, 1.0),
+        ] {
+            let (lo, hi) = gain_range(preset);
+            assert!((hi - 1.0).abs() < 1e-3, "max gain {}", hi);
+            assert!((lo - (1.0 - depth)).abs() < 1e-3, "min gain {}", lo);
+        }
+    }
+}
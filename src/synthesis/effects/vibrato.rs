@@ -1,6 +1,6 @@
 //! Vibrato effect using pitch modulation.
 
-use crate::core::{AudioSignal, Param, Signal};
+use crate::core::{AudioSignal, Param, Signal, debug_assert_finite};
 
 /// Vibrato effect that creates pitch modulation.
 ///
@@ -169,8 +169,15 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for Vibrato<SAM
         // Advance write position
         self.write_pos = (self.write_pos + 1) % self.delay_buffer.len();
 
+        debug_assert_finite("Vibrato", input, output);
         output
     }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.rate.prepare(max_block_size, sample_rate);
+        self.depth.prepare(max_block_size, sample_rate);
+    }
 }
 
 impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE>
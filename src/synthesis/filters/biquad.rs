@@ -1,14 +1,15 @@
 //! Biquad filter implementations.
 //!
 //! This module provides a versatile biquad filter that can operate in various
-//! modes (low-pass, high-pass, band-pass, notch, all-pass) using the standard
+//! modes (low-pass, high-pass, band-pass, notch, all-pass, peaking and
+//! shelving EQ) using the standard
 //! biquad difference equation. The implementation uses Robert Bristow-Johnson's
 //! Audio EQ Cookbook formulas for coefficient calculation.
 
-use crate::core::{AudioSignal, Param, Signal};
+use crate::core::{AudioSignal, Param, Signal, debug_assert_finite};
 
 /// The type of filter to apply.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum FilterType {
     /// Low-pass filter - attenuates frequencies above the cutoff
     LowPass,
@@ -20,6 +21,24 @@ pub enum FilterType {
     Notch,
     /// All-pass filter - passes all frequencies but shifts phase
     AllPass,
+    /// Peaking EQ - boosts (positive `gain_db`) or cuts a band around the
+    /// center frequency; Q sets the band's width
+    Peaking {
+        /// Boost or cut at the center frequency in dB
+        gain_db: f64,
+    },
+    /// Low shelf - boosts or cuts everything below the corner frequency
+    /// (Q is ignored)
+    LowShelf {
+        /// Boost or cut of the shelf in dB
+        gain_db: f64,
+    },
+    /// High shelf - boosts or cuts everything above the corner frequency
+    /// (Q is ignored)
+    HighShelf {
+        /// Boost or cut of the shelf in dB
+        gain_db: f64,
+    },
 }
 
 /// A biquad filter that processes an input signal.
@@ -60,6 +79,12 @@ pub struct BiquadFilter<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
 
     // Optimization: only update coefficients if at least one param is modulated
     needs_coefficient_update: bool,
+
+    // Coefficient update throttling for modulated params
+    update_interval: usize,      // samples between coefficient recalculations
+    samples_until_update: usize, // countdown to the next recalculation
+    interpolate: bool,           // ramp coefficients between recalculations
+    coefficient_steps: [f64; 5], // per-sample increments for b0, b1, b2, a1, a2
 }
 
 impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> BiquadFilter<SAMPLE_RATE, S> {
@@ -90,94 +115,102 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> BiquadFilter<SAMPLE_RA
             a1: 0.0,
             a2: 0.0,
             needs_coefficient_update,
+            update_interval: 1,
+            samples_until_update: 0,
+            interpolate: false,
+            coefficient_steps: [0.0; 5],
         };
 
         // Calculate initial coefficients
-        filter.update_coefficients();
+        let freq = filter.cutoff.value();
+        let q = filter.resonance.value();
+        filter.set_coefficients(filter.calculate_coefficients(freq, q));
         filter
     }
 
-    /// Updates the filter coefficients based on current parameters.
+    /// Recalculates coefficients only every `interval` samples when parameters
+    /// are modulated.
     ///
-    /// Uses Robert Bristow-Johnson's Audio EQ Cookbook formulas.
-    fn update_coefficients(&mut self) {
-        use std::f64::consts::PI;
-
-        let freq = self.cutoff.value();
-        let q = self.resonance.value().max(0.001); // Prevent division by zero
-
-        // Clamp frequency to valid range (avoid nyquist issues)
-        let sample_rate = SAMPLE_RATE as f64;
-        let freq = freq.clamp(1.0, sample_rate * 0.49);
-
-        // Common calculations
-        let omega = 2.0 * PI * freq / sample_rate;
-        let sin_omega = omega.sin();
-        let cos_omega = omega.cos();
-        let alpha = sin_omega / (2.0 * q);
-
-        // Calculate coefficients based on filter type
-        let (mut b0, mut b1, mut b2, a0, mut a1, mut a2) = match self.filter_type {
-            FilterType::LowPass => {
-                let b0 = (1.0 - cos_omega) / 2.0;
-                let b1 = 1.0 - cos_omega;
-                let b2 = (1.0 - cos_omega) / 2.0;
-                let a0 = 1.0 + alpha;
-                let a1 = -2.0 * cos_omega;
-                let a2 = 1.0 - alpha;
-                (b0, b1, b2, a0, a1, a2)
-            }
+    /// Coefficient calculation is trig-heavy, and slow modulation (e.g. an LFO
+    /// sweep) rarely needs it every sample. Modulation sources are still pulled
+    /// every sample so they keep running at their normal speed; only the
+    /// coefficient update is throttled. Has no effect when all parameters are
+    /// fixed. Values below 1 are clamped to 1 (update every sample, the default).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{SignalExt, SineOscillator, synthesis::filters::BiquadFilter};
+    ///
+    /// let osc = SineOscillator::<44100>::new(440.0);
+    /// let lfo = SineOscillator::<44100>::new(0.5).gain(400.0).offset(1000.0);
+    /// let mut filter = BiquadFilter::lowpass(osc, lfo, 0.707).with_update_interval(32);
+    /// ```
+    pub fn with_update_interval(mut self, interval: usize) -> Self {
+        self.update_interval = interval.max(1);
+        self.samples_until_update = 0;
+        self
+    }
 
-            FilterType::HighPass => {
-                let b0 = (1.0 + cos_omega) / 2.0;
-                let b1 = -(1.0 + cos_omega);
-                let b2 = (1.0 + cos_omega) / 2.0;
-                let a0 = 1.0 + alpha;
-                let a1 = -2.0 * cos_omega;
-                let a2 = 1.0 - alpha;
-                (b0, b1, b2, a0, a1, a2)
-            }
+    /// Enables or disables coefficient interpolation between throttled updates.
+    ///
+    /// When enabled, each recalculation ramps the coefficients linearly toward
+    /// their new values over the next update interval instead of jumping,
+    /// which avoids zipper noise on fast sweeps with long intervals.
+    pub fn with_coefficient_interpolation(mut self, enabled: bool) -> Self {
+        self.interpolate = enabled;
+        self
+    }
 
-            FilterType::BandPass => {
-                // Constant 0 dB peak gain (constant skirt gain)
-                let b0 = alpha;
-                let b1 = 0.0;
-                let b2 = -alpha;
-                let a0 = 1.0 + alpha;
-                let a1 = -2.0 * cos_omega;
-                let a2 = 1.0 - alpha;
-                (b0, b1, b2, a0, a1, a2)
-            }
+    /// Returns the number of samples between coefficient recalculations.
+    pub fn update_interval(&self) -> usize {
+        self.update_interval
+    }
 
-            FilterType::Notch => {
-                let b0 = 1.0;
-                let b1 = -2.0 * cos_omega;
-                let b2 = 1.0;
-                let a0 = 1.0 + alpha;
-                let a1 = -2.0 * cos_omega;
-                let a2 = 1.0 - alpha;
-                (b0, b1, b2, a0, a1, a2)
+    /// Pulls the current parameter values and recalculates coefficients when due.
+    fn update_coefficients(&mut self) {
+        // Pull both params every sample so modulation sources advance in real time
+        let freq = self.cutoff.value();
+        let q = self.resonance.value();
+
+        if self.samples_until_update > 0 {
+            self.samples_until_update -= 1;
+            if self.interpolate {
+                self.b0 += self.coefficient_steps[0];
+                self.b1 += self.coefficient_steps[1];
+                self.b2 += self.coefficient_steps[2];
+                self.a1 += self.coefficient_steps[3];
+                self.a2 += self.coefficient_steps[4];
             }
-
-            FilterType::AllPass => {
-                let b0 = 1.0 - alpha;
-                let b1 = -2.0 * cos_omega;
-                let b2 = 1.0 + alpha;
-                let a0 = 1.0 + alpha;
-                let a1 = -2.0 * cos_omega;
-                let a2 = 1.0 - alpha;
-                (b0, b1, b2, a0, a1, a2)
+            return;
+        }
+        self.samples_until_update = self.update_interval - 1;
+
+        let target = self.calculate_coefficients(freq, q);
+        if self.interpolate && self.update_interval > 1 {
+            let current = [self.b0, self.b1, self.b2, self.a1, self.a2];
+            let steps = self.update_interval as f64;
+            for (step, (target, current)) in self
+                .coefficient_steps
+                .iter_mut()
+                .zip(target.iter().zip(current.iter()))
+            {
+                *step = (target - current) / steps;
             }
-        };
-
-        // Normalize by a0
-        b0 /= a0;
-        b1 /= a0;
-        b2 /= a0;
-        a1 /= a0;
-        a2 /= a0;
+            // Take the first step now; the rest happen over the interval
+            self.b0 += self.coefficient_steps[0];
+            self.b1 += self.coefficient_steps[1];
+            self.b2 += self.coefficient_steps[2];
+            self.a1 += self.coefficient_steps[3];
+            self.a2 += self.coefficient_steps[4];
+        } else {
+            self.set_coefficients(target);
+        }
+    }
 
-        // Store normalized coefficients
+    /// Stores normalized coefficients in `[b0, b1, b2, a1, a2]` order.
+    fn set_coefficients(&mut self, coefficients: [f64; 5]) {
+        let [b0, b1, b2, a1, a2] = coefficients;
         self.b0 = b0;
         self.b1 = b1;
         self.b2 = b2;
@@ -185,6 +218,11 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> BiquadFilter<SAMPLE_RA
         self.a2 = a2;
     }
 
+    /// Calculates normalized filter coefficients `[b0, b1, b2, a1, a2]`.
+    fn calculate_coefficients(&self, freq: f64, q: f64) -> [f64; 5] {
+        biquad_coefficients(self.filter_type, freq, q, SAMPLE_RATE)
+    }
+
     /// Creates a low-pass filter.
     ///
     /// # Arguments
@@ -245,6 +283,53 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> BiquadFilter<SAMPLE_RA
     pub fn allpass(source: S, frequency: impl Into<Param>, q: impl Into<Param>) -> Self {
         Self::new(source, frequency, q, FilterType::AllPass)
     }
+
+    /// Creates a peaking EQ filter.
+    ///
+    /// Boosts or cuts a band around the center frequency, leaving the rest
+    /// of the spectrum alone.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Input signal
+    /// * `center` - Center frequency in Hz
+    /// * `q` - Q factor (bandwidth), typically 0.5-10.0. Higher = narrower band.
+    /// * `gain_db` - Boost (positive) or cut (negative) in dB
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{SawtoothOscillator, synthesis::filters::BiquadFilter};
+    ///
+    /// let osc = SawtoothOscillator::<44100>::new(110.0);
+    /// // Notch out some boxiness at 400Hz
+    /// let mut filter = BiquadFilter::peaking(osc, 400.0, 2.0, -6.0);
+    /// ```
+    pub fn peaking(source: S, center: impl Into<Param>, q: impl Into<Param>, gain_db: f64) -> Self {
+        Self::new(source, center, q, FilterType::Peaking { gain_db })
+    }
+
+    /// Creates a low shelf filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Input signal
+    /// * `corner` - Corner frequency in Hz
+    /// * `gain_db` - Boost (positive) or cut (negative) below the corner in dB
+    pub fn low_shelf(source: S, corner: impl Into<Param>, gain_db: f64) -> Self {
+        Self::new(source, corner, 0.707, FilterType::LowShelf { gain_db })
+    }
+
+    /// Creates a high shelf filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Input signal
+    /// * `corner` - Corner frequency in Hz
+    /// * `gain_db` - Boost (positive) or cut (negative) above the corner in dB
+    pub fn high_shelf(source: S, corner: impl Into<Param>, gain_db: f64) -> Self {
+        Self::new(source, corner, 0.707, FilterType::HighShelf { gain_db })
+    }
 }
 
 impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for BiquadFilter<SAMPLE_RATE, S> {
@@ -268,8 +353,15 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for BiquadFilte
         self.y2 = self.y1;
         self.y1 = y0;
 
+        debug_assert_finite("BiquadFilter", x0, y0);
         y0
     }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.cutoff.prepare(max_block_size, sample_rate);
+        self.resonance.prepare(max_block_size, sample_rate);
+    }
 }
 
 // Implement AudioSignal for BiquadFilter when the source is an AudioSignal
@@ -278,6 +370,203 @@ impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RAT
 {
 }
 
+/// Calculates normalized filter coefficients `[b0, b1, b2, a1, a2]`.
+///
+/// Uses Robert Bristow-Johnson's Audio EQ Cookbook formulas.
+pub(crate) fn biquad_coefficients(
+    filter_type: FilterType,
+    freq: f64,
+    q: f64,
+    sample_rate: u32,
+) -> [f64; 5] {
+    use std::f64::consts::PI;
+
+    let q = q.max(0.001); // Prevent division by zero
+
+    // Clamp frequency to valid range (avoid nyquist issues)
+    let sample_rate = sample_rate as f64;
+    let freq = freq.clamp(1.0, sample_rate * 0.49);
+
+    // Common calculations
+    let omega = 2.0 * PI * freq / sample_rate;
+    let sin_omega = omega.sin();
+    let cos_omega = omega.cos();
+    let alpha = sin_omega / (2.0 * q);
+
+    // Calculate coefficients based on filter type
+    let (mut b0, mut b1, mut b2, a0, mut a1, mut a2) = match filter_type {
+        FilterType::LowPass => {
+            let b0 = (1.0 - cos_omega) / 2.0;
+            let b1 = 1.0 - cos_omega;
+            let b2 = (1.0 - cos_omega) / 2.0;
+            let a0 = 1.0 + alpha;
+            let a1 = -2.0 * cos_omega;
+            let a2 = 1.0 - alpha;
+            (b0, b1, b2, a0, a1, a2)
+        }
+
+        FilterType::HighPass => {
+            let b0 = (1.0 + cos_omega) / 2.0;
+            let b1 = -(1.0 + cos_omega);
+            let b2 = (1.0 + cos_omega) / 2.0;
+            let a0 = 1.0 + alpha;
+            let a1 = -2.0 * cos_omega;
+            let a2 = 1.0 - alpha;
+            (b0, b1, b2, a0, a1, a2)
+        }
+
+        FilterType::BandPass => {
+            // Constant 0 dB peak gain (constant skirt gain)
+            let b0 = alpha;
+            let b1 = 0.0;
+            let b2 = -alpha;
+            let a0 = 1.0 + alpha;
+            let a1 = -2.0 * cos_omega;
+            let a2 = 1.0 - alpha;
+            (b0, b1, b2, a0, a1, a2)
+        }
+
+        FilterType::Notch => {
+            let b0 = 1.0;
+            let b1 = -2.0 * cos_omega;
+            let b2 = 1.0;
+            let a0 = 1.0 + alpha;
+            let a1 = -2.0 * cos_omega;
+            let a2 = 1.0 - alpha;
+            (b0, b1, b2, a0, a1, a2)
+        }
+
+        FilterType::AllPass => {
+            let b0 = 1.0 - alpha;
+            let b1 = -2.0 * cos_omega;
+            let b2 = 1.0 + alpha;
+            let a0 = 1.0 + alpha;
+            let a1 = -2.0 * cos_omega;
+            let a2 = 1.0 - alpha;
+            (b0, b1, b2, a0, a1, a2)
+        }
+
+        // EQ shapes have their own gain-dependent formulas
+        FilterType::Peaking { gain_db } => {
+            return peaking_coefficients(freq, q, gain_db, sample_rate as u32);
+        }
+        FilterType::LowShelf { gain_db } => {
+            return shelf_coefficients(freq, gain_db, false, sample_rate as u32);
+        }
+        FilterType::HighShelf { gain_db } => {
+            return shelf_coefficients(freq, gain_db, true, sample_rate as u32);
+        }
+    };
+
+    // Normalize by a0
+    b0 /= a0;
+    b1 /= a0;
+    b2 /= a0;
+    a1 /= a0;
+    a2 /= a0;
+
+    [b0, b1, b2, a1, a2]
+}
+
+/// Normalized peaking EQ coefficients `[b0, b1, b2, a1, a2]`.
+///
+/// Boosts (positive `gain_db`) or cuts a band around `freq`; `q` sets its width.
+pub(crate) fn peaking_coefficients(freq: f64, q: f64, gain_db: f64, sample_rate: u32) -> [f64; 5] {
+    use std::f64::consts::PI;
+
+    let q = q.max(0.001);
+    let sample_rate = sample_rate as f64;
+    let freq = freq.clamp(1.0, sample_rate * 0.49);
+    let amplitude = 10.0_f64.powf(gain_db / 40.0);
+    let omega = 2.0 * PI * freq / sample_rate;
+    let alpha = omega.sin() / (2.0 * q);
+    let cos_omega = omega.cos();
+
+    let a0 = 1.0 + alpha / amplitude;
+    [
+        (1.0 + alpha * amplitude) / a0,
+        -2.0 * cos_omega / a0,
+        (1.0 - alpha * amplitude) / a0,
+        -2.0 * cos_omega / a0,
+        (1.0 - alpha / amplitude) / a0,
+    ]
+}
+
+/// Normalized shelving EQ coefficients `[b0, b1, b2, a1, a2]` (shelf slope 1).
+///
+/// A low shelf boosts or cuts everything below `freq` by `gain_db`; a high
+/// shelf everything above it.
+pub(crate) fn shelf_coefficients(
+    freq: f64,
+    gain_db: f64,
+    high: bool,
+    sample_rate: u32,
+) -> [f64; 5] {
+    use std::f64::consts::PI;
+
+    let sample_rate = sample_rate as f64;
+    let freq = freq.clamp(1.0, sample_rate * 0.49);
+    let amplitude = 10.0_f64.powf(gain_db / 40.0);
+    let omega = 2.0 * PI * freq / sample_rate;
+    let cos_omega = omega.cos();
+    let alpha = omega.sin() / 2.0 * 2.0_f64.sqrt();
+    let root = 2.0 * amplitude.sqrt() * alpha;
+
+    // High shelves mirror the low shelf formulas by flipping the sign of cos(w)
+    let sign = if high { -1.0 } else { 1.0 };
+    let (ap1, am1) = (amplitude + 1.0, amplitude - 1.0);
+    let b0 = amplitude * (ap1 - sign * am1 * cos_omega + root);
+    let b1 = 2.0 * sign * amplitude * (am1 - sign * ap1 * cos_omega);
+    let b2 = amplitude * (ap1 - sign * am1 * cos_omega - root);
+    let a0 = ap1 + sign * am1 * cos_omega + root;
+    let a1 = -2.0 * sign * (am1 + sign * ap1 * cos_omega);
+    let a2 = ap1 + sign * am1 * cos_omega - root;
+
+    [b0 / a0, b1 / a0, b2 / a0, a1 / a0, a2 / a0]
+}
+
+/// A biquad section with fixed coefficients and no input signal of its own.
+///
+/// Building block for effects that chain several filters internally (e.g.
+/// cabinet simulation) without wrapping each one in a [`BiquadFilter`].
+#[derive(Debug, Clone)]
+pub(crate) struct Biquad {
+    coefficients: [f64; 5],
+    x1: f64,
+    x2: f64,
+    y1: f64,
+    y2: f64,
+}
+
+impl Biquad {
+    /// Creates a section from normalized `[b0, b1, b2, a1, a2]` coefficients.
+    pub(crate) fn new(coefficients: [f64; 5]) -> Self {
+        Self {
+            coefficients,
+            x1: 0.0,
+            x2: 0.0,
+            y1: 0.0,
+            y2: 0.0,
+        }
+    }
+
+    /// Replaces the coefficients, keeping the filter state.
+    pub(crate) fn set_coefficients(&mut self, coefficients: [f64; 5]) {
+        self.coefficients = coefficients;
+    }
+
+    /// Filters one sample.
+    pub(crate) fn process(&mut self, x0: f64) -> f64 {
+        let [b0, b1, b2, a1, a2] = self.coefficients;
+        let y0 = b0 * x0 + b1 * self.x1 + b2 * self.x2 - a1 * self.y1 - a2 * self.y2;
+        self.x2 = self.x1;
+        self.x1 = x0;
+        self.y2 = self.y1;
+        self.y1 = y0;
+        y0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -469,6 +758,92 @@ mod tests {
         assert!(!filter.needs_coefficient_update);
     }
 
+    #[test]
+    fn test_update_interval_matches_per_sample_for_fixed_steps() {
+        // A stepped cutoff that only changes every 16 samples
+        fn stepped_cutoff() -> impl Signal + Send + 'static {
+            let mut n = 0;
+            ConstantSignal::<44100>(0.0).map(move |_| {
+                n += 1;
+                500.0 + ((n - 1) / 16) as f64 * 100.0
+            })
+        }
+        let (cutoff_a, cutoff_b) = (stepped_cutoff(), stepped_cutoff());
+
+        let mut every_sample =
+            BiquadFilter::lowpass(SineOscillator::<44100>::new(440.0), cutoff_a, 0.707);
+        let mut throttled =
+            BiquadFilter::lowpass(SineOscillator::<44100>::new(440.0), cutoff_b, 0.707)
+                .with_update_interval(16);
+        assert_eq!(throttled.update_interval(), 16);
+
+        // The throttled filter picks up each new cutoff up to one sample late,
+        // so the outputs differ only slightly
+        let mut max_diff: f64 = 0.0;
+        for _ in 0..1000 {
+            max_diff = max_diff.max((every_sample.next_sample() - throttled.next_sample()).abs());
+        }
+        assert!(max_diff < 0.05, "Throttled output diverged by {}", max_diff);
+    }
+
+    #[test]
+    fn test_update_interval_keeps_modulator_speed() {
+        // The modulator must still be pulled every sample
+        let pulls = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = pulls.clone();
+        let cutoff = ConstantSignal::<44100>(1000.0).map(move |x| {
+            counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            x
+        });
+        let mut filter = BiquadFilter::lowpass(ConstantSignal::<44100>(0.5), cutoff, 0.707)
+            .with_update_interval(64);
+
+        for _ in 0..256 {
+            filter.next_sample();
+        }
+        // One pull at construction plus one per sample
+        assert_eq!(pulls.load(std::sync::atomic::Ordering::Relaxed), 257);
+    }
+
+    #[test]
+    fn test_coefficient_interpolation_reaches_target() {
+        let cutoff = SineOscillator::<44100>::new(2.0).gain(800.0).offset(1200.0);
+        let mut filter = BiquadFilter::lowpass(SineOscillator::<44100>::new(440.0), cutoff, 2.0)
+            .with_update_interval(32)
+            .with_coefficient_interpolation(true);
+
+        for _ in 0..44100 {
+            let sample = filter.next_sample();
+            assert!(sample.is_finite() && sample.abs() < 10.0);
+        }
+
+        // Interpolated coefficients land on the exact target at the end of each interval
+        let mut fixed = BiquadFilter::lowpass(ConstantSignal::<44100>(0.0), 1000.0, 0.707)
+            .with_update_interval(8)
+            .with_coefficient_interpolation(true);
+        let target = fixed.calculate_coefficients(1000.0, 0.707);
+        for _ in 0..8 {
+            fixed.next_sample();
+        }
+        let current = [fixed.b0, fixed.b1, fixed.b2, fixed.a1, fixed.a2];
+        for (c, t) in current.iter().zip(target.iter()) {
+            assert!((c - t).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_block_processing_matches_per_sample() {
+        let mut per_sample = BiquadFilter::lowpass(SineOscillator::<44100>::new(440.0), 800.0, 2.0);
+        let mut block = BiquadFilter::lowpass(SineOscillator::<44100>::new(440.0), 800.0, 2.0);
+
+        let expected: Vec<f64> = (0..1000).map(|_| per_sample.next_sample()).collect();
+        let mut buffer = vec![0.0; 1000];
+        for chunk in buffer.chunks_mut(300) {
+            block.process(chunk);
+        }
+        assert_eq!(buffer, expected);
+    }
+
     #[test]
     fn test_notch_filter() {
         // Notch should attenuate the center frequency
@@ -549,4 +924,28 @@ mod tests {
             assert!(sample.is_finite());
         }
     }
+
+    #[test]
+    fn test_eq_filter_gains() {
+        // Level of a steady tone after the filter
+        fn level(filter: &mut impl Signal) -> f64 {
+            for _ in 0..4410 {
+                filter.next_sample();
+            }
+            (0..4410).fold(0.0_f64, |max, _| max.max(filter.next_sample().abs()))
+        }
+        let db = |gain: f64| 10.0_f64.powf(gain / 20.0);
+        let tone = SineOscillator::<44100>::new;
+
+        let mut peak = BiquadFilter::peaking(tone(1000.0), 1000.0, 1.0, 6.0);
+        assert!((level(&mut peak) - db(6.0)).abs() < 0.02);
+        let mut off_peak = BiquadFilter::peaking(tone(8000.0), 1000.0, 4.0, 6.0);
+        assert!((level(&mut off_peak) - 1.0).abs() < 0.05);
+
+        let mut low = BiquadFilter::low_shelf(tone(50.0), 500.0, -12.0);
+        assert!((level(&mut low) - db(-12.0)).abs() < 0.02);
+        let mut high = BiquadFilter::high_shelf(tone(50.0), 5000.0, 12.0);
+        assert!((level(&mut high) - 1.0).abs() < 0.02);
+        assert_eq!(high.filter_type, FilterType::HighShelf { gain_db: 12.0 });
+    }
 }
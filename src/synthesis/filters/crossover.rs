@@ -0,0 +1,187 @@
+//! Linkwitz-Riley band splitting.
+
+use super::biquad::{Biquad, FilterType, biquad_coefficients};
+use crate::core::{AudioFrameSignal, AudioSignal, FrameSignal};
+
+/// Butterworth Q; two cascaded sections give a 4th-order Linkwitz-Riley slope.
+const BUTTERWORTH_Q: f64 = std::f64::consts::FRAC_1_SQRT_2;
+
+/// Two-band Linkwitz-Riley (LR4) crossover.
+///
+/// Splits a signal into low and high bands with 24dB/octave slopes. The bands
+/// are in phase at the crossover frequency, so adding them back together gives
+/// a flat magnitude response.
+#[derive(Debug, Clone)]
+pub(crate) struct Crossover {
+    lows: [Biquad; 2],
+    highs: [Biquad; 2],
+}
+
+impl Crossover {
+    /// Creates a crossover at `frequency` Hz.
+    pub(crate) fn new(frequency: f64, sample_rate: u32) -> Self {
+        let section = |filter_type| {
+            Biquad::new(biquad_coefficients(
+                filter_type,
+                frequency,
+                BUTTERWORTH_Q,
+                sample_rate,
+            ))
+        };
+        Self {
+            lows: [section(FilterType::LowPass), section(FilterType::LowPass)],
+            highs: [section(FilterType::HighPass), section(FilterType::HighPass)],
+        }
+    }
+
+    /// Splits one sample into `(low, high)` bands.
+    pub(crate) fn split(&mut self, input: f64) -> (f64, f64) {
+        let low = self.lows.iter_mut().fold(input, |x, f| f.process(x));
+        let high = self.highs.iter_mut().fold(input, |x, f| f.process(x));
+        (low, high)
+    }
+}
+
+/// Three-band LR4 split: the [`Crossover`]s behind [`ThreeBandCrossover`]
+/// and [`MultibandCompressor`](crate::MultibandCompressor).
+#[derive(Debug, Clone)]
+pub(crate) struct BandSplitter {
+    low: Crossover,
+    high: Crossover,
+    // Gives the low band the high crossover's phase shift, so all three
+    // bands sum flat
+    low_allpass: Crossover,
+}
+
+impl BandSplitter {
+    /// Creates a splitter with bands meeting at `low_freq` and `high_freq`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `low_freq >= high_freq`.
+    pub(crate) fn new(low_freq: f64, high_freq: f64, sample_rate: u32) -> Self {
+        assert!(
+            low_freq < high_freq,
+            "low crossover ({} Hz) must be below the high crossover ({} Hz)",
+            low_freq,
+            high_freq
+        );
+        Self {
+            low: Crossover::new(low_freq, sample_rate),
+            high: Crossover::new(high_freq, sample_rate),
+            low_allpass: Crossover::new(high_freq, sample_rate),
+        }
+    }
+
+    /// Splits one sample into `[low, mid, high]` bands.
+    pub(crate) fn split(&mut self, input: f64) -> [f64; 3] {
+        let (low, rest) = self.low.split(input);
+        let (mid, high) = self.high.split(rest);
+        let (low_low, low_high) = self.low_allpass.split(low);
+        [low_low + low_high, mid, high]
+    }
+}
+
+/// Three-band Linkwitz-Riley (LR4) crossover.
+///
+/// Splits a signal into low, mid and high bands with 24dB/octave slopes,
+/// as a three-channel [`FrameSignal`] of `[low, mid, high]`. The bands sum
+/// back to a flat magnitude response, so each can be processed on its own
+/// (compressed, saturated, panned) and mixed back together.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{FrameSignal, SawtoothOscillator, ThreeBandCrossover};
+///
+/// let synth = SawtoothOscillator::<44100>::new(110.0);
+/// // Lows below 200Hz, highs above 3kHz
+/// let mut bands = ThreeBandCrossover::new(synth, 200.0, 3000.0);
+/// let [low, mid, high] = bands.next_frame();
+/// ```
+pub struct ThreeBandCrossover<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
+    source: S,
+    splitter: BandSplitter,
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> ThreeBandCrossover<SAMPLE_RATE, S> {
+    /// Creates a crossover splitting `source` at `low_freq` and `high_freq`
+    /// Hz.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `low_freq >= high_freq`.
+    pub fn new(source: S, low_freq: f64, high_freq: f64) -> Self {
+        Self {
+            source,
+            splitter: BandSplitter::new(low_freq, high_freq, SAMPLE_RATE),
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> FrameSignal<3>
+    for ThreeBandCrossover<SAMPLE_RATE, S>
+{
+    fn next_frame(&mut self) -> [f64; 3] {
+        self.splitter.split(self.source.next_sample())
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioFrameSignal<SAMPLE_RATE, 3>
+    for ThreeBandCrossover<SAMPLE_RATE, S>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bands_sum_flat() {
+        for freq in [100.0, 800.0, 5000.0] {
+            let mut crossover = Crossover::new(800.0, 44100);
+            let omega = std::f64::consts::TAU * freq / 44100.0;
+            let summed: Vec<f64> = (0..44100)
+                .map(|n| {
+                    let (low, high) = crossover.split((omega * n as f64).sin());
+                    low + high
+                })
+                .collect();
+            let peak = summed[22050..]
+                .iter()
+                .fold(0.0_f64, |max, s| max.max(s.abs()));
+            assert!((peak - 1.0).abs() < 0.01, "{} Hz peak {}", freq, peak);
+        }
+    }
+
+    #[test]
+    fn test_three_bands_sum_flat_and_separate() {
+        let peak = |samples: &[f64]| samples.iter().fold(0.0_f64, |max, s| max.max(s.abs()));
+        for freq in [60.0, 1000.0, 10000.0] {
+            let tone = crate::SineOscillator::<44100>::new(freq);
+            let mut bands = ThreeBandCrossover::new(tone, 200.0, 3000.0);
+            let frames: Vec<[f64; 3]> = (0..44100).map(|_| bands.next_frame()).collect();
+            let summed: Vec<f64> = frames[22050..].iter().map(|f| f.iter().sum()).collect();
+            assert!((peak(&summed) - 1.0).abs() < 0.01, "{} Hz", freq);
+
+            // Each tone lands mostly in its own band
+            let band = match freq {
+                60.0 => 0,
+                1000.0 => 1,
+                _ => 2,
+            };
+            let own: Vec<f64> = frames[22050..].iter().map(|f| f[band]).collect();
+            assert!(peak(&own) > 0.8, "{} Hz in band {}", freq, band);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "must be below")]
+    fn test_three_band_rejects_inverted_crossovers() {
+        ThreeBandCrossover::new(crate::SineOscillator::<44100>::new(440.0), 3000.0, 200.0);
+    }
+}
@@ -0,0 +1,270 @@
+//! Transistor-ladder-style 4-pole low-pass filter.
+//!
+//! Four one-pole low-pass stages in series with negative feedback from the
+//! last stage to the input, after the classic Moog design. Each stage uses
+//! the trapezoidal (topology-preserving) integrator, and the feedback loop is
+//! solved without a unit delay, so the resonant peak and the self-oscillation
+//! pitch land on the cutoff frequency across the whole audio range. A `tanh`
+//! stage at the loop input provides the saturation that keeps high
+//! resonance bounded and gives the filter its squelch.
+
+use crate::core::{AudioSignal, Param, Signal, debug_assert_finite};
+use std::f64::consts::PI;
+
+/// Feedback gain at full resonance; the loop self-oscillates above 4.0.
+const MAX_FEEDBACK: f64 = 4.5;
+
+/// A 4-pole (24 dB/octave) resonant low-pass filter modeled on the
+/// transistor ladder.
+///
+/// - **Resonance** runs from 0.0 to 1.0. It emphasizes the cutoff frequency
+///   and thins out the bass like the original circuit; from about 0.9
+///   upwards the filter self-oscillates, producing a sine near the cutoff
+///   even with no input. Half of the bass loss is made up with output gain.
+/// - **Drive** scales the signal into the saturating input stage: 1.0 is
+///   gentle, higher values add grit and compress resonant peaks.
+/// - **Key tracking** multiplies the cutoff by a [`Param`], typically a
+///   [`NoteSources::key_track`](crate::music::NoteSources::key_track)
+///   multiplier, so the cutoff follows the notes being played.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{LadderFilter, SawtoothOscillator, Signal, SignalExt};
+///
+/// const SAMPLE_RATE: u32 = 44100;
+///
+/// // Squelchy bass: cutoff swept by an LFO, resonance just below oscillation
+/// let osc = SawtoothOscillator::<SAMPLE_RATE>::new(55.0);
+/// let sweep = SawtoothOscillator::<SAMPLE_RATE>::new(2.0).gain(600.0).offset(900.0);
+/// let mut filter = LadderFilter::new(osc, sweep, 0.85).with_drive(2.0);
+/// let sample = filter.next_sample();
+/// ```
+///
+/// Cutoff following the keyboard:
+///
+#[cfg_attr(feature = "music", doc = "```")]
+#[cfg_attr(not(feature = "music"), doc = "```ignore")]
+/// use earworm::music::NoteSources;
+/// use earworm::{LadderFilter, SawtoothOscillator};
+///
+/// const SAMPLE_RATE: u32 = 44100;
+///
+/// let sources = NoteSources::new();
+/// let osc = SawtoothOscillator::<SAMPLE_RATE>::new(220.0);
+/// // 800 Hz at middle C, opening an octave per octave played
+/// let filter = LadderFilter::new(osc, 800.0, 0.6)
+///     .with_key_tracking(sources.key_track(60.0, 1.0));
+/// ```
+pub struct LadderFilter<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
+    source: S,
+    cutoff: Param,
+    resonance: Param,
+    key_tracking: Option<Param>,
+    drive: f64,
+    // Integrator state of the four stages
+    stages: [f64; 4],
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> LadderFilter<SAMPLE_RATE, S> {
+    /// Creates a ladder filter with a drive of 1.0 and no key tracking.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Input signal
+    /// * `cutoff` - Cutoff frequency in Hz (fixed or modulated)
+    /// * `resonance` - Resonance, 0.0 to 1.0 (fixed or modulated)
+    pub fn new(source: S, cutoff: impl Into<Param>, resonance: impl Into<Param>) -> Self {
+        Self {
+            source,
+            cutoff: cutoff.into(),
+            resonance: resonance.into(),
+            key_tracking: None,
+            drive: 1.0,
+            stages: [0.0; 4],
+        }
+    }
+
+    /// Sets the input drive (builder style).
+    ///
+    /// Values below 0.0 are clamped to 0.0.
+    pub fn with_drive(mut self, drive: f64) -> Self {
+        self.drive = drive.max(0.0);
+        self
+    }
+
+    /// Multiplies the cutoff by `tracking` every sample (builder style).
+    pub fn with_key_tracking(mut self, tracking: impl Into<Param>) -> Self {
+        self.key_tracking = Some(tracking.into());
+        self
+    }
+
+    /// Returns the input drive.
+    pub fn drive(&self) -> f64 {
+        self.drive
+    }
+
+    /// Clears the filter state, silencing any self-oscillation.
+    pub fn reset(&mut self) {
+        self.stages = [0.0; 4];
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for LadderFilter<SAMPLE_RATE, S> {
+    fn next_sample(&mut self) -> f64 {
+        let x = self.source.next_sample();
+        let mut cutoff = self.cutoff.value();
+        if let Some(tracking) = self.key_tracking.as_mut() {
+            cutoff *= tracking.value();
+        }
+        let feedback = self.resonance.value().clamp(0.0, 1.0) * MAX_FEEDBACK;
+
+        // Prewarped one-pole gain, kept below Nyquist
+        let nyquist = SAMPLE_RATE as f64 * 0.5;
+        let g = (PI * cutoff.clamp(1.0, nyquist * 0.98) / SAMPLE_RATE as f64).tan();
+        let gain = g / (1.0 + g);
+
+        // The output is gain^4 * u + contributions from the stored state, so
+        // the feedback loop can be solved for the ladder input u directly
+        let [s1, s2, s3, s4] = self.stages;
+        let held = (1.0 - gain) * (((s1 * gain + s2) * gain + s3) * gain + s4);
+        let gain4 = gain * gain * gain * gain;
+        let input = (self.drive * (x - feedback * held) / (1.0 + feedback * gain4)).tanh();
+
+        let mut y = input;
+        for stage in self.stages.iter_mut() {
+            let v = (y - *stage) * gain;
+            y = v + *stage;
+            *stage = y + v;
+        }
+
+        let output = y * (1.0 + 0.5 * feedback);
+        debug_assert_finite("LadderFilter", x, output);
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.cutoff.prepare(max_block_size, sample_rate);
+        self.resonance.prepare(max_block_size, sample_rate);
+        if let Some(tracking) = self.key_tracking.as_mut() {
+            tracking.prepare(max_block_size, sample_rate);
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE>
+    for LadderFilter<SAMPLE_RATE, S>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstantSignal, SignalExt, SineOscillator};
+
+    const SR: u32 = 48000;
+
+    // Single-sample impulse at the start of the signal
+    struct Impulse<const SAMPLE_RATE: u32> {
+        fired: bool,
+    }
+
+    impl<const SAMPLE_RATE: u32> Impulse<SAMPLE_RATE> {
+        fn new() -> Self {
+            Self { fired: false }
+        }
+    }
+
+    impl<const SAMPLE_RATE: u32> Signal for Impulse<SAMPLE_RATE> {
+        fn next_sample(&mut self) -> f64 {
+            if self.fired {
+                0.0
+            } else {
+                self.fired = true;
+                1.0
+            }
+        }
+    }
+
+    impl<const SAMPLE_RATE: u32> AudioSignal<SAMPLE_RATE> for Impulse<SAMPLE_RATE> {}
+
+    /// Peak level over the last `len` samples.
+    fn tail_peak(samples: &[f64], len: usize) -> f64 {
+        samples[samples.len() - len..]
+            .iter()
+            .fold(0.0, |peak, s| peak.max(s.abs()))
+    }
+
+    /// Frequency estimated from rising zero crossings over the last 0.1 s.
+    fn tail_frequency(samples: &[f64]) -> f64 {
+        let tail = &samples[samples.len() - SR as usize / 10..];
+        let crossings = tail
+            .windows(2)
+            .filter(|w| w[0] < 0.0 && w[1] >= 0.0)
+            .count();
+        crossings as f64 * 10.0
+    }
+
+    fn render(signal: &mut impl Signal, len: usize) -> Vec<f64> {
+        (0..len).map(|_| signal.next_sample()).collect()
+    }
+
+    #[test]
+    fn test_passes_lows_and_cuts_highs() {
+        let low = SineOscillator::<SR>::new(100.0).gain(0.1);
+        let mut filter = LadderFilter::new(low, 1000.0, 0.0);
+        let peak = tail_peak(&render(&mut filter, 9600), 4800);
+        assert!((peak - 0.1).abs() < 0.005, "passband peak {}", peak);
+
+        // Two octaves above: 24 dB/octave leaves well under 2%
+        let high = SineOscillator::<SR>::new(4000.0).gain(0.1);
+        let mut filter = LadderFilter::new(high, 1000.0, 0.0);
+        let peak = tail_peak(&render(&mut filter, 9600), 4800);
+        assert!(peak < 0.002, "stopband peak {}", peak);
+    }
+
+    #[test]
+    fn test_self_oscillates_at_cutoff() {
+        for cutoff in [220.0, 1000.0, 5000.0] {
+            // A single impulse starts the oscillation
+            let mut filter = LadderFilter::new(Impulse::<SR>::new(), cutoff, 1.0);
+            let output = render(&mut filter, SR as usize * 2);
+
+            let peak = tail_peak(&output, 4800);
+            assert!(peak > 0.2 && peak < 1.0, "{} Hz: peak {}", cutoff, peak);
+            let frequency = tail_frequency(&output);
+            assert!(
+                (frequency - cutoff).abs() < cutoff * 0.03,
+                "{} Hz: oscillates at {}",
+                cutoff,
+                frequency
+            );
+        }
+    }
+
+    #[test]
+    fn test_moderate_resonance_rings_out() {
+        let mut filter = LadderFilter::new(Impulse::<SR>::new(), 1000.0, 0.7);
+        let output = render(&mut filter, SR as usize);
+        assert!(tail_peak(&output, 4800) < 1e-6);
+    }
+
+    #[test]
+    fn test_key_tracking_scales_cutoff() {
+        let mut filter = LadderFilter::new(Impulse::<SR>::new(), 500.0, 1.0)
+            .with_key_tracking(ConstantSignal::<SR>(2.0));
+        let output = render(&mut filter, SR as usize * 2);
+        assert!((tail_frequency(&output) - 1000.0).abs() < 30.0);
+    }
+
+    #[test]
+    fn test_heavy_drive_stays_bounded() {
+        let loud = SineOscillator::<SR>::new(110.0).gain(4.0);
+        let mut filter = LadderFilter::new(loud, 3000.0, 1.0).with_drive(10.0);
+        for _ in 0..SR {
+            let sample = filter.next_sample();
+            assert!(sample.is_finite() && sample.abs() < 4.0);
+        }
+    }
+}
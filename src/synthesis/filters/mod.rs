@@ -1,13 +1,31 @@
 //! Audio filters for signal processing.
 //!
 //! This module provides various types of audio filters including
-//! low-pass, high-pass, band-pass, notch, and all-pass filters.
+//! low-pass, high-pass, band-pass, notch, all-pass, peaking and shelving
+//! filters.
 //!
 //! The primary filter implementation is [`BiquadFilter`], which uses
 //! second-order IIR filtering to provide efficient, high-quality filtering
-//! with support for parameter modulation.
+//! with support for parameter modulation. [`LadderFilter`] is a resonant
+//! 4-pole low-pass with analog-style saturation and self-oscillation.
+//! [`OnePoleLowpass`], [`OnePoleHighpass`] and [`DcBlocker`] are cheap
+//! utility filters for smoothing control signals and removing DC offset.
+//! [`ThreeBandCrossover`] splits a signal into Linkwitz-Riley bands, and
+//! [`ParametricEq`] chains any number of switchable EQ bands.
 
 mod biquad;
+mod crossover;
+mod ladder;
+mod one_pole;
+mod parametric_eq;
 
+pub(crate) use self::biquad::{
+    Biquad, biquad_coefficients, peaking_coefficients, shelf_coefficients,
+};
 pub use self::biquad::{BiquadFilter, FilterType};
+pub use self::crossover::ThreeBandCrossover;
+pub(crate) use self::crossover::{BandSplitter, Crossover};
+pub use self::ladder::LadderFilter;
+pub use self::one_pole::{DcBlocker, OnePoleHighpass, OnePoleLowpass};
+pub use self::parametric_eq::{EqBand, ParametricEq};
 // mod bandpass;
@@ -0,0 +1,355 @@
+//! One-pole filters and a DC blocker.
+//!
+//! These are the cheapest filters there are: one multiply-add and one state
+//! value per sample, with a gentle 6 dB/octave slope. They're meant for
+//! utility work rather than tone shaping: smoothing stepped or noisy control
+//! signals, taming zipper noise, and removing the DC offset that rectifying,
+//! ring modulation or asymmetric distortion leaves behind.
+
+use crate::core::{AudioSignal, Param, Signal, debug_assert_finite};
+use std::f64::consts::PI;
+
+/// Cutoff used by [`DcBlocker::new`], well below the audible range.
+const DEFAULT_DC_CUTOFF: f64 = 10.0;
+
+/// Returns the cutoff in Hz whose one-pole response has time constant
+/// `seconds` (0.0 or less gives an infinite cutoff, i.e. no smoothing).
+fn cutoff_for_time_constant(seconds: f64) -> f64 {
+    if seconds <= 0.0 {
+        f64::INFINITY
+    } else {
+        1.0 / (2.0 * PI * seconds)
+    }
+}
+
+/// Tracks a one-pole lowpass state, recomputing the coefficient only when
+/// the cutoff changes.
+struct OnePole {
+    state: Option<f64>,
+    cutoff: f64,
+    coeff: f64,
+}
+
+impl OnePole {
+    fn new() -> Self {
+        Self {
+            state: None,
+            cutoff: f64::NAN,
+            coeff: 1.0,
+        }
+    }
+
+    fn next(&mut self, input: f64, cutoff: f64, sample_rate: u32) -> f64 {
+        if cutoff != self.cutoff {
+            self.cutoff = cutoff;
+            self.coeff = 1.0 - (-2.0 * PI * cutoff.max(0.0) / sample_rate as f64).exp();
+        }
+        // The first sample initializes the state so the output doesn't glide
+        // up from zero
+        let state = match self.state {
+            Some(state) => state + (input - state) * self.coeff,
+            None => input,
+        };
+        self.state = Some(state);
+        state
+    }
+}
+
+/// A one-pole (6 dB/octave) low-pass filter.
+///
+/// The output starts at the first input sample rather than gliding up from
+/// zero, which makes it a good smoother for control signals: see
+/// [`from_time_constant`](Self::from_time_constant) and
+/// [`AudioSignalExt::smooth`](crate::AudioSignalExt::smooth).
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{OnePoleLowpass, Signal, SquareOscillator};
+///
+/// const SAMPLE_RATE: u32 = 44100;
+///
+/// // Round the corners of a square LFO
+/// let lfo = SquareOscillator::<SAMPLE_RATE>::new(2.0);
+/// let mut smoothed = OnePoleLowpass::from_time_constant(lfo, 0.02);
+/// let value = smoothed.next_sample();
+/// ```
+pub struct OnePoleLowpass<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
+    source: S,
+    cutoff: Param,
+    pole: OnePole,
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> OnePoleLowpass<SAMPLE_RATE, S> {
+    /// Creates a low-pass filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Input signal
+    /// * `cutoff` - -3 dB frequency in Hz (fixed or modulated)
+    pub fn new(source: S, cutoff: impl Into<Param>) -> Self {
+        Self {
+            source,
+            cutoff: cutoff.into(),
+            pole: OnePole::new(),
+        }
+    }
+
+    /// Creates a low-pass filter that settles to 63% of a step in
+    /// `time_constant` seconds (0.0 passes the input unchanged).
+    pub fn from_time_constant(source: S, time_constant: f64) -> Self {
+        Self::new(source, cutoff_for_time_constant(time_constant))
+    }
+
+    /// Clears the filter state.
+    pub fn reset(&mut self) {
+        self.pole.state = None;
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal
+    for OnePoleLowpass<SAMPLE_RATE, S>
+{
+    fn next_sample(&mut self) -> f64 {
+        let input = self.source.next_sample();
+        let output = self.pole.next(input, self.cutoff.value(), SAMPLE_RATE);
+        debug_assert_finite("OnePoleLowpass", input, output);
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.cutoff.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE>
+    for OnePoleLowpass<SAMPLE_RATE, S>
+{
+}
+
+/// A one-pole (6 dB/octave) high-pass filter.
+///
+/// Outputs the input minus its one-pole low-pass, so the two filters at the
+/// same cutoff sum back to the original signal.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{OnePoleHighpass, SawtoothOscillator, Signal};
+///
+/// let osc = SawtoothOscillator::<44100>::new(110.0);
+/// let mut thinned = OnePoleHighpass::new(osc, 300.0);
+/// let sample = thinned.next_sample();
+/// ```
+pub struct OnePoleHighpass<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
+    source: S,
+    cutoff: Param,
+    pole: OnePole,
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> OnePoleHighpass<SAMPLE_RATE, S> {
+    /// Creates a high-pass filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Input signal
+    /// * `cutoff` - -3 dB frequency in Hz (fixed or modulated)
+    pub fn new(source: S, cutoff: impl Into<Param>) -> Self {
+        Self {
+            source,
+            cutoff: cutoff.into(),
+            pole: OnePole::new(),
+        }
+    }
+
+    /// Clears the filter state.
+    pub fn reset(&mut self) {
+        self.pole.state = None;
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal
+    for OnePoleHighpass<SAMPLE_RATE, S>
+{
+    fn next_sample(&mut self) -> f64 {
+        let input = self.source.next_sample();
+        let output = input - self.pole.next(input, self.cutoff.value(), SAMPLE_RATE);
+        debug_assert_finite("OnePoleHighpass", input, output);
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        self.cutoff.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE>
+    for OnePoleHighpass<SAMPLE_RATE, S>
+{
+}
+
+/// Removes DC offset from a signal.
+///
+/// The classic one-zero, one-pole blocker `y[n] = x[n] - x[n-1] + R·y[n-1]`:
+/// the zero at DC removes any constant offset completely, and the pole just
+/// inside the unit circle keeps everything above the cutoff (10 Hz by
+/// default) flat.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{DcBlocker, Signal, SignalExt, SineOscillator};
+///
+/// const SAMPLE_RATE: u32 = 44100;
+///
+/// // Full-wave rectifying a sine leaves a large DC offset
+/// let rectified = SineOscillator::<SAMPLE_RATE>::new(220.0).abs();
+/// let mut centered = DcBlocker::new(rectified);
+/// let sample = centered.next_sample();
+/// ```
+pub struct DcBlocker<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
+    source: S,
+    cutoff: f64,
+    pole: f64,
+    previous_input: f64,
+    previous_output: f64,
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> DcBlocker<SAMPLE_RATE, S> {
+    /// Creates a DC blocker with a 10 Hz cutoff.
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            cutoff: 0.0,
+            pole: 0.0,
+            previous_input: 0.0,
+            previous_output: 0.0,
+        }
+        .with_cutoff(DEFAULT_DC_CUTOFF)
+    }
+
+    /// Sets the cutoff in Hz (builder style).
+    ///
+    /// Lower cutoffs disturb low bass less but take longer to settle after a
+    /// jump in offset. Values below 0.0 are clamped to 0.0.
+    pub fn with_cutoff(mut self, cutoff: f64) -> Self {
+        self.cutoff = cutoff.max(0.0);
+        self.pole = (-2.0 * PI * self.cutoff / SAMPLE_RATE as f64).exp();
+        self
+    }
+
+    /// Returns the cutoff in Hz.
+    pub fn cutoff(&self) -> f64 {
+        self.cutoff
+    }
+
+    /// Clears the filter state.
+    pub fn reset(&mut self) {
+        self.previous_input = 0.0;
+        self.previous_output = 0.0;
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for DcBlocker<SAMPLE_RATE, S> {
+    fn next_sample(&mut self) -> f64 {
+        let input = self.source.next_sample();
+        let output = input - self.previous_input + self.pole * self.previous_output;
+        self.previous_input = input;
+        self.previous_output = output;
+        debug_assert_finite("DcBlocker", input, output);
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE>
+    for DcBlocker<SAMPLE_RATE, S>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstantSignal, SignalExt, SineOscillator};
+
+    const SR: u32 = 48000;
+
+    /// Peak level once the filter has settled (second half of one second).
+    fn settled_peak(signal: &mut impl Signal) -> f64 {
+        let samples: Vec<f64> = (0..SR).map(|_| signal.next_sample()).collect();
+        samples[SR as usize / 2..]
+            .iter()
+            .fold(0.0, |peak, s| peak.max(s.abs()))
+    }
+
+    #[test]
+    fn test_lowpass_is_3db_down_at_cutoff() {
+        let mut filter = OnePoleLowpass::new(SineOscillator::<SR>::new(100.0), 100.0);
+        let peak = settled_peak(&mut filter);
+        assert!((peak - 0.5f64.sqrt()).abs() < 0.01, "peak {}", peak);
+
+        // 6 dB/octave: a decade above the cutoff is about 20 dB down
+        let mut filter = OnePoleLowpass::new(SineOscillator::<SR>::new(1000.0), 100.0);
+        let peak = settled_peak(&mut filter);
+        assert!((peak - 0.1).abs() < 0.005, "peak {}", peak);
+    }
+
+    #[test]
+    fn test_time_constant_and_first_sample() {
+        // Starts at the first input, so a constant passes straight through
+        let mut filter = OnePoleLowpass::from_time_constant(ConstantSignal::<SR>(0.7), 0.1);
+        assert_eq!(filter.next_sample(), 0.7);
+
+        // A step reaches 1 - 1/e after one time constant
+        let step = ConstantSignal::<SR>(1.0).gain(0.0);
+        let mut filter = OnePoleLowpass::from_time_constant(step, 0.01);
+        filter.next_sample();
+        filter.source = ConstantSignal::<SR>(1.0).gain(1.0);
+        let mut value = 0.0;
+        for _ in 0..SR / 100 {
+            value = filter.next_sample();
+        }
+        assert!((value - (1.0 - (-1.0f64).exp())).abs() < 0.001, "{}", value);
+
+        // Zero time constant is a pass-through
+        let mut filter = OnePoleLowpass::from_time_constant(SineOscillator::<SR>::new(5000.0), 0.0);
+        let mut reference = SineOscillator::<SR>::new(5000.0);
+        for _ in 0..100 {
+            assert!((filter.next_sample() - reference.next_sample()).abs() < 1e-15);
+        }
+    }
+
+    #[test]
+    fn test_highpass_complements_lowpass() {
+        let mut low = OnePoleLowpass::new(SineOscillator::<SR>::new(440.0), 300.0);
+        let mut high = OnePoleHighpass::new(SineOscillator::<SR>::new(440.0), 300.0);
+        let mut dry = SineOscillator::<SR>::new(440.0);
+        for _ in 0..1000 {
+            let sum = low.next_sample() + high.next_sample();
+            assert!((sum - dry.next_sample()).abs() < 1e-12);
+        }
+
+        let mut filter = OnePoleHighpass::new(ConstantSignal::<SR>(0.5), 50.0);
+        assert!(settled_peak(&mut filter) < 1e-9);
+    }
+
+    #[test]
+    fn test_dc_blocker_removes_offset_keeps_audio() {
+        let offset = SineOscillator::<SR>::new(440.0).offset(0.8);
+        let mut blocker = DcBlocker::new(offset);
+        let samples: Vec<f64> = (0..SR).map(|_| blocker.next_sample()).collect();
+        let tail = &samples[SR as usize / 2..];
+        let mean = tail.iter().sum::<f64>() / tail.len() as f64;
+        let peak = tail.iter().fold(0.0f64, |peak, s| peak.max(s.abs()));
+        assert!(mean.abs() < 1e-3, "mean {}", mean);
+        assert!((peak - 1.0).abs() < 0.01, "peak {}", peak);
+
+        let blocker = DcBlocker::new(ConstantSignal::<SR>(0.0)).with_cutoff(-5.0);
+        assert_eq!(blocker.cutoff(), 0.0);
+    }
+}
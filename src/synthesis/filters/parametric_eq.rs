@@ -0,0 +1,254 @@
+//! Multi-band parametric equalizer.
+
+use super::biquad::{Biquad, FilterType, biquad_coefficients};
+use crate::core::{AudioSignal, Param, Signal, debug_assert_finite};
+
+/// One band of a [`ParametricEq`].
+///
+/// Any [`FilterType`] works as a band, though EQs are mostly built from
+/// [`Peaking`](FilterType::Peaking) bands between a
+/// [`LowShelf`](FilterType::LowShelf) and a
+/// [`HighShelf`](FilterType::HighShelf), with a high-pass to clear out rumble.
+/// Frequency and Q can be modulated; the band's coefficients are recomputed
+/// whenever either changes.
+pub struct EqBand {
+    filter_type: FilterType,
+    frequency: Param,
+    q: Param,
+    enabled: bool,
+    filter: Biquad,
+    designed: Option<(FilterType, f64, f64)>, // settings the filter was designed for
+}
+
+impl EqBand {
+    /// Creates a band of any filter type.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter_type` - Band shape
+    /// * `frequency` - Center or corner frequency in Hz
+    /// * `q` - Q factor (bandwidth or resonance)
+    pub fn new(filter_type: FilterType, frequency: impl Into<Param>, q: impl Into<Param>) -> Self {
+        Self {
+            filter_type,
+            frequency: frequency.into(),
+            q: q.into(),
+            enabled: true,
+            filter: Biquad::new([1.0, 0.0, 0.0, 0.0, 0.0]),
+            designed: None,
+        }
+    }
+
+    /// Creates a peaking band boosting or cutting `gain_db` around `frequency`.
+    pub fn peaking(frequency: impl Into<Param>, q: impl Into<Param>, gain_db: f64) -> Self {
+        Self::new(FilterType::Peaking { gain_db }, frequency, q)
+    }
+
+    /// Creates a low shelf boosting or cutting `gain_db` below `frequency`.
+    pub fn low_shelf(frequency: impl Into<Param>, gain_db: f64) -> Self {
+        Self::new(FilterType::LowShelf { gain_db }, frequency, 0.707)
+    }
+
+    /// Creates a high shelf boosting or cutting `gain_db` above `frequency`.
+    pub fn high_shelf(frequency: impl Into<Param>, gain_db: f64) -> Self {
+        Self::new(FilterType::HighShelf { gain_db }, frequency, 0.707)
+    }
+
+    /// Sets whether the band starts enabled.
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Enables or bypasses the band.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Returns `true` if the band is being applied.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Changes the band's shape, e.g. to adjust a peaking band's gain.
+    pub fn set_filter_type(&mut self, filter_type: FilterType) {
+        self.filter_type = filter_type;
+    }
+
+    /// Returns the band's shape.
+    pub fn filter_type(&self) -> FilterType {
+        self.filter_type
+    }
+
+    /// Sets the center or corner frequency in Hz.
+    pub fn set_frequency(&mut self, frequency: impl Into<Param>) {
+        self.frequency = frequency.into();
+    }
+
+    /// Sets the Q factor.
+    pub fn set_q(&mut self, q: impl Into<Param>) {
+        self.q = q.into();
+    }
+
+    /// Filters one sample, redesigning the filter if a setting moved.
+    fn process(&mut self, input: f64, sample_rate: u32) -> f64 {
+        // Pull params even when bypassed so modulation keeps its place
+        let frequency = self.frequency.value();
+        let q = self.q.value();
+        if !self.enabled {
+            return input;
+        }
+        let settings = (self.filter_type, frequency, q);
+        if self.designed != Some(settings) {
+            self.filter.set_coefficients(biquad_coefficients(
+                self.filter_type,
+                frequency,
+                q,
+                sample_rate,
+            ));
+            self.designed = Some(settings);
+        }
+        self.filter.process(input)
+    }
+}
+
+/// Parametric equalizer with any number of bands.
+///
+/// Bands are applied in series, in the order they were added. Each can be
+/// switched in and out without losing its settings, for A/B comparisons.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{EqBand, ParametricEq, SawtoothOscillator};
+///
+/// let synth = SawtoothOscillator::<44100>::new(110.0);
+/// let mut eq = ParametricEq::new(synth)
+///     .with_band(EqBand::low_shelf(100.0, 3.0))
+///     .with_band(EqBand::peaking(400.0, 2.0, -4.0))
+///     .with_band(EqBand::high_shelf(8000.0, 2.0));
+///
+/// // Bypass the mid cut to hear the difference
+/// eq.band_mut(1).unwrap().set_enabled(false);
+/// ```
+pub struct ParametricEq<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> {
+    source: S,
+    bands: Vec<EqBand>,
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> ParametricEq<SAMPLE_RATE, S> {
+    /// Creates an equalizer with no bands, which passes `source` through.
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            bands: Vec::new(),
+        }
+    }
+
+    /// Adds a band after the existing ones.
+    pub fn with_band(mut self, band: EqBand) -> Self {
+        self.bands.push(band);
+        self
+    }
+
+    /// Adds a band after the existing ones, returning its index.
+    pub fn add_band(&mut self, band: EqBand) -> usize {
+        self.bands.push(band);
+        self.bands.len() - 1
+    }
+
+    /// Removes and returns the band at `index`, if there is one.
+    pub fn remove_band(&mut self, index: usize) -> Option<EqBand> {
+        (index < self.bands.len()).then(|| self.bands.remove(index))
+    }
+
+    /// Returns the band at `index`.
+    pub fn band(&self, index: usize) -> Option<&EqBand> {
+        self.bands.get(index)
+    }
+
+    /// Returns the band at `index` for adjustment.
+    pub fn band_mut(&mut self, index: usize) -> Option<&mut EqBand> {
+        self.bands.get_mut(index)
+    }
+
+    /// Returns the number of bands.
+    pub fn len(&self) -> usize {
+        self.bands.len()
+    }
+
+    /// Returns `true` if there are no bands.
+    pub fn is_empty(&self) -> bool {
+        self.bands.is_empty()
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> Signal for ParametricEq<SAMPLE_RATE, S> {
+    fn next_sample(&mut self) -> f64 {
+        let input = self.source.next_sample();
+        let output = self
+            .bands
+            .iter_mut()
+            .fold(input, |x, band| band.process(x, SAMPLE_RATE));
+        debug_assert_finite("ParametricEq", input, output);
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+        for band in &mut self.bands {
+            band.frequency.prepare(max_block_size, sample_rate);
+            band.q.prepare(max_block_size, sample_rate);
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE>> AudioSignal<SAMPLE_RATE>
+    for ParametricEq<SAMPLE_RATE, S>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SineOscillator;
+
+    /// Peak level of a steady tone through `eq`.
+    fn level(eq: &mut impl Signal) -> f64 {
+        for _ in 0..4410 {
+            eq.next_sample();
+        }
+        (0..4410).fold(0.0_f64, |max, _| max.max(eq.next_sample().abs()))
+    }
+
+    #[test]
+    fn test_bands_apply_in_series() {
+        let db = |gain: f64| 10.0_f64.powf(gain / 20.0);
+        let tone = SineOscillator::<44100>::new(1000.0);
+        let mut eq = ParametricEq::new(tone)
+            .with_band(EqBand::peaking(1000.0, 1.0, 6.0))
+            .with_band(EqBand::peaking(1000.0, 1.0, 3.0));
+        assert_eq!(eq.len(), 2);
+        assert!((level(&mut eq) - db(9.0)).abs() < 0.03);
+    }
+
+    #[test]
+    fn test_disabled_band_is_bypassed() {
+        let tone = SineOscillator::<44100>::new(1000.0);
+        let mut eq = ParametricEq::new(tone).with_band(EqBand::peaking(1000.0, 1.0, -12.0));
+        assert!(level(&mut eq) < 0.3);
+
+        eq.band_mut(0).unwrap().set_enabled(false);
+        assert!((level(&mut eq) - 1.0).abs() < 0.01);
+
+        // Re-enabled with a new gain
+        let band = eq.band_mut(0).unwrap();
+        band.set_enabled(true);
+        band.set_filter_type(FilterType::Peaking { gain_db: 6.0 });
+        assert!(level(&mut eq) > 1.9);
+
+        assert!(eq.remove_band(0).is_some());
+        assert!(eq.is_empty());
+        assert!(eq.remove_band(0).is_none());
+    }
+}
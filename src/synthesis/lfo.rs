@@ -0,0 +1,344 @@
+//! Low-frequency oscillators with per-note retriggering.
+
+use crate::core::Param;
+use crate::synthesis::oscillators::{SinePrecision, fast_sine, wrap_phase};
+use crate::{AudioSignal, Signal};
+use std::f64::consts::TAU;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Default crossfade applied when an LFO restarts, in seconds.
+const DEFAULT_CROSSFADE: f64 = 0.002;
+
+/// Shape of an [`Lfo`]'s output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LfoWaveform {
+    /// Sine wave, starting at 0 and rising
+    Sine,
+    /// Triangle wave, starting at 0 and rising
+    Triangle,
+    /// Rising sawtooth, starting at -1
+    Saw,
+    /// Square wave, +1 for the first half of the cycle
+    Square,
+}
+
+impl LfoWaveform {
+    fn value(self, phase: f64) -> f64 {
+        match self {
+            LfoWaveform::Sine => (phase * TAU).sin(),
+            LfoWaveform::Triangle => {
+                if phase < 0.25 {
+                    4.0 * phase
+                } else if phase < 0.75 {
+                    2.0 - 4.0 * phase
+                } else {
+                    4.0 * phase - 4.0
+                }
+            }
+            LfoWaveform::Saw => 2.0 * phase - 1.0,
+            LfoWaveform::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+        }
+    }
+}
+
+/// How an [`Lfo`]'s phase relates to the notes being played.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LfoRetrigger {
+    /// The LFO runs continuously and ignores note-ons
+    Free,
+    /// The LFO restarts from its start phase on every note-on
+    Note,
+    /// The phase is locked to elapsed time at a tempo, so every note at the
+    /// same beat position sees the same phase; the rate parameter is ignored
+    TempoLocked {
+        /// Tempo in beats per minute
+        bpm: f64,
+        /// Length of one LFO cycle in beats
+        beats_per_cycle: f64,
+    },
+}
+
+/// A handle for restarting an [`Lfo`] from another part of the graph.
+///
+/// An LFO usually ends up inside the signal it modulates (as a [`Param`]),
+/// out of reach of the code that plays notes. Pass a handle to
+/// [`Voice::with_lfo`](crate::music::Voice::with_lfo) (or keep one and call
+/// [`fire`](Self::fire)) to retrigger it. Firing is lock-free and takes effect
+/// on the LFO's next sample.
+#[derive(Debug, Clone, Default)]
+pub struct LfoTrigger {
+    count: Arc<AtomicU64>,
+}
+
+impl LfoTrigger {
+    /// Signals a note-on to every LFO sharing this handle.
+    pub fn fire(&self) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+}
+
+/// A bipolar low-frequency oscillator for modulating parameters.
+///
+/// Outputs values in [-1.0, 1.0]. The [`LfoRetrigger`] mode decides whether
+/// note-ons restart it; restarts are crossfaded over a couple of milliseconds
+/// so a retrigger mid-cycle doesn't click.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "music", doc = "```")]
+#[cfg_attr(not(feature = "music"), doc = "```ignore")]
+/// use earworm::music::Voice;
+/// use earworm::{ADSR, Lfo, LfoRetrigger, LfoWaveform, SawtoothOscillator, Signal, SignalExt};
+///
+/// const SAMPLE_RATE: u32 = 44100;
+///
+/// // A 5 Hz vibrato that starts from the same phase on every note
+/// let lfo = Lfo::<SAMPLE_RATE>::new(LfoWaveform::Sine, 5.0).with_retrigger(LfoRetrigger::Note);
+/// let trigger = lfo.trigger();
+/// let vibrato = lfo.gain(3.0);
+///
+/// let osc = SawtoothOscillator::<SAMPLE_RATE>::with_frequency_mod(220.0, vibrato);
+/// let env = ADSR::new(0.01, 0.1, 0.7, 0.3, SAMPLE_RATE as f64);
+/// let mut voice = Voice::new(osc, env).with_lfo(trigger);
+///
+/// voice.note_on(220.0, 0.8);
+/// voice.next_sample();
+/// ```
+pub struct Lfo<const SAMPLE_RATE: u32> {
+    waveform: LfoWaveform,
+    rate: Param,
+    retrigger: LfoRetrigger,
+    start_phase: f64,
+    phase: f64,
+    elapsed: u64,
+    trigger: LfoTrigger,
+    seen_triggers: u64,
+    crossfade_samples: usize,
+    fade_remaining: usize,
+    fade_from: f64,
+    last_output: f64,
+    precision: SinePrecision,
+}
+
+impl<const SAMPLE_RATE: u32> Lfo<SAMPLE_RATE> {
+    /// Creates a free-running LFO.
+    ///
+    /// # Arguments
+    ///
+    /// * `waveform` - Output shape
+    /// * `rate` - Rate in Hz (fixed or modulated)
+    pub fn new(waveform: LfoWaveform, rate: impl Into<Param>) -> Self {
+        Self {
+            waveform,
+            rate: rate.into(),
+            retrigger: LfoRetrigger::Free,
+            start_phase: 0.0,
+            phase: 0.0,
+            elapsed: 0,
+            trigger: LfoTrigger::default(),
+            seen_triggers: 0,
+            crossfade_samples: (DEFAULT_CROSSFADE * SAMPLE_RATE as f64).round() as usize,
+            fade_remaining: 0,
+            fade_from: 0.0,
+            last_output: 0.0,
+            precision: SinePrecision::Exact,
+        }
+    }
+
+    /// Sets the retrigger mode (builder style).
+    pub fn with_retrigger(mut self, retrigger: LfoRetrigger) -> Self {
+        self.retrigger = retrigger;
+        self
+    }
+
+    /// Sets the phase (0.0 to 1.0) the LFO starts and restarts from (builder style).
+    pub fn with_start_phase(mut self, phase: f64) -> Self {
+        self.start_phase = wrap_phase(phase);
+        self.phase = self.start_phase;
+        self
+    }
+
+    /// Sets how long restarts are crossfaded, in seconds (builder style).
+    ///
+    /// 0.0 restarts instantly, which is sample-accurate but may click.
+    pub fn with_crossfade(mut self, seconds: f64) -> Self {
+        self.crossfade_samples = (seconds.max(0.0) * SAMPLE_RATE as f64).round() as usize;
+        self
+    }
+
+    /// Sets how the sine waveform is computed (builder style).
+    ///
+    /// [`SinePrecision::Fast`] is far more accurate than modulation needs.
+    pub fn with_precision(mut self, precision: SinePrecision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Returns a handle that restarts this LFO in [`LfoRetrigger::Note`] mode.
+    pub fn trigger(&self) -> LfoTrigger {
+        self.trigger.clone()
+    }
+
+    /// Returns the retrigger mode.
+    pub fn retrigger_mode(&self) -> LfoRetrigger {
+        self.retrigger
+    }
+
+    /// Returns the current phase (0.0 to 1.0).
+    pub fn phase(&self) -> f64 {
+        self.phase
+    }
+
+    /// Restarts the LFO from its start phase, as a note-on would.
+    ///
+    /// Only has an effect in [`LfoRetrigger::Note`] mode.
+    pub fn retrigger(&mut self) {
+        if self.retrigger != LfoRetrigger::Note {
+            return;
+        }
+        self.phase = self.start_phase;
+        self.fade_from = self.last_output;
+        self.fade_remaining = self.crossfade_samples;
+    }
+}
+
+impl<const SAMPLE_RATE: u32> Signal for Lfo<SAMPLE_RATE> {
+    fn next_sample(&mut self) -> f64 {
+        let rate = self.rate.value();
+        let triggers = self.trigger.count();
+        if triggers != self.seen_triggers {
+            self.seen_triggers = triggers;
+            self.retrigger();
+        }
+
+        if let LfoRetrigger::TempoLocked {
+            bpm,
+            beats_per_cycle,
+        } = self.retrigger
+        {
+            let cycle_samples = beats_per_cycle * 60.0 / bpm * SAMPLE_RATE as f64;
+            self.phase = wrap_phase(self.start_phase + self.elapsed as f64 / cycle_samples);
+        }
+
+        let mut output = match (self.waveform, self.precision) {
+            (LfoWaveform::Sine, SinePrecision::Fast) => fast_sine(self.phase),
+            (waveform, _) => waveform.value(self.phase),
+        };
+        if self.fade_remaining > 0 {
+            let t = self.fade_remaining as f64 / (self.crossfade_samples + 1) as f64;
+            output = self.fade_from * t + output * (1.0 - t);
+            self.fade_remaining -= 1;
+        }
+
+        if !matches!(self.retrigger, LfoRetrigger::TempoLocked { .. }) {
+            self.phase = wrap_phase(self.phase + rate / SAMPLE_RATE as f64);
+        }
+        self.elapsed += 1;
+        self.last_output = output;
+        output
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.rate.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32> AudioSignal<SAMPLE_RATE> for Lfo<SAMPLE_RATE> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SR: u32 = 1000;
+
+    #[test]
+    fn test_waveforms_at_quarter_phases() {
+        for (waveform, expected) in [
+            (LfoWaveform::Sine, [0.0, 1.0, 0.0, -1.0]),
+            (LfoWaveform::Triangle, [0.0, 1.0, 0.0, -1.0]),
+            (LfoWaveform::Saw, [-1.0, -0.5, 0.0, 0.5]),
+            (LfoWaveform::Square, [1.0, 1.0, -1.0, -1.0]),
+        ] {
+            let mut lfo = Lfo::<SR>::new(waveform, 250.0);
+            for e in expected {
+                assert!((lfo.next_sample() - e).abs() < 1e-9, "{:?}", waveform);
+            }
+        }
+    }
+
+    #[test]
+    fn test_free_running_ignores_triggers() {
+        let mut lfo = Lfo::<SR>::new(LfoWaveform::Saw, 10.0);
+        let trigger = lfo.trigger();
+        for _ in 0..30 {
+            lfo.next_sample();
+        }
+        trigger.fire();
+        lfo.next_sample();
+        assert!((lfo.phase() - 0.31).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_note_retrigger_is_sample_accurate_without_crossfade() {
+        let mut lfo = Lfo::<SR>::new(LfoWaveform::Saw, 10.0)
+            .with_retrigger(LfoRetrigger::Note)
+            .with_start_phase(0.5)
+            .with_crossfade(0.0);
+        let trigger = lfo.trigger();
+        for _ in 0..37 {
+            lfo.next_sample();
+        }
+        trigger.fire();
+        assert_eq!(lfo.next_sample(), 0.0);
+        assert!((lfo.next_sample() - 0.02).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_retrigger_crossfades() {
+        let mut lfo = Lfo::<SR>::new(LfoWaveform::Square, 1.0)
+            .with_retrigger(LfoRetrigger::Note)
+            .with_start_phase(0.5)
+            .with_crossfade(0.004);
+        let trigger = lfo.trigger();
+        assert_eq!(lfo.next_sample(), -1.0);
+        for _ in 0..600 {
+            lfo.next_sample();
+        }
+        assert_eq!(lfo.next_sample(), 1.0);
+        trigger.fire();
+
+        // Moves from +1 towards -1 over four samples instead of jumping
+        let fade: Vec<f64> = (0..5).map(|_| lfo.next_sample()).collect();
+        assert!(fade.windows(2).all(|w| w[1] < w[0]));
+        assert!(fade[0] > 0.5);
+        assert_eq!(fade[4], -1.0);
+    }
+
+    #[test]
+    fn test_tempo_locked_phase_follows_elapsed_time() {
+        // 120 BPM, one cycle per beat = 500 samples per cycle at 1 kHz
+        let mut lfo =
+            Lfo::<SR>::new(LfoWaveform::Saw, 99.0).with_retrigger(LfoRetrigger::TempoLocked {
+                bpm: 120.0,
+                beats_per_cycle: 1.0,
+            });
+        let trigger = lfo.trigger();
+        for _ in 0..250 {
+            lfo.next_sample();
+        }
+        trigger.fire();
+        assert!(lfo.next_sample().abs() < 1e-9);
+        assert!((lfo.phase() - 0.5).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,140 @@
+//! Macro controls: one knob driving many parameters.
+
+use crate::core::ParamHandle;
+use crate::synthesis::envelopes::Curve;
+
+/// One parameter driven by a [`MacroControl`].
+#[derive(Debug, Clone)]
+struct MacroTarget {
+    handle: ParamHandle,
+    min: f64,
+    max: f64,
+    curve: Curve,
+}
+
+/// A single 0.0 to 1.0 control mapped onto several parameters.
+///
+/// Each target has its own range and [`Curve`], so one knob can open a
+/// filter exponentially while adding resonance linearly and pulling the
+/// reverb mix down. A range with `min` greater than `max` moves that target
+/// in the opposite direction.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::core::ParamHandle;
+/// use earworm::{Curve, MacroControl};
+///
+/// let cutoff = ParamHandle::new(200.0);
+/// let resonance = ParamHandle::new(0.7);
+///
+/// // A "brightness" knob
+/// let mut brightness = MacroControl::new()
+///     .with_target(cutoff.clone(), 200.0, 8000.0, Curve::Exponential(2.0))
+///     .with_target(resonance.clone(), 0.7, 2.0, Curve::Linear);
+///
+/// brightness.set(0.5);
+/// assert_eq!(cutoff.get(), 200.0 + 7800.0 * 0.25);
+/// assert!((resonance.get() - 1.35).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MacroControl {
+    value: f64,
+    targets: Vec<MacroTarget>,
+}
+
+impl MacroControl {
+    /// Creates a macro with no targets, set to 0.0.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a target (builder style).
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - Parameter to drive
+    /// * `min` - Parameter value with the macro at 0.0
+    /// * `max` - Parameter value with the macro at 1.0
+    /// * `curve` - Shape of the mapping between the two
+    pub fn with_target(mut self, handle: ParamHandle, min: f64, max: f64, curve: Curve) -> Self {
+        self.add_target(handle, min, max, curve);
+        self
+    }
+
+    /// Adds a target.
+    ///
+    /// The target is immediately set to match the macro's current value.
+    pub fn add_target(&mut self, handle: ParamHandle, min: f64, max: f64, curve: Curve) {
+        let target = MacroTarget {
+            handle,
+            min,
+            max,
+            curve,
+        };
+        Self::update(&target, self.value);
+        self.targets.push(target);
+    }
+
+    /// Removes every target driving `handle`, returning how many were removed.
+    pub fn remove_target(&mut self, handle: &ParamHandle) -> usize {
+        let before = self.targets.len();
+        self.targets.retain(|target| !target.handle.same_as(handle));
+        before - self.targets.len()
+    }
+
+    /// Returns the number of targets.
+    pub fn target_count(&self) -> usize {
+        self.targets.len()
+    }
+
+    /// Returns the macro's current value.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Sets the macro (clamped to 0.0-1.0) and updates every target.
+    pub fn set(&mut self, value: f64) {
+        self.value = value.clamp(0.0, 1.0);
+        for target in &self.targets {
+            Self::update(target, self.value);
+        }
+    }
+
+    fn update(target: &MacroTarget, value: f64) {
+        let shaped = target.curve.apply(value);
+        target
+            .handle
+            .set(target.min + (target.max - target.min) * shaped);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inverted_range() {
+        let mix = ParamHandle::new(0.0);
+        let mut knob = MacroControl::new().with_target(mix.clone(), 0.4, 0.0, Curve::Linear);
+        assert_eq!(mix.get(), 0.4);
+        knob.set(1.0);
+        assert_eq!(mix.get(), 0.0);
+        knob.set(2.0);
+        assert_eq!(knob.value(), 1.0);
+    }
+
+    #[test]
+    fn test_remove_target() {
+        let a = ParamHandle::new(0.0);
+        let b = ParamHandle::new(0.0);
+        let mut knob = MacroControl::new()
+            .with_target(a.clone(), 0.0, 1.0, Curve::Linear)
+            .with_target(b.clone(), 0.0, 1.0, Curve::Linear);
+        assert_eq!(knob.remove_target(&a), 1);
+        knob.set(1.0);
+        assert_eq!(a.get(), 0.0);
+        assert_eq!(b.get(), 1.0);
+        assert_eq!(knob.target_count(), 1);
+    }
+}
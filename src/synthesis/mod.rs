@@ -1,28 +1,52 @@
 //! Audio synthesis components.
 //!
 //! This module provides high-level building blocks for audio synthesis, including:
-//! - Oscillators (sine, triangle, sawtooth, square, pulse)
+//! - Oscillators (sine, triangle, sawtooth, square, pulse), a plucked string
+//!   model, and modulation and glide wrappers
 //! - Filters (biquad IIR filters)
-//! - Effects (delay, tremolo, vibrato, distortion, etc.)
+//! - Effects (delay, reverb, tremolo, vibrato, distortion, etc.)
 //! - Curve utilities for shaping parameters
+//! - LFOs with free-running, per-note and tempo-locked phase
+//! - Macro controls mapping one knob onto many parameters
 //! - Noise generators (white, pink)
+//! - Calibration signals for lining up levels and channel routing
 //! - AudioSignalExt trait for convenient filter/effect chaining
 //!
-//! All synthesis components require the `synth` feature to be enabled.
+//! All synthesis components require the `synth` feature to be enabled. The
+//! optional `simd` feature adds a vectorized block path for linear wavetable
+//! interpolation.
 
 mod audio_ext;
+pub mod calibration;
 pub mod effects;
 pub mod envelopes;
 pub mod filters;
+mod lfo;
+mod macro_control;
 pub mod noise;
 pub mod oscillators;
+mod sampler;
+#[cfg(feature = "simd")]
+pub(crate) mod simd;
 
 pub use audio_ext::AudioSignalExt;
-pub use effects::{Bitcrusher, Compressor, Delay, Distortion, Limiter, Tremolo, Vibrato};
+pub use calibration::{CalibrationNoise, ChannelIdent, ReferenceTone};
+pub use effects::{
+    AmpSim, Bitcrusher, CabModel, CabSim, Compressor, CompressorBand, Delay, Diffuser, Distortion,
+    HarmonicTremolo, Limiter, MultibandCompressor, Octaver, PingPongDelay, PlateReverb, Reverb,
+    SpringReverb, ToneStack, Tremolo, Vibrato,
+};
 pub use envelopes::Curve;
-pub use filters::{BiquadFilter, FilterType};
+pub use filters::{
+    BiquadFilter, DcBlocker, EqBand, FilterType, LadderFilter, OnePoleHighpass, OnePoleLowpass,
+    ParametricEq, ThreeBandCrossover,
+};
+pub use lfo::{Lfo, LfoRetrigger, LfoTrigger, LfoWaveform};
+pub use macro_control::MacroControl;
 pub use noise::{PinkNoise, WhiteNoise};
 pub use oscillators::{
-    InterpolationMode, Oscillator, PulseOscillator, SawtoothOscillator, SineOscillator,
-    SquareOscillator, TriangleOscillator, WavetableOscillator,
+    Glide, InterpolationMode, ModulatedOscillator, Oscillator, PhaseAccumulator, PluckedString,
+    PulseOscillator, SawtoothOscillator, SineOscillator, SinePrecision, SquareOscillator,
+    SyncOscillator, TriangleOscillator, WavetableOscillator,
 };
+pub use sampler::{KeyZone, LoopMode, MultiSampler, Sampler};
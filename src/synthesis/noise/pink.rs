@@ -1,5 +1,6 @@
 //! Pink noise generator implementation.
 
+use crate::core::{RngContext, SeededRng};
 use crate::{AudioSignal, Signal};
 use rand::Rng;
 
@@ -46,6 +47,14 @@ impl<const SAMPLE_RATE: u32> PinkNoise<SAMPLE_RATE, rand::rngs::ThreadRng> {
     }
 }
 
+impl<const SAMPLE_RATE: u32> PinkNoise<SAMPLE_RATE, SeededRng> {
+    /// Creates a pink noise generator seeded from `context`, so the noise is
+    /// reproduced along with the rest of a piece.
+    pub fn from_context(context: &mut RngContext) -> Self {
+        Self::with_rng(context.rng())
+    }
+}
+
 impl<const SAMPLE_RATE: u32, R: Rng> PinkNoise<SAMPLE_RATE, R> {
     /// Creates a new pink noise generator with a custom RNG.
     ///
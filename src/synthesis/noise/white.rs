@@ -1,5 +1,6 @@
 //! White noise generator implementation.
 
+use crate::core::{RngContext, SeededRng};
 use crate::{AudioSignal, Signal};
 use rand::Rng;
 
@@ -36,6 +37,25 @@ impl<const SAMPLE_RATE: u32> WhiteNoise<SAMPLE_RATE, rand::rngs::ThreadRng> {
     }
 }
 
+impl<const SAMPLE_RATE: u32> WhiteNoise<SAMPLE_RATE, SeededRng> {
+    /// Creates a white noise generator seeded from `context`, so the noise
+    /// is reproduced along with the rest of a piece.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::core::RngContext;
+    /// use earworm::{Signal, WhiteNoise};
+    ///
+    /// let mut a = WhiteNoise::<44100, _>::from_context(&mut RngContext::new(5));
+    /// let mut b = WhiteNoise::<44100, _>::from_context(&mut RngContext::new(5));
+    /// assert_eq!(a.next_sample(), b.next_sample());
+    /// ```
+    pub fn from_context(context: &mut RngContext) -> Self {
+        Self::with_rng(context.rng())
+    }
+}
+
 impl<const SAMPLE_RATE: u32, R: Rng> WhiteNoise<SAMPLE_RATE, R> {
     /// Creates a new white noise generator with a custom RNG.
     ///
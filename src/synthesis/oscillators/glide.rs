@@ -0,0 +1,308 @@
+//! Glide (portamento) between frequencies.
+
+use super::Oscillator;
+use crate::core::Pitched;
+use crate::synthesis::envelopes::Curve;
+use crate::{AudioSignal, Signal};
+
+/// Frequency slew shared by [`Glide`] and voice-level portamento.
+///
+/// Interpolates in the log-frequency domain so a glide covers equal musical
+/// intervals in equal time, shaped by a [`Curve`].
+#[derive(Debug, Clone)]
+pub(crate) struct GlideState {
+    start: f64,
+    target: f64,
+    current: f64,
+    position: usize,
+    length: usize,
+    curve: Curve,
+}
+
+impl GlideState {
+    pub(crate) fn new(frequency: f64, curve: Curve) -> Self {
+        Self {
+            start: frequency,
+            target: frequency,
+            current: frequency,
+            position: 0,
+            length: 0,
+            curve,
+        }
+    }
+
+    /// Starts a glide from the current frequency to `target` over `length` samples.
+    pub(crate) fn glide_to(&mut self, target: f64, length: usize) {
+        self.start = self.current;
+        self.target = target;
+        self.position = 0;
+        self.length = length;
+        if length == 0 {
+            self.current = target;
+        }
+    }
+
+    /// Moves straight to `frequency` with no glide.
+    pub(crate) fn jump_to(&mut self, frequency: f64) {
+        self.start = frequency;
+        self.target = frequency;
+        self.current = frequency;
+        self.length = 0;
+        self.position = 0;
+    }
+
+    pub(crate) fn set_curve(&mut self, curve: Curve) {
+        self.curve = curve;
+    }
+
+    pub(crate) fn target(&self) -> f64 {
+        self.target
+    }
+
+    pub(crate) fn is_gliding(&self) -> bool {
+        self.position < self.length
+    }
+
+    /// Advances one sample and returns the instantaneous frequency.
+    pub(crate) fn next_frequency(&mut self) -> f64 {
+        if self.position < self.length {
+            self.position += 1;
+            let t = self.curve.apply(self.position as f64 / self.length as f64);
+            self.current = if self.start > 0.0 && self.target > 0.0 {
+                self.start * (self.target / self.start).powf(t)
+            } else {
+                self.start + (self.target - self.start) * t
+            };
+        }
+        self.current
+    }
+}
+
+/// Portamento wrapper that slews any [`Pitched`] signal between frequencies.
+///
+/// Calls to [`set_frequency`](Pitched::set_frequency) don't change the pitch
+/// immediately. Instead the wrapped signal's frequency moves from its current
+/// value to the new one over the glide time, following the configured
+/// [`Curve`] in the log-frequency domain. That means an octave takes the same
+/// time no matter where it starts.
+///
+/// `frequency()` reports the target frequency.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{Glide, Pitched, Signal, SawtoothOscillator};
+///
+/// let osc = SawtoothOscillator::<44100>::new(220.0);
+/// let mut lead = Glide::new(osc, 0.1); // 100 ms glide
+///
+/// lead.set_frequency(440.0);
+/// for _ in 0..4410 {
+///     lead.next_sample();
+/// }
+/// assert!((lead.current_frequency() - 440.0).abs() < 1e-6);
+/// ```
+pub struct Glide<const SAMPLE_RATE: u32, S> {
+    source: S,
+    glide_time: f64,
+    state: GlideState,
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE> + Pitched> Glide<SAMPLE_RATE, S> {
+    /// Creates a glide wrapper.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The pitched signal to control
+    /// * `glide_time` - Time to reach a new frequency, in seconds
+    pub fn new(source: S, glide_time: f64) -> Self {
+        let state = GlideState::new(source.frequency(), Curve::Linear);
+        Self {
+            source,
+            glide_time: glide_time.max(0.0),
+            state,
+        }
+    }
+
+    /// Sets the curve used to shape the glide (default: linear in log-frequency).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{Curve, Glide, SineOscillator};
+    ///
+    /// let glide = Glide::new(SineOscillator::<44100>::new(220.0), 0.2)
+    ///     .with_curve(Curve::SCurve);
+    /// ```
+    pub fn with_curve(mut self, curve: Curve) -> Self {
+        self.state.set_curve(curve);
+        self
+    }
+
+    /// Sets the glide time in seconds.
+    pub fn set_glide_time(&mut self, glide_time: f64) {
+        self.glide_time = glide_time.max(0.0);
+    }
+
+    /// Returns the glide time in seconds.
+    pub fn glide_time(&self) -> f64 {
+        self.glide_time
+    }
+
+    /// Moves to `frequency` immediately, cancelling any glide in progress.
+    pub fn jump_to(&mut self, frequency: f64) {
+        self.state.jump_to(frequency);
+        self.source.set_frequency(frequency);
+    }
+
+    /// Returns the instantaneous frequency (partway through a glide).
+    pub fn current_frequency(&self) -> f64 {
+        self.source.frequency()
+    }
+
+    /// Returns true while a glide is in progress.
+    pub fn is_gliding(&self) -> bool {
+        self.state.is_gliding()
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE> + Pitched> Signal
+    for Glide<SAMPLE_RATE, S>
+{
+    fn next_sample(&mut self) -> f64 {
+        if self.state.is_gliding() {
+            let frequency = self.state.next_frequency();
+            self.source.set_frequency(frequency);
+        }
+        self.source.next_sample()
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.source.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE> + Pitched> AudioSignal<SAMPLE_RATE>
+    for Glide<SAMPLE_RATE, S>
+{
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE> + Pitched> Pitched
+    for Glide<SAMPLE_RATE, S>
+{
+    fn set_frequency(&mut self, freq: f64) {
+        let length = (self.glide_time * SAMPLE_RATE as f64).round() as usize;
+        self.state.glide_to(freq, length);
+        if length == 0 {
+            self.source.set_frequency(freq);
+        }
+    }
+
+    fn frequency(&self) -> f64 {
+        self.state.target()
+    }
+
+    fn jump_to_frequency(&mut self, freq: f64) {
+        self.jump_to(freq);
+    }
+
+    fn restart(&mut self) {
+        self.source.restart();
+    }
+
+    fn release(&mut self) {
+        self.source.release();
+    }
+}
+
+impl<const SAMPLE_RATE: u32, S: AudioSignal<SAMPLE_RATE> + Oscillator> Oscillator
+    for Glide<SAMPLE_RATE, S>
+{
+    fn reset(&mut self) {
+        self.source.reset();
+        let target = self.state.target();
+        self.jump_to(target);
+    }
+
+    fn phase(&self) -> f64 {
+        self.source.phase()
+    }
+
+    fn set_phase(&mut self, phase: f64) {
+        self.source.set_phase(phase);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SineOscillator;
+
+    #[test]
+    fn test_glide_reaches_target() {
+        let mut glide = Glide::new(SineOscillator::<1000>::new(100.0), 0.1);
+        glide.set_frequency(200.0);
+        assert_eq!(glide.frequency(), 200.0);
+        assert!(glide.is_gliding());
+
+        for _ in 0..100 {
+            glide.next_sample();
+        }
+        assert!(!glide.is_gliding());
+        assert!((glide.current_frequency() - 200.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_glide_is_exponential() {
+        // Halfway through an octave glide is the geometric mean
+        let mut glide = Glide::new(SineOscillator::<1000>::new(100.0), 0.1);
+        glide.set_frequency(400.0);
+        for _ in 0..50 {
+            glide.next_sample();
+        }
+        assert!((glide.current_frequency() - 200.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_zero_glide_time_is_immediate() {
+        let mut glide = Glide::new(SineOscillator::<1000>::new(100.0), 0.0);
+        glide.set_frequency(300.0);
+        assert_eq!(glide.current_frequency(), 300.0);
+        assert!(!glide.is_gliding());
+    }
+
+    #[test]
+    fn test_retarget_mid_glide_starts_from_current() {
+        let mut glide = Glide::new(SineOscillator::<1000>::new(100.0), 0.1);
+        glide.set_frequency(400.0);
+        for _ in 0..50 {
+            glide.next_sample();
+        }
+        glide.set_frequency(100.0);
+        glide.next_sample();
+        // Heading back down from ~200 Hz, not jumping from 400 Hz
+        assert!(glide.current_frequency() < 200.0 && glide.current_frequency() > 190.0);
+    }
+
+    #[test]
+    fn test_jump_to_cancels_glide() {
+        let mut glide = Glide::new(SineOscillator::<1000>::new(100.0), 1.0);
+        glide.set_frequency(400.0);
+        glide.next_sample();
+        glide.jump_to(250.0);
+        assert!(!glide.is_gliding());
+        glide.next_sample();
+        assert_eq!(glide.current_frequency(), 250.0);
+    }
+
+    #[test]
+    fn test_jump_to_frequency_skips_glide() {
+        let mut glide = Glide::new(SineOscillator::<1000>::new(100.0), 0.1);
+        glide.set_frequency(200.0);
+        glide.next_sample();
+        glide.jump_to_frequency(300.0);
+        assert!(!glide.is_gliding());
+        assert_eq!(glide.current_frequency(), 300.0);
+        assert_eq!(glide.frequency(), 300.0);
+    }
+}
@@ -2,18 +2,36 @@
 //!
 //! This module contains the core `Oscillator` trait and various oscillator implementations.
 
+mod glide;
+mod modulated;
+mod phasor;
+mod plucked;
 mod pulse;
 mod sawtooth;
 mod sine;
+#[cfg(test)]
+mod spectrum;
 mod square;
+mod sync;
 mod traits;
 mod triangle;
 mod wavetable;
 
+pub use glide::Glide;
+#[cfg(feature = "music")]
+pub(crate) use glide::GlideState;
+pub use modulated::ModulatedOscillator;
+pub use phasor::PhaseAccumulator;
+pub(crate) use phasor::Phasor;
+pub use plucked::PluckedString;
 pub use pulse::PulseOscillator;
 pub use sawtooth::SawtoothOscillator;
-pub use sine::SineOscillator;
+pub use sine::{SineOscillator, SinePrecision};
 pub use square::SquareOscillator;
+pub use sync::SyncOscillator;
 pub use traits::Oscillator;
+pub(crate) use traits::{fast_sine, wrap_phase};
 pub use triangle::TriangleOscillator;
+#[cfg(feature = "wavetable-loader")]
+pub(crate) use wavetable::read_wav_channel;
 pub use wavetable::{InterpolationMode, WavetableOscillator};
@@ -0,0 +1,260 @@
+//! Oscillator wrapper adding audio-rate modulation inputs.
+
+use super::Oscillator;
+use super::traits::wrap_phase;
+use crate::core::Pitched;
+use crate::{AudioSignal, Param, Signal};
+
+/// Wraps any oscillator to add frequency, phase, and amplitude modulation inputs.
+///
+/// All three inputs are [`Param`]s expressing a deviation, so a value of 0.0
+/// means "unmodulated":
+///
+/// - **Frequency** (Hz): the wrapped oscillator's frequency is set to the base
+///   frequency plus the modulation each sample. A slow modulator gives
+///   vibrato; an audio-rate modulator gives classic linear FM. Negative
+///   instantaneous frequencies are allowed (through-zero FM).
+/// - **Phase** (cycles): the modulation is added to the oscillator's phase
+///   when the sample is read, without affecting how the phase advances. This
+///   is the "FM" of classic FM synthesizers (phase modulation).
+/// - **Amplitude** (ratio): the output is scaled by `1.0 + modulation`, so a
+///   modulator with depth 0.5 swings the level between 0.5 and 1.5.
+///
+/// The base frequency is controlled through [`Pitched`], so a modulated
+/// oscillator can be used directly as a voice: new notes change the base and
+/// the modulation rides on top.
+///
+/// Every oscillator has a `with_frequency_mod` constructor returning this
+/// wrapper; [`ModulatedOscillator::new`] wraps an existing oscillator, such as
+/// a wavetable.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{Signal, SignalExt, SineOscillator};
+///
+/// // 220 Hz carrier, modulated by a 330 Hz sine with 150 Hz of deviation
+/// let modulator = SineOscillator::<44100>::new(330.0).gain(150.0);
+/// let mut fm = SineOscillator::<44100>::with_frequency_mod(220.0, modulator);
+/// let sample = fm.next_sample();
+/// ```
+///
+/// Two-operator phase modulation with a tremolo:
+///
+/// ```
+/// use earworm::{ModulatedOscillator, Signal, SignalExt, SineOscillator};
+///
+/// let modulator = SineOscillator::<44100>::new(440.0).gain(0.3);
+/// let tremolo = SineOscillator::<44100>::new(4.0).gain(0.2);
+/// let mut pm = ModulatedOscillator::new(SineOscillator::<44100>::new(220.0))
+///     .with_phase_mod(modulator)
+///     .with_amplitude_mod(tremolo);
+/// let sample = pm.next_sample();
+/// ```
+pub struct ModulatedOscillator<O> {
+    oscillator: O,
+    base_frequency: f64,
+    frequency_mod: Option<Param>,
+    phase_mod: Option<Param>,
+    amplitude_mod: Option<Param>,
+}
+
+impl<O: Oscillator + Signal> ModulatedOscillator<O> {
+    /// Wraps an oscillator, using its current frequency as the base frequency.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{ModulatedOscillator, SineOscillator, WavetableOscillator};
+    ///
+    /// let table = WavetableOscillator::<44100>::saw(110.0, 2048);
+    /// let vibrato = SineOscillator::<44100>::new(5.0);
+    /// let osc = ModulatedOscillator::new(table).with_frequency_mod(vibrato);
+    /// ```
+    pub fn new(oscillator: O) -> Self {
+        let base_frequency = oscillator.frequency();
+        Self {
+            oscillator,
+            base_frequency,
+            frequency_mod: None,
+            phase_mod: None,
+            amplitude_mod: None,
+        }
+    }
+
+    /// Sets the frequency modulation input (deviation in Hz).
+    pub fn with_frequency_mod(mut self, modulation: impl Into<Param>) -> Self {
+        self.frequency_mod = Some(modulation.into());
+        self
+    }
+
+    /// Sets the phase modulation input (offset in cycles).
+    pub fn with_phase_mod(mut self, modulation: impl Into<Param>) -> Self {
+        self.phase_mod = Some(modulation.into());
+        self
+    }
+
+    /// Sets the amplitude modulation input (output is scaled by `1.0 + modulation`).
+    pub fn with_amplitude_mod(mut self, modulation: impl Into<Param>) -> Self {
+        self.amplitude_mod = Some(modulation.into());
+        self
+    }
+
+    /// Returns a reference to the wrapped oscillator.
+    pub fn oscillator(&self) -> &O {
+        &self.oscillator
+    }
+}
+
+impl<O: Oscillator + Signal> Signal for ModulatedOscillator<O> {
+    fn next_sample(&mut self) -> f64 {
+        if let Some(modulation) = self.frequency_mod.as_mut() {
+            self.oscillator
+                .set_frequency(self.base_frequency + modulation.value());
+        }
+
+        let sample = match self.phase_mod.as_mut() {
+            Some(modulation) => {
+                // Read at the offset phase, then remove the offset so the
+                // underlying phase keeps advancing at the carrier frequency
+                let offset = modulation.value();
+                let phase = self.oscillator.phase();
+                self.oscillator.set_phase(phase + offset);
+                let sample = self.oscillator.next_sample();
+                let advanced = self.oscillator.phase();
+                self.oscillator.set_phase(advanced - offset);
+                sample
+            }
+            None => self.oscillator.next_sample(),
+        };
+
+        match self.amplitude_mod.as_mut() {
+            Some(modulation) => sample * (1.0 + modulation.value()),
+            None => sample,
+        }
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.oscillator.prepare(max_block_size, sample_rate);
+        for param in [
+            &mut self.frequency_mod,
+            &mut self.phase_mod,
+            &mut self.amplitude_mod,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            param.prepare(max_block_size, sample_rate);
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32, O> AudioSignal<SAMPLE_RATE> for ModulatedOscillator<O> where
+    O: Oscillator + AudioSignal<SAMPLE_RATE>
+{
+}
+
+impl<O: Oscillator + Signal> Pitched for ModulatedOscillator<O> {
+    fn set_frequency(&mut self, frequency: f64) {
+        self.base_frequency = frequency;
+        self.oscillator.set_frequency(frequency);
+    }
+
+    fn frequency(&self) -> f64 {
+        self.base_frequency
+    }
+}
+
+impl<O: Oscillator + Signal> Oscillator for ModulatedOscillator<O> {
+    fn reset(&mut self) {
+        self.oscillator.reset();
+    }
+
+    fn phase(&self) -> f64 {
+        self.oscillator.phase()
+    }
+
+    fn set_phase(&mut self, phase: f64) {
+        self.oscillator.set_phase(wrap_phase(phase));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstantSignal, SawtoothOscillator, SineOscillator, SquareOscillator};
+
+    #[test]
+    fn test_no_modulation_matches_plain_oscillator() {
+        let mut plain = SineOscillator::<44100>::new(440.0);
+        let mut wrapped = ModulatedOscillator::new(SineOscillator::<44100>::new(440.0));
+        for _ in 0..100 {
+            assert_eq!(plain.next_sample(), wrapped.next_sample());
+        }
+    }
+
+    #[test]
+    fn test_constant_modulation_shifts_frequency() {
+        // 400 Hz base + constant 40 Hz offset behaves like 440 Hz
+        let mut plain = SawtoothOscillator::<44100>::new(440.0);
+        let mut fm =
+            SawtoothOscillator::<44100>::with_frequency_mod(400.0, ConstantSignal::<44100>(40.0));
+        for _ in 0..1000 {
+            assert!((plain.next_sample() - fm.next_sample()).abs() < 1e-9);
+        }
+        assert_eq!(fm.frequency(), 400.0);
+    }
+
+    #[test]
+    fn test_set_frequency_changes_base() {
+        let mut fm = SquareOscillator::<44100>::with_frequency_mod(100.0, 5.0);
+        fm.set_frequency(200.0);
+        assert_eq!(fm.frequency(), 200.0);
+        fm.next_sample();
+        assert_eq!(fm.oscillator().frequency(), 205.0);
+    }
+
+    #[test]
+    fn test_constant_phase_mod_is_phase_shift() {
+        // A quarter-cycle phase offset turns a sine into a cosine
+        let mut pm =
+            ModulatedOscillator::new(SineOscillator::<44100>::new(100.0)).with_phase_mod(0.25);
+        let mut reference = SineOscillator::<44100>::new(100.0);
+        reference.set_phase(0.25);
+        for _ in 0..1000 {
+            assert!((pm.next_sample() - reference.next_sample()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_phase_mod_does_not_change_pitch() {
+        let mut pm = ModulatedOscillator::new(SineOscillator::<44100>::new(441.0))
+            .with_phase_mod(SineOscillator::<44100>::new(30.0));
+        for _ in 0..100 {
+            pm.next_sample();
+        }
+        // 100 samples at 441 Hz = exactly one cycle
+        assert!(pm.phase() < 1e-9 || pm.phase() > 1.0 - 1e-9);
+    }
+
+    #[test]
+    fn test_amplitude_mod() {
+        let mut am = ModulatedOscillator::new(SquareOscillator::<44100>::new(10.0))
+            .with_amplitude_mod(ConstantSignal::<44100>(-0.5));
+        assert_eq!(am.next_sample(), 0.5);
+    }
+
+    #[test]
+    fn test_through_zero_fm_stays_bounded() {
+        let modulator = SineOscillator::<44100>::new(3.0);
+        let mut fm = ModulatedOscillator::new(SineOscillator::<44100>::new(10.0))
+            .with_frequency_mod(crate::Gain {
+                source: modulator,
+                gain: 500.0.into(),
+            });
+        for _ in 0..44100 {
+            let sample = fm.next_sample();
+            assert!((-1.0..=1.0).contains(&sample));
+        }
+    }
+}
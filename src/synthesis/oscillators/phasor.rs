@@ -0,0 +1,214 @@
+//! Phase accumulation shared by the basic oscillators.
+
+use super::traits::wrap_phase;
+
+/// Scale of a fixed-point phase: one cycle is 2^64 units.
+const FIXED_SCALE: f64 = 18_446_744_073_709_551_616.0;
+
+/// How an oscillator accumulates its phase from sample to sample.
+///
+/// Supported by [`SineOscillator`](super::SineOscillator),
+/// [`SawtoothOscillator`](super::SawtoothOscillator),
+/// [`SquareOscillator`](super::SquareOscillator),
+/// [`PulseOscillator`](super::PulseOscillator) and
+/// [`TriangleOscillator`](super::TriangleOscillator).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PhaseAccumulator {
+    /// Adds a floating-point increment each sample and wraps into
+    /// `[0.0, 1.0)`. Every addition rounds, and the rounding errors build up,
+    /// so over hours two oscillators at the same frequency slowly drift apart.
+    #[default]
+    Float,
+    /// Keeps the phase as a 64-bit fixed-point fraction of a cycle. Additions
+    /// are exact and wrap for free, so no error builds up from sample to
+    /// sample; all that remains is the rounding of the increment, a constant
+    /// tuning offset of a few billionths of a cent.
+    FixedPoint,
+}
+
+/// A phase in cycles advanced by a fixed increment each sample.
+#[derive(Debug, Clone)]
+pub(crate) struct Phasor {
+    mode: PhaseAccumulator,
+    phase: f64,
+    increment: f64,
+    fixed_phase: u64,
+    fixed_increment: u64,
+}
+
+impl Phasor {
+    /// Creates a phasor at phase 0.0 using floating-point accumulation.
+    pub(crate) fn new(increment: f64) -> Self {
+        Self {
+            mode: PhaseAccumulator::Float,
+            phase: 0.0,
+            increment,
+            fixed_phase: 0,
+            fixed_increment: to_fixed(increment),
+        }
+    }
+
+    /// Switches accumulation mode, keeping the current phase.
+    pub(crate) fn set_accumulator(&mut self, mode: PhaseAccumulator) {
+        self.mode = mode;
+        self.fixed_phase = to_fixed(self.phase);
+    }
+
+    pub(crate) fn accumulator(&self) -> PhaseAccumulator {
+        self.mode
+    }
+
+    /// Returns the phase in cycles, in `[0.0, 1.0)`.
+    #[inline]
+    pub(crate) fn phase(&self) -> f64 {
+        self.phase
+    }
+
+    /// Sets the phase in cycles, wrapping into range.
+    pub(crate) fn set_phase(&mut self, phase: f64) {
+        self.phase = wrap_phase(phase);
+        self.fixed_phase = to_fixed(self.phase);
+    }
+
+    /// Returns the increment in cycles per sample.
+    #[inline]
+    pub(crate) fn increment(&self) -> f64 {
+        self.increment
+    }
+
+    pub(crate) fn set_increment(&mut self, increment: f64) {
+        self.increment = increment;
+        self.fixed_increment = to_fixed(increment);
+    }
+
+    /// Advances one sample.
+    ///
+    /// Negative increments (through-zero FM) run the phase backwards.
+    #[inline]
+    pub(crate) fn advance(&mut self) {
+        match self.mode {
+            PhaseAccumulator::Float => {
+                self.phase += self.increment;
+                if self.phase >= 1.0 {
+                    self.phase -= 1.0;
+                } else if self.phase < 0.0 {
+                    self.phase += 1.0;
+                }
+            }
+            PhaseAccumulator::FixedPoint => {
+                self.fixed_phase = self.fixed_phase.wrapping_add(self.fixed_increment);
+                self.phase = from_fixed(self.fixed_phase);
+            }
+        }
+    }
+
+    /// Advances `samples` samples at once.
+    pub(crate) fn advance_by(&mut self, samples: usize) {
+        match self.mode {
+            PhaseAccumulator::Float => {
+                self.phase = wrap_phase(self.phase + self.increment * samples as f64);
+            }
+            PhaseAccumulator::FixedPoint => {
+                let step = self.fixed_increment.wrapping_mul(samples as u64);
+                self.fixed_phase = self.fixed_phase.wrapping_add(step);
+                self.phase = from_fixed(self.fixed_phase);
+            }
+        }
+    }
+}
+
+/// Converts cycles to a fixed-point fraction of a cycle, dropping whole cycles.
+fn to_fixed(cycles: f64) -> u64 {
+    // wrap_phase is below 1.0, so the product fits; the cast saturates anyway
+    (wrap_phase(cycles) * FIXED_SCALE).round() as u64
+}
+
+/// Converts a fixed-point phase to cycles in `[0.0, 1.0)`.
+#[inline]
+fn from_fixed(phase: u64) -> f64 {
+    // Keep the top 53 bits so the result is exact and never rounds up to 1.0
+    (phase >> 11) as f64 * (2048.0 / FIXED_SCALE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Distance between two phases, accounting for wrap-around.
+    fn phase_error(a: f64, b: f64) -> f64 {
+        let d = (a - b).abs();
+        d.min(1.0 - d)
+    }
+
+    #[test]
+    fn test_float_matches_plain_addition() {
+        let mut phasor = Phasor::new(0.3);
+        for expected in [0.3, 0.6, 0.9, 0.2] {
+            phasor.advance();
+            assert!((phasor.phase() - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_fixed_point_wraps_both_ways() {
+        let mut phasor = Phasor::new(0.25);
+        phasor.set_accumulator(PhaseAccumulator::FixedPoint);
+        for expected in [0.25, 0.5, 0.75, 0.0, 0.25] {
+            phasor.advance();
+            assert_eq!(phasor.phase(), expected);
+        }
+
+        phasor.set_increment(-0.5);
+        phasor.advance();
+        assert_eq!(phasor.phase(), 0.75);
+        phasor.set_phase(-0.125);
+        assert_eq!(phasor.phase(), 0.875);
+    }
+
+    #[test]
+    fn test_fixed_point_does_not_drift() {
+        // 440 Hz at 44.1 kHz completes exactly 440 cycles every second
+        let increment = 440.0 / 44100.0;
+        let mut float = Phasor::new(increment);
+        let mut fixed = Phasor::new(increment);
+        fixed.set_accumulator(PhaseAccumulator::FixedPoint);
+
+        for _ in 0..44100 * 200 {
+            float.advance();
+            fixed.advance();
+        }
+        let float_error = phase_error(float.phase(), 0.0);
+        let fixed_error = phase_error(fixed.phase(), 0.0);
+        // Only the f64 increment's rounding remains: about 1e-18 cycles per sample
+        assert!(fixed_error < 1e-11, "fixed-point error {}", fixed_error);
+        assert!(fixed_error < float_error);
+    }
+
+    #[test]
+    fn test_advance_by_matches_repeated_advance() {
+        for mode in [PhaseAccumulator::Float, PhaseAccumulator::FixedPoint] {
+            let mut stepped = Phasor::new(0.37);
+            let mut jumped = Phasor::new(0.37);
+            stepped.set_accumulator(mode);
+            jumped.set_accumulator(mode);
+            for _ in 0..13 {
+                stepped.advance();
+            }
+            jumped.advance_by(13);
+            assert!(phase_error(stepped.phase(), jumped.phase()) < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_switching_mode_keeps_phase() {
+        let mut phasor = Phasor::new(0.1);
+        for _ in 0..7 {
+            phasor.advance();
+        }
+        let phase = phasor.phase();
+        phasor.set_accumulator(PhaseAccumulator::FixedPoint);
+        assert!((phasor.phase() - phase).abs() < 1e-15);
+        phasor.advance();
+        assert!((phasor.phase() - (phase + 0.1)).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,374 @@
+//! Karplus-Strong plucked string model.
+
+use super::{Oscillator, Phasor};
+use crate::core::{Pitched, RngContext, SeededRng};
+use crate::synthesis::effects::DelayLine;
+use crate::{AudioSignal, Signal};
+use rand::Rng;
+
+/// Lowest frequency the string can be tuned to, which sets its buffer size.
+const MIN_FREQUENCY: f64 = 20.0;
+
+/// A plucked string, synthesized with the Karplus-Strong algorithm.
+///
+/// A pluck fills a delay line one period long with a burst of noise, which
+/// then circulates through a gentle lowpass filter. Each pass loses a little
+/// more of the high harmonics than the low ones, so the tone starts bright
+/// and mellows as it dies away, the way a guitar or harp string does. An
+/// allpass filter in the loop makes up the fraction of a sample the period
+/// doesn't fill, which keeps high notes in tune without dulling them.
+///
+/// The string is silent until it is plucked. [`pluck`](Self::pluck) plucks
+/// it directly; [`restart`](Pitched::restart), which
+/// [`Voice`](crate::music::Voice) calls on every note-on, plucks it too, so
+/// a string can be the signal of a `VoiceAllocator` to build a plucked
+/// instrument. Retuning a ringing string bends its pitch.
+///
+/// The sound is shaped by three settings:
+///
+/// * Damping (0.0 to 1.0) - how quickly the high harmonics die away compared
+///   with the fundamental; 1.0 is the classic Karplus-Strong averaging filter
+/// * Decay - the time in seconds for the fundamental to fall by 60 dB
+/// * Pick position (0.0 to 0.5) - where along the string it is plucked, as a
+///   fraction of its length from the bridge; nearer the middle sounds
+///   rounder and hollower, since harmonics with a node at the pick point are
+///   not excited. 0.0 leaves the noise burst unshaped
+///
+/// # Type Parameters
+///
+/// * `SAMPLE_RATE` - Sample rate in Hz
+/// * `R` - Random number generator for the pluck's noise burst
+///
+/// # Examples
+///
+#[cfg_attr(feature = "music", doc = "```")]
+#[cfg_attr(not(feature = "music"), doc = "```ignore")]
+/// use earworm::{ADSR, PluckedString, Signal};
+/// use earworm::music::VoiceAllocator;
+///
+/// const SAMPLE_RATE: u32 = 44100;
+///
+/// let mut harp = VoiceAllocator::<SAMPLE_RATE, 8, _, _>::new(|| {
+///     let string = PluckedString::<SAMPLE_RATE>::new(440.0)
+///         .with_damping(0.3)
+///         .with_decay(3.0)
+///         .with_pick_position(0.2);
+///     let env = ADSR::new(0.0, 0.0, 1.0, 0.5, SAMPLE_RATE as f64);
+///     (string, env)
+/// });
+///
+/// // Each note-on plucks a string
+/// harp.note_on(48, 0.8);
+/// harp.note_on(55, 0.8);
+/// let sample = harp.next_sample();
+/// ```
+pub struct PluckedString<const SAMPLE_RATE: u32, R: Rng = rand::rngs::ThreadRng> {
+    rng: R,
+    line: DelayLine,
+    burst: Vec<f64>,    // scratch space for shaping a pluck's noise burst
+    previous: f64,      // last sample read, for the damping filter
+    tuning: (f64, f64), // allpass input and output from the last sample
+    frequency: f64,
+    damping: f64,
+    decay: f64,
+    pick_position: f64,
+    feedback: f64, // gain per trip around the string, from the decay time
+    /// Periods of the frequency since the last pluck
+    phasor: Phasor,
+}
+
+impl<const SAMPLE_RATE: u32> PluckedString<SAMPLE_RATE, rand::rngs::ThreadRng> {
+    /// Creates a string tuned to `frequency`, at rest.
+    ///
+    /// Damping starts at 0.5, decay at 2 seconds and the pick position at
+    /// 0.0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{PluckedString, Signal};
+    ///
+    /// let mut string = PluckedString::<44100>::new(196.0);
+    /// string.pluck();
+    /// let sample = string.next_sample();
+    /// ```
+    pub fn new(frequency: f64) -> Self {
+        Self::with_rng(frequency, rand::thread_rng())
+    }
+}
+
+impl<const SAMPLE_RATE: u32> PluckedString<SAMPLE_RATE, SeededRng> {
+    /// Creates a string seeded from `context`, so its plucks are reproduced
+    /// along with the rest of a piece.
+    pub fn from_context(frequency: f64, context: &mut RngContext) -> Self {
+        Self::with_rng(frequency, context.rng())
+    }
+}
+
+impl<const SAMPLE_RATE: u32, R: Rng> PluckedString<SAMPLE_RATE, R> {
+    /// Creates a string tuned to `frequency` with a custom RNG, at rest.
+    ///
+    /// # Arguments
+    ///
+    /// * `frequency` - Frequency in Hz
+    /// * `rng` - Random number generator for the pluck's noise burst
+    pub fn with_rng(frequency: f64, rng: R) -> Self {
+        let capacity = (SAMPLE_RATE as f64 / MIN_FREQUENCY).ceil() as usize + 3;
+        let mut string = Self {
+            rng,
+            line: DelayLine::new(capacity),
+            burst: Vec::with_capacity(capacity + capacity / 2),
+            previous: 0.0,
+            tuning: (0.0, 0.0),
+            frequency: 0.0,
+            damping: 0.5,
+            decay: 2.0,
+            pick_position: 0.0,
+            feedback: 0.0,
+            phasor: Phasor::new(0.0),
+        };
+        string.set_frequency(frequency);
+        string
+    }
+
+    /// Sets the damping of the high harmonics (builder style).
+    pub fn with_damping(mut self, damping: f64) -> Self {
+        self.set_damping(damping);
+        self
+    }
+
+    /// Sets the decay time in seconds (builder style).
+    pub fn with_decay(mut self, decay: f64) -> Self {
+        self.set_decay(decay);
+        self
+    }
+
+    /// Sets the pick position (builder style).
+    pub fn with_pick_position(mut self, position: f64) -> Self {
+        self.set_pick_position(position);
+        self
+    }
+
+    /// Sets how quickly the high harmonics die away, from 0.0 (they ring as
+    /// long as the fundamental) to 1.0 (classic Karplus-Strong).
+    pub fn set_damping(&mut self, damping: f64) {
+        self.damping = damping.clamp(0.0, 1.0);
+    }
+
+    /// Returns the damping.
+    pub fn damping(&self) -> f64 {
+        self.damping
+    }
+
+    /// Sets the time in seconds for the fundamental to fall by 60 dB.
+    pub fn set_decay(&mut self, decay: f64) {
+        self.decay = decay.max(0.001);
+        self.update_feedback();
+    }
+
+    /// Returns the decay time in seconds.
+    pub fn decay(&self) -> f64 {
+        self.decay
+    }
+
+    /// Sets where the next pluck strikes, from 0.0 (at the bridge) to 0.5
+    /// (the middle of the string).
+    pub fn set_pick_position(&mut self, position: f64) {
+        self.pick_position = position.clamp(0.0, 0.5);
+    }
+
+    /// Returns the pick position.
+    pub fn pick_position(&self) -> f64 {
+        self.pick_position
+    }
+
+    /// Plucks the string, replacing whatever it was playing.
+    pub fn pluck(&mut self) {
+        let period = SAMPLE_RATE as f64 / self.frequency;
+        let length = period.ceil() as usize;
+        self.burst.clear();
+        self.burst
+            .extend((0..length).map(|_| self.rng.gen_range(-1.0..=1.0)));
+
+        // Plucking at a point cancels the harmonics with a node there: comb
+        // the burst against itself, delayed by the distance to the pick
+        let pick = (self.pick_position * period).round() as usize;
+        if pick > 0 {
+            self.burst.extend_from_within(..pick);
+            for i in 0..length {
+                self.burst[i] = self.burst[i + pick] - self.burst[i];
+            }
+            self.burst.truncate(length);
+        }
+
+        let mean = self.burst.iter().sum::<f64>() / length as f64;
+        let peak = self
+            .burst
+            .iter()
+            .fold(0.0_f64, |peak, &x| peak.max((x - mean).abs()));
+        let scale = if peak > 0.0 { 1.0 / peak } else { 0.0 };
+        for &x in &self.burst {
+            self.line.write((x - mean) * scale);
+        }
+        self.previous = 0.0;
+        self.tuning = (0.0, 0.0);
+        self.phasor.set_phase(0.0);
+    }
+
+    /// Silences the string.
+    pub fn mute(&mut self) {
+        for _ in 0..=self.line.max_delay() as usize {
+            self.line.write(0.0);
+        }
+        self.previous = 0.0;
+        self.tuning = (0.0, 0.0);
+    }
+
+    fn update_feedback(&mut self) {
+        // The fundamental goes round the string `frequency` times a second
+        self.feedback = 10.0_f64.powf(-3.0 / (self.decay * self.frequency));
+    }
+}
+
+impl<const SAMPLE_RATE: u32, R: Rng> Signal for PluckedString<SAMPLE_RATE, R> {
+    fn next_sample(&mut self) -> f64 {
+        // The damping filter averages neighbouring samples and delays by its
+        // weight; the line is a whole number of samples and the allpass
+        // delays by the remaining 0.1 to 1.1 samples
+        let weight = 0.5 * self.damping;
+        let loop_delay = SAMPLE_RATE as f64 / self.frequency - weight;
+        let whole = (loop_delay - 0.1).floor().max(1.0);
+        let fraction = loop_delay - whole;
+        let coefficient = (1.0 - fraction) / (1.0 + fraction);
+
+        let newer = self.line.read(whole);
+        let damped = newer + (self.previous - newer) * weight;
+        self.previous = newer;
+        let (last_in, last_out) = self.tuning;
+        let tuned = coefficient * (damped - last_out) + last_in;
+        self.tuning = (damped, tuned);
+
+        let sample = self.feedback * tuned;
+        self.line.write(sample);
+        self.phasor.advance();
+        sample
+    }
+}
+
+impl<const SAMPLE_RATE: u32, R: Rng> AudioSignal<SAMPLE_RATE> for PluckedString<SAMPLE_RATE, R> {}
+
+impl<const SAMPLE_RATE: u32, R: Rng> Pitched for PluckedString<SAMPLE_RATE, R> {
+    fn set_frequency(&mut self, freq: f64) {
+        self.frequency = freq.clamp(MIN_FREQUENCY, SAMPLE_RATE as f64 / 2.0);
+        self.phasor
+            .set_increment(self.frequency / SAMPLE_RATE as f64);
+        self.update_feedback();
+    }
+
+    fn frequency(&self) -> f64 {
+        self.frequency
+    }
+
+    fn restart(&mut self) {
+        self.pluck();
+    }
+}
+
+impl<const SAMPLE_RATE: u32, R: Rng> Oscillator for PluckedString<SAMPLE_RATE, R> {
+    /// Silences the string and resets the phase.
+    fn reset(&mut self) {
+        self.mute();
+        self.phasor.set_phase(0.0);
+    }
+
+    /// Returns the phase of the frequency, counted from the last pluck.
+    fn phase(&self) -> f64 {
+        self.phasor.phase()
+    }
+
+    /// Moves the phase count; the sound already on the string is unchanged.
+    fn set_phase(&mut self, phase: f64) {
+        self.phasor.set_phase(phase);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::spectrum::{amplitude, render};
+    use super::*;
+    use rand::SeedableRng;
+
+    fn string(frequency: f64) -> PluckedString<44100, rand::rngs::StdRng> {
+        PluckedString::with_rng(frequency, rand::rngs::StdRng::seed_from_u64(7))
+    }
+
+    fn rms(samples: &[f64]) -> f64 {
+        (samples.iter().map(|x| x * x).sum::<f64>() / samples.len() as f64).sqrt()
+    }
+
+    #[test]
+    fn test_silent_until_plucked() {
+        let mut string = string(220.0);
+        assert!(render(&mut string, 1000).iter().all(|&x| x == 0.0));
+
+        string.restart();
+        let samples = render(&mut string, 44100);
+        assert!(rms(&samples[..4410]) > 0.1);
+        // Dies away
+        assert!(rms(&samples[39690..]) < rms(&samples[..4410]) * 0.5);
+
+        string.reset();
+        assert!(render(&mut string, 1000).iter().all(|&x| x == 0.0));
+    }
+
+    #[test]
+    fn test_rings_at_its_frequency() {
+        let mut string = string(220.0).with_damping(1.0);
+        string.pluck();
+        render(&mut string, 4410);
+        let samples = render(&mut string, 44100);
+        let fundamental = amplitude(&samples, 220.0, 44100.0);
+        // Far more energy at the fundamental than just off it
+        assert!(fundamental > 10.0 * amplitude(&samples, 233.0, 44100.0));
+        assert!(fundamental > 10.0 * amplitude(&samples, 207.0, 44100.0));
+
+        // Retuning bends the ringing string
+        string.set_frequency(330.0);
+        let samples = render(&mut string, 44100);
+        assert!(amplitude(&samples, 330.0, 44100.0) > 10.0 * amplitude(&samples, 220.0, 44100.0));
+    }
+
+    #[test]
+    fn test_decay_time() {
+        // After the decay time the fundamental is down by about 60 dB
+        let mut string = string(110.0).with_decay(0.5);
+        string.pluck();
+        let start = amplitude(&render(&mut string, 4410), 110.0, 44100.0);
+        render(&mut string, 22050 - 4410);
+        let end = amplitude(&render(&mut string, 4410), 110.0, 44100.0);
+        let drop_db = 20.0 * (end / start).log10();
+        assert!((-66.0..=-54.0).contains(&drop_db), "dropped {} dB", drop_db);
+    }
+
+    #[test]
+    fn test_damping_darkens_tone() {
+        let brightness = |damping| {
+            let mut string = string(441.0).with_damping(damping);
+            string.pluck();
+            render(&mut string, 22050);
+            let samples = render(&mut string, 44100);
+            amplitude(&samples, 4410.0, 44100.0) / amplitude(&samples, 441.0, 44100.0)
+        };
+        assert!(brightness(1.0) < 0.5 * brightness(0.1));
+    }
+
+    #[test]
+    fn test_pick_at_middle_cancels_even_harmonics() {
+        let mut string = string(441.0).with_damping(0.0).with_pick_position(0.5);
+        string.pluck();
+        let samples = render(&mut string, 44100);
+        let second = amplitude(&samples, 882.0, 44100.0);
+        let third = amplitude(&samples, 1323.0, 44100.0);
+        assert!(second < 0.1 * third, "second {} third {}", second, third);
+    }
+}
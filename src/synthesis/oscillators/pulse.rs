@@ -1,54 +1,122 @@
 //! Pulse wave oscillator with modulating duty cycle.
 
-use super::Oscillator;
+use super::traits::{poly_blep, wrap_phase};
+use super::{ModulatedOscillator, Oscillator, PhaseAccumulator, Phasor};
 use crate::core::Pitched;
 use crate::{AudioSignal, Param, Signal};
 
 pub struct PulseOscillator<const SAMPLE_RATE: u32> {
-    phase: f64,
-    phase_increment: f64,
+    /// Phase and per-sample increment (frequency / sample_rate)
+    phasor: Phasor,
     duty_cycle: Param,
+    band_limited: bool,
 }
 
 impl<const SAMPLE_RATE: u32> PulseOscillator<SAMPLE_RATE> {
     pub fn new(frequency: f64, duty_cycle: Param) -> Self {
-        let phase_increment = frequency / SAMPLE_RATE as f64;
         Self {
-            phase: 0.0,
-            phase_increment,
+            phasor: Phasor::new(frequency / SAMPLE_RATE as f64),
             duty_cycle,
+            band_limited: false,
         }
     }
+
+    /// Sets whether the edges are band-limited with polyBLEP (builder style).
+    ///
+    /// Both edges are corrected at their exact sub-sample positions, so the
+    /// harmonic levels stay correct as the duty cycle sweeps and aliasing is
+    /// mostly removed. Samples next to an edge are then between -1.0 and
+    /// 1.0. Off by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{PulseOscillator, SineOscillator};
+    ///
+    /// // Band-limited PWM
+    /// let pwm = SineOscillator::<44100>::new(0.5);
+    /// let osc = PulseOscillator::<44100>::new(220.0, pwm.into()).with_band_limiting(true);
+    /// ```
+    pub fn with_band_limiting(mut self, band_limited: bool) -> Self {
+        self.band_limited = band_limited;
+        self
+    }
+
+    /// Sets how the phase is accumulated (builder style).
+    ///
+    /// See [`PhaseAccumulator`].
+    pub fn with_phase_accumulator(mut self, accumulator: PhaseAccumulator) -> Self {
+        self.phasor.set_accumulator(accumulator);
+        self
+    }
+
+    /// Returns how the phase is accumulated.
+    pub fn phase_accumulator(&self) -> PhaseAccumulator {
+        self.phasor.accumulator()
+    }
+
+    /// Creates a pulse oscillator whose frequency is modulated by a [`Param`].
+    ///
+    /// The instantaneous frequency is `base_frequency + modulation` in Hz.
+    /// See [`ModulatedOscillator`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_frequency` - Unmodulated frequency in Hz
+    /// * `modulation` - Frequency deviation in Hz (fixed or signal)
+    /// * `duty_cycle` - Duty cycle (0.0 to 1.0)
+    pub fn with_frequency_mod(
+        base_frequency: f64,
+        modulation: impl Into<Param>,
+        duty_cycle: Param,
+    ) -> ModulatedOscillator<Self> {
+        ModulatedOscillator::new(Self::new(base_frequency, duty_cycle))
+            .with_frequency_mod(modulation)
+    }
 }
 
 impl<const SAMPLE_RATE: u32> AudioSignal<SAMPLE_RATE> for PulseOscillator<SAMPLE_RATE> {}
 
 impl<const SAMPLE_RATE: u32> Signal for PulseOscillator<SAMPLE_RATE> {
     fn next_sample(&mut self) -> f64 {
+        let phase = self.phasor.phase();
         let duty = self.duty_cycle.value();
         let duty = (duty * 0.5 + 0.5).clamp(0.0, 1.0);
-        let sample = if self.phase < duty { 1.0 } else { -1.0 };
-        self.phase += self.phase_increment;
-        if self.phase >= 1.0 {
-            self.phase -= 1.0;
+        let mut sample = if phase < duty { 1.0 } else { -1.0 };
+        if self.band_limited {
+            let dt = self.phasor.increment().abs().min(0.5);
+            sample += poly_blep(phase, dt) - poly_blep(wrap_phase(phase + 1.0 - duty), dt);
         }
+        self.phasor.advance();
         sample
     }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.duty_cycle.prepare(max_block_size, sample_rate);
+    }
 }
 
 impl<const SAMPLE_RATE: u32> Pitched for PulseOscillator<SAMPLE_RATE> {
     fn set_frequency(&mut self, frequency: f64) {
-        self.phase_increment = frequency / SAMPLE_RATE as f64;
+        self.phasor.set_increment(frequency / SAMPLE_RATE as f64);
     }
 
     fn frequency(&self) -> f64 {
-        self.phase_increment * SAMPLE_RATE as f64
+        self.phasor.increment() * SAMPLE_RATE as f64
     }
 }
 
 impl<const SAMPLE_RATE: u32> Oscillator for PulseOscillator<SAMPLE_RATE> {
     fn reset(&mut self) {
-        self.phase = 0.0;
+        self.phasor.set_phase(0.0);
+    }
+
+    fn phase(&self) -> f64 {
+        self.phasor.phase()
+    }
+
+    fn set_phase(&mut self, phase: f64) {
+        self.phasor.set_phase(phase);
     }
 }
 
@@ -120,7 +188,7 @@ mod tests {
         for _ in 0..100000 {
             osc.next_sample();
         }
-        assert!(osc.phase >= 0.0 && osc.phase < 1.0);
+        assert!(osc.phase() >= 0.0 && osc.phase() < 1.0);
     }
 
     #[test]
@@ -130,7 +198,7 @@ mod tests {
             osc.next_sample();
         }
         osc.reset();
-        assert_eq!(osc.phase, 0.0);
+        assert_eq!(osc.phase(), 0.0);
     }
 
     #[test]
@@ -172,4 +240,62 @@ mod tests {
         let sample2 = osc.next_sample();
         assert_eq!(sample2, 1.0);
     }
+
+    #[test]
+    fn test_band_limited_duty_cycle_sweep() {
+        use crate::synthesis::oscillators::spectrum::{
+            amplitude, blep_rolloff, pulse_harmonic, render,
+        };
+
+        // Harmonic k of a pulse with duty d is (4/(πk))|sin(πkd)|
+        for duty in [0.1, 0.2, 0.25, 0.3, 0.4, 0.5, 0.6, 0.75, 0.9] {
+            let param = (duty * 2.0 - 1.0).into();
+            let mut osc = PulseOscillator::<48000>::new(1000.0, param).with_band_limiting(true);
+            let samples = render(&mut osc, 48000);
+
+            for k in 1..=6 {
+                let frequency = 1000.0 * k as f64;
+                let measured = amplitude(&samples, frequency, 48000.0);
+                let expected = pulse_harmonic(k, duty) * blep_rolloff(frequency, 48000.0);
+                assert!(
+                    (measured - expected).abs() < 0.01 * expected + 1e-3,
+                    "duty {}, harmonic {}: {} vs {}",
+                    duty,
+                    k,
+                    measured,
+                    expected
+                );
+            }
+
+            // DC offset is 2d - 1
+            let dc = amplitude(&samples, 0.0, 48000.0);
+            assert!(
+                (dc - (2.0 * duty - 1.0).abs()).abs() < 1e-3,
+                "duty {}: dc {}",
+                duty,
+                dc
+            );
+        }
+    }
+
+    #[test]
+    fn test_band_limiting_reduces_aliasing() {
+        use crate::synthesis::oscillators::spectrum::{alias_of, amplitude, render};
+
+        let alias = alias_of(17, 2900.0, 48000.0);
+        for duty in [0.2, 0.35] {
+            let param = duty * 2.0 - 1.0;
+            let naive = render(
+                &mut PulseOscillator::<48000>::new(2900.0, param.into()),
+                48000,
+            );
+            let mut osc =
+                PulseOscillator::<48000>::new(2900.0, param.into()).with_band_limiting(true);
+            let band_limited = render(&mut osc, 48000);
+
+            let naive_alias = amplitude(&naive, alias, 48000.0);
+            let band_limited_alias = amplitude(&band_limited, alias, 48000.0);
+            assert!(band_limited_alias < naive_alias / 100.0, "duty {}", duty);
+        }
+    }
 }
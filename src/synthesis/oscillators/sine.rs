@@ -1,10 +1,24 @@
 //! Sine wave oscillator implementation.
 
-use super::Oscillator;
+use super::traits::fast_sine;
+use super::{ModulatedOscillator, Oscillator, PhaseAccumulator, Phasor};
 use crate::core::Pitched;
-use crate::{AudioSignal, Signal};
+use crate::{AudioSignal, Param, Signal};
 use std::f64::consts::PI;
 
+/// How a sine wave is computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SinePrecision {
+    /// Exact, using the standard library's `sin`
+    #[default]
+    Exact,
+    /// A polynomial approximation, several times cheaper than `sin`, with a
+    /// maximum error of about 6e-7 (-124 dB). Inaudible in practice; meant
+    /// for CPU-constrained targets such as wasm and embedded. Run the
+    /// `sine_benchmark` example to measure the speedup on a given machine.
+    Fast,
+}
+
 /// A simple sine wave oscillator for audio synthesis.
 ///
 /// This oscillator generates a continuous sine wave at a specified frequency.
@@ -15,10 +29,9 @@ use std::f64::consts::PI;
 /// * `SAMPLE_RATE` - Sample rate in Hz (e.g., 44100 for CD quality)
 #[derive(Clone)]
 pub struct SineOscillator<const SAMPLE_RATE: u32> {
-    /// Current phase of the oscillator (0.0 to 1.0)
-    phase: f64,
-    /// Phase increment per sample (frequency / sample_rate)
-    phase_increment: f64,
+    /// Phase and per-sample increment (frequency / sample_rate)
+    phasor: Phasor,
+    precision: SinePrecision,
 }
 
 impl<const SAMPLE_RATE: u32> SineOscillator<SAMPLE_RATE> {
@@ -38,28 +51,102 @@ impl<const SAMPLE_RATE: u32> SineOscillator<SAMPLE_RATE> {
     /// let sample = osc.next_sample();
     /// ```
     pub fn new(frequency: f64) -> Self {
-        let phase_increment = frequency / SAMPLE_RATE as f64;
         Self {
-            phase: 0.0,
-            phase_increment,
+            phasor: Phasor::new(frequency / SAMPLE_RATE as f64),
+            precision: SinePrecision::Exact,
         }
     }
+
+    /// Sets how the sine is computed (builder style).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{SinePrecision, SineOscillator};
+    ///
+    /// // Cheaper sine for a CPU-constrained target
+    /// let osc = SineOscillator::<44100>::new(440.0).with_precision(SinePrecision::Fast);
+    /// ```
+    pub fn with_precision(mut self, precision: SinePrecision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Returns how the sine is computed.
+    pub fn precision(&self) -> SinePrecision {
+        self.precision
+    }
+
+    /// Sets how the phase is accumulated (builder style).
+    ///
+    /// [`PhaseAccumulator::FixedPoint`] keeps long renders exactly in tune
+    /// and in phase; see [`PhaseAccumulator`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{PhaseAccumulator, SineOscillator};
+    ///
+    /// // A drone for an hour-long ambient render
+    /// let drone = SineOscillator::<48000>::new(55.0)
+    ///     .with_phase_accumulator(PhaseAccumulator::FixedPoint);
+    /// ```
+    pub fn with_phase_accumulator(mut self, accumulator: PhaseAccumulator) -> Self {
+        self.phasor.set_accumulator(accumulator);
+        self
+    }
+
+    /// Returns how the phase is accumulated.
+    pub fn phase_accumulator(&self) -> PhaseAccumulator {
+        self.phasor.accumulator()
+    }
+
+    /// Creates an oscillator whose frequency is modulated by a [`Param`].
+    ///
+    /// The instantaneous frequency is `base_frequency + modulation` in Hz.
+    /// See [`ModulatedOscillator`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_frequency` - Unmodulated frequency in Hz
+    /// * `modulation` - Frequency deviation in Hz (fixed or signal)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{Signal, SignalExt, SineOscillator};
+    ///
+    /// // 440 Hz with +/- 5 Hz vibrato at 6 Hz
+    /// let vibrato = SineOscillator::<44100>::new(6.0).gain(5.0);
+    /// let mut osc = SineOscillator::<44100>::with_frequency_mod(440.0, vibrato);
+    /// let sample = osc.next_sample();
+    /// ```
+    pub fn with_frequency_mod(
+        base_frequency: f64,
+        modulation: impl Into<Param>,
+    ) -> ModulatedOscillator<Self> {
+        ModulatedOscillator::new(Self::new(base_frequency)).with_frequency_mod(modulation)
+    }
 }
 
 impl<const SAMPLE_RATE: u32> Signal for SineOscillator<SAMPLE_RATE> {
     fn next_sample(&mut self) -> f64 {
+        let phase = self.phasor.phase();
         // Generate sine wave sample
-        let sample = (self.phase * 2.0 * PI).sin();
+        let sample = match self.precision {
+            SinePrecision::Exact => (phase * 2.0 * PI).sin(),
+            SinePrecision::Fast => fast_sine(phase),
+        };
 
-        // Increment phase and wrap to [0.0, 1.0)
-        self.phase += self.phase_increment;
-        if self.phase >= 1.0 {
-            self.phase -= 1.0;
-        }
+        self.phasor.advance();
 
         sample
     }
 
+    fn skip(&mut self, samples: usize) {
+        self.phasor.advance_by(samples);
+    }
+
     // Uses default implementation of process() from the trait
 }
 
@@ -67,17 +154,25 @@ impl<const SAMPLE_RATE: u32> AudioSignal<SAMPLE_RATE> for SineOscillator<SAMPLE_
 
 impl<const SAMPLE_RATE: u32> Pitched for SineOscillator<SAMPLE_RATE> {
     fn set_frequency(&mut self, frequency: f64) {
-        self.phase_increment = frequency / SAMPLE_RATE as f64;
+        self.phasor.set_increment(frequency / SAMPLE_RATE as f64);
     }
 
     fn frequency(&self) -> f64 {
-        self.phase_increment * SAMPLE_RATE as f64
+        self.phasor.increment() * SAMPLE_RATE as f64
     }
 }
 
 impl<const SAMPLE_RATE: u32> Oscillator for SineOscillator<SAMPLE_RATE> {
     fn reset(&mut self) {
-        self.phase = 0.0;
+        self.phasor.set_phase(0.0);
+    }
+
+    fn phase(&self) -> f64 {
+        self.phasor.phase()
+    }
+
+    fn set_phase(&mut self, phase: f64) {
+        self.phasor.set_phase(phase);
     }
 }
 
@@ -124,7 +219,7 @@ mod tests {
             osc.next_sample();
         }
         // Phase should still be in valid range
-        assert!(osc.phase >= 0.0 && osc.phase < 1.0);
+        assert!(osc.phase() >= 0.0 && osc.phase() < 1.0);
     }
 
     #[test]
@@ -135,7 +230,7 @@ mod tests {
             osc.next_sample();
         }
         osc.reset();
-        assert_eq!(osc.phase, 0.0);
+        assert_eq!(osc.phase(), 0.0);
     }
 
     #[test]
@@ -158,4 +253,35 @@ mod tests {
         // With 0 Hz, phase doesn't advance, so samples should be identical
         assert_eq!(sample1, sample2);
     }
+
+    #[test]
+    fn test_fast_precision_accuracy() {
+        let mut exact = SineOscillator::<44100>::new(440.0);
+        let mut fast = SineOscillator::<44100>::new(440.0).with_precision(SinePrecision::Fast);
+        for _ in 0..44100 {
+            assert!((exact.next_sample() - fast.next_sample()).abs() < 1e-6);
+        }
+
+        let mut fast = SineOscillator::<4>::new(1.0).with_precision(SinePrecision::Fast);
+        assert_eq!(fast.next_sample(), 0.0);
+        assert!((fast.next_sample() - 1.0).abs() < 1e-6);
+        assert_eq!(fast.next_sample(), 0.0);
+        assert!((fast.next_sample() + 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_fixed_point_phase_stays_in_tune() {
+        let mut osc = SineOscillator::<44100>::new(440.0)
+            .with_phase_accumulator(PhaseAccumulator::FixedPoint);
+        assert_eq!(osc.phase_accumulator(), PhaseAccumulator::FixedPoint);
+
+        // Ten seconds is exactly 4400 cycles, so the waveform starts over
+        for _ in 0..441_000 {
+            osc.next_sample();
+        }
+        let mut fresh = SineOscillator::<44100>::new(440.0);
+        for _ in 0..100 {
+            assert!((osc.next_sample() - fresh.next_sample()).abs() < 1e-9);
+        }
+    }
 }
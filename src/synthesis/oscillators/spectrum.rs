@@ -0,0 +1,52 @@
+//! Spectrum measurements for oscillator tests.
+//!
+//! Band-limited oscillators are checked against the Fourier series of their
+//! ideal waveforms: harmonic levels should match theory and aliases (partials
+//! folded back from above Nyquist) should be far below the naive waveform's.
+
+use crate::Signal;
+use std::f64::consts::TAU;
+
+/// Renders `len` samples of `signal`.
+pub(crate) fn render(signal: &mut impl Signal, len: usize) -> Vec<f64> {
+    (0..len).map(|_| signal.next_sample()).collect()
+}
+
+/// Returns the amplitude of the component at `frequency` Hz in `samples`.
+///
+/// Uses a single-bin DFT, which is exact when the buffer holds a whole
+/// number of cycles of `frequency` (e.g. one second of audio and an integer
+/// frequency). A frequency of 0.0 measures the DC offset.
+pub(crate) fn amplitude(samples: &[f64], frequency: f64, sample_rate: f64) -> f64 {
+    let step = TAU * frequency / sample_rate;
+    let (re, im) = samples
+        .iter()
+        .enumerate()
+        .fold((0.0, 0.0), |(re, im), (n, &x)| {
+            let angle = step * n as f64;
+            (re + x * angle.cos(), im - x * angle.sin())
+        });
+    let scale = if frequency == 0.0 { 1.0 } else { 2.0 };
+    scale * re.hypot(im) / samples.len() as f64
+}
+
+/// Theoretical amplitude of harmonic `k` of a ±1 pulse wave with duty `duty`.
+pub(crate) fn pulse_harmonic(k: u32, duty: f64) -> f64 {
+    4.0 / (std::f64::consts::PI * k as f64) * (std::f64::consts::PI * k as f64 * duty).sin().abs()
+}
+
+/// Gain of a polyBLEP-corrected edge at `frequency`.
+///
+/// The two-sample polynomial step is a step convolved with a one-sample-wide
+/// triangle, so band-limited harmonics droop by `sinc²(f / fs)`: about 0.5%
+/// at 2 kHz and 3.5% at 5 kHz for a 48 kHz sample rate.
+pub(crate) fn blep_rolloff(frequency: f64, sample_rate: f64) -> f64 {
+    let x = std::f64::consts::PI * frequency / sample_rate;
+    if x == 0.0 { 1.0 } else { (x.sin() / x).powi(2) }
+}
+
+/// Frequency that harmonic `k` of `fundamental` lands on after aliasing.
+pub(crate) fn alias_of(k: u32, fundamental: f64, sample_rate: f64) -> f64 {
+    let folded = (k as f64 * fundamental).rem_euclid(sample_rate);
+    folded.min(sample_rate - folded)
+}
@@ -1,51 +1,126 @@
 //! Square wave oscillator implementation.
 
-use super::Oscillator;
+use super::traits::{poly_blep, wrap_phase};
+use super::{ModulatedOscillator, Oscillator, PhaseAccumulator, Phasor};
 use crate::core::Pitched;
-use crate::{AudioSignal, Signal};
+use crate::{AudioSignal, Param, Signal};
 
 #[derive(Clone)]
 pub struct SquareOscillator<const SAMPLE_RATE: u32> {
-    phase: f64,
-    phase_increment: f64,
+    /// Phase and per-sample increment (frequency / sample_rate)
+    phasor: Phasor,
+    band_limited: bool,
 }
 
 impl<const SAMPLE_RATE: u32> SquareOscillator<SAMPLE_RATE> {
     pub fn new(frequency: f64) -> Self {
-        let phase_increment = frequency / SAMPLE_RATE as f64;
         Self {
-            phase: 0.0,
-            phase_increment,
+            phasor: Phasor::new(frequency / SAMPLE_RATE as f64),
+            band_limited: false,
         }
     }
+
+    /// Sets whether the edges are band-limited with polyBLEP (builder style).
+    ///
+    /// A naive square wave aliases badly at high pitches: its harmonics above
+    /// Nyquist fold back as inharmonic tones. Band-limiting rounds off each
+    /// edge over a couple of samples, which removes most of that aliasing
+    /// while keeping the harmonics below Nyquist at their correct levels.
+    /// Samples next to an edge are then between -1.0 and 1.0. Off by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::SquareOscillator;
+    ///
+    /// let lead = SquareOscillator::<44100>::new(1760.0).with_band_limiting(true);
+    /// ```
+    pub fn with_band_limiting(mut self, band_limited: bool) -> Self {
+        self.band_limited = band_limited;
+        self
+    }
+
+    /// Sets how the phase is accumulated (builder style).
+    ///
+    /// See [`PhaseAccumulator`].
+    pub fn with_phase_accumulator(mut self, accumulator: PhaseAccumulator) -> Self {
+        self.phasor.set_accumulator(accumulator);
+        self
+    }
+
+    /// Returns how the phase is accumulated.
+    pub fn phase_accumulator(&self) -> PhaseAccumulator {
+        self.phasor.accumulator()
+    }
+
+    /// Creates an oscillator whose frequency is modulated by a [`Param`].
+    ///
+    /// The instantaneous frequency is `base_frequency + modulation` in Hz.
+    /// See [`ModulatedOscillator`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_frequency` - Unmodulated frequency in Hz
+    /// * `modulation` - Frequency deviation in Hz (fixed or signal)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{Signal, SignalExt, SquareOscillator, SineOscillator};
+    ///
+    /// // 440 Hz with +/- 5 Hz vibrato at 6 Hz
+    /// let vibrato = SineOscillator::<44100>::new(6.0).gain(5.0);
+    /// let mut osc = SquareOscillator::<44100>::with_frequency_mod(440.0, vibrato);
+    /// let sample = osc.next_sample();
+    /// ```
+    pub fn with_frequency_mod(
+        base_frequency: f64,
+        modulation: impl Into<Param>,
+    ) -> ModulatedOscillator<Self> {
+        ModulatedOscillator::new(Self::new(base_frequency)).with_frequency_mod(modulation)
+    }
 }
 
 impl<const SAMPLE_RATE: u32> Signal for SquareOscillator<SAMPLE_RATE> {
     fn next_sample(&mut self) -> f64 {
-        let sample = if self.phase < 0.5 { 1.0 } else { -1.0 };
-        self.phase += self.phase_increment;
-        if self.phase >= 1.0 {
-            self.phase -= 1.0;
+        let phase = self.phasor.phase();
+        let mut sample = if phase < 0.5 { 1.0 } else { -1.0 };
+        if self.band_limited {
+            let dt = self.phasor.increment().abs().min(0.5);
+            sample += poly_blep(phase, dt) - poly_blep(wrap_phase(phase + 0.5), dt);
         }
+        self.phasor.advance();
         sample
     }
+
+    fn skip(&mut self, samples: usize) {
+        self.phasor.advance_by(samples);
+    }
 }
 
 impl<const SAMPLE_RATE: u32> AudioSignal<SAMPLE_RATE> for SquareOscillator<SAMPLE_RATE> {}
 
 impl<const SAMPLE_RATE: u32> Pitched for SquareOscillator<SAMPLE_RATE> {
     fn set_frequency(&mut self, frequency: f64) {
-        self.phase_increment = frequency / SAMPLE_RATE as f64;
+        self.phasor.set_increment(frequency / SAMPLE_RATE as f64);
     }
 
     fn frequency(&self) -> f64 {
-        self.phase_increment * SAMPLE_RATE as f64
+        self.phasor.increment() * SAMPLE_RATE as f64
     }
 }
 
 impl<const SAMPLE_RATE: u32> Oscillator for SquareOscillator<SAMPLE_RATE> {
     fn reset(&mut self) {
-        self.phase = 0.0;
+        self.phasor.set_phase(0.0);
+    }
+
+    fn phase(&self) -> f64 {
+        self.phasor.phase()
+    }
+
+    fn set_phase(&mut self, phase: f64) {
+        self.phasor.set_phase(phase);
     }
 }
 
@@ -166,4 +241,58 @@ mod tests {
             assert_eq!(osc1.next_sample(), osc2.next_sample());
         }
     }
+
+    #[test]
+    fn test_band_limited_harmonics_match_theory() {
+        use crate::synthesis::oscillators::spectrum::{
+            amplitude, blep_rolloff, pulse_harmonic, render,
+        };
+
+        let mut osc = SquareOscillator::<48000>::new(1000.0).with_band_limiting(true);
+        let samples = render(&mut osc, 48000);
+
+        // Odd harmonics at 4/(πk), even harmonics and DC absent
+        for k in [1, 3, 5, 7, 9] {
+            let frequency = 1000.0 * k as f64;
+            let measured = amplitude(&samples, frequency, 48000.0);
+            let expected = pulse_harmonic(k, 0.5) * blep_rolloff(frequency, 48000.0);
+            assert!(
+                (measured - expected).abs() < 0.01 * expected,
+                "harmonic {}: {} vs {}",
+                k,
+                measured,
+                expected
+            );
+        }
+        for k in [2, 4, 6] {
+            assert!(amplitude(&samples, 1000.0 * k as f64, 48000.0) < 1e-3);
+        }
+        assert!(amplitude(&samples, 0.0, 48000.0) < 1e-3);
+    }
+
+    #[test]
+    fn test_band_limiting_reduces_aliasing() {
+        use crate::synthesis::oscillators::spectrum::{alias_of, amplitude, render};
+
+        // Harmonic 17 of 2900 Hz (49300 Hz) folds back to 1300 Hz
+        let alias = alias_of(17, 2900.0, 48000.0);
+        assert_eq!(alias, 1300.0);
+
+        let naive = render(&mut SquareOscillator::<48000>::new(2900.0), 48000);
+        let mut osc = SquareOscillator::<48000>::new(2900.0).with_band_limiting(true);
+        let band_limited = render(&mut osc, 48000);
+
+        let naive_alias = amplitude(&naive, alias, 48000.0);
+        let band_limited_alias = amplitude(&band_limited, alias, 48000.0);
+        assert!(band_limited_alias < naive_alias / 100.0);
+    }
+
+    #[test]
+    fn test_band_limited_output_stays_in_range() {
+        let mut osc = SquareOscillator::<44100>::new(3000.0).with_band_limiting(true);
+        for _ in 0..1000 {
+            let sample = osc.next_sample();
+            assert!((-1.0..=1.0).contains(&sample));
+        }
+    }
 }
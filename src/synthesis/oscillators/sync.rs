@@ -0,0 +1,220 @@
+//! Hard sync between two oscillators.
+
+use super::Oscillator;
+use super::traits::wrap_phase;
+use crate::core::Pitched;
+use crate::{AudioSignal, Signal};
+
+/// Hard-syncs a slave oscillator to a master.
+///
+/// The master runs silently and sets the pitch; the output is the slave,
+/// whose phase is reset every time the master completes a cycle. With the
+/// slave tuned above the master, each master cycle holds a fragment of the
+/// slave's waveform that is cut off mid-cycle, and sweeping the slave's
+/// frequency gives the classic tearing sync lead.
+///
+/// Resets land at their exact sub-sample position: after a master cycle ends
+/// part-way through a sample, the slave restarts with the phase it would have
+/// reached by the end of that sample.
+///
+/// Through [`Pitched`], the wrapper's frequency is the master's. Changing it
+/// scales the slave's frequency by the same factor, so the timbre stays the
+/// same across notes; use [`slave_mut`](Self::slave_mut) to change the slave
+/// on its own. A master with a negative frequency never resets the slave.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{Pitched, SawtoothOscillator, Signal, SyncOscillator};
+///
+/// // Saw synced to a 110 Hz master, 2.5x above it
+/// let master = SawtoothOscillator::<44100>::new(110.0);
+/// let slave = SawtoothOscillator::<44100>::new(275.0);
+/// let mut sync = SyncOscillator::new(master, slave);
+/// let sample = sync.next_sample();
+///
+/// // Sweep the slave for the characteristic sync timbre
+/// sync.slave_mut().set_frequency(440.0);
+/// assert_eq!(sync.frequency(), 110.0);
+/// ```
+pub struct SyncOscillator<M, S> {
+    master: M,
+    slave: S,
+}
+
+impl<M: Oscillator + Signal, S: Oscillator + Signal> SyncOscillator<M, S> {
+    /// Creates a synced pair, restarting both oscillators from phase 0.0.
+    ///
+    /// # Arguments
+    ///
+    /// * `master` - Oscillator whose cycles reset the slave; its output is not heard
+    /// * `slave` - Oscillator whose output is heard
+    pub fn new(mut master: M, mut slave: S) -> Self {
+        master.reset();
+        slave.reset();
+        Self { master, slave }
+    }
+
+    /// Returns a reference to the master oscillator.
+    pub fn master(&self) -> &M {
+        &self.master
+    }
+
+    /// Returns a mutable reference to the master oscillator.
+    pub fn master_mut(&mut self) -> &mut M {
+        &mut self.master
+    }
+
+    /// Returns a reference to the slave oscillator.
+    pub fn slave(&self) -> &S {
+        &self.slave
+    }
+
+    /// Returns a mutable reference to the slave oscillator.
+    pub fn slave_mut(&mut self) -> &mut S {
+        &mut self.slave
+    }
+
+    /// Returns the slave's phase at a given master phase, just after a reset.
+    fn synced_slave_phase(&self, master_phase: f64) -> f64 {
+        let master_frequency = self.master.frequency();
+        if master_frequency > 0.0 {
+            master_phase * self.slave.frequency() / master_frequency
+        } else {
+            0.0
+        }
+    }
+}
+
+impl<M: Oscillator + Signal, S: Oscillator + Signal> Signal for SyncOscillator<M, S> {
+    fn next_sample(&mut self) -> f64 {
+        let sample = self.slave.next_sample();
+
+        let before = self.master.phase();
+        self.master.next_sample();
+        let after = self.master.phase();
+        if self.master.frequency() > 0.0 && after < before {
+            self.slave.set_phase(self.synced_slave_phase(after));
+        }
+        sample
+    }
+
+    fn prepare(&mut self, max_block_size: usize, sample_rate: u32) {
+        self.master.prepare(max_block_size, sample_rate);
+        self.slave.prepare(max_block_size, sample_rate);
+    }
+}
+
+impl<const SAMPLE_RATE: u32, M, S> AudioSignal<SAMPLE_RATE> for SyncOscillator<M, S>
+where
+    M: Oscillator + AudioSignal<SAMPLE_RATE>,
+    S: Oscillator + AudioSignal<SAMPLE_RATE>,
+{
+}
+
+impl<M: Oscillator + Signal, S: Oscillator + Signal> Pitched for SyncOscillator<M, S> {
+    fn set_frequency(&mut self, frequency: f64) {
+        let old = self.master.frequency();
+        if old != 0.0 {
+            let ratio = self.slave.frequency() / old;
+            self.slave.set_frequency(frequency * ratio);
+        }
+        self.master.set_frequency(frequency);
+    }
+
+    fn frequency(&self) -> f64 {
+        self.master.frequency()
+    }
+}
+
+impl<M: Oscillator + Signal, S: Oscillator + Signal> Oscillator for SyncOscillator<M, S> {
+    fn reset(&mut self) {
+        self.master.reset();
+        self.slave.reset();
+    }
+
+    /// Returns the master's phase.
+    fn phase(&self) -> f64 {
+        self.master.phase()
+    }
+
+    /// Sets the master's phase and moves the slave to match, as if the
+    /// master's last cycle had started in sync.
+    fn set_phase(&mut self, phase: f64) {
+        let phase = wrap_phase(phase);
+        self.master.set_phase(phase);
+        let slave_phase = self.synced_slave_phase(phase);
+        self.slave.set_phase(slave_phase);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SawtoothOscillator, SineOscillator};
+
+    #[test]
+    fn test_slave_resets_each_master_cycle() {
+        // Master cycle is 128 samples; the slave would otherwise drift
+        let master = SawtoothOscillator::<1024>::new(8.0);
+        let slave = SawtoothOscillator::<1024>::new(23.0);
+        let mut sync = SyncOscillator::new(master, slave);
+
+        let first: Vec<f64> = (0..128).map(|_| sync.next_sample()).collect();
+        for _ in 0..5 {
+            let cycle: Vec<f64> = (0..128).map(|_| sync.next_sample()).collect();
+            for (a, b) in first.iter().zip(&cycle) {
+                assert!((a - b).abs() < 1e-9);
+            }
+        }
+        assert_eq!(first[0], -1.0);
+    }
+
+    #[test]
+    fn test_unsynced_when_slave_matches_master() {
+        let mut plain = SineOscillator::<44100>::new(220.0);
+        let mut sync = SyncOscillator::new(
+            SineOscillator::<44100>::new(220.0),
+            SineOscillator::<44100>::new(220.0),
+        );
+        for _ in 0..10000 {
+            assert!((plain.next_sample() - sync.next_sample()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_reset_is_sub_sample_accurate() {
+        // Master period of 2.5 samples ends half-way through the third sample
+        let master = SawtoothOscillator::<1000>::new(400.0);
+        let slave = SawtoothOscillator::<1000>::new(100.0);
+        let mut sync = SyncOscillator::new(master, slave);
+        for _ in 0..3 {
+            sync.next_sample();
+        }
+        // Half a sample of slave travel after the reset
+        assert!((sync.slave().phase() - 0.05).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_set_frequency_keeps_ratio() {
+        let mut sync = SyncOscillator::new(
+            SawtoothOscillator::<44100>::new(100.0),
+            SawtoothOscillator::<44100>::new(250.0),
+        );
+        sync.set_frequency(200.0);
+        assert_eq!(sync.frequency(), 200.0);
+        assert_eq!(sync.slave().frequency(), 500.0);
+    }
+
+    #[test]
+    fn test_negative_master_never_resets() {
+        let mut sync = SyncOscillator::new(
+            SawtoothOscillator::<1000>::new(-10.0),
+            SawtoothOscillator::<1000>::new(1.0),
+        );
+        for _ in 0..500 {
+            sync.next_sample();
+        }
+        assert!((sync.slave().phase() - 0.5).abs() < 1e-9);
+    }
+}
@@ -5,12 +5,76 @@ use crate::core::Pitched;
 /// Oscillators are pitched signals with additional state control.
 ///
 /// This trait extends `Pitched` to add oscillator-specific functionality
-/// like state reset. All oscillators have controllable frequency (via `Pitched`)
-/// and can reset their internal state to initial conditions.
+/// like state reset and phase access. All oscillators have controllable
+/// frequency (via `Pitched`) and can reset their internal state to initial
+/// conditions.
 pub trait Oscillator: Pitched {
     /// Resets the oscillator to its initial state.
     ///
     /// This typically resets the phase to zero and any other internal
     /// state variables to their initial values.
     fn reset(&mut self);
+
+    /// Returns the current phase, normalized to `[0.0, 1.0)` of a cycle.
+    fn phase(&self) -> f64;
+
+    /// Sets the current phase, in cycles.
+    ///
+    /// Values outside `[0.0, 1.0)` are wrapped into range.
+    fn set_phase(&mut self, phase: f64);
+}
+
+/// Wraps a phase in cycles into `[0.0, 1.0)`.
+pub(crate) fn wrap_phase(phase: f64) -> f64 {
+    let wrapped = phase.rem_euclid(1.0);
+    // rem_euclid can round up to exactly 1.0 for tiny negative inputs
+    if wrapped >= 1.0 { 0.0 } else { wrapped }
+}
+
+/// PolyBLEP correction for a rising step of height 2 at phase 0.0.
+///
+/// Adding it to a naive waveform replaces the step with a band-limited
+/// (polynomial) one spread over the samples on either side, which removes
+/// most of the aliasing the hard edge would cause. Subtract it for a falling
+/// step; for a step at phase `p`, pass the phase relative to `p`.
+///
+/// # Arguments
+///
+/// * `t` - Phase in cycles, in `[0.0, 1.0)`
+/// * `dt` - Phase increment per sample (frequency / sample rate)
+#[inline]
+pub(crate) fn poly_blep(t: f64, dt: f64) -> f64 {
+    if t < dt {
+        let t = t / dt;
+        2.0 * t - t * t - 1.0
+    } else if t > 1.0 - dt {
+        let t = (t - 1.0) / dt;
+        t * t + 2.0 * t + 1.0
+    } else {
+        0.0
+    }
+}
+
+/// Approximates `sin(2π · phase)` for a phase in cycles in `[0.0, 1.0)`.
+///
+/// Folds the phase into the quarter cycle around zero and evaluates a
+/// degree-7 odd minimax polynomial. The maximum absolute error is about
+/// 6e-7 (-124 dB), and the result is exactly 0.0 at phases 0.0 and 0.5.
+#[inline]
+pub(crate) fn fast_sine(phase: f64) -> f64 {
+    const C1: f64 = 0.999_996_615_908_008_3;
+    const C3: f64 = -0.166_648_283_819_041_9;
+    const C5: f64 = 0.008_306_325_227_266_032;
+    const C7: f64 = -0.000_183_636_539_797_264_95;
+
+    let quarter = if phase < 0.25 {
+        phase
+    } else if phase < 0.75 {
+        0.5 - phase
+    } else {
+        phase - 1.0
+    };
+    let x = quarter * std::f64::consts::TAU;
+    let x2 = x * x;
+    x * (C1 + x2 * (C3 + x2 * (C5 + x2 * C7)))
 }
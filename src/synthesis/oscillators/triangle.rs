@@ -1,8 +1,8 @@
 //! Triangle wave oscillator implementation.
 
-use super::Oscillator;
+use super::{ModulatedOscillator, Oscillator, PhaseAccumulator, Phasor};
 use crate::core::Pitched;
-use crate::{AudioSignal, Signal};
+use crate::{AudioSignal, Param, Signal};
 
 /// A triangle wave oscillator for audio synthesis.
 ///
@@ -15,10 +15,8 @@ use crate::{AudioSignal, Signal};
 /// * `SAMPLE_RATE` - Sample rate in Hz (e.g., 44100 for CD quality)
 #[derive(Clone)]
 pub struct TriangleOscillator<const SAMPLE_RATE: u32> {
-    /// Current phase of the oscillator (0.0 to 1.0)
-    phase: f64,
-    /// Phase increment per sample (frequency / sample_rate)
-    phase_increment: f64,
+    /// Phase and per-sample increment (frequency / sample_rate)
+    phasor: Phasor,
 }
 
 impl<const SAMPLE_RATE: u32> TriangleOscillator<SAMPLE_RATE> {
@@ -38,35 +36,74 @@ impl<const SAMPLE_RATE: u32> TriangleOscillator<SAMPLE_RATE> {
     /// let sample = osc.next_sample();
     /// ```
     pub fn new(frequency: f64) -> Self {
-        let phase_increment = frequency / SAMPLE_RATE as f64;
         Self {
-            phase: 0.0,
-            phase_increment,
+            phasor: Phasor::new(frequency / SAMPLE_RATE as f64),
         }
     }
+
+    /// Sets how the phase is accumulated (builder style).
+    ///
+    /// See [`PhaseAccumulator`].
+    pub fn with_phase_accumulator(mut self, accumulator: PhaseAccumulator) -> Self {
+        self.phasor.set_accumulator(accumulator);
+        self
+    }
+
+    /// Returns how the phase is accumulated.
+    pub fn phase_accumulator(&self) -> PhaseAccumulator {
+        self.phasor.accumulator()
+    }
+
+    /// Creates an oscillator whose frequency is modulated by a [`Param`].
+    ///
+    /// The instantaneous frequency is `base_frequency + modulation` in Hz.
+    /// See [`ModulatedOscillator`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_frequency` - Unmodulated frequency in Hz
+    /// * `modulation` - Frequency deviation in Hz (fixed or signal)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use earworm::{Signal, SignalExt, TriangleOscillator, SineOscillator};
+    ///
+    /// // 440 Hz with +/- 5 Hz vibrato at 6 Hz
+    /// let vibrato = SineOscillator::<44100>::new(6.0).gain(5.0);
+    /// let mut osc = TriangleOscillator::<44100>::with_frequency_mod(440.0, vibrato);
+    /// let sample = osc.next_sample();
+    /// ```
+    pub fn with_frequency_mod(
+        base_frequency: f64,
+        modulation: impl Into<Param>,
+    ) -> ModulatedOscillator<Self> {
+        ModulatedOscillator::new(Self::new(base_frequency)).with_frequency_mod(modulation)
+    }
 }
 
 impl<const SAMPLE_RATE: u32> Signal for TriangleOscillator<SAMPLE_RATE> {
     fn next_sample(&mut self) -> f64 {
+        let phase = self.phasor.phase();
         // Generate triangle wave sample
         // Triangle wave: rises from -1 to 1 in first half, falls from 1 to -1 in second half
-        let sample = if self.phase < 0.5 {
+        let sample = if phase < 0.5 {
             // Rising: -1.0 to 1.0 over phase 0.0 to 0.5
-            4.0 * self.phase - 1.0
+            4.0 * phase - 1.0
         } else {
             // Falling: 1.0 to -1.0 over phase 0.5 to 1.0
-            3.0 - 4.0 * self.phase
+            3.0 - 4.0 * phase
         };
 
-        // Increment phase and wrap to [0.0, 1.0)
-        self.phase += self.phase_increment;
-        if self.phase >= 1.0 {
-            self.phase -= 1.0;
-        }
+        self.phasor.advance();
 
         sample
     }
 
+    fn skip(&mut self, samples: usize) {
+        self.phasor.advance_by(samples);
+    }
+
     // Uses default implementation of process() from the trait
 }
 
@@ -74,17 +111,25 @@ impl<const SAMPLE_RATE: u32> AudioSignal<SAMPLE_RATE> for TriangleOscillator<SAM
 
 impl<const SAMPLE_RATE: u32> Pitched for TriangleOscillator<SAMPLE_RATE> {
     fn set_frequency(&mut self, frequency: f64) {
-        self.phase_increment = frequency / SAMPLE_RATE as f64;
+        self.phasor.set_increment(frequency / SAMPLE_RATE as f64);
     }
 
     fn frequency(&self) -> f64 {
-        self.phase_increment * SAMPLE_RATE as f64
+        self.phasor.increment() * SAMPLE_RATE as f64
     }
 }
 
 impl<const SAMPLE_RATE: u32> Oscillator for TriangleOscillator<SAMPLE_RATE> {
     fn reset(&mut self) {
-        self.phase = 0.0;
+        self.phasor.set_phase(0.0);
+    }
+
+    fn phase(&self) -> f64 {
+        self.phasor.phase()
+    }
+
+    fn set_phase(&mut self, phase: f64) {
+        self.phasor.set_phase(phase);
     }
 }
 
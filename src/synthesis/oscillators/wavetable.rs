@@ -124,6 +124,7 @@
 //! - Efficient computation via simple arithmetic
 
 use super::Oscillator;
+use super::traits::wrap_phase;
 use crate::core::Pitched;
 use crate::{AudioSignal, Signal};
 use std::f64::consts::PI;
@@ -423,42 +424,11 @@ impl<const SAMPLE_RATE: u32> WavetableOscillator<SAMPLE_RATE> {
         frequency: f64,
         path: P,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        let mut reader = hound::WavReader::open(path)?;
-        let spec = reader.spec();
-
-        // Read all samples from the first channel
-        let samples: Result<Vec<f64>, _> = match spec.sample_format {
-            hound::SampleFormat::Float => reader
-                .samples::<f32>()
-                .map(|s| s.map(|v| v as f64))
-                .collect(),
-            hound::SampleFormat::Int => {
-                let max_value = (1 << (spec.bits_per_sample - 1)) as f64;
-                reader
-                    .samples::<i32>()
-                    .map(|s| s.map(|v| v as f64 / max_value))
-                    .collect()
-            }
-        };
-
-        let samples = samples?;
-
+        let (samples, _sample_rate) = read_wav_channel(path)?;
         if samples.is_empty() {
             return Err("WAV file contains no samples".into());
         }
-
-        // For multi-channel files, we only take every Nth sample (first channel)
-        let channel_samples: Vec<f64> = if spec.channels > 1 {
-            samples
-                .iter()
-                .step_by(spec.channels as usize)
-                .copied()
-                .collect()
-        } else {
-            samples
-        };
-
-        Ok(Self::from_samples(frequency, channel_samples))
+        Ok(Self::from_samples(frequency, samples))
     }
 
     /// Reads a sample from the wavetable at the current phase using the configured interpolation.
@@ -515,20 +485,102 @@ impl<const SAMPLE_RATE: u32> WavetableOscillator<SAMPLE_RATE> {
 impl<const SAMPLE_RATE: u32> Signal for WavetableOscillator<SAMPLE_RATE> {
     fn next_sample(&mut self) -> f64 {
         let sample = self.read_sample();
+        self.advance_phase();
+        sample
+    }
+
+    #[cfg(feature = "simd")]
+    fn process(&mut self, buffer: &mut [f64]) {
+        use crate::synthesis::simd::{LANES, lerp};
+
+        if self.interpolation != InterpolationMode::Linear {
+            for sample in buffer.iter_mut() {
+                *sample = self.next_sample();
+            }
+            return;
+        }
+
+        // Gather table reads for a lane group, then interpolate them together
+        let table_size = self.table.len();
+        let mut a = [0.0; LANES];
+        let mut b = [0.0; LANES];
+        let mut frac = [0.0; LANES];
+        for chunk in buffer.chunks_mut(LANES) {
+            for lane in 0..chunk.len() {
+                // Phase is kept in [0, table_size), so truncation is floor and
+                // no modulo is needed
+                let index0 = (self.phase as usize).min(table_size - 1);
+                let index1 = if index0 + 1 == table_size {
+                    0
+                } else {
+                    index0 + 1
+                };
+                a[lane] = self.table[index0];
+                b[lane] = self.table[index1];
+                frac[lane] = self.phase - index0 as f64;
+                self.advance_phase();
+            }
+            lerp(chunk, &a, &b, &frac);
+        }
+    }
+}
 
-        // Advance phase and wrap
+impl<const SAMPLE_RATE: u32> WavetableOscillator<SAMPLE_RATE> {
+    /// Advances the phase by one sample and wraps it into the table.
+    fn advance_phase(&mut self) {
         self.phase += self.phase_increment;
         let table_size = self.table.len() as f64;
         if self.phase >= table_size {
             self.phase -= table_size;
+        } else if self.phase < 0.0 {
+            // Negative frequencies (through-zero FM) run the phase backwards
+            self.phase += table_size;
         }
-
-        sample
     }
 }
 
 impl<const SAMPLE_RATE: u32> AudioSignal<SAMPLE_RATE> for WavetableOscillator<SAMPLE_RATE> {}
 
+/// Reads the first channel of a WAV file, normalized to [-1.0, 1.0], along
+/// with the file's sample rate.
+#[cfg(feature = "wavetable-loader")]
+pub(crate) fn read_wav_channel<P: AsRef<Path>>(
+    path: P,
+) -> Result<(Vec<f64>, u32), Box<dyn std::error::Error + Send + Sync>> {
+    let mut reader = hound::WavReader::open(path)?;
+    let spec = reader.spec();
+
+    // Read all samples from the first channel
+    let samples: Result<Vec<f64>, _> = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .map(|s| s.map(|v| v as f64))
+            .collect(),
+        hound::SampleFormat::Int => {
+            let max_value = (1 << (spec.bits_per_sample - 1)) as f64;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|v| v as f64 / max_value))
+                .collect()
+        }
+    };
+
+    let samples = samples?;
+
+    // For multi-channel files, we only take every Nth sample (first channel)
+    let channel_samples: Vec<f64> = if spec.channels > 1 {
+        samples
+            .iter()
+            .step_by(spec.channels as usize)
+            .copied()
+            .collect()
+    } else {
+        samples
+    };
+
+    Ok((channel_samples, spec.sample_rate))
+}
+
 impl<const SAMPLE_RATE: u32> Pitched for WavetableOscillator<SAMPLE_RATE> {
     fn set_frequency(&mut self, frequency: f64) {
         let table_size = self.table.len() as f64;
@@ -545,4 +597,12 @@ impl<const SAMPLE_RATE: u32> Oscillator for WavetableOscillator<SAMPLE_RATE> {
     fn reset(&mut self) {
         self.phase = 0.0;
     }
+
+    fn phase(&self) -> f64 {
+        self.phase / self.table.len() as f64
+    }
+
+    fn set_phase(&mut self, phase: f64) {
+        self.phase = wrap_phase(phase) * self.table.len() as f64;
+    }
 }
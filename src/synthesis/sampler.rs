@@ -0,0 +1,585 @@
+//! Sample playback with repitching and loop points, and key-zoned
+//! multisampled instruments built from several samplers.
+
+use crate::synthesis::oscillators::InterpolationMode;
+use crate::{AudioSignal, Pitched, Signal};
+use std::sync::Arc;
+
+#[cfg(feature = "wavetable-loader")]
+use std::path::Path;
+
+/// What a [`Sampler`] does when playback reaches the end of its loop region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoopMode {
+    /// Play through once and fall silent at the end of the sample
+    #[default]
+    OneShot,
+    /// Jump from the loop end back to the loop start for as long as it plays
+    Loop,
+}
+
+/// Plays a recorded sample, repitched relative to the note it was recorded at.
+///
+/// The sample plays at its original speed when the frequency equals the root
+/// note's frequency; an octave up plays it twice as fast. Playback starts from
+/// the top on [`restart`](Pitched::restart), which [`Voice`](crate::music::Voice)
+/// calls on every note-on, so a sampler can be the signal of a
+/// `VoiceAllocator` to build a sample-based instrument.
+///
+/// The sample data is shared: cloning a sampler is cheap and clones play
+/// independently, so load a sample once and clone it into each voice.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "music", doc = "```")]
+#[cfg_attr(not(feature = "music"), doc = "```ignore")]
+/// use earworm::{ADSR, LoopMode, Sampler};
+/// use earworm::music::VoiceAllocator;
+///
+/// const SAMPLE_RATE: u32 = 44100;
+///
+/// // A 1-second decaying tone recorded at A3 (MIDI 57)
+/// let recording: Vec<f64> = (0..44100)
+///     .map(|i| {
+///         let t = i as f64 / 44100.0;
+///         (t * 220.0 * std::f64::consts::TAU).sin() * (-3.0 * t).exp()
+///     })
+///     .collect();
+/// let sampler = Sampler::<SAMPLE_RATE>::new(recording, 44100, 57)
+///     .with_loop(22050, 44100);
+///
+/// let mut keys = VoiceAllocator::<SAMPLE_RATE, 8, _, _>::new(|| {
+///     let env = ADSR::new(0.005, 0.0, 1.0, 0.2, SAMPLE_RATE as f64);
+///     (sampler.clone(), env)
+/// });
+/// keys.note_on(57, 0.8); // plays the recording as-is
+/// keys.note_on(64, 0.8); // repitched up a fifth
+/// ```
+#[derive(Clone)]
+pub struct Sampler<const SAMPLE_RATE: u32> {
+    data: Arc<[f64]>,
+    source_rate: u32,
+    root_frequency: f64,
+    frequency: f64,
+    increment: f64, // source samples per output sample
+    position: f64,
+    playing: bool,
+    loop_mode: LoopMode,
+    loop_start: usize,
+    loop_end: usize,
+    interpolation: InterpolationMode,
+}
+
+impl<const SAMPLE_RATE: u32> Sampler<SAMPLE_RATE> {
+    /// Creates a one-shot sampler, ready to play from the start.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - The recording (mono)
+    /// * `source_rate` - Sample rate the recording was made at, in Hz; it is
+    ///   resampled to `SAMPLE_RATE` on playback
+    /// * `root_note` - MIDI note the recording plays at unchanged
+    ///
+    /// # Panics
+    ///
+    /// Panics if `samples` is empty or `source_rate` is 0.
+    pub fn new(samples: impl Into<Arc<[f64]>>, source_rate: u32, root_note: u8) -> Self {
+        let data: Arc<[f64]> = samples.into();
+        assert!(!data.is_empty(), "sample must not be empty");
+        assert!(source_rate > 0, "source sample rate must be greater than 0");
+        let root_frequency = 440.0 * ((root_note as f64 - 69.0) / 12.0).exp2();
+        let len = data.len();
+        let mut sampler = Self {
+            data,
+            source_rate,
+            root_frequency,
+            frequency: root_frequency,
+            increment: 0.0,
+            position: 0.0,
+            playing: true,
+            loop_mode: LoopMode::OneShot,
+            loop_start: 0,
+            loop_end: len,
+            interpolation: InterpolationMode::Linear,
+        };
+        sampler.set_frequency(root_frequency);
+        sampler
+    }
+
+    /// Loads a sampler from a WAV file (requires `wavetable-loader` feature).
+    ///
+    /// Uses the first channel of the file and its sample rate.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, is not a valid WAV file,
+    /// or contains no samples.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use earworm::Sampler;
+    ///
+    /// let piano = Sampler::<44100>::from_wav_file("samples/piano_c4.wav", 60)?;
+    /// ```
+    #[cfg(feature = "wavetable-loader")]
+    pub fn from_wav_file<P: AsRef<Path>>(
+        path: P,
+        root_note: u8,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let (samples, sample_rate) = crate::synthesis::oscillators::read_wav_channel(path)?;
+        if samples.is_empty() {
+            return Err("WAV file contains no samples".into());
+        }
+        Ok(Self::new(samples, sample_rate, root_note))
+    }
+
+    /// Loops playback between two points, in samples of the recording
+    /// (builder style).
+    ///
+    /// Playback runs from the start of the sample into the loop, then repeats
+    /// the region from `start` up to (not including) `end`.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `start < end <= len()`.
+    pub fn with_loop(mut self, start: usize, end: usize) -> Self {
+        assert!(
+            start < end && end <= self.data.len(),
+            "loop points {}..{} out of range for a {}-sample recording",
+            start,
+            end,
+            self.data.len()
+        );
+        self.loop_start = start;
+        self.loop_end = end;
+        self.loop_mode = LoopMode::Loop;
+        self
+    }
+
+    /// Sets the loop mode (builder style).
+    ///
+    /// [`LoopMode::Loop`] without [`with_loop`](Self::with_loop) loops the
+    /// whole recording.
+    pub fn with_loop_mode(mut self, mode: LoopMode) -> Self {
+        self.loop_mode = mode;
+        self
+    }
+
+    /// Retunes the recording by `cents` (builder style).
+    ///
+    /// Positive values play it sharper at every note, for recordings that
+    /// are not exactly in tune with their root note.
+    pub fn with_tuning(mut self, cents: f64) -> Self {
+        self.root_frequency *= (-cents / 1200.0).exp2();
+        let frequency = self.frequency;
+        self.set_frequency(frequency);
+        self
+    }
+
+    /// Sets the interpolation used between recorded samples (builder style).
+    ///
+    /// Defaults to [`InterpolationMode::Linear`].
+    pub fn with_interpolation(mut self, mode: InterpolationMode) -> Self {
+        self.interpolation = mode;
+        self
+    }
+
+    /// Returns the loop mode.
+    pub fn loop_mode(&self) -> LoopMode {
+        self.loop_mode
+    }
+
+    /// Returns the loop start and end, in samples of the recording.
+    pub fn loop_points(&self) -> (usize, usize) {
+        (self.loop_start, self.loop_end)
+    }
+
+    /// Returns the length of the recording in samples.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns true if the recording has no samples (never, as construction
+    /// rejects empty recordings).
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns true until a one-shot sample has played to its end.
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Returns the playback position, in samples of the recording.
+    pub fn position(&self) -> f64 {
+        self.position
+    }
+
+    /// Returns the recorded sample at `index`, following the loop when looping.
+    fn sample_at(&self, index: usize) -> f64 {
+        if self.loop_mode == LoopMode::Loop && index >= self.loop_end {
+            let loop_len = self.loop_end - self.loop_start;
+            self.data[self.loop_start + (index - self.loop_end) % loop_len]
+        } else {
+            self.data.get(index).copied().unwrap_or(0.0)
+        }
+    }
+
+    /// Reads the recording at the current fractional position.
+    fn read_sample(&self) -> f64 {
+        let index = self.position.floor() as usize;
+        let frac = self.position.fract();
+        match self.interpolation {
+            InterpolationMode::None => self.sample_at(self.position.round() as usize),
+            InterpolationMode::Linear => {
+                let y0 = self.sample_at(index);
+                let y1 = self.sample_at(index + 1);
+                y0 + frac * (y1 - y0)
+            }
+            InterpolationMode::Cubic => {
+                let y0 = if index == 0 {
+                    0.0
+                } else {
+                    self.sample_at(index - 1)
+                };
+                let y1 = self.sample_at(index);
+                let y2 = self.sample_at(index + 1);
+                let y3 = self.sample_at(index + 2);
+
+                // Hermite interpolation
+                let c0 = y1;
+                let c1 = 0.5 * (y2 - y0);
+                let c2 = y0 - 2.5 * y1 + 2.0 * y2 - 0.5 * y3;
+                let c3 = 0.5 * (y3 - y0) + 1.5 * (y1 - y2);
+
+                c0 + frac * (c1 + frac * (c2 + frac * c3))
+            }
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32> Signal for Sampler<SAMPLE_RATE> {
+    fn next_sample(&mut self) -> f64 {
+        if !self.playing {
+            return 0.0;
+        }
+
+        let sample = self.read_sample();
+        self.position += self.increment;
+        match self.loop_mode {
+            LoopMode::OneShot => {
+                if self.position >= self.data.len() as f64 {
+                    self.playing = false;
+                }
+            }
+            LoopMode::Loop => {
+                let end = self.loop_end as f64;
+                if self.position >= end {
+                    let loop_len = (self.loop_end - self.loop_start) as f64;
+                    self.position = self.loop_start as f64 + (self.position - end) % loop_len;
+                }
+            }
+        }
+        sample
+    }
+}
+
+impl<const SAMPLE_RATE: u32> AudioSignal<SAMPLE_RATE> for Sampler<SAMPLE_RATE> {}
+
+impl<const SAMPLE_RATE: u32> Pitched for Sampler<SAMPLE_RATE> {
+    fn set_frequency(&mut self, freq: f64) {
+        self.frequency = freq.max(0.0);
+        self.increment =
+            self.frequency / self.root_frequency * self.source_rate as f64 / SAMPLE_RATE as f64;
+    }
+
+    fn frequency(&self) -> f64 {
+        self.frequency
+    }
+
+    fn restart(&mut self) {
+        self.position = 0.0;
+        self.playing = true;
+    }
+}
+
+/// One sample of a [`MultiSampler`] and the notes it plays.
+#[derive(Clone)]
+pub struct KeyZone<const SAMPLE_RATE: u32> {
+    low: u8,
+    high: u8,
+    gain: f64,
+    sampler: Sampler<SAMPLE_RATE>,
+}
+
+impl<const SAMPLE_RATE: u32> KeyZone<SAMPLE_RATE> {
+    /// Returns the lowest and highest MIDI notes the zone plays.
+    pub fn keys(&self) -> (u8, u8) {
+        (self.low, self.high)
+    }
+
+    /// Returns the zone's gain.
+    pub fn gain(&self) -> f64 {
+        self.gain
+    }
+
+    /// Returns the zone's sampler.
+    pub fn sampler(&self) -> &Sampler<SAMPLE_RATE> {
+        &self.sampler
+    }
+}
+
+/// A multisampled instrument: several recordings, each played over its own
+/// range of keys.
+///
+/// Each note picks the zone that covers it, or the nearest zone when none
+/// does, so a few recordings spread over the keyboard stay close to their
+/// natural pitch. The zone is chosen on [`restart`](Pitched::restart), which
+/// [`Voice`](crate::music::Voice) calls on every note-on, so pitch bends and
+/// glides repitch the playing zone instead of jumping to another. When zones
+/// overlap the first one added wins.
+///
+/// Like [`Sampler`], clones share the recordings and are cheap.
+///
+/// # Examples
+///
+/// ```
+/// use earworm::{MultiSampler, Pitched, Sampler, Signal};
+///
+/// const SAMPLE_RATE: u32 = 44100;
+///
+/// let low = Sampler::<SAMPLE_RATE>::new(vec![0.25; 4410], 44100, 48);
+/// let high = Sampler::<SAMPLE_RATE>::new(vec![0.5; 4410], 44100, 72);
+/// let mut keys = MultiSampler::new()
+///     .with_zone(0, 59, low)
+///     .with_zone(60, 127, high);
+///
+/// keys.set_frequency(440.0); // A4 falls in the upper zone
+/// keys.restart();
+/// assert_eq!(keys.next_sample(), 0.5);
+/// ```
+#[derive(Clone)]
+pub struct MultiSampler<const SAMPLE_RATE: u32> {
+    zones: Vec<KeyZone<SAMPLE_RATE>>,
+    active: Option<usize>,
+    frequency: f64,
+}
+
+impl<const SAMPLE_RATE: u32> MultiSampler<SAMPLE_RATE> {
+    /// Creates an instrument with no zones, which is silent.
+    pub fn new() -> Self {
+        Self {
+            zones: Vec::new(),
+            active: None,
+            frequency: 440.0,
+        }
+    }
+
+    /// Adds a zone playing `sampler` for MIDI notes `low` to `high`
+    /// inclusive (builder style).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `low > high`.
+    pub fn with_zone(self, low: u8, high: u8, sampler: Sampler<SAMPLE_RATE>) -> Self {
+        self.with_zone_gain(low, high, sampler, 1.0)
+    }
+
+    /// Adds a zone with its own gain (builder style).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `low > high`.
+    pub fn with_zone_gain(
+        mut self,
+        low: u8,
+        high: u8,
+        sampler: Sampler<SAMPLE_RATE>,
+        gain: f64,
+    ) -> Self {
+        self.add_zone(low, high, sampler, gain);
+        self
+    }
+
+    /// Adds a zone with its own gain.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `low > high`.
+    pub fn add_zone(&mut self, low: u8, high: u8, sampler: Sampler<SAMPLE_RATE>, gain: f64) {
+        assert!(low <= high, "key range {}..={} is empty", low, high);
+        self.zones.push(KeyZone {
+            low,
+            high,
+            gain,
+            sampler,
+        });
+    }
+
+    /// Returns the zones in the order they were added.
+    pub fn zones(&self) -> &[KeyZone<SAMPLE_RATE>] {
+        &self.zones
+    }
+
+    /// Returns the zone playing the current note, if any.
+    pub fn active_zone(&self) -> Option<&KeyZone<SAMPLE_RATE>> {
+        self.active.map(|index| &self.zones[index])
+    }
+
+    /// Returns the index of the zone for `note`: the first covering it, or
+    /// the nearest.
+    fn zone_for(&self, note: u8) -> Option<usize> {
+        self.zones
+            .iter()
+            .position(|zone| (zone.low..=zone.high).contains(&note))
+            .or_else(|| {
+                (0..self.zones.len()).min_by_key(|&index| {
+                    let zone = &self.zones[index];
+                    zone.low.abs_diff(note).min(zone.high.abs_diff(note))
+                })
+            })
+    }
+}
+
+impl<const SAMPLE_RATE: u32> Default for MultiSampler<SAMPLE_RATE> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const SAMPLE_RATE: u32> Signal for MultiSampler<SAMPLE_RATE> {
+    fn next_sample(&mut self) -> f64 {
+        match self.active {
+            Some(index) => {
+                let zone = &mut self.zones[index];
+                zone.sampler.next_sample() * zone.gain
+            }
+            None => 0.0,
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: u32> AudioSignal<SAMPLE_RATE> for MultiSampler<SAMPLE_RATE> {}
+
+impl<const SAMPLE_RATE: u32> Pitched for MultiSampler<SAMPLE_RATE> {
+    fn set_frequency(&mut self, freq: f64) {
+        self.frequency = freq.max(0.0);
+        if let Some(index) = self.active {
+            self.zones[index].sampler.set_frequency(self.frequency);
+        }
+    }
+
+    fn frequency(&self) -> f64 {
+        self.frequency
+    }
+
+    fn restart(&mut self) {
+        let note = (69.0 + 12.0 * (self.frequency.max(1e-3) / 440.0).log2())
+            .round()
+            .clamp(0.0, 127.0) as u8;
+        self.active = self.zone_for(note);
+        if let Some(index) = self.active {
+            let sampler = &mut self.zones[index].sampler;
+            sampler.set_frequency(self.frequency);
+            sampler.restart();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ramp(len: usize) -> Vec<f64> {
+        (0..len).map(|i| i as f64).collect()
+    }
+
+    #[test]
+    fn test_plays_at_root_note_unchanged() {
+        let mut sampler = Sampler::<1000>::new(ramp(4), 1000, 69);
+        let out: Vec<f64> = (0..6).map(|_| sampler.next_sample()).collect();
+        assert_eq!(out, vec![0.0, 1.0, 2.0, 3.0, 0.0, 0.0]);
+        assert!(!sampler.is_playing());
+    }
+
+    #[test]
+    fn test_repitching_and_resampling() {
+        // An octave up plays every other sample
+        let mut sampler = Sampler::<1000>::new(ramp(8), 1000, 60);
+        sampler.set_frequency(440.0 * (3.0f64 / 12.0).exp2()); // C5
+        let out: Vec<f64> = (0..4).map(|_| sampler.next_sample()).collect();
+        for (got, want) in out.iter().zip([0.0, 2.0, 4.0, 6.0]) {
+            assert!((got - want).abs() < 1e-9);
+        }
+
+        // A recording at half the output rate plays at half speed
+        let mut slow = Sampler::<1000>::new(ramp(8), 500, 69);
+        let out: Vec<f64> = (0..4).map(|_| slow.next_sample()).collect();
+        assert_eq!(out, vec![0.0, 0.5, 1.0, 1.5]);
+    }
+
+    #[test]
+    fn test_loop_points() {
+        let mut sampler = Sampler::<1000>::new(ramp(6), 1000, 69).with_loop(2, 5);
+        let out: Vec<f64> = (0..10).map(|_| sampler.next_sample()).collect();
+        assert_eq!(out, vec![0.0, 1.0, 2.0, 3.0, 4.0, 2.0, 3.0, 4.0, 2.0, 3.0]);
+        assert!(sampler.is_playing());
+
+        // Interpolating across the loop end reads the loop start
+        let mut sampler = Sampler::<1000>::new(ramp(6), 1000, 69).with_loop(2, 5);
+        sampler.set_frequency(440.0 * 1.5);
+        let out: Vec<f64> = (0..4).map(|_| sampler.next_sample()).collect();
+        assert_eq!(out, vec![0.0, 1.5, 3.0, 3.0]);
+    }
+
+    #[test]
+    fn test_restart_plays_from_the_top() {
+        let mut sampler = Sampler::<1000>::new(ramp(3), 1000, 69);
+        for _ in 0..5 {
+            sampler.next_sample();
+        }
+        assert!(!sampler.is_playing());
+        sampler.restart();
+        assert_eq!(sampler.next_sample(), 0.0);
+        assert_eq!(sampler.next_sample(), 1.0);
+    }
+
+    #[test]
+    fn test_tuning_shifts_pitch() {
+        // An octave sharp plays every other sample at the root note
+        let mut sampler = Sampler::<1000>::new(ramp(8), 1000, 69).with_tuning(1200.0);
+        let out: Vec<f64> = (0..3).map(|_| sampler.next_sample()).collect();
+        for (got, want) in out.iter().zip([0.0, 2.0, 4.0]) {
+            assert!((got - want).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_multisampler_picks_zone_on_restart() {
+        let low = Sampler::<1000>::new(vec![1.0; 100], 1000, 48);
+        let high = Sampler::<1000>::new(vec![2.0; 100], 1000, 72);
+        let mut keys = MultiSampler::new()
+            .with_zone(40, 55, low)
+            .with_zone_gain(70, 80, high, 0.5);
+        assert_eq!(keys.next_sample(), 0.0);
+
+        // Covered, then nearest to each side
+        for (note, want) in [(50, 1.0), (75, 1.0), (10, 1.0), (100, 1.0), (64, 1.0)] {
+            keys.set_frequency(440.0 * ((note as f64 - 69.0) / 12.0).exp2());
+            keys.restart();
+            let expected = if note < 62 { want } else { want * 2.0 * 0.5 };
+            assert_eq!(keys.next_sample(), expected, "note {}", note);
+        }
+
+        // A bend keeps the zone
+        keys.set_frequency(100.0);
+        assert_eq!(keys.active_zone().unwrap().keys(), (70, 80));
+    }
+
+    #[test]
+    #[should_panic(expected = "loop points 4..9 out of range for a 6-sample recording")]
+    fn test_invalid_loop_points_panic() {
+        let _ = Sampler::<1000>::new(ramp(6), 1000, 69).with_loop(4, 9);
+    }
+}
@@ -0,0 +1,60 @@
+//! Block kernels for the `simd` feature.
+//!
+//! The kernels work on fixed-size lane arrays so LLVM can lower them to vector
+//! instructions on stable Rust without platform intrinsics. Results are
+//! bit-identical to the per-sample paths they replace.
+//!
+//! Only linear wavetable interpolation uses them. In `examples/simd_benchmark.rs`
+//! (release build, 512-sample blocks, x86_64) it renders about 1.7-2.0x faster
+//! with the feature than without. Voice mixing and biquad filtering measured
+//! within noise of their scalar loops (a biquad is a serial recurrence), so
+//! they have no `simd` path.
+
+/// Number of samples processed together by each kernel step.
+pub(crate) const LANES: usize = 4;
+
+/// Linear interpolation of one lane group: `out[i] = a[i] + frac[i] * (b[i] - a[i])`.
+///
+/// Only the first `out.len()` lanes are written, so partial groups at the end
+/// of a block work too.
+#[inline]
+pub(crate) fn lerp(out: &mut [f64], a: &[f64; LANES], b: &[f64; LANES], frac: &[f64; LANES]) {
+    let mut lanes = [0.0; LANES];
+    for lane in 0..LANES {
+        lanes[lane] = a[lane] + frac[lane] * (b[lane] - a[lane]);
+    }
+    out.copy_from_slice(&lanes[..out.len()]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lerp_partial_group() {
+        let mut out = [0.0; 3];
+        lerp(
+            &mut out,
+            &[0.0, 1.0, 2.0, 3.0],
+            &[1.0, 3.0, 2.0, 9.0],
+            &[0.5, 0.25, 0.9, 1.0],
+        );
+        assert_eq!(out, [0.5, 1.5, 2.0]);
+    }
+
+    #[test]
+    fn test_wavetable_block_matches_per_sample() {
+        use crate::{Signal, WavetableOscillator};
+
+        let table: Vec<f64> = (0..64).map(|i| (i as f64 / 64.0).powi(2)).collect();
+        let mut per_sample = WavetableOscillator::<44100>::from_samples(523.25, table.clone());
+        let mut block = WavetableOscillator::<44100>::from_samples(523.25, table);
+
+        let expected: Vec<f64> = (0..1001).map(|_| per_sample.next_sample()).collect();
+        let mut buffer = vec![0.0; 1001];
+        for chunk in buffer.chunks_mut(129) {
+            block.process(chunk);
+        }
+        assert_eq!(buffer, expected);
+    }
+}